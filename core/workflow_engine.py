@@ -48,6 +48,7 @@ class WorkflowStep:
         self.timeout = config.get('timeout', 300)
         self.retry = config.get('retry', 0)
         self.save_output = config.get('save_output', None)
+        self.continue_on_error = bool(config.get('continue_on_error', False))
 
         self.status = StepStatus.PENDING
         self.result = None
@@ -176,7 +177,7 @@ class WorkflowEngine:
                     result = self._execute_step(step, wf)
                     results.append(result)
 
-                    if not result.get('success'):
+                    if not result.get('success') and not step.continue_on_error:
                         failed = True
 
                 progress.advance(task)
@@ -213,6 +214,26 @@ class WorkflowEngine:
 
         return summary
 
+    def execute_step(self, workflow: Workflow, number: int, outputs: Dict[str, Any] = None,
+                     dry_run: bool = False) -> Dict[str, Any]:
+        """Execute only the step at 1-based `number`, given the outputs saved by earlier steps"""
+        if number < 1 or number > len(workflow.steps):
+            raise ValueError(f"Step {number} out of range (workflow has {len(workflow.steps)} steps)")
+
+        self.current_workflow = workflow
+        workflow.outputs.update(outputs or {})
+        step = workflow.steps[number - 1]
+
+        if dry_run:
+            console.print(f"[dim]DRY RUN: Would execute '{step.name}'[/dim]")
+            step.status = StepStatus.COMPLETED
+            result = {"step_id": step.id, "success": True, "result": None, "error": None, "execution_time": 0.0}
+        else:
+            result = self._execute_step(step, workflow)
+
+        saved = {step.save_output: step.result} if step.save_output and result["success"] else {}
+        return {**result, "step": number, "name": step.name, "agent": step.agent, "outputs": saved}
+
     def _execute_step(self, step: WorkflowStep, workflow: Workflow) -> Dict[str, Any]:
         """Execute a single step"""
         step.status = StepStatus.RUNNING
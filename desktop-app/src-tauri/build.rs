@@ -1,3 +1,18 @@
+use std::process::Command;
+
 fn main() {
+    // Embed the commit this build came from (empty outside a git checkout)
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=VIBECODE_GIT_COMMIT={}", commit);
+    println!("cargo:rustc-env=VIBECODE_TARGET={}", std::env::var("TARGET").unwrap_or_default());
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+    println!("cargo:rerun-if-changed=../../.git/refs/heads");
+
     tauri_build::build()
 }
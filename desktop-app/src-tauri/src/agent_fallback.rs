@@ -0,0 +1,122 @@
+/// Agent Fallback - Re-running a task with another agent when its agent fails
+///
+/// `execute_task` with `auto_fallback` looks at a failed run's output: when it
+/// matches one of `agentFallback.patterns` (auth failures, exhausted quota,
+/// a missing API key) the task runs again with the next agent of
+/// `agentFallback.chain` that was not tried yet. Patterns are compared
+/// lowercase; a pattern made only of digits ("401") must appear as a number of
+/// its own. Every agent tried is listed in `TaskResult.agent_attempts`, and
+/// each switch is announced as a `task-agent-fallback` event.
+
+use serde::{Deserialize, Serialize};
+
+pub const TASK_AGENT_FALLBACK_EVENT: &str = "task-agent-fallback";
+
+/// `agentFallback` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AgentFallbackSettings {
+    /// Agents in the order they are tried
+    pub chain: Vec<String>,
+    /// Output fragments of failures another agent may not have
+    pub patterns: Vec<String>,
+}
+
+impl Default for AgentFallbackSettings {
+    fn default() -> Self {
+        Self {
+            chain: ["api", "cli", "antigravity"].map(String::from).to_vec(),
+            patterns: [
+                "401",
+                "403",
+                "unauthorized",
+                "authentication",
+                "quota exceeded",
+                "insufficient_quota",
+                "resource_exhausted",
+                "api key",
+                "api_key",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+impl AgentFallbackSettings {
+    /// Load the agentFallback block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("agentFallback").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+
+    /// The pattern that makes `output` worth another agent, if any
+    pub fn failure_reason(&self, output: &str) -> Option<String> {
+        let lower = output.to_lowercase();
+        self.patterns
+            .iter()
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| !p.is_empty())
+            .find(|pattern| {
+                if pattern.chars().all(|c| c.is_ascii_digit()) {
+                    lower.split(|c: char| !c.is_ascii_digit()).any(|number| number == pattern)
+                } else {
+                    lower.contains(pattern.as_str())
+                }
+            })
+    }
+
+    /// The chain's next agent after `current` that is not in `tried`
+    ///
+    /// An agent outside the chain ("auto" that could not be resolved) starts at its beginning.
+    pub fn next_agent(&self, current: &str, tried: &[String]) -> Option<String> {
+        let start = self.chain.iter().position(|a| a == current).map_or(0, |i| i + 1);
+        self.chain[start..].iter().find(|a| !tried.contains(a)).cloned()
+    }
+}
+
+/// One agent's run of a task, in `TaskResult.agent_attempts`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentAttempt {
+    pub agent: String,
+    pub success: bool,
+    pub execution_time: f64,
+    /// Pattern that made the run fall back to the next agent
+    #[serde(default)]
+    pub fallback_reason: Option<String>,
+}
+
+/// Payload of `task-agent-fallback`
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentFallback<'a> {
+    pub task_id: &'a str,
+    pub from: &'a str,
+    pub to: &'a str,
+    pub reason: &'a str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_patterns_and_chain_order() {
+        let settings = AgentFallbackSettings::default();
+        assert_eq!(settings.failure_reason("Error code: 401 - invalid x-api-key").as_deref(), Some("401"));
+        assert_eq!(settings.failure_reason("You exceeded your current quota: Quota exceeded").as_deref(), Some("quota exceeded"));
+        assert_eq!(settings.failure_reason("ANTHROPIC_API_KEY not set").as_deref(), Some("api_key"));
+        assert_eq!(settings.failure_reason("took 24019ms, tests failed"), None);
+
+        let tried = |agents: &[&str]| agents.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(settings.next_agent("api", &tried(&["api"])).as_deref(), Some("cli"));
+        assert_eq!(settings.next_agent("cli", &tried(&["api", "cli"])).as_deref(), Some("antigravity"));
+        assert_eq!(settings.next_agent("antigravity", &tried(&["antigravity"])), None);
+        // "auto" resolved to cli, which then failed: only what comes after cli
+        assert_eq!(settings.next_agent("cli", &tried(&["auto"])).as_deref(), Some("antigravity"));
+        assert_eq!(settings.next_agent("auto", &tried(&["auto"])).as_deref(), Some("api"));
+    }
+}
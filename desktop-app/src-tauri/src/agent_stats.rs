@@ -0,0 +1,128 @@
+/// Agent Stats - Per-agent run counts, durations and success rates
+///
+/// Aggregates every completed task by the agent that actually handled it
+/// (as resolved from vibe.py output when the task was auto-routed) and
+/// persists the totals to `agent_stats.json` in the config directory.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Serializes read-modify-write of the stats file
+static STATS_LOCK: Mutex<()> = Mutex::new(());
+
+/// How `TaskResult.agent_used` was determined
+pub const RESOLUTION_EXPLICIT: &str = "explicit";
+pub const RESOLUTION_DETECTED: &str = "detected";
+pub const RESOLUTION_UNKNOWN: &str = "unknown";
+
+/// Persisted totals for one agent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AgentTotals {
+    runs: u64,
+    successes: u64,
+    total_execution_time: f64,
+}
+
+/// Persisted stats file layout
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AgentStatsFile {
+    #[serde(default)]
+    agents: BTreeMap<String, AgentTotals>,
+    #[serde(default)]
+    unknown_resolutions: u64,
+}
+
+/// Breakdown entry for one agent
+#[derive(Debug, Serialize)]
+pub struct AgentBreakdownEntry {
+    pub agent: String,
+    pub runs: u64,
+    pub successes: u64,
+    pub success_rate: f64,
+    pub total_execution_time: f64,
+    pub avg_execution_time: f64,
+}
+
+/// Response of `get_agent_breakdown`
+#[derive(Debug, Serialize)]
+pub struct AgentBreakdown {
+    pub total_runs: u64,
+    pub unknown_resolutions: u64,
+    pub agents: Vec<AgentBreakdownEntry>,
+}
+
+fn get_stats_path() -> PathBuf {
+    crate::get_app_config_dir().join("agent_stats.json")
+}
+
+fn load_stats() -> AgentStatsFile {
+    std::fs::read_to_string(get_stats_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Record a completed task run
+pub fn record_run(agent: &str, resolution: &str, success: bool, execution_time: f64) -> Result<(), String> {
+    let _guard = STATS_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut stats = load_stats();
+    let totals = stats.agents.entry(agent.to_string()).or_default();
+    totals.runs += 1;
+    if success {
+        totals.successes += 1;
+    }
+    totals.total_execution_time += execution_time;
+
+    if resolution == RESOLUTION_UNKNOWN {
+        stats.unknown_resolutions += 1;
+    }
+
+    let stats_path = get_stats_path();
+    if let Some(parent) = stats_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(&stats)
+        .map_err(|e| format!("Failed to serialize agent stats: {}", e))?;
+    std::fs::write(&stats_path, content)
+        .map_err(|e| format!("Failed to save agent stats: {}", e))
+}
+
+/// Get how often each agent was chosen and its success rate
+#[tauri::command]
+pub async fn get_agent_breakdown() -> Result<AgentBreakdown, String> {
+    crate::command_metrics::timed("get_agent_breakdown", async move {
+        let stats = {
+            let _guard = STATS_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+            load_stats()
+        };
+
+        let mut agents: Vec<AgentBreakdownEntry> = stats.agents
+            .into_iter()
+            .map(|(agent, totals)| {
+                let runs = totals.runs.max(1) as f64;
+                AgentBreakdownEntry {
+                    agent,
+                    runs: totals.runs,
+                    successes: totals.successes,
+                    success_rate: totals.successes as f64 / runs,
+                    total_execution_time: totals.total_execution_time,
+                    avg_execution_time: totals.total_execution_time / runs,
+                }
+            })
+            .collect();
+
+        // Most used agents first
+        agents.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.agent.cmp(&b.agent)));
+
+        Ok(AgentBreakdown {
+            total_runs: agents.iter().map(|a| a.runs).sum(),
+            unknown_resolutions: stats.unknown_resolutions,
+            agents,
+        })
+    }).await
+}
@@ -1,9 +1,12 @@
 // Antigravity Integration Module
 // Ports ProcessFinder and QuotaService from Antigravity Toolkit (TypeScript → Rust)
 
+pub mod model_match;
 pub mod process_finder;
+pub mod quota_cache;
 pub mod quota_service;
 pub mod types;
+pub mod watcher;
 
 // Re-export main types for Tauri commands
 pub use process_finder::ProcessFinder;
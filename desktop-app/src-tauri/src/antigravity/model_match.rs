@@ -0,0 +1,196 @@
+// ModelMatch: Map requested model names onto Antigravity quota entries
+//
+// Callers ask for "gemini-flash" while the language server reports labels like
+// "Gemini 2.0 Flash" and ids like "gemini-2.0-flash-exp". Both sides are
+// canonicalized (lowercase, punctuation and version tokens stripped) and
+// user-defined aliases from the `modelAliases` settings block are applied.
+
+use std::collections::BTreeMap;
+use serde::Serialize;
+
+use crate::antigravity::quota_service::{ModelQuotaInfo, QuotaSnapshot};
+
+/// Release-channel words that don't identify a model
+const VERSION_WORDS: &[&str] = &["exp", "experimental", "preview", "latest", "stable"];
+
+/// `modelAliases` block of settings.json: requested name → labels or ids it means
+pub type ModelAliases = BTreeMap<String, Vec<String>>;
+
+/// Load the alias map from settings.json (empty when absent or invalid)
+pub fn load_aliases() -> ModelAliases {
+    crate::load_settings_file()
+        .ok()
+        .flatten()
+        .and_then(|settings| settings.get("modelAliases").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// How a requested name was matched (ordered weakest to strongest)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchConfidence {
+    /// Every requested token appears in the label or id
+    Partial,
+    /// Same tokens once versions and punctuation are stripped
+    Canonical,
+    /// Matched through a configured alias
+    Alias,
+    /// Label or id equal to the request (ignoring case)
+    Exact,
+}
+
+/// Quota entry chosen for a requested model
+#[derive(Debug, Clone)]
+pub struct ModelMatch<'a> {
+    pub model: &'a ModelQuotaInfo,
+    pub confidence: MatchConfidence,
+}
+
+/// Lowercase tokens with punctuation, version numbers, channel words and repeats removed
+pub fn canonicalize(name: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    for token in name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .filter(|t| !t.chars().all(|c| c.is_ascii_digit()))
+        .filter(|t| !VERSION_WORDS.contains(t))
+    {
+        if !tokens.iter().any(|t| t == token) {
+            tokens.push(token.to_string());
+        }
+    }
+    tokens
+}
+
+/// Confidence and number of unrequested tokens when `wanted` matches `model`
+fn score(model: &ModelQuotaInfo, requested: &str, wanted: &[String]) -> Option<(MatchConfidence, usize)> {
+    let names = [&model.label, &model.model_id];
+
+    if names.iter().any(|n| n.eq_ignore_ascii_case(requested.trim())) {
+        return Some((MatchConfidence::Exact, 0));
+    }
+    if wanted.is_empty() {
+        return None;
+    }
+
+    names
+        .iter()
+        .filter_map(|name| {
+            let tokens = canonicalize(name);
+            if tokens == wanted {
+                Some((MatchConfidence::Canonical, 0))
+            } else if wanted.iter().all(|t| tokens.contains(t)) {
+                Some((MatchConfidence::Partial, tokens.len().saturating_sub(wanted.len())))
+            } else {
+                None
+            }
+        })
+        .max_by_key(|(confidence, extra)| (*confidence, std::cmp::Reverse(*extra)))
+}
+
+/// Best quota entry for `requested`, or the available labels when nothing matches
+pub fn find_model_quota<'a>(
+    snapshot: &'a QuotaSnapshot,
+    requested: &str,
+    aliases: &ModelAliases,
+) -> Result<ModelMatch<'a>, Vec<String>> {
+    let wanted = canonicalize(requested);
+
+    // Alias targets are matched like direct requests, then reported as Alias
+    let alias_targets: Vec<&String> = aliases
+        .iter()
+        .filter(|(alias, _)| alias.eq_ignore_ascii_case(requested.trim()) || canonicalize(alias) == wanted)
+        .flat_map(|(_, targets)| targets)
+        .collect();
+
+    let best = snapshot
+        .models
+        .iter()
+        .filter_map(|model| {
+            let direct = score(model, requested, &wanted);
+            let via_alias = alias_targets
+                .iter()
+                .filter_map(|target| score(model, target, &canonicalize(target)))
+                .filter(|(confidence, _)| *confidence >= MatchConfidence::Canonical)
+                .map(|_| (MatchConfidence::Alias, 0))
+                .next();
+            direct.max(via_alias).map(|s| (model, s))
+        })
+        .max_by_key(|(_, (confidence, extra))| (*confidence, std::cmp::Reverse(*extra)));
+
+    match best {
+        Some((model, (confidence, _))) => Ok(ModelMatch { model, confidence }),
+        None => Err(snapshot.models.iter().map(|m| m.label.clone()).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(label: &str, model_id: &str) -> ModelQuotaInfo {
+        ModelQuotaInfo {
+            label: label.to_string(),
+            model_id: model_id.to_string(),
+            remaining_percentage: 50.0,
+            is_exhausted: false,
+            reset_time: String::new(),
+            time_until_reset: String::new(),
+        }
+    }
+
+    fn snapshot() -> QuotaSnapshot {
+        QuotaSnapshot {
+            timestamp: String::new(),
+            prompt_credits: None,
+            flow_credits: None,
+            token_usage: None,
+            user_info: None,
+            models: vec![
+                model("Gemini 2.0 Flash", "gemini-2.0-flash-exp"),
+                model("Gemini 2.5 Flash Thinking", "gemini-2.5-flash-thinking"),
+                model("Gemini 2.5 Pro", "gemini-2.5-pro"),
+                model("Claude Sonnet 4", "claude-sonnet-4"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_requests_match_labels_and_ids() {
+        let snapshot = snapshot();
+        let aliases = ModelAliases::new();
+        let find = |requested: &str| {
+            find_model_quota(&snapshot, requested, &aliases).map(|m| (m.model.label.clone(), m.confidence))
+        };
+
+        assert_eq!(find("gemini-flash"), Ok(("Gemini 2.0 Flash".to_string(), MatchConfidence::Canonical)));
+        assert_eq!(find("gemini-2.5-pro"), Ok(("Gemini 2.5 Pro".to_string(), MatchConfidence::Exact)));
+        assert_eq!(find("thinking"), Ok(("Gemini 2.5 Flash Thinking".to_string(), MatchConfidence::Partial)));
+        assert_eq!(find("sonnet"), Ok(("Claude Sonnet 4".to_string(), MatchConfidence::Partial)));
+        assert_eq!(find("gemini-gemini-flash"), Ok(("Gemini 2.0 Flash".to_string(), MatchConfidence::Canonical)));
+        assert_eq!(find("gemini-flash-thinking-flash"), Ok(("Gemini 2.5 Flash Thinking".to_string(), MatchConfidence::Canonical)));
+
+        let unmatched = find("gpt-4o").unwrap_err();
+        assert_eq!(unmatched.len(), 4);
+        assert!(unmatched.contains(&"Gemini 2.5 Pro".to_string()));
+    }
+
+    #[test]
+    fn test_aliases_override_partial_matches() {
+        let snapshot = snapshot();
+        let mut aliases = ModelAliases::new();
+        aliases.insert("fast".to_string(), vec!["gemini-2.5-flash-thinking".to_string()]);
+        aliases.insert("pro".to_string(), vec!["Claude Sonnet".to_string()]);
+
+        let fast = find_model_quota(&snapshot, "fast", &aliases).unwrap();
+        assert_eq!(fast.model.model_id, "gemini-2.5-flash-thinking");
+        assert_eq!(fast.confidence, MatchConfidence::Alias);
+
+        // Without the alias "pro" would partially match Gemini 2.5 Pro
+        let pro = find_model_quota(&snapshot, "pro", &aliases).unwrap();
+        assert_eq!(pro.model.label, "Claude Sonnet 4");
+        assert_eq!(canonicalize("gemini-2.0-flash-exp"), vec!["gemini", "flash"]);
+    }
+}
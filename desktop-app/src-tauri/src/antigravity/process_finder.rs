@@ -1,7 +1,7 @@
 // ProcessFinder: Detects Antigravity Language Server process across platforms
 // Ported from Antigravity Toolkit (TypeScript → Rust)
 
-use std::process::Command;
+use crate::child_env::system_command;
 use std::time::Duration;
 use tokio::time::sleep;
 use crate::antigravity::types::*;
@@ -130,6 +130,48 @@ impl ProcessFinder {
         })
     }
     
+    /// PIDs of running language server processes
+    ///
+    /// Cheap enumeration for polling: one tasklist/ps call, no command line
+    /// parsing, parent lookups or port probing.
+    pub fn list_server_pids(&self) -> Result<Vec<u32>, String> {
+        let mut pids = Vec::new();
+        
+        if self.platform == "windows" {
+            let output = system_command("tasklist")
+                .args(["/FI", &format!("IMAGENAME eq {}", self.process_name), "/FO", "CSV", "/NH"])
+                .output()
+                .map_err(|e| format!("Failed to run tasklist: {}", e))?;
+            
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                let parts: Vec<&str> = line.split(',').map(|s| s.trim_matches('"')).collect();
+                if let Some(pid) = parts.get(1).and_then(|p| p.parse::<u32>().ok()) {
+                    pids.push(pid);
+                }
+            }
+        } else {
+            let output = system_command("ps")
+                .args(["-A", "-o", "pid=,comm="])
+                .output()
+                .map_err(|e| format!("Failed to run ps: {}", e))?;
+            
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                let mut parts = line.split_whitespace();
+                let pid = parts.next().and_then(|p| p.parse::<u32>().ok());
+                let comm = parts.collect::<Vec<_>>().join(" ");
+                // comm is truncated to 15 chars on Linux, so also accept a long enough prefix
+                if let Some(pid) = pid {
+                    if comm.contains(&self.process_name) || (comm.len() >= 15 && self.process_name.starts_with(&comm)) {
+                        pids.push(pid);
+                    }
+                }
+            }
+        }
+        
+        pids.sort_unstable();
+        Ok(pids)
+    }
+    
     /// Get all candidate processes matching the server name
     fn get_process_candidates(&self) -> Result<Vec<ProcessInfo>, String> {
         match self.platform.as_str() {
@@ -142,7 +184,7 @@ impl ProcessFinder {
     /// Get processes on Windows using tasklist and wmic
     fn get_windows_processes(&self) -> Result<Vec<ProcessInfo>, String> {
         // Use tasklist to find PIDs
-        let output = Command::new("tasklist")
+        let output = system_command("tasklist")
             .args(&["/FI", &format!("IMAGENAME eq {}", self.process_name), "/FO", "CSV", "/NH"])
             .output()
             .map_err(|e| format!("Failed to run tasklist: {}", e))?;
@@ -173,7 +215,7 @@ impl ProcessFinder {
             pid
         );
         
-        let output = Command::new("powershell")
+        let output = system_command("powershell")
             .args(&["-NoProfile", "-Command", &ps_script])
             .output()
             .map_err(|e| format!("Failed to run PowerShell: {}", e))?;
@@ -214,7 +256,7 @@ impl ProcessFinder {
     
     /// Get processes on Unix (macOS/Linux) using ps
     fn get_unix_processes(&self) -> Result<Vec<ProcessInfo>, String> {
-        let output = Command::new("ps")
+        let output = system_command("ps")
             .args(&["aux"])
             .output()
             .map_err(|e| format!("Failed to run ps: {}", e))?;
@@ -304,7 +346,7 @@ impl ProcessFinder {
     
     /// Get parent PID on Unix
     fn get_parent_pid_unix(&self, pid: u32) -> Result<u32, String> {
-        let output = Command::new("ps")
+        let output = system_command("ps")
             .args(&["-o", "ppid=", "-p", &pid.to_string()])
             .output()
             .map_err(|e| format!("Failed to get PPID: {}", e))?;
@@ -350,7 +392,7 @@ impl ProcessFinder {
     
     /// Get listening ports on Windows using netstat
     fn get_windows_ports(&self, pid: u32) -> Result<Vec<u16>, String> {
-        let output = Command::new("netstat")
+        let output = system_command("netstat")
             .args(&["-ano"])
             .output()
             .map_err(|e| format!("Failed to run netstat: {}", e))?;
@@ -380,7 +422,7 @@ impl ProcessFinder {
     /// Get listening ports on Unix using lsof or netstat
     fn get_unix_ports(&self, pid: u32) -> Result<Vec<u16>, String> {
         // Try lsof first (more reliable)
-        let result = Command::new("lsof")
+        let result = system_command("lsof")
             .args(&["-iTCP", "-sTCP:LISTEN", "-n", "-P", "-p", &pid.to_string()])
             .output();
         
@@ -469,8 +511,7 @@ impl ProcessFinder {
     async fn test_port_with_protocol(&self, port: u16, csrf_token: &str, protocol: &str) -> TestPortResult {
         let url = format!("{}://127.0.0.1:{}/exa.language_server_pb.LanguageServerService/GetUnleashData", protocol, port);
         
-        let client = reqwest::Client::builder()
-            .danger_accept_invalid_certs(true) // Accept self-signed certs
+        let client = crate::http_client::local_builder()
             .timeout(Duration::from_secs(3))
             .build()
             .unwrap();
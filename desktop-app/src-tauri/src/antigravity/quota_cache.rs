@@ -0,0 +1,129 @@
+// QuotaCache: Per-account quota snapshots persisted across restarts
+//
+// Written on every sync and loaded when the API server starts, so the
+// extension and the desktop UI have data immediately after a restart. Entries
+// older than STALE_AFTER_MINUTES are still served but flagged `stale`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+
+use crate::antigravity::quota_service::QuotaSnapshot;
+
+/// Snapshots older than this are flagged stale
+pub const STALE_AFTER_MINUTES: i64 = 10;
+
+/// Snapshot for one account, tagged with its source email and fetch time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedQuota {
+    pub email: String,
+    pub fetched_at: String, // ISO 8601
+    pub snapshot: QuotaSnapshot,
+}
+
+impl CachedQuota {
+    pub fn new(email: String, snapshot: QuotaSnapshot) -> Self {
+        Self {
+            email,
+            fetched_at: chrono::Utc::now().to_rfc3339(),
+            snapshot,
+        }
+    }
+
+    /// Whether the snapshot is older than the freshness threshold
+    pub fn is_stale(&self) -> bool {
+        chrono::DateTime::parse_from_rfc3339(&self.fetched_at)
+            .map(|t| chrono::Utc::now().signed_duration_since(t) > chrono::Duration::minutes(STALE_AFTER_MINUTES))
+            .unwrap_or(true)
+    }
+
+    pub fn view(&self) -> CachedQuotaView {
+        CachedQuotaView {
+            email: self.email.clone(),
+            fetched_at: self.fetched_at.clone(),
+            stale: self.is_stale(),
+            snapshot: self.snapshot.clone(),
+        }
+    }
+}
+
+/// Cached quota as returned to consumers
+#[derive(Debug, Clone, Serialize)]
+pub struct CachedQuotaView {
+    pub email: String,
+    pub fetched_at: String,
+    pub stale: bool,
+    pub snapshot: QuotaSnapshot,
+}
+
+/// Persisted cache file layout
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuotaCache {
+    /// Email the IDE reported on the last sync
+    #[serde(default)]
+    pub current_email: Option<String>,
+    /// Latest snapshot per account email
+    #[serde(default)]
+    pub accounts: BTreeMap<String, CachedQuota>,
+}
+
+impl QuotaCache {
+    /// Cached snapshot of the account the IDE is signed into
+    pub fn current(&self) -> Option<&CachedQuota> {
+        self.current_email.as_ref().and_then(|email| self.accounts.get(email))
+    }
+
+    /// Record a fresh snapshot for `email` and make it the current account
+    pub fn update(&mut self, email: &str, snapshot: QuotaSnapshot) {
+        self.current_email = Some(email.to_string());
+        self.accounts
+            .insert(email.to_string(), CachedQuota::new(email.to_string(), snapshot));
+    }
+}
+
+fn get_cache_path() -> PathBuf {
+    crate::get_app_config_dir().join("quota_cache.json")
+}
+
+/// Load the cache; a corrupted file is discarded (and logged), never fatal
+pub fn load() -> QuotaCache {
+    let path = get_cache_path();
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) => return QuotaCache::default(),
+    };
+
+    match serde_json::from_str(&content) {
+        Ok(cache) => cache,
+        Err(e) => {
+            eprintln!("QuotaCache: discarding corrupted {}: {}", path.display(), e);
+            let _ = std::fs::remove_file(&path);
+            QuotaCache::default()
+        }
+    }
+}
+
+/// Persist the cache (temp file + rename so readers never see a partial file)
+pub fn save(cache: &QuotaCache) -> Result<(), String> {
+    let path = get_cache_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(cache)
+        .map_err(|e| format!("Failed to serialize quota cache: {}", e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to save quota cache: {}", e))?;
+    std::fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Failed to save quota cache: {}", e))
+}
+
+/// Get the cached quota of the current account (works before any sync this session)
+#[tauri::command]
+pub fn get_cached_quota() -> Result<Option<CachedQuotaView>, String> {
+    crate::command_metrics::timed_sync("get_cached_quota", move || {
+        Ok(load().current().map(CachedQuota::view))
+    })
+}
@@ -178,13 +178,27 @@ impl QuotaService {
     
     /// Fetch quota with retry (2 attempts)
     pub async fn fetch_quota(&self, server_info: &LanguageServerInfo) -> Result<QuotaSnapshot, String> {
-        // Try once, if fails try again after 1s delay
-        match self.do_fetch_quota(server_info).await {
-            Ok(snapshot) => Ok(snapshot),
-            Err(e) => {
-                eprintln!("QuotaService: First attempt failed ({}), retrying...", e);
-                tokio::time::sleep(Duration::from_secs(1)).await;
-                self.do_fetch_quota(server_info).await
+        self.fetch_quota_with_retry(server_info, 2, Duration::from_secs(1)).await
+    }
+    
+    /// Fetch quota, retrying up to `attempts` times with a fixed delay
+    pub async fn fetch_quota_with_retry(
+        &self,
+        server_info: &LanguageServerInfo,
+        attempts: u32,
+        delay: Duration,
+    ) -> Result<QuotaSnapshot, String> {
+        let attempts = attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            match self.do_fetch_quota(server_info).await {
+                Ok(snapshot) => return Ok(snapshot),
+                Err(e) if attempt < attempts => {
+                    eprintln!("QuotaService: Attempt {} failed ({}), retrying...", attempt, e);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
             }
         }
     }
@@ -245,8 +259,7 @@ impl QuotaService {
         csrf_token: &str,
         body: &serde_json::Value
     ) -> Result<HttpResponse<T>, String> {
-        let client = reqwest::Client::builder()
-            .danger_accept_invalid_certs(true)
+        let client = crate::http_client::local_builder()
             .timeout(Duration::from_secs(5))
             .build()
             .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
@@ -0,0 +1,214 @@
+// Watcher: Background detection of the Antigravity IDE starting or stopping
+//
+// Polls the (cheap) language server PID list and only runs full detection
+// with port probing when that set changes. Emits `antigravity-connected` /
+// `antigravity-disconnected` and keeps the last known connection in memory.
+// Saving a changed `antigravityWatcher` block wakes the loop so it applies at once.
+
+use std::sync::RwLock;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+use tokio::sync::Notify;
+
+use crate::antigravity::{DetectOptions, LanguageServerInfo, ProcessFinder};
+use crate::api_server::SharedApiState;
+
+/// Probe attempts while the process exists but no port answers yet (IDE still starting)
+const MAX_PROBE_RETRIES: u32 = 3;
+
+/// `antigravityWatcher` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WatcherSettings {
+    /// Set to false to pause the watcher
+    pub enabled: bool,
+    pub interval_secs: u64,
+    /// Run one quota sync whenever the IDE connects
+    pub auto_sync_on_connect: bool,
+}
+
+impl Default for WatcherSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 15,
+            auto_sync_on_connect: false,
+        }
+    }
+}
+
+impl WatcherSettings {
+    /// Load the watcher block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("antigravityWatcher").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Last known connection to the language server
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConnectionState {
+    pub connected: bool,
+    pub pids: Vec<u32>,
+    pub port: Option<u16>,
+    /// Not serialized: carries the CSRF token
+    #[serde(skip)]
+    pub server: Option<LanguageServerInfo>,
+    pub paused: bool,
+    pub changed_at: Option<String>,
+}
+
+static CONNECTION: RwLock<Option<ConnectionState>> = RwLock::new(None);
+
+/// Cuts the wait between polls short (the settings changed)
+static WAKE: Notify = Notify::const_new();
+
+/// Current connection state
+pub fn connection_state() -> ConnectionState {
+    CONNECTION
+        .read()
+        .ok()
+        .and_then(|c| c.clone())
+        .unwrap_or_default()
+}
+
+fn update_connection<F: FnOnce(&mut ConnectionState)>(f: F) {
+    if let Ok(mut guard) = CONNECTION.write() {
+        f(guard.get_or_insert_with(ConnectionState::default));
+    }
+}
+
+/// Payload of `antigravity-connected` / `antigravity-disconnected`
+#[derive(Debug, Clone, Serialize)]
+struct ConnectionEvent {
+    pids: Vec<u32>,
+    port: Option<u16>,
+}
+
+/// Spawn the watcher loop
+pub fn spawn(app: tauri::AppHandle) {
+    crate::settings_events::listen(&["/antigravityWatcher"], |_| WAKE.notify_one());
+    tauri::async_runtime::spawn(async move {
+        let mut known_pids: Option<Vec<u32>> = None;
+        let mut probe_failures = 0;
+
+        loop {
+            let settings = WatcherSettings::load();
+            update_connection(|c| c.paused = !settings.enabled);
+
+            if settings.enabled {
+                let pids = match tauri::async_runtime::spawn_blocking(move || {
+                    ProcessFinder::new().list_server_pids()
+                })
+                .await
+                {
+                    Ok(Ok(pids)) => Some(pids),
+                    Ok(Err(e)) => {
+                        eprintln!("Antigravity watcher: {}", e);
+                        None
+                    }
+                    Err(e) => {
+                        eprintln!("Antigravity watcher: enumeration task failed: {}", e);
+                        None
+                    }
+                };
+
+                if let Some(pids) = pids {
+                    let changed = known_pids.as_ref() != Some(&pids);
+                    let retry = !changed
+                        && !pids.is_empty()
+                        && !connection_state().connected
+                        && probe_failures < MAX_PROBE_RETRIES;
+
+                    if changed {
+                        probe_failures = 0;
+                    }
+                    if (changed || retry) && !handle_change(&app, &pids, &settings).await && !pids.is_empty() {
+                        probe_failures += 1;
+                    }
+                    known_pids = Some(pids);
+                }
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(settings.interval_secs.max(1))) => {}
+                _ = WAKE.notified() => {}
+            }
+        }
+    });
+
+    eprintln!("Antigravity watcher started for {}", ProcessFinder::new().get_process_name());
+}
+
+/// React to a changed process set; returns whether the server is reachable
+async fn handle_change(app: &tauri::AppHandle, pids: &[u32], settings: &WatcherSettings) -> bool {
+    let previous = connection_state();
+    let was_connected = previous.connected;
+    let previous_port = previous.port;
+
+    if pids.is_empty() {
+        update_connection(|c| {
+            c.connected = false;
+            c.pids.clear();
+            c.port = None;
+            c.server = None;
+            c.changed_at = Some(chrono::Utc::now().to_rfc3339());
+        });
+        if was_connected {
+            let _ = app.emit("antigravity-disconnected", ConnectionEvent { pids: Vec::new(), port: None });
+        }
+        return false;
+    }
+
+    // Full detection (port probing) only now that the process set changed
+    let mut finder = ProcessFinder::new();
+    let detected = finder
+        .detect(DetectOptions {
+            attempts: 1,
+            base_delay: 0,
+            verbose: false,
+        })
+        .await
+        .ok();
+
+    let connected = detected.is_some();
+    let port = detected.as_ref().map(|info| info.port);
+    update_connection(|c| {
+        c.connected = connected;
+        c.pids = pids.to_vec();
+        c.port = port;
+        c.server = detected.clone();
+        c.changed_at = Some(chrono::Utc::now().to_rfc3339());
+    });
+
+    // A new port while connected means the IDE restarted; report it as a reconnect
+    if connected && (!was_connected || port != previous_port) {
+        let _ = app.emit("antigravity-connected", ConnectionEvent { pids: pids.to_vec(), port });
+
+        if settings.auto_sync_on_connect {
+            if let Some(state) = app.try_state::<SharedApiState>() {
+                let result = crate::api_server::sync_quota(state.inner().clone(), detected).await;
+                if !result.success {
+                    eprintln!("Antigravity watcher: auto sync failed: {}", result.message);
+                }
+            }
+        }
+    } else if !connected && was_connected {
+        let _ = app.emit("antigravity-disconnected", ConnectionEvent { pids: pids.to_vec(), port: None });
+    }
+
+    connected
+}
+
+/// Get the watcher's view of the Antigravity connection
+#[tauri::command]
+pub fn get_antigravity_connection() -> Result<ConnectionState, String> {
+    crate::command_metrics::timed_sync("get_antigravity_connection", move || {
+        Ok(connection_state())
+    })
+}
@@ -1,7 +1,8 @@
 /// REST API Server for Vibecode Desktop App
 /// 
 /// Provides HTTP endpoints for VS Code Extension communication
-/// Runs on localhost:7890 (configurable)
+/// Runs on localhost:7890 (`apiServerPort` in settings.json; saving a new
+/// port moves the server there, and the old one stops once it is bound)
 /// 
 /// Endpoints:
 /// - GET /api/health               → Health check
@@ -9,24 +10,162 @@
 /// - GET /api/accounts/best        → Get best account for model
 /// - GET /api/accounts/current     → Get current active account
 /// - POST /api/accounts/switch     → Switch to different account
-/// - POST /api/quota/sync          → Trigger quota sync from Antigravity
+/// - POST /api/quota/sync          → Trigger quota sync from Antigravity (504 past the deadline)
+/// - GET /api/quota/cached         → Last cached quota of the current account (may be stale)
+/// - POST /api/skills/{id}/scripts/{name}/run?mode=queue|reject
+///                                 → Start a skill script run (429 when busy in reject mode;
+///                                   403 from a web page other than the app, see `trusted_origin`)
 
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{watch, RwLock};
 use warp::Filter;
 use serde::{Deserialize, Serialize};
 
+use crate::script_runner;
 use crate::services::{AccountService, SavedAccount};
-use crate::antigravity::{ProcessFinder, QuotaService, DetectOptions};
+use crate::antigravity::{ProcessFinder, QuotaService, DetectOptions, LanguageServerInfo};
+use crate::antigravity::model_match::{self, MatchConfidence, ModelAliases};
+use crate::antigravity::quota_cache::{self, CachedQuota, QuotaCache};
 use crate::antigravity::quota_service::QuotaSnapshot;
 
-/// API Server configuration
+/// API Server configuration: port used without an `apiServerPort` setting
 pub const API_PORT: u16 = 7890;
 
+/// Origins of the app's own webview (bundled, and `tauri dev`)
+const APP_ORIGINS: [&str; 3] = ["tauri://localhost", "http://tauri.localhost", "http://localhost:1420"];
+
+/// Port the server was bound to
+static BOUND_PORT: AtomicU16 = AtomicU16::new(API_PORT);
+
+/// Stops the server currently bound (gracefully, letting requests finish)
+static SHUTDOWN: Mutex<Option<tokio::sync::oneshot::Sender<()>>> = Mutex::new(None);
+
+/// The `apiServerPort` setting, or `API_PORT`
+pub fn configured_port() -> u16 {
+    crate::load_settings_file()
+        .ok()
+        .flatten()
+        .and_then(|settings| settings.get("apiServerPort").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or(API_PORT)
+}
+
+/// `apiSync` block of settings.json: limits for `POST /api/quota/sync`
+///
+/// Extension HTTP clients give up after ~30s and retry, so this path gets a
+/// tighter budget than the desktop sync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ApiSyncSettings {
+    /// Overall deadline for detection + quota fetch
+    pub deadline_secs: u64,
+    pub detect_attempts: u32,
+    pub detect_base_delay_ms: u64,
+    pub fetch_attempts: u32,
+    pub fetch_retry_delay_ms: u64,
+}
+
+impl Default for ApiSyncSettings {
+    fn default() -> Self {
+        Self {
+            deadline_secs: 15,
+            detect_attempts: 2,
+            detect_base_delay_ms: 500,
+            fetch_attempts: 2,
+            fetch_retry_delay_ms: 500,
+        }
+    }
+}
+
+impl ApiSyncSettings {
+    /// Load the apiSync block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("apiSync").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Retry/backoff parameters for one sync run
+struct SyncRetry {
+    detect: DetectOptions,
+    fetch_attempts: u32,
+    fetch_retry_delay: Duration,
+}
+
+impl SyncRetry {
+    /// Desktop path: patient, verbose detection
+    fn desktop() -> Self {
+        Self {
+            detect: DetectOptions {
+                attempts: 3,
+                base_delay: 1500,
+                verbose: true,
+            },
+            fetch_attempts: 2,
+            fetch_retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl From<&ApiSyncSettings> for SyncRetry {
+    fn from(settings: &ApiSyncSettings) -> Self {
+        Self {
+            detect: DetectOptions {
+                attempts: settings.detect_attempts.max(1),
+                base_delay: settings.detect_base_delay_ms,
+                verbose: false,
+            },
+            fetch_attempts: settings.fetch_attempts,
+            fetch_retry_delay: Duration::from_millis(settings.fetch_retry_delay_ms),
+        }
+    }
+}
+
+/// Progress notes of a sync run, kept so a timed-out run can report how far it got
+type Diagnostics = Arc<Mutex<Vec<String>>>;
+
+fn note(diagnostics: &Diagnostics, message: String) {
+    if let Ok(mut notes) = diagnostics.lock() {
+        notes.push(format!("{} {}", chrono::Utc::now().format("%H:%M:%S%.3f"), message));
+    }
+}
+
+fn notes(diagnostics: &Diagnostics) -> Vec<String> {
+    diagnostics.lock().map(|n| n.clone()).unwrap_or_default()
+}
+
+/// Result of the sync in flight; concurrent `POST /api/quota/sync` requests wait on it
+static IN_FLIGHT_SYNC: Mutex<Option<watch::Receiver<Option<Arc<SyncResponse>>>>> = Mutex::new(None);
+
 /// Shared state containing Tauri AppHandle and cached quota
 pub struct ApiState {
     pub app: tauri::AppHandle,
-    pub cached_quota: Option<QuotaSnapshot>,
+    /// Per-account snapshots, persisted to quota_cache.json (snapshots without
+    /// an email are never cached, since they can't be attributed to an account)
+    pub quota_cache: QuotaCache,
+}
+
+/// API state shared between the server, background tasks and commands
+pub type SharedApiState = Arc<RwLock<ApiState>>;
+
+/// Create the shared API state (managed by the app so other subsystems can use it)
+///
+/// Starts from the persisted quota cache so consumers have (possibly stale)
+/// data before the first sync.
+pub fn create_state(app: tauri::AppHandle) -> SharedApiState {
+    Arc::new(RwLock::new(ApiState {
+        app,
+        quota_cache: quota_cache::load(),
+    }))
 }
 
 /// Account response with quota info
@@ -60,6 +199,11 @@ pub struct BestAccountResponse {
     pub available_quota: i64,
     pub percentage: f64,
     pub model: String,
+    /// Antigravity label the requested model resolved to
+    pub matched_model: Option<String>,
+    pub match_confidence: Option<MatchConfidence>,
+    /// Labels in the account's snapshot when the model couldn't be matched
+    pub available_models: Vec<String>,
 }
 
 /// Sync response
@@ -70,6 +214,27 @@ pub struct SyncResponse {
     pub current_account: Option<String>,
     pub message: String,
     pub quota: Option<QuotaSnapshot>,
+    /// The reported email didn't match a saved account and was added
+    pub auto_added: bool,
+    /// The overall deadline was exceeded (served as HTTP 504)
+    pub timed_out: bool,
+    /// Steps completed so far, with timestamps
+    pub diagnostics: Vec<String>,
+}
+
+impl SyncResponse {
+    fn failure(message: String, diagnostics: &Diagnostics) -> Self {
+        Self {
+            success: false,
+            synced_accounts: 0,
+            current_account: None,
+            message,
+            quota: None,
+            auto_added: false,
+            timed_out: false,
+            diagnostics: notes(diagnostics),
+        }
+    }
 }
 
 /// Health check response
@@ -81,6 +246,13 @@ pub struct HealthResponse {
     pub antigravity_detected: bool,
 }
 
+/// Script run query params
+#[derive(Debug, Deserialize)]
+pub struct ScriptRunQuery {
+    /// "queue" (default) waits for a free slot, "reject" answers 429 instead
+    pub mode: Option<String>,
+}
+
 /// Switch account request
 #[derive(Debug, Deserialize)]
 pub struct SwitchAccountRequest {
@@ -99,13 +271,26 @@ pub struct SwitchAccountResponse {
 /// Google Account Chooser URL
 const GOOGLE_ACCOUNT_CHOOSER_URL: &str = "https://accounts.google.com/AccountChooser";
 
+/// Templates of the routes served below, naming requests in metrics and spans
+const ROUTES: &[&str] = &[
+    "/api/health",
+    "/api/accounts",
+    "/api/accounts/best",
+    "/api/accounts/current",
+    "/api/accounts/switch",
+    "/api/quota/sync",
+    "/api/quota/cached",
+    "/api/skills/{id}/scripts/{name}/run",
+    "/api/version",
+    "/api/metrics",
+];
+
 /// Start the REST API server
-pub async fn start_server(app: tauri::AppHandle) {
-    let state = Arc::new(RwLock::new(ApiState { 
-        app,
-        cached_quota: None,
-    }));
-    
+///
+/// Binds the listener and spawns the server in the background, returning the
+/// bound address. Bind failures are returned instead of panicking so startup
+/// can report them.
+pub async fn start_server(state: SharedApiState) -> Result<SocketAddr, String> {
     // CORS configuration for localhost
     let cors = warp::cors()
         .allow_any_origin()
@@ -168,6 +353,40 @@ pub async fn start_server(app: tauri::AppHandle) {
             }
         });
     
+    // GET /api/quota/cached
+    let state_cached = state.clone();
+    let cached_quota = warp::path!("api" / "quota" / "cached")
+        .and(warp::get())
+        .and_then(move || {
+            let state = state_cached.clone();
+            async move {
+                get_cached_quota_handler(state).await
+            }
+        });
+    
+    // POST /api/skills/{id}/scripts/{name}/run?mode=queue|reject
+    let state_script = state.clone();
+    let run_script = warp::path!("api" / "skills" / String / "scripts" / String / "run")
+        .and(warp::post())
+        .and(warp::query::<ScriptRunQuery>())
+        .and(warp::header::optional::<String>("origin"))
+        .and_then(move |skill_id: String, script_name: String, query: ScriptRunQuery, origin: Option<String>| {
+            let state = state_script.clone();
+            async move {
+                run_skill_script_handler(state, skill_id, script_name, query, origin).await
+            }
+        });
+    
+    // GET /api/version
+    let version = warp::path!("api" / "version")
+        .and(warp::get())
+        .map(|| warp::reply::json(&crate::version_info::collect()));
+    
+    // GET /api/metrics
+    let metrics = warp::path!("api" / "metrics")
+        .and(warp::get())
+        .map(|| warp::reply::json(&crate::command_metrics::snapshot()));
+    
     // POST /api/accounts/switch
     let switch_account = warp::path!("api" / "accounts" / "switch")
         .and(warp::post())
@@ -183,14 +402,57 @@ pub async fn start_server(app: tauri::AppHandle) {
         .or(best_account)
         .or(current_account)
         .or(sync_quota)
+        .or(cached_quota)
+        .or(run_script)
+        .or(version)
+        .or(metrics)
         .or(switch_account)
-        .with(cors);
+        .with(cors)
+        .with(warp::trace(|info| {
+            let route = crate::command_metrics::route_name(info.method().as_str(), info.path(), ROUTES);
+            tracing::info_span!("rest", route = %route)
+        }))
+        .with(warp::log::custom(|info| {
+            crate::command_metrics::record(
+                crate::command_metrics::SOURCE_REST,
+                &crate::command_metrics::route_name(info.method().as_str(), info.path(), ROUTES),
+                info.elapsed(),
+                !info.status().is_server_error(),
+            );
+        }));
     
-    println!("🚀 Vibecode API Server starting on http://localhost:{}", API_PORT);
+    // Serve on `port`, then stop the server bound before
+    let bind = move |port: u16| -> Result<SocketAddr, String> {
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let (addr, server) = warp::serve(routes.clone())
+            .try_bind_with_graceful_shutdown(([127, 0, 0, 1], port), async move {
+                let _ = stopped.await;
+            })
+            .map_err(|e| format!("Failed to bind API server on port {}: {}", port, e))?;
+        BOUND_PORT.store(addr.port(), Ordering::SeqCst);
+        if let Some(previous) = SHUTDOWN.lock().unwrap_or_else(|e| e.into_inner()).replace(stop) {
+            let _ = previous.send(());
+        }
+
+        println!("🚀 Vibecode API Server starting on http://localhost:{}", addr.port());
+
+        tauri::async_runtime::spawn(server);
+        Ok(addr)
+    };
+
+    let addr = bind(configured_port())?;
+    crate::settings_events::listen(&["/apiServerPort"], move |change| {
+        let port = change.settings.api_server_port;
+        if port == BOUND_PORT.load(Ordering::SeqCst) {
+            return;
+        }
+        // Keep serving on the old port when the new one is taken
+        if let Err(e) = bind(port) {
+            eprintln!("API server: {}", e);
+        }
+    });
     
-    warp::serve(routes)
-        .run(([127, 0, 0, 1], API_PORT))
-        .await;
+    Ok(addr)
 }
 
 /// Handler: Health check with Antigravity detection
@@ -204,7 +466,7 @@ async fn health_handler(
     Ok(warp::reply::json(&HealthResponse {
         status: "ok".to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
-        port: API_PORT,
+        port: BOUND_PORT.load(Ordering::SeqCst),
         antigravity_detected,
     }))
 }
@@ -228,7 +490,7 @@ async fn get_accounts_handler(
                 })
                 .collect();
             
-            let current = accounts.first().map(|a| a.email.clone());
+            let current = current_account(&accounts, state.quota_cache.current_email.as_deref()).map(|a| a.email.clone());
             let total = account_responses.len();
             
             Ok(warp::reply::json(&AccountsResponse {
@@ -254,30 +516,24 @@ async fn get_best_account_handler(
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let state = state.read().await;
     let model = query.model.unwrap_or_else(|| "gemini-flash".to_string());
+    let aliases = model_match::load_aliases();
     
     match AccountService::get_accounts(&state.app) {
         Ok(accounts) => {
-            // TODO: Implement actual quota comparison using cached_quota
-            // For now, return the most recently used account (sorted by last_seen)
-            if let Some(best) = accounts.first() {
-                // Calculate quota from cached data if available
-                let (available_quota, percentage) = if let Some(ref quota) = state.cached_quota {
-                    // Use cached quota data
-                    if let Some(ref pc) = quota.prompt_credits {
-                        let available = pc.available;
-                        let pct = pc.used_percentage;
-                        (available, pct)
-                    } else {
-                        (1000, 0.0) // Default
-                    }
-                } else {
-                    (1000, 0.0) // Default placeholder
-                };
+            if let Some((best, quota)) = rank_best_account(&accounts, &state.quota_cache.accounts, &model, &aliases) {
+                let (available_quota, percentage) = quota
+                    .and_then(|q| q.prompt_credits.as_ref())
+                    .map(|pc| (pc.available, pc.used_percentage))
+                    .unwrap_or((1000, 0.0)); // Default placeholder until the account is synced
+                let matched = quota.map(|q| model_match::find_model_quota(q, &model, &aliases));
                 
                 Ok(warp::reply::json(&BestAccountResponse {
                     email: best.email.clone(),
                     available_quota,
                     percentage,
+                    matched_model: matched.as_ref().and_then(|m| m.as_ref().ok()).map(|m| m.model.label.clone()),
+                    match_confidence: matched.as_ref().and_then(|m| m.as_ref().ok()).map(|m| m.confidence),
+                    available_models: matched.and_then(Result::err).unwrap_or_default(),
                     model,
                 }))
             } else {
@@ -296,6 +552,49 @@ async fn get_best_account_handler(
     }
 }
 
+/// Remaining percentage (in tenths) of the requested model, None when the
+/// snapshot has no matching entry
+fn model_remaining(quota: &QuotaSnapshot, model: &str, aliases: &ModelAliases) -> Option<i64> {
+    model_match::find_model_quota(quota, model, aliases)
+        .ok()
+        .map(|m| if m.model.is_exhausted { 0 } else { (m.model.remaining_percentage * 10.0).round() as i64 })
+}
+
+/// Pick the best account using each account's own snapshot
+///
+/// Synced accounts rank above never-synced ones, accounts whose snapshot shows
+/// the model exhausted rank below the rest, then the most remaining quota for
+/// the requested model and finally the most available prompt credits win.
+/// Ties keep the most recently seen account.
+fn rank_best_account<'a>(
+    accounts: &'a [SavedAccount],
+    quotas: &'a BTreeMap<String, CachedQuota>,
+    model: &str,
+    aliases: &ModelAliases,
+) -> Option<(&'a SavedAccount, Option<&'a QuotaSnapshot>)> {
+    accounts
+        .iter()
+        .enumerate()
+        .map(|(i, account)| (i, account, quotas.get(&account.email).map(|c| &c.snapshot)))
+        .max_by_key(|(i, _, quota)| {
+            let synced = quota.is_some();
+            let remaining = quota.and_then(|q| model_remaining(q, model, aliases));
+            let usable = remaining != Some(0);
+            let available = quota
+                .and_then(|q| q.prompt_credits.as_ref())
+                .map_or(0, |pc| pc.available);
+            (synced && usable, usable, remaining.unwrap_or(0), available, Reverse(*i))
+        })
+        .map(|(_, account, quota)| (account, quota))
+}
+
+/// Account the IDE is signed into, falling back to the most recently seen one
+fn current_account<'a>(accounts: &'a [SavedAccount], current_email: Option<&str>) -> Option<&'a SavedAccount> {
+    current_email
+        .and_then(|email| accounts.iter().find(|a| a.email == email))
+        .or_else(|| accounts.first())
+}
+
 /// Handler: Get current active account
 async fn get_current_account_handler(
     state: Arc<RwLock<ApiState>>,
@@ -304,7 +603,7 @@ async fn get_current_account_handler(
     
     match AccountService::get_accounts(&state.app) {
         Ok(accounts) => {
-            if let Some(current) = accounts.first() {
+            if let Some(current) = current_account(&accounts, state.quota_cache.current_email.as_deref()) {
                 Ok(warp::reply::json(&AccountResponse {
                     id: current.id.clone(),
                     email: current.email.clone(),
@@ -330,53 +629,77 @@ async fn get_current_account_handler(
 async fn sync_quota_handler(
     state: Arc<RwLock<ApiState>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    // Step 1: Detect Antigravity Language Server
-    let mut finder = ProcessFinder::new();
-    let detect_options = DetectOptions {
-        attempts: 3,
-        base_delay: 1500,
-        verbose: true,
+    let response = coalesced_sync(state).await;
+    let status = if response.timed_out {
+        warp::http::StatusCode::GATEWAY_TIMEOUT
+    } else {
+        warp::http::StatusCode::OK
     };
-    
-    let server_info = match finder.detect(detect_options).await {
-        Ok(info) => info,
-        Err(e) => {
-            return Ok(warp::reply::json(&SyncResponse {
-                success: false,
-                synced_accounts: 0,
-                current_account: None,
-                message: format!("Antigravity not detected: {}", e),
-                quota: None,
-            }));
+    Ok(warp::reply::with_status(warp::reply::json(&*response), status))
+}
+
+/// Sync quota from Antigravity into the shared state
+///
+/// Detects the language server unless `server_info` is already known (e.g.
+/// from the background watcher).
+pub async fn sync_quota(state: SharedApiState, server_info: Option<LanguageServerInfo>) -> SyncResponse {
+    run_sync(state, server_info, &SyncRetry::desktop(), &Diagnostics::default()).await
+}
+
+async fn run_sync(
+    state: SharedApiState,
+    server_info: Option<LanguageServerInfo>,
+    retry: &SyncRetry,
+    diagnostics: &Diagnostics,
+) -> SyncResponse {
+    // Step 1: Detect Antigravity Language Server
+    let server_info = match server_info {
+        Some(info) => info,
+        None => {
+            note(diagnostics, format!("detecting language server ({} attempts)", retry.detect.attempts));
+            let mut finder = ProcessFinder::new();
+            
+            match finder.detect(retry.detect.clone()).await {
+                Ok(info) => info,
+                Err(e) => {
+                    note(diagnostics, format!("detection failed: {}", e));
+                    return SyncResponse::failure(format!("Antigravity not detected: {}", e), diagnostics);
+                }
+            }
         }
     };
+    note(diagnostics, format!("language server on port {}", server_info.port));
     
     // Step 2: Fetch quota data
+    note(diagnostics, format!("fetching quota ({} attempts)", retry.fetch_attempts.max(1)));
     let quota_service = QuotaService::new();
-    let quota = match quota_service.fetch_quota(&server_info).await {
+    let quota = match quota_service
+        .fetch_quota_with_retry(&server_info, retry.fetch_attempts, retry.fetch_retry_delay)
+        .await
+    {
         Ok(snapshot) => snapshot,
         Err(e) => {
-            return Ok(warp::reply::json(&SyncResponse {
-                success: false,
-                synced_accounts: 0,
-                current_account: None,
-                message: format!("Failed to fetch quota: {}", e),
-                quota: None,
-            }));
+            note(diagnostics, format!("quota fetch failed: {}", e));
+            return SyncResponse::failure(format!("Failed to fetch quota: {}", e), diagnostics);
         }
     };
+    note(diagnostics, "quota fetched".to_string());
     
     // Step 3: Extract current email from user_info
     let current_email = quota.user_info.as_ref()
         .and_then(|u| u.email.clone());
     
+    let mut auto_added = false;
     {
         let mut state = state.write().await;
-        state.cached_quota = Some(quota.clone());
-        
         // Step 4: Sync account to database if user info available
         if let Some(ref user) = quota.user_info {
             if let Some(ref email) = user.email {
+                state.quota_cache.update(email, quota.clone());
+                if let Err(e) = quota_cache::save(&state.quota_cache) {
+                    eprintln!("Failed to persist quota cache: {}", e);
+                }
+                
                 let account = SavedAccount {
                     id: String::new(), // Will be generated
                     email: email.clone(),
@@ -387,20 +710,146 @@ async fn sync_quota_handler(
                     last_seen: chrono::Utc::now().timestamp_millis(),
                 };
                 
-                if let Err(e) = AccountService::sync_current_account(&state.app, account) {
-                    eprintln!("Failed to sync account: {}", e);
+                match AccountService::sync_current_account(&state.app, account) {
+                    Ok(added) => auto_added = added,
+                    Err(e) => eprintln!("Failed to sync account: {}", e),
                 }
             }
         }
     }
     
-    Ok(warp::reply::json(&SyncResponse {
+    SyncResponse {
         success: true,
         synced_accounts: 1,
         current_account: current_email,
-        message: "Quota synced successfully".to_string(),
+        message: if auto_added {
+            format!("Quota synced; {} was not a saved account and has been added", current_email.as_deref().unwrap_or_default())
+        } else {
+            "Quota synced successfully".to_string()
+        },
         quota: Some(quota),
-    }))
+        auto_added,
+        timed_out: false,
+        diagnostics: notes(diagnostics),
+    }
+}
+
+/// HTTP sync: the whole pipeline under the `apiSync` deadline
+async fn sync_quota_with_deadline(state: SharedApiState) -> SyncResponse {
+    let settings = ApiSyncSettings::load();
+    let deadline = Duration::from_secs(settings.deadline_secs.max(1));
+    let diagnostics = Diagnostics::default();
+    
+    let pipeline = run_sync(state, None, &SyncRetry::from(&settings), &diagnostics);
+    match tokio::time::timeout(deadline, pipeline).await {
+        Ok(response) => response,
+        Err(_) => {
+            note(&diagnostics, format!("deadline of {}s exceeded", deadline.as_secs()));
+            SyncResponse {
+                timed_out: true,
+                ..SyncResponse::failure(
+                    format!("Quota sync timed out after {}s", deadline.as_secs()),
+                    &diagnostics,
+                )
+            }
+        }
+    }
+}
+
+/// Join the sync in flight, or start one that later requests can join
+async fn coalesced_sync(state: SharedApiState) -> Arc<SyncResponse> {
+    let mut result = {
+        let mut in_flight = IN_FLIGHT_SYNC.lock().unwrap_or_else(|e| e.into_inner());
+        match in_flight.as_ref() {
+            Some(result) => result.clone(),
+            None => {
+                let (tx, rx) = watch::channel(None);
+                *in_flight = Some(rx.clone());
+                
+                // Detached so a client hanging up doesn't cancel the sync for the others
+                tokio::spawn(async move {
+                    let response = Arc::new(sync_quota_with_deadline(state).await);
+                    IN_FLIGHT_SYNC.lock().unwrap_or_else(|e| e.into_inner()).take();
+                    let _ = tx.send(Some(response));
+                });
+                rx
+            }
+        }
+    };
+    
+    loop {
+        let current = result.borrow().clone();
+        if let Some(response) = current {
+            return response;
+        }
+        if result.changed().await.is_err() {
+            return Arc::new(SyncResponse::failure(
+                "Sync task ended without a result".to_string(),
+                &Diagnostics::default(),
+            ));
+        }
+    }
+}
+
+/// Handler: Get the last quota snapshot of the current account
+/// Served even when old; the snapshot itself says whether it is stale
+async fn get_cached_quota_handler(
+    state: Arc<RwLock<ApiState>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let state = state.read().await;
+    
+    match state.quota_cache.current() {
+        Some(cached) => Ok(warp::reply::json(&cached.view())),
+        None => Ok(warp::reply::json(&serde_json::json!({
+            "error": "No cached quota"
+        }))),
+    }
+}
+
+/// Whether a request may start work: browsers send an `Origin` with every
+/// cross-site POST (even bodiless ones, which skip the CORS preflight), while
+/// the extension and other local clients send none
+fn trusted_origin(origin: Option<&str>) -> bool {
+    match origin {
+        Some(origin) => APP_ORIGINS.contains(&origin),
+        None => true,
+    }
+}
+
+/// Handler: Submit a skill script run
+async fn run_skill_script_handler(
+    state: Arc<RwLock<ApiState>>,
+    skill_id: String,
+    script_name: String,
+    query: ScriptRunQuery,
+    origin: Option<String>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    use warp::http::StatusCode;
+    use warp::Reply;
+    
+    if !trusted_origin(origin.as_deref()) {
+        let body = warp::reply::json(&serde_json::json!({ "error": "Scripts can't be started from a web page" }));
+        return Ok(warp::reply::with_status(body, StatusCode::FORBIDDEN).into_response());
+    }
+    if let Err(e) = script_runner::script_path(&skill_id, &script_name) {
+        let body = warp::reply::json(&serde_json::json!({ "error": e }));
+        return Ok(warp::reply::with_status(body, StatusCode::NOT_FOUND).into_response());
+    }
+    
+    let app = state.read().await.app.clone();
+    let reject_when_busy = query.mode.as_deref() == Some("reject");
+    
+    match script_runner::submit(app, skill_id, script_name, reject_when_busy) {
+        Ok(submitted) => {
+            let status = if submitted.queue_position.is_some() { StatusCode::ACCEPTED } else { StatusCode::OK };
+            Ok(warp::reply::with_status(warp::reply::json(&submitted), status).into_response())
+        }
+        Err(rejected) => {
+            let retry_after = rejected.retry_after_secs.to_string();
+            let reply = warp::reply::with_status(warp::reply::json(&rejected), StatusCode::TOO_MANY_REQUESTS);
+            Ok(warp::reply::with_header(reply, "Retry-After", retry_after).into_response())
+        }
+    }
 }
 
 /// Handler: Switch to a different account
@@ -0,0 +1,320 @@
+/// Audit - Append-only record of destructive operations
+///
+/// Every command that deletes or discards user data (skills, saved accounts,
+/// OAuth grants, changed-file tracking, artifact purges) appends one JSON line
+/// to `<config>/audit/audit.jsonl` describing what ran, where it came from and
+/// how it ended. Argument values under sensitive-looking keys are redacted.
+///
+/// The active file rotates into `audit-<timestamp>.jsonl` segments; the
+/// retention pass only prunes the oldest segments, and only once the log as a
+/// whole exceeds `MIN_RETAINED_BYTES`.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const ACTIVE_FILE: &str = "audit.jsonl";
+const SEGMENT_PREFIX: &str = "audit-";
+
+const MB: u64 = 1024 * 1024;
+/// Active file size that triggers rotation
+const ROTATE_BYTES: u64 = 5 * MB;
+/// The log is never pruned below this total size
+pub const MIN_RETAINED_BYTES: u64 = 20 * MB;
+
+/// Key fragments whose values never reach the log
+const SENSITIVE_KEYS: &[&str] = &["token", "key", "secret", "password"];
+
+pub const ORIGIN_COMMAND: &str = "tauri-command";
+pub const ORIGIN_SCHEDULED: &str = "scheduled";
+
+/// Serializes appends and rotation within the process
+static AUDIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// One audited operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: String, // ISO 8601
+    pub command: String,
+    /// Argument summary (sensitive values redacted)
+    pub args: Value,
+    /// "tauri-command" | "scheduled"
+    pub origin: String,
+    /// OS user running the app
+    pub user: Option<String>,
+    /// "ok" | "error"
+    pub outcome: String,
+    pub error: Option<String>,
+}
+
+/// Optional filters for `get_audit_log`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AuditFilter {
+    pub command: Option<String>,
+    pub origin: Option<String>,
+    pub outcome: Option<String>,
+}
+
+impl AuditFilter {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.command.as_ref().is_none_or(|c| &entry.command == c)
+            && self.origin.as_ref().is_none_or(|o| &entry.origin == o)
+            && self.outcome.as_ref().is_none_or(|o| &entry.outcome == o)
+    }
+}
+
+/// Folder holding the active log and rotated segments
+pub fn audit_dir() -> PathBuf {
+    crate::get_app_config_dir().join("audit")
+}
+
+/// Replace values under sensitive-looking keys, recursively
+pub fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let lower = key.to_lowercase();
+                    if SENSITIVE_KEYS.iter().any(|s| lower.contains(s)) {
+                        (key, Value::String("[redacted]".to_string()))
+                    } else {
+                        (key, redact(value))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+        other => other,
+    }
+}
+
+fn current_user() -> Option<String> {
+    std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok()
+}
+
+/// Log files in `dir`, oldest first (rotated segments by name, then the active file)
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut segments: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(SEGMENT_PREFIX) && n.ends_with(".jsonl"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    segments.sort();
+
+    let active = dir.join(ACTIVE_FILE);
+    if active.exists() {
+        segments.push(active);
+    }
+    segments
+}
+
+fn append_to(dir: &Path, entry: &AuditEntry) -> Result<(), String> {
+    let _guard = AUDIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create audit directory: {}", e))?;
+
+    let active = dir.join(ACTIVE_FILE);
+    if std::fs::metadata(&active).map(|m| m.len() >= ROTATE_BYTES).unwrap_or(false) {
+        let segment = dir.join(format!(
+            "{}{}.jsonl",
+            SEGMENT_PREFIX,
+            chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+        ));
+        std::fs::rename(&active, &segment)
+            .map_err(|e| format!("Failed to rotate audit log: {}", e))?;
+    }
+
+    let line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&active)
+        .map_err(|e| format!("Failed to open audit log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write audit log: {}", e))
+}
+
+/// Record the outcome of a destructive operation (failures to log are reported, never fatal)
+pub fn record<T, E: std::fmt::Display>(command: &str, args: Value, origin: &str, result: &Result<T, E>) {
+    let entry = AuditEntry {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        command: command.to_string(),
+        args: redact(args),
+        origin: origin.to_string(),
+        user: current_user(),
+        outcome: if result.is_ok() { "ok" } else { "error" }.to_string(),
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+
+    if let Err(e) = append_to(&audit_dir(), &entry) {
+        eprintln!("Audit: {}", e);
+    }
+}
+
+fn read_entries(dir: &Path, limit: usize, filter: &AuditFilter) -> Vec<AuditEntry> {
+    let mut entries = Vec::new();
+
+    // Newest file first, newest line first
+    for path in log_files(dir).iter().rev() {
+        let content = std::fs::read_to_string(path).unwrap_or_default();
+        for line in content.lines().rev() {
+            if let Ok(entry) = serde_json::from_str::<AuditEntry>(line) {
+                if filter.matches(&entry) {
+                    entries.push(entry);
+                    if entries.len() >= limit {
+                        return entries;
+                    }
+                }
+            }
+        }
+    }
+
+    entries
+}
+
+/// String values in the args of every entry not made by `except_command`,
+/// for checking whether a path is still referenced
+pub fn referenced_paths(except_command: &str) -> Vec<String> {
+    referenced_paths_in(&audit_dir(), except_command)
+}
+
+fn referenced_paths_in(dir: &Path, except_command: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    for content in log_files(dir).iter().filter_map(|path| std::fs::read_to_string(path).ok()) {
+        for entry in content.lines().filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok()) {
+            if entry.command != except_command {
+                collect_strings(&entry.args, &mut paths);
+            }
+        }
+    }
+    paths
+}
+
+fn collect_strings(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => out.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+/// Delete the oldest rotated segments while the log exceeds `min_retained` bytes
+fn prune_dir(dir: &Path, min_retained: u64) -> Vec<PathBuf> {
+    let _guard = AUDIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let files = log_files(dir);
+    let size = |p: &PathBuf| std::fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+    let mut total: u64 = files.iter().map(size).sum();
+    let mut removed = Vec::new();
+
+    for segment in files.iter().filter(|p| !p.ends_with(ACTIVE_FILE)) {
+        if total <= min_retained {
+            break;
+        }
+        let bytes = size(segment);
+        if std::fs::remove_file(segment).is_ok() {
+            total -= bytes;
+            removed.push(segment.clone());
+        }
+    }
+
+    removed
+}
+
+/// Retention hook: prune old segments once the log outgrows its minimum size
+pub fn prune() -> Vec<PathBuf> {
+    prune_dir(&audit_dir(), MIN_RETAINED_BYTES)
+}
+
+/// Get audit entries, newest first
+#[tauri::command]
+pub async fn get_audit_log(limit: Option<usize>, filter: Option<AuditFilter>) -> Result<Vec<AuditEntry>, String> {
+    crate::command_metrics::timed("get_audit_log", async move {
+        Ok(read_entries(&audit_dir(), limit.unwrap_or(200), &filter.unwrap_or_default()))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(command: &str, outcome: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            command: command.to_string(),
+            args: Value::Null,
+            origin: ORIGIN_COMMAND.to_string(),
+            user: None,
+            outcome: outcome.to_string(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_referenced_paths_skip_the_excluded_command() {
+        let dir = std::env::temp_dir().join(format!("vibecode-audit-refs-{}", uuid::Uuid::new_v4()));
+        let mut import = entry("import_skill", "ok");
+        import.args = serde_json::json!({ "source": "/a/b.zip", "files": ["/c/d"], "count": 2 });
+        let mut sweep = entry("clean_temp_artifacts", "error");
+        sweep.args = serde_json::json!({ "path": "/tmp/old" });
+        append_to(&dir, &import).unwrap();
+        append_to(&dir, &sweep).unwrap();
+
+        let mut paths = referenced_paths_in(&dir, "clean_temp_artifacts");
+        paths.sort();
+        assert_eq!(paths, vec!["/a/b.zip", "/c/d"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_entries_are_read_newest_first_and_filtered() {
+        let dir = std::env::temp_dir().join(format!("vibecode-audit-{}", uuid::Uuid::new_v4()));
+        append_to(&dir, &entry("delete_skill", "ok")).unwrap();
+        append_to(&dir, &entry("remove_saved_account", "error")).unwrap();
+        append_to(&dir, &entry("delete_skill", "error")).unwrap();
+
+        let all = read_entries(&dir, 10, &AuditFilter::default());
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].command, "delete_skill");
+        assert_eq!(all[0].outcome, "error");
+        assert_eq!(all[2].outcome, "ok");
+
+        let filter = AuditFilter { command: Some("delete_skill".to_string()), ..Default::default() };
+        assert_eq!(read_entries(&dir, 10, &filter).len(), 2);
+        assert_eq!(read_entries(&dir, 1, &AuditFilter::default()).len(), 1);
+
+        // The active file is kept even when everything is over the minimum
+        std::fs::write(dir.join("audit-20240101T000000.000Z.jsonl"), "x".repeat(64)).unwrap();
+        let removed = prune_dir(&dir, 0);
+        assert_eq!(removed.len(), 1);
+        assert!(dir.join(ACTIVE_FILE).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sensitive_args_are_redacted() {
+        let args = serde_json::json!({
+            "email": "a@b.c",
+            "accessToken": "ya29",
+            "nested": [{ "api_key": "sk-1", "name": "x" }],
+        });
+        let redacted = redact(args);
+        assert_eq!(redacted["email"], "a@b.c");
+        assert_eq!(redacted["accessToken"], "[redacted]");
+        assert_eq!(redacted["nested"][0]["api_key"], "[redacted]");
+        assert_eq!(redacted["nested"][0]["name"], "x");
+    }
+}
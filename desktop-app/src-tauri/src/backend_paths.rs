@@ -0,0 +1,176 @@
+/// Backend Paths - Finding the files the app runs outside Rust
+///
+/// vibe.py (with the Python packages beside it) and the node scripts ship as
+/// Tauri resources laid out as in the repository (`bundle.resources` in
+/// tauri.conf.json), so the relative paths between them keep working. Each
+/// file is looked for in the resource directory first, then in a checkout:
+/// the directory of the executable or the current one, or a parent of
+/// either, which covers `tauri dev` started from any folder of the
+/// repository. `get_backend_paths` reports where each file was found and
+/// everywhere it was looked for.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use serde::Serialize;
+use tauri::Manager;
+
+pub const FOUND_IN_RESOURCES: &str = "resources";
+pub const FOUND_IN_CHECKOUT: &str = "checkout";
+
+/// A file the backend runs, by its path in the repository and the resources
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackendFile {
+    pub name: &'static str,
+    pub path: &'static str,
+}
+
+pub const VIBE_PY: BackendFile = BackendFile { name: "vibe.py", path: "vibe.py" };
+pub const GENERATE_WORKFLOW_JS: BackendFile = BackendFile {
+    name: "generate_workflow.js",
+    path: "desktop-app/src-tauri/scripts/generate_workflow.js",
+};
+
+/// Files `get_backend_paths` reports on
+const FILES: [BackendFile; 2] = [VIBE_PY, GENERATE_WORKFLOW_JS];
+
+static RESOURCE_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Where one file was found
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BackendPath {
+    pub name: String,
+    /// None when it was found nowhere
+    pub path: Option<String>,
+    /// "resources" | "checkout"
+    pub found_in: Option<String>,
+    /// Where it was looked for, in order
+    pub searched: Vec<String>,
+}
+
+/// Result of `get_backend_paths`
+#[derive(Debug, Serialize)]
+pub struct BackendPaths {
+    /// The app's resource directory, None when Tauri can't tell
+    pub resource_dir: Option<String>,
+    pub files: Vec<BackendPath>,
+    /// What python commands run with (`vibePath` in settings wins over the vibe.py found)
+    pub python_env: crate::python_env::PythonEnv,
+}
+
+/// Directories a file is looked for in
+#[derive(Debug, Clone)]
+pub struct SearchRoots {
+    pub resource_dir: Option<PathBuf>,
+    /// Searched with their parents
+    pub checkout_from: Vec<PathBuf>,
+}
+
+impl SearchRoots {
+    /// The resource directory, and the directories of the executable and the process
+    pub fn current() -> Self {
+        let exe_dir = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf));
+        Self {
+            resource_dir: resource_dir(),
+            checkout_from: exe_dir.into_iter().chain(std::env::current_dir().ok()).collect(),
+        }
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// Find `file` under `roots`
+pub fn locate_in(file: BackendFile, roots: &SearchRoots) -> BackendPath {
+    let found = |path: &Path, found_in: &str, searched: Vec<String>| BackendPath {
+        name: file.name.to_string(),
+        path: Some(path_string(path)),
+        found_in: Some(found_in.to_string()),
+        searched,
+    };
+
+    let mut searched = Vec::new();
+    if let Some(dir) = &roots.resource_dir {
+        let path = dir.join(file.path);
+        searched.push(path_string(&path));
+        if path.is_file() {
+            return found(&path, FOUND_IN_RESOURCES, searched);
+        }
+    }
+    for start in &roots.checkout_from {
+        searched.push(format!("{}, or the same under a parent directory", start.join(file.path).display()));
+        if let Some(path) = start.ancestors().map(|dir| dir.join(file.path)).find(|path| path.is_file()) {
+            return found(&path, FOUND_IN_CHECKOUT, searched);
+        }
+    }
+    BackendPath { name: file.name.to_string(), path: None, found_in: None, searched }
+}
+
+/// Find `file` where the running app would
+pub fn locate(file: BackendFile) -> BackendPath {
+    locate_in(file, &SearchRoots::current())
+}
+
+/// The path of `file`, or an error listing where it was looked for
+pub fn require(file: BackendFile) -> Result<PathBuf, String> {
+    let located = locate(file);
+    located
+        .path
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("{} not found. Looked in:\n  {}", file.name, located.searched.join("\n  ")))
+}
+
+/// The app's resource directory (None before startup, and in tests)
+pub fn resource_dir() -> Option<PathBuf> {
+    RESOURCE_DIR.get().cloned().flatten()
+}
+
+/// Remember the resource directory (called once at startup, before `python_env`)
+pub fn init(app: &tauri::AppHandle) {
+    let _ = RESOURCE_DIR.set(app.path().resource_dir().ok());
+}
+
+/// Where vibe.py and the node scripts were found, for diagnosing installs
+#[tauri::command]
+pub async fn get_backend_paths() -> Result<BackendPaths, String> {
+    crate::command_metrics::timed("get_backend_paths", async move {
+        let roots = SearchRoots::current();
+        Ok(BackendPaths {
+            resource_dir: roots.resource_dir.as_deref().map(path_string),
+            files: FILES.iter().map(|file| locate_in(*file, &roots)).collect(),
+            python_env: crate::python_env::current(),
+        })
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resources_come_before_the_checkout() {
+        let root = std::env::temp_dir().join(format!("vibecode-backend-paths-{}", uuid::Uuid::new_v4()));
+        let (resources, checkout) = (root.join("resources"), root.join("checkout"));
+        let launched_in = checkout.join("desktop-app").join("src-tauri");
+        std::fs::create_dir_all(launched_in.join("scripts")).unwrap();
+        std::fs::create_dir_all(resources.join("desktop-app/src-tauri/scripts")).unwrap();
+        std::fs::write(launched_in.join("scripts/generate_workflow.js"), "").unwrap();
+        let roots = SearchRoots { resource_dir: Some(resources.clone()), checkout_from: vec![launched_in.clone()] };
+
+        let located = locate_in(GENERATE_WORKFLOW_JS, &roots);
+        assert_eq!(located.found_in.as_deref(), Some(FOUND_IN_CHECKOUT));
+        assert_eq!(located.path.map(PathBuf::from), Some(checkout.join(GENERATE_WORKFLOW_JS.path)));
+        assert_eq!(located.searched.len(), 2);
+
+        std::fs::write(resources.join(GENERATE_WORKFLOW_JS.path), "").unwrap();
+        let located = locate_in(GENERATE_WORKFLOW_JS, &roots);
+        assert_eq!(located.found_in.as_deref(), Some(FOUND_IN_RESOURCES));
+        assert_eq!(located.path.map(PathBuf::from), Some(resources.join(GENERATE_WORKFLOW_JS.path)));
+
+        let missing = locate_in(VIBE_PY, &SearchRoots { resource_dir: Some(resources), checkout_from: vec![launched_in] });
+        assert_eq!((missing.path, missing.found_in), (None, None));
+        assert!(missing.searched[0].ends_with("vibe.py"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,257 @@
+/// Bookmarks - Files and folders pinned to the top of the explorer
+///
+/// Bookmarks live in bookmarks.json in the config dir, a list per project in
+/// the order the user gave them, each with its path relative to the project.
+/// Every command returns the current project's list as `BookmarkEntry`s,
+/// with absolute paths and whether the target still exists. When the
+/// project folder was renamed or moved, its list would be stranded under the
+/// old path: a project without a list takes over the list of a project
+/// folder that no longer exists, if any of its bookmarks resolve in the new
+/// folder (the list with the most that do, when several qualify).
+
+use std::path::{Component, Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::instance_lock;
+
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// A bookmark as stored
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Bookmark {
+    id: String,
+    label: String,
+    /// Relative to the project, with `/` separators ("" is the project itself)
+    path: String,
+}
+
+/// Entry of the bookmark commands
+#[derive(Debug, Serialize)]
+pub struct BookmarkEntry {
+    pub id: String,
+    pub label: String,
+    pub relative_path: String,
+    pub path: String,
+    pub exists: bool,
+    pub is_dir: bool,
+}
+
+fn store_path() -> PathBuf {
+    crate::get_app_config_dir().join(BOOKMARKS_FILE)
+}
+
+fn current_project() -> Result<String, String> {
+    crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()).ok_or_else(|| "No project is open".to_string())
+}
+
+fn list_in(store: &Value, project: &str) -> Vec<Bookmark> {
+    store
+        .get(project)
+        .and_then(|list| serde_json::from_value(list.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn set_list(store: &mut Value, project: &str, list: &[Bookmark]) {
+    if !store.is_object() {
+        *store = serde_json::json!({});
+    }
+    if let Some(store) = store.as_object_mut() {
+        if list.is_empty() {
+            store.remove(project);
+        } else {
+            store.insert(project.to_string(), serde_json::json!(list));
+        }
+    }
+}
+
+fn resolve(project: &Path, relative: &str) -> PathBuf {
+    relative.split('/').filter(|part| !part.is_empty()).fold(project.to_path_buf(), |path, part| path.join(part))
+}
+
+/// `path` (absolute, or relative to `project`) relative to `project`
+fn relative_path(project: &Path, path: &str) -> Result<String, String> {
+    let relative = Path::new(path).strip_prefix(project).unwrap_or(Path::new(path));
+    if relative.is_absolute() || relative.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) {
+        return Err(format!("{} is not inside the project", path));
+    }
+    let parts: Vec<String> = relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+    Ok(parts.join("/"))
+}
+
+/// Give `project` the list of a renamed or moved project folder, if it has
+/// none (see the module docs); whether it took one over
+fn adopt(store: &mut Value, project: &str) -> bool {
+    let Some(lists) = store.as_object() else { return false };
+    if lists.contains_key(project) {
+        return false;
+    }
+    let root = Path::new(project);
+    let best = lists
+        .keys()
+        .filter(|old| !Path::new(old.as_str()).exists())
+        .map(|old| {
+            let resolving = list_in(store, old).iter().filter(|b| resolve(root, &b.path).exists()).count();
+            (old.clone(), resolving)
+        })
+        .filter(|(_, resolving)| *resolving > 0)
+        .max_by_key(|(_, resolving)| *resolving);
+    let Some((old, _)) = best else { return false };
+    let list = list_in(store, &old);
+    set_list(store, &old, &[]);
+    set_list(store, project, &list);
+    true
+}
+
+/// `list` with a bookmark for `path`, or the bookmark already there relabeled
+fn add(mut list: Vec<Bookmark>, path: String, label: String) -> Vec<Bookmark> {
+    match list.iter_mut().find(|b| b.path == path) {
+        Some(existing) => existing.label = label,
+        None => list.push(Bookmark { id: uuid::Uuid::new_v4().to_string(), label, path }),
+    }
+    list
+}
+
+/// `list` in the order of `ids`; bookmarks missing from `ids` keep their
+/// order after the listed ones
+fn reorder(mut list: Vec<Bookmark>, ids: &[String]) -> Result<Vec<Bookmark>, String> {
+    if let Some(unknown) = ids.iter().find(|id| !list.iter().any(|b| &b.id == *id)) {
+        return Err(format!("Bookmark not found: {}", unknown));
+    }
+    // Stable: the unlisted ones stay in order
+    list.sort_by_key(|b| ids.iter().position(|id| *id == b.id).unwrap_or(ids.len()));
+    Ok(list)
+}
+
+fn entries(project: &str, list: Vec<Bookmark>) -> Vec<BookmarkEntry> {
+    list.into_iter()
+        .map(|bookmark| {
+            let path = resolve(Path::new(project), &bookmark.path);
+            BookmarkEntry {
+                id: bookmark.id,
+                label: bookmark.label,
+                relative_path: bookmark.path,
+                exists: path.exists(),
+                is_dir: path.is_dir(),
+                path: path.to_string_lossy().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Change the current project's list under the lock
+fn update(change: impl FnOnce(Vec<Bookmark>) -> Result<Vec<Bookmark>, String>) -> Result<Vec<BookmarkEntry>, String> {
+    let project = current_project()?;
+    let list = instance_lock::update_json_file(&store_path(), |store| {
+        adopt(store, &project);
+        let list = change(list_in(store, &project))?;
+        set_list(store, &project, &list);
+        Ok(list)
+    })?;
+    Ok(entries(&project, list))
+}
+
+/// Bookmarks of the current project, in the user's order
+#[tauri::command]
+pub async fn list_bookmarks() -> Result<Vec<BookmarkEntry>, String> {
+    crate::command_metrics::timed("list_bookmarks", async move {
+        let Ok(project) = current_project() else { return Ok(Vec::new()) };
+        let store: Value = std::fs::read_to_string(store_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        // Take over a renamed folder's list under the lock, if there is one
+        if store.get(&project).is_some() || !adopt(&mut store.clone(), &project) {
+            return Ok(entries(&project, list_in(&store, &project)));
+        }
+        update(Ok)
+    }).await
+}
+
+/// Bookmark `path` (in the current project) as `label`, by default its name
+#[tauri::command]
+pub async fn add_bookmark(path: String, label: Option<String>) -> Result<Vec<BookmarkEntry>, String> {
+    crate::command_metrics::timed("add_bookmark", async move {
+        let project = current_project()?;
+        let relative = relative_path(Path::new(&project), &path)?;
+        if !resolve(Path::new(&project), &relative).exists() {
+            return Err(format!("Path does not exist: {}", path));
+        }
+        let label = label
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty())
+            .or_else(|| Path::new(&path).file_name().map(|name| name.to_string_lossy().to_string()))
+            .unwrap_or_else(|| path.clone());
+        update(|list| Ok(add(list, relative, label)))
+    }).await
+}
+
+/// Remove the bookmark `id`
+#[tauri::command]
+pub async fn remove_bookmark(id: String) -> Result<Vec<BookmarkEntry>, String> {
+    crate::command_metrics::timed("remove_bookmark", async move {
+        update(|mut list| {
+            list.retain(|b| b.id != id);
+            Ok(list)
+        })
+    }).await
+}
+
+/// Put the bookmarks in the order of `ids`
+#[tauri::command]
+pub async fn reorder_bookmarks(ids: Vec<String>) -> Result<Vec<BookmarkEntry>, String> {
+    crate::command_metrics::timed("reorder_bookmarks", async move {
+        update(|list| reorder(list, &ids))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bookmarks_are_ordered_and_follow_renamed_projects() {
+        let root = std::env::temp_dir().join(format!("vibecode-bookmarks-{}", uuid::Uuid::new_v4()));
+        let project = root.join("monorepo");
+        std::fs::create_dir_all(project.join("services").join("api").join("src")).unwrap();
+        std::fs::write(project.join("config.toml"), "").unwrap();
+        let project_str = project.to_string_lossy().to_string();
+
+        let api = project.join("services").join("api").join("src").to_string_lossy().to_string();
+        assert_eq!(relative_path(&project, &api).unwrap(), "services/api/src");
+        assert_eq!(relative_path(&project, "config.toml").unwrap(), "config.toml");
+        assert!(relative_path(&project, "../elsewhere").is_err());
+        assert!(relative_path(&project, &root.join("other").to_string_lossy()).is_err());
+
+        let list = add(Vec::new(), "services/api/src".to_string(), "api".to_string());
+        let list = add(list, "config.toml".to_string(), "config".to_string());
+        let list = add(list, "config.toml".to_string(), "settings".to_string());
+        assert_eq!(list.iter().map(|b| b.label.as_str()).collect::<Vec<_>>(), vec!["api", "settings"]);
+        let ids: Vec<String> = list.iter().map(|b| b.id.clone()).collect();
+        let reordered = reorder(list.clone(), &[ids[1].clone()]).unwrap();
+        assert_eq!(reordered.iter().map(|b| &b.id).collect::<Vec<_>>(), vec![&ids[1], &ids[0]]);
+        assert!(reorder(list.clone(), &["nope".to_string()]).is_err());
+
+        // Stored under the old path, then the folder is renamed
+        let mut store = Value::Null;
+        set_list(&mut store, &project_str, &list);
+        let renamed = root.join("monorepo-renamed");
+        std::fs::rename(&project, &renamed).unwrap();
+        let renamed_str = renamed.to_string_lossy().to_string();
+        assert!(adopt(&mut store, &renamed_str));
+        assert!(store.get(&project_str).is_none());
+        let adopted = entries(&renamed_str, list_in(&store, &renamed_str));
+        assert_eq!(adopted.len(), 2);
+        assert!(adopted.iter().all(|e| e.exists) && adopted[0].is_dir && !adopted[1].is_dir);
+        // Already has a list: nothing to take over
+        assert!(!adopt(&mut store, &renamed_str));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
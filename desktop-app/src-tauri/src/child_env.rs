@@ -0,0 +1,599 @@
+/// Child Env - Environment construction for every spawned child process
+///
+/// Children never inherit the app's full environment. `build_child_env` starts
+/// from a minimal base (PATH, HOME, temp dirs, locale, display), adds host
+/// variables named in `childEnv.inherit`, the project `.env`, the variables
+/// stored for the project with `set_project_env` and `childEnv.vars` from
+/// settings, then applies the allowlist/denylist declared by the skill
+/// (guardrails) or workflow (YAML `env:` block) being run.
+///
+/// Skills declare their policy in guardrails.md (or guardrails/*.md):
+///
+/// ```text
+/// env_allow: OPENAI_API_KEY, DATA_DIR
+/// env_deny: AWS_*
+/// ```
+///
+/// Workflows declare it in their YAML:
+///
+/// ```yaml
+/// env:
+///   allow: [ANTHROPIC_API_KEY]
+///   deny:
+///     - AWS_*
+/// ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Command;
+use serde::{Deserialize, Serialize};
+
+/// Host variables every child gets, so basic tooling keeps working
+const BASE_VARS: &[&str] = &[
+    "PATH", "HOME", "USER", "USERNAME", "USERPROFILE", "LANG", "LC_ALL", "LC_CTYPE", "TERM",
+    "TMP", "TEMP", "TMPDIR", "SHELL", "SYSTEMROOT", "SYSTEMDRIVE", "WINDIR", "COMSPEC", "PATHEXT",
+    "APPDATA", "LOCALAPPDATA", "PROGRAMDATA", "PROGRAMFILES", "DISPLAY", "WAYLAND_DISPLAY",
+    "XDG_RUNTIME_DIR", "DBUS_SESSION_BUS_ADDRESS",
+];
+
+pub const SOURCE_BASE: &str = "base";
+pub const SOURCE_INHERIT: &str = "inherit";
+pub const SOURCE_PROJECT_ENV: &str = "project_env";
+pub const SOURCE_SETTINGS: &str = "settings";
+pub const SOURCE_RUN: &str = "run";
+pub const SOURCE_PROJECT_STORE: &str = "project_store";
+pub const SOURCE_REQUEST: &str = "request";
+
+/// Values shorter than this are too common to mask in output ("1", "true", "debug")
+const MIN_MASKED_LEN: usize = 8;
+
+/// `childEnv` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChildEnvSettings {
+    /// Extra variables injected into children
+    pub vars: BTreeMap<String, String>,
+    /// Host variables passed through on top of the base set
+    pub inherit: Vec<String>,
+}
+
+impl Default for ChildEnvSettings {
+    fn default() -> Self {
+        Self {
+            vars: BTreeMap::new(),
+            // Provider keys vibe.py's agents read when they aren't in its .env
+            inherit: [
+                "ANTHROPIC_API_KEY",
+                "GEMINI_API_KEY",
+                "GOOGLE_API_KEY",
+                "OPENAI_API_KEY",
+                "MOONSHOT_API_KEY",
+                "CLAUDE_CODE_PATH",
+                "DEFAULT_MODEL",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        }
+    }
+}
+
+impl ChildEnvSettings {
+    /// Load the childEnv block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("childEnv").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// What a child is being spawned for
+pub enum ChildContext<'a> {
+    /// Host tools (process discovery, file openers, generators): base only
+    System,
+    /// vibe.py commands
+    Task,
+    /// A workflow, by its YAML file
+    Workflow(&'a Path),
+    /// A script inside this skill folder
+    SkillScript(&'a Path),
+}
+
+impl ChildContext<'_> {
+    fn label(&self) -> String {
+        match self {
+            ChildContext::System => "system".to_string(),
+            ChildContext::Task => "task".to_string(),
+            ChildContext::Workflow(path) => {
+                format!("workflow:{}", path.file_stem().unwrap_or_default().to_string_lossy())
+            }
+            ChildContext::SkillScript(folder) => format!(
+                "skill:{}",
+                folder.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Allowlist/denylist for injected variables (`*` suffix matches a prefix)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvPolicy {
+    /// When set, only these non-base variables are passed
+    pub allow: Option<Vec<String>>,
+    /// Never passed, base variables included
+    pub deny: Vec<String>,
+}
+
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+impl EnvPolicy {
+    fn permits(&self, name: &str, source: &str) -> bool {
+        if self.deny.iter().any(|p| matches_pattern(p, name)) {
+            return false;
+        }
+        if source == SOURCE_BASE {
+            return true;
+        }
+        match &self.allow {
+            Some(allow) => allow.iter().any(|p| matches_pattern(p, name)),
+            None => true,
+        }
+    }
+
+    fn add_allow(&mut self, names: Vec<String>) {
+        self.allow.get_or_insert_with(Vec::new).extend(names);
+    }
+}
+
+/// Variable provided to a child (values are never recorded)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProvidedVar {
+    pub name: String,
+    pub source: String,
+}
+
+/// Resolved environment for one child
+#[derive(Debug, Clone, Default)]
+pub struct ChildEnv {
+    vars: BTreeMap<String, (String, &'static str)>,
+}
+
+impl ChildEnv {
+    /// Replace the command's environment with this one
+    pub fn apply<'c>(&self, cmd: &'c mut Command) -> &'c mut Command {
+        cmd.env_clear()
+            .envs(self.vars.iter().map(|(name, (value, _))| (name, value)))
+    }
+
+    /// Redacted record of what was provided, for run records
+    pub fn provided(&self) -> Vec<ProvidedVar> {
+        self.vars
+            .iter()
+            .map(|(name, (_, source))| ProvidedVar {
+                name: name.clone(),
+                source: source.to_string(),
+            })
+            .collect()
+    }
+
+    /// Add a per-run variable (e.g. the run's temp directory)
+    pub fn with_run_var(mut self, name: &str, value: String) -> Self {
+        self.vars.insert(name.to_string(), (value, SOURCE_RUN));
+        self
+    }
+
+    /// Add the variables a caller passed with the command (they override every other source)
+    pub fn with_request_vars(mut self, vars: Option<&HashMap<String, String>>) -> Self {
+        for (name, value) in vars.into_iter().flatten() {
+            self.vars.insert(name.clone(), (value.clone(), SOURCE_REQUEST));
+        }
+        self
+    }
+
+    /// Replace injected values in a child's output, in case it prints its environment
+    pub fn mask_values(&self, output: &str) -> String {
+        let mut secrets: Vec<&str> = self
+            .vars
+            .values()
+            .filter(|(value, source)| *source != SOURCE_BASE && *source != SOURCE_RUN && value.len() >= MIN_MASKED_LEN)
+            .map(|(value, _)| value.as_str())
+            .collect();
+        // Longest first, so a value containing another is masked whole
+        secrets.sort_by_key(|value| std::cmp::Reverse(value.len()));
+
+        secrets
+            .into_iter()
+            .fold(output.to_string(), |masked, secret| masked.replace(secret, REDACTED))
+    }
+
+    #[cfg(test)]
+    fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(|(value, _)| value.as_str())
+    }
+}
+
+/// Parse a `.env` file (KEY=VALUE lines, `#` comments, optional `export` and quotes)
+pub fn parse_dotenv(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Split `A, B` or `[A, B]` into names
+fn parse_name_list(value: &str) -> Vec<String> {
+    value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|s| s.trim().trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Read `env_allow:` / `env_deny:` lines from a skill's guardrails
+pub fn skill_policy(skill_folder: &Path) -> EnvPolicy {
+    let mut files = vec![skill_folder.join("guardrails.md")];
+    if let Ok(entries) = std::fs::read_dir(skill_folder.join("guardrails")) {
+        let mut extra: Vec<_> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+            .collect();
+        extra.sort();
+        files.extend(extra);
+    }
+
+    let mut policy = EnvPolicy::default();
+    for file in files {
+        let Ok(content) = std::fs::read_to_string(&file) else { continue };
+        for line in content.lines() {
+            let line = line.trim().trim_start_matches(['-', '*']).trim();
+            if let Some(rest) = line.strip_prefix("env_allow:") {
+                policy.add_allow(parse_name_list(rest));
+            } else if let Some(rest) = line.strip_prefix("env_deny:") {
+                policy.deny.extend(parse_name_list(rest));
+            }
+        }
+    }
+    policy
+}
+
+/// Read the top-level `env:` block (allow/deny lists) of a workflow YAML
+pub fn workflow_policy(yaml: &str) -> EnvPolicy {
+    let mut policy = EnvPolicy::default();
+    let mut in_env = false;
+    let mut current: Option<&str> = None;
+
+    for line in yaml.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        if !indented {
+            in_env = trimmed == "env:";
+            current = None;
+            continue;
+        }
+        if !in_env {
+            continue;
+        }
+
+        if let Some(item) = trimmed.strip_prefix("- ") {
+            let names = parse_name_list(item);
+            match current {
+                Some("allow") => policy.add_allow(names),
+                Some("deny") => policy.deny.extend(names),
+                _ => {}
+            }
+        } else if let Some((key, value)) = trimmed.split_once(':') {
+            let key = key.trim();
+            current = Some(if key == "allow" { "allow" } else if key == "deny" { "deny" } else { "" });
+            let names = parse_name_list(value);
+            match key {
+                // `allow: []` still means "nothing extra"
+                "allow" => policy.add_allow(names),
+                "deny" => policy.deny.extend(names),
+                _ => {}
+            }
+        }
+    }
+    policy
+}
+
+/// Build the environment from explicit inputs
+fn build(
+    policy: &EnvPolicy,
+    project_dir: Option<&Path>,
+    project_vars: &BTreeMap<String, String>,
+    settings: &ChildEnvSettings,
+    host: &dyn Fn(&str) -> Option<String>,
+) -> ChildEnv {
+    let mut vars: BTreeMap<String, (String, &'static str)> = BTreeMap::new();
+
+    for name in BASE_VARS {
+        if let Some(value) = host(name) {
+            vars.insert(name.to_string(), (value, SOURCE_BASE));
+        }
+    }
+    for name in &settings.inherit {
+        if let Some(value) = host(name) {
+            vars.insert(name.clone(), (value, SOURCE_INHERIT));
+        }
+    }
+    if let Some(dir) = project_dir {
+        if let Ok(content) = std::fs::read_to_string(dir.join(".env")) {
+            for (name, value) in parse_dotenv(&content) {
+                vars.insert(name, (value, SOURCE_PROJECT_ENV));
+            }
+        }
+    }
+    for (name, value) in project_vars {
+        vars.insert(name.clone(), (value.clone(), SOURCE_PROJECT_STORE));
+    }
+    for (name, value) in &settings.vars {
+        vars.insert(name.clone(), (value.clone(), SOURCE_SETTINGS));
+    }
+
+    vars.retain(|name, (_, source)| policy.permits(name, source));
+    ChildEnv { vars }
+}
+
+/// Build the environment for a child spawned in `context`
+pub fn build_child_env(context: &ChildContext) -> ChildEnv {
+    let host = |name: &str| std::env::var(name).ok();
+
+    let env = match context {
+        ChildContext::System => {
+            let base_only = ChildEnvSettings { vars: BTreeMap::new(), inherit: Vec::new() };
+            build(&EnvPolicy::default(), None, &BTreeMap::new(), &base_only, &host)
+        }
+        _ => {
+            let policy = match context {
+                ChildContext::Workflow(path) => {
+                    std::fs::read_to_string(path).map(|yaml| workflow_policy(&yaml)).unwrap_or_default()
+                }
+                ChildContext::SkillScript(folder) => skill_policy(folder),
+                _ => EnvPolicy::default(),
+            };
+            let project = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone());
+            let project_vars = project.as_deref().map(crate::project_env::load).unwrap_or_default();
+            build(&policy, project.as_deref().map(Path::new), &project_vars, &ChildEnvSettings::load(), &host)
+        }
+    };
+
+    if !matches!(context, ChildContext::System) {
+        let names: Vec<String> = env
+            .provided()
+            .into_iter()
+            .filter(|v| v.source != SOURCE_BASE)
+            .map(|v| format!("{} ({})", v.name, v.source))
+            .collect();
+        eprintln!("Child env [{}]: {}", context.label(), if names.is_empty() { "base only".to_string() } else { names.join(", ") });
+    }
+
+    env
+}
+
+/// Command for a host tool, with the base environment only
+pub fn system_command<S: AsRef<std::ffi::OsStr>>(program: S) -> Command {
+    let mut cmd = Command::new(program);
+    build_child_env(&ChildContext::System).apply(&mut cmd);
+    cmd
+}
+
+/// Placeholder for redacted argument values
+const REDACTED: &str = "[redacted]";
+
+/// What was spawned and where, for run records (env values are never recorded)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Invocation {
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: Option<String>,
+    pub env_keys: Vec<String>,
+}
+
+impl Invocation {
+    /// Capture a configured command right before it is spawned
+    pub fn capture(cmd: &Command) -> Self {
+        let args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        let mut env_keys: Vec<String> = cmd
+            .get_envs()
+            .filter(|(_, value)| value.is_some())
+            .map(|(name, _)| name.to_string_lossy().to_string())
+            .collect();
+        env_keys.sort();
+
+        Self {
+            program: cmd.get_program().to_string_lossy().to_string(),
+            args: redact_args(&args),
+            cwd: cmd.get_current_dir().map(|d| d.to_string_lossy().to_string()),
+            env_keys,
+        }
+    }
+
+}
+
+fn is_sensitive_flag(arg: &str) -> bool {
+    let lower = arg.to_lowercase();
+    lower.starts_with('-') && (lower.contains("token") || lower.contains("key"))
+}
+
+/// Hide values following flags like `--api-key` / `--token` (and `--token=...`)
+pub fn redact_args(args: &[String]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut hide_next = false;
+
+    for arg in args {
+        if hide_next && !arg.starts_with('-') {
+            redacted.push(REDACTED.to_string());
+            hide_next = false;
+            continue;
+        }
+
+        match arg.split_once('=') {
+            Some((flag, _)) if is_sensitive_flag(flag) => {
+                redacted.push(format!("{}={}", flag, REDACTED));
+                hide_next = false;
+            }
+            _ => {
+                hide_next = is_sensitive_flag(arg);
+                redacted.push(arg.clone());
+            }
+        }
+    }
+
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root() -> std::path::PathBuf {
+        let root = std::env::temp_dir().join(format!("vibecode-child-env-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn no_inherit() -> ChildEnvSettings {
+        ChildEnvSettings { vars: BTreeMap::new(), inherit: Vec::new() }
+    }
+
+    #[test]
+    fn test_skill_allowlist_blocks_project_env_vars() {
+        let project = temp_root();
+        std::fs::write(project.join(".env"), "SECRET_TOKEN=leak\nexport DATA_DIR=\"/data\"\n").unwrap();
+        let skill = project.join(".agent").join("skills").join("reporter");
+        std::fs::create_dir_all(&skill).unwrap();
+        std::fs::write(skill.join("guardrails.md"), "# Guardrails\n\n- env_allow: DATA_DIR\n").unwrap();
+
+        let host = |name: &str| match name {
+            "PATH" => Some("/usr/bin:/bin".to_string()),
+            "SECRET_TOKEN" => Some("from-host".to_string()),
+            _ => None,
+        };
+        let env = build(&skill_policy(&skill), Some(&project), &BTreeMap::new(), &no_inherit(), &host);
+
+        assert_eq!(env.get("DATA_DIR"), Some("/data"));
+        assert_eq!(env.get("PATH"), Some("/usr/bin:/bin"));
+        assert_eq!(env.get("SECRET_TOKEN"), None);
+        assert!(env.provided().iter().all(|v| v.name != "SECRET_TOKEN"));
+
+        // And it really never reaches the spawned script
+        #[cfg(unix)]
+        {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg("echo \"secret=${SECRET_TOKEN:-unset} data=${DATA_DIR:-unset}\"");
+            // Stands in for an inherited variable without touching this process's environment
+            cmd.env("SECRET_TOKEN", "from-host");
+            let output = env.apply(&mut cmd).output().unwrap();
+            assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "secret=unset data=/data");
+        }
+
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[test]
+    fn test_denylist_and_settings_vars() {
+        let settings = ChildEnvSettings {
+            vars: BTreeMap::from([("AWS_SECRET".to_string(), "x".to_string()), ("LOG_LEVEL".to_string(), "debug".to_string())]),
+            inherit: vec!["HOME".to_string()],
+        };
+        let policy = EnvPolicy { allow: None, deny: vec!["AWS_*".to_string(), "HOME".to_string()] };
+        let host = |name: &str| (name == "HOME").then(|| "/home/me".to_string());
+
+        let env = build(&policy, None, &BTreeMap::new(), &settings, &host);
+
+        assert_eq!(env.get("LOG_LEVEL"), Some("debug"));
+        assert_eq!(env.get("AWS_SECRET"), None);
+        assert_eq!(env.get("HOME"), None);
+        assert_eq!(env.provided(), vec![ProvidedVar { name: "LOG_LEVEL".to_string(), source: SOURCE_SETTINGS.to_string() }]);
+    }
+
+    #[test]
+    fn test_project_and_request_vars_are_masked_in_output() {
+        let stored = BTreeMap::from([("DEPLOY_TOKEN".to_string(), "tok-1234567890".to_string())]);
+        let host = |name: &str| (name == "PATH").then(|| "/usr/bin:/bin".to_string());
+
+        let env = build(&EnvPolicy::default(), None, &stored, &no_inherit(), &host)
+            .with_request_vars(Some(&HashMap::from([
+                ("REGION".to_string(), "eu".to_string()),
+                ("DB_PASSWORD".to_string(), "hunter2-long".to_string()),
+            ])));
+
+        assert_eq!(env.get("DEPLOY_TOKEN"), Some("tok-1234567890"));
+        assert_eq!(env.get("REGION"), Some("eu"));
+        assert!(env.provided().contains(&ProvidedVar { name: "DB_PASSWORD".to_string(), source: SOURCE_REQUEST.to_string() }));
+        assert!(env.provided().contains(&ProvidedVar { name: "DEPLOY_TOKEN".to_string(), source: SOURCE_PROJECT_STORE.to_string() }));
+
+        let printed = "DEPLOY_TOKEN=tok-1234567890\nDB_PASSWORD=hunter2-long\nREGION=eu\nPATH=/usr/bin:/bin\n";
+        assert_eq!(
+            env.mask_values(printed),
+            "DEPLOY_TOKEN=[redacted]\nDB_PASSWORD=[redacted]\nREGION=eu\nPATH=/usr/bin:/bin\n"
+        );
+    }
+
+    #[test]
+    fn test_workflow_env_block() {
+        let yaml = "name: Demo\nenv:\n  allow: [ANTHROPIC_API_KEY, \"MY_*\"]\n  deny:\n    - MY_SECRET\nsteps:\n  - id: a\n    allow: ignored\n";
+        let policy = workflow_policy(yaml);
+
+        assert_eq!(policy.allow, Some(vec!["ANTHROPIC_API_KEY".to_string(), "MY_*".to_string()]));
+        assert_eq!(policy.deny, vec!["MY_SECRET".to_string()]);
+        assert!(policy.permits("MY_VAR", SOURCE_SETTINGS));
+        assert!(!policy.permits("MY_SECRET", SOURCE_SETTINGS));
+        assert!(!policy.permits("OTHER", SOURCE_PROJECT_ENV));
+        assert!(workflow_policy("name: No env\n").allow.is_none());
+    }
+
+    #[test]
+    fn test_invocation_redacts_secrets_and_env_values() {
+        let mut cmd = Command::new("python");
+        cmd.args(["vibe.py", "task", "deploy", "--api-key", "sk-123", "--auth-token=abc", "--verbose"])
+            .current_dir("/work")
+            .env_clear()
+            .env("PATH", "/usr/bin")
+            .env("OPENAI_API_KEY", "secret");
+
+        let invocation = Invocation::capture(&cmd);
+
+        assert_eq!(invocation.program, "python");
+        assert_eq!(
+            invocation.args,
+            vec!["vibe.py", "task", "deploy", "--api-key", "[redacted]", "--auth-token=[redacted]", "--verbose"]
+        );
+        assert_eq!(invocation.cwd.as_deref(), Some("/work"));
+        assert_eq!(invocation.env_keys, vec!["OPENAI_API_KEY", "PATH"]);
+    }
+}
@@ -0,0 +1,274 @@
+/// Command Metrics - Timing of Tauri commands and REST routes since launch
+///
+/// Every command body runs inside `timed` (or `timed_sync`), and the REST
+/// server records each request through a `warp::log::custom` hook under its
+/// route template. Both also run inside a `tracing` span named after the
+/// command or route, for whichever subscriber is installed. Per name we
+/// keep call/error/slow counters, the maximum, and a rolling window of recent
+/// durations from which p50/p95 are computed. Calls slower than
+/// `commandMetrics.slowThresholdMs` are logged as they happen.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tracing::Instrument;
+
+pub const SOURCE_COMMAND: &str = "command";
+pub const SOURCE_REST: &str = "rest";
+
+/// `commandMetrics` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommandMetricsSettings {
+    /// Calls taking longer than this are flagged and logged
+    pub slow_threshold_ms: u64,
+    /// Recent durations kept per command for percentiles
+    pub window: usize,
+}
+
+impl Default for CommandMetricsSettings {
+    fn default() -> Self {
+        Self {
+            slow_threshold_ms: 500,
+            window: 512,
+        }
+    }
+}
+
+impl CommandMetricsSettings {
+    /// Load the commandMetrics block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("commandMetrics").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Counters for one command or route
+#[derive(Debug, Default)]
+struct Stats {
+    source: &'static str,
+    calls: u64,
+    errors: u64,
+    slow: u64,
+    max: Duration,
+    recent: VecDeque<Duration>,
+}
+
+/// Aggregated timings for one command or route
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandMetric {
+    pub name: String,
+    /// "command" | "rest"
+    pub source: String,
+    pub calls: u64,
+    pub errors: u64,
+    /// Calls over the slow threshold
+    pub slow: u64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Result of `get_command_metrics`
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandMetrics {
+    pub since: String, // ISO 8601
+    pub slow_threshold_ms: u64,
+    /// Slowest p95 first
+    pub commands: Vec<CommandMetric>,
+}
+
+struct Registry {
+    settings: CommandMetricsSettings,
+    since: chrono::DateTime<chrono::Utc>,
+    stats: Mutex<BTreeMap<String, Stats>>,
+}
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(|| Registry {
+        settings: CommandMetricsSettings::load(),
+        since: chrono::Utc::now(),
+        stats: Mutex::new(BTreeMap::new()),
+    })
+}
+
+fn as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Nearest-rank percentile of an ascending slice
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+impl Registry {
+    fn record(&self, source: &'static str, name: &str, elapsed: Duration, ok: bool) {
+        let slow = elapsed >= Duration::from_millis(self.settings.slow_threshold_ms);
+        {
+            let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+            let entry = stats.entry(name.to_string()).or_default();
+            entry.source = source;
+            entry.calls += 1;
+            entry.errors += u64::from(!ok);
+            entry.slow += u64::from(slow);
+            entry.max = entry.max.max(elapsed);
+            entry.recent.push_back(elapsed);
+            while entry.recent.len() > self.settings.window.max(1) {
+                entry.recent.pop_front();
+            }
+        }
+
+        if slow {
+            eprintln!("Slow {} {}: {:.0} ms{}", source, name, as_ms(elapsed), if ok { "" } else { " (failed)" });
+        }
+    }
+
+    fn snapshot(&self) -> CommandMetrics {
+        let stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        let mut commands: Vec<CommandMetric> = stats
+            .iter()
+            .map(|(name, s)| {
+                let mut sorted: Vec<Duration> = s.recent.iter().copied().collect();
+                sorted.sort();
+                CommandMetric {
+                    name: name.clone(),
+                    source: s.source.to_string(),
+                    calls: s.calls,
+                    errors: s.errors,
+                    slow: s.slow,
+                    p50_ms: as_ms(percentile(&sorted, 50)),
+                    p95_ms: as_ms(percentile(&sorted, 95)),
+                    max_ms: as_ms(s.max),
+                }
+            })
+            .collect();
+        commands.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms));
+
+        CommandMetrics {
+            since: self.since.to_rfc3339(),
+            slow_threshold_ms: self.settings.slow_threshold_ms,
+            commands,
+        }
+    }
+}
+
+/// Record one call
+pub fn record(source: &'static str, name: &str, elapsed: Duration, ok: bool) {
+    registry().record(source, name, elapsed, ok);
+}
+
+/// Run an async command body, recording its duration and outcome
+pub async fn timed<T, E, F>(name: &'static str, body: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let result = body.instrument(tracing::info_span!("command", name)).await;
+    record(SOURCE_COMMAND, name, started.elapsed(), result.is_ok());
+    result
+}
+
+/// Run a sync command body, recording its duration and outcome
+pub fn timed_sync<T, E>(name: &'static str, body: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    let _span = tracing::info_span!("command", name).entered();
+    let started = Instant::now();
+    let result = body();
+    record(SOURCE_COMMAND, name, started.elapsed(), result.is_ok());
+    result
+}
+
+/// Metric name for a REST request: the first of `templates` matching `path`,
+/// where a `{param}` segment matches any one non-empty segment. Paths no
+/// template matches share one entry per method.
+pub fn route_name(method: &str, path: &str, templates: &[&str]) -> String {
+    let segments: Vec<&str> = path.split('/').collect();
+    let matches = |template: &&&str| {
+        let parts: Vec<&str> = template.split('/').collect();
+        parts.len() == segments.len()
+            && parts.iter().zip(&segments).all(|(part, segment)| {
+                part == segment || (part.starts_with('{') && part.ends_with('}') && !segment.is_empty())
+            })
+    };
+    match templates.iter().find(matches) {
+        Some(template) => format!("{} {}", method, template),
+        None => format!("{} (unmatched)", method),
+    }
+}
+
+/// Current metrics for every command and route called since launch
+pub fn snapshot() -> CommandMetrics {
+    registry().snapshot()
+}
+
+/// Get p50/p95/max timings per command since launch
+#[tauri::command]
+pub async fn get_command_metrics() -> Result<CommandMetrics, String> {
+    Ok(snapshot())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_registry(window: usize) -> Registry {
+        Registry {
+            settings: CommandMetricsSettings { slow_threshold_ms: 100, window },
+            since: chrono::Utc::now(),
+            stats: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    #[test]
+    fn test_percentiles_window_and_slow_flags() {
+        let registry = test_registry(100);
+        for ms in 1..=100 {
+            registry.record(SOURCE_COMMAND, "list_skills", Duration::from_millis(ms), ms % 10 != 0);
+        }
+        registry.record(SOURCE_REST, "GET /api/health", Duration::from_millis(5), true);
+
+        let metrics = registry.snapshot();
+        let skills = &metrics.commands[0];
+        assert_eq!(skills.name, "list_skills");
+        assert_eq!((skills.calls, skills.errors, skills.slow), (100, 10, 1));
+        assert_eq!(skills.p50_ms.round(), 50.0);
+        assert_eq!(skills.p95_ms.round(), 95.0);
+        assert_eq!(metrics.commands[1].source, "rest");
+
+        // Only the window feeds percentiles; max covers the whole run
+        let registry = test_registry(2);
+        for ms in [900, 10, 20] {
+            registry.record(SOURCE_COMMAND, "fetch_quota", Duration::from_millis(ms), true);
+        }
+        let fetch = &registry.snapshot().commands[0];
+        assert_eq!(fetch.p95_ms.round(), 20.0);
+        assert_eq!(fetch.max_ms.round(), 900.0);
+    }
+
+    #[test]
+    fn test_route_names_use_templates() {
+        let templates = ["/api/health", "/api/accounts/switch", "/api/skills/{id}/scripts/{name}/run"];
+        assert_eq!(
+            route_name("POST", "/api/skills/pdf-tools/scripts/run.py/run", &templates),
+            "POST /api/skills/{id}/scripts/{name}/run"
+        );
+        assert_eq!(route_name("GET", "/api/health", &templates), "GET /api/health");
+        assert_eq!(route_name("POST", "/api/accounts/switch", &templates), "POST /api/accounts/switch");
+
+        // Unknown paths must not grow the registry one entry per path
+        assert_eq!(route_name("GET", "/api/skills/a/scripts//run", &templates), "GET (unmatched)");
+        assert_eq!(route_name("GET", "/api/accounts/1234", &templates), "GET (unmatched)");
+        assert_eq!(route_name("GET", "/favicon.ico", &templates), "GET (unmatched)");
+    }
+}
@@ -0,0 +1,404 @@
+/// Content Search - "Find in files" for the project
+///
+/// `search_in_files` walks the folder breadth first like the file explorer,
+/// skipping what the shared ignore matcher ignores, and searches every text
+/// file line by line for the query: literal text unless `regex` is set,
+/// case-insensitive unless `case_sensitive` is. `include_glob` and
+/// `exclude_glob` take comma-separated gitignore-style globs; one without a
+/// `/` matches file names (`*.rs`), one with a `/` the path below the folder
+/// (`src/**/*.ts`). Files over 1 MB and files with a NUL byte in their first
+/// 8 KB (binary) are skipped. Matches are emitted in batches as
+/// `content-search-results` while the walk goes on, tagged with the caller's
+/// `search_id`, and the command returns the totals once it is done or
+/// `max_results` matches were found. The search is an operation (see
+/// `operations`), so `cancel_operation` stops it. Errors are
+/// `{ kind, message }`; a query that is not a valid regex is `invalid_regex`.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use regex::{Regex, RegexBuilder};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use tauri::Emitter;
+
+use crate::command_metrics;
+use crate::file_explorer;
+use crate::ignore_rules::{self, IgnoreMatcher};
+use crate::operations::ProgressReporter;
+
+pub const CONTENT_SEARCH_EVENT: &str = "content-search-results";
+
+pub const KIND_CONTENT_SEARCH: &str = "content_search";
+
+/// Matches returned when the caller sets no cap
+const DEFAULT_MAX_RESULTS: usize = 2000;
+
+/// Larger files are not searched
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Leading bytes checked for a NUL
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+/// Longer lines are cut to a window around the match
+const MAX_LINE_CHARS: usize = 500;
+
+/// Characters kept before the match when a line is cut
+const CONTEXT_CHARS: usize = 100;
+
+/// A batch goes out once it holds this many matches or is this old
+const BATCH_SIZE: usize = 100;
+const BATCH_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Folders visited by one search at most
+const MAX_SEARCH_DIRS: usize = 50_000;
+
+/// `options` of `search_in_files`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ContentSearchOptions {
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub include_glob: Option<String>,
+    pub exclude_glob: Option<String>,
+    pub max_results: Option<usize>,
+}
+
+/// One matching line
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ContentMatch {
+    pub path: String,
+    /// 1-based
+    pub line_number: usize,
+    /// The line, or a window of it around the match when it is very long
+    pub line_text: String,
+    /// Character positions of the match in `line_text`
+    pub match_start: usize,
+    pub match_end: usize,
+}
+
+/// Payload of `content-search-results`
+#[derive(Debug, Clone, Serialize)]
+pub struct ContentSearchBatch<'a> {
+    pub search_id: &'a str,
+    /// Pass to `cancel_operation` to stop the search
+    pub operation_id: &'a str,
+    pub matches: &'a [ContentMatch],
+}
+
+/// Result of `search_in_files`; the matches themselves came as events
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct ContentSearchSummary {
+    pub search_id: String,
+    pub matches: usize,
+    pub files_searched: usize,
+    /// Binary or too large
+    pub files_skipped: usize,
+    /// Stopped at `max_results`; more lines may match
+    pub truncated: bool,
+    /// Stopped by `cancel_operation`
+    pub cancelled: bool,
+}
+
+/// Errors from `search_in_files`
+#[derive(Debug)]
+pub enum ContentSearchError {
+    EmptyQuery,
+    InvalidRegex(String),
+    Io(String),
+}
+
+impl ContentSearchError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::EmptyQuery => "empty_query",
+            Self::InvalidRegex(_) => "invalid_regex",
+            Self::Io(_) => "io",
+        }
+    }
+}
+
+impl fmt::Display for ContentSearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyQuery => write!(f, "Nothing to search for"),
+            Self::InvalidRegex(msg) => write!(f, "Invalid regular expression: {}", msg),
+            Self::Io(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<String> for ContentSearchError {
+    fn from(msg: String) -> Self {
+        Self::Io(msg)
+    }
+}
+
+/// Serialized as `{ kind, message }` so the frontend can branch on `kind`
+impl Serialize for ContentSearchError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ContentSearchError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+/// The query as a regex, escaped unless `options.regex`
+fn compile(query: &str, options: &ContentSearchOptions) -> Result<Regex, ContentSearchError> {
+    if query.is_empty() {
+        return Err(ContentSearchError::EmptyQuery);
+    }
+    let pattern = if options.regex { query.to_string() } else { regex::escape(query) };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|e| ContentSearchError::InvalidRegex(e.to_string()))
+}
+
+/// Comma-separated globs; each matches the file name, or the relative path when it has a `/`
+struct Globs(Vec<(Vec<char>, bool)>);
+
+impl Globs {
+    fn parse(globs: Option<&str>) -> Self {
+        Self(
+            globs
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|glob| !glob.is_empty())
+                .map(|glob| {
+                    let glob = glob.trim_start_matches('/');
+                    (glob.chars().collect(), glob.contains('/'))
+                })
+                .collect(),
+        )
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn matches(&self, relative_path: &str) -> bool {
+        let path: Vec<char> = relative_path.chars().collect();
+        let name_start = path.iter().rposition(|&c| c == '/').map_or(0, |slash| slash + 1);
+        self.0.iter().any(|(pattern, anchored)| {
+            let subject = if *anchored { &path[..] } else { &path[name_start..] };
+            ignore_rules::glob(pattern, subject)
+        })
+    }
+}
+
+/// `line` with the match at characters `start..end`, cut to a window when it is very long
+fn excerpt(line: &str, start: usize, end: usize) -> (String, usize, usize) {
+    let length = line.chars().count();
+    if length <= MAX_LINE_CHARS {
+        return (line.to_string(), start, end);
+    }
+    let from = start.saturating_sub(CONTEXT_CHARS);
+    let end = end.min(from + MAX_LINE_CHARS);
+    let text = line.chars().skip(from).take(MAX_LINE_CHARS).collect();
+    (text, start - from, end - from)
+}
+
+/// Matches in the file at `path`, or None when it is binary or too large
+fn search_file(path: &Path, regex: &Regex, limit: usize) -> Option<Vec<ContentMatch>> {
+    if std::fs::metadata(path).ok()?.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    let bytes = std::fs::read(path).ok()?;
+    if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let display = path.to_string_lossy().to_string();
+
+    let mut matches = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        for found in regex.find_iter(line) {
+            if matches.len() == limit {
+                return Some(matches);
+            }
+            if found.is_empty() {
+                continue;
+            }
+            let start = line[..found.start()].chars().count();
+            let end = start + found.as_str().chars().count();
+            let (line_text, match_start, match_end) = excerpt(line, start, end);
+            matches.push(ContentMatch { path: display.clone(), line_number: index + 1, line_text, match_start, match_end });
+        }
+    }
+    Some(matches)
+}
+
+/// Search the files under `root`, handing matches to `on_batch` as they add up
+fn search(
+    root: &Path,
+    matcher: &IgnoreMatcher,
+    regex: &Regex,
+    options: &ContentSearchOptions,
+    on_batch: &mut dyn FnMut(&[ContentMatch], usize),
+    cancelled: &dyn Fn() -> bool,
+) -> ContentSearchSummary {
+    let max_results = options.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+    let include = Globs::parse(options.include_glob.as_deref());
+    let exclude = Globs::parse(options.exclude_glob.as_deref());
+    let mut summary = ContentSearchSummary::default();
+    let mut batch: Vec<ContentMatch> = Vec::new();
+    let mut last_flush = Instant::now();
+    let mut worklist = VecDeque::from([root.to_path_buf()]);
+    let mut visited = 0;
+    // Directories reached again through a symlink are skipped
+    let mut seen = file_explorer::Visited::default();
+
+    'walk: while let Some(dir) = worklist.pop_front() {
+        if !seen.first_visit(&dir) {
+            continue;
+        }
+        visited += 1;
+        if visited > MAX_SEARCH_DIRS {
+            eprintln!("Content search: stopped in {} after {} folders", root.display(), MAX_SEARCH_DIRS);
+            break;
+        }
+        let Ok(entries) = file_explorer::read_sorted(&dir, matcher) else { continue };
+        for entry in entries {
+            if cancelled() {
+                summary.cancelled = true;
+                break 'walk;
+            }
+            if entry.is_dir {
+                worklist.push_back(entry.path);
+                continue;
+            }
+            let relative_path = entry
+                .path
+                .strip_prefix(root)
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+            if (!include.is_empty() && !include.matches(&relative_path)) || exclude.matches(&relative_path) {
+                continue;
+            }
+
+            // One more than allowed tells whether anything was left out
+            let Some(found) = search_file(&entry.path, regex, max_results - summary.matches + 1) else {
+                summary.files_skipped += 1;
+                continue;
+            };
+            summary.files_searched += 1;
+            for found in found {
+                if summary.matches == max_results {
+                    summary.truncated = true;
+                    break 'walk;
+                }
+                summary.matches += 1;
+                batch.push(found);
+            }
+            if batch.len() >= BATCH_SIZE || (!batch.is_empty() && last_flush.elapsed() >= BATCH_INTERVAL) {
+                on_batch(&batch, summary.files_searched);
+                batch.clear();
+                last_flush = Instant::now();
+            }
+        }
+    }
+    if !batch.is_empty() {
+        on_batch(&batch, summary.files_searched);
+    }
+    summary
+}
+
+/// Search file contents under `root` (see the module docs)
+#[tauri::command]
+pub async fn search_in_files(
+    app: tauri::AppHandle,
+    root: String,
+    query: String,
+    options: Option<ContentSearchOptions>,
+    search_id: Option<String>,
+) -> Result<ContentSearchSummary, ContentSearchError> {
+    command_metrics::timed("search_in_files", async move {
+        let dir = file_explorer::check_directory(&root)?;
+        let options = options.unwrap_or_default();
+        let regex = compile(&query, &options)?;
+        let search_id = search_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+        tauri::async_runtime::spawn_blocking(move || {
+            let matcher = ignore_rules::current_matcher(&dir);
+            let reporter = ProgressReporter::start(&app, KIND_CONTENT_SEARCH);
+            let mut emit = |matches: &[ContentMatch], files_searched: usize| {
+                let batch = ContentSearchBatch { search_id: &search_id, operation_id: reporter.id(), matches };
+                let _ = app.emit(CONTENT_SEARCH_EVENT, batch);
+                reporter.report(files_searched as u64, None, Some("Searching files".to_string()));
+            };
+            let cancelled = || reporter.is_cancelled();
+            let summary = search(&dir, &matcher, &regex, &options, &mut emit, &cancelled);
+            ContentSearchSummary { search_id: search_id.clone(), ..summary }
+        })
+        .await
+        .map_err(|e| ContentSearchError::Io(format!("Content search failed: {}", e)))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(root: &Path, query: &str, options: &ContentSearchOptions) -> (Vec<ContentMatch>, ContentSearchSummary) {
+        let matcher = ignore_rules::current_matcher(root);
+        let regex = compile(query, options).unwrap();
+        let mut found = Vec::new();
+        let summary = search(root, &matcher, &regex, options, &mut |batch, _| found.extend_from_slice(batch), &|| false);
+        (found, summary)
+    }
+
+    #[test]
+    fn test_search_filters_skips_and_caps() {
+        let root = std::env::temp_dir().join(format!("vibecode-content-search-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules")).unwrap();
+        std::fs::write(root.join("src").join("main.rs"), "fn main() {\n    let todo = 1; // TODO: more\n}\n").unwrap();
+        std::fs::write(root.join("src").join("app.ts"), "// todo later\n").unwrap();
+        std::fs::write(root.join("notes.md"), format!("{}TODO\n", "x".repeat(1000))).unwrap();
+        std::fs::write(root.join("node_modules").join("dep.js"), "TODO\n").unwrap();
+        std::fs::write(root.join("logo.bin"), b"TODO\0\x01\x02").unwrap();
+        std::fs::write(root.join("huge.txt"), "TODO\n".repeat(300_000)).unwrap();
+
+        let (found, summary) = run(&root, "todo", &ContentSearchOptions::default());
+        let hits: Vec<(String, usize, usize)> = found
+            .iter()
+            .map(|m| (m.path.strip_prefix(&*root.to_string_lossy()).unwrap().to_string(), m.line_number, m.match_start))
+            .collect();
+        let sep = std::path::MAIN_SEPARATOR;
+        assert_eq!(
+            hits,
+            vec![
+                (format!("{}notes.md", sep), 1, CONTEXT_CHARS),
+                (format!("{}src{}app.ts", sep, sep), 1, 3),
+                (format!("{}src{}main.rs", sep, sep), 2, 8),
+                (format!("{}src{}main.rs", sep, sep), 2, 21),
+            ]
+        );
+        assert_eq!(found[0].line_text.chars().count(), CONTEXT_CHARS + 4);
+        assert_eq!((summary.matches, summary.files_searched, summary.files_skipped), (4, 3, 2));
+
+        let case_sensitive =
+            ContentSearchOptions { case_sensitive: true, include_glob: Some("*.rs, *.ts".into()), ..Default::default() };
+        let (found, _) = run(&root, "TODO", &case_sensitive);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].match_start, found[0].match_end), (21, 25));
+
+        let regex = ContentSearchOptions { regex: true, exclude_glob: Some("src/**".into()), ..Default::default() };
+        assert_eq!(run(&root, r"x+TODO$", &regex).0.len(), 1);
+
+        let capped = ContentSearchOptions { max_results: Some(2), ..Default::default() };
+        let (found, summary) = run(&root, "todo", &capped);
+        assert_eq!(found.len(), 2);
+        assert!(summary.truncated);
+
+        let invalid = compile("fn (", &ContentSearchOptions { regex: true, ..Default::default() }).unwrap_err();
+        assert_eq!(serde_json::to_value(&invalid).unwrap()["kind"], "invalid_regex");
+        assert!(matches!(compile("", &ContentSearchOptions::default()), Err(ContentSearchError::EmptyQuery)));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,213 @@
+/// Directory Size - How much disk space a folder and its entries take up
+///
+/// `get_directory_size` walks the folder on a blocking thread from a worklist,
+/// summing file sizes, and reports the total, the number of files and the ten
+/// largest entries directly inside it (a folder counts with everything below
+/// it). With `respect_ignore` the shared ignore matcher applies, so
+/// `node_modules` and the like are left out; without it everything counts.
+/// Symlinks are not followed, which keeps loops and double counting out. The
+/// walk is an operation (see `operations`) whose progress message is
+/// `Sizing <path>`, so `cancel_operation` stops it and the partial sums come
+/// back with `cancelled` set. Walks that took a while or counted many files
+/// are kept for 30 seconds, per folder, ignore setting and ignore-rules
+/// generation, and served from there with `cached` set.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+
+use crate::command_metrics;
+use crate::file_explorer;
+use crate::ignore_rules::{self, IgnoreMatcher};
+use crate::operations::ProgressReporter;
+
+pub const KIND_DIRECTORY_SIZE: &str = "directory_size";
+
+/// Entries listed in `largest_entries`
+const LARGEST_ENTRIES: usize = 10;
+
+/// How long a cached result is served
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Walks that took this long, or counted this many files, are cached
+const CACHE_MIN_WALK: Duration = Duration::from_millis(500);
+const CACHE_MIN_FILES: u64 = 10_000;
+
+/// Files counted between progress events
+const PROGRESS_EVERY: u64 = 2000;
+
+/// An entry directly inside the sized folder
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SizedEntry {
+    pub path: String,
+    pub bytes: u64,
+    pub is_dir: bool,
+}
+
+/// Result of `get_directory_size`
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectorySize {
+    pub path: String,
+    pub total_bytes: u64,
+    pub file_count: u64,
+    /// Largest first
+    pub largest_entries: Vec<SizedEntry>,
+    /// Stopped by `cancel_operation`; the sums are partial
+    pub cancelled: bool,
+    /// Served from a recent walk
+    pub cached: bool,
+}
+
+/// Folder, `respect_ignore` and ignore-rules generation of a cached walk
+type CacheKey = (PathBuf, bool, u64);
+
+static CACHE: Mutex<Option<HashMap<CacheKey, (Instant, DirectorySize)>>> = Mutex::new(None);
+
+fn cached(key: &CacheKey) -> Option<DirectorySize> {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = cache.get_or_insert_with(HashMap::new);
+    cache.retain(|_, (at, _)| at.elapsed() < CACHE_TTL);
+    cache.get(key).map(|(_, size)| DirectorySize { cached: true, ..size.clone() })
+}
+
+fn remember(key: CacheKey, size: &DirectorySize) {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.get_or_insert_with(HashMap::new).insert(key, (Instant::now(), size.clone()));
+}
+
+/// Sizes under `root`, skipping what `matcher` ignores, until `is_cancelled`;
+/// `progress` gets the files and bytes counted so far now and then
+fn walk(
+    root: &Path,
+    matcher: Option<&IgnoreMatcher>,
+    is_cancelled: &dyn Fn() -> bool,
+    progress: &mut dyn FnMut(u64, u64),
+) -> DirectorySize {
+    let mut size = DirectorySize {
+        path: root.to_string_lossy().to_string(),
+        total_bytes: 0,
+        file_count: 0,
+        largest_entries: Vec::new(),
+        cancelled: false,
+        cached: false,
+    };
+    // Folders still to read, with the entry of `root` they are inside
+    let mut worklist: Vec<(PathBuf, Option<usize>)> = vec![(root.to_path_buf(), None)];
+    let mut entries: Vec<SizedEntry> = Vec::new();
+
+    while let Some((dir, owner)) = worklist.pop() {
+        if is_cancelled() {
+            size.cancelled = true;
+            break;
+        }
+        let Ok(read_dir) = std::fs::read_dir(&dir) else { continue };
+        for entry in read_dir.flatten() {
+            let Ok(file_type) = entry.file_type() else { continue };
+            if file_type.is_symlink() {
+                continue;
+            }
+            let path = entry.path();
+            let is_dir = file_type.is_dir();
+            if matcher.is_some_and(|m| m.is_ignored(&path, is_dir)) {
+                continue;
+            }
+            let owner = owner.unwrap_or_else(|| {
+                entries.push(SizedEntry { path: path.to_string_lossy().to_string(), bytes: 0, is_dir });
+                entries.len() - 1
+            });
+            if is_dir {
+                worklist.push((path, Some(owner)));
+                continue;
+            }
+            let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            entries[owner].bytes += bytes;
+            size.total_bytes += bytes;
+            size.file_count += 1;
+            if size.file_count.is_multiple_of(PROGRESS_EVERY) {
+                progress(size.file_count, size.total_bytes);
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    entries.truncate(LARGEST_ENTRIES);
+    size.largest_entries = entries;
+    size
+}
+
+/// Total size, file count and largest entries of `path` (see the module docs)
+#[tauri::command]
+pub async fn get_directory_size(
+    app: tauri::AppHandle,
+    path: String,
+    respect_ignore: Option<bool>,
+) -> Result<DirectorySize, String> {
+    command_metrics::timed("get_directory_size", async move {
+        let dir = file_explorer::check_directory(&path)?;
+        let respect_ignore = respect_ignore.unwrap_or(true);
+
+        tauri::async_runtime::spawn_blocking(move || {
+            let matcher = respect_ignore.then(|| ignore_rules::current_matcher(&dir));
+            let key = (dir.clone(), respect_ignore, matcher.as_ref().map_or(0, |m| m.generation()));
+            if let Some(size) = cached(&key) {
+                return size;
+            }
+
+            let reporter = ProgressReporter::start(&app, KIND_DIRECTORY_SIZE);
+            let message = format!("Sizing {}", dir.display());
+            reporter.report(0, None, Some(message.clone()));
+            let started = Instant::now();
+            let size = walk(&dir, matcher.as_deref(), &|| reporter.is_cancelled(), &mut |files, _| {
+                reporter.report(files, None, Some(message.clone()))
+            });
+            if !size.cancelled && (started.elapsed() >= CACHE_MIN_WALK || size.file_count >= CACHE_MIN_FILES) {
+                remember(key, &size);
+            }
+            size
+        })
+        .await
+        .map_err(|e| format!("Directory size failed: {}", e))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes_sum_per_entry_and_respect_ignores() {
+        let root = std::env::temp_dir().join(format!("vibecode-directory-size-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("data").join("raw")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules").join("pkg")).unwrap();
+        std::fs::write(root.join("data").join("raw").join("dump.csv"), vec![b'x'; 3000]).unwrap();
+        std::fs::write(root.join("data").join("small.csv"), vec![b'x'; 100]).unwrap();
+        std::fs::write(root.join("README.md"), vec![b'x'; 500]).unwrap();
+        std::fs::write(root.join("node_modules").join("pkg").join("index.js"), vec![b'x'; 9000]).unwrap();
+        let matcher = ignore_rules::current_matcher(&root);
+        let entry = |rel: &str, bytes: u64, is_dir: bool| SizedEntry {
+            path: root.join(rel).to_string_lossy().to_string(),
+            bytes,
+            is_dir,
+        };
+
+        let size = walk(&root, Some(&matcher), &|| false, &mut |_, _| {});
+        assert_eq!((size.total_bytes, size.file_count, size.cancelled), (3600, 3, false));
+        assert_eq!(size.largest_entries, vec![entry("data", 3100, true), entry("README.md", 500, false)]);
+
+        let everything = walk(&root, None, &|| false, &mut |_, _| {});
+        assert_eq!((everything.total_bytes, everything.file_count), (12_600, 4));
+        assert_eq!(everything.largest_entries[0], entry("node_modules", 9000, true));
+
+        let cancelled = walk(&root, None, &|| true, &mut |_, _| {});
+        assert!(cancelled.cancelled && cancelled.file_count == 0);
+
+        let key = (root.clone(), true, 0);
+        remember(key.clone(), &size);
+        assert!(cached(&key).is_some_and(|hit| hit.cached && hit.total_bytes == 3600));
+        assert!(cached(&(root.clone(), false, 0)).is_none());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
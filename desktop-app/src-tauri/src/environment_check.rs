@@ -0,0 +1,120 @@
+/// Environment Check - One-shot health report for the Settings page
+///
+/// Verifies the pieces the app depends on (Python and vibe.py as `python_env`
+/// resolved them, skills and workflows folders) and includes a condensed
+/// skill/workflow name lint.
+
+use serde::Serialize;
+
+use crate::child_env::{build_child_env, ChildContext};
+use crate::python_env::PythonEnv;
+
+pub const STATUS_OK: &str = "ok";
+pub const STATUS_WARN: &str = "warn";
+pub const STATUS_ERROR: &str = "error";
+
+/// Maximum lint findings listed in the condensed summary
+const MAX_NAME_DETAILS: usize = 3;
+
+/// Result of one check
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvironmentCheck {
+    pub name: String,
+    /// "ok" | "warn" | "error"
+    pub status: String,
+    pub message: String,
+    pub details: Vec<String>,
+}
+
+/// Result of `run_environment_check`
+#[derive(Debug, Serialize)]
+pub struct EnvironmentReport {
+    pub ok: bool,
+    pub checks: Vec<EnvironmentCheck>,
+}
+
+fn check(name: &str, status: &str, message: String, details: Vec<String>) -> EnvironmentCheck {
+    EnvironmentCheck {
+        name: name.to_string(),
+        status: status.to_string(),
+        message,
+        details,
+    }
+}
+
+fn check_python(env: &PythonEnv) -> EnvironmentCheck {
+    let details = vec![format!("{} ({})", env.interpreter, env.interpreter_from)];
+    let mut cmd = match env.command() {
+        Ok(cmd) => cmd,
+        Err(e) => return check("python", STATUS_ERROR, e, details),
+    };
+    let output = build_child_env(&ChildContext::Task)
+        .apply(&mut cmd)
+        .arg("--version")
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            // Python 2 printed the version to stderr
+            let version = if output.stdout.is_empty() { output.stderr } else { output.stdout };
+            check("python", STATUS_OK, String::from_utf8_lossy(&version).trim().to_string(), details)
+        }
+        Ok(output) => check("python", STATUS_ERROR, String::from_utf8_lossy(&output.stderr).trim().to_string(), details),
+        Err(e) => check("python", STATUS_ERROR, format!("Python not found: {}", e), details),
+    }
+}
+
+fn check_path(name: &str, path: &std::path::Path, missing_status: &str) -> EnvironmentCheck {
+    if path.exists() {
+        check(name, STATUS_OK, path.to_string_lossy().to_string(), Vec::new())
+    } else {
+        check(name, missing_status, format!("Not found: {}", path.display()), Vec::new())
+    }
+}
+
+/// Condensed `lint_names` result
+fn check_names() -> EnvironmentCheck {
+    let issues = crate::name_lint::lint(&crate::name_lint::collect_items());
+    let errors = issues.iter().filter(|i| i.severity == "error").count();
+    let warnings = issues.len() - errors;
+
+    let status = if errors > 0 {
+        STATUS_ERROR
+    } else if warnings > 0 {
+        STATUS_WARN
+    } else {
+        STATUS_OK
+    };
+    let details = issues
+        .iter()
+        .take(MAX_NAME_DETAILS)
+        .map(|i| format!("{} → {}", i.message, i.suggestion))
+        .collect();
+
+    check(
+        "names",
+        status,
+        format!("{} naming error(s), {} warning(s)", errors, warnings),
+        details,
+    )
+}
+
+/// Run all environment checks
+#[tauri::command]
+pub async fn run_environment_check() -> Result<EnvironmentReport, String> {
+    crate::command_metrics::timed("run_environment_check", async move {
+        let env = crate::python_env::current();
+        let checks = vec![
+            check_python(&env),
+            check_path("vibe.py", &env.vibe_path(), STATUS_ERROR),
+            check_path("skills", &crate::get_skills_path(), STATUS_WARN),
+            check_path("workflows", &crate::get_workflows_path(), STATUS_WARN),
+            check_names(),
+        ];
+
+        Ok(EnvironmentReport {
+            ok: checks.iter().all(|c| c.status != STATUS_ERROR),
+            checks,
+        })
+    }).await
+}
@@ -0,0 +1,536 @@
+/// File Content - What the file viewer shows for a file
+///
+/// `read_file_content` sniffs the first 8 KB of a file before reading it. A
+/// file that starts with the signature of a PNG, JPEG, GIF, WebP, BMP or ICO
+/// image comes back as `kind: "image"` with its mime type, and with its bytes
+/// in `base64` when it is at most 5 MB. Sniffed bytes `text_encoding` finds no
+/// encoding for make it `kind: "binary"` with metadata only (sqlite databases,
+/// archives, executables). Everything else is `kind: "text"`, read whole and
+/// decoded when it is at most 1 MB, with the `encoding` it was decoded with;
+/// the caller can pass an `encoding` instead of having one detected.
+/// `write_file_content` encodes text back into a given encoding.
+///
+/// Larger text files are paged: `get_file_info` counts lines and finds the
+/// longest one, and `read_file_range` returns a window of lines, streamed
+/// through a buffered reader so the file is never held in memory. A line
+/// longer than 64 KB is cut in that window (its number is listed in
+/// `cut_lines`); byte-offset mode (`offset_bytes`) reads such a line, or any
+/// stretch of the file, a chunk at a time. Lines are split at newline bytes,
+/// so UTF-16 files are not paged.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use base64::Engine;
+use encoding_rs::Encoding;
+use serde::Serialize;
+
+use crate::text_encoding::{self, Detected};
+
+/// Bytes sniffed to tell text from binary
+const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Largest text file returned
+const MAX_TEXT_BYTES: u64 = 1024 * 1024;
+
+/// Largest image returned as base64
+const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Lines returned by `read_file_range` when the caller sets no count, and at most
+const DEFAULT_RANGE_LINES: usize = 1000;
+const MAX_RANGE_LINES: usize = 10_000;
+
+/// Longest line returned whole in line mode
+const MAX_LINE_BYTES: usize = 64 * 1024;
+
+/// Bytes returned in byte-offset mode when the caller sets no count, and at most
+const DEFAULT_RANGE_BYTES: usize = 64 * 1024;
+const MAX_RANGE_BYTES: usize = 1024 * 1024;
+
+/// Image signatures and their mime types (WebP is `RIFF....WEBP`)
+const IMAGE_SIGNATURES: [(&[u8], &str); 6] = [
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"BM", "image/bmp"),
+    (b"\x00\x00\x01\x00", "image/x-icon"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    Text,
+    Binary,
+    Image,
+}
+
+/// Result of `read_file_content`
+#[derive(Debug, Serialize)]
+pub struct FileContent {
+    pub kind: ContentKind,
+    /// Content of a text file; None when it is over 1 MB (page through it
+    /// with `read_file_range`)
+    pub text: Option<String>,
+    /// Bytes of an image small enough to preview
+    pub base64: Option<String>,
+    /// Mime type of an image
+    pub mime: Option<String>,
+    /// Size of the file in bytes
+    pub size: u64,
+    /// Encoding of a text file (`UTF-8`, `Shift_JIS`, `windows-1252`, ...)
+    pub encoding: Option<String>,
+    /// The text file starts with a byte order mark, left out of `text`
+    pub bom: bool,
+    /// Malformed bytes were replaced with U+FFFD, so writing `text` back
+    /// would not restore them
+    pub lossy: bool,
+}
+
+/// Result of `get_file_info`
+#[derive(Debug, Serialize)]
+pub struct FileInfo {
+    pub kind: ContentKind,
+    pub size: u64,
+    /// Encoding of a text file, judged by its start
+    pub encoding: Option<String>,
+    /// Lines in the file, the last one counted even without a final newline
+    pub line_count: u64,
+    /// Bytes in the longest line; over 64 KB, read it in byte-offset mode
+    pub longest_line: u64,
+}
+
+/// Result of `read_file_range`
+#[derive(Debug, Serialize)]
+pub struct FileRange {
+    /// Number (from 0) of the first line returned; None in byte-offset mode,
+    /// where the chunk may start inside a line
+    pub first_line: Option<u64>,
+    /// Lines without their line endings
+    pub lines: Vec<String>,
+    /// Numbers of the lines cut at 64 KB
+    pub cut_lines: Vec<u64>,
+    /// Bytes of the file the lines came from: `byte_start..byte_end`
+    pub byte_start: u64,
+    pub byte_end: u64,
+    /// Nothing follows `byte_end`
+    pub eof: bool,
+}
+
+/// Mime type of the image `head` starts, if it starts one; text that happens
+/// to begin like a signature ("BM...") is not an image
+fn image_mime(head: &[u8]) -> Option<&'static str> {
+    if text_encoding::detect(head, false).is_some() {
+        return None;
+    }
+    if head.starts_with(b"RIFF") && head.get(8..12) == Some(b"WEBP".as_slice()) {
+        return Some("image/webp");
+    }
+    IMAGE_SIGNATURES.iter().find(|(signature, _)| head.starts_with(signature)).map(|(_, mime)| *mime)
+}
+
+/// Encoding of `bytes`: `forced` if the caller chose one, detected otherwise
+/// (None: binary); `complete` is false when they are the start of the file
+fn encoding_of(forced: Option<&'static Encoding>, bytes: &[u8], complete: bool) -> Option<Detected> {
+    match forced {
+        Some(encoding) => Some(text_encoding::with_encoding(encoding, bytes)),
+        None => text_encoding::detect(bytes, complete),
+    }
+}
+
+fn read_error(e: std::io::Error) -> String {
+    format!("Failed to read file: {}", e)
+}
+
+/// Size of the file at `path`, which must be a file
+fn file_size(path: &Path) -> Result<u64, String> {
+    let display = path.display();
+    if !path.exists() {
+        return Err(format!("File does not exist: {}", display));
+    }
+    if !path.is_file() {
+        return Err(format!("Path is not a file: {}", display));
+    }
+    Ok(std::fs::metadata(path).map_err(|e| format!("Failed to read metadata: {}", e))?.len())
+}
+
+/// The first `SNIFF_BYTES` of the file at `path`
+fn sniff(path: &Path) -> Result<Vec<u8>, String> {
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    File::open(path)
+        .and_then(|file| file.take(SNIFF_BYTES as u64).read_to_end(&mut head))
+        .map_err(read_error)?;
+    Ok(head)
+}
+
+fn read(path: &Path, max_image: u64, forced: Option<&'static Encoding>) -> Result<FileContent, String> {
+    let size = file_size(path)?;
+    let head = sniff(path)?;
+    let binary = FileContent {
+        kind: ContentKind::Binary,
+        text: None,
+        base64: None,
+        mime: None,
+        size,
+        encoding: None,
+        bom: false,
+        lossy: false,
+    };
+
+    if let Some(mime) = image_mime(&head) {
+        let base64 = if size <= max_image {
+            let bytes = std::fs::read(path).map_err(read_error)?;
+            Some(base64::engine::general_purpose::STANDARD.encode(bytes))
+        } else {
+            None
+        };
+        return Ok(FileContent { kind: ContentKind::Image, base64, mime: Some(mime.to_string()), ..binary });
+    }
+    let Some(sniffed) = encoding_of(forced, &head, false) else { return Ok(binary) };
+
+    if size > MAX_TEXT_BYTES {
+        let encoding = Some(sniffed.encoding.name().to_string());
+        return Ok(FileContent { kind: ContentKind::Text, encoding, bom: sniffed.bom, ..binary });
+    }
+    let bytes = std::fs::read(path).map_err(read_error)?;
+    // Detected again on the whole file: binary, or another encoding, may
+    // start further in than the sniff reached
+    let Some(detected) = encoding_of(forced, &bytes, true) else { return Ok(binary) };
+    let (text, lossy) = text_encoding::decode(detected.encoding, &bytes);
+    Ok(FileContent {
+        kind: ContentKind::Text,
+        text: Some(text),
+        encoding: Some(detected.encoding.name().to_string()),
+        bom: detected.bom,
+        lossy,
+        ..binary
+    })
+}
+
+/// Line count and longest line of `reader`, read to the end
+fn line_stats(reader: &mut impl BufRead) -> std::io::Result<(u64, u64)> {
+    let (mut lines, mut longest, mut current) = (0, 0, 0);
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+        for &byte in buf {
+            if byte == b'\n' {
+                lines += 1;
+                longest = longest.max(current);
+                current = 0;
+            } else {
+                current += 1;
+            }
+        }
+        let len = buf.len();
+        reader.consume(len);
+    }
+    if current > 0 {
+        lines += 1;
+        longest = longest.max(current);
+    }
+    Ok((lines, longest))
+}
+
+/// Skip past the next newline; the bytes skipped (0 at the end)
+fn skip_line(reader: &mut impl BufRead) -> std::io::Result<u64> {
+    let mut skipped = 0;
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(skipped);
+        }
+        let (len, done) = match buf.iter().position(|&b| b == b'\n') {
+            Some(i) => (i + 1, true),
+            None => (buf.len(), false),
+        };
+        reader.consume(len);
+        skipped += len as u64;
+        if done {
+            return Ok(skipped);
+        }
+    }
+}
+
+/// The next line, its first `max` bytes kept and the rest skipped, with the
+/// bytes it took up (None at the end)
+fn next_line(reader: &mut impl BufRead, max: usize) -> std::io::Result<Option<(Vec<u8>, bool, u64)>> {
+    let mut line = Vec::new();
+    let mut consumed = 0;
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+        let (len, done) = match buf.iter().position(|&b| b == b'\n') {
+            Some(i) => (i + 1, true),
+            None => (buf.len(), false),
+        };
+        let keep = len.min(max.saturating_sub(line.len()));
+        line.extend_from_slice(&buf[..keep]);
+        reader.consume(len);
+        consumed += len as u64;
+        if done {
+            break;
+        }
+    }
+    if consumed == 0 {
+        return Ok(None);
+    }
+    let cut = (line.len() as u64) < consumed;
+    while line.last().is_some_and(|&b| b == b'\n' || b == b'\r') {
+        line.pop();
+    }
+    Ok(Some((line, cut, consumed)))
+}
+
+/// `count` lines from line `offset` on, decoded with `encoding`
+fn read_lines(
+    path: &Path,
+    offset: u64,
+    count: usize,
+    max_line: usize,
+    encoding: &'static Encoding,
+) -> std::io::Result<FileRange> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut byte_start = 0;
+    for _ in 0..offset {
+        match skip_line(&mut reader)? {
+            0 => break,
+            skipped => byte_start += skipped,
+        }
+    }
+
+    let mut range = FileRange {
+        first_line: Some(offset),
+        lines: Vec::new(),
+        cut_lines: Vec::new(),
+        byte_start,
+        byte_end: byte_start,
+        eof: false,
+    };
+    while range.lines.len() < count {
+        let Some((line, cut, consumed)) = next_line(&mut reader, max_line)? else { break };
+        if cut {
+            range.cut_lines.push(offset + range.lines.len() as u64);
+        }
+        let (text, _) = if range.byte_end == 0 {
+            encoding.decode_with_bom_removal(&line)
+        } else {
+            encoding.decode_without_bom_handling(&line)
+        };
+        range.lines.push(text.into_owned());
+        range.byte_end += consumed;
+    }
+    range.eof = reader.fill_buf()?.is_empty();
+    Ok(range)
+}
+
+/// `count` bytes from byte `offset` on, decoded with `encoding` and split
+/// into lines
+fn read_bytes(path: &Path, offset: u64, count: usize, encoding: &'static Encoding) -> std::io::Result<FileRange> {
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+    file.seek(SeekFrom::Start(offset))?;
+    let mut bytes = Vec::with_capacity(count.min(size.saturating_sub(offset) as usize));
+    file.take(count as u64).read_to_end(&mut bytes)?;
+
+    let byte_start = offset.min(size);
+    let byte_end = byte_start + bytes.len() as u64;
+    Ok(FileRange {
+        first_line: None,
+        lines: encoding.decode_without_bom_handling(&bytes).0.lines().map(str::to_string).collect(),
+        cut_lines: Vec::new(),
+        byte_start,
+        byte_end,
+        eof: byte_end >= size,
+    })
+}
+
+/// `label` as an encoding, when the caller passed one
+fn forced_encoding(label: Option<String>) -> Result<Option<&'static Encoding>, String> {
+    label.as_deref().map(text_encoding::for_label).transpose()
+}
+
+/// Read a file for the viewer (see the module docs), in `encoding` if set
+#[tauri::command]
+pub async fn read_file_content(path: String, encoding: Option<String>) -> Result<FileContent, String> {
+    crate::command_metrics::timed("read_file_content", async move {
+        let content = read(Path::new(&path), MAX_IMAGE_BYTES, forced_encoding(encoding)?)?;
+        crate::recent_files::record(Path::new(&path));
+        Ok(content)
+    }).await
+}
+
+/// Kind, size and line counts of a file, for paging through it
+#[tauri::command]
+pub async fn get_file_info(path: String) -> Result<FileInfo, String> {
+    crate::command_metrics::timed("get_file_info", async move {
+        tauri::async_runtime::spawn_blocking(move || {
+            let path = Path::new(&path);
+            let size = file_size(path)?;
+            let head = sniff(path)?;
+            let detected = text_encoding::detect(&head, false);
+            let kind = match image_mime(&head) {
+                Some(_) => ContentKind::Image,
+                None if detected.is_some() => ContentKind::Text,
+                None => ContentKind::Binary,
+            };
+            let encoding = detected.map(|d| d.encoding.name().to_string());
+            let file = File::open(path).map_err(read_error)?;
+            let (line_count, longest_line) = line_stats(&mut BufReader::new(file)).map_err(read_error)?;
+            Ok(FileInfo { kind, size, encoding, line_count, longest_line })
+        })
+        .await
+        .map_err(|e| format!("Reading file info failed: {}", e))?
+    }).await
+}
+
+/// A window of a file: `line_count` lines from `offset_lines`, or with
+/// `offset_bytes` set, `byte_count` bytes from there; in `encoding` if set,
+/// else in the one its start is in
+#[tauri::command]
+pub async fn read_file_range(
+    path: String,
+    offset_lines: Option<u64>,
+    line_count: Option<usize>,
+    offset_bytes: Option<u64>,
+    byte_count: Option<usize>,
+    encoding: Option<String>,
+) -> Result<FileRange, String> {
+    crate::command_metrics::timed("read_file_range", async move {
+        let forced = forced_encoding(encoding)?;
+        tauri::async_runtime::spawn_blocking(move || {
+            let path = Path::new(&path);
+            file_size(path)?;
+            let encoding = encoding_of(forced, &sniff(path)?, false).map_or(encoding_rs::UTF_8, |d| d.encoding);
+            if !encoding.is_ascii_compatible() {
+                return Err(format!("{} files cannot be read by range", encoding.name()));
+            }
+            let range = match offset_bytes {
+                Some(offset) => {
+                    let count = byte_count.unwrap_or(DEFAULT_RANGE_BYTES).min(MAX_RANGE_BYTES);
+                    read_bytes(path, offset, count, encoding)
+                }
+                None => {
+                    let count = line_count.unwrap_or(DEFAULT_RANGE_LINES).min(MAX_RANGE_LINES);
+                    read_lines(path, offset_lines.unwrap_or(0), count, MAX_LINE_BYTES, encoding)
+                }
+            };
+            range.map_err(read_error)
+        })
+        .await
+        .map_err(|e| format!("Reading file range failed: {}", e))?
+    }).await
+}
+
+/// Write `content` to a file in `encoding` (UTF-8 by default), after a byte
+/// order mark with `bom`; pass what `read_file_content` returned to keep the
+/// file's encoding
+#[tauri::command]
+pub async fn write_file_content(
+    path: String,
+    content: String,
+    encoding: Option<String>,
+    bom: Option<bool>,
+) -> Result<(), String> {
+    crate::command_metrics::timed("write_file_content", async move {
+        let encoding = forced_encoding(encoding)?.unwrap_or(encoding_rs::UTF_8);
+        let bytes = text_encoding::encode(encoding, &content, bom.unwrap_or(false))?;
+        std::fs::write(&path, bytes).map_err(|e| format!("Failed to write file: {}", e))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_binary_and_images_are_told_apart() {
+        let dir = std::env::temp_dir().join(format!("vibecode-file-content-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR".to_vec();
+        // A multi-byte character straddling the end of the sniffed bytes
+        let mut long_text = "a".repeat(SNIFF_BYTES - 1);
+        long_text.push_str("é and more");
+        let files: [(&str, &[u8]); 8] = [
+            ("main.rs", b"fn main() {}\n"),
+            ("notes.md", b"BMI notes\n"),
+            ("long.txt", long_text.as_bytes()),
+            ("logo.png", &png),
+            ("data.sqlite", b"SQLite format 3\x00\x10\x00"),
+            ("latin1.txt", b"caf\xe9\n"),
+            ("sjis.txt", b"// \x93\xfa\x96\x7b\x8c\xea\n"),
+            ("anim.webp", b"RIFF\x10\x00\x00\x00WEBPVP8 "),
+        ];
+        for (name, bytes) in files {
+            std::fs::write(dir.join(name), bytes).unwrap();
+        }
+        let read = |name: &str, max_image: u64| read(&dir.join(name), max_image, None).unwrap();
+
+        let text = read("main.rs", MAX_IMAGE_BYTES);
+        assert_eq!((text.kind, text.text.as_deref(), text.size), (ContentKind::Text, Some("fn main() {}\n"), 13));
+        assert_eq!((text.encoding.as_deref(), text.bom, text.lossy), (Some("UTF-8"), false, false));
+        assert_eq!(read("notes.md", MAX_IMAGE_BYTES).kind, ContentKind::Text);
+        assert_eq!(read("long.txt", MAX_IMAGE_BYTES).text.as_deref(), Some(long_text.as_str()));
+
+        let image = read("logo.png", MAX_IMAGE_BYTES);
+        assert_eq!((image.kind, image.mime.as_deref()), (ContentKind::Image, Some("image/png")));
+        let decoded = base64::engine::general_purpose::STANDARD.decode(image.base64.unwrap()).unwrap();
+        assert_eq!(decoded, png);
+        let too_big = read("logo.png", 4);
+        assert_eq!((too_big.kind, too_big.base64), (ContentKind::Image, None));
+        assert_eq!(read("anim.webp", MAX_IMAGE_BYTES).mime.as_deref(), Some("image/webp"));
+
+        let binary = read("data.sqlite", MAX_IMAGE_BYTES);
+        assert_eq!((binary.kind, binary.text, binary.base64), (ContentKind::Binary, None, None));
+        assert!(super::read(&dir.join("missing.txt"), MAX_IMAGE_BYTES, None).unwrap_err().starts_with("File does not exist"));
+
+        let latin1 = read("latin1.txt", MAX_IMAGE_BYTES);
+        assert_eq!((latin1.text.as_deref(), latin1.encoding.as_deref()), (Some("café\n"), Some("windows-1252")));
+        let sjis = read("sjis.txt", MAX_IMAGE_BYTES);
+        assert_eq!((sjis.text.as_deref(), sjis.encoding.as_deref()), (Some("// 日本語\n"), Some("Shift_JIS")));
+        // Forced to UTF-8, the Shift_JIS bytes are replaced
+        let forced = super::read(&dir.join("sjis.txt"), MAX_IMAGE_BYTES, Some(encoding_rs::UTF_8)).unwrap();
+        assert_eq!((forced.kind, forced.encoding.as_deref(), forced.lossy), (ContentKind::Text, Some("UTF-8"), true));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_large_files_are_paged_by_lines_and_bytes() {
+        let dir = std::env::temp_dir().join(format!("vibecode-file-range-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("app.log");
+        std::fs::write(&log, "zero\r\none\na very long line\nthree\nfour").unwrap();
+
+        let (lines, longest) = line_stats(&mut BufReader::new(File::open(&log).unwrap())).unwrap();
+        assert_eq!((lines, longest), (5, 16));
+
+        let range = read_lines(&log, 1, 2, 8, encoding_rs::UTF_8).unwrap();
+        assert_eq!(range.first_line, Some(1));
+        assert_eq!(range.lines, vec!["one", "a very l"]);
+        assert_eq!(range.cut_lines, vec![2]);
+        assert_eq!((range.byte_start, range.byte_end, range.eof), (6, 27, false));
+        let rest = read_lines(&log, 3, 10, 8, encoding_rs::UTF_8).unwrap();
+        assert_eq!((rest.lines, rest.eof), (vec!["three".to_string(), "four".to_string()], true));
+        assert!(read_lines(&log, 9, 10, 8, encoding_rs::UTF_8).unwrap().lines.is_empty());
+
+        let chunk = read_bytes(&log, 17, 10, encoding_rs::UTF_8).unwrap();
+        assert_eq!((chunk.first_line, chunk.lines), (None, vec!["long line".to_string()]));
+        assert_eq!((chunk.byte_start, chunk.byte_end, chunk.eof), (17, 27, false));
+        let tail = read_bytes(&log, 33, 100, encoding_rs::UTF_8).unwrap();
+        assert_eq!((tail.lines, tail.eof), (vec!["four".to_string()], true));
+        assert!(read_bytes(&log, 1000, 10, encoding_rs::UTF_8).unwrap().eof);
+
+        // Over the 1 MB cap: text to page through, not an error
+        let big = dir.join("big.log");
+        std::fs::write(&big, "x\n".repeat(MAX_TEXT_BYTES as usize)).unwrap();
+        let content = read(&big, MAX_IMAGE_BYTES, None).unwrap();
+        assert_eq!((content.kind, content.text), (ContentKind::Text, None));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
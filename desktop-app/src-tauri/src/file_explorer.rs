@@ -0,0 +1,421 @@
+/// File Explorer - Directory listings for the project tree
+///
+/// Listings are filtered through the shared ignore matcher (the
+/// `ignorePatterns` setting and the project's git ignore files) unless
+/// `show_ignored` is set, and sorted directories first, then
+/// case-insensitively by name. Only listings are filtered: an ignored file
+/// opened by path still reads. Huge directories (generated assets,
+/// datasets) are capped at `max_entries` per response so the IPC payload
+/// stays small; the listing reports `truncated` and `total_count`, and the
+/// rest is fetched with `list_directory_page` as the user scrolls. Metadata
+/// is only read for the entries actually returned.
+///
+/// `list_directory_tree` returns nested `children` in one call, read breadth
+/// first from a worklist (no recursion, however deep the tree) until
+/// `max_depth` levels or `max_entries` entries in all. A directory whose
+/// listing ran into `max_entries` is marked `truncated`; directories below
+/// `max_depth`, or not reached before the limit, keep `children: None` and
+/// are listed when opened.
+///
+/// Symlinks are listed with `is_symlink` and their `symlink_target`; one whose
+/// target is missing is listed as `broken` rather than failing the listing.
+/// Walks (the tree, file and content search) remember the canonical path of
+/// every directory they entered and skip one they reach again, so a symlink
+/// back up the tree or a package linked twice is not walked around forever.
+
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use serde::Serialize;
+
+use crate::command_metrics;
+use crate::ignore_rules::{self, IgnoreMatcher};
+use crate::FileEntry;
+
+/// Entries returned by `list_directory` when the caller sets no cap
+pub const DEFAULT_MAX_ENTRIES: usize = 2000;
+
+/// Most entries returned by one `list_directory_tree` call (and its default)
+const MAX_TREE_ENTRIES: usize = 5000;
+
+/// Directory listing tagged with the ignore-rules generation it was filtered with
+#[derive(Debug, Serialize)]
+pub struct DirectoryListing {
+    /// Re-fetch when an `ignore-rules-changed` event carries a newer generation
+    pub generation: u64,
+    pub entries: Vec<FileEntry>,
+    /// Position of the first entry in the full sorted listing
+    pub offset: usize,
+    /// Visible (non-ignored) entries in the directory
+    pub total_count: usize,
+    /// More entries follow; fetch them with `list_directory_page`
+    pub truncated: bool,
+}
+
+/// Directory entry before its metadata is read
+pub(crate) struct SortedEntry {
+    pub name: String,
+    pub path: PathBuf,
+    /// A directory or a symlink to one
+    pub is_dir: bool,
+    pub is_symlink: bool,
+}
+
+impl SortedEntry {
+    pub fn into_file_entry(self) -> FileEntry {
+        let extension = if self.is_dir {
+            None
+        } else {
+            self.path.extension().map(|e| e.to_string_lossy().to_string())
+        };
+        // Follows symlinks, so it fails for a broken one
+        let metadata = std::fs::metadata(&self.path).ok();
+        let symlink_target = if self.is_symlink {
+            std::fs::read_link(&self.path).ok().map(|target| target.to_string_lossy().to_string())
+        } else {
+            None
+        };
+
+        FileEntry {
+            name: self.name,
+            path: self.path.to_string_lossy().to_string(),
+            is_dir: self.is_dir,
+            extension,
+            size: metadata.as_ref().map(|m| m.len()),
+            children: None,
+            truncated: false,
+            is_symlink: self.is_symlink,
+            symlink_target,
+            broken: self.is_symlink && metadata.is_none(),
+        }
+    }
+}
+
+/// Canonical paths of the directories a walk entered
+#[derive(Default)]
+pub(crate) struct Visited(HashSet<PathBuf>);
+
+impl Visited {
+    /// Whether `dir` was not entered yet (through any path); records it
+    pub fn first_visit(&mut self, dir: &Path) -> bool {
+        match dir.canonicalize() {
+            Ok(canonical) => self.0.insert(canonical),
+            // Cannot be read either; let the caller's read_dir fail
+            Err(_) => true,
+        }
+    }
+}
+
+/// Read one directory level, minus ignored entries; directories first, then alphabetically
+pub(crate) fn read_sorted(dir_path: &Path, matcher: &IgnoreMatcher) -> Result<Vec<SortedEntry>, String> {
+    let read_dir = std::fs::read_dir(dir_path)
+        .map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    let mut entries: Vec<SortedEntry> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            // file_type() needs no extra stat; only symlinks are followed
+            let (is_dir, is_symlink) = match entry.file_type() {
+                Ok(t) if t.is_symlink() => (path.is_dir(), true),
+                Ok(t) => (t.is_dir(), false),
+                Err(_) => (path.is_dir(), false),
+            };
+            if matcher.is_ignored(&path, is_dir) {
+                return None;
+            }
+            Some(SortedEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path,
+                is_dir,
+                is_symlink,
+            })
+        })
+        .collect();
+
+    // Lowercased once per entry instead of once per comparison
+    entries.sort_by_cached_key(|e| (!e.is_dir, e.name.to_lowercase()));
+
+    Ok(entries)
+}
+
+/// Up to `limit` entries starting at `offset` of the sorted listing, plus the total count
+fn read_page(dir_path: &Path, matcher: &IgnoreMatcher, offset: usize, limit: usize) -> Result<(Vec<FileEntry>, usize), String> {
+    let entries = read_sorted(dir_path, matcher)?;
+    let total = entries.len();
+    let page = entries
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .map(SortedEntry::into_file_entry)
+        .collect();
+    Ok((page, total))
+}
+
+/// `entries` with the `children` of their directories filled down to
+/// `depth` more levels, reading at most `budget` entries; directories in
+/// `visited` are left unfilled
+fn fill_tree(
+    entries: Vec<FileEntry>,
+    depth: u32,
+    mut budget: usize,
+    matcher: &IgnoreMatcher,
+    visited: &mut Visited,
+) -> Vec<FileEntry> {
+    // Every entry read, with the position of its parent; nested at the end
+    let mut nodes: Vec<(FileEntry, Option<usize>)> = entries.into_iter().map(|entry| (entry, None)).collect();
+    let mut worklist: VecDeque<(usize, u32)> = VecDeque::new();
+    if depth > 0 {
+        worklist.extend((0..nodes.len()).filter(|&i| nodes[i].0.is_dir).map(|i| (i, depth)));
+    }
+
+    while let Some((parent, depth)) = worklist.pop_front() {
+        if budget == 0 {
+            break;
+        }
+        if !visited.first_visit(Path::new(&nodes[parent].0.path)) {
+            continue;
+        }
+        let Ok((children, total)) = read_page(Path::new(&nodes[parent].0.path), matcher, 0, budget) else { continue };
+        budget -= children.len();
+        nodes[parent].0.truncated = children.len() < total;
+        nodes[parent].0.children = Some(Vec::new());
+        for child in children {
+            if child.is_dir && depth > 1 {
+                worklist.push_back((nodes.len(), depth - 1));
+            }
+            nodes.push((child, Some(parent)));
+        }
+    }
+
+    // Children come after their parent, so walking backwards moves each
+    // entry into its parent after its own children were moved into it
+    let mut children: Vec<Vec<FileEntry>> = vec![Vec::new(); nodes.len()];
+    let mut roots = Vec::new();
+    for (i, (mut entry, parent)) in nodes.into_iter().enumerate().rev() {
+        if entry.children.is_some() {
+            let mut own = std::mem::take(&mut children[i]);
+            own.reverse();
+            entry.children = Some(own);
+        }
+        match parent {
+            Some(parent) => children[parent].push(entry),
+            None => roots.push(entry),
+        }
+    }
+    roots.reverse();
+    roots
+}
+
+/// Matcher for listing `dir`: the project's rules, or none with `show_ignored`
+fn listing_matcher(dir: &Path, show_ignored: Option<bool>) -> Arc<IgnoreMatcher> {
+    if show_ignored.unwrap_or(false) {
+        ignore_rules::nothing_ignored(dir)
+    } else {
+        ignore_rules::current_matcher(dir)
+    }
+}
+
+pub(crate) fn check_directory(path: &str) -> Result<PathBuf, String> {
+    let dir_path = PathBuf::from(path);
+
+    if !dir_path.exists() {
+        return Err(format!("Path does not exist: {}", path));
+    }
+
+    if !dir_path.is_dir() {
+        return Err(format!("Path is not a directory: {}", path));
+    }
+
+    Ok(dir_path)
+}
+
+fn listing(dir_path: &Path, matcher: &IgnoreMatcher, offset: usize, limit: usize) -> Result<DirectoryListing, String> {
+    let (entries, total_count) = read_page(dir_path, matcher, offset, limit)?;
+
+    Ok(DirectoryListing {
+        generation: matcher.generation(),
+        truncated: offset + entries.len() < total_count,
+        offset,
+        total_count,
+        entries,
+    })
+}
+
+/// Listing of `dir_path` with `max_depth` levels of entries, `max_entries` in all
+fn tree(dir_path: &Path, matcher: &IgnoreMatcher, max_depth: u32, max_entries: usize) -> Result<DirectoryListing, String> {
+    let mut listing = listing(dir_path, matcher, 0, max_entries)?;
+    let budget = max_entries - listing.entries.len();
+    let mut visited = Visited::default();
+    visited.first_visit(dir_path);
+    listing.entries = fill_tree(listing.entries, max_depth.saturating_sub(1), budget, matcher, &mut visited);
+    Ok(listing)
+}
+
+/// List directory contents for file explorer (first `max_entries`, default 2,000)
+#[tauri::command]
+pub async fn list_directory(path: String, max_entries: Option<usize>, show_ignored: Option<bool>) -> Result<DirectoryListing, String> {
+    command_metrics::timed("list_directory", async move {
+        let dir_path = check_directory(&path)?;
+        let matcher = listing_matcher(&dir_path, show_ignored);
+        listing(&dir_path, &matcher, 0, max_entries.unwrap_or(DEFAULT_MAX_ENTRIES))
+    }).await
+}
+
+/// Fetch `limit` more entries of a truncated listing, starting at `offset`
+#[tauri::command]
+pub async fn list_directory_page(
+    path: String,
+    offset: usize,
+    limit: usize,
+    show_ignored: Option<bool>,
+) -> Result<DirectoryListing, String> {
+    command_metrics::timed("list_directory_page", async move {
+        let dir_path = check_directory(&path)?;
+        let matcher = listing_matcher(&dir_path, show_ignored);
+        listing(&dir_path, &matcher, offset, limit)
+    }).await
+}
+
+/// List a directory with nested children down to `max_depth` levels (default
+/// 3), `max_entries` entries in all (default and at most 5,000)
+#[tauri::command]
+pub async fn list_directory_tree(
+    path: String,
+    max_depth: Option<u32>,
+    max_entries: Option<usize>,
+    show_ignored: Option<bool>,
+) -> Result<DirectoryListing, String> {
+    command_metrics::timed("list_directory_tree", async move {
+        let dir_path = check_directory(&path)?;
+        let matcher = listing_matcher(&dir_path, show_ignored);
+        let max_entries = max_entries.unwrap_or(MAX_TREE_ENTRIES).min(MAX_TREE_ENTRIES);
+        tree(&dir_path, &matcher, max_depth.unwrap_or(3), max_entries)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Generous enough for debug builds on slow CI disks
+    const LATENCY_BUDGET: Duration = Duration::from_secs(3);
+
+    #[tokio::test]
+    #[ignore = "writes 50,000 files; run with --ignored"]
+    async fn test_huge_directory_is_capped_paged_and_fast() {
+        let dir = std::env::temp_dir().join(format!("vibecode-explorer-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("Zeta")).unwrap();
+        std::fs::create_dir_all(dir.join("alpha")).unwrap();
+        for i in 0..50_000 {
+            std::fs::write(dir.join(format!("asset-{:05}.bin", 49_999 - i)), b"").unwrap();
+        }
+        let path = dir.to_string_lossy().to_string();
+
+        let started = Instant::now();
+        let first = list_directory(path.clone(), None, None).await.unwrap();
+        let elapsed = started.elapsed();
+        assert!(elapsed < LATENCY_BUDGET, "list_directory took {:?}", elapsed);
+
+        assert_eq!(first.total_count, 50_002);
+        assert_eq!(first.entries.len(), DEFAULT_MAX_ENTRIES);
+        assert!(first.truncated);
+        let names: Vec<&str> = first.entries.iter().take(3).map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "Zeta", "asset-00000.bin"]);
+
+        let last = list_directory_page(path, 49_000, 5_000, None).await.unwrap();
+        assert_eq!(last.offset, 49_000);
+        assert_eq!(last.entries.len(), 1_002);
+        assert!(!last.truncated);
+        assert_eq!(last.entries.last().map(|e| e.name.as_str()), Some("asset-49999.bin"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_listing_is_capped_and_paged() {
+        let dir = std::env::temp_dir().join(format!("vibecode-explorer-page-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("Zeta")).unwrap();
+        std::fs::create_dir_all(dir.join("alpha")).unwrap();
+        std::fs::create_dir_all(dir.join("build")).unwrap();
+        for i in 0..25 {
+            std::fs::write(dir.join(format!("asset-{:02}.bin", 24 - i)), b"").unwrap();
+        }
+        std::fs::write(dir.join("debug.log"), b"").unwrap();
+        let matcher = ignore_rules::with_patterns(&dir, &["build", "*.log"]);
+
+        let first = listing(&dir, &matcher, 0, 10).unwrap();
+        assert_eq!((first.total_count, first.entries.len(), first.truncated), (27, 10, true));
+        let names: Vec<&str> = first.entries.iter().take(3).map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "Zeta", "asset-00.bin"]);
+
+        let last = listing(&dir, &matcher, 20, 10).unwrap();
+        assert_eq!((last.offset, last.entries.len(), last.truncated), (20, 7, false));
+        assert_eq!(last.entries.last().map(|e| e.name.as_str()), Some("asset-24.bin"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_tree_stops_at_depth_and_entry_limits() {
+        let dir = std::env::temp_dir().join(format!("vibecode-explorer-tree-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src").join("deep").join("deeper")).unwrap();
+        std::fs::create_dir_all(dir.join("node_modules").join("pkg")).unwrap();
+        std::fs::create_dir_all(dir.join("docs")).unwrap();
+        for file in ["src/main.rs", "src/deep/mod.rs", "docs/a.md", "docs/b.md", "docs/c.md", "README.md"] {
+            std::fs::write(dir.join(file), b"").unwrap();
+        }
+        let matcher = ignore_rules::with_patterns(&dir, &["node_modules"]);
+
+        let listing = tree(&dir, &matcher, 2, 100).unwrap();
+        let names: Vec<&str> = listing.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["docs", "src", "README.md"]);
+        let src = &listing.entries[1];
+        let children: Vec<&str> = src.children.iter().flatten().map(|e| e.name.as_str()).collect();
+        assert_eq!(children, vec!["deep", "main.rs"]);
+        assert!(src.children.as_ref().unwrap()[0].children.is_none());
+        assert!(listing.entries[2].children.is_none());
+
+        // Breadth first: docs takes what is left after the top level
+        let listing = tree(&dir, &matcher, 3, 5).unwrap();
+        let docs = &listing.entries[0];
+        assert_eq!(docs.children.as_ref().map(Vec::len), Some(2));
+        assert!(docs.truncated);
+        assert!(listing.entries[1].children.is_none() && !listing.entries[1].truncated);
+        assert!(!listing.truncated);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // Creating symlinks on Windows needs privileges
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_cycles_are_cut_and_broken_links_listed() {
+        let dir = std::env::temp_dir().join(format!("vibecode-explorer-links-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("pkg")).unwrap();
+        std::fs::write(dir.join("pkg").join("index.js"), b"").unwrap();
+        std::os::unix::fs::symlink(".", dir.join("pkg").join("self")).unwrap();
+        std::os::unix::fs::symlink("pkg", dir.join("linked")).unwrap();
+        std::os::unix::fs::symlink("missing.txt", dir.join("dangling")).unwrap();
+        let matcher = ignore_rules::current_matcher(&dir);
+
+        let listing = tree(&dir, &matcher, 10, 100).unwrap();
+        let entries: Vec<(&str, bool, bool, bool)> =
+            listing.entries.iter().map(|e| (e.name.as_str(), e.is_dir, e.is_symlink, e.broken)).collect();
+        assert_eq!(
+            entries,
+            vec![("linked", true, true, false), ("pkg", true, false, false), ("dangling", false, true, true)]
+        );
+        assert_eq!(listing.entries[2].symlink_target.as_deref(), Some("missing.txt"));
+        // pkg is walked once, through whichever path reached it first
+        let filled: Vec<&str> =
+            listing.entries.iter().filter(|e| e.children.is_some()).map(|e| e.name.as_str()).collect();
+        assert_eq!(filled, vec!["linked"]);
+        let linked = listing.entries[0].children.as_ref().unwrap();
+        let self_link = linked.iter().find(|e| e.name == "self").unwrap();
+        assert!(self_link.is_symlink && self_link.children.is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,229 @@
+/// File Search - Find project files by name
+///
+/// `search_files` walks the folder breadth first, skipping what the shared
+/// ignore matcher ignores (ignored folders are not entered), and matches file
+/// names against the query without regard to case: exact names first, then
+/// names starting with the query, names containing it, and last names that
+/// hold its characters in order (`fexp` finds `FileExplorer.tsx`). Every
+/// result carries the character positions of its name that matched, for
+/// highlighting. The walk stops once `max_results` files matched. Each call
+/// starts a new search generation; a search still walking when a newer one
+/// starts gives up and returns what it found with `cancelled` set, so every
+/// keystroke only costs one walk.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use serde::Serialize;
+
+use crate::command_metrics;
+use crate::file_explorer;
+use crate::ignore_rules::{self, IgnoreMatcher};
+use crate::FileEntry;
+
+/// Results returned when the caller sets no cap
+const DEFAULT_MAX_RESULTS: usize = 200;
+
+/// Folders visited by one search at most
+const MAX_SEARCH_DIRS: usize = 50_000;
+
+/// Generation of the newest search
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// How a name matched, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    Exact,
+    Prefix,
+    Substring,
+    Fuzzy,
+}
+
+/// One file found by `search_files`
+#[derive(Debug, Clone, Serialize)]
+pub struct FileMatch {
+    #[serde(flatten)]
+    pub entry: FileEntry,
+    /// Path below the searched folder, with `/` separators
+    pub relative_path: String,
+    pub kind: MatchKind,
+    /// Positions (in characters) of `name` that matched the query
+    pub positions: Vec<usize>,
+}
+
+/// Result of `search_files`
+#[derive(Debug, Serialize)]
+pub struct FileSearchResults {
+    /// Drop results of an older generation than the last one received
+    pub generation: u64,
+    /// Best matches first
+    pub results: Vec<FileMatch>,
+    /// Stopped at `max_results`; more files may match
+    pub truncated: bool,
+    /// A newer search started before this one finished
+    pub cancelled: bool,
+}
+
+/// Lowercased characters, one per character of `text`, so positions line up
+fn folded(text: &str) -> Vec<char> {
+    text.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+}
+
+/// How `name` matches `query` (both folded), with the matched positions and
+/// how many characters come before and between them
+fn match_name(name: &[char], query: &[char]) -> Option<(MatchKind, Vec<usize>, usize)> {
+    if query.is_empty() || query.len() > name.len() {
+        return None;
+    }
+    if let Some(start) = name.windows(query.len()).position(|window| window == query) {
+        let kind = match (start, name.len() == query.len()) {
+            (0, true) => MatchKind::Exact,
+            (0, false) => MatchKind::Prefix,
+            _ => MatchKind::Substring,
+        };
+        return Some((kind, (start..start + query.len()).collect(), start));
+    }
+
+    let mut positions = Vec::with_capacity(query.len());
+    let mut from = 0;
+    for c in query {
+        let found = from + name[from..].iter().position(|n| n == c)?;
+        positions.push(found);
+        from = found + 1;
+    }
+    let gaps = positions.last().map_or(0, |last| last + 1 - positions.len());
+    Some((MatchKind::Fuzzy, positions, gaps))
+}
+
+/// Best first: by kind, then fewer unmatched characters, shorter names and paths
+fn rank(matches: &mut [(FileMatch, usize)]) {
+    matches.sort_by(|(a, a_gaps), (b, b_gaps)| {
+        (a.kind, a_gaps, a.entry.name.len(), a.relative_path.len(), &a.relative_path)
+            .cmp(&(b.kind, b_gaps, b.entry.name.len(), b.relative_path.len(), &b.relative_path))
+    });
+}
+
+/// Files under `root` whose names match `query`, until `max_results` did or
+/// `is_current` turns false
+fn search(
+    root: &Path,
+    matcher: &IgnoreMatcher,
+    query: &str,
+    max_results: usize,
+    is_current: &dyn Fn() -> bool,
+) -> (Vec<FileMatch>, bool, bool) {
+    let query: Vec<char> = folded(query).into_iter().filter(|c| !c.is_whitespace()).collect();
+    let mut found: Vec<(FileMatch, usize)> = Vec::new();
+    let mut worklist = VecDeque::from([root.to_path_buf()]);
+    let mut visited = 0;
+    // Directories reached again through a symlink are skipped
+    let mut seen = file_explorer::Visited::default();
+    let (mut truncated, mut cancelled) = (false, false);
+
+    'walk: while let Some(dir) = worklist.pop_front() {
+        if !is_current() {
+            cancelled = true;
+            break;
+        }
+        if !seen.first_visit(&dir) {
+            continue;
+        }
+        visited += 1;
+        if visited > MAX_SEARCH_DIRS {
+            eprintln!("File search: stopped in {} after {} folders", root.display(), MAX_SEARCH_DIRS);
+            break;
+        }
+        let Ok(entries) = file_explorer::read_sorted(&dir, matcher) else { continue };
+        for entry in entries {
+            if entry.is_dir {
+                worklist.push_back(entry.path);
+                continue;
+            }
+            let Some((kind, positions, gaps)) = match_name(&folded(&entry.name), &query) else { continue };
+            if found.len() == max_results {
+                truncated = true;
+                break 'walk;
+            }
+            let relative_path = entry
+                .path
+                .strip_prefix(root)
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+            found.push((FileMatch { entry: entry.into_file_entry(), relative_path, kind, positions }, gaps));
+        }
+    }
+
+    rank(&mut found);
+    (found.into_iter().map(|(m, _)| m).collect(), truncated, cancelled)
+}
+
+/// Find files under `root` by name (see the module docs)
+#[tauri::command]
+pub async fn search_files(root: String, query: String, max_results: Option<usize>) -> Result<FileSearchResults, String> {
+    command_metrics::timed("search_files", async move {
+        let dir = file_explorer::check_directory(&root)?;
+        let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+        let max_results = max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+
+        tauri::async_runtime::spawn_blocking(move || {
+            let matcher = ignore_rules::current_matcher(&dir);
+            let is_current = || GENERATION.load(Ordering::SeqCst) == generation;
+            let (results, truncated, cancelled) = search(&dir, &matcher, &query, max_results, &is_current);
+            FileSearchResults { generation, results, truncated, cancelled }
+        })
+        .await
+        .map_err(|e| format!("File search failed: {}", e))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_kinds_and_positions() {
+        let m = |name: &str, query: &str| match_name(&folded(name), &folded(query));
+        assert_eq!(m("main.rs", "MAIN.RS"), Some((MatchKind::Exact, (0..7).collect(), 0)));
+        assert_eq!(m("Main.rs", "main"), Some((MatchKind::Prefix, vec![0, 1, 2, 3], 0)));
+        assert_eq!(m("domain.rs", "main"), Some((MatchKind::Substring, vec![2, 3, 4, 5], 2)));
+        assert_eq!(m("FileExplorer.tsx", "fexp"), Some((MatchKind::Fuzzy, vec![0, 3, 5, 6], 3)));
+        assert_eq!(m("lib.rs", "rsl"), None);
+        assert_eq!(m("a.rs", "a.rs.bak"), None);
+        assert_eq!(m("a.rs", ""), None);
+    }
+
+    #[test]
+    fn test_search_ranks_respects_ignores_and_stops() {
+        let root = std::env::temp_dir().join(format!("vibecode-file-search-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src").join("config")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules").join("pkg")).unwrap();
+        for file in [
+            "src/config/settings.rs",
+            "src/settings_panel.tsx",
+            "src/main.rs",
+            "settings.rs",
+            "node_modules/pkg/settings.js",
+            "setup-testing.sh",
+        ] {
+            std::fs::write(root.join(file), b"").unwrap();
+        }
+        let matcher = ignore_rules::current_matcher(&root);
+        let always = || true;
+
+        let (results, truncated, cancelled) = search(&root, &matcher, "settings", 10, &always);
+        let paths: Vec<&str> = results.iter().map(|r| r.relative_path.as_str()).collect();
+        assert_eq!(paths, vec!["settings.rs", "src/config/settings.rs", "src/settings_panel.tsx", "setup-testing.sh"]);
+        assert_eq!(results[3].kind, MatchKind::Fuzzy);
+        assert!(!truncated && !cancelled);
+
+        let (results, truncated, _) = search(&root, &matcher, "settings", 2, &always);
+        assert_eq!(results.len(), 2);
+        assert!(truncated);
+
+        let (results, _, cancelled) = search(&root, &matcher, "settings", 10, &|| false);
+        assert!(results.is_empty() && cancelled);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,302 @@
+/// FS Watcher - Pushes changes to the open project's files to the UI
+///
+/// A recursive `notify` watcher on `CURRENT_PROJECT` collects file events
+/// until none has arrived for 300 ms, drops paths the shared ignore matcher
+/// ignores (and anything inside `.git`), and emits `fs-changed` with the
+/// paths created, modified and deleted in that burst; a burst touching a
+/// `.gitignore` first rebuilds that matcher. A burst touching more
+/// than 1,000 paths is sent as `rescan: true` with no paths instead. `sync`
+/// runs after every project switch: it drops the watcher of the previous
+/// project, which ends its thread, and watches the new one. While
+/// `pause_fs_watcher` is in effect events are dropped; `resume_fs_watcher`
+/// then emits one `rescan: true` so the UI catches up on what it missed.
+/// A burst that changes project files or git's own state (HEAD, index,
+/// refs: commits, checkouts, fetches) also refreshes `git-status-updated`,
+/// and its paths, all of them and paused or not, go to `task_changes` for
+/// the tasks running in the project. Unless paused, a burst that leaves
+/// conflict markers in tracked files also emits `conflicts-detected` (see
+/// `merge_conflicts`).
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use notify::event::{EventKind, ModifyKind};
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::ignore_rules::{self, IgnoreMatcher};
+
+pub const FS_CHANGED_EVENT: &str = "fs-changed";
+
+/// Quiet period that ends a burst of events (tools write in several steps)
+pub(crate) const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Larger bursts are reported as a rescan
+const MAX_PATHS: usize = 1000;
+
+/// Payload of `fs-changed`: absolute paths inside `root`
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct FsChanged {
+    pub root: String,
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+    /// Too much changed (or events were paused) to list: re-read everything
+    pub rescan: bool,
+}
+
+impl FsChanged {
+    fn is_empty(&self) -> bool {
+        !self.rescan && self.created.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+}
+
+/// The watcher in use and the project it watches
+struct Active {
+    root: PathBuf,
+    _watcher: notify::RecommendedWatcher,
+}
+
+static APP: OnceLock<tauri::AppHandle> = OnceLock::new();
+static ACTIVE: Mutex<Option<Active>> = Mutex::new(None);
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+fn is_watched(root: &Path) -> bool {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner()).as_ref().is_some_and(|a| a.root == root)
+}
+
+fn emit(change: FsChanged) {
+    if let Some(app) = APP.get() {
+        let _ = app.emit(FS_CHANGED_EVENT, change);
+    }
+}
+
+/// What a burst changed: `touched` paths, each with whether it was created
+/// (or renamed into place) during the burst; whether a path still exists
+/// decides between the lists
+fn classify(root: &Path, touched: &BTreeMap<PathBuf, bool>, matcher: &IgnoreMatcher) -> FsChanged {
+    let mut change = FsChanged { root: root.to_string_lossy().to_string(), ..Default::default() };
+    let in_git = |path: &Path| {
+        path.strip_prefix(root)
+            .is_ok_and(|rel| rel.components().any(|c| c == Component::Normal(".git".as_ref())))
+    };
+    for (path, created) in touched {
+        if in_git(path) || matcher.is_ignored(path, path.is_dir()) {
+            continue;
+        }
+        let list = match (path.exists(), created) {
+            (true, true) => &mut change.created,
+            (true, false) => &mut change.modified,
+            // Created and removed again within the burst
+            (false, true) => continue,
+            (false, false) => &mut change.deleted,
+        };
+        list.push(path.to_string_lossy().to_string());
+    }
+    change
+}
+
+/// `change` as emitted: a rescan when it lists too many paths
+fn cap(change: FsChanged) -> FsChanged {
+    if change.created.len() + change.modified.len() + change.deleted.len() > MAX_PATHS {
+        return FsChanged { root: change.root, rescan: true, ..Default::default() };
+    }
+    change
+}
+
+/// Whether `path` is part of the state `git-status-updated` reports: HEAD,
+/// the index and refs in `root`'s `.git`
+fn touches_git_state(root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else { return false };
+    let mut parts = relative.components();
+    parts.next() == Some(Component::Normal(".git".as_ref()))
+        && parts.next().is_some_and(|part| {
+            ["HEAD", "index", "refs", "packed-refs", "FETCH_HEAD"].iter().any(|name| part == Component::Normal(name.as_ref()))
+        })
+}
+
+/// Start watching `root` recursively
+fn start(root: &Path) -> notify::Result<notify::RecommendedWatcher> {
+    let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })?;
+    watcher.watch(root, RecursiveMode::Recursive)?;
+
+    // Ends when the watcher is dropped, which closes the channel
+    let root = root.to_path_buf();
+    std::thread::spawn(move || {
+        while let Ok(first) = rx.recv() {
+            let mut touched: BTreeMap<PathBuf, bool> = BTreeMap::new();
+            let mut note = |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else { return };
+                if matches!(event.kind, EventKind::Access(_) | EventKind::Modify(ModifyKind::Metadata(_))) {
+                    return;
+                }
+                // A rename creates the path it moved to
+                let renamed = matches!(event.kind, EventKind::Modify(ModifyKind::Name(_)));
+                for path in event.paths {
+                    let created = matches!(event.kind, EventKind::Create(_)) || (renamed && path.exists());
+                    *touched.entry(path).or_default() |= created;
+                }
+            };
+            note(first);
+            while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+                note(event);
+            }
+
+            // The project may have been switched while the burst settled
+            if !is_watched(&root) {
+                continue;
+            }
+            ignore_rules::paths_changed(&root, &touched);
+            let change = classify(&root, &touched, &ignore_rules::current_matcher(&root));
+            crate::task_changes::observe(&root, &change);
+            if PAUSED.load(Ordering::SeqCst) {
+                continue;
+            }
+            let change = cap(change);
+            let git_state = touched.keys().any(|path| touches_git_state(&root, path));
+            if git_state || !change.is_empty() {
+                if let Some(app) = APP.get() {
+                    crate::git_repository::refresh_in_background(app);
+                }
+            }
+            if let (Some(conflicts), Some(app)) = (crate::merge_conflicts::detect(&root, &change), APP.get()) {
+                let _ = app.emit(crate::merge_conflicts::CONFLICTS_DETECTED_EVENT, conflicts);
+            }
+            if !change.is_empty() {
+                emit(change);
+            }
+        }
+    });
+    Ok(watcher)
+}
+
+/// Watch the current project, replacing the watcher of the previous one
+pub fn sync() {
+    let project = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()).map(PathBuf::from);
+    let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+    if active.as_ref().map(|a| &a.root) == project.as_ref() {
+        return;
+    }
+    *active = None;
+
+    if let Some(root) = project {
+        match start(&root) {
+            Ok(watcher) => *active = Some(Active { root, _watcher: watcher }),
+            Err(e) => eprintln!("FS watcher: failed to watch {}: {}", root.display(), e),
+        }
+    }
+}
+
+/// Start the project watcher (called once at startup)
+pub fn spawn(app: tauri::AppHandle) {
+    let _ = APP.set(app);
+    std::thread::spawn(sync);
+}
+
+/// Stop emitting `fs-changed` (during bulk operations)
+#[tauri::command]
+pub async fn pause_fs_watcher() -> Result<(), String> {
+    crate::command_metrics::timed("pause_fs_watcher", async move {
+        PAUSED.store(true, Ordering::SeqCst);
+        Ok(())
+    }).await
+}
+
+/// Emit `fs-changed` again, starting with a rescan for what was missed
+#[tauri::command]
+pub async fn resume_fs_watcher() -> Result<(), String> {
+    crate::command_metrics::timed("resume_fs_watcher", async move {
+        if PAUSED.swap(false, Ordering::SeqCst) {
+            let root = ACTIVE.lock().unwrap_or_else(|e| e.into_inner()).as_ref().map(|a| a.root.clone());
+            if let Some(root) = root {
+                emit(FsChanged { root: root.to_string_lossy().to_string(), rescan: true, ..Default::default() });
+            }
+        }
+        Ok(())
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bursts_are_sorted_into_created_modified_and_deleted() {
+        let root = std::env::temp_dir().join(format!("vibecode-fs-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules")).unwrap();
+        for file in ["src/new.rs", "src/main.rs", ".git/index", "node_modules/dep.js"] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+        let matcher = ignore_rules::current_matcher(&root);
+        let touched: BTreeMap<PathBuf, bool> = [
+            ("src/new.rs", true),
+            ("src/main.rs", false),
+            ("src/old.rs", false),
+            ("src/scratch.tmp", true),
+            (".git/index", false),
+            ("node_modules/dep.js", true),
+        ]
+        .into_iter()
+        .map(|(path, created)| (root.join(path), created))
+        .collect();
+
+        let change = classify(&root, &touched, &matcher);
+        let path = |rel: &str| root.join(rel).to_string_lossy().to_string();
+        assert_eq!(change.created, vec![path("src/new.rs")]);
+        assert_eq!(change.modified, vec![path("src/main.rs")]);
+        assert_eq!(change.deleted, vec![path("src/old.rs")]);
+        assert!(!change.rescan);
+        assert!(touches_git_state(&root, &root.join(".git/refs/heads/main")));
+        assert!(touches_git_state(&root, &root.join(".git/HEAD")));
+        assert!(!touches_git_state(&root, &root.join(".git/objects/ab/cdef")));
+        assert!(!touches_git_state(&root, &root.join(".git/index.lock")));
+        assert!(!touches_git_state(&root, &root.join("src/main.rs")));
+
+        let many: BTreeMap<PathBuf, bool> = (0..=MAX_PATHS).map(|i| (root.join(format!("gone-{}.txt", i)), false)).collect();
+        assert_eq!(classify(&root, &many, &matcher).deleted.len(), MAX_PATHS + 1);
+        let change = cap(classify(&root, &many, &matcher));
+        assert!(change.rescan && change.deleted.is_empty());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_saved_ignore_patterns_stop_matching_paths_being_reported() {
+        let root = std::env::temp_dir().join(format!("vibecode-fs-watch-settings-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        for file in ["build.log", "main.rs"] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+        let touched: BTreeMap<PathBuf, bool> =
+            [(root.join("build.log"), true), (root.join("main.rs"), false)].into_iter().collect();
+        let path = |rel: &str| root.join(rel).to_string_lossy().to_string();
+        let change = classify(&root, &touched, &ignore_rules::current_matcher(&root));
+        assert_eq!((change.created, change.modified), (vec![path("build.log")], vec![path("main.rs")]));
+
+        // What saving the settings page does, and what the ignore rules' listener does with it
+        let old = crate::settings::AppSettings::default();
+        let mut new = old.clone();
+        new.ignore_patterns.push("*.log".to_string());
+        let mut changes = crate::settings_events::subscribe();
+        crate::settings_events::publish(&old, &new);
+        let saved = std::iter::from_fn(|| changes.try_recv().ok())
+            .find(|change| change.touches("/ignorePatterns") && change.settings.ignore_patterns == new.ignore_patterns)
+            .unwrap();
+        ignore_rules::settings_changed(&saved);
+
+        let change = classify(&root, &touched, &ignore_rules::current_matcher(&root));
+        assert!(change.created.is_empty());
+        assert_eq!(change.modified, vec![path("main.rs")]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,332 @@
+/// Gemini Budget - Keeps Skill Factory prompts inside the model's input limit
+///
+/// The composed prompt is sized with a cheap chars/4 estimate and, when
+/// enabled, Gemini's `countTokens` endpoint. A `context` that does not fit is
+/// truncated with a visible marker, or first compressed by a summarization
+/// call when `geminiBudget.summarizeOverflow` is set. What happened is
+/// reported back in `InputBudgetReport`. "Request too large" responses from
+/// the API become `GeminiError::InputTooLarge` with the measured size and the
+/// limit, so the UI can say how much to trim.
+
+use std::fmt;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Model used by the Skill Factory
+pub const GEMINI_MODEL_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models/gemini-1.5-flash";
+
+/// Model id at the end of `GEMINI_MODEL_URL` (recorded in generation.json)
+pub fn model_name() -> &'static str {
+    GEMINI_MODEL_URL.rsplit('/').next().unwrap_or(GEMINI_MODEL_URL)
+}
+
+/// Tokens kept free for the truncation marker and estimate error
+const MARKER_RESERVE_TOKENS: u64 = 32;
+
+/// `geminiBudget` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GeminiBudgetSettings {
+    /// Largest prompt sent to the model, in tokens
+    pub max_input_tokens: u64,
+    /// Ask `countTokens` for the real size after the estimate
+    pub count_tokens: bool,
+    /// Summarize an oversized context instead of cutting it off
+    pub summarize_overflow: bool,
+    /// Largest context fed into the summarization pass, in tokens
+    pub max_summary_input_tokens: u64,
+}
+
+impl Default for GeminiBudgetSettings {
+    fn default() -> Self {
+        Self {
+            max_input_tokens: 32_000,
+            count_tokens: true,
+            summarize_overflow: false,
+            max_summary_input_tokens: 200_000,
+        }
+    }
+}
+
+impl GeminiBudgetSettings {
+    /// Load the geminiBudget block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("geminiBudget").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Errors from the Skill Factory's Gemini calls
+#[derive(Debug)]
+pub enum GeminiError {
+    /// The prompt is over the model's (or the configured) input limit
+    InputTooLarge { measured_tokens: u64, limit_tokens: u64 },
+    Other(String),
+}
+
+impl GeminiError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::InputTooLarge { .. } => "input_too_large",
+            Self::Other(_) => "other",
+        }
+    }
+}
+
+impl fmt::Display for GeminiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InputTooLarge { measured_tokens, limit_tokens } => write!(
+                f,
+                "Nội dung gửi Gemini quá lớn: ~{} tokens, giới hạn {} tokens. Hãy rút gọn phần ngữ cảnh bổ sung.",
+                measured_tokens, limit_tokens
+            ),
+            Self::Other(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<String> for GeminiError {
+    fn from(msg: String) -> Self {
+        Self::Other(msg)
+    }
+}
+
+impl From<&str> for GeminiError {
+    fn from(msg: &str) -> Self {
+        Self::Other(msg.to_string())
+    }
+}
+
+/// Serialized as `{ kind, message, measured_tokens?, limit_tokens? }`
+impl Serialize for GeminiError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("GeminiError", 4)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        if let Self::InputTooLarge { measured_tokens, limit_tokens } = self {
+            state.serialize_field("measured_tokens", measured_tokens)?;
+            state.serialize_field("limit_tokens", limit_tokens)?;
+        }
+        state.end()
+    }
+}
+
+/// How the context was fitted into the prompt
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputBudgetReport {
+    pub limit_tokens: u64,
+    /// chars/4 estimate of the final prompt
+    pub estimated_tokens: u64,
+    /// `countTokens` result for the final prompt, when it was asked
+    pub counted_tokens: Option<u64>,
+    pub original_context_tokens: u64,
+    pub context_truncated: bool,
+    pub context_summarized: bool,
+}
+
+/// Cheap token estimate: one token per four characters, rounded up
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// Cut `text` to about `max_tokens`, ending with a marker saying how much was dropped
+pub fn truncate_context(text: &str, max_tokens: u64) -> String {
+    let total = text.chars().count();
+    let keep = (max_tokens.saturating_sub(MARKER_RESERVE_TOKENS) * 4) as usize;
+    if total <= keep {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(keep).collect();
+    format!("{}\n\n[… đã cắt bớt ngữ cảnh: giữ {} / {} ký tự …]", kept.trim_end(), keep, total)
+}
+
+/// Map a failed generateContent response to a typed error
+pub fn map_api_error(status: u16, body: &str, measured_tokens: u64, limit_tokens: u64) -> GeminiError {
+    let lower = body.to_lowercase();
+    let too_large = status == 413
+        || lower.contains("exceeds the maximum number of tokens")
+        || lower.contains("request payload size exceeds")
+        || lower.contains("too large");
+    if !too_large {
+        return GeminiError::Other(format!("Gemini API error: {}", body));
+    }
+
+    // "The input token count (123) exceeds the maximum number of tokens allowed (456)."
+    let number_after = |marker: &str| -> Option<u64> {
+        let rest = &lower[lower.find(marker)? + marker.len()..];
+        let digits: String = rest
+            .trim_start_matches([' ', '('])
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        digits.parse().ok()
+    };
+    GeminiError::InputTooLarge {
+        measured_tokens: number_after("input token count").unwrap_or(measured_tokens),
+        limit_tokens: number_after("tokens allowed").unwrap_or(limit_tokens),
+    }
+}
+
+/// Ask Gemini for the real token count of `prompt` (None when unavailable)
+async fn count_tokens(client: &reqwest::Client, api_key: &str, prompt: &str) -> Option<u64> {
+    let response = client
+        .post(format!("{}:countTokens?key={}", GEMINI_MODEL_URL, api_key))
+        .json(&serde_json::json!({ "contents": [{ "parts": [{ "text": prompt }] }] }))
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let json: serde_json::Value = response.json().await.ok()?;
+    json["totalTokens"].as_u64()
+}
+
+/// Compress `context` to roughly `max_tokens` with a separate generation call
+async fn summarize(
+    client: &reqwest::Client,
+    api_key: &str,
+    context: &str,
+    max_tokens: u64,
+    settings: &GeminiBudgetSettings,
+) -> Result<String, GeminiError> {
+    let input = truncate_context(context, settings.max_summary_input_tokens);
+    let prompt = format!(
+        "Tóm tắt tài liệu sau thành tối đa khoảng {} từ, giữ lại các sự kiện, thuật ngữ, quy trình và con số quan trọng. \
+         Chỉ trả về phần tóm tắt, bằng TIẾNG VIỆT.\n\n---\n{}",
+        max_tokens * 3 / 4,
+        input
+    );
+    let response = client
+        .post(format!("{}:generateContent?key={}", GEMINI_MODEL_URL, api_key))
+        .json(&serde_json::json!({
+            "contents": [{ "parts": [{ "text": prompt }] }],
+            "generationConfig": { "temperature": 0.2, "maxOutputTokens": max_tokens.min(8192) }
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to call Gemini API: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(map_api_error(status, &body, estimate_tokens(&prompt), settings.max_input_tokens));
+    }
+    let json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Gemini response: {}", e))?;
+    json["candidates"][0]["content"]["parts"][0]["text"]
+        .as_str()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| GeminiError::Other("Gemini returned an empty summary".to_string()))
+}
+
+/// Build a prompt from `build_prompt(context)` that fits `settings.max_input_tokens`
+pub async fn fit_prompt(
+    client: &reqwest::Client,
+    api_key: &str,
+    settings: &GeminiBudgetSettings,
+    context: &str,
+    build_prompt: impl Fn(&str) -> String,
+) -> Result<(String, InputBudgetReport), GeminiError> {
+    let limit = settings.max_input_tokens;
+    let overhead = estimate_tokens(&build_prompt(""));
+    let available = limit.saturating_sub(overhead);
+    let mut report = InputBudgetReport {
+        limit_tokens: limit,
+        original_context_tokens: estimate_tokens(context),
+        ..Default::default()
+    };
+    if available <= MARKER_RESERVE_TOKENS {
+        return Err(GeminiError::InputTooLarge {
+            measured_tokens: overhead + report.original_context_tokens,
+            limit_tokens: limit,
+        });
+    }
+
+    let mut context = context.to_string();
+    if report.original_context_tokens > available && settings.summarize_overflow {
+        match summarize(client, api_key, &context, available, settings).await {
+            Ok(summary) => {
+                context = summary;
+                report.context_summarized = true;
+            }
+            Err(e) => eprintln!("Gemini budget: summarization failed, truncating instead: {}", e),
+        }
+    }
+    if estimate_tokens(&context) > available {
+        context = truncate_context(&context, available);
+        report.context_truncated = true;
+    }
+
+    let mut prompt = build_prompt(&context);
+    if settings.count_tokens {
+        report.counted_tokens = count_tokens(client, api_key, &prompt).await;
+
+        // The estimate runs low for some scripts; shrink once by the measured ratio
+        if let Some(counted) = report.counted_tokens.filter(|&c| c > limit) {
+            let scale = limit as f64 / counted as f64 * 0.9;
+            let target = (estimate_tokens(&context) as f64 * scale) as u64;
+            if target <= MARKER_RESERVE_TOKENS {
+                return Err(GeminiError::InputTooLarge { measured_tokens: counted, limit_tokens: limit });
+            }
+            context = truncate_context(&context, target);
+            report.context_truncated = true;
+            prompt = build_prompt(&context);
+            report.counted_tokens = count_tokens(client, api_key, &prompt).await;
+            if let Some(counted) = report.counted_tokens.filter(|&c| c > limit) {
+                return Err(GeminiError::InputTooLarge { measured_tokens: counted, limit_tokens: limit });
+            }
+        }
+    }
+
+    report.estimated_tokens = estimate_tokens(&prompt);
+    Ok((prompt, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncation_keeps_char_boundaries_and_marks_the_cut() {
+        let text = "Phân tích tài chính ".repeat(500);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+
+        let cut = truncate_context(&text, 100);
+        assert!(cut.contains("đã cắt bớt ngữ cảnh"));
+        assert!(estimate_tokens(&cut) <= 100);
+        assert!(text.starts_with(cut.split("\n\n[").next().unwrap()));
+
+        let short = "ngắn gọn";
+        assert_eq!(truncate_context(short, 100), short);
+    }
+
+    #[test]
+    fn test_too_large_responses_become_typed_errors() {
+        let body = r#"{"error":{"code":400,"message":"The input token count (1200345) exceeds the maximum number of tokens allowed (1048576).","status":"INVALID_ARGUMENT"}}"#;
+        match map_api_error(400, body, 10, 20) {
+            GeminiError::InputTooLarge { measured_tokens, limit_tokens } => {
+                assert_eq!((measured_tokens, limit_tokens), (1_200_345, 1_048_576));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            map_api_error(413, "Request Entity Too Large", 50_000, 32_000),
+            GeminiError::InputTooLarge { measured_tokens: 50_000, limit_tokens: 32_000 }
+        ));
+        assert!(matches!(map_api_error(400, "API key not valid", 1, 2), GeminiError::Other(_)));
+
+        let json = serde_json::to_value(GeminiError::InputTooLarge { measured_tokens: 5, limit_tokens: 4 }).unwrap();
+        assert_eq!(json["kind"], "input_too_large");
+        assert_eq!(json["limit_tokens"], 4);
+    }
+}
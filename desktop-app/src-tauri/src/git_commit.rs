@@ -0,0 +1,209 @@
+/// Git Commit - Commit reviewed changes without leaving the app
+///
+/// `commit_changes` stages the given paths of the open project (`git add -A`,
+/// so deletions count) and commits only those paths, whatever else is staged,
+/// returning the new commit's hash. The committed files leave the changed
+/// files list, which is emitted as `changed-files-updated`. The author is the
+/// configured `user.name`/`user.email` (see `get_git_identity`) unless an
+/// override `Name <email>` is given, which also stands in as committer when
+/// git has no identity; with neither the error kind is `no_identity`, and an
+/// empty message is `empty_message`.
+
+use std::path::Path;
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::git_diff::{self, GitDiffError};
+use crate::git_status::CHANGED_FILES_UPDATED_EVENT;
+
+/// Result of `get_git_identity`
+#[derive(Debug, Serialize)]
+pub struct GitIdentity {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    /// Both are set: git can commit without an author override
+    pub configured: bool,
+}
+
+fn config_value(project: &Path, key: &str) -> Result<Option<String>, GitDiffError> {
+    // Exits with 1 when the key is not set
+    let output = git_diff::git(project, &["config", "--get", key])?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(value).filter(|v| output.status.success() && !v.is_empty()))
+}
+
+fn identity(project: &Path) -> Result<GitIdentity, GitDiffError> {
+    let name = config_value(project, "user.name")?;
+    let email = config_value(project, "user.email")?;
+    Ok(GitIdentity { configured: name.is_some() && email.is_some(), name, email })
+}
+
+/// Name and email of an author override `Name <email>`
+fn parse_author(author: &str) -> Result<(String, String), GitDiffError> {
+    let invalid = || GitDiffError::Git(format!("Author must look like \"Name <email>\", got \"{}\"", author));
+    let (name, rest) = author.trim().split_once('<').ok_or_else(invalid)?;
+    let email = rest.strip_suffix('>').ok_or_else(invalid)?.trim();
+    let name = name.trim();
+    if name.is_empty() || email.is_empty() || email.contains(['<', '>']) {
+        return Err(invalid());
+    }
+    Ok((name.to_string(), email.to_string()))
+}
+
+/// Stage and commit `paths` (absolute, or relative to `project`); the new
+/// commit's hash and the committed paths relative to the repository root
+fn commit(project: &Path, paths: &[String], message: &str, author: Option<&str>) -> Result<(String, Vec<String>), GitDiffError> {
+    if message.trim().is_empty() {
+        return Err(GitDiffError::EmptyMessage);
+    }
+    if paths.is_empty() {
+        return Err(GitDiffError::Git("No files to commit".to_string()));
+    }
+    let root = git_diff::repository_root(project)?;
+    let relative = paths
+        .iter()
+        .map(|path| git_diff::repository_path(&root, project, path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let author = author.map(str::trim).filter(|a| !a.is_empty()).map(parse_author).transpose()?;
+
+    // Without an identity git refuses to commit even with --author
+    let mut config = Vec::new();
+    if !identity(project)?.configured {
+        let (name, email) = author.as_ref().ok_or(GitDiffError::NoIdentity)?;
+        config = vec!["-c".to_string(), format!("user.name={}", name), "-c".to_string(), format!("user.email={}", email)];
+    }
+    let pathspecs: Vec<String> = relative.iter().map(|path| format!(":(top,literal){}", path)).collect();
+
+    let mut add = vec!["add", "-A", "--"];
+    add.extend(pathspecs.iter().map(String::as_str));
+    let output = git_diff::git(&root, &add)?;
+    if !output.status.success() {
+        return Err(git_diff::git_failure(&output));
+    }
+
+    let author_arg = author.map(|(name, email)| format!("--author={} <{}>", name, email));
+    let mut args: Vec<&str> = config.iter().map(String::as_str).collect();
+    args.extend(["commit", "--quiet", "--no-edit", "-m", message]);
+    args.extend(author_arg.as_deref());
+    args.push("--");
+    args.extend(pathspecs.iter().map(String::as_str));
+    let output = git_diff::git(&root, &args)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("Please tell me who you are") {
+            return Err(GitDiffError::NoIdentity);
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let reason = if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() };
+        return Err(GitDiffError::Git(format!("git commit failed: {}", reason)));
+    }
+
+    let head = git_diff::git(&root, &["rev-parse", "HEAD"])?;
+    if !head.status.success() {
+        return Err(git_diff::git_failure(&head));
+    }
+    Ok((String::from_utf8_lossy(&head.stdout).trim().to_string(), relative))
+}
+
+fn current_project() -> Result<String, GitDiffError> {
+    crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()).ok_or(GitDiffError::NoProject)
+}
+
+/// Drop the committed files from the changed files list and emit it
+fn forget_committed(app: &tauri::AppHandle, project: &Path, committed: &[String]) -> Result<(), GitDiffError> {
+    let root = git_diff::repository_root(project)?;
+    let mut changed = crate::CHANGED_FILES.write().map_err(|e| GitDiffError::Git(format!("Lock error: {}", e)))?;
+    changed.retain(|file| {
+        git_diff::repository_path(&root, project, &file.path).map_or(true, |path| !committed.contains(&path))
+    });
+    let _ = app.emit(CHANGED_FILES_UPDATED_EVENT, &*changed);
+    Ok(())
+}
+
+/// Who commits in the current project would be attributed to
+#[tauri::command]
+pub async fn get_git_identity() -> Result<GitIdentity, GitDiffError> {
+    crate::command_metrics::timed("get_git_identity", async move {
+        let project = current_project()?;
+        tauri::async_runtime::spawn_blocking(move || identity(Path::new(&project)))
+            .await
+            .map_err(|e| GitDiffError::Git(format!("git config failed: {}", e)))?
+    }).await
+}
+
+/// Stage and commit `paths` of the current project as `message`, authored by
+/// `author_override` (`Name <email>`) if given; the new commit's hash
+#[tauri::command]
+pub async fn commit_changes(
+    app: tauri::AppHandle,
+    paths: Vec<String>,
+    message: String,
+    author_override: Option<String>,
+) -> Result<String, GitDiffError> {
+    crate::command_metrics::timed("commit_changes", async move {
+        let args = serde_json::json!({ "paths": paths, "author_override": author_override });
+        let result = async {
+            let project = current_project()?;
+            let dir = project.clone();
+            let (hash, committed) = tauri::async_runtime::spawn_blocking(move || {
+                commit(Path::new(&dir), &paths, &message, author_override.as_deref())
+            })
+            .await
+            .map_err(|e| GitDiffError::Git(format!("git commit failed: {}", e)))??;
+            forget_committed(&app, Path::new(&project), &committed)?;
+            Ok(hash)
+        }
+        .await;
+        crate::audit::record("commit_changes", args, crate::audit::ORIGIN_COMMAND, &result);
+        result
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authors_are_parsed() {
+        assert_eq!(parse_author(" Ada Lovelace <ada@example.com> ").unwrap(), ("Ada Lovelace".to_string(), "ada@example.com".to_string()));
+        assert!(parse_author("Ada Lovelace").is_err());
+        assert!(parse_author("<ada@example.com>").is_err());
+        assert!(parse_author("Ada <>").is_err());
+    }
+
+    #[test]
+    fn test_only_the_given_paths_are_committed() {
+        if git_diff::git(&std::env::temp_dir(), &["--version"]).is_err() {
+            return;
+        }
+        let root = std::env::temp_dir().join(format!("vibecode-git-commit-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let run = |args: &[&str]| assert!(git_diff::git(&root, args).unwrap().status.success(), "git {:?}", args);
+        run(&["init", "--quiet"]);
+        run(&["config", "user.name", "Test"]);
+        run(&["config", "user.email", "test@example.com"]);
+        std::fs::write(root.join("old.txt"), "old\n").unwrap();
+        std::fs::write(root.join("keep.txt"), "keep\n").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "--quiet", "-m", "init"]);
+
+        std::fs::remove_file(root.join("old.txt")).unwrap();
+        std::fs::write(root.join("src").join("new file.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("keep.txt"), "changed\n").unwrap();
+        let paths = vec!["old.txt".to_string(), root.join("src").join("new file.rs").to_string_lossy().to_string()];
+
+        assert!(matches!(commit(&root, &paths, "  \n", None), Err(GitDiffError::EmptyMessage)));
+        let identity = identity(&root).unwrap();
+        assert_eq!((identity.name.as_deref(), identity.configured), (Some("Test"), true));
+
+        let (hash, committed) = commit(&root, &paths, "Replace old.txt", Some("Ada <ada@example.com>")).unwrap();
+        assert_eq!(committed, vec!["old.txt", "src/new file.rs"]);
+        let log = git_diff::git(&root, &["log", "-1", "--format=%H %an %cn %s"]).unwrap();
+        assert_eq!(String::from_utf8_lossy(&log.stdout).trim(), format!("{} Ada Test Replace old.txt", hash));
+        // keep.txt was not part of it
+        let status = git_diff::git(&root, &["status", "--porcelain"]).unwrap();
+        assert_eq!(String::from_utf8_lossy(&status.stdout).trim(), "M keep.txt");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
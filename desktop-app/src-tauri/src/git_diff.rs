@@ -0,0 +1,398 @@
+/// Git Diff - What changed in a project file since the last commit
+///
+/// `get_file_diff` runs the git CLI in the open project: `git diff --no-color
+/// HEAD -- <path>` for a tracked file (staged and unstaged changes together;
+/// against the empty tree before the first commit), and `git diff --no-index
+/// /dev/null <path>` for an untracked one, which shows all of it as added.
+/// The unified diff is parsed into hunks whose lines carry their old and new
+/// line numbers, and is also returned as `raw` for anything the parser does
+/// not cover. Errors are `{ kind, message }`, with `kind: "not_a_repository"`
+/// when the project is not under git. `is_tracked` answers whether git
+/// tracks a path at all, for anything outside this module.
+
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+
+use crate::child_env;
+
+/// Tree git uses for "nothing", to diff against before the first commit
+pub(crate) const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// Errors of the git commands (`get_file_diff`, `refresh_changed_files`,
+/// `review_diff`, `git_commit`, `git_push`)
+#[derive(Debug)]
+pub enum GitDiffError {
+    NoProject,
+    NotARepository(String),
+    GitNotFound,
+    /// Committing without `user.name`/`user.email` configured
+    NoIdentity,
+    EmptyMessage,
+    /// Pushing needs credentials git could not get without a prompt, or the
+    /// remote refused them
+    AuthFailed(String),
+    /// The remote branch has commits the pushed one lacks
+    NonFastForward(String),
+    /// The remote could not be reached
+    Network(String),
+    Git(String),
+}
+
+impl GitDiffError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::NoProject => "no_project",
+            Self::NotARepository(_) => "not_a_repository",
+            Self::GitNotFound => "git_not_found",
+            Self::NoIdentity => "no_identity",
+            Self::EmptyMessage => "empty_message",
+            Self::AuthFailed(_) => "auth_failed",
+            Self::NonFastForward(_) => "non_fast_forward",
+            Self::Network(_) => "network",
+            Self::Git(_) => "git",
+        }
+    }
+}
+
+impl fmt::Display for GitDiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoProject => write!(f, "No project is open"),
+            Self::NotARepository(dir) => write!(f, "{} is not a git repository", dir),
+            Self::GitNotFound => write!(f, "git was not found on PATH"),
+            Self::NoIdentity => write!(
+                f,
+                "git does not know who to attribute the commit to: set user.name and user.email \
+                 (git config --global user.name \"Your Name\") or give an author"
+            ),
+            Self::EmptyMessage => write!(f, "The commit message is empty"),
+            Self::AuthFailed(msg) | Self::NonFastForward(msg) | Self::Network(msg) | Self::Git(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<String> for GitDiffError {
+    fn from(msg: String) -> Self {
+        Self::Git(msg)
+    }
+}
+
+/// Serialized as `{ kind, message }` so the frontend can branch on `kind`
+impl Serialize for GitDiffError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("GitDiffError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+/// One line of a hunk
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    /// Line number before the change (None for added lines)
+    pub old_line: Option<u32>,
+    /// Line number after the change (None for removed lines)
+    pub new_line: Option<u32>,
+    pub text: String,
+}
+
+/// One `@@ -a,b +c,d @@` section
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// Text after the closing `@@` (often the enclosing function)
+    pub section: String,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Result of `get_file_diff`
+#[derive(Debug, Serialize)]
+pub struct FileDiff {
+    /// Path relative to the repository root, with `/` separators
+    pub path: String,
+    /// "modified" | "added" | "deleted" | "untracked" | "unchanged"
+    pub status: String,
+    /// Git found the file binary; there are no hunks
+    pub binary: bool,
+    pub hunks: Vec<DiffHunk>,
+    /// The unified diff as git printed it
+    pub raw: String,
+}
+
+/// `-a,b` / `+c,d` of a hunk header (the count defaults to 1)
+fn parse_range(range: &str) -> Option<(u32, u32)> {
+    let (start, count) = range.split_once(',').unwrap_or((range, "1"));
+    Some((start.parse().ok()?, count.parse().ok()?))
+}
+
+/// `@@ -a,b +c,d @@ section`
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let (old_start, old_lines) = parse_range(old)?;
+    let (new_start, new_lines) = parse_range(new)?;
+    Some(DiffHunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        section: section.trim().to_string(),
+        lines: Vec::new(),
+    })
+}
+
+/// Hunks of a unified diff of one file
+pub(crate) fn parse_hunks(raw: &str) -> Vec<DiffHunk> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let (mut old_line, mut new_line) = (0, 0);
+    for line in raw.lines() {
+        if let Some(hunk) = parse_hunk_header(line) {
+            (old_line, new_line) = (hunk.old_start, hunk.new_start);
+            hunks.push(hunk);
+            continue;
+        }
+        // File headers come before the first hunk
+        let Some(hunk) = hunks.last_mut() else { continue };
+        let kind = match line.as_bytes().first() {
+            Some(b' ') | None => LineKind::Context,
+            Some(b'+') => LineKind::Added,
+            Some(b'-') => LineKind::Removed,
+            // "\ No newline at end of file"
+            _ => continue,
+        };
+        let text = line.get(1..).unwrap_or_default();
+        let (old, new) = match kind {
+            LineKind::Context => (Some(old_line), Some(new_line)),
+            LineKind::Added => (None, Some(new_line)),
+            LineKind::Removed => (Some(old_line), None),
+        };
+        old_line += u32::from(old.is_some());
+        new_line += u32::from(new.is_some());
+        hunk.lines.push(DiffLine { kind, old_line: old, new_line: new, text: text.to_string() });
+    }
+    hunks
+}
+
+/// Status of a tracked file from the extended headers of its diff
+fn tracked_status(raw: &str) -> &'static str {
+    let headers = raw.lines().take_while(|line| !line.starts_with("@@"));
+    for line in headers {
+        if line.starts_with("new file mode") {
+            return "added";
+        }
+        if line.starts_with("deleted file mode") {
+            return "deleted";
+        }
+    }
+    if raw.is_empty() { "unchanged" } else { "modified" }
+}
+
+pub(crate) fn git(repo: &Path, args: &[&str]) -> Result<Output, GitDiffError> {
+    git_with_env(repo, &[], args)
+}
+
+/// `git` with extra environment variables (e.g. `GIT_INDEX_FILE`)
+pub(crate) fn git_with_env(repo: &Path, env: &[(&str, &OsStr)], args: &[&str]) -> Result<Output, GitDiffError> {
+    child_env::system_command("git")
+        .arg("-C")
+        .arg(repo)
+        .envs(env.iter().copied())
+        .args(args)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => GitDiffError::GitNotFound,
+            _ => GitDiffError::Git(format!("Failed to run git: {}", e)),
+        })
+}
+
+pub(crate) fn git_failure(output: &Output) -> GitDiffError {
+    GitDiffError::Git(format!("git failed: {}", String::from_utf8_lossy(&output.stderr).trim()))
+}
+
+/// Root of the repository `dir` is in
+pub(crate) fn repository_root(dir: &Path) -> Result<PathBuf, GitDiffError> {
+    let output = git(dir, &["rev-parse", "--show-toplevel"])?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(if stderr.contains("not a git repository") {
+            GitDiffError::NotARepository(dir.display().to_string())
+        } else {
+            git_failure(&output)
+        });
+    }
+    Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// Whether git tracks `path` (a folder: anything in it); None when it is not
+/// in a repository or git cannot be run
+pub fn is_tracked(path: &Path) -> Option<bool> {
+    let (dir, pathspec) = if path.is_dir() {
+        (path, std::ffi::OsStr::new("."))
+    } else {
+        (path.parent()?, path.file_name()?)
+    };
+    let output = child_env::system_command("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["ls-files", "--error-unmatch", "--"])
+        .arg(pathspec)
+        .output()
+        .ok()?;
+    // 1: not tracked; 128: not in a repository
+    match output.status.code() {
+        Some(0) => Some(true),
+        Some(1) => Some(false),
+        _ => None,
+    }
+}
+
+/// `path` (absolute, or relative to `project`) relative to the repository
+/// `root`, with `/` separators
+pub(crate) fn repository_path(root: &Path, project: &Path, path: &str) -> Result<String, GitDiffError> {
+    let absolute = project.join(path);
+    // Canonical on both sides, so symlinked temp dirs and Windows prefixes agree
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let parent = absolute.parent().and_then(|p| p.canonicalize().ok()).unwrap_or_default();
+    parent
+        .join(absolute.file_name().unwrap_or_default())
+        .strip_prefix(&canonical_root)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .map_err(|_| GitDiffError::Git(format!("{} is outside the repository at {}", path, root.display())))
+}
+
+/// Diff of `path` (absolute, or relative to `project`) against HEAD
+fn diff(project: &Path, path: &str) -> Result<FileDiff, GitDiffError> {
+    let root = repository_root(project)?;
+    let relative = repository_path(&root, project, path)?;
+
+    let tracked = git(&root, &["ls-files", "--error-unmatch", "--", &relative])?.status.success();
+    let (output, status) = if tracked {
+        let head = git(&root, &["rev-parse", "--verify", "--quiet", "HEAD"])?.status.success();
+        let base = if head { "HEAD" } else { EMPTY_TREE };
+        (git(&root, &["diff", "--no-color", base, "--", &relative])?, None)
+    } else if root.join(&relative).is_file() {
+        (git(&root, &["diff", "--no-color", "--no-index", "--", "/dev/null", &relative])?, Some("untracked"))
+    } else {
+        return Err(GitDiffError::Git(format!("{} is neither tracked by git nor a file", relative)));
+    };
+    // `--no-index` exits with 1 when the files differ
+    let differs = status.is_some() && output.status.code() == Some(1);
+    if !(output.status.success() || differs) {
+        return Err(git_failure(&output));
+    }
+
+    let raw = String::from_utf8_lossy(&output.stdout).into_owned();
+    let binary = raw.lines().any(|line| line.starts_with("Binary files ") && line.ends_with(" differ"));
+    Ok(FileDiff {
+        path: relative,
+        status: status.unwrap_or_else(|| tracked_status(&raw)).to_string(),
+        binary,
+        hunks: parse_hunks(&raw),
+        raw,
+    })
+}
+
+/// Changes to `path` in the current project since the last commit
+#[tauri::command]
+pub async fn get_file_diff(path: String) -> Result<FileDiff, GitDiffError> {
+    crate::command_metrics::timed("get_file_diff", async move {
+        let project = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()).ok_or(GitDiffError::NoProject)?;
+        tauri::async_runtime::spawn_blocking(move || diff(Path::new(&project), &path))
+            .await
+            .map_err(|e| GitDiffError::Git(format!("Diff failed: {}", e)))?
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hunks_carry_old_and_new_line_numbers() {
+        let raw = "diff --git a/src/main.rs b/src/main.rs\n\
+                   index 1111111..2222222 100644\n\
+                   --- a/src/main.rs\n\
+                   +++ b/src/main.rs\n\
+                   @@ -1,3 +1,3 @@ fn main() {\n \
+                   keep\n\
+                   -old\n\
+                   +new\n \
+                   tail\n\
+                   @@ -10 +10,2 @@\n\
+                   -last\n\
+                   +last\n\
+                   +added\n\
+                   \\ No newline at end of file\n";
+        let hunks = parse_hunks(raw);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!((hunks[0].old_start, hunks[0].old_lines, hunks[0].section.as_str()), (1, 3, "fn main() {"));
+        let numbered: Vec<(LineKind, Option<u32>, Option<u32>, &str)> =
+            hunks[0].lines.iter().map(|l| (l.kind, l.old_line, l.new_line, l.text.as_str())).collect();
+        assert_eq!(
+            numbered,
+            vec![
+                (LineKind::Context, Some(1), Some(1), "keep"),
+                (LineKind::Removed, Some(2), None, "old"),
+                (LineKind::Added, None, Some(2), "new"),
+                (LineKind::Context, Some(3), Some(3), "tail"),
+            ]
+        );
+        assert_eq!((hunks[1].old_lines, hunks[1].new_lines), (1, 2));
+        assert_eq!(hunks[1].lines.last().map(|l| (l.new_line, l.text.as_str())), Some((Some(11), "added")));
+
+        assert_eq!(tracked_status(raw), "modified");
+        assert_eq!(tracked_status("diff --git a/x b/x\nnew file mode 100644\n"), "added");
+        assert_eq!(tracked_status(""), "unchanged");
+    }
+
+    #[test]
+    fn test_diffs_against_head_and_untracked_files() {
+        if git(&std::env::temp_dir(), &["--version"]).is_err() {
+            return;
+        }
+        let root = std::env::temp_dir().join(format!("vibecode-git-diff-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        assert!(matches!(diff(&root, "a.txt"), Err(GitDiffError::NotARepository(_))));
+
+        let run = |args: &[&str]| assert!(git(&root, args).unwrap().status.success(), "git {:?}", args);
+        run(&["init", "--quiet"]);
+        std::fs::write(root.join("a.txt"), "one\ntwo\n").unwrap();
+        run(&["add", "a.txt"]);
+        run(&["-c", "user.name=t", "-c", "user.email=t@example.com", "commit", "--quiet", "-m", "init"]);
+        std::fs::write(root.join("a.txt"), "one\n2\n").unwrap();
+        std::fs::write(root.join("new.txt"), "hello\n").unwrap();
+
+        let modified = diff(&root, "a.txt").unwrap();
+        assert_eq!((modified.path.as_str(), modified.status.as_str()), ("a.txt", "modified"));
+        let changed: Vec<(LineKind, &str)> =
+            modified.hunks[0].lines.iter().map(|l| (l.kind, l.text.as_str())).collect();
+        assert_eq!(changed, vec![(LineKind::Context, "one"), (LineKind::Removed, "two"), (LineKind::Added, "2")]);
+
+        let untracked = diff(&root, &root.join("new.txt").to_string_lossy()).unwrap();
+        assert_eq!(untracked.status, "untracked");
+        assert_eq!(untracked.hunks[0].lines[0].text, "hello");
+
+        std::fs::write(root.join("a.txt"), "one\ntwo\n").unwrap();
+        assert_eq!(diff(&root, "a.txt").unwrap().status, "unchanged");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
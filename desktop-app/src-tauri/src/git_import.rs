@@ -0,0 +1,685 @@
+/// Git Import - Skills and workflows straight from a GitHub repository
+///
+/// The repository is fetched through GitHub's zipball endpoint (no git binary
+/// needed) into a throwaway folder under the OS temp dir, which is removed on
+/// every path out of `import_from_git`, including errors and cancellation.
+/// Folders containing a SKILL.md become skills and top-level workflow YAML
+/// files (with `name:` and `steps:`) become workflows. The download and the
+/// extracted entries are held to the `skillArchive` size and entry limits.
+/// Each item is validated and installed with the usual rule that existing skills and workflows are
+/// never overwritten; the result is a per-item report.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+
+use crate::operations::ProgressReporter;
+use crate::skill_archive::{self, SkillArchiveLimits};
+use crate::skill_frontmatter::SkillDocument;
+
+const GITHUB_API: &str = "https://api.github.com";
+/// Parent of per-import temp folders
+const CHECKOUT_DIRS: &str = "vibecode-git-import";
+/// Plugin store key holding the GitHub token (next to the other provider keys)
+const TOKEN_STORE_KEY: &str = "github_token";
+/// Emit a download progress event at most once per this many bytes
+const PROGRESS_INTERVAL_BYTES: u64 = 1024 * 1024;
+
+pub const KIND_GIT_IMPORT: &str = "git_import";
+
+/// What to import from the repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitImportKind {
+    Skills,
+    Workflows,
+    #[default]
+    Both,
+}
+
+impl GitImportKind {
+    fn skills(self) -> bool {
+        self != Self::Workflows
+    }
+
+    fn workflows(self) -> bool {
+        self != Self::Skills
+    }
+}
+
+/// Outcome for one discovered skill or workflow
+#[derive(Debug, Clone, Serialize)]
+pub struct GitImportItem {
+    /// "skill" | "workflow"
+    pub kind: String,
+    pub id: String,
+    /// Path inside the repository, `/`-separated
+    pub source: String,
+    /// "imported" | "skipped" | "failed"
+    pub status: String,
+    pub message: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Result of `import_from_git`
+#[derive(Debug, Clone, Serialize)]
+pub struct GitImportReport {
+    pub operation_id: String,
+    pub repository: String,
+    pub git_ref: Option<String>,
+    pub items: Vec<GitImportItem>,
+    pub imported: usize,
+    /// Items left unprocessed because the operation was cancelled
+    pub cancelled: bool,
+}
+
+/// `owner/repo` on GitHub
+#[derive(Debug, Clone, PartialEq)]
+struct GitHubRepo {
+    owner: String,
+    repo: String,
+}
+
+/// Parse `https://github.com/owner/repo[.git][/...]`, `git@github.com:owner/repo.git` or `owner/repo`
+fn parse_repo_url(url: &str) -> Result<GitHubRepo, String> {
+    let trimmed = url.trim().trim_end_matches('/');
+    let path = trimmed
+        .strip_prefix("git@github.com:")
+        .or_else(|| {
+            let rest = trimmed.strip_prefix("https://").or_else(|| trimmed.strip_prefix("http://"))?;
+            rest.strip_prefix("www.github.com/").or_else(|| rest.strip_prefix("github.com/"))
+        })
+        .unwrap_or(trimmed);
+
+    let mut parts = path.split('/');
+    let (owner, repo) = match (parts.next(), parts.next()) {
+        (Some(owner), Some(repo)) => (owner, repo.trim_end_matches(".git")),
+        _ => return Err(format!("Not a GitHub repository URL: {}", url)),
+    };
+    let valid = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+    if !valid(owner) || !valid(repo) {
+        return Err(format!("Not a GitHub repository URL: {}", url));
+    }
+
+    Ok(GitHubRepo {
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+    })
+}
+
+/// A relative, `..`-free subpath inside the checkout
+fn safe_subpath(subpath: Option<&str>) -> Result<PathBuf, String> {
+    let subpath = subpath.unwrap_or_default().trim_matches('/');
+    let path = PathBuf::from(subpath);
+    if path.components().all(|c| matches!(c, Component::Normal(_))) {
+        Ok(path)
+    } else {
+        Err(format!("Invalid subpath: {}", subpath))
+    }
+}
+
+/// Temp folder for one import, removed when dropped
+struct TempCheckout {
+    dir: PathBuf,
+}
+
+impl TempCheckout {
+    fn create(base: &Path) -> Result<Self, String> {
+        let dir = base.join(CHECKOUT_DIRS).join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create temp folder: {}", e))?;
+        Ok(Self { dir })
+    }
+}
+
+impl Drop for TempCheckout {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            eprintln!("Git import: failed to remove {}: {}", self.dir.display(), e);
+        }
+    }
+}
+
+/// GitHub token from the plugin store, falling back to the environment
+fn github_token(app: &tauri::AppHandle) -> Option<String> {
+    use tauri_plugin_store::StoreExt;
+
+    app.store("settings.json")
+        .ok()
+        .and_then(|store| store.get(TOKEN_STORE_KEY))
+        .and_then(|value| value.as_str().map(String::from))
+        .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+        .or_else(|| std::env::var("GH_TOKEN").ok())
+        .filter(|token| !token.trim().is_empty())
+}
+
+/// `git_ref` as a URL path, each `/`-separated part percent-encoded
+fn ref_path(git_ref: &str) -> Result<String, String> {
+    let parts: Vec<&str> = git_ref.trim().split('/').collect();
+    if parts.iter().any(|p| p.is_empty() || *p == "." || *p == "..") {
+        return Err(format!("Invalid git ref '{}'", git_ref));
+    }
+    Ok(parts.iter().map(|p| urlencoding::encode(p)).collect::<Vec<_>>().join("/"))
+}
+
+fn archive_too_large(limits: &SkillArchiveLimits) -> String {
+    format!("Repository exceeds the total size limit of {} bytes", limits.max_archive_bytes)
+}
+
+/// Stream the repository zipball to `dest`
+async fn download_zipball(
+    repo: &GitHubRepo,
+    git_ref: Option<&str>,
+    token: Option<&str>,
+    dest: &Path,
+    limits: &SkillArchiveLimits,
+    reporter: &ProgressReporter,
+) -> Result<(), String> {
+    let mut url = format!("{}/repos/{}/{}/zipball", GITHUB_API, repo.owner, repo.repo);
+    if let Some(git_ref) = git_ref {
+        url.push('/');
+        url.push_str(&ref_path(git_ref)?);
+    }
+
+    let mut request = crate::http_client::client()?
+        .get(&url)
+        .header("User-Agent", "vibecode-desktop")
+        .header("Accept", "application/vnd.github+json");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download repository: {}", e))?;
+
+    match response.status() {
+        status if status.is_success() => {}
+        reqwest::StatusCode::NOT_FOUND if token.is_none() => {
+            return Err(format!(
+                "Repository {}/{} not found (private repositories need a GitHub token)",
+                repo.owner, repo.repo
+            ))
+        }
+        status => return Err(format!("GitHub returned {} for {}/{}", status, repo.owner, repo.repo)),
+    }
+
+    let total = response.content_length();
+    if total.is_some_and(|t| t > limits.max_archive_bytes) {
+        return Err(archive_too_large(limits));
+    }
+    let mut writer = BufWriter::new(
+        File::create(dest).map_err(|e| format!("Failed to create download file: {}", e))?,
+    );
+    let (mut done, mut reported) = (0u64, 0u64);
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to download repository: {}", e))?
+    {
+        if reporter.is_cancelled() {
+            return Err("Import cancelled during download".to_string());
+        }
+        writer
+            .write_all(&chunk)
+            .map_err(|e| format!("Failed to write download file: {}", e))?;
+        done += chunk.len() as u64;
+        if done > limits.max_archive_bytes {
+            return Err(archive_too_large(limits));
+        }
+        if done - reported >= PROGRESS_INTERVAL_BYTES {
+            reported = done;
+            reporter.report(done, total, Some("Downloading".to_string()));
+        }
+    }
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write download file: {}", e))
+}
+
+/// Extract a GitHub zipball into `dest`, dropping its `<owner>-<repo>-<sha>/` top folder
+///
+/// Limits are checked on the bytes actually extracted, not the sizes the
+/// archive declares.
+fn extract_zipball(zip_path: &Path, dest: &Path, limits: &SkillArchiveLimits) -> Result<(), String> {
+    let file = File::open(zip_path).map_err(|e| format!("Failed to open download: {}", e))?;
+    let mut archive = zip::ZipArchive::new(BufReader::new(file))
+        .map_err(|e| format!("Invalid repository archive: {}", e))?;
+    if archive.len() > limits.max_entries {
+        return Err(format!("Repository has more than {} files and folders", limits.max_entries));
+    }
+
+    let mut extracted = 0u64;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Invalid repository archive: {}", e))?;
+        let Some(enclosed) = entry.enclosed_name().map(Path::to_path_buf) else {
+            return Err(format!("Unsafe path in repository archive: {}", entry.name()));
+        };
+        let relative: PathBuf = enclosed.components().skip(1).collect();
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+
+        let target = dest.join(&relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)
+                .map_err(|e| format!("Failed to create '{}': {}", relative.display(), e))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create '{}': {}", relative.display(), e))?;
+        }
+        let mut writer = File::create(&target)
+            .map_err(|e| format!("Failed to create '{}': {}", relative.display(), e))?;
+        let copied = std::io::copy(&mut (&mut entry).take(limits.max_file_bytes + 1), &mut writer)
+            .map_err(|e| format!("Failed to extract '{}': {}", relative.display(), e))?;
+        if copied > limits.max_file_bytes {
+            return Err(format!(
+                "File '{}' exceeds the per-file limit of {} bytes",
+                relative.display(),
+                limits.max_file_bytes
+            ));
+        }
+        extracted += copied;
+        if extracted > limits.max_archive_bytes {
+            return Err(archive_too_large(limits));
+        }
+    }
+    Ok(())
+}
+
+/// Skill folders (containing SKILL.md) and candidate workflow files under `dir`
+fn discover(dir: &Path, skills: &mut Vec<PathBuf>, workflows: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            if path.join("SKILL.md").is_file() {
+                skills.push(path);
+            } else {
+                discover(&path, skills, workflows);
+            }
+        } else if name.ends_with(".yaml") || name.ends_with(".yml") {
+            workflows.push(path);
+        }
+    }
+}
+
+/// True when the YAML has top-level `name:` and `steps:` keys (the vibe.py workflow shape)
+fn is_workflow_yaml(content: &str) -> bool {
+    let has_key = |key: &str| content.lines().any(|line| line.starts_with(key));
+    has_key("name:") && has_key("steps:")
+}
+
+fn item(kind: &str, id: &str, source: String) -> GitImportItem {
+    GitImportItem {
+        kind: kind.to_string(),
+        id: id.to_string(),
+        source,
+        status: "failed".to_string(),
+        message: None,
+        warnings: Vec::new(),
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+    for entry in std::fs::read_dir(from).map_err(|e| format!("Failed to read {}: {}", from.display(), e))?.flatten() {
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir(&path, &target)?;
+        } else {
+            std::fs::copy(&path, &target)
+                .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Validate and install one skill folder
+fn import_skill_dir(folder: &Path, skills_path: &Path, limits: &SkillArchiveLimits, entry: &mut GitImportItem) -> Result<(), String> {
+    if !skill_archive::is_valid_skill_id(&entry.id) {
+        return Err(format!("Invalid skill id '{}'", entry.id));
+    }
+    let destination = skills_path.join(&entry.id);
+    if destination.exists() {
+        entry.status = "skipped".to_string();
+        entry.message = Some(format!("Skill '{}' already exists", entry.id));
+        return Ok(());
+    }
+
+    let mut files = Vec::new();
+    skill_archive::collect_files(folder, folder, &mut files)?;
+    if let Some((_, relative, _)) = files.iter().find(|(_, _, size)| *size > limits.max_file_bytes) {
+        return Err(format!("File '{}' exceeds the per-file limit of {} bytes", relative, limits.max_file_bytes));
+    }
+    if files.iter().map(|(_, _, size)| size).sum::<u64>() > limits.max_archive_bytes {
+        return Err(format!("Skill exceeds the total size limit of {} bytes", limits.max_archive_bytes));
+    }
+
+    let content = std::fs::read_to_string(folder.join("SKILL.md"))
+        .map_err(|e| format!("Failed to read SKILL.md: {}", e))?;
+    let doc = SkillDocument::parse(&content);
+    for key in ["name", "description"] {
+        if doc.get(key).is_none_or(|v| v.trim().is_empty()) {
+            entry.warnings.push(format!("SKILL.md frontmatter has no '{}'", key));
+        }
+    }
+
+    // Copy next to the destination and rename, like `import_skill`
+    let staging = skills_path.join(format!(".{}.importing-{}", entry.id, uuid::Uuid::new_v4()));
+    let installed = copy_dir(folder, &staging).and_then(|_| {
+        std::fs::rename(&staging, &destination).map_err(|e| format!("Failed to install skill: {}", e))
+    });
+    if installed.is_err() {
+        let _ = std::fs::remove_dir_all(&staging);
+    }
+    installed?;
+    entry.status = "imported".to_string();
+    Ok(())
+}
+
+/// Validate (like `validate_workflow`) and install one workflow file
+fn import_workflow_file(file: &Path, workflows_path: &Path, entry: &mut GitImportItem) -> Result<(), String> {
+    let content = std::fs::read_to_string(file).map_err(|e| format!("Failed to read workflow: {}", e))?;
+    if !is_workflow_yaml(&content) {
+        entry.status = "skipped".to_string();
+        entry.message = Some("Not a workflow (needs top-level name: and steps:)".to_string());
+        return Ok(());
+    }
+
+    let file_name = file.file_name().unwrap_or_default();
+    let destination = workflows_path.join(file_name);
+    if destination.exists() {
+        entry.status = "skipped".to_string();
+        entry.message = Some(format!("Workflow '{}' already exists", entry.id));
+        return Ok(());
+    }
+
+    let line = |d: &crate::workflow_validation::Diagnostic| format!("line {}: {}", d.line, d.message);
+    let (errors, warnings) = crate::workflow_validation::validate_yaml(&content);
+    if !errors.is_empty() {
+        return Err(format!("Invalid workflow: {}", errors.iter().map(line).collect::<Vec<_>>().join("; ")));
+    }
+    entry.warnings.extend(warnings.iter().map(line));
+
+    std::fs::create_dir_all(workflows_path)
+        .map_err(|e| format!("Failed to create workflows folder: {}", e))?;
+    std::fs::write(&destination, content).map_err(|e| format!("Failed to save workflow: {}", e))?;
+    entry.status = "imported".to_string();
+    Ok(())
+}
+
+/// Discover and import everything under `root/subpath` of an extracted checkout
+fn import_checkout(
+    root: &Path,
+    subpath: &Path,
+    kind: GitImportKind,
+    skills_path: &Path,
+    workflows_path: &Path,
+    reporter: &ProgressReporter,
+) -> Result<(Vec<GitImportItem>, bool), String> {
+    let base = root.join(subpath);
+    if !base.is_dir() {
+        return Err(format!("Path '{}' not found in repository", subpath.display()));
+    }
+
+    let (mut skills, mut workflows) = (Vec::new(), Vec::new());
+    discover(&base, &mut skills, &mut workflows);
+    if !kind.skills() {
+        skills.clear();
+    }
+    if !kind.workflows() {
+        workflows.clear();
+    }
+
+    let limits = SkillArchiveLimits::load();
+    let total = Some((skills.len() + workflows.len()) as u64);
+    let source = |path: &Path| path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/");
+    let mut items = Vec::new();
+
+    let work = skills.iter().map(|p| (crate::name_lint::KIND_SKILL, p)).chain(workflows.iter().map(|p| (crate::name_lint::KIND_WORKFLOW, p)));
+    for (done, (item_kind, path)) in work.enumerate() {
+        if reporter.is_cancelled() {
+            return Ok((items, true));
+        }
+        let id = if item_kind == crate::name_lint::KIND_SKILL {
+            path.file_name().unwrap_or_default().to_string_lossy().to_string()
+        } else {
+            path.file_stem().unwrap_or_default().to_string_lossy().to_string()
+        };
+        reporter.report(done as u64, total, Some(format!("Importing {}", id)));
+
+        let mut entry = item(item_kind, &id, source(path));
+        let result = if item_kind == crate::name_lint::KIND_SKILL {
+            import_skill_dir(path, skills_path, &limits, &mut entry)
+        } else {
+            import_workflow_file(path, workflows_path, &mut entry)
+        };
+        if let Err(e) = result {
+            entry.status = "failed".to_string();
+            entry.message = Some(e);
+        }
+        items.push(entry);
+    }
+
+    Ok((items, false))
+}
+
+async fn run_import(
+    app: &tauri::AppHandle,
+    url: &str,
+    git_ref: Option<String>,
+    subpath: Option<String>,
+    kind: GitImportKind,
+) -> Result<GitImportReport, String> {
+    let repo = parse_repo_url(url)?;
+    let subpath = safe_subpath(subpath.as_deref())?;
+    let git_ref = git_ref.filter(|r| !r.trim().is_empty());
+    let limits = SkillArchiveLimits::load();
+    let reporter = Arc::new(ProgressReporter::start(app, KIND_GIT_IMPORT));
+
+    // Removed when this function returns, whatever the outcome
+    let checkout = TempCheckout::create(&std::env::temp_dir())?;
+    let zip_path = checkout.dir.join("repo.zip");
+    let root = checkout.dir.join("repo");
+
+    download_zipball(&repo, git_ref.as_deref(), github_token(app).as_deref(), &zip_path, &limits, &reporter).await?;
+    reporter.report(0, None, Some("Extracting".to_string()));
+
+    let (items, cancelled) = tauri::async_runtime::spawn_blocking({
+        let reporter = reporter.clone();
+        move || {
+            extract_zipball(&zip_path, &root, &limits)?;
+            let skills_path = crate::get_skills_path();
+            let workflows_path = crate::get_workflows_path();
+            import_checkout(&root, &subpath, kind, &skills_path, &workflows_path, &reporter)
+        }
+    })
+    .await
+    .map_err(|e| format!("Import task failed: {}", e))??;
+
+    if items.iter().any(|i| i.kind == crate::name_lint::KIND_SKILL && i.status == "imported") {
+        crate::skill_index::invalidate();
+    }
+
+    Ok(GitImportReport {
+        operation_id: reporter.id().to_string(),
+        repository: format!("{}/{}", repo.owner, repo.repo),
+        git_ref,
+        imported: items.iter().filter(|i| i.status == "imported").count(),
+        items,
+        cancelled,
+    })
+}
+
+/// Import skills and/or workflows from a GitHub repository
+#[tauri::command]
+pub async fn import_from_git(
+    app: tauri::AppHandle,
+    url: String,
+    git_ref: Option<String>,
+    subpath: Option<String>,
+    kind: Option<GitImportKind>,
+) -> Result<GitImportReport, String> {
+    crate::command_metrics::timed("import_from_git", async move {
+        let args = serde_json::json!({ "url": url, "ref": git_ref, "subpath": subpath });
+        let result = run_import(&app, &url, git_ref, subpath, kind.unwrap_or_default()).await;
+        crate::audit::record("import_from_git", args, crate::audit::ORIGIN_COMMAND, &result);
+        result
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(label: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("vibecode-git-import-test-{}-{}", label, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_parse_repo_url_forms() {
+        let expected = GitHubRepo { owner: "acme".to_string(), repo: "skills".to_string() };
+        for url in [
+            "https://github.com/acme/skills",
+            "https://github.com/acme/skills.git",
+            "https://github.com/acme/skills/tree/main/finance",
+            "git@github.com:acme/skills.git",
+            "acme/skills",
+        ] {
+            assert_eq!(parse_repo_url(url).unwrap(), expected, "{}", url);
+        }
+        assert!(parse_repo_url("https://gitlab.com/acme/skills").is_err());
+        assert!(parse_repo_url("skills").is_err());
+        assert!(safe_subpath(Some("../etc")).is_err());
+        assert_eq!(safe_subpath(Some("/skills/finance/")).unwrap(), PathBuf::from("skills/finance"));
+        assert_eq!(ref_path("release/v1 beta#2").unwrap(), "release/v1%20beta%232");
+        assert!(ref_path("../../user").is_err());
+        assert!(ref_path("main?archive_format=tar").unwrap().starts_with("main%3F"));
+    }
+
+    #[test]
+    fn test_zipball_import_reports_each_item_and_cleans_up() {
+        let root = temp_root("import");
+        let skills_path = root.join("skills");
+        let workflows_path = root.join("workflows");
+        std::fs::create_dir_all(skills_path.join("existing")).unwrap();
+
+        // A GitHub-style zipball with everything under one top folder
+        let zip_path = root.join("repo.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        let files = [
+            ("acme-skills-abc123/lib/pdf-tools/SKILL.md", "---\nname: PDF Tools\ndescription: PDFs\n---\n"),
+            ("acme-skills-abc123/lib/pdf-tools/scripts/run.py", "print('hi')\n"),
+            ("acme-skills-abc123/lib/existing/SKILL.md", "---\nname: Existing\n---\n"),
+            (
+                "acme-skills-abc123/lib/release.yaml",
+                "name: release\ndescription: Ship it\nsteps:\n  - name: Build\n    agent: api\n    prompt: Build\n",
+            ),
+            ("acme-skills-abc123/lib/broken.yaml", "name: broken\nsteps:\n  - name: Build\n"),
+            ("acme-skills-abc123/lib/config.yml", "debug: true\n"),
+            ("acme-skills-abc123/lib/.github/ci.yaml", "name: ci\nsteps:\n"),
+            ("acme-skills-abc123/README.md", "# skills\n"),
+        ];
+        for (name, content) in files {
+            zip.start_file(name, zip::write::FileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let checkout = TempCheckout::create(&root).unwrap();
+        let checkout_dir = checkout.dir.clone();
+        let extracted = checkout.dir.join("repo");
+        extract_zipball(&zip_path, &extracted, &SkillArchiveLimits::default()).unwrap();
+        assert!(extracted.join("README.md").exists());
+
+        let reporter = ProgressReporter::detached(KIND_GIT_IMPORT);
+        let (items, cancelled) =
+            import_checkout(&extracted, Path::new("lib"), GitImportKind::Both, &skills_path, &workflows_path, &reporter).unwrap();
+        assert!(!cancelled);
+        let summary: Vec<(&str, &str, &str)> =
+            items.iter().map(|i| (i.kind.as_str(), i.id.as_str(), i.status.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("skill", "existing", "skipped"),
+                ("skill", "pdf-tools", "imported"),
+                ("workflow", "broken", "failed"),
+                ("workflow", "config", "skipped"),
+                ("workflow", "release", "imported"),
+            ]
+        );
+        assert!(items[1].warnings.is_empty());
+        assert!(items[2].message.as_deref().unwrap().contains("has no `agent`"));
+        assert!(!workflows_path.join("broken.yaml").exists());
+        assert!(skills_path.join("pdf-tools").join("scripts").join("run.py").exists());
+        assert!(workflows_path.join("release.yaml").exists());
+        assert!(!workflows_path.join("ci.yaml").exists());
+
+        // Only skills this time: nothing new, everything already there
+        let (items, _) =
+            import_checkout(&extracted, Path::new("lib"), GitImportKind::Skills, &skills_path, &workflows_path, &reporter).unwrap();
+        assert!(items.iter().all(|i| i.kind == "skill" && i.status == "skipped"));
+        assert!(import_checkout(&extracted, Path::new("missing"), GitImportKind::Both, &skills_path, &workflows_path, &reporter).is_err());
+
+        drop(checkout);
+        assert!(!checkout_dir.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_zipball_extraction_is_limited() {
+        let root = temp_root("limits");
+        let zip_path = root.join("repo.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        for name in ["a", "b", "c"] {
+            zip.start_file(format!("acme-skills-abc123/{}.bin", name), zip::write::FileOptions::default()).unwrap();
+            zip.write_all(&[7u8; 1024]).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let extract = |limits: SkillArchiveLimits| extract_zipball(&zip_path, &root.join(uuid::Uuid::new_v4().to_string()), &limits);
+        assert!(extract(SkillArchiveLimits::default()).is_ok());
+        let err = extract(SkillArchiveLimits { max_entries: 2, ..Default::default() }).unwrap_err();
+        assert!(err.contains("more than 2"), "{}", err);
+        let err = extract(SkillArchiveLimits { max_file_bytes: 1000, ..Default::default() }).unwrap_err();
+        assert!(err.contains("per-file limit"), "{}", err);
+        let err = extract(SkillArchiveLimits { max_archive_bytes: 2048, ..Default::default() }).unwrap_err();
+        assert!(err.contains("total size limit"), "{}", err);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_checkout_is_removed_when_extraction_fails() {
+        let root = temp_root("cleanup");
+        let zip_path = root.join("broken.zip");
+        std::fs::write(&zip_path, "not a zip").unwrap();
+
+        let checkout_dir = {
+            let checkout = TempCheckout::create(&root).unwrap();
+            assert!(extract_zipball(&zip_path, &checkout.dir.join("repo"), &SkillArchiveLimits::default()).is_err());
+            checkout.dir.clone()
+        };
+        assert!(!checkout_dir.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,335 @@
+/// Git Log - Commit history of the open project
+///
+/// `get_git_log` pages through `git log` of the project folder, newest first:
+/// at most 200 commits per call (50 by default), `skip` to go further back,
+/// and `has_more` when there is more. Each commit has its files with lines
+/// added and removed from `--numstat`, renames paired up. `path_filter` (a
+/// file or folder, absolute or relative to the project) limits both the
+/// commits and their file lists to what touched it. `get_commit_diff` is one
+/// commit's hunks per file against its first parent (the empty tree for a
+/// root commit), cut per file like `review_diff` unless `full` is set.
+/// Errors are `GitDiffError`s.
+
+use std::path::Path;
+use serde::Serialize;
+
+use crate::git_diff::{self, GitDiffError};
+use crate::review_diff::{self, DiffPart};
+
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 200;
+
+/// Fields of a commit record: hash, author, email, date, subject
+const LOG_FORMAT: &str = "--format=%x1e%H%x00%an%x00%ae%x00%aI%x00%s%x00";
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct CommitFileStat {
+    /// Relative to the repository root
+    pub path: String,
+    pub old_path: Option<String>,
+    pub lines_added: u32,
+    pub lines_removed: u32,
+    /// No line counts: git found the file binary
+    pub binary: bool,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct CommitSummary {
+    pub hash: String,
+    pub author: String,
+    pub email: String,
+    /// RFC 3339
+    pub date: String,
+    pub subject: String,
+    pub files: Vec<CommitFileStat>,
+    pub lines_added: u32,
+    pub lines_removed: u32,
+}
+
+/// Result of `get_git_log`
+#[derive(Debug, Serialize)]
+pub struct GitLogPage {
+    pub commits: Vec<CommitSummary>,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommitFileDiff {
+    pub path: String,
+    pub old_path: Option<String>,
+    /// "added" | "modified" | "deleted" | "renamed"
+    pub status: String,
+    #[serde(flatten)]
+    pub diff: DiffPart,
+}
+
+/// Result of `get_commit_diff`
+#[derive(Debug, Serialize)]
+pub struct CommitDiff {
+    pub hash: String,
+    pub parent: Option<String>,
+    pub author: String,
+    pub email: String,
+    pub date: String,
+    pub subject: String,
+    /// Full message
+    pub message: String,
+    pub files: Vec<CommitFileDiff>,
+}
+
+/// Commits of `git log --numstat -z` output in `LOG_FORMAT`
+fn parse_log(output: &str) -> Vec<CommitSummary> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.split('\0');
+            let mut next = || fields.next().map(str::to_string);
+            let (hash, author, email, date, subject) = (next()?, next()?, next()?, next()?, next()?);
+
+            let mut files = Vec::new();
+            // Numstat entries follow, "-" counts for binary files; a rename
+            // has an empty path, then the old and the new path
+            let mut rest = fields.map(|field| field.trim_start_matches('\n'));
+            while let Some(entry) = rest.next() {
+                let mut parts = entry.splitn(3, '\t');
+                let (Some(added), Some(removed), Some(path)) = (parts.next(), parts.next(), parts.next()) else { continue };
+                let (path, old_path) = if path.is_empty() {
+                    let old = rest.next().unwrap_or_default().to_string();
+                    (rest.next().unwrap_or_default().to_string(), Some(old))
+                } else {
+                    (path.to_string(), None)
+                };
+                files.push(CommitFileStat {
+                    path,
+                    old_path,
+                    lines_added: added.parse().unwrap_or(0),
+                    lines_removed: removed.parse().unwrap_or(0),
+                    binary: added == "-",
+                });
+            }
+            Some(CommitSummary {
+                lines_added: files.iter().map(|f| f.lines_added).sum(),
+                lines_removed: files.iter().map(|f| f.lines_removed).sum(),
+                hash,
+                author,
+                email,
+                date,
+                subject,
+                files,
+            })
+        })
+        .collect()
+}
+
+fn log(project: &Path, limit: u32, skip: u32, path_filter: Option<&str>) -> Result<GitLogPage, GitDiffError> {
+    let root = git_diff::repository_root(project)?;
+    let pathspec = match path_filter.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => format!(":(top,literal){}", git_diff::repository_path(&root, project, path)?),
+        None => ".".to_string(),
+    };
+    // Fails before the first commit
+    if !git_diff::git(project, &["rev-parse", "--verify", "--quiet", "HEAD"])?.status.success() {
+        return Ok(GitLogPage { commits: Vec::new(), has_more: false });
+    }
+    let limit = limit.clamp(1, MAX_LIMIT);
+    let (skip_arg, count_arg) = (format!("--skip={}", skip), format!("--max-count={}", limit + 1));
+    let output = git_diff::git(
+        project,
+        &["-c", "core.quotePath=false", "log", "--no-color", "--numstat", "-z", "-M", LOG_FORMAT, &skip_arg, &count_arg, "--", &pathspec],
+    )?;
+    if !output.status.success() {
+        return Err(git_diff::git_failure(&output));
+    }
+    let mut commits = parse_log(&String::from_utf8_lossy(&output.stdout));
+    // One more than asked for tells whether there is a next page
+    let has_more = commits.len() > limit as usize;
+    commits.truncate(limit as usize);
+    Ok(GitLogPage { commits, has_more })
+}
+
+/// Files of `git diff --name-status -z -M` output: status, path, old path
+fn parse_name_status(output: &str) -> Vec<(&'static str, String, Option<String>)> {
+    let mut fields = output.split('\0').filter(|f| !f.is_empty());
+    let mut files = Vec::new();
+    while let Some(status) = fields.next() {
+        let Some(path) = fields.next() else { break };
+        let entry = match status.chars().next() {
+            Some('R') => ("renamed", fields.next().unwrap_or_default().to_string(), Some(path.to_string())),
+            // A copy adds the file it copied to
+            Some('C') => ("added", fields.next().unwrap_or_default().to_string(), None),
+            Some('A') => ("added", path.to_string(), None),
+            Some('D') => ("deleted", path.to_string(), None),
+            _ => ("modified", path.to_string(), None),
+        };
+        files.push(entry);
+    }
+    files
+}
+
+fn commit_diff(project: &Path, hash: &str, full: bool) -> Result<CommitDiff, GitDiffError> {
+    // Also keeps it from being read as an option
+    if hash.len() < 4 || hash.len() > 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(GitDiffError::Git(format!("Not a commit hash: {}", hash)));
+    }
+    git_diff::repository_root(project)?;
+    let commit = format!("{}^{{commit}}", hash);
+    let show = git_diff::git(project, &["show", "--no-patch", "--format=%H%x00%P%x00%an%x00%ae%x00%aI%x00%s%x00%B", &commit])?;
+    if !show.status.success() {
+        return Err(GitDiffError::Git(format!("Unknown commit: {}", hash)));
+    }
+    let show = String::from_utf8_lossy(&show.stdout).into_owned();
+    let fields: Vec<&str> = show.splitn(7, '\0').collect();
+    let [hash, parents, author, email, date, subject, message] = fields[..] else {
+        return Err(GitDiffError::Git(format!("Unexpected git show output for {}", hash)));
+    };
+    let parent = parents.split(' ').next().filter(|p| !p.is_empty()).map(str::to_string);
+    let base = parent.as_deref().unwrap_or(git_diff::EMPTY_TREE);
+
+    let run_diff = |extra: &[&str]| -> Result<String, GitDiffError> {
+        let mut args = vec!["-c", "core.quotePath=false", "diff", "--no-color", "-M"];
+        args.extend(extra);
+        args.extend([base, hash, "--", "."]);
+        let output = git_diff::git(project, &args)?;
+        if !output.status.success() {
+            return Err(git_diff::git_failure(&output));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    let names = run_diff(&["--name-status", "-z"])?;
+    let patch = run_diff(&[])?;
+    let chunks = review_diff::split_files(&patch);
+
+    let files = parse_name_status(&names)
+        .into_iter()
+        .map(|(status, path, old_path)| {
+            let header = format!("diff --git a/{} b/{}", old_path.as_deref().unwrap_or(&path), path);
+            let mut diff = chunks.get(header.as_str()).map_or_else(|| review_diff::chunk_part(""), |chunk| review_diff::chunk_part(chunk));
+            if !full {
+                let mut budget = review_diff::MAX_LINES_PER_FILE;
+                review_diff::truncate(&mut diff, &mut budget);
+            }
+            CommitFileDiff { path, old_path, status: status.to_string(), diff }
+        })
+        .collect();
+
+    Ok(CommitDiff {
+        hash: hash.to_string(),
+        parent,
+        author: author.to_string(),
+        email: email.to_string(),
+        date: date.to_string(),
+        subject: subject.to_string(),
+        message: message.trim_end().to_string(),
+        files,
+    })
+}
+
+fn current_project() -> Result<String, GitDiffError> {
+    crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()).ok_or(GitDiffError::NoProject)
+}
+
+/// A page of the current project's commits, newest first, optionally only
+/// those touching `path_filter`
+#[tauri::command]
+pub async fn get_git_log(limit: Option<u32>, skip: Option<u32>, path_filter: Option<String>) -> Result<GitLogPage, GitDiffError> {
+    crate::command_metrics::timed("get_git_log", async move {
+        let project = current_project()?;
+        tauri::async_runtime::spawn_blocking(move || {
+            log(Path::new(&project), limit.unwrap_or(DEFAULT_LIMIT), skip.unwrap_or(0), path_filter.as_deref())
+        })
+        .await
+        .map_err(|e| GitDiffError::Git(format!("git log failed: {}", e)))?
+    }).await
+}
+
+/// Message and per-file hunks of one commit; with `full`, not cut at the
+/// per-file limit
+#[tauri::command]
+pub async fn get_commit_diff(hash: String, full: Option<bool>) -> Result<CommitDiff, GitDiffError> {
+    crate::command_metrics::timed("get_commit_diff", async move {
+        let project = current_project()?;
+        tauri::async_runtime::spawn_blocking(move || commit_diff(Path::new(&project), &hash, full.unwrap_or(false)))
+            .await
+            .map_err(|e| GitDiffError::Git(format!("git diff failed: {}", e)))?
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_and_name_status_are_parsed() {
+        let output = "\x1ebbbb\0B\0b@example.com\x002026-10-16T09:00:00+00:00\0second\0\0\n1\t0\t\0a b.txt\0c.txt\0\
+                      \x1eaaaa\0A\0a@example.com\x002026-10-15T09:00:00+00:00\0first\0\0\n2\t1\ta b.txt\0-\t-\tlogo.png\0\
+                      \x1ecccc\0C\0c@example.com\x002026-10-14T09:00:00+00:00\0empty\0\0";
+        let commits = parse_log(output);
+        assert_eq!(commits.len(), 3);
+        assert_eq!((commits[0].hash.as_str(), commits[0].subject.as_str()), ("bbbb", "second"));
+        assert_eq!(
+            commits[0].files,
+            vec![CommitFileStat {
+                path: "c.txt".to_string(),
+                old_path: Some("a b.txt".to_string()),
+                lines_added: 1,
+                lines_removed: 0,
+                binary: false
+            }]
+        );
+        assert_eq!((commits[1].lines_added, commits[1].lines_removed), (2, 1));
+        assert!(commits[1].files[1].binary);
+        assert!(commits[2].files.is_empty());
+
+        let names = parse_name_status("M\0src/main.rs\0R087\0old.rs\0new.rs\0A\0added.rs\0D\0gone.rs\0");
+        let expected = [
+            ("modified", "src/main.rs", None),
+            ("renamed", "new.rs", Some("old.rs")),
+            ("added", "added.rs", None),
+            ("deleted", "gone.rs", None),
+        ];
+        assert_eq!(names, expected.map(|(s, p, o)| (s, p.to_string(), o.map(str::to_string))).to_vec());
+    }
+
+    #[test]
+    fn test_history_pages_and_commit_diffs() {
+        if git_diff::git(&std::env::temp_dir(), &["--version"]).is_err() {
+            return;
+        }
+        let root = std::env::temp_dir().join(format!("vibecode-git-log-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src").join("api")).unwrap();
+        let run = |args: &[&str]| assert!(git_diff::git(&root, args).unwrap().status.success(), "git {:?}", args);
+        run(&["init", "--quiet"]);
+        assert!(log(&root, 10, 0, None).unwrap().commits.is_empty());
+        let commit = |message: &str| {
+            run(&["add", "-A"]);
+            run(&["-c", "user.name=Ada", "-c", "user.email=ada@example.com", "commit", "--quiet", "-m", message]);
+        };
+        std::fs::write(root.join("README.md"), "hello\n").unwrap();
+        commit("readme");
+        for i in 0..3 {
+            std::fs::write(root.join("src").join("api").join(format!("v{}.rs", i)), "fn v() {}\n").unwrap();
+            commit(&format!("api {}", i));
+        }
+        std::fs::write(root.join("README.md"), "hello\nworld\n").unwrap();
+        commit("more readme");
+
+        let first = log(&root, 2, 0, None).unwrap();
+        assert_eq!(first.commits.iter().map(|c| c.subject.as_str()).collect::<Vec<_>>(), vec!["more readme", "api 2"]);
+        assert!(first.has_more);
+        let last = log(&root, 2, 4, None).unwrap();
+        assert_eq!((last.commits.len(), last.has_more), (1, false));
+
+        let api = log(&root, 10, 0, Some("src/api")).unwrap();
+        assert_eq!(api.commits.iter().map(|c| c.subject.as_str()).collect::<Vec<_>>(), vec!["api 2", "api 1", "api 0"]);
+        assert_eq!(api.commits[0].files[0].path, "src/api/v2.rs");
+
+        let diff = commit_diff(&root, &first.commits[0].hash, false).unwrap();
+        assert_eq!((diff.subject.as_str(), diff.files.len()), ("more readme", 1));
+        assert_eq!((diff.files[0].status.as_str(), diff.files[0].diff.lines_added), ("modified", 1));
+        let initial = commit_diff(&root, &last.commits[0].hash, false).unwrap();
+        assert_eq!((initial.parent, initial.files[0].status.as_str()), (None, "added"));
+        assert!(commit_diff(&root, "--all", false).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
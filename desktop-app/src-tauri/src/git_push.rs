@@ -0,0 +1,381 @@
+/// Git Push - Push committed changes of the open project to a remote
+///
+/// `git_push(remote, branch, set_upstream)` runs `git push --porcelain
+/// --progress <remote> <branch>` in the project's repository (by default the
+/// current branch, to the remote it tracks or `origin`), emitting each
+/// progress line git prints as `git-push-progress`. git never prompts: the
+/// user's credential helper, stored credentials or SSH agent are used (their
+/// variables are passed through on top of the minimal child environment), and
+/// when none of them can answer the push fails with kind `auth_failed`
+/// instead of hanging on a prompt nobody sees. A rejection because the
+/// remote has commits the branch lacks is `non_fast_forward` (fetch and
+/// merge or rebase first), a remote that cannot be reached is `network`. On
+/// success the result lists every ref git reported, and the branch status
+/// is refreshed as `git-status-updated`.
+
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::process::Stdio;
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::git_diff::{self, GitDiffError};
+
+pub const GIT_PUSH_PROGRESS_EVENT: &str = "git-push-progress";
+
+/// stderr of a push that could not authenticate without a prompt
+const AUTH_FAILURES: &[&str] = &[
+    "terminal prompts disabled",
+    "could not read username",
+    "could not read password",
+    "authentication failed",
+    "invalid username or password",
+    "permission denied (publickey",
+    "host key verification failed",
+    "the requested url returned error: 401",
+    "the requested url returned error: 403",
+];
+
+/// stderr of a push that did not reach the remote
+const NETWORK_FAILURES: &[&str] = &[
+    "could not resolve host",
+    "could not resolve hostname",
+    "connection refused",
+    "connection timed out",
+    "operation timed out",
+    "network is unreachable",
+    "failed to connect",
+    "connection reset",
+    "couldn't connect to server",
+];
+
+/// Host variables git needs to authenticate: the SSH agent, custom SSH
+/// commands and credential-helper configuration (`*` suffix matches a prefix)
+const AUTH_VARS: &[&str] = &[
+    "SSH_AUTH_SOCK",
+    "SSH_AGENT_PID",
+    "GIT_SSH_COMMAND",
+    "GIT_SSH",
+    "GIT_SSH_VARIANT",
+    "GIT_CONFIG_GLOBAL",
+    "GIT_CONFIG_SYSTEM",
+    "XDG_CONFIG_HOME",
+    "GH_TOKEN",
+    "GITHUB_TOKEN",
+    "GCM_*",
+];
+
+/// Payload of `git-push-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct PushProgress {
+    pub remote: String,
+    pub branch: String,
+    pub line: String,
+}
+
+/// A ref `git push --porcelain` reported
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PushedRef {
+    pub local: String,
+    pub remote: String,
+    /// "fast_forward", "forced", "new", "deleted", "up_to_date" or "rejected"
+    pub status: String,
+    /// git's summary: `abc123..def456`, `[new branch]`, `[rejected] (fetch first)`...
+    pub summary: String,
+}
+
+/// Result of `git_push`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PushResult {
+    pub remote: String,
+    pub branch: String,
+    /// The branch now tracks `remote/branch`
+    pub upstream_set: bool,
+    pub refs: Vec<PushedRef>,
+}
+
+/// Ref lines of `git push --porcelain` output (`<flag>\t<from>:<to>\t<summary>`)
+fn parse_porcelain(output: &str) -> Vec<PushedRef> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let (flag, refs, summary) = (fields.next()?, fields.next()?, fields.next().unwrap_or_default());
+            let status = match flag {
+                " " => "fast_forward",
+                "+" => "forced",
+                "*" => "new",
+                "-" => "deleted",
+                "=" => "up_to_date",
+                "!" => "rejected",
+                _ => return None,
+            };
+            let (local, remote) = refs.split_once(':')?;
+            Some(PushedRef {
+                local: local.to_string(),
+                remote: remote.to_string(),
+                status: status.to_string(),
+                summary: summary.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Why a push failed, from its refs and stderr
+fn classify(refs: &[PushedRef], stderr: &str) -> GitDiffError {
+    let lower = stderr.to_lowercase();
+    let rejected = refs.iter().find(|r| r.status == "rejected");
+    let non_fast_forward = |text: &str| ["non-fast-forward", "fetch first", "stale info"].iter().any(|s| text.contains(s));
+    if rejected.is_some_and(|r| non_fast_forward(&r.summary)) || (rejected.is_none() && non_fast_forward(&lower)) {
+        return GitDiffError::NonFastForward(
+            "The remote has commits this branch does not have: pull (fetch and merge or rebase) before pushing".to_string(),
+        );
+    }
+    if lower.contains("terminal prompts disabled") || lower.contains("could not read username") || lower.contains("could not read password") {
+        return GitDiffError::AuthFailed(
+            "Pushing needs credentials git would have to prompt for: store them with a credential helper \
+             (git config --global credential.helper) or use an SSH key"
+                .to_string(),
+        );
+    }
+    let reason = stderr.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join("\n");
+    if AUTH_FAILURES.iter().any(|s| lower.contains(s)) {
+        return GitDiffError::AuthFailed(format!("The remote refused the credentials: {}", reason));
+    }
+    if NETWORK_FAILURES.iter().any(|s| lower.contains(s)) {
+        return GitDiffError::Network(format!("Could not reach the remote: {}", reason));
+    }
+    match rejected {
+        Some(r) => GitDiffError::Git(format!("git push rejected {}: {}\n{}", r.local, r.summary, reason)),
+        None => GitDiffError::Git(format!("git push failed: {}", reason)),
+    }
+}
+
+fn config_value(root: &Path, key: &str) -> Result<Option<String>, GitDiffError> {
+    let output = git_diff::git(root, &["config", "--get", key])?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(value).filter(|v| output.status.success() && !v.is_empty()))
+}
+
+/// The current branch, and the remote it tracks
+fn defaults(root: &Path) -> Result<(Option<String>, Option<String>), GitDiffError> {
+    let output = git_diff::git(root, &["symbolic-ref", "--quiet", "--short", "HEAD"])?;
+    let branch = Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|b| output.status.success() && !b.is_empty());
+    let remote = match &branch {
+        Some(branch) => config_value(root, &format!("branch.{}.remote", branch))?,
+        None => None,
+    };
+    Ok((branch, remote))
+}
+
+/// The `AUTH_VARS` among `host`
+fn auth_vars(host: impl IntoIterator<Item = (String, String)>) -> Vec<(String, String)> {
+    host.into_iter()
+        .filter(|(name, _)| {
+            AUTH_VARS.iter().any(|var| match var.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == var,
+            })
+        })
+        .collect()
+}
+
+/// Run the push, passing each progress line to `progress`
+fn push(
+    project: &Path,
+    remote: Option<&str>,
+    branch: Option<&str>,
+    set_upstream: bool,
+    mut progress: impl FnMut(&str, &str, &str),
+) -> Result<PushResult, GitDiffError> {
+    let root = git_diff::repository_root(project)?;
+    let (current, tracked) = defaults(&root)?;
+    let branch = match branch.map(str::trim).filter(|b| !b.is_empty()) {
+        Some(branch) => branch.to_string(),
+        None => current.ok_or_else(|| GitDiffError::Git("HEAD is detached: name the branch to push".to_string()))?,
+    };
+    let remote = remote.map(str::trim).filter(|r| !r.is_empty()).map(str::to_string).or(tracked).unwrap_or_else(|| "origin".to_string());
+    // Would be read as options
+    if remote.starts_with('-') || branch.starts_with('-') {
+        return Err(GitDiffError::Git(format!("Invalid remote or branch: {} {}", remote, branch)));
+    }
+
+    let mut command = crate::child_env::system_command("git");
+    command
+        .envs(auth_vars(std::env::vars()))
+        .arg("-C")
+        .arg(&root)
+        // No prompts: credential helpers still answer, nothing asks the user
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_ASKPASS", "")
+        .env("SSH_ASKPASS", "")
+        .env("GCM_INTERACTIVE", "never");
+    let ssh_configured = ["GIT_SSH_COMMAND", "GIT_SSH"].iter().any(|var| std::env::var_os(var).is_some())
+        || config_value(&root, "core.sshCommand")?.is_some();
+    if !ssh_configured {
+        command.env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+    }
+    command.args(["push", "--porcelain", "--progress"]);
+    if set_upstream {
+        command.arg("--set-upstream");
+    }
+    let mut child = command
+        .args([remote.as_str(), branch.as_str()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => GitDiffError::GitNotFound,
+            _ => GitDiffError::Git(format!("Failed to run git: {}", e)),
+        })?;
+
+    let mut stdout = child.stdout.take().ok_or_else(|| GitDiffError::Git("git push has no stdout".to_string()))?;
+    let reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stdout.read_to_string(&mut output);
+        output
+    });
+    // Progress lines end in \r while they count up
+    let mut stderr_text = String::new();
+    if let Some(stderr) = child.stderr.take() {
+        let mut line = Vec::new();
+        let mut last = String::new();
+        for byte in BufReader::new(stderr).bytes().map_while(Result::ok) {
+            if byte != b'\r' && byte != b'\n' {
+                line.push(byte);
+                continue;
+            }
+            let text = String::from_utf8_lossy(&line).trim().to_string();
+            line.clear();
+            if text.is_empty() || text == last {
+                continue;
+            }
+            progress(&remote, &branch, &text);
+            if byte == b'\n' {
+                stderr_text.push_str(&text);
+                stderr_text.push('\n');
+            }
+            last = text;
+        }
+    }
+    let status = child.wait().map_err(|e| GitDiffError::Git(format!("git push failed: {}", e)))?;
+    let refs = parse_porcelain(&reader.join().unwrap_or_default());
+
+    if !status.success() || refs.iter().any(|r| r.status == "rejected") {
+        return Err(classify(&refs, &stderr_text));
+    }
+    Ok(PushResult { remote, branch, upstream_set: set_upstream, refs })
+}
+
+/// Push `branch` (default: the current one) of the open project to `remote`
+/// (default: the one it tracks, or `origin`), making it the branch's
+/// upstream with `set_upstream`
+#[tauri::command]
+pub async fn git_push(
+    app: tauri::AppHandle,
+    remote: Option<String>,
+    branch: Option<String>,
+    set_upstream: bool,
+) -> Result<PushResult, GitDiffError> {
+    crate::command_metrics::timed("git_push", async move {
+        let args = serde_json::json!({ "remote": remote, "branch": branch, "set_upstream": set_upstream });
+        let emitter = app.clone();
+        let result = async {
+            let project = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()).ok_or(GitDiffError::NoProject)?;
+            tauri::async_runtime::spawn_blocking(move || {
+                push(Path::new(&project), remote.as_deref(), branch.as_deref(), set_upstream, |remote, branch, line| {
+                    let progress = PushProgress { remote: remote.to_string(), branch: branch.to_string(), line: line.to_string() };
+                    let _ = emitter.emit(GIT_PUSH_PROGRESS_EVENT, progress);
+                })
+            })
+            .await
+            .map_err(|e| GitDiffError::Git(format!("git push failed: {}", e)))?
+        }
+        .await;
+        crate::audit::record("git_push", args, crate::audit::ORIGIN_COMMAND, &result);
+        crate::git_repository::refresh_in_background(&app);
+        result
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_failures_are_classified() {
+        let refs = parse_porcelain(
+            "To /tmp/remote.git\n\
+             *\trefs/heads/feature:refs/heads/feature\t[new branch]\n\
+             !\trefs/heads/main:refs/heads/main\t[rejected] (fetch first)\n\
+             Done\n",
+        );
+        assert_eq!(refs.iter().map(|r| (r.local.as_str(), r.status.as_str())).collect::<Vec<_>>(), vec![
+            ("refs/heads/feature", "new"),
+            ("refs/heads/main", "rejected"),
+        ]);
+        assert!(matches!(classify(&refs, "error: failed to push some refs"), GitDiffError::NonFastForward(_)));
+        let kind = |stderr: &str| serde_json::to_value(classify(&[], stderr)).unwrap()["kind"].as_str().unwrap().to_string();
+        assert_eq!(kind("fatal: could not read Username for 'https://github.com': terminal prompts disabled"), "auth_failed");
+        assert_eq!(kind("git@github.com: Permission denied (publickey).\nfatal: Could not read from remote repository."), "auth_failed");
+        assert_eq!(kind("fatal: unable to access 'https://github.com/x/y.git/': Could not resolve host: github.com"), "network");
+        assert_eq!(kind("remote: error: GH006: Protected branch update failed"), "git");
+    }
+
+    #[test]
+    fn test_auth_variables_are_forwarded() {
+        let host = [
+            ("SSH_AUTH_SOCK", "/tmp/ssh-agent.sock"),
+            ("GIT_SSH_COMMAND", "ssh -i ~/.ssh/deploy"),
+            ("GCM_CREDENTIAL_STORE", "secretservice"),
+            ("AWS_SECRET_ACCESS_KEY", "hidden"),
+            ("GCM", "no prefix match"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+        let names: Vec<String> = auth_vars(host).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["SSH_AUTH_SOCK", "GIT_SSH_COMMAND", "GCM_CREDENTIAL_STORE"]);
+    }
+
+    #[test]
+    fn test_pushes_to_a_local_remote() {
+        if git_diff::git(&std::env::temp_dir(), &["--version"]).is_err() {
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("vibecode-git-push-{}", uuid::Uuid::new_v4()));
+        let (remote, work, other) = (dir.join("remote.git"), dir.join("work"), dir.join("other"));
+        std::fs::create_dir_all(&work).unwrap();
+        let run = |repo: &Path, args: &[&str]| {
+            let mut all = vec!["-c", "user.name=Ada", "-c", "user.email=ada@example.com"];
+            all.extend(args);
+            assert!(git_diff::git(repo, &all).unwrap().status.success(), "git {:?}", args);
+        };
+        run(&dir, &["init", "--quiet", "--bare", "-b", "main", "remote.git"]);
+        run(&work, &["init", "--quiet", "-b", "main"]);
+        run(&work, &["remote", "add", "origin", &remote.to_string_lossy()]);
+        std::fs::write(work.join("a.txt"), "a\n").unwrap();
+        run(&work, &["add", "a.txt"]);
+        run(&work, &["commit", "--quiet", "-m", "first"]);
+
+        let mut lines = Vec::new();
+        let pushed = push(&work, None, None, true, |_, _, line| lines.push(line.to_string())).unwrap();
+        assert_eq!((pushed.remote.as_str(), pushed.branch.as_str(), pushed.upstream_set), ("origin", "main", true));
+        assert_eq!(pushed.refs.iter().map(|r| r.status.as_str()).collect::<Vec<_>>(), vec!["new"]);
+        let upstream = git_diff::git(&work, &["rev-parse", "--abbrev-ref", "main@{upstream}"]).unwrap();
+        assert_eq!(String::from_utf8_lossy(&upstream.stdout).trim(), "origin/main");
+        let again = push(&work, Some("origin"), Some("main"), false, |_, _, _| {}).unwrap();
+        assert_eq!(again.refs[0].status, "up_to_date");
+
+        run(&dir, &["clone", "--quiet", &remote.to_string_lossy(), "other"]);
+        std::fs::write(other.join("b.txt"), "b\n").unwrap();
+        run(&other, &["add", "b.txt"]);
+        run(&other, &["commit", "--quiet", "-m", "theirs"]);
+        run(&other, &["push", "--quiet", "origin", "main"]);
+        std::fs::write(work.join("a.txt"), "ours\n").unwrap();
+        run(&work, &["commit", "--quiet", "-am", "ours"]);
+        assert!(matches!(push(&work, None, None, false, |_, _, _| {}), Err(GitDiffError::NonFastForward(_))));
+        assert!(matches!(push(&work, Some("--mirror"), None, false, |_, _, _| {}), Err(GitDiffError::Git(_))));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
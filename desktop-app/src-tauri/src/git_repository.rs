@@ -0,0 +1,203 @@
+/// Git Repository - Branch and state of the open project's repository
+///
+/// `get_git_status` reports the branch (None when HEAD is detached), how far
+/// it is ahead of and behind its upstream (0 without one), whether the work
+/// tree has changes (untracked files included), the URL of the branch's
+/// remote (`origin` when it has none) and the last commit, None before the
+/// first. It comes from `git status --porcelain=v2 --branch` run with
+/// `--no-optional-locks`, so it never rewrites the index and sets off the
+/// fs watcher. A project that is not under git is the error kind
+/// `not_a_repository`. `refresh_in_background` emits the same as
+/// `git-status-updated` (null for a project that is not under git); it runs
+/// on project switches, after task and workflow runs, and after file changes
+/// in the project or its `.git` state.
+
+use std::path::Path;
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::git_diff::{self, GitDiffError};
+
+pub const GIT_STATUS_UPDATED_EVENT: &str = "git-status-updated";
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LastCommit {
+    pub hash: String,
+    /// Subject line
+    pub message: String,
+    pub author: String,
+    /// RFC 3339
+    pub time: String,
+}
+
+/// Result of `get_git_status`
+#[derive(Debug, Clone, Serialize, PartialEq, Default)]
+pub struct GitRepoStatus {
+    pub branch: Option<String>,
+    pub detached: bool,
+    pub ahead: u32,
+    pub behind: u32,
+    pub dirty: bool,
+    pub remote_url: Option<String>,
+    pub last_commit: Option<LastCommit>,
+}
+
+/// Branch, ahead/behind and dirty from `git status --porcelain=v2 --branch -z`
+fn parse_branch_status(output: &str) -> GitRepoStatus {
+    let mut status = GitRepoStatus::default();
+    for record in output.split('\0').filter(|r| !r.is_empty()) {
+        let Some(header) = record.strip_prefix("# ") else {
+            status.dirty = true;
+            continue;
+        };
+        match header.split_once(' ') {
+            Some(("branch.head", "(detached)")) => status.detached = true,
+            Some(("branch.head", name)) => status.branch = Some(name.to_string()),
+            Some(("branch.ab", counts)) => {
+                for count in counts.split(' ') {
+                    if let Some(ahead) = count.strip_prefix('+') {
+                        status.ahead = ahead.parse().unwrap_or(0);
+                    } else if let Some(behind) = count.strip_prefix('-') {
+                        status.behind = behind.parse().unwrap_or(0);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    status
+}
+
+/// Last commit from `git log -1 --format=%H%x00%s%x00%an%x00%aI`
+fn parse_last_commit(output: &str) -> Option<LastCommit> {
+    let mut fields = output.trim_end_matches('\n').split('\0');
+    let (hash, message, author, time) = (fields.next()?, fields.next()?, fields.next()?, fields.next()?);
+    Some(LastCommit {
+        hash: hash.to_string(),
+        message: message.to_string(),
+        author: author.to_string(),
+        time: time.to_string(),
+    })
+}
+
+fn config_value(root: &Path, key: &str) -> Result<Option<String>, GitDiffError> {
+    let output = git_diff::git(root, &["config", "--get", key])?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(value).filter(|v| output.status.success() && !v.is_empty()))
+}
+
+fn repo_status(project: &Path) -> Result<GitRepoStatus, GitDiffError> {
+    let root = git_diff::repository_root(project)?;
+    let output = git_diff::git(&root, &["--no-optional-locks", "status", "--porcelain=v2", "--branch", "-z"])?;
+    if !output.status.success() {
+        return Err(git_diff::git_failure(&output));
+    }
+    let mut status = parse_branch_status(&String::from_utf8_lossy(&output.stdout));
+
+    let remote = match &status.branch {
+        Some(branch) => config_value(&root, &format!("branch.{}.remote", branch))?,
+        None => None,
+    };
+    status.remote_url = config_value(&root, &format!("remote.{}.url", remote.as_deref().unwrap_or("origin")))?;
+
+    // Fails before the first commit
+    let log = git_diff::git(&root, &["log", "-1", "--format=%H%x00%s%x00%an%x00%aI"])?;
+    if log.status.success() {
+        status.last_commit = parse_last_commit(&String::from_utf8_lossy(&log.stdout));
+    }
+    Ok(status)
+}
+
+fn current_project() -> Result<String, GitDiffError> {
+    crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()).ok_or(GitDiffError::NoProject)
+}
+
+/// Emit the current project's status as `git-status-updated` in the
+/// background; failures are only logged
+pub fn refresh_in_background(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let Ok(project) = current_project() else { return };
+        match repo_status(Path::new(&project)) {
+            // A project switch meanwhile brings its own status
+            Ok(status) if current_project().ok().as_deref() == Some(project.as_str()) => {
+                let _ = app.emit(GIT_STATUS_UPDATED_EVENT, Some(status));
+            }
+            Ok(_) => {}
+            Err(GitDiffError::NotARepository(_)) => {
+                let _ = app.emit(GIT_STATUS_UPDATED_EVENT, None::<GitRepoStatus>);
+            }
+            Err(e) => eprintln!("Git status: refresh failed: {}", e),
+        }
+    });
+}
+
+/// Branch, upstream distance, dirty state, remote and last commit of the
+/// current project's repository
+#[tauri::command]
+pub async fn get_git_status() -> Result<GitRepoStatus, GitDiffError> {
+    crate::command_metrics::timed("get_git_status", async move {
+        let project = current_project()?;
+        tauri::async_runtime::spawn_blocking(move || repo_status(Path::new(&project)))
+            .await
+            .map_err(|e| GitDiffError::Git(format!("git status failed: {}", e)))?
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_status_and_last_commit_are_parsed() {
+        let output = "# branch.oid 1111111111111111111111111111111111111111\0\
+                      # branch.head feature/login\0\
+                      # branch.upstream origin/feature/login\0\
+                      # branch.ab +2 -5\0\
+                      1 .M N... 100644 100644 100644 1111111 1111111 src/main.rs\0";
+        let status = parse_branch_status(output);
+        assert_eq!((status.branch.as_deref(), status.detached), (Some("feature/login"), false));
+        assert_eq!((status.ahead, status.behind, status.dirty), (2, 5, true));
+
+        let detached = parse_branch_status("# branch.oid 1111111\0# branch.head (detached)\0");
+        assert_eq!((detached.branch, detached.detached, detached.dirty), (None, true, false));
+
+        let commit = parse_last_commit("abc123\0Fix login\0Ada\x002026-10-16T09:30:00+02:00\n").unwrap();
+        assert_eq!((commit.hash.as_str(), commit.message.as_str()), ("abc123", "Fix login"));
+        assert_eq!((commit.author.as_str(), commit.time.as_str()), ("Ada", "2026-10-16T09:30:00+02:00"));
+        assert_eq!(parse_last_commit(""), None);
+    }
+
+    #[test]
+    fn test_repository_status_from_git() {
+        if git_diff::git(&std::env::temp_dir(), &["--version"]).is_err() {
+            return;
+        }
+        let root = std::env::temp_dir().join(format!("vibecode-git-repository-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        assert!(matches!(repo_status(&root), Err(GitDiffError::NotARepository(_))));
+
+        let run = |args: &[&str]| assert!(git_diff::git(&root, args).unwrap().status.success(), "git {:?}", args);
+        run(&["init", "--quiet", "--initial-branch=main"]);
+        let empty = repo_status(&root).unwrap();
+        assert_eq!((empty.branch.as_deref(), empty.dirty, empty.last_commit), (Some("main"), false, None));
+
+        std::fs::write(root.join("a.txt"), "a\n").unwrap();
+        run(&["add", "a.txt"]);
+        run(&["-c", "user.name=Ada", "-c", "user.email=ada@example.com", "commit", "--quiet", "-m", "First"]);
+        run(&["remote", "add", "origin", "https://example.com/repo.git"]);
+        std::fs::write(root.join("b.txt"), "b\n").unwrap();
+
+        let status = repo_status(&root).unwrap();
+        assert!(status.dirty);
+        assert_eq!(status.remote_url.as_deref(), Some("https://example.com/repo.git"));
+        let commit = status.last_commit.unwrap();
+        assert_eq!((commit.message.as_str(), commit.author.as_str()), ("First", "Ada"));
+        assert!(chrono::DateTime::parse_from_rfc3339(&commit.time).is_ok());
+
+        run(&["checkout", "--quiet", "--detach"]);
+        assert!(repo_status(&root).unwrap().detached);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
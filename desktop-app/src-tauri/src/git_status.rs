@@ -0,0 +1,362 @@
+/// Git Status - The changed files list, as git sees the project
+///
+/// `refresh_changed_files` runs `git status --porcelain=v2` in the open
+/// project and replaces `CHANGED_FILES` with what it reports: new and
+/// untracked files as "added", removed ones as "deleted", anything else
+/// (edits, conflicts) as "modified". A rename is a "deleted" entry for the
+/// old path and an "added" one with `renamed_from` for the new path: staged
+/// renames as git pairs them, and an untracked file with exactly the content
+/// a deleted one had in HEAD (how an unstaged rename looks). A file added and
+/// removed again is left out. Line counts come from `git diff --numstat`
+/// against HEAD (the empty tree before the first commit), so a deleted file
+/// counts all its lines removed, and for untracked files from their number
+/// of lines; binary files count 0. Paths are absolute, and entries keep the
+/// `detected_during_task` flag of the entry they replace. The list is
+/// emitted as `changed-files-updated`. `refresh_after_run` does the same in
+/// the background once `execute_task` or `run_workflow` is done, skipping
+/// projects that are not under git. `status` is shared with `review_diff`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::Emitter;
+
+use crate::git_diff::{self, GitDiffError};
+use crate::ChangedFile;
+
+pub const CHANGED_FILES_UPDATED_EVENT: &str = "changed-files-updated";
+
+/// A path in `git status --porcelain=v2 -z` output
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StatusEntry {
+    /// Relative to the repository root, with `/` separators
+    pub path: String,
+    /// Where a renamed file was
+    pub old_path: Option<String>,
+    /// Status letters in the index and in the work tree: `.` unchanged,
+    /// `M`, `A`, `D`, `R`..., `?` for an untracked file
+    pub index: char,
+    pub worktree: char,
+    /// Merge conflict
+    pub unmerged: bool,
+    /// Object name of the file in HEAD, for tracked files
+    pub head_object: Option<String>,
+}
+
+impl StatusEntry {
+    fn has(&self, letter: char) -> bool {
+        self.index == letter || self.worktree == letter
+    }
+
+    /// "added", "deleted" or "modified", as the changed files list has it
+    pub fn change(&self) -> &'static str {
+        if self.has('?') || self.has('A') {
+            "added"
+        } else if self.has('D') {
+            "deleted"
+        } else {
+            "modified"
+        }
+    }
+}
+
+/// Entries of `git status --porcelain=v2 -z` output
+fn parse_status(output: &str) -> Vec<StatusEntry> {
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    let mut entries = Vec::new();
+    while let Some(entry) = fields.next() {
+        // Fields before the path: ordinary 8, renamed 9 (then the old path
+        // as a field of its own), unmerged 10, untracked none
+        let (fields_before, renamed) = match entry.as_bytes()[0] {
+            b'1' => (8, false),
+            b'2' => (9, true),
+            b'u' => (10, false),
+            b'?' => (1, false),
+            _ => continue,
+        };
+        let parts: Vec<&str> = entry.splitn(fields_before + 1, ' ').collect();
+        let Some(path) = parts.get(fields_before) else { continue };
+        let old_path = if renamed { fields.next().map(str::to_string) } else { None };
+        let mut xy = parts.get(1).filter(|_| !entry.starts_with('?')).copied().unwrap_or("??").chars();
+        // hH, after the submodule state and three modes
+        let head_object = parts.get(6).filter(|_| matches!(entry.as_bytes()[0], b'1' | b'2')).map(|h| h.to_string());
+        entries.push(StatusEntry {
+            path: path.to_string(),
+            old_path,
+            index: xy.next().unwrap_or('.'),
+            worktree: xy.next().unwrap_or('.'),
+            unmerged: entry.starts_with('u'),
+            head_object,
+        });
+    }
+    entries
+}
+
+/// Status of the part of `project`'s repository inside it, renames paired
+/// up when `renames` is set
+pub(crate) fn status(project: &Path, renames: bool) -> Result<Vec<StatusEntry>, GitDiffError> {
+    let rename_flag = if renames { "--find-renames" } else { "--no-renames" };
+    let output = git_diff::git(project, &["status", "--porcelain=v2", "-z", rename_flag, "--untracked-files=all", "--", "."])?;
+    if !output.status.success() {
+        return Err(git_diff::git_failure(&output));
+    }
+    Ok(parse_status(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Lines added and removed per path in `git diff --numstat -z` output (None
+/// for binary files)
+fn parse_numstat(output: &str) -> HashMap<String, Option<(u32, u32)>> {
+    output
+        .split('\0')
+        .filter_map(|record| {
+            let mut parts = record.splitn(3, '\t');
+            let (added, removed, path) = (parts.next()?, parts.next()?, parts.next()?);
+            // "-" for binary files
+            Some((path.to_string(), added.parse().ok().zip(removed.parse().ok())))
+        })
+        .collect()
+}
+
+/// Lines in the file at `path`; None for binary files
+pub(crate) fn count_lines(path: &Path) -> Option<u32> {
+    let Ok(bytes) = std::fs::read(path) else { return Some(0) };
+    if bytes.contains(&0) {
+        return None;
+    }
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    let unterminated = bytes.last().is_some_and(|&b| b != b'\n');
+    Some((newlines + usize::from(unterminated)) as u32)
+}
+
+/// Object names of the files at `paths` (relative to `root`), as git would
+/// store them
+fn hash_objects(root: &Path, paths: &[&str]) -> Result<Vec<String>, GitDiffError> {
+    let mut hashes = Vec::with_capacity(paths.len());
+    // A few hundred paths per run keeps the command line short
+    for chunk in paths.chunks(200) {
+        let mut args = vec!["hash-object", "--"];
+        args.extend(chunk);
+        let output = git_diff::git(root, &args)?;
+        if !output.status.success() {
+            return Err(git_diff::git_failure(&output));
+        }
+        hashes.extend(String::from_utf8_lossy(&output.stdout).lines().map(str::to_string));
+    }
+    Ok(hashes)
+}
+
+/// Turn each untracked file whose object name (from `hash`) is that of a
+/// deleted file into a rename of it, dropping the deleted entry
+fn pair_renames(
+    entries: Vec<StatusEntry>,
+    hash: impl FnOnce(&[&str]) -> Result<Vec<String>, GitDiffError>,
+) -> Result<Vec<StatusEntry>, GitDiffError> {
+    let deleted = |e: &StatusEntry| e.old_path.is_none() && !e.unmerged && e.worktree == 'D' && e.index == '.';
+    let untracked: Vec<&str> = entries.iter().filter(|e| e.worktree == '?').map(|e| e.path.as_str()).collect();
+    if untracked.is_empty() || !entries.iter().any(deleted) {
+        return Ok(entries);
+    }
+    let hashes: HashMap<String, String> = untracked.iter().map(|p| p.to_string()).zip(hash(&untracked)?).collect();
+
+    let mut renamed_from: HashMap<String, String> = HashMap::new();
+    for entry in entries.iter().filter(|e| deleted(e)) {
+        let Some(object) = &entry.head_object else { continue };
+        let new = hashes
+            .iter()
+            .filter(|(path, hash)| *hash == object && !renamed_from.contains_key(*path))
+            .map(|(path, _)| path)
+            .min();
+        if let Some(new) = new {
+            renamed_from.insert(new.clone(), entry.path.clone());
+        }
+    }
+    let moved: Vec<String> = renamed_from.values().cloned().collect();
+    Ok(entries
+        .into_iter()
+        .filter(|e| !(deleted(e) && moved.contains(&e.path)))
+        .map(|mut e| {
+            if let Some(old) = renamed_from.get(&e.path) {
+                e.old_path = Some(old.clone());
+            }
+            e
+        })
+        .collect())
+}
+
+/// Changed files list entries for a status entry: path, change, and the
+/// old path of a rename
+fn list_entries(entry: StatusEntry) -> Vec<(String, &'static str, Option<String>)> {
+    // Added, then removed from disk: nothing changed against HEAD
+    if entry.index == 'A' && entry.worktree == 'D' {
+        return Vec::new();
+    }
+    match entry.old_path {
+        Some(old) if entry.worktree == 'D' => vec![(old, "deleted", None)],
+        Some(old) => vec![(old.clone(), "deleted", None), (entry.path, "added", Some(old))],
+        None => {
+            let change = entry.change();
+            vec![(entry.path, change, None)]
+        }
+    }
+}
+
+/// Lines added and removed against HEAD (the empty tree before the first
+/// commit) per path relative to the repository root, for `pathspecs` in
+/// `project`; None for binary files
+pub(crate) fn line_counts(project: &Path, pathspecs: &[&str]) -> Result<HashMap<String, Option<(u32, u32)>>, GitDiffError> {
+    let head = git_diff::git(project, &["rev-parse", "--verify", "--quiet", "HEAD"])?.status.success();
+    let base = if head { "HEAD" } else { git_diff::EMPTY_TREE };
+    let mut args = vec!["diff", "--numstat", "-z", "--no-renames", base, "--"];
+    args.extend(pathspecs);
+    let numstat = git_diff::git(project, &args)?;
+    if !numstat.status.success() {
+        return Err(git_diff::git_failure(&numstat));
+    }
+    Ok(parse_numstat(&String::from_utf8_lossy(&numstat.stdout)))
+}
+
+/// Changed files of `project` (the part of its repository inside it)
+fn changed_files(project: &Path) -> Result<Vec<ChangedFile>, GitDiffError> {
+    let root = git_diff::repository_root(project)?;
+    let entries = pair_renames(status(project, true)?, |paths| hash_objects(&root, paths))?;
+    let counts = line_counts(project, &["."])?;
+
+    let absolute = |path: &str| root.join(path).to_string_lossy().to_string();
+    Ok(entries
+        .into_iter()
+        .flat_map(list_entries)
+        .map(|(relative, status, renamed_from)| {
+            let path = root.join(&relative);
+            let counts = match counts.get(&relative) {
+                Some(&counts) => counts,
+                None if status == "added" => count_lines(&path).map(|lines| (lines, 0)),
+                None => Some((0, 0)),
+            };
+            ChangedFile {
+                path: path.to_string_lossy().to_string(),
+                status: status.to_string(),
+                lines_added: counts.map(|c| c.0),
+                lines_removed: counts.map(|c| c.1),
+                renamed_from: renamed_from.as_deref().map(absolute),
+                detected_during_task: false,
+            }
+        })
+        .collect())
+}
+
+fn current_project() -> Option<String> {
+    crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone())
+}
+
+/// Rebuild the list from git, store it and emit it
+async fn refresh(app: &tauri::AppHandle) -> Result<Vec<ChangedFile>, GitDiffError> {
+    let project = current_project().ok_or(GitDiffError::NoProject)?;
+    let dir = project.clone();
+    let mut files = tauri::async_runtime::spawn_blocking(move || changed_files(Path::new(&dir)))
+        .await
+        .map_err(|e| GitDiffError::Git(format!("git status failed: {}", e)))??;
+
+    // A project switch meanwhile brought that project's own list
+    if current_project().as_deref() == Some(project.as_str()) {
+        let mut changed = crate::CHANGED_FILES.write().map_err(|e| GitDiffError::Git(format!("Lock error: {}", e)))?;
+        for file in &mut files {
+            file.detected_during_task = changed.iter().any(|old| old.path == file.path && old.detected_during_task);
+        }
+        *changed = files.clone();
+        let _ = app.emit(CHANGED_FILES_UPDATED_EVENT, &files);
+    }
+    Ok(files)
+}
+
+/// `refresh` in the background after a run, with the repository status;
+/// failures are only logged
+pub fn refresh_after_run(app: &tauri::AppHandle) {
+    crate::git_repository::refresh_in_background(app);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match refresh(&app).await {
+            Ok(_) | Err(GitDiffError::NoProject) | Err(GitDiffError::NotARepository(_)) => {}
+            Err(e) => eprintln!("Changed files: refresh failed: {}", e),
+        }
+    });
+}
+
+/// Replace the changed files list with the project's git status
+#[tauri::command]
+pub async fn refresh_changed_files(app: tauri::AppHandle) -> Result<Vec<ChangedFile>, GitDiffError> {
+    crate::command_metrics::timed("refresh_changed_files", async move { refresh(&app).await }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_and_numstat_are_parsed() {
+        let status = "1 .M N... 100644 100644 100644 1111111 1111111 src/main.rs\0\
+                      1 A. N... 000000 100644 100644 0000000 2222222 src/new file.rs\0\
+                      1 .D N... 100644 100644 000000 3333333 3333333 old.rs\0\
+                      2 R. N... 100644 100644 100644 4444444 4444444 R100 moved.rs\0before.rs\0\
+                      u UU N... 100644 100644 100644 100644 5555555 6666666 7777777 conflict.rs\0\
+                      ? notes/todo.md\0";
+        let entries = parse_status(status);
+        let changes: Vec<(&str, &str)> = entries.iter().map(|e| (e.path.as_str(), e.change())).collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("src/main.rs", "modified"),
+                ("src/new file.rs", "added"),
+                ("old.rs", "deleted"),
+                ("moved.rs", "modified"),
+                ("conflict.rs", "modified"),
+                ("notes/todo.md", "added"),
+            ]
+        );
+        assert_eq!((entries[3].old_path.as_deref(), entries[3].index, entries[3].worktree), (Some("before.rs"), 'R', '.'));
+        assert!(entries[4].unmerged && !entries[0].unmerged);
+        assert_eq!(entries[2].head_object.as_deref(), Some("3333333"));
+        assert_eq!((entries[4].head_object.as_deref(), entries[5].head_object.as_deref()), (None, None));
+        assert_eq!((entries[5].index, entries[5].worktree), ('?', '?'));
+
+        let counts = parse_numstat("3\t1\tsrc/main.rs\0-\t-\tlogo.png\0");
+        assert_eq!(counts.get("src/main.rs"), Some(&Some((3, 1))));
+        assert_eq!(counts.get("logo.png"), Some(&None));
+
+        let dir = std::env::temp_dir().join(format!("vibecode-git-status-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "one\ntwo\nthree").unwrap();
+        std::fs::write(dir.join("b.bin"), b"\x00\x01\n").unwrap();
+        assert_eq!((count_lines(&dir.join("a.txt")), count_lines(&dir.join("b.bin"))), (Some(3), None));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_renames_pair_up_as_deleted_and_added() {
+        let status = "1 .D N... 100644 100644 000000 aaaaaaa aaaaaaa old.rs\0\
+                      1 .D N... 100644 100644 000000 bbbbbbb bbbbbbb gone.rs\0\
+                      2 RD N... 100644 100644 000000 ccccccc ccccccc R100 moved.rs\0was.rs\0\
+                      1 AD N... 000000 100644 000000 0000000 ddddddd scratch.rs\0\
+                      ? new.rs\0\
+                      ? other.rs\0";
+        let hash = |paths: &[&str]| {
+            assert_eq!(paths, ["new.rs", "other.rs"]);
+            Ok(vec!["aaaaaaa".to_string(), "eeeeeee".to_string()])
+        };
+        let entries = pair_renames(parse_status(status), hash).unwrap();
+        let listed: Vec<(String, &str, Option<String>)> = entries.into_iter().flat_map(list_entries).collect();
+        let expected = [
+            ("gone.rs", "deleted", None),
+            ("was.rs", "deleted", None),
+            ("old.rs", "deleted", None),
+            ("new.rs", "added", Some("old.rs")),
+            ("other.rs", "added", None),
+        ];
+        assert_eq!(
+            listed,
+            expected.map(|(path, change, old)| (path.to_string(), change, old.map(str::to_string))).to_vec()
+        );
+
+        // Nothing deleted: untracked files are not hashed
+        let entries = pair_renames(parse_status("? new.rs\0"), |_| panic!("hashed")).unwrap();
+        assert_eq!(entries.len(), 1);
+    }
+}
@@ -0,0 +1,311 @@
+/// Gitignore - Propose and write a `.gitignore` for the open project
+///
+/// `suggest_gitignore` looks for the files that mark a kind of project
+/// (`package.json`, `Cargo.toml`, `requirements.txt`/`pyproject.toml`,
+/// `go.mod`, `pom.xml`/`build.gradle`) in the project root and the folders
+/// right below it, and assembles the templates compiled into the app for
+/// them, after a common section (OS and editor files, `.env`). A pattern two
+/// sections share is only listed once. `apply_gitignore(content, merge)`
+/// writes the project's `.gitignore`: replacing it, or with `merge` keeping
+/// the existing file as it is and appending only the patterns it does not
+/// already cover (`node_modules` covers `node_modules/` and `/node_modules`,
+/// `**/x` is `x`), each block with its comment. A pattern the file negates
+/// (`!.env`) is left out rather than overriding that choice. The ignore
+/// rules are rescanned right after.
+
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+const GITIGNORE: &str = ".gitignore";
+
+/// A template section of the proposal
+struct Section {
+    id: &'static str,
+    title: &'static str,
+    /// Files that mark a project of this kind (empty: always included)
+    markers: &'static [&'static str],
+    template: &'static str,
+}
+
+const SECTIONS: [Section; 6] = [
+    Section { id: "common", title: "Common", markers: &[], template: include_str!("../gitignore-templates/common.gitignore") },
+    Section { id: "node", title: "Node.js", markers: &["package.json"], template: include_str!("../gitignore-templates/node.gitignore") },
+    Section { id: "rust", title: "Rust", markers: &["Cargo.toml"], template: include_str!("../gitignore-templates/rust.gitignore") },
+    Section {
+        id: "python",
+        title: "Python",
+        markers: &["requirements.txt", "pyproject.toml", "setup.py", "Pipfile"],
+        template: include_str!("../gitignore-templates/python.gitignore"),
+    },
+    Section { id: "go", title: "Go", markers: &["go.mod"], template: include_str!("../gitignore-templates/go.gitignore") },
+    Section {
+        id: "java",
+        title: "Java",
+        markers: &["pom.xml", "build.gradle", "build.gradle.kts"],
+        template: include_str!("../gitignore-templates/java.gitignore"),
+    },
+];
+
+/// A section `suggest_gitignore` chose
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GitignoreSection {
+    pub id: String,
+    pub title: String,
+}
+
+/// Result of `suggest_gitignore`
+#[derive(Debug, Serialize)]
+pub struct GitignoreSuggestion {
+    /// The proposed `.gitignore`, on its own
+    pub content: String,
+    pub sections: Vec<GitignoreSection>,
+    /// The project already has a `.gitignore`
+    pub exists: bool,
+    /// Patterns merging would add to the existing file
+    pub missing: usize,
+}
+
+/// Result of `apply_gitignore`
+#[derive(Debug, Serialize)]
+pub struct AppliedGitignore {
+    pub path: String,
+    /// Patterns the file did not have before
+    pub added: usize,
+}
+
+/// A `.gitignore` line as far as overlap is concerned
+#[derive(Debug, PartialEq)]
+struct Pattern {
+    negated: bool,
+    /// Without the leading and trailing `/`
+    body: String,
+    anchored: bool,
+    dir_only: bool,
+}
+
+impl Pattern {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let dir_only = line.ends_with('/');
+        let line = line.trim_end_matches('/');
+        let (line, anywhere) = match line.strip_prefix("**/") {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        // A slash other than a trailing one ties the pattern to the file's folder
+        let anchored = !anywhere && line.contains('/');
+        Some(Pattern { negated, body: line.trim_start_matches('/').to_string(), anchored, dir_only })
+    }
+
+    /// Whether this pattern already does what `other` would
+    fn covers(&self, other: &Pattern) -> bool {
+        self.negated == other.negated
+            && self.body == other.body
+            && (!self.anchored || other.anchored)
+            && (!self.dir_only || other.dir_only)
+    }
+
+    /// `other` would undo what this pattern says about the same paths
+    fn contradicts(&self, other: &Pattern) -> bool {
+        self.negated != other.negated && self.body == other.body
+    }
+}
+
+/// `existing` with the patterns of `proposed` it does not cover appended, in
+/// blocks with their comments; and how many patterns were appended
+pub(crate) fn merge(existing: &str, proposed: &str) -> (String, usize) {
+    let mut known: Vec<Pattern> = existing.lines().filter_map(Pattern::parse).collect();
+    let mut merged = existing.to_string();
+    let mut added = 0;
+    let (mut comments, mut block): (Vec<&str>, Vec<&str>) = (Vec::new(), Vec::new());
+
+    let mut flush = |comments: &mut Vec<&str>, block: &mut Vec<&str>, merged: &mut String| {
+        if !block.is_empty() {
+            if !merged.is_empty() && !merged.ends_with('\n') {
+                merged.push('\n');
+            }
+            if !merged.is_empty() && !merged.ends_with("\n\n") {
+                merged.push('\n');
+            }
+            for line in comments.iter().chain(block.iter()) {
+                merged.push_str(line);
+                merged.push('\n');
+            }
+            added += block.len();
+        }
+        comments.clear();
+        block.clear();
+    };
+    for line in proposed.lines() {
+        let Some(pattern) = Pattern::parse(line) else {
+            let comment = line.trim_start().starts_with('#');
+            if !comment || !block.is_empty() {
+                flush(&mut comments, &mut block, &mut merged);
+            }
+            if comment {
+                comments.push(line.trim_end());
+            }
+            continue;
+        };
+        if known.iter().any(|k| k.covers(&pattern) || k.contradicts(&pattern)) {
+            continue;
+        }
+        block.push(line.trim_end());
+        known.push(pattern);
+    }
+    flush(&mut comments, &mut block, &mut merged);
+    (merged, added)
+}
+
+/// Whether `dir` has one of `markers`
+fn has_marker(dir: &Path, markers: &[&str]) -> bool {
+    markers.iter().any(|marker| dir.join(marker).is_file())
+}
+
+/// Folders to look for markers in: `project` and the folders right below it
+/// (not hidden, not ignored)
+fn marker_dirs(project: &Path) -> Vec<PathBuf> {
+    let matcher = crate::ignore_rules::current_matcher(project);
+    let mut dirs = vec![project.to_path_buf()];
+    if let Ok(entries) = std::fs::read_dir(project) {
+        let mut children: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()) && !entry.file_name().to_string_lossy().starts_with('.'))
+            .map(|entry| entry.path())
+            .filter(|path| !matcher.is_ignored(path, true))
+            .collect();
+        children.sort();
+        dirs.extend(children);
+    }
+    dirs
+}
+
+/// The proposal for `project` and the sections it is made of
+fn suggest(project: &Path) -> (String, Vec<GitignoreSection>) {
+    let dirs = marker_dirs(project);
+    let chosen: Vec<&Section> = SECTIONS
+        .iter()
+        .filter(|section| section.markers.is_empty() || dirs.iter().any(|dir| has_marker(dir, section.markers)))
+        .collect();
+    let content = chosen.iter().fold(String::new(), |content, section| {
+        merge(&content, &format!("# {}\n{}", section.title, section.template)).0
+    });
+    let sections = chosen.iter().map(|s| GitignoreSection { id: s.id.to_string(), title: s.title.to_string() }).collect();
+    (content, sections)
+}
+
+fn current_project() -> Result<PathBuf, String> {
+    crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()).map(PathBuf::from).ok_or_else(|| "No project is open".to_string())
+}
+
+/// Write `content` as `project`'s `.gitignore`, merged into the existing one
+/// with `merge`
+fn apply(project: &Path, content: &str, merge_existing: bool) -> Result<AppliedGitignore, String> {
+    let path = project.join(GITIGNORE);
+    let existing = match std::fs::read_to_string(&path) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let (written, added) = if merge_existing {
+        merge(&existing, content)
+    } else {
+        let mut written = content.to_string();
+        if !written.is_empty() && !written.ends_with('\n') {
+            written.push('\n');
+        }
+        // Patterns the old file did not have
+        let (_, added) = merge(&existing, content);
+        (written, added)
+    };
+    std::fs::write(&path, written).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(AppliedGitignore { path: path.to_string_lossy().to_string(), added })
+}
+
+/// Propose a `.gitignore` for the open project from its kind
+#[tauri::command]
+pub async fn suggest_gitignore() -> Result<GitignoreSuggestion, String> {
+    crate::command_metrics::timed("suggest_gitignore", async move {
+        let project = current_project()?;
+        tauri::async_runtime::spawn_blocking(move || {
+            let (content, sections) = suggest(&project);
+            let existing = std::fs::read_to_string(project.join(GITIGNORE)).ok();
+            let missing = merge(existing.as_deref().unwrap_or_default(), &content).1;
+            GitignoreSuggestion { content, sections, exists: existing.is_some(), missing }
+        })
+        .await
+        .map_err(|e| format!("Suggesting a .gitignore failed: {}", e))
+    }).await
+}
+
+/// Write the open project's `.gitignore` (see the module docs)
+#[tauri::command]
+pub async fn apply_gitignore(content: String, merge: bool) -> Result<AppliedGitignore, String> {
+    crate::command_metrics::timed("apply_gitignore", async move {
+        let args = serde_json::json!({ "merge": merge });
+        let result = async {
+            let project = current_project()?;
+            tauri::async_runtime::spawn_blocking(move || apply(&project, &content, merge))
+                .await
+                .map_err(|e| format!("Writing .gitignore failed: {}", e))?
+        }
+        .await;
+        crate::audit::record("apply_gitignore", args, crate::audit::ORIGIN_COMMAND, &result);
+        if result.is_ok() {
+            crate::ignore_rules::gitignore_written();
+        }
+        result
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_skips_covered_and_contradicted_patterns() {
+        let existing = "# mine\nnode_modules\n/target/\n!.env\n*.log";
+        let proposed = "# Node\nnode_modules/\n/node_modules\n**/node_modules\ndist/\n\n\
+                        # Rust\ntarget/\n**/*.rs.bk\n\n# Env\n.env\n\n# Logs\n*.log\n";
+        let (merged, added) = merge(existing, proposed);
+        assert_eq!(merged, "# mine\nnode_modules\n/target/\n!.env\n*.log\n\n# Node\ndist/\n\n# Rust\ntarget/\n**/*.rs.bk\n");
+        assert_eq!(added, 3);
+        // Merging again changes nothing
+        assert_eq!(merge(&merged, proposed), (merged.clone(), 0));
+        assert_eq!(merge("", "# A\na/\n"), ("# A\na/\n".to_string(), 1));
+        assert_eq!(merge("build/\n", "build\n").1, 1);
+        assert_eq!(merge("docs/api\n", "/docs/api\n").1, 0);
+    }
+
+    #[test]
+    fn test_sections_follow_the_project_kind() {
+        let project = std::env::temp_dir().join(format!("vibecode-gitignore-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(project.join("desktop-app")).unwrap();
+        std::fs::write(project.join("requirements.txt"), "requests\n").unwrap();
+        std::fs::write(project.join("desktop-app").join("package.json"), "{}").unwrap();
+
+        let (content, sections) = suggest(&project);
+        let ids: Vec<&str> = sections.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["common", "node", "python"]);
+        assert!(content.starts_with("# Common\n") && content.contains("\n# Python\n__pycache__/\n"));
+        // build/ and dist/ come with Node already
+        assert_eq!(content.matches("\nbuild/\n").count(), 1);
+
+        std::fs::write(project.join(GITIGNORE), "node_modules\n").unwrap();
+        let applied = apply(&project, &content, true).unwrap();
+        let written = std::fs::read_to_string(project.join(GITIGNORE)).unwrap();
+        assert!(written.starts_with("node_modules\n\n# Common\n") && !written.contains("node_modules/"));
+        assert_eq!(applied.added, content.lines().filter_map(Pattern::parse).count() - 1);
+        apply(&project, "dist/", false).unwrap();
+        assert_eq!(std::fs::read_to_string(project.join(GITIGNORE)).unwrap(), "dist/\n");
+
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+}
@@ -0,0 +1,585 @@
+/// Ignore Rules - Shared, hot-reloaded ignore matcher for the project tree
+///
+/// Rules come from the `ignorePatterns` setting, the repository's
+/// `.git/info/exclude` and every `.gitignore` in the project (root and
+/// nested, deeper files winning over shallower ones and over the exclude
+/// file), with gitignore semantics:
+/// `!` negation, leading `/` anchoring, trailing `/` for directories, `*`,
+/// `?`, `[...]` and `**`. The compiled matcher is cached once per project and
+/// shared by every caller. The project's fs watcher hands every burst of
+/// `notify` events to `paths_changed`; when one touches a `.gitignore` or the
+/// exclude file, or the setting changes, the matcher is rebuilt under a new
+/// generation and `ignore-rules-changed` is emitted so the explorer can re-fetch.
+/// `set_ignore_patterns` stores the setting and rebuilds at once, and so does
+/// saving settings with new `ignorePatterns` (`settings-changed`); listings,
+/// file and content search, folder sizes and the fs watcher all go through
+/// this matcher, and cached results are keyed by its generation.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::UNIX_EPOCH;
+use serde::Serialize;
+use tauri::Emitter;
+
+pub const RULES_CHANGED_EVENT: &str = "ignore-rules-changed";
+
+const GITIGNORE: &str = ".gitignore";
+/// Per-repository excludes, relative to the project root
+const GIT_EXCLUDE: [&str; 3] = [".git", "info", "exclude"];
+/// Directories visited while looking for nested `.gitignore` files
+const MAX_SCAN_DIRS: usize = 20_000;
+
+/// Used when settings.json has no `ignorePatterns` (the explorer's old hardcoded list)
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[".*", "!.env", "node_modules", "target", "__pycache__"];
+
+/// One compiled rule
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    /// Directory the rule is relative to, `/`-separated ("" for the root)
+    base: String,
+    pattern: Vec<char>,
+    negated: bool,
+    dir_only: bool,
+    /// Matched against the path below `base` instead of the file name
+    anchored: bool,
+}
+
+impl Rule {
+    fn parse(line: &str, base: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.trim_start_matches('/');
+        if line.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            base: base.to_string(),
+            pattern: line.chars().collect(),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    fn hits(&self, rel: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let below = if self.base.is_empty() {
+            rel
+        } else {
+            match rel.strip_prefix(self.base.as_str()).and_then(|r| r.strip_prefix('/')) {
+                Some(below) => below,
+                None => return false,
+            }
+        };
+        let subject = if self.anchored {
+            below
+        } else {
+            below.rsplit('/').next().unwrap_or(below)
+        };
+        glob(&self.pattern, &subject.chars().collect::<Vec<_>>())
+    }
+}
+
+/// Gitignore-style glob: `*` and `?` stay within a segment, `**` crosses them
+pub(crate) fn glob(pattern: &[char], text: &[char]) -> bool {
+    let mut memo = vec![None; (pattern.len() + 1) * (text.len() + 1)];
+    glob_from(pattern, text, 0, 0, &mut memo)
+}
+
+/// `glob` of `pattern[p..]` against `text[t..]`; every pair is decided once,
+/// so patterns with many stars stay polynomial
+fn glob_from(pattern: &[char], text: &[char], p: usize, t: usize, memo: &mut [Option<bool>]) -> bool {
+    let key = p * (text.len() + 1) + t;
+    if let Some(hit) = memo[key] {
+        return hit;
+    }
+    let hit = glob_step(pattern, text, p, t, memo);
+    memo[key] = Some(hit);
+    hit
+}
+
+fn glob_step(pattern: &[char], text: &[char], p: usize, t: usize, memo: &mut [Option<bool>]) -> bool {
+    let mut next = |p: usize, t: usize| glob_from(pattern, text, p, t, memo);
+    match pattern.get(p) {
+        None => t == text.len(),
+        Some('*') if pattern.get(p + 1) == Some(&'*') => {
+            let rest = match pattern.get(p + 2) {
+                Some('/') => p + 3,
+                _ => p + 2,
+            };
+            if rest == pattern.len() {
+                return true;
+            }
+            (t..=text.len()).any(|i| (i == t || text[i - 1] == '/') && next(rest, i))
+        }
+        Some('*') => {
+            for i in t..=text.len() {
+                if next(p + 1, i) {
+                    return true;
+                }
+                if i < text.len() && text[i] == '/' {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => text.get(t).is_some_and(|&c| c != '/') && next(p + 1, t + 1),
+        Some('[') => {
+            let Some(close) = pattern[p..].iter().skip(2).position(|&c| c == ']').map(|i| p + i + 2) else {
+                return text.get(t) == Some(&'[') && next(p + 1, t + 1);
+            };
+            let Some(&c) = text.get(t) else { return false };
+            let (negate, class) = match pattern[p + 1] {
+                '!' | '^' => (true, &pattern[p + 2..close]),
+                _ => (false, &pattern[p + 1..close]),
+            };
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    matched |= class[i] <= c && c <= class[i + 2];
+                    i += 3;
+                } else {
+                    matched |= class[i] == c;
+                    i += 1;
+                }
+            }
+            c != '/' && matched != negate && next(close + 1, t + 1)
+        }
+        Some('\\') if p + 1 < pattern.len() => text.get(t) == Some(&pattern[p + 1]) && next(p + 2, t + 1),
+        Some(&c) => text.get(t) == Some(&c) && next(p + 1, t + 1),
+    }
+}
+
+/// Compiled rules for one project root
+#[derive(Debug)]
+pub struct IgnoreMatcher {
+    root: PathBuf,
+    generation: u64,
+    patterns: Vec<String>,
+    /// `.gitignore` files the rules were read from, with their modification times
+    sources: Vec<(PathBuf, u128)>,
+    rules: Vec<Rule>,
+}
+
+impl IgnoreMatcher {
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn matches(&self, rel: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.hits(rel, is_dir) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+
+    /// Whether `path` (or one of its parent folders below the root) is ignored
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let rel = match path.strip_prefix(&self.root) {
+            Ok(rel) => rel.to_string_lossy().replace('\\', "/"),
+            // Outside the project only name-based rules can apply
+            Err(_) => path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        };
+        if rel.is_empty() {
+            return false;
+        }
+
+        let mut prefix_end = 0;
+        while let Some(slash) = rel[prefix_end..].find('/') {
+            prefix_end += slash;
+            if self.matches(&rel[..prefix_end], true) {
+                return true;
+            }
+            prefix_end += 1;
+        }
+        self.matches(&rel, is_dir)
+    }
+}
+
+fn modified_nanos(path: &Path) -> u128 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+/// The `ignorePatterns` setting as read from settings.json (defaults when absent or invalid)
+fn read_patterns() -> Vec<String> {
+    crate::load_settings_file()
+        .ok()
+        .flatten()
+        .and_then(|settings| settings.get("ignorePatterns").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_else(|| DEFAULT_IGNORE_PATTERNS.iter().map(|s| s.to_string()).collect())
+}
+
+/// The `ignorePatterns` setting, as last read or saved
+fn load_patterns() -> Vec<String> {
+    PATTERNS.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert_with(read_patterns).clone()
+}
+
+fn store_patterns(patterns: &[String]) {
+    *PATTERNS.lock().unwrap_or_else(|e| e.into_inner()) = Some(patterns.to_vec());
+}
+
+/// Compile `patterns` alone, without reading any `.gitignore`
+fn patterns_only(root: &Path, patterns: Vec<String>, generation: u64) -> IgnoreMatcher {
+    IgnoreMatcher {
+        root: root.to_path_buf(),
+        generation,
+        rules: patterns.iter().filter_map(|p| Rule::parse(p, "")).collect(),
+        patterns,
+        sources: Vec::new(),
+    }
+}
+
+/// Matcher for exactly `patterns`, independent of settings and `.gitignore` files
+#[cfg(test)]
+pub(crate) fn with_patterns(root: &Path, patterns: &[&str]) -> Arc<IgnoreMatcher> {
+    Arc::new(patterns_only(root, patterns.iter().map(|p| p.to_string()).collect(), 0))
+}
+
+/// Matcher that ignores nothing, for listings asked to show ignored entries
+pub fn nothing_ignored(root: &Path) -> Arc<IgnoreMatcher> {
+    Arc::new(patterns_only(root, Vec::new(), GENERATION.load(Ordering::SeqCst)))
+}
+
+fn exclude_file(root: &Path) -> PathBuf {
+    GIT_EXCLUDE.iter().fold(root.to_path_buf(), |path, part| path.join(part))
+}
+
+/// Compile `patterns` plus `.git/info/exclude` and the root and nested
+/// `.gitignore` files under `root`
+fn build(root: &Path, patterns: Vec<String>, generation: u64) -> IgnoreMatcher {
+    let mut matcher = patterns_only(root, patterns, generation);
+
+    // Before any .gitignore, which overrides it
+    let exclude = exclude_file(root);
+    if let Ok(content) = std::fs::read_to_string(&exclude) {
+        matcher.rules.extend(content.lines().filter_map(|line| Rule::parse(line, "")));
+        matcher.sources.push((exclude.clone(), modified_nanos(&exclude)));
+    }
+
+    // Breadth-first, so deeper .gitignore files come later and win; never
+    // descend into folders the rules gathered so far already ignore
+    let mut queue = std::collections::VecDeque::from([root.to_path_buf()]);
+    let mut visited = 0;
+    while let Some(dir) = queue.pop_front() {
+        visited += 1;
+        if visited > MAX_SCAN_DIRS {
+            eprintln!("Ignore rules: stopped scanning {} after {} folders", root.display(), MAX_SCAN_DIRS);
+            break;
+        }
+
+        let gitignore = dir.join(GITIGNORE);
+        if let Ok(content) = std::fs::read_to_string(&gitignore) {
+            let base = dir
+                .strip_prefix(root)
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+            matcher.rules.extend(content.lines().filter_map(|line| Rule::parse(line, &base)));
+            matcher.sources.push((gitignore.clone(), modified_nanos(&gitignore)));
+        }
+
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        let mut subdirs: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| e.path())
+            .filter(|p| p.file_name().is_some_and(|n| n != ".git") && !matcher.is_ignored(p, true))
+            .collect();
+        subdirs.sort();
+        queue.extend(subdirs);
+    }
+
+    matcher
+}
+
+static CACHE: Mutex<Option<Arc<IgnoreMatcher>>> = Mutex::new(None);
+/// Read from settings.json on first use, then by every refresh
+static PATTERNS: Mutex<Option<Vec<String>>> = Mutex::new(None);
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+static APP: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+/// Result of `get_ignore_patterns` / `set_ignore_patterns`
+#[derive(Debug, Serialize)]
+pub struct IgnorePatterns {
+    /// The `ignorePatterns` setting, or the defaults without one
+    pub patterns: Vec<String>,
+    pub defaults: Vec<String>,
+    /// Generation of the shared matcher
+    pub generation: u64,
+}
+
+/// Payload of `ignore-rules-changed`
+#[derive(Debug, Clone, Serialize)]
+struct RulesChangedEvent {
+    root: String,
+    generation: u64,
+}
+
+/// The shared matcher for `root`, compiled on first use
+pub fn matcher_for(root: &Path) -> Arc<IgnoreMatcher> {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(matcher) = cache.as_ref().filter(|m| m.root == root) {
+        return matcher.clone();
+    }
+    let matcher = Arc::new(build(root, load_patterns(), GENERATION.fetch_add(1, Ordering::SeqCst) + 1));
+    *cache = Some(matcher.clone());
+    matcher
+}
+
+/// Matcher for `dir`: the open project's when `dir` is inside it, otherwise
+/// just the `ignorePatterns` setting (folders outside the project are not scanned)
+pub fn current_matcher(dir: &Path) -> Arc<IgnoreMatcher> {
+    let project = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone());
+    match project {
+        Some(project) if dir.starts_with(&project) => matcher_for(Path::new(&project)),
+        _ => Arc::new(patterns_only(dir, load_patterns(), GENERATION.load(Ordering::SeqCst))),
+    }
+}
+
+/// Rebuild the cached matcher if its inputs changed; returns the new generation
+///
+/// With `rescan` false only the known `.gitignore` files and the setting are
+/// checked, without walking the project for new ones.
+fn refresh(rescan: bool) -> Option<u64> {
+    let patterns = read_patterns();
+    store_patterns(&patterns);
+    rebuild(rescan, patterns)
+}
+
+/// `refresh` with the `ignorePatterns` setting already known
+fn rebuild(rescan: bool, patterns: Vec<String>) -> Option<u64> {
+    let cached = CACHE.lock().unwrap_or_else(|e| e.into_inner()).clone()?;
+    let stale = patterns != cached.patterns
+        || cached.sources.iter().any(|(path, modified)| modified_nanos(path) != *modified);
+    if !stale && !rescan {
+        return None;
+    }
+
+    let candidate = build(&cached.root, patterns, 0);
+    if !stale && candidate.sources == cached.sources {
+        return None;
+    }
+
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let matcher = Arc::new(IgnoreMatcher { generation, ..candidate });
+    let root = matcher.root.to_string_lossy().to_string();
+    {
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        // The project may have changed while we were scanning
+        if cache.as_ref().is_some_and(|m| m.root != matcher.root) {
+            return None;
+        }
+        *cache = Some(matcher);
+    }
+
+    if let Some(app) = APP.get() {
+        let _ = app.emit(RULES_CHANGED_EVENT, RulesChangedEvent { root, generation });
+    }
+    Some(generation)
+}
+
+/// Whether a change to `path` in the project at `root` can change its rules: a
+/// `.gitignore`, the exclude file, or a folder created with a `.gitignore` inside
+fn affects_rules(root: &Path, path: &Path, created: bool) -> bool {
+    path.file_name().is_some_and(|name| name == GITIGNORE)
+        || path == exclude_file(root)
+        || (created && path.join(GITIGNORE).is_file())
+}
+
+/// Rebuild the matcher of `root` if a burst of fs events (paths, each with
+/// whether it was created) touched its rules; returns the new generation
+pub fn paths_changed(root: &Path, touched: &BTreeMap<PathBuf, bool>) -> Option<u64> {
+    let cached = CACHE.lock().unwrap_or_else(|e| e.into_inner()).as_ref().is_some_and(|m| m.root == root);
+    if !cached || !touched.iter().any(|(path, created)| affects_rules(root, path, *created)) {
+        return None;
+    }
+    refresh(true)
+}
+
+/// Use the `ignorePatterns` of newly saved settings
+pub fn settings_changed(change: &crate::settings_events::SettingsChanged) {
+    store_patterns(&change.settings.ignore_patterns);
+    rebuild(false, change.settings.ignore_patterns.clone());
+}
+
+/// Rescan the rules now (after a `.gitignore` was written, maybe a new one)
+pub fn gitignore_written() {
+    std::thread::spawn(|| refresh(true));
+}
+
+/// Set `ignorePatterns` in the settings `value` (remove it for the defaults)
+fn set_patterns(value: &mut serde_json::Value, patterns: Option<Vec<String>>) -> Result<(), String> {
+    let settings = value.as_object_mut().ok_or("Invalid settings.json: not an object")?;
+    match patterns {
+        Some(patterns) => {
+            let patterns: Vec<String> =
+                patterns.iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect();
+            settings.insert("ignorePatterns".to_string(), serde_json::json!(patterns));
+        }
+        None => {
+            settings.remove("ignorePatterns");
+        }
+    }
+    Ok(())
+}
+
+fn current_patterns() -> IgnorePatterns {
+    IgnorePatterns {
+        patterns: load_patterns(),
+        defaults: DEFAULT_IGNORE_PATTERNS.iter().map(|s| s.to_string()).collect(),
+        generation: GENERATION.load(Ordering::SeqCst),
+    }
+}
+
+/// Patterns the explorer, search and watcher skip, on top of the git ignore files
+#[tauri::command]
+pub async fn get_ignore_patterns() -> Result<IgnorePatterns, String> {
+    crate::command_metrics::timed("get_ignore_patterns", async move {
+        Ok(current_patterns())
+    }).await
+}
+
+/// Replace the ignore patterns (None restores the defaults) and rebuild the
+/// matcher now, emitting `ignore-rules-changed`
+#[tauri::command]
+pub async fn set_ignore_patterns(patterns: Option<Vec<String>>) -> Result<IgnorePatterns, String> {
+    crate::command_metrics::timed("set_ignore_patterns", async move {
+        tauri::async_runtime::spawn_blocking(move || {
+            crate::instance_lock::update_json_file(&crate::get_settings_path(), |settings| set_patterns(settings, patterns))?;
+            refresh(false);
+            Ok(current_patterns())
+        })
+        .await
+        .map_err(|e| format!("Saving ignore patterns failed: {}", e))?
+    }).await
+}
+
+/// Spawn the `ignorePatterns` listener; `.gitignore` edits arrive through
+/// `paths_changed` from the fs watcher
+pub fn spawn(app: tauri::AppHandle) {
+    let _ = APP.set(app);
+    crate::settings_events::listen(&["/ignorePatterns"], |change| {
+        let change = change.clone();
+        std::thread::spawn(move || settings_changed(&change));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(root: &Path, patterns: &[&str]) -> IgnoreMatcher {
+        build(root, patterns.iter().map(|s| s.to_string()).collect(), 1)
+    }
+
+    #[test]
+    fn test_glob_segments_and_globstar() {
+        let g = |p: &str, t: &str| glob(&p.chars().collect::<Vec<_>>(), &t.chars().collect::<Vec<_>>());
+        assert!(g("*.log", "debug.log"));
+        assert!(!g("*.log", "logs/debug.log"));
+        assert!(g("**/build", "a/b/build"));
+        assert!(g("**/build", "build"));
+        assert!(g("docs/**", "docs/a/b.md"));
+        assert!(g("a/**/b", "a/b"));
+        assert!(g("a/**/b", "a/x/y/b"));
+        assert!(g("file?.[ch]", "file1.c"));
+        assert!(!g("file?.[!ch]", "file1.c"));
+        assert!(g("[a-c]*", "beta"));
+
+        // Backtracking over many stars used to take exponential time
+        let long = "a".repeat(60);
+        assert!(!g("*a*a*a*a*a*a*a*a*a*a*b", &long));
+        assert!(!g("**/**/**/**/**/**/x", &"a/".repeat(30)));
+        assert!(g("*a*a*a*a*a*a*a*a*a*a", &long));
+    }
+
+    #[test]
+    fn test_settings_root_and_nested_gitignores_combine() {
+        let root = std::env::temp_dir().join(format!("vibecode-ignore-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("app").join("dist")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules").join("pkg")).unwrap();
+        std::fs::write(root.join(".gitignore"), "*.log\n/build/\ndist/\n").unwrap();
+        std::fs::write(root.join("app").join(".gitignore"), "!keep.log\nsecret.txt\n!scratch.txt\n").unwrap();
+        // Never read: node_modules is ignored by the settings patterns
+        std::fs::write(root.join("node_modules").join(".gitignore"), "*\n").unwrap();
+        std::fs::create_dir_all(root.join(".git").join("info")).unwrap();
+        std::fs::write(root.join(".git").join("info").join("exclude"), "venv/\nscratch.txt\n").unwrap();
+
+        let m = matcher(&root, DEFAULT_IGNORE_PATTERNS);
+        assert_eq!(m.sources.len(), 3);
+        assert!(m.is_ignored(&root.join(".git"), true));
+        assert!(!m.is_ignored(&root.join(".env"), false));
+        assert!(m.is_ignored(&root.join("node_modules").join("pkg").join("index.js"), false));
+        assert!(m.is_ignored(&root.join("debug.log"), false));
+        assert!(m.is_ignored(&root.join("app").join("debug.log"), false));
+        assert!(!m.is_ignored(&root.join("app").join("keep.log"), false));
+        assert!(m.is_ignored(&root.join("app").join("secret.txt"), false));
+        assert!(!m.is_ignored(&root.join("secret.txt"), false));
+        assert!(m.is_ignored(&root.join("build"), true));
+        assert!(!m.is_ignored(&root.join("app").join("build"), true));
+        assert!(m.is_ignored(&root.join("app").join("dist").join("main.js"), false));
+        assert!(!m.is_ignored(&root.join("src").join("main.rs"), false));
+        assert!(m.is_ignored(&root.join("tools").join("venv"), true));
+        assert!(m.is_ignored(&root.join("scratch.txt"), false));
+        assert!(!m.is_ignored(&root.join("app").join("scratch.txt"), false));
+        assert!(!nothing_ignored(&root).is_ignored(&root.join("debug.log"), false));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_rule_files_and_folders_bringing_them_affect_rules() {
+        let root = std::env::temp_dir().join(format!("vibecode-ignore-events-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("vendored")).unwrap();
+        std::fs::write(root.join("vendored").join(GITIGNORE), "*.o\n").unwrap();
+        std::fs::create_dir_all(root.join("plain")).unwrap();
+
+        assert!(affects_rules(&root, &root.join(GITIGNORE), false));
+        assert!(affects_rules(&root, &root.join("app").join(GITIGNORE), false));
+        assert!(affects_rules(&root, &root.join(".git").join("info").join("exclude"), false));
+        assert!(affects_rules(&root, &root.join("vendored"), true));
+        assert!(!affects_rules(&root, &root.join("vendored"), false));
+        assert!(!affects_rules(&root, &root.join("plain"), true));
+        assert!(!affects_rules(&root, &root.join("src").join("main.rs"), true));
+        assert!(!affects_rules(&root, &root.join("notes.gitignore.md"), false));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_set_patterns_edits_only_the_setting() {
+        let mut settings = serde_json::json!({ "theme": "light" });
+        let patterns = vec![" vendor/ ".to_string(), "".to_string(), ".terraform".to_string()];
+        set_patterns(&mut settings, Some(patterns)).unwrap();
+        assert_eq!(settings, serde_json::json!({ "theme": "light", "ignorePatterns": ["vendor/", ".terraform"] }));
+
+        set_patterns(&mut settings, None).unwrap();
+        assert_eq!(settings, serde_json::json!({ "theme": "light" }));
+        assert!(set_patterns(&mut serde_json::json!([]), None).is_err());
+    }
+}
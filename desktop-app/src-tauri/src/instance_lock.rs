@@ -0,0 +1,219 @@
+/// Instance Lock - Cross-process safety for shared config and store files
+///
+/// Two app instances (say the packaged app and a dev build) share
+/// settings.json, config.json and the store.json written by the store plugin.
+/// Every read-modify-write of those files runs under an advisory lock on a
+/// `<name>.lock` sidecar in the config dir, and re-reads the file inside the
+/// lock so the other instance's changes are merged rather than overwritten.
+///
+/// The first instance also holds `instance.lock` for its whole lifetime. A
+/// later instance that cannot take it runs as a read-only secondary: writers
+/// refuse with an error and the API server, watchers and pollers stay off.
+
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use serde::Serialize;
+use serde_json::Value;
+
+const PRIMARY_LOCK: &str = "instance.lock";
+/// The store plugin's file (also the name of its lock)
+pub const STORE_FILE: &str = "store.json";
+
+/// Whether this process owns the shared files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceMode {
+    Primary,
+    /// Another instance holds the primary lock; writers are disabled
+    Secondary,
+}
+
+/// The primary lock file, kept open (and locked) until exit
+static PRIMARY: OnceLock<Option<File>> = OnceLock::new();
+
+/// Held while a shared file is being read-modify-written
+pub struct FileLock {
+    file: File,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+fn lock_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.lock", name))
+}
+
+fn open_lock_file(path: &Path) -> Result<File, String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open lock file {}: {}", path.display(), e))
+}
+
+/// Take the primary lock if no other instance holds it (first call decides)
+pub fn acquire_primary() -> InstanceMode {
+    let held = PRIMARY.get_or_init(|| {
+        let file = open_lock_file(&crate::get_app_config_dir().join(PRIMARY_LOCK)).ok()?;
+        file.try_lock().ok().map(|_| file)
+    });
+    if held.is_some() {
+        InstanceMode::Primary
+    } else {
+        InstanceMode::Secondary
+    }
+}
+
+/// Current mode (primary until `acquire_primary` has decided otherwise)
+pub fn mode() -> InstanceMode {
+    match PRIMARY.get() {
+        Some(None) => InstanceMode::Secondary,
+        _ => InstanceMode::Primary,
+    }
+}
+
+/// Fail when this instance must not write shared files
+pub fn ensure_writable() -> Result<(), String> {
+    match mode() {
+        InstanceMode::Primary => Ok(()),
+        InstanceMode::Secondary => {
+            Err("Another Vibecode instance is running; this window is read-only".to_string())
+        }
+    }
+}
+
+pub(crate) fn lock_in(dir: &Path, name: &str) -> Result<FileLock, String> {
+    let file = open_lock_file(&lock_path(dir, name))?;
+    file.lock()
+        .map_err(|e| format!("Failed to lock {}: {}", name, e))?;
+    Ok(FileLock { file })
+}
+
+/// Block until the shared file `name` is exclusively ours
+pub fn lock(name: &str) -> Result<FileLock, String> {
+    ensure_writable()?;
+    lock_in(&crate::get_app_config_dir(), name)
+}
+
+/// Read-modify-write a JSON file under its lock (missing files start as `{}`)
+fn update_json_in<R>(
+    lock_dir: &Path,
+    path: &Path,
+    update: impl FnOnce(&mut Value) -> Result<R, String>,
+) -> Result<R, String> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let _lock = lock_in(lock_dir, &name)?;
+
+    let mut value = match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", name, e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Value::Object(Default::default()),
+        Err(e) => return Err(format!("Failed to read {}: {}", name, e)),
+    };
+    let result = update(&mut value)?;
+
+    let content = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to save {}: {}", name, e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to save {}: {}", name, e))?;
+    Ok(result)
+}
+
+/// Read-modify-write a JSON file in the config dir under its lock
+pub fn update_json_file<R>(path: &Path, update: impl FnOnce(&mut Value) -> Result<R, String>) -> Result<R, String> {
+    ensure_writable()?;
+    update_json_in(&crate::get_app_config_dir(), path, update)
+}
+
+/// Read-modify-write the plugin store: lock, reload from disk, update, save
+pub fn update_store<R>(
+    app: &tauri::AppHandle,
+    update: impl FnOnce(&tauri_plugin_store::Store<tauri::Wry>) -> Result<R, String>,
+) -> Result<R, String> {
+    use tauri::Manager;
+    use tauri_plugin_store::StoreExt;
+
+    let _lock = lock(STORE_FILE)?;
+    let store = app.store(STORE_FILE)
+        .map_err(|e| format!("Failed to get store: {}", e))?;
+    // Relative store paths live in the app data dir; nothing to reload before the first save
+    let on_disk = app.path().app_data_dir().map(|dir| dir.join(STORE_FILE).exists()).unwrap_or(true);
+    if on_disk {
+        store.reload().map_err(|e| format!("Failed to reload store: {}", e))?;
+    }
+    let result = update(&store)?;
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contending_writers_lose_no_updates() {
+        let dir = std::env::temp_dir().join(format!("vibecode-lock-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = dir.join("store.json");
+
+        // Two "instances" each adding accounts to the same store file
+        let writers: Vec<_> = (0..2)
+            .map(|writer| {
+                let (dir, store) = (dir.clone(), store.clone());
+                std::thread::spawn(move || {
+                    for i in 0..25 {
+                        update_json_in(&dir, &store, |value| {
+                            let accounts = value
+                                .as_object_mut()
+                                .unwrap()
+                                .entry("saved_accounts")
+                                .or_insert_with(|| Value::Array(Vec::new()));
+                            accounts
+                                .as_array_mut()
+                                .unwrap()
+                                .push(serde_json::json!({ "email": format!("w{}-{}@example.com", writer, i) }));
+                            Ok(())
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let value: Value = serde_json::from_str(&std::fs::read_to_string(&store).unwrap()).unwrap();
+        let accounts = value["saved_accounts"].as_array().unwrap();
+        assert_eq!(accounts.len(), 50);
+        for writer in 0..2 {
+            assert!(accounts.iter().any(|a| a["email"] == format!("w{}-24@example.com", writer)));
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_lock_is_exclusive_across_handles() {
+        let dir = std::env::temp_dir().join(format!("vibecode-lock-{}", uuid::Uuid::new_v4()));
+        let held = lock_in(&dir, "settings.json").unwrap();
+        let other = open_lock_file(&lock_path(&dir, "settings.json")).unwrap();
+        assert!(other.try_lock().is_err());
+        drop(held);
+        assert!(other.try_lock().is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,8 +1,9 @@
 // Vibecode Desktop App - Tauri Commands
 // Bridges the React frontend with Python vibe.py backend
 
+use std::collections::HashMap;
 use std::process::Command;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 use serde::{Deserialize, Serialize};
 
@@ -11,16 +12,62 @@ static CURRENT_PROJECT: RwLock<Option<String>> = RwLock::new(None);
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TaskResult {
+    /// Id of the run (see `get_task_status`)
+    #[serde(default)]
+    pub task_id: String,
     pub success: bool,
+    /// Console output: stdout, or vibe.py's log in `--json` mode (which is
+    /// written to stderr). With `tasks.combinedOutput`, stderr is appended on
+    /// failure as in older versions.
     pub output: String,
+    /// Captured stderr (the same log as `output` in `--json` mode)
+    #[serde(default)]
+    pub stderr: String,
     pub agent_used: String,
+    pub agent_resolution: String, // "explicit" | "detected" | "unknown"
     pub execution_time: f64,
+    /// Variables provided to the child (names and sources only)
+    #[serde(default)]
+    pub env: Vec<child_env::ProvidedVar>,
+    /// Program, arguments and working directory actually spawned
+    #[serde(default)]
+    pub invocation: Option<child_env::Invocation>,
+    /// Files whose git status changed during the run (empty with older vibe.py)
+    #[serde(default)]
+    pub files_changed: Vec<String>,
+    /// Process exit code (None when killed by a signal)
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    /// Runs it took, retries included (0 in records made before retries existed)
+    #[serde(default)]
+    pub attempts: u32,
+    /// Agents tried in order when `auto_fallback` switched agents (empty otherwise)
+    #[serde(default)]
+    pub agent_attempts: Vec<agent_fallback::AgentAttempt>,
+    /// Output and stderr were cut at `tasks.maxOutputBytes`
+    #[serde(default)]
+    pub output_truncated: bool,
+    /// Complete output when truncated (page through it with `read_task_log`)
+    #[serde(default)]
+    pub full_output_path: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WorkflowInfo {
     pub name: String,
     pub description: String,
+    /// Steps in the workflow (None with older vibe.py)
+    #[serde(default)]
+    pub steps_count: Option<usize>,
+    /// Path of the workflow YAML (None with older vibe.py)
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// "project" or "global" (see `workflow_scope`)
+    #[serde(default)]
+    pub scope: String,
+    /// Where the YAML fails to parse, when it does
+    #[serde(default)]
+    pub yaml_error: Option<workflow_yaml::YamlDiagnostic>,
 }
 
 /// File/folder entry for file explorer
@@ -32,6 +79,17 @@ pub struct FileEntry {
     pub extension: Option<String>,
     pub size: Option<u64>,
     pub children: Option<Vec<FileEntry>>,
+    /// `children` stop short of the directory's entries (`list_directory_tree` limits)
+    #[serde(default)]
+    pub truncated: bool,
+    #[serde(default)]
+    pub is_symlink: bool,
+    /// Where a symlink points, as stored in the link
+    #[serde(default)]
+    pub symlink_target: Option<String>,
+    /// A symlink whose target does not exist
+    #[serde(default)]
+    pub broken: bool,
 }
 
 /// Changed file tracking
@@ -39,8 +97,17 @@ pub struct FileEntry {
 pub struct ChangedFile {
     pub path: String,
     pub status: String, // "added", "modified", "deleted"
-    pub lines_added: u32,
-    pub lines_removed: u32,
+    /// None for binary files, or with nothing to compare with (see `line_stats`)
+    pub lines_added: Option<u32>,
+    pub lines_removed: Option<u32>,
+    /// Old path of a renamed file, on its "added" entry (the old path has a
+    /// "deleted" entry of its own)
+    #[serde(default)]
+    pub renamed_from: Option<String>,
+    /// Seen by the fs watcher while a task ran, not reported by the task
+    /// itself: may be the user's own edit (see `task_changes`)
+    #[serde(default)]
+    pub detected_during_task: bool,
 }
 
 /// Skill metadata from SKILL.md frontmatter
@@ -52,6 +119,8 @@ pub struct SkillMetadata {
     pub author: Option<String>,
     pub category: Option<String>,
     pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub archived: bool,
 }
 
 /// Skill entry for Skills Manager
@@ -63,6 +132,10 @@ pub struct Skill {
     pub path: String,
     pub version: String,
     pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub archived: bool,
     pub has_scripts: bool,
     pub has_guardrails: bool,
     pub created_at: String,
@@ -120,6 +193,83 @@ mod antigravity;
 mod services;
 mod api_server;
 mod workflow_generator;
+mod startup;
+mod agent_stats;
+mod retention;
+mod skill_index;
+mod settings;
+mod settings_events;
+mod settings_migration;
+mod config_bundle;
+mod skill_archive;
+mod child_env;
+mod name_lint;
+mod environment_check;
+mod skill_frontmatter;
+mod audit;
+mod script_runner;
+mod project_switch;
+mod skill_preview;
+mod version_info;
+mod temp_sweep;
+mod command_metrics;
+mod instance_lock;
+mod operations;
+mod git_import;
+mod gemini_budget;
+mod ignore_rules;
+mod skill_provenance;
+mod file_explorer;
+mod file_search;
+mod content_search;
+mod fs_watcher;
+mod file_content;
+mod text_encoding;
+mod path_metadata;
+mod git_diff;
+mod git_status;
+mod review_diff;
+mod git_commit;
+mod git_repository;
+mod git_log;
+mod git_push;
+mod gitignore;
+mod line_stats;
+mod merge_conflicts;
+mod task_changes;
+mod directory_size;
+mod recent_files;
+mod recent_projects;
+mod bookmarks;
+mod task_process;
+mod task_queue;
+mod task_history;
+mod project_env;
+mod vibe_json;
+mod task_retry;
+mod task_templates;
+mod task_input;
+mod task_manager;
+mod python_interpreter;
+mod python_env;
+mod http_client;
+mod backend_paths;
+mod scheduler;
+mod agent_fallback;
+mod task_log;
+mod task_progress;
+mod workflow_yaml;
+mod workflow_validation;
+mod workflow_variables;
+mod workflow_condition;
+mod workflow_scope;
+mod workflow_templates;
+mod workflow_run;
+mod workflow_files;
+mod workflow_history;
+mod workflow_snapshot;
+mod workflow_archive;
+mod workflow_watcher;
 
 // ============================================================================
 // End Modules
@@ -128,36 +278,26 @@ mod workflow_generator;
 // Global state for changed files (tracked during task execution)
 static CHANGED_FILES: RwLock<Vec<ChangedFile>> = RwLock::new(Vec::new());
 
-/// Get the path to vibe.py relative to the app
-fn get_vibe_path() -> PathBuf {
-    // In development, vibe.py is in the parent directory
-    let mut path = std::env::current_dir().unwrap_or_default();
-    
-    // Check if we're in desktop-app/src-tauri
-    if path.ends_with("src-tauri") {
-        path.pop(); // Remove src-tauri
-        path.pop(); // Remove desktop-app
-    } else if path.ends_with("desktop-app") {
-        path.pop(); // Remove desktop-app
+/// Point a vibe.py command at the open project: run inside it and pass `--project`
+///
+/// Without a project (or when its folder is gone) vibe.py runs in its own directory.
+fn target_project(cmd: &mut Command, vibe_path: &Path) {
+    let project = CURRENT_PROJECT.read().ok().and_then(|p| p.clone());
+    match project.filter(|p| Path::new(p).is_dir()) {
+        Some(project) => {
+            cmd.arg("--project").arg(&project).current_dir(&project);
+        }
+        None => {
+            if let Some(parent) = vibe_path.parent() {
+                cmd.current_dir(parent);
+            }
+        }
     }
-    
-    path.push("vibe.py");
-    path
 }
 
-/// Get the workflows directory path
+/// Get the workflows directory path (next to vibe.py, which runs them)
 fn get_workflows_path() -> PathBuf {
-    let mut path = std::env::current_dir().unwrap_or_default();
-    
-    if path.ends_with("src-tauri") {
-        path.pop();
-        path.pop();
-    } else if path.ends_with("desktop-app") {
-        path.pop();
-    }
-    
-    path.push("workflows");
-    path
+    python_env::vibe_dir().join("workflows")
 }
 
 /// Get the skills directory path (.agent/skills in current project)
@@ -172,30 +312,27 @@ fn get_skills_path() -> PathBuf {
         }
     }
     
-    // Fallback to current directory
-    let mut path = std::env::current_dir().unwrap_or_default();
-    
-    if path.ends_with("src-tauri") {
-        path.pop();
-        path.pop();
-    } else if path.ends_with("desktop-app") {
-        path.pop();
-    }
-    
-    path.push(".agent");
-    path.push("skills");
-    path
+    get_default_skills_path()
 }
 
-/// Get the config file path (for persisting settings)
-fn get_config_path() -> PathBuf {
+/// Get the app's own skills directory (used when no project is open)
+fn get_default_skills_path() -> PathBuf {
+    python_env::vibe_dir().join(".agent").join("skills")
+}
+
+/// Get the app config directory (settings, config and app-generated data)
+fn get_app_config_dir() -> PathBuf {
     dirs::config_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join("vibecode-desktop")
-        .join("config.json")
 }
 
-/// Save project path to config file
+/// Get the config file path (for persisting settings)
+fn get_config_path() -> PathBuf {
+    get_app_config_dir().join("config.json")
+}
+
+/// Save project path to config file (and to the recent projects)
 fn save_project_path(path: &str) -> Result<(), String> {
     let config_path = get_config_path();
     
@@ -205,14 +342,15 @@ fn save_project_path(path: &str) -> Result<(), String> {
             .map_err(|e| format!("Failed to create config directory: {}", e))?;
     }
     
-    let config = serde_json::json!({
-        "last_project": path
-    });
-    
-    std::fs::write(&config_path, config.to_string())
-        .map_err(|e| format!("Failed to save config: {}", e))?;
-    
-    Ok(())
+    instance_lock::update_json_file(&config_path, |config| {
+        if !config.is_object() {
+            *config = serde_json::json!({});
+        }
+        config["last_project"] = serde_json::json!(path);
+        recent_projects::record_in(config, path);
+        Ok(())
+    })
+    .map_err(|e| format!("Failed to save config: {}", e))
 }
 
 /// Load project path from config file
@@ -231,81 +369,233 @@ fn load_project_path() -> Option<String> {
 
 /// Get the settings file path
 fn get_settings_path() -> PathBuf {
-    dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("vibecode-desktop")
-        .join("settings.json")
+    get_app_config_dir().join("settings.json")
 }
 
-/// Get app settings
-#[tauri::command]
-async fn get_settings() -> Result<String, String> {
-    let settings_path = get_settings_path();
-    
-    if !settings_path.exists() {
-        // Return default settings
-        return Ok(serde_json::json!({
-            "pythonPath": "python ../vibe.py",
-            "theme": "dark",
-            "apiKeys": []
-        }).to_string());
-    }
-    
-    std::fs::read_to_string(&settings_path)
-        .map_err(|e| format!("Failed to read settings: {}", e))
+/// Read and parse settings.json, migrated to the current schema (None when no
+/// settings have been saved yet)
+fn load_settings_file() -> Result<Option<serde_json::Value>, String> {
+    settings_migration::load(&get_settings_path()).map_err(|e| e.message)
 }
 
-/// Save app settings
+/// Test Python connection: the interpreter runs, and can load vibe.py
 #[tauri::command]
-async fn save_settings(settings: String) -> Result<(), String> {
-    let settings_path = get_settings_path();
-    
-    // Create directory if it doesn't exist
-    if let Some(parent) = settings_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+async fn test_python_connection(python_path: String) -> Result<String, String> {
+    command_metrics::timed("test_python_connection", async move {
+        if python_path.trim().is_empty() {
+            return Err("Invalid Python path".to_string());
+        }
+        let (version, vibe_path) = python_env::probe_python_path(&python_path)?;
+        Ok(format!("Connected: {} ({})", version, vibe_path.display()))
+    }).await
+}
+
+/// Execute a task using vibe.py (`task_id` lets `cancel_task` stop it)
+///
+/// The run is killed after `timeout_secs` (default: the `tasks.timeoutSecs` setting).
+/// `env` is added to the child's environment on top of the project's variables.
+/// With `retry`, transient failures (rate limits, 429, connection resets) are re-run.
+/// `labels` tag the run in the task history (see `search_task_history`).
+/// With `auto_fallback`, auth and quota failures re-run the task with the next
+/// agent of the `agentFallback.chain` setting. The task is refused while the
+/// project has unresolved merge conflicts unless `allow_conflicts` is set
+/// (see `merge_conflicts`). Once the run is done the changed files list is
+/// rebuilt from git (see `git_status`).
+#[tauri::command]
+#[allow(clippy::too_many_arguments)] // arguments of the frontend's invoke call
+async fn execute_task(
+    app: tauri::AppHandle,
+    task: String,
+    agent: String,
+    task_id: Option<String>,
+    timeout_secs: Option<u64>,
+    env: Option<HashMap<String, String>>,
+    retry: Option<task_retry::RetryPolicy>,
+    labels: Option<Vec<String>>,
+    auto_fallback: Option<bool>,
+    allow_conflicts: Option<bool>,
+) -> Result<TaskResult, String> {
+    command_metrics::timed("execute_task", async move {
+        merge_conflicts::check(allow_conflicts.unwrap_or(false)).await?;
+        let run = TaskRun {
+            task,
+            agent,
+            task_id: task_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            timeout: task_process::TaskProcessSettings::load().timeout(timeout_secs),
+            env,
+            retry,
+            labels: labels.unwrap_or_default(),
+            auto_fallback: auto_fallback.unwrap_or(false),
+        };
+        let result = run_task(&app, run).await;
+        git_status::refresh_after_run(&app);
+        result
+    }).await
+}
+
+/// One task to run through vibe.py
+pub(crate) struct TaskRun {
+    pub task: String,
+    pub agent: String,
+    pub task_id: String,
+    pub timeout: Option<std::time::Duration>,
+    pub env: Option<HashMap<String, String>>,
+    pub retry: Option<task_retry::RetryPolicy>,
+    pub labels: Vec<String>,
+    /// Switch agents on failures matching `agentFallback.patterns`
+    pub auto_fallback: bool,
+}
+
+/// Run one task (retrying per `retry`) and record it in the current project's task history
+async fn run_task(app: &tauri::AppHandle, run: TaskRun) -> Result<TaskResult, String> {
+    let project = task_history::current_project();
+    let tasks = task_manager::manager(app);
+    tasks.start(&run.task_id, task_manager::TaskKind::Task, &run.task);
+    task_changes::begin(&run.task_id).await;
+    let outcome = run_with_fallback(app, &run).await;
+    task_changes::finish(app, &run.task_id).await;
+    let status = match &outcome {
+        Ok((result, cancelled)) => task_manager::TaskStatus::finished(result.success, *cancelled),
+        Err(_) => task_manager::TaskStatus::Failed,
+    };
+    tasks.finish(&run.task_id, status);
+
+    let (mut result, _) = outcome?;
+    task_log::cap(&mut result);
+    let changed_files = tasks.get(&run.task_id).map(|task| task.changed_files).unwrap_or_default();
+    task_history::record(&run.task_id, project, &run.task, &run.labels, &result, changed_files);
+    Ok(result)
+}
+
+/// Run a task with its agent, then (with `auto_fallback`) the next agents of the chain
+async fn run_with_fallback(app: &tauri::AppHandle, run: &TaskRun) -> Result<(TaskResult, bool), String> {
+    use tauri::Emitter;
+
+    if !run.auto_fallback {
+        return run_attempts(app, run, &run.agent).await;
+    }
+    let settings = agent_fallback::AgentFallbackSettings::load();
+    let mut agent = run.agent.clone();
+    let mut tried: Vec<String> = Vec::new();
+    let mut agent_attempts = Vec::new();
+
+    loop {
+        let (mut result, cancelled) = run_attempts(app, run, &agent).await?;
+        tried.extend([agent.clone(), result.agent_used.clone()]);
+        let mut attempt = agent_fallback::AgentAttempt {
+            agent: result.agent_used.clone(),
+            success: result.success,
+            execution_time: result.execution_time,
+            fallback_reason: None,
+        };
+
+        let fallback = if result.success || cancelled {
+            None
+        } else {
+            settings
+                .failure_reason(&format!("{}\n{}", result.output, result.stderr))
+                .and_then(|reason| Some((settings.next_agent(&result.agent_used, &tried)?, reason)))
+        };
+        let Some((next, reason)) = fallback else {
+            agent_attempts.push(attempt);
+            result.agent_attempts = agent_attempts;
+            return Ok((result, cancelled));
+        };
+
+        let _ = app.emit(
+            agent_fallback::TASK_AGENT_FALLBACK_EVENT,
+            agent_fallback::AgentFallback { task_id: &run.task_id, from: &result.agent_used, to: &next, reason: &reason },
+        );
+        attempt.fallback_reason = Some(reason);
+        agent_attempts.push(attempt);
+        agent = next;
     }
-    
-    std::fs::write(&settings_path, settings)
-        .map_err(|e| format!("Failed to save settings: {}", e))?;
-    
-    Ok(())
 }
 
-/// Test Python connection
-#[tauri::command]
-async fn test_python_connection(python_path: String) -> Result<String, String> {
-    let parts: Vec<&str> = python_path.split_whitespace().collect();
-    if parts.is_empty() {
-        return Err("Invalid Python path".to_string());
+/// Attempt a task with `agent` until it succeeds, fails for good or is cancelled (true when cancelled)
+async fn run_attempts(app: &tauri::AppHandle, run: &TaskRun, agent: &str) -> Result<(TaskResult, bool), String> {
+    use tauri::Emitter;
+
+    let TaskRun { task, task_id, timeout, env, retry, .. } = run;
+    let (timeout, retry) = (*timeout, *retry);
+    let max_attempts = retry.map_or(1, |policy| policy.max_attempts.max(1));
+    let mut attempt = 1;
+
+    loop {
+        let (mut result, ending) =
+            run_vibe_task(app, task.clone(), agent.to_string(), task_id.clone(), timeout, env.clone()).await?;
+        result.attempts = attempt;
+        for path in &result.files_changed {
+            record_reported_file(app, task_id, path);
+        }
+
+        let reason = match (retry, ending) {
+            (Some(policy), task_process::Ending::Exited) if !result.success && attempt < max_attempts => {
+                task_retry::retryable_reason(&result.output).map(|reason| (policy, reason))
+            }
+            _ => None,
+        };
+        let Some((policy, reason)) = reason else {
+            return Ok((result, ending == task_process::Ending::Cancelled));
+        };
+
+        let delay = policy.delay(attempt);
+        attempt += 1;
+        let _ = app.emit(
+            task_retry::TASK_RETRY_EVENT,
+            task_retry::TaskRetry {
+                task_id,
+                attempt,
+                max_attempts,
+                delay_ms: delay.as_millis() as u64,
+                reason,
+            },
+        );
+        if !task_process::wait_before_retry(task_id, delay).await {
+            result.output.push_str(&format!("\n⛔ Task cancelled before attempt {}/{}", attempt, max_attempts));
+            return Ok((result, true));
+        }
     }
-    
-    let python_cmd = parts[0];
-    
-    let output = Command::new(python_cmd)
-        .arg("--version")
-        .output()
-        .map_err(|e| format!("Failed to execute Python: {}", e))?;
-    
-    if output.status.success() {
-        let version = String::from_utf8_lossy(&output.stdout).to_string();
-        Ok(format!("Connected: {}", version.trim()))
-    } else {
-        Err(format!("Python error: {}", String::from_utf8_lossy(&output.stderr)))
+}
+
+/// Record a file vibe.py reported as changed on the run's own list
+///
+/// vibe.py reports paths only, so the change counts as "modified", with the
+/// line counts `line_stats` finds.
+pub(crate) fn record_reported_file(app: &tauri::AppHandle, task_id: &str, path: &str) {
+    let counts = line_stats::counts_in_current_project(path, None);
+    let file = ChangedFile {
+        path: path.to_string(),
+        status: "modified".to_string(),
+        lines_added: counts.map(|c| c.0),
+        lines_removed: counts.map(|c| c.1),
+        renamed_from: None,
+        detected_during_task: false,
+    };
+    if let Err(e) = task_manager::manager(app).record_file(task_id, file) {
+        eprintln!("Failed to record changed file: {}", e);
     }
 }
 
-/// Execute a task using vibe.py
-#[tauri::command]
-async fn execute_task(task: String, agent: String) -> Result<TaskResult, String> {
-    let vibe_path = get_vibe_path();
+/// Run vibe.py for one task until it exits or is cancelled
+async fn run_vibe_task(
+    app: &tauri::AppHandle,
+    task: String,
+    agent: String,
+    task_id: String,
+    timeout: Option<std::time::Duration>,
+    env: Option<HashMap<String, String>>,
+) -> Result<(TaskResult, task_process::Ending), String> {
+    let vibe_path = python_env::vibe_path();
     let start = std::time::Instant::now();
-    
-    let mut cmd = Command::new("python");
+
+    let mut cmd = python_env::command()?;
     cmd.arg(&vibe_path)
        .arg("task")
-       .arg(&task);
-    
+       .arg(&task)
+       // After the command, where a vibe.py without --json ignores it
+       .arg(vibe_json::JSON_FLAG);
+
     // Add agent flag if not auto
     match agent.as_str() {
         "api" => { cmd.arg("--api"); }
@@ -313,139 +603,227 @@ async fn execute_task(task: String, agent: String) -> Result<TaskResult, String>
         "antigravity" => { cmd.arg("--antigravity"); }
         _ => {} // auto - no flag needed
     }
-    
-    // Set working directory to project root
-    if let Some(parent) = vibe_path.parent() {
-        cmd.current_dir(parent);
-    }
-    
-    let output = cmd.output().map_err(|e| format!("Failed to execute: {}", e))?;
-    
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    
+
+    target_project(&mut cmd, &vibe_path);
+
+    let child_env = child_env::build_child_env(&child_env::ChildContext::Task).with_request_vars(env.as_ref());
+    child_env.apply(&mut cmd);
+    let invocation = child_env::Invocation::capture(&cmd);
+
+    let prompts = task_input::Prompts::emitting(app, &task_id);
+    let progress = task_progress::watcher(app, &task_id, child_env.clone());
+    let run_id = task_id.clone();
+    let output = tauri::async_runtime::spawn_blocking(move || {
+        task_process::run(&run_id, &mut cmd, timeout, Some(prompts), Some(progress))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+    let mut output = output;
+    output.stdout = child_env.mask_values(&output.stdout);
+    output.stderr = child_env.mask_values(&output.stderr);
+    let report = vibe_json::parse::<vibe_json::RunReport>(&output.stdout);
+    let combined = task_process::TaskProcessSettings::load().combined_output;
+    let (success, exit_code, ending) = (output.success, output.exit_code, output.ending);
+
     let execution_time = start.elapsed().as_secs_f64();
-    
-    if output.status.success() {
-        Ok(TaskResult {
-            success: true,
-            output: stdout,
-            agent_used: if agent == "auto" { "auto".to_string() } else { agent },
-            execution_time,
-        })
-    } else {
-        Ok(TaskResult {
+
+    let stopped = match ending {
+        task_process::Ending::Cancelled => Some(format!("⛔ Task cancelled after {:.1}s", execution_time)),
+        task_process::Ending::TimedOut(limit) => Some(format!("⏱️ Task timed out after {}s", limit.as_secs())),
+        task_process::Ending::Exited => None,
+    };
+    if let Some(note) = stopped {
+        let result = TaskResult {
+            task_id,
             success: false,
-            output: format!("{}\n{}", stdout, stderr),
+            output: format!("{}\n{}", output.console(combined), note),
+            stderr: output.stderr,
             agent_used: agent,
+            agent_resolution: agent_stats::RESOLUTION_UNKNOWN.to_string(),
             execution_time,
-        })
+            env: child_env.provided(),
+            invocation: Some(invocation),
+            files_changed: Vec::new(),
+            exit_code,
+            attempts: 1,
+            agent_attempts: Vec::new(),
+            output_truncated: false,
+            full_output_path: None,
+        };
+        return Ok((result, ending));
     }
-}
 
-/// List available workflows
-#[tauri::command]
-async fn list_workflows() -> Result<Vec<WorkflowInfo>, String> {
-    let vibe_path = get_vibe_path();
-    
-    let mut cmd = Command::new("python");
-    cmd.arg(&vibe_path)
-       .arg("workflow")
-       .arg("list");
-    
-    if let Some(parent) = vibe_path.parent() {
-        cmd.current_dir(parent);
-    }
-    
-    let output = cmd.output().map_err(|e| format!("Failed to list workflows: {}", e))?;
-    
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    
-    // Parse workflow list from output
-    let mut workflows = Vec::new();
-    for line in stdout.lines() {
-        if line.contains(" - ") {
-            let parts: Vec<&str> = line.splitn(2, " - ").collect();
-            if parts.len() == 2 {
-                workflows.push(WorkflowInfo {
-                    name: parts[0].trim().replace("• ", "").to_string(),
-                    description: parts[1].trim().to_string(),
-                });
-            }
-        }
+    // In --json mode stdout holds only the report and the log is on stderr
+    let (console, files_changed, reported_agent) = match report {
+        Some(report) => (output.stderr.clone(), report.files_changed, report.agent_used),
+        None => (output.console(combined), Vec::new(), detect_agent_from_output(&output.stdout)),
+    };
+
+    // Resolve which agent vibe.py actually routed to
+    let (agent_used, agent_resolution) = match agent.as_str() {
+        "api" | "cli" | "antigravity" => (agent, agent_stats::RESOLUTION_EXPLICIT),
+        _ => match reported_agent {
+            Some(detected) => (detected.to_lowercase(), agent_stats::RESOLUTION_DETECTED),
+            None => ("auto".to_string(), agent_stats::RESOLUTION_UNKNOWN),
+        },
+    };
+
+    if let Err(e) = agent_stats::record_run(&agent_used, agent_resolution, success, execution_time) {
+        eprintln!("Failed to record agent stats: {}", e);
     }
-    
-    Ok(workflows)
+
+    let result = TaskResult {
+        task_id,
+        success,
+        output: console,
+        stderr: output.stderr,
+        agent_used,
+        agent_resolution: agent_resolution.to_string(),
+        execution_time,
+        env: child_env.provided(),
+        invocation: Some(invocation),
+        files_changed,
+        exit_code,
+        attempts: 1,
+        agent_attempts: Vec::new(),
+        output_truncated: false,
+        full_output_path: None,
+    };
+    Ok((result, ending))
 }
 
-/// Run a workflow by name
-#[tauri::command]
-async fn run_workflow(name: String, dry_run: bool) -> Result<TaskResult, String> {
-    let vibe_path = get_vibe_path();
-    let start = std::time::Instant::now();
-    
-    let mut cmd = Command::new("python");
-    cmd.arg(&vibe_path)
-       .arg("workflow")
-       .arg(&name);
-    
-    if dry_run {
-        cmd.arg("--dry-run");
-    }
+/// Parse the agent announcement printed by vibe.py's orchestrator
+/// ("🧠 Auto-routed: api (85% confidence)" or "⚡ Forced agent: cli")
+fn detect_agent_from_output(output: &str) -> Option<String> {
+    const MARKERS: [&str; 2] = ["Auto-routed:", "Forced agent:"];
     
-    if let Some(parent) = vibe_path.parent() {
-        cmd.current_dir(parent);
-    }
-    
-    let output = cmd.output().map_err(|e| format!("Failed to run workflow: {}", e))?;
-    
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    
-    let execution_time = start.elapsed().as_secs_f64();
-    
-    Ok(TaskResult {
-        success: output.status.success(),
-        output: format!("{}{}", stdout, stderr),
-        agent_used: "workflow".to_string(),
-        execution_time,
+    output.lines().find_map(|line| {
+        MARKERS.iter().find_map(|marker| {
+            let rest = &line[line.find(marker)? + marker.len()..];
+            let agent = rest.split_whitespace().next()?;
+            let agent = agent.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+            if agent.is_empty() { None } else { Some(agent.to_lowercase()) }
+        })
     })
 }
 
+/// List available workflows: the open project's, then the global ones they do not shadow
+#[tauri::command]
+async fn list_workflows() -> Result<Vec<WorkflowInfo>, String> {
+    command_metrics::timed("list_workflows", async move {
+        let vibe_path = python_env::vibe_path();
+
+        let mut cmd = python_env::command()?;
+        cmd.arg(&vibe_path)
+           .arg("workflow")
+           .arg("list")
+           .arg(vibe_json::JSON_FLAG);
+
+        if let Some(parent) = vibe_path.parent() {
+            cmd.current_dir(parent);
+        }
+        child_env::build_child_env(&child_env::ChildContext::Task).apply(&mut cmd);
+
+        let output = cmd.output().map_err(|e| format!("Failed to list workflows: {}", e))?;
+        let global = workflow_scope::with_yaml_errors(vibe_json::workflows(&String::from_utf8_lossy(&output.stdout)));
+
+        let project = workflow_scope::project_folder()
+            .map(|folder| workflow_scope::list_in(&folder, workflow_scope::SCOPE_PROJECT))
+            .unwrap_or_default();
+        Ok(workflow_scope::merge(project, global))
+    }).await
+}
+
+/// Run a workflow by name, step by step (`env` is added to each step's environment)
+///
+/// Every step reports `workflow-step-started` and `workflow-step-finished`,
+/// and the result lists each step's outcome (see `workflow_run`). `variables`
+/// override the defaults of the workflow's `variables:` block
+/// (see `get_workflow_variables`). The run is tracked under `task_id` (a new
+/// UUID when omitted, returned as the result's `task_id`), which
+/// `cancel_task`, `send_task_input`, `get_task_status` and `abort_workflow`
+/// accept. `scope` ("project" or "global") picks which folder `name` is
+/// looked up in; without it a project workflow shadows the global one. A
+/// workflow that fails `validate_workflow` is refused with its diagnostics
+/// unless `force` is set. `snapshot` saves the project first so
+/// `restore_snapshot` can undo the run (default: `workflowSnapshots.enabled`;
+/// see `workflow_snapshot`). Like `execute_task`, a run (not a dry run) is
+/// refused while the project has merge conflicts unless `allow_conflicts` is
+/// set. Afterwards the changed files list is rebuilt from git, as after
+/// `execute_task`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)] // arguments of the frontend's invoke call
+async fn run_workflow(
+    app: tauri::AppHandle,
+    name: String,
+    dry_run: bool,
+    variables: Option<HashMap<String, String>>,
+    env: Option<HashMap<String, String>>,
+    task_id: Option<String>,
+    force: Option<bool>,
+    scope: Option<String>,
+    snapshot: Option<bool>,
+    allow_conflicts: Option<bool>,
+) -> Result<workflow_run::WorkflowRunResult, String> {
+    command_metrics::timed("run_workflow", async move {
+        if !dry_run {
+            merge_conflicts::check(allow_conflicts.unwrap_or(false)).await?;
+        }
+        let request = workflow_run::RunRequest {
+            name,
+            dry_run,
+            variables: variables.unwrap_or_default(),
+            env,
+            task_id: task_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            triggered_by: None,
+            scope,
+            step: None,
+            snapshot,
+        };
+        let result = workflow_run::run_if_valid(&app, request, force.unwrap_or(false)).await;
+        git_status::refresh_after_run(&app);
+        result
+    }).await
+}
+
 /// Get project context
 #[tauri::command]
-async fn get_context() -> Result<String, String> {
-    let vibe_path = get_vibe_path();
-    
-    let mut cmd = Command::new("python");
-    cmd.arg(&vibe_path)
-       .arg("context");
-    
-    if let Some(parent) = vibe_path.parent() {
-        cmd.current_dir(parent);
-    }
-    
-    let output = cmd.output().map_err(|e| format!("Failed to get context: {}", e))?;
-    
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+async fn get_context() -> Result<vibe_json::ProjectContext, String> {
+    command_metrics::timed("get_context", async move {
+        let vibe_path = python_env::vibe_path();
+
+        let mut cmd = python_env::command()?;
+        cmd.arg(&vibe_path)
+           .arg("context")
+           .arg(vibe_json::JSON_FLAG);
+
+        target_project(&mut cmd, &vibe_path);
+        child_env::build_child_env(&child_env::ChildContext::Task).apply(&mut cmd);
+
+        let output = cmd.output().map_err(|e| format!("Failed to get context: {}", e))?;
+
+        Ok(vibe_json::context(&String::from_utf8_lossy(&output.stdout)))
+    }).await
 }
 
 /// Get session statistics
 #[tauri::command]
-async fn get_stats() -> Result<String, String> {
-    let vibe_path = get_vibe_path();
-    
-    let mut cmd = Command::new("python");
-    cmd.arg(&vibe_path)
-       .arg("stats");
-    
-    if let Some(parent) = vibe_path.parent() {
-        cmd.current_dir(parent);
-    }
-    
-    let output = cmd.output().map_err(|e| format!("Failed to get stats: {}", e))?;
-    
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+async fn get_stats() -> Result<vibe_json::SessionStats, String> {
+    command_metrics::timed("get_stats", async move {
+        let vibe_path = python_env::vibe_path();
+
+        let mut cmd = python_env::command()?;
+        cmd.arg(&vibe_path)
+           .arg("stats")
+           .arg(vibe_json::JSON_FLAG);
+
+        target_project(&mut cmd, &vibe_path);
+        child_env::build_child_env(&child_env::ChildContext::Task).apply(&mut cmd);
+
+        let output = cmd.output().map_err(|e| format!("Failed to get stats: {}", e))?;
+
+        Ok(vibe_json::stats(&String::from_utf8_lossy(&output.stdout)))
+    }).await
 }
 
 /// Simple greet command for testing
@@ -457,64 +835,85 @@ fn greet(name: &str) -> String {
 /// Open the workflows folder in file explorer
 #[tauri::command]
 async fn open_workflows_folder() -> Result<String, String> {
-    let workflows_path = get_workflows_path();
-    
-    // Create folder if it doesn't exist
-    if !workflows_path.exists() {
-        std::fs::create_dir_all(&workflows_path)
-            .map_err(|e| format!("Failed to create workflows folder: {}", e))?;
-    }
-    
-    // Open in file explorer based on OS
-    #[cfg(target_os = "windows")]
-    {
-        Command::new("explorer")
-            .arg(&workflows_path)
-            .spawn()
-            .map_err(|e| format!("Failed to open folder: {}", e))?;
-    }
-    
-    #[cfg(target_os = "macos")]
-    {
-        Command::new("open")
-            .arg(&workflows_path)
-            .spawn()
-            .map_err(|e| format!("Failed to open folder: {}", e))?;
-    }
-    
-    #[cfg(target_os = "linux")]
-    {
-        Command::new("xdg-open")
-            .arg(&workflows_path)
-            .spawn()
-            .map_err(|e| format!("Failed to open folder: {}", e))?;
-    }
-    
-    Ok(workflows_path.to_string_lossy().to_string())
+    command_metrics::timed("open_workflows_folder", async move {
+        let workflows_path = get_workflows_path();
+
+        // Create folder if it doesn't exist
+        if !workflows_path.exists() {
+            std::fs::create_dir_all(&workflows_path)
+                .map_err(|e| format!("Failed to create workflows folder: {}", e))?;
+        }
+
+        // Open in file explorer based on OS
+        #[cfg(target_os = "windows")]
+        {
+            child_env::system_command("explorer")
+                .arg(&workflows_path)
+                .spawn()
+                .map_err(|e| format!("Failed to open folder: {}", e))?;
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            child_env::system_command("open")
+                .arg(&workflows_path)
+                .spawn()
+                .map_err(|e| format!("Failed to open folder: {}", e))?;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            child_env::system_command("xdg-open")
+                .arg(&workflows_path)
+                .spawn()
+                .map_err(|e| format!("Failed to open folder: {}", e))?;
+        }
+
+        Ok(workflows_path.to_string_lossy().to_string())
+    }).await
 }
 
-/// Create a new workflow file
+/// Create a new workflow file in `scope` ("project" or "global", the default)
+///
+/// With `template_id` (see `list_workflow_templates`) the file is that
+/// template, its `variables` defaults set from `variables`; without it, a
+/// two-step stub.
 #[tauri::command]
-async fn create_workflow(name: String) -> Result<String, String> {
-    let workflows_path = get_workflows_path();
-    
-    // Create folder if it doesn't exist
-    if !workflows_path.exists() {
-        std::fs::create_dir_all(&workflows_path)
-            .map_err(|e| format!("Failed to create workflows folder: {}", e))?;
-    }
-    
-    // Sanitize name for filename
-    let file_name = name.to_lowercase().replace(" ", "-");
-    let file_path = workflows_path.join(format!("{}.yaml", file_name));
-    
-    // Check if file already exists
-    if file_path.exists() {
-        return Err(format!("Workflow '{}' already exists", name));
-    }
-    
-    // Create workflow template
-    let template = format!(r#"# {} Workflow
+async fn create_workflow(
+    app: tauri::AppHandle,
+    name: String,
+    scope: Option<String>,
+    template_id: Option<String>,
+    variables: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    command_metrics::timed("create_workflow", async move {
+        let workflows_path = workflow_scope::folder(scope.as_deref())?;
+        let lint_scope = if scope.as_deref() == Some(workflow_scope::SCOPE_PROJECT) { "project" } else { "app" };
+
+        // Create folder if it doesn't exist
+        if !workflows_path.exists() {
+            std::fs::create_dir_all(&workflows_path)
+                .map_err(|e| format!("Failed to create workflows folder: {}", e))?;
+        }
+
+        // Sanitize name for filename
+        let file_name = workflow_files::file_stem(&name)?;
+        let file_path = workflows_path.join(format!("{}.yaml", file_name));
+
+        // Check if file already exists
+        if file_path.exists() {
+            return Err(format!("Workflow '{}' already exists", name));
+        }
+
+        let name_warnings = name_lint::check_new_name(name_lint::KIND_WORKFLOW, lint_scope, &file_name, Some(&file_name));
+
+        // Create workflow from the chosen template, or the stub
+        let template = match &template_id {
+            Some(id) => workflow_templates::create_content(id, &name, &variables.unwrap_or_default())?,
+            None if variables.as_ref().is_some_and(|v| !v.is_empty()) => {
+                return Err("Workflow variables need a template_id".to_string());
+            }
+            None => format!(r#"# {} Workflow
 name: {}
 description: Add description here
 
@@ -531,100 +930,98 @@ steps:
     agent: cli
     task: |
       echo "Step 2 completed"
-"#, name, file_name);
-    
-    // Write template to file
-    std::fs::write(&file_path, template)
-        .map_err(|e| format!("Failed to create workflow file: {}", e))?;
-    
-    // Open the file in default editor
-    #[cfg(target_os = "windows")]
-    {
-        Command::new("notepad")
-            .arg(&file_path)
-            .spawn()
-            .map_err(|e| format!("Failed to open file: {}", e))?;
-    }
-    
-    #[cfg(target_os = "macos")]
-    {
-        Command::new("open")
-            .arg("-t")
-            .arg(&file_path)
-            .spawn()
-            .map_err(|e| format!("Failed to open file: {}", e))?;
-    }
-    
-    #[cfg(target_os = "linux")]
-    {
-        Command::new("xdg-open")
-            .arg(&file_path)
-            .spawn()
-            .map_err(|e| format!("Failed to open file: {}", e))?;
-    }
-    
-    Ok(file_path.to_string_lossy().to_string())
+"#, name, file_name),
+        };
+
+        // Write template to file
+        std::fs::write(&file_path, template)
+            .map_err(|e| format!("Failed to create workflow file: {}", e))?;
+
+        name_lint::emit_warnings(&app, name_lint::KIND_WORKFLOW, &file_name, &name_warnings);
+
+        // Open the file in default editor
+        #[cfg(target_os = "windows")]
+        {
+            child_env::system_command("notepad")
+                .arg(&file_path)
+                .spawn()
+                .map_err(|e| format!("Failed to open file: {}", e))?;
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            child_env::system_command("open")
+                .arg("-t")
+                .arg(&file_path)
+                .spawn()
+                .map_err(|e| format!("Failed to open file: {}", e))?;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            child_env::system_command("xdg-open")
+                .arg(&file_path)
+                .spawn()
+                .map_err(|e| format!("Failed to open file: {}", e))?;
+        }
+
+        Ok(file_path.to_string_lossy().to_string())
+    }).await
 }
 
-/// Set the current project path
+/// Set the current project path (refuses to drop unsaved changes unless `force`)
 #[tauri::command]
-async fn set_project_path(path: String) -> Result<String, String> {
-    let path_buf = PathBuf::from(&path);
-    
-    if !path_buf.exists() {
-        return Err(format!("Path does not exist: {}", path));
-    }
-    
-    if !path_buf.is_dir() {
-        return Err(format!("Path is not a directory: {}", path));
-    }
-    
-    // Store the project path in memory
-    let mut current = CURRENT_PROJECT.write().map_err(|e| format!("Lock error: {}", e))?;
-    *current = Some(path.clone());
-    
-    // Persist to config file
-    save_project_path(&path)?;
-    
-    Ok(path)
+async fn set_project_path(
+    app: tauri::AppHandle,
+    path: String,
+    force: Option<bool>,
+) -> Result<String, project_switch::ProjectSwitchError> {
+    command_metrics::timed("set_project_path", async move {
+        project_switch::switch_project(&app, &path, force.unwrap_or(false))
+    }).await
 }
 
 /// Get the current project path
 #[tauri::command]
 async fn get_project_path() -> Result<Option<String>, String> {
-    let current = CURRENT_PROJECT.read().map_err(|e| format!("Lock error: {}", e))?;
-    Ok(current.clone())
+    command_metrics::timed("get_project_path", async move {
+        let current = CURRENT_PROJECT.read().map_err(|e| format!("Lock error: {}", e))?;
+        Ok(current.clone())
+    }).await
 }
 
 /// Open folder dialog to select project
 #[tauri::command]
-async fn open_project_dialog(app: tauri::AppHandle) -> Result<Option<String>, String> {
-    use tauri_plugin_dialog::DialogExt;
-    
-    let folder = app.dialog()
-        .file()
-        .add_filter("All Files", &["*"])
-        .blocking_pick_folder();
-    
-    match folder {
-        Some(path) => {
-            let path_str = path.to_string();
-            // Set as current project in memory
-            let mut current = CURRENT_PROJECT.write().map_err(|e| format!("Lock error: {}", e))?;
-            *current = Some(path_str.clone());
-            
-            // Persist to config file
-            save_project_path(&path_str)?;
-            
-            Ok(Some(path_str))
+async fn open_project_dialog(app: tauri::AppHandle) -> Result<Option<String>, project_switch::ProjectSwitchError> {
+    command_metrics::timed("open_project_dialog", async move {
+        use tauri_plugin_dialog::DialogExt;
+
+        let folder = app.dialog()
+            .file()
+            .add_filter("All Files", &["*"])
+            .blocking_pick_folder();
+
+        match folder {
+            Some(path) => {
+                let path_str = path.to_string();
+                project_switch::switch_project(&app, &path_str, false)?;
+                Ok(Some(path_str))
+            }
+            None => Ok(None)
         }
-        None => Ok(None)
-    }
+    }).await
 }
 
 /// Load saved project path from config (called on app startup)
 #[tauri::command]
 async fn load_saved_project() -> Result<Option<String>, String> {
+    command_metrics::timed("load_saved_project", async move {
+        restore_saved_project()
+    }).await
+}
+
+/// Restore the saved project into memory if it still exists
+fn restore_saved_project() -> Result<Option<String>, String> {
     // First check memory
     {
         let current = CURRENT_PROJECT.read().map_err(|e| format!("Lock error: {}", e))?;
@@ -648,143 +1045,101 @@ async fn load_saved_project() -> Result<Option<String>, String> {
     Ok(None)
 }
 
-/// List directory contents for file explorer
+/// Add a changed file to tracking (to the run's own list when `task_id` is
+/// given), with line counts from `line_stats`
 #[tauri::command]
-async fn list_directory(path: String) -> Result<Vec<FileEntry>, String> {
-    let dir_path = PathBuf::from(&path);
-    
-    if !dir_path.exists() {
-        return Err(format!("Path does not exist: {}", path));
-    }
-    
-    if !dir_path.is_dir() {
-        return Err(format!("Path is not a directory: {}", path));
-    }
-    
-    let mut entries = Vec::new();
-    
-    let read_dir = std::fs::read_dir(&dir_path)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
-    
-    for entry in read_dir {
-        if let Ok(entry) = entry {
-            let file_name = entry.file_name().to_string_lossy().to_string();
-            
-            // Skip hidden files and common ignore patterns
-            if file_name.starts_with('.') && file_name != ".env" {
-                continue;
-            }
-            if file_name == "node_modules" || file_name == "target" || file_name == "__pycache__" || file_name == ".git" {
-                continue;
-            }
-            
-            let file_path = entry.path();
-            let is_dir = file_path.is_dir();
-            let metadata = entry.metadata().ok();
-            
-            let extension = if is_dir {
-                None
-            } else {
-                file_path.extension().map(|e| e.to_string_lossy().to_string())
-            };
-            
-            entries.push(FileEntry {
-                name: file_name,
-                path: file_path.to_string_lossy().to_string(),
-                is_dir,
-                extension,
-                size: metadata.map(|m| m.len()),
-                children: None,
-            });
-        }
-    }
-    
-    // Sort: directories first, then files, alphabetically
-    entries.sort_by(|a, b| {
-        match (a.is_dir, b.is_dir) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+async fn add_changed_file(
+    tasks: tauri::State<'_, task_manager::TaskManager>,
+    path: String,
+    status: String,
+    task_id: Option<String>,
+) -> Result<(), String> {
+    command_metrics::timed("add_changed_file", async move {
+        let lookup = path.clone();
+        let counts = tauri::async_runtime::spawn_blocking(move || line_stats::counts_in_current_project(&lookup, None))
+            .await
+            .map_err(|e| format!("Line stats failed: {}", e))?;
+        let (lines_added, lines_removed) = (counts.map(|c| c.0), counts.map(|c| c.1));
+        if let Some(task_id) = task_id {
+            return tasks.record_file(&task_id, ChangedFile { path, status, lines_added, lines_removed, renamed_from: None, detected_during_task: false });
         }
-    });
-    
-    Ok(entries)
-}
 
-/// Read file content
-#[tauri::command]
-async fn read_file_content(path: String) -> Result<String, String> {
-    let file_path = PathBuf::from(&path);
-    
-    if !file_path.exists() {
-        return Err(format!("File does not exist: {}", path));
-    }
-    
-    if !file_path.is_file() {
-        return Err(format!("Path is not a file: {}", path));
-    }
-    
-    // Check if file is too large (> 1MB)
-    let metadata = std::fs::metadata(&file_path)
-        .map_err(|e| format!("Failed to read metadata: {}", e))?;
-    
-    if metadata.len() > 1024 * 1024 {
-        return Err("File is too large to display (> 1MB)".to_string());
-    }
-    
-    std::fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read file: {}", e))
-}
+        let mut files = CHANGED_FILES.write().map_err(|e| format!("Lock error: {}", e))?;
 
-/// Add a changed file to tracking
-#[tauri::command]
-async fn add_changed_file(path: String, status: String, lines_added: u32, lines_removed: u32) -> Result<(), String> {
-    let mut files = CHANGED_FILES.write().map_err(|e| format!("Lock error: {}", e))?;
-    
-    // Remove existing entry for same path
-    files.retain(|f| f.path != path);
-    
-    files.push(ChangedFile {
-        path,
-        status,
-        lines_added,
-        lines_removed,
-    });
-    
-    Ok(())
+        // Remove existing entry for same path
+        files.retain(|f| f.path != path);
+
+        files.push(ChangedFile {
+            path,
+            status,
+            lines_added,
+            lines_removed,
+            renamed_from: None,
+            detected_during_task: false,
+        });
+
+        Ok(())
+    }).await
 }
 
-/// Get all changed files
+/// Get all changed files, or those of one run when `task_id` is given
 #[tauri::command]
-async fn get_changed_files() -> Result<Vec<ChangedFile>, String> {
-    let files = CHANGED_FILES.read().map_err(|e| format!("Lock error: {}", e))?;
-    Ok(files.clone())
+async fn get_changed_files(
+    tasks: tauri::State<'_, task_manager::TaskManager>,
+    task_id: Option<String>,
+) -> Result<Vec<ChangedFile>, String> {
+    command_metrics::timed("get_changed_files", async move {
+        if let Some(task_id) = task_id {
+            let task = tasks.get(&task_id).ok_or_else(|| format!("Unknown task '{}'", task_id))?;
+            return Ok(task.changed_files);
+        }
+
+        let files = CHANGED_FILES.read().map_err(|e| format!("Lock error: {}", e))?;
+        Ok(files.clone())
+    }).await
 }
 
-/// Clear changed files
+/// Clear the live changed files (snapshots in task history are kept)
 #[tauri::command]
 async fn clear_changed_files() -> Result<(), String> {
-    let mut files = CHANGED_FILES.write().map_err(|e| format!("Lock error: {}", e))?;
-    files.clear();
-    Ok(())
+    command_metrics::timed("clear_changed_files", async move {
+        let result = CHANGED_FILES
+            .write()
+            .map(|mut files| files.clear())
+            .map_err(|e| format!("Lock error: {}", e));
+        audit::record("clear_changed_files", serde_json::Value::Null, audit::ORIGIN_COMMAND, &result);
+        result
+    }).await
 }
 
 // ============================================
 // SKILLS ECOSYSTEM COMMANDS
 // ============================================
 
-/// List all skills in the .agent/skills directory
-#[tauri::command]
-async fn list_skills() -> Result<Vec<Skill>, String> {
+/// All skills in the skills directory, without recording a command timing
+pub(crate) fn current_skills() -> Result<Vec<Skill>, String> {
     let skills_path = get_skills_path();
-    
+
     if !skills_path.exists() {
         return Ok(Vec::new());
     }
-    
+
+    skill_index::get_or_scan(&skills_path, scan_skills)
+}
+
+/// List all skills in the .agent/skills directory
+#[tauri::command]
+async fn list_skills() -> Result<Vec<Skill>, String> {
+    command_metrics::timed("list_skills", async move {
+        current_skills()
+    }).await
+}
+
+/// Parse every skill folder in a skills directory
+fn scan_skills(skills_path: &std::path::Path) -> Result<Vec<Skill>, String> {
     let mut skills = Vec::new();
     
-    let entries = std::fs::read_dir(&skills_path)
+    let entries = std::fs::read_dir(skills_path)
         .map_err(|e| format!("Failed to read skills directory: {}", e))?;
     
     for entry in entries.flatten() {
@@ -800,12 +1155,11 @@ async fn list_skills() -> Result<Vec<Skill>, String> {
             .to_string();
         
         // Parse SKILL.md if exists
-        let (name, description, version, category) = if skill_md_path.exists() {
-            parse_skill_frontmatter(&skill_md_path).unwrap_or_else(|_| {
-                (skill_name.clone(), String::new(), "1.0.0".to_string(), None)
-            })
+        let metadata = if skill_md_path.exists() {
+            parse_skill_frontmatter(&skill_md_path)
+                .unwrap_or_else(|_| SkillMetadata::fallback(&skill_name))
         } else {
-            (skill_name.clone(), String::new(), "1.0.0".to_string(), None)
+            SkillMetadata::fallback(&skill_name)
         };
         
         // Check for scripts and guardrails
@@ -816,20 +1170,22 @@ async fn list_skills() -> Result<Vec<Skill>, String> {
         let metadata = std::fs::metadata(&path).ok();
         let created_at = metadata.as_ref()
             .and_then(|m| m.created().ok())
-            .map(|t| format!("{:?}", t))
+            .map(path_metadata::rfc3339)
             .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
         let updated_at = metadata.as_ref()
             .and_then(|m| m.modified().ok())
-            .map(|t| format!("{:?}", t))
+            .map(path_metadata::rfc3339)
             .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
         
         skills.push(Skill {
             id: skill_name.clone(),
-            name,
-            description,
+            name: metadata.name,
+            description: metadata.description,
             path: path.to_string_lossy().to_string(),
-            version,
-            category,
+            version: metadata.version,
+            category: metadata.category,
+            tags: metadata.tags.unwrap_or_default(),
+            archived: metadata.archived,
             has_scripts,
             has_guardrails,
             created_at,
@@ -841,74 +1197,110 @@ async fn list_skills() -> Result<Vec<Skill>, String> {
 }
 
 /// Parse SKILL.md frontmatter (YAML between ---)
-fn parse_skill_frontmatter(path: &PathBuf) -> Result<(String, String, String, Option<String>), String> {
+fn parse_skill_frontmatter(path: &PathBuf) -> Result<SkillMetadata, String> {
     let content = std::fs::read_to_string(path)
         .map_err(|e| format!("Failed to read SKILL.md: {}", e))?;
     
     // Simple frontmatter parsing
-    let mut name = String::new();
-    let mut description = String::new();
-    let mut version = "1.0.0".to_string();
-    let mut category = None;
+    let mut metadata = SkillMetadata::fallback("");
+    let mut in_tag_list = false;
     
     if content.starts_with("---") {
         if let Some(end_idx) = content[3..].find("---") {
             let frontmatter = &content[3..end_idx + 3];
             for line in frontmatter.lines() {
                 let line = line.trim();
+                
+                // Block-style tag list ("tags:" followed by "- tag" lines)
+                if in_tag_list {
+                    if let Some(tag) = line.strip_prefix("- ") {
+                        metadata.tags.get_or_insert_with(Vec::new)
+                            .push(tag.trim().trim_matches('"').to_string());
+                        continue;
+                    }
+                    in_tag_list = false;
+                }
+                
                 if line.starts_with("name:") {
-                    name = line[5..].trim().trim_matches('"').to_string();
+                    metadata.name = line[5..].trim().trim_matches('"').to_string();
                 } else if line.starts_with("description:") {
-                    description = line[12..].trim().trim_matches('"').to_string();
+                    metadata.description = line[12..].trim().trim_matches('"').to_string();
                 } else if line.starts_with("version:") {
-                    version = line[8..].trim().trim_matches('"').to_string();
+                    metadata.version = line[8..].trim().trim_matches('"').to_string();
+                } else if line.starts_with("author:") {
+                    metadata.author = Some(line[7..].trim().trim_matches('"').to_string());
                 } else if line.starts_with("category:") {
-                    category = Some(line[9..].trim().trim_matches('"').to_string());
+                    metadata.category = Some(line[9..].trim().trim_matches('"').to_string());
+                } else if line.starts_with("archived:") {
+                    metadata.archived = line[9..].trim().trim_matches('"') == "true";
+                } else if line.starts_with("tags:") {
+                    let value = line[5..].trim();
+                    if value.is_empty() {
+                        in_tag_list = true;
+                    } else {
+                        // Inline list: tags: [a, b] or tags: a, b
+                        let tags = value.trim_start_matches('[').trim_end_matches(']')
+                            .split(',')
+                            .map(|t| t.trim().trim_matches('"').trim_matches('\'').to_string())
+                            .filter(|t| !t.is_empty())
+                            .collect();
+                        metadata.tags = Some(tags);
+                    }
                 }
             }
         }
     }
     
-    Ok((name, description, version, category))
+    Ok(metadata)
+}
+
+impl SkillMetadata {
+    /// Metadata for a skill folder without (valid) frontmatter
+    fn fallback(skill_name: &str) -> Self {
+        Self {
+            name: skill_name.to_string(),
+            description: String::new(),
+            version: "1.0.0".to_string(),
+            author: None,
+            category: None,
+            tags: None,
+            archived: false,
+        }
+    }
 }
 
 /// Get a specific skill by ID
 #[tauri::command]
 async fn get_skill(skill_id: String) -> Result<Skill, String> {
-    let skills = list_skills().await?;
-    skills.into_iter()
-        .find(|s| s.id == skill_id)
-        .ok_or_else(|| format!("Skill '{}' not found", skill_id))
+    command_metrics::timed("get_skill", async move {
+        let skills = current_skills()?;
+        skills.into_iter()
+            .find(|s| s.id == skill_id)
+            .ok_or_else(|| format!("Skill '{}' not found", skill_id))
+    }).await
 }
 
-/// Create a new skill folder with SKILL.md template
-#[tauri::command]
-async fn create_skill(name: String, description: String, category: Option<String>) -> Result<Skill, String> {
-    let skills_path = get_skills_path();
-    
-    // Create skills directory if it doesn't exist
-    std::fs::create_dir_all(&skills_path)
-        .map_err(|e| format!("Failed to create skills directory: {}", e))?;
-    
-    // Create skill folder name (kebab-case)
-    let skill_id = name.to_lowercase().replace(' ', "-");
-    let skill_folder = skills_path.join(&skill_id);
-    
-    if skill_folder.exists() {
-        return Err(format!("Skill '{}' already exists", skill_id));
+/// Folder name for a new skill called `name`, refused when nothing usable is left
+fn new_skill_id(name: &str) -> Result<String, String> {
+    let skill_id = name_lint::slugify(name);
+    if !skill_archive::is_valid_skill_id(&skill_id) {
+        return Err(format!("'{}' can't be used as a skill name", name));
     }
-    
-    // Create skill folder structure
-    std::fs::create_dir_all(&skill_folder)
+    Ok(skill_id)
+}
+
+/// Create `skill_folder` with a SKILL.md and guardrails.md template and an empty scripts/ folder
+fn write_new_skill(skill_folder: &std::path::Path, name: &str, description: &str, category: Option<&str>) -> Result<(), String> {
+    std::fs::create_dir_all(skill_folder)
         .map_err(|e| format!("Failed to create skill folder: {}", e))?;
     std::fs::create_dir_all(skill_folder.join("scripts"))
         .map_err(|e| format!("Failed to create scripts folder: {}", e))?;
-    
+
     // Create SKILL.md with frontmatter
-    let category_line = category.as_ref()
+    let category_line = category
         .map(|c| format!("category: \"{}\"\n", c))
         .unwrap_or_default();
-    
+
     let skill_md_content = format!(r#"---
 name: "{}"
 description: "{}"
@@ -927,10 +1319,10 @@ Describe how to use this skill.
 
 Add examples of skill usage.
 "#, name, description, category_line, name, description);
-    
+
     std::fs::write(skill_folder.join("SKILL.md"), skill_md_content)
         .map_err(|e| format!("Failed to create SKILL.md: {}", e))?;
-    
+
     // Create guardrails.md template
     let guardrails_content = format!(r#"# Guardrails for {}
 
@@ -944,59 +1336,195 @@ Add examples of skill usage.
 
 - Maximum execution time: 30s
 - Rate limit: 10 requests/minute
+
+## Environment
+
+<!-- Only these variables (plus PATH, HOME, ...) reach this skill's scripts -->
+- env_allow:
 "#, name);
-    
+
     std::fs::write(skill_folder.join("guardrails.md"), guardrails_content)
-        .map_err(|e| format!("Failed to create guardrails.md: {}", e))?;
-    
-    // Return the created skill
-    get_skill(skill_id).await
+        .map_err(|e| format!("Failed to create guardrails.md: {}", e))
+}
+
+/// Create a new skill folder with SKILL.md template
+#[tauri::command]
+async fn create_skill(app: tauri::AppHandle, name: String, description: String, category: Option<String>) -> Result<Skill, String> {
+    command_metrics::timed("create_skill", async move {
+        let skills_path = get_skills_path();
+
+        // Create skills directory if it doesn't exist
+        std::fs::create_dir_all(&skills_path)
+            .map_err(|e| format!("Failed to create skills directory: {}", e))?;
+
+        // Create skill folder name (kebab-case)
+        let skill_id = new_skill_id(&name)?;
+        let skill_folder = skills_path.join(&skill_id);
+
+        if skill_folder.exists() {
+            return Err(format!("Skill '{}' already exists", skill_id));
+        }
+
+        // Near-duplicates and cross-scope collisions only warn
+        let name_warnings = name_lint::check_new_name(
+            name_lint::KIND_SKILL,
+            name_lint::current_skill_scope(),
+            &skill_id,
+            Some(&name),
+        );
+
+        // Create skill folder structure
+        write_new_skill(&skill_folder, &name, &description, category.as_deref())?;
+
+        skill_index::invalidate();
+        name_lint::emit_warnings(&app, name_lint::KIND_SKILL, &skill_id, &name_warnings);
+
+        // Return the created skill
+        get_skill(skill_id).await
+    }).await
 }
 
 /// Update skill SKILL.md content
 #[tauri::command]
 async fn update_skill(skill_id: String, content: String) -> Result<(), String> {
-    let skills_path = get_skills_path();
-    let skill_folder = skills_path.join(&skill_id);
-    
-    if !skill_folder.exists() {
-        return Err(format!("Skill '{}' not found", skill_id));
-    }
-    
-    std::fs::write(skill_folder.join("SKILL.md"), content)
-        .map_err(|e| format!("Failed to update SKILL.md: {}", e))?;
-    
-    Ok(())
+    command_metrics::timed("update_skill", async move {
+        let skills_path = get_skills_path();
+        let skill_folder = skills_path.join(&skill_id);
+
+        if !skill_folder.exists() {
+            return Err(format!("Skill '{}' not found", skill_id));
+        }
+
+        std::fs::write(skill_folder.join("SKILL.md"), content)
+            .map_err(|e| format!("Failed to update SKILL.md: {}", e))?;
+
+        skill_index::invalidate();
+
+        Ok(())
+    }).await
 }
 
-/// Delete a skill folder
+/// Update SKILL.md frontmatter fields, leaving unknown keys and the body untouched
 #[tauri::command]
-async fn delete_skill(skill_id: String) -> Result<(), String> {
-    let skills_path = get_skills_path();
-    let skill_folder = skills_path.join(&skill_id);
-    
-    if !skill_folder.exists() {
-        return Err(format!("Skill '{}' not found", skill_id));
-    }
-    
-    std::fs::remove_dir_all(&skill_folder)
-        .map_err(|e| format!("Failed to delete skill: {}", e))?;
-    
-    Ok(())
+async fn update_skill_metadata(skill_id: String, patch: skill_frontmatter::SkillMetadataPatch) -> Result<Skill, String> {
+    command_metrics::timed("update_skill_metadata", async move {
+        let skill_md_path = get_skills_path().join(&skill_id).join("SKILL.md");
+
+        if !skill_md_path.exists() {
+            return Err(format!("Skill '{}' not found", skill_id));
+        }
+
+        let mut doc = skill_frontmatter::SkillDocument::load(&skill_md_path)?;
+        skill_frontmatter::apply_patch(&mut doc, &patch);
+        doc.save(&skill_md_path)?;
+
+        skill_index::invalidate();
+
+        get_skill(skill_id).await
+    }).await
+}
+
+/// Bump the skill version ("major" | "minor" | "patch"); returns the new version
+#[tauri::command]
+async fn bump_skill_version(skill_id: String, part: String) -> Result<String, String> {
+    command_metrics::timed("bump_skill_version", async move {
+        let skill_md_path = get_skills_path().join(&skill_id).join("SKILL.md");
+
+        if !skill_md_path.exists() {
+            return Err(format!("Skill '{}' not found", skill_id));
+        }
+
+        let mut doc = skill_frontmatter::SkillDocument::load(&skill_md_path)?;
+        let current = doc.get("version").unwrap_or_else(|| "1.0.0".to_string());
+        let version = skill_frontmatter::bump_version(&current, &part)?;
+        doc.set("version", &version);
+        doc.save(&skill_md_path)?;
+
+        skill_index::invalidate();
+
+        Ok(version)
+    }).await
+}
+
+/// Regenerate one markdown section of SKILL.md (e.g. "## Usage") in place
+///
+/// AI-generated sections pass their `provenance`, which is appended to the
+/// skill's generation.json with the section heading.
+#[tauri::command]
+async fn update_skill_section(
+    skill_id: String,
+    heading: String,
+    content: String,
+    provenance: Option<skill_provenance::GenerationRecord>,
+) -> Result<(), String> {
+    command_metrics::timed("update_skill_section", async move {
+        let skill_md_path = get_skills_path().join(&skill_id).join("SKILL.md");
+
+        if !skill_md_path.exists() {
+            return Err(format!("Skill '{}' not found", skill_id));
+        }
+
+        let mut doc = skill_frontmatter::SkillDocument::load(&skill_md_path)?;
+        doc.set_section(&heading, &content);
+        doc.save(&skill_md_path)?;
+
+        if let Some(mut record) = provenance {
+            record.section = Some(heading.clone());
+            if let Some(skill_folder) = skill_md_path.parent() {
+                skill_provenance::append(skill_folder, record)?;
+            }
+        }
+
+        skill_index::invalidate();
+
+        Ok(())
+    }).await
+}
+
+/// Delete a skill folder (refused if `preview_token` no longer matches the folder)
+#[tauri::command]
+async fn delete_skill(skill_id: String, preview_token: Option<String>) -> Result<(), String> {
+    command_metrics::timed("delete_skill", async move {
+        let skills_path = get_skills_path();
+        let skill_folder = skills_path.join(&skill_id);
+
+        let result = if !skill_folder.exists() {
+            Err(format!("Skill '{}' not found", skill_id))
+        } else {
+            preview_token
+                .as_deref()
+                .map_or(Ok(()), |token| skill_preview::verify_delete_token(&skill_folder, token))
+                .and_then(|_| std::fs::remove_dir_all(&skill_folder)
+                    .map_err(|e| format!("Failed to delete skill: {}", e)))
+        };
+        audit::record(
+            "delete_skill",
+            serde_json::json!({ "skill_id": skill_id, "path": skill_folder }),
+            audit::ORIGIN_COMMAND,
+            &result,
+        );
+        result?;
+
+        skill_index::invalidate();
+
+        Ok(())
+    }).await
 }
 
 /// Read skill SKILL.md content
 #[tauri::command]
 async fn read_skill_content(skill_id: String) -> Result<String, String> {
-    let skills_path = get_skills_path();
-    let skill_md_path = skills_path.join(&skill_id).join("SKILL.md");
-    
-    if !skill_md_path.exists() {
-        return Err(format!("Skill '{}' not found", skill_id));
-    }
-    
-    std::fs::read_to_string(&skill_md_path)
-        .map_err(|e| format!("Failed to read SKILL.md: {}", e))
+    command_metrics::timed("read_skill_content", async move {
+        let skills_path = get_skills_path();
+        let skill_md_path = skills_path.join(&skill_id).join("SKILL.md");
+
+        if !skill_md_path.exists() {
+            return Err(format!("Skill '{}' not found", skill_id));
+        }
+
+        std::fs::read_to_string(&skill_md_path)
+            .map_err(|e| format!("Failed to read SKILL.md: {}", e))
+    }).await
 }
 
 /// Script execution result
@@ -1005,97 +1533,55 @@ pub struct ScriptResult {
     pub success: bool,
     pub output: String,
     pub error: Option<String>,
+    /// Process exit code (None when killed by a signal)
+    #[serde(default)]
+    pub exit_code: Option<i32>,
     pub execution_time: f64,
+    /// Variables provided to the script (names and sources only)
+    #[serde(default)]
+    pub env: Vec<child_env::ProvidedVar>,
+    /// Program, arguments and working directory actually spawned
+    #[serde(default)]
+    pub invocation: Option<child_env::Invocation>,
+    /// Id shared with the skill-script-started/finished events
+    #[serde(default)]
+    pub execution_id: String,
 }
 
 /// List all scripts in a skill's scripts folder
 #[tauri::command]
 async fn list_skill_scripts(skill_id: String) -> Result<Vec<String>, String> {
-    let skills_path = get_skills_path();
-    let scripts_folder = skills_path.join(&skill_id).join("scripts");
-    
-    if !scripts_folder.exists() {
-        return Ok(Vec::new());
-    }
-    
-    let mut scripts = Vec::new();
-    let entries = std::fs::read_dir(&scripts_folder)
-        .map_err(|e| format!("Failed to read scripts folder: {}", e))?;
-    
-    for entry in entries {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(ext) = path.extension() {
-                    let ext_str = ext.to_string_lossy().to_lowercase();
-                    if ext_str == "py" || ext_str == "js" || ext_str == "mjs" {
-                        if let Some(name) = path.file_name() {
-                            scripts.push(name.to_string_lossy().to_string());
+    command_metrics::timed("list_skill_scripts", async move {
+        let skills_path = get_skills_path();
+        let scripts_folder = skills_path.join(&skill_id).join("scripts");
+
+        if !scripts_folder.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut scripts = Vec::new();
+        let entries = std::fs::read_dir(&scripts_folder)
+            .map_err(|e| format!("Failed to read scripts folder: {}", e))?;
+
+        for entry in entries {
+            if let Ok(entry) = entry {
+                let path = entry.path();
+                if path.is_file() {
+                    if let Some(ext) = path.extension() {
+                        let ext_str = ext.to_string_lossy().to_lowercase();
+                        if ext_str == "py" || ext_str == "js" || ext_str == "mjs" {
+                            if let Some(name) = path.file_name() {
+                                scripts.push(name.to_string_lossy().to_string());
+                            }
                         }
                     }
                 }
             }
         }
-    }
-    
-    scripts.sort();
-    Ok(scripts)
-}
 
-/// Run a skill script (Python, Node.js, etc.)
-#[tauri::command]
-async fn run_skill_script(skill_id: String, script_name: String) -> Result<ScriptResult, String> {
-    use std::time::Instant;
-    
-    let skills_path = get_skills_path();
-    let skill_folder = skills_path.join(&skill_id);
-    let scripts_folder = skill_folder.join("scripts");
-    let script_path = scripts_folder.join(&script_name);
-    
-    if !script_path.exists() {
-        return Err(format!("Script '{}' not found in skill '{}'", script_name, skill_id));
-    }
-    
-    // Determine script type by extension
-    let extension = script_path.extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("");
-    
-    let start_time = Instant::now();
-    
-    let output = match extension {
-        "py" => {
-            // Run Python script
-            Command::new("python")
-                .arg(&script_path)
-                .current_dir(&skill_folder)
-                .output()
-                .map_err(|e| format!("Failed to execute Python script: {}", e))?
-        },
-        "js" | "mjs" => {
-            // Run Node.js script
-            Command::new("node")
-                .arg(&script_path)
-                .current_dir(&skill_folder)
-                .output()
-                .map_err(|e| format!("Failed to execute Node.js script: {}", e))?
-        },
-        _ => {
-            return Err(format!("Unsupported script type: .{}", extension));
-        }
-    };
-    
-    let execution_time = start_time.elapsed().as_secs_f64();
-    
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    
-    Ok(ScriptResult {
-        success: output.status.success(),
-        output: stdout,
-        error: if stderr.is_empty() { None } else { Some(stderr) },
-        execution_time,
-    })
+        scripts.sort();
+        Ok(scripts)
+    }).await
 }
 
 // ============================================================================
@@ -1118,172 +1604,80 @@ pub struct SkillValidation {
 /// Test a skill by validating its structure and content
 #[tauri::command]
 async fn test_skill(skill_id: String) -> Result<SkillValidation, String> {
-    let skills_path = get_skills_path();
-    let skill_folder = skills_path.join(&skill_id);
-    
-    if !skill_folder.exists() {
-        return Err(format!("Skill '{}' not found", skill_id));
-    }
-    
-    let skill_md = skill_folder.join("SKILL.md");
-    let mut errors = Vec::new();
-    let mut warnings = Vec::new();
-    let mut skill_name = skill_id.clone();
-    let mut version = "1.0.0".to_string();
-    let mut has_required_fields = false;
-    
-    // Check SKILL.md exists
-    if !skill_md.exists() {
-        errors.push("Missing SKILL.md file".to_string());
-    } else {
-        // Parse frontmatter
-        let content = std::fs::read_to_string(&skill_md)
-            .map_err(|e| format!("Failed to read SKILL.md: {}", e))?;
-        
-        if content.starts_with("---") {
-            if let Some(end_idx) = content[3..].find("---") {
-                let frontmatter = &content[3..3+end_idx];
-                // Check required fields
-                has_required_fields = frontmatter.contains("name:") && frontmatter.contains("description:");
-                
-                // Extract name
-                if let Some(name_line) = frontmatter.lines().find(|l| l.starts_with("name:")) {
-                    skill_name = name_line.replace("name:", "").trim().trim_matches('"').to_string();
-                }
-                // Extract version
-                if let Some(ver_line) = frontmatter.lines().find(|l| l.starts_with("version:")) {
-                    version = ver_line.replace("version:", "").trim().trim_matches('"').to_string();
-                }
-                
-                if !has_required_fields {
-                    errors.push("Missing required fields: name and description".to_string());
-                }
-            } else {
-                errors.push("Invalid YAML frontmatter format".to_string());
-            }
-        } else {
-            errors.push("SKILL.md must start with YAML frontmatter (---)".to_string());
+    command_metrics::timed("test_skill", async move {
+        let skills_path = get_skills_path();
+        let skill_folder = skills_path.join(&skill_id);
+
+        if !skill_folder.exists() {
+            return Err(format!("Skill '{}' not found", skill_id));
         }
-    }
-    
-    // Check scripts folder
-    let scripts_folder = skill_folder.join("scripts");
-    let has_scripts = scripts_folder.exists();
-    if !has_scripts {
-        warnings.push("No scripts/ folder found".to_string());
-    }
-    
-    // Check guardrails folder
-    let guardrails_folder = skill_folder.join("guardrails");
-    let has_guardrails = guardrails_folder.exists();
-    if !has_guardrails {
-        warnings.push("No guardrails/ folder found".to_string());
-    }
-    
-    Ok(SkillValidation {
-        is_valid: errors.is_empty(),
-        skill_name,
-        version,
-        has_required_fields,
-        has_scripts,
-        has_guardrails,
-        errors,
-        warnings,
-    })
-}
 
-/// Export result containing file path
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ExportResult {
-    pub success: bool,
-    pub export_path: String,
-    pub file_size: u64,
-    pub skill_name: String,
-    pub version: String,
-}
+        let skill_md = skill_folder.join("SKILL.md");
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        let mut skill_name = skill_id.clone();
+        let mut version = "1.0.0".to_string();
+        let mut has_required_fields = false;
+
+        // Check SKILL.md exists
+        if !skill_md.exists() {
+            errors.push("Missing SKILL.md file".to_string());
+        } else {
+            // Parse frontmatter
+            let content = std::fs::read_to_string(&skill_md)
+                .map_err(|e| format!("Failed to read SKILL.md: {}", e))?;
 
-/// Export a skill as a ZIP package for sharing
-#[tauri::command]
-async fn export_skill(skill_id: String) -> Result<ExportResult, String> {
-    use std::io::{Read, Write};
-    
-    let skills_path = get_skills_path();
-    let skill_folder = skills_path.join(&skill_id);
-    
-    if !skill_folder.exists() {
-        return Err(format!("Skill '{}' not found", skill_id));
-    }
-    
-    // Get skill metadata
-    let skill_md = skill_folder.join("SKILL.md");
-    let mut skill_name = skill_id.clone();
-    let mut version = "1.0.0".to_string();
-    
-    if skill_md.exists() {
-        if let Ok(content) = std::fs::read_to_string(&skill_md) {
             if content.starts_with("---") {
                 if let Some(end_idx) = content[3..].find("---") {
                     let frontmatter = &content[3..3+end_idx];
+                    // Check required fields
+                    has_required_fields = frontmatter.contains("name:") && frontmatter.contains("description:");
+
+                    // Extract name
                     if let Some(name_line) = frontmatter.lines().find(|l| l.starts_with("name:")) {
                         skill_name = name_line.replace("name:", "").trim().trim_matches('"').to_string();
                     }
+                    // Extract version
                     if let Some(ver_line) = frontmatter.lines().find(|l| l.starts_with("version:")) {
                         version = ver_line.replace("version:", "").trim().trim_matches('"').to_string();
                     }
+
+                    if !has_required_fields {
+                        errors.push("Missing required fields: name and description".to_string());
+                    }
+                } else {
+                    errors.push("Invalid YAML frontmatter format".to_string());
                 }
-            }
-        }
-    }
-    
-    // Create ZIP file
-    let export_filename = format!("{}_v{}.zip", skill_id, version);
-    let export_path = skills_path.join(&export_filename);
-    
-    let file = std::fs::File::create(&export_path)
-        .map_err(|e| format!("Failed to create export file: {}", e))?;
-    
-    let mut zip = zip::ZipWriter::new(file);
-    let options = zip::write::FileOptions::default()
-        .compression_method(zip::CompressionMethod::Deflated);
-    
-    // Add all files from skill folder
-    fn add_dir_to_zip(zip: &mut zip::ZipWriter<std::fs::File>, base_path: &std::path::Path, current_path: &std::path::Path, options: zip::write::FileOptions) -> Result<(), String> {
-        for entry in std::fs::read_dir(current_path).map_err(|e| e.to_string())? {
-            let entry = entry.map_err(|e| e.to_string())?;
-            let path = entry.path();
-            let relative_path = path.strip_prefix(base_path).map_err(|e| e.to_string())?;
-            
-            if path.is_dir() {
-                add_dir_to_zip(zip, base_path, &path, options)?;
             } else {
-                let mut file_content = Vec::new();
-                std::fs::File::open(&path)
-                    .map_err(|e| e.to_string())?
-                    .read_to_end(&mut file_content)
-                    .map_err(|e| e.to_string())?;
-                
-                zip.start_file(relative_path.to_string_lossy().to_string(), options)
-                    .map_err(|e| e.to_string())?;
-                zip.write_all(&file_content).map_err(|e| e.to_string())?;
+                errors.push("SKILL.md must start with YAML frontmatter (---)".to_string());
             }
         }
-        Ok(())
-    }
-    
-    add_dir_to_zip(&mut zip, &skill_folder, &skill_folder, options)?;
-    zip.finish().map_err(|e| format!("Failed to finalize ZIP: {}", e))?;
-    
-    let file_size = std::fs::metadata(&export_path)
-        .map(|m| m.len())
-        .unwrap_or(0);
-    
-    Ok(ExportResult {
-        success: true,
-        export_path: export_path.to_string_lossy().to_string(),
-        file_size,
-        skill_name,
-        version,
-    })
+
+        // Check scripts folder
+        let scripts_folder = skill_folder.join("scripts");
+        let has_scripts = scripts_folder.exists();
+        if !has_scripts {
+            warnings.push("No scripts/ folder found".to_string());
+        }
+
+        // Check guardrails folder
+        let guardrails_folder = skill_folder.join("guardrails");
+        let has_guardrails = guardrails_folder.exists();
+        if !has_guardrails {
+            warnings.push("No guardrails/ folder found".to_string());
+        }
+
+        Ok(SkillValidation {
+            is_valid: errors.is_empty(),
+            skill_name,
+            version,
+            has_required_fields,
+            has_scripts,
+            has_guardrails,
+            errors,
+            warnings,
+        })
+    }).await
 }
 
 // ============================================================================
@@ -1293,16 +1687,18 @@ async fn export_skill(skill_id: String) -> Result<ExportResult, String> {
 /// Save Gemini API Key to store for AI Skill Factory
 #[tauri::command]
 async fn save_gemini_api_key(app: tauri::AppHandle, api_key: String) -> Result<String, String> {
-    use tauri_plugin_store::StoreExt;
-    
-    let store = app.store("settings.json")
-        .map_err(|e| format!("Lỗi khởi tạo Store: {}", e))?;
-    
-    store.set("gemini_api_key", serde_json::json!(api_key));
-    store.save()
-        .map_err(|e| format!("Lỗi lưu API key: {}", e))?;
-    
-    Ok("Gemini API Key đã được lưu thành công".to_string())
+    command_metrics::timed("save_gemini_api_key", async move {
+        use tauri_plugin_store::StoreExt;
+
+        let store = app.store("settings.json")
+            .map_err(|e| format!("Lỗi khởi tạo Store: {}", e))?;
+
+        store.set("gemini_api_key", serde_json::json!(api_key));
+        store.save()
+            .map_err(|e| format!("Lỗi lưu API key: {}", e))?;
+
+        Ok("Gemini API Key đã được lưu thành công".to_string())
+    }).await
 }
 
 /// Generate skill content using Gemini AI
@@ -1314,53 +1710,42 @@ pub struct GeminiSkillResult {
     pub tools: Vec<String>,
     pub patterns: Vec<String>,
     pub error: Option<String>,
+    /// Whether the context had to be truncated or summarized to fit
+    pub input_budget: Option<gemini_budget::InputBudgetReport>,
+    /// What produced this content; passed back to `save_generated_skill`
+    pub provenance: Option<skill_provenance::GenerationRecord>,
 }
 
-/// Generate skill with Gemini AI - creates intelligent, context-aware content
-#[tauri::command]
-async fn generate_skill_with_gemini(app: tauri::AppHandle, intent: SkillIntent) -> Result<GeminiSkillResult, String> {
-    use tauri_plugin_store::StoreExt;
-    
-    // Read GEMINI_API_KEY from Tauri Store (set via Settings page)
-    let store = app.store("settings.json")
-        .map_err(|e| format!("Lỗi khởi tạo Store: {}", e))?;
-    
-    let api_key = store.get("gemini_api_key")
-        .and_then(|v| v.as_str().map(String::from))
-        .ok_or("⚠️ Gemini API Key chưa được cấu hình.\n\nVào Settings → Nhập Gemini API Key để sử dụng AI.\n\nLấy key tại: https://aistudio.google.com/apikey")?;
-    
-    if api_key.trim().is_empty() {
-        return Err("⚠️ Gemini API Key trống. Vào Settings để nhập key.".to_string());
-    }
-    
-    // Build improved Vietnamese prompt
-    let context_text = intent.context.clone().unwrap_or_default();
-    let prompt = format!(r#"Bạn là CHUYÊN GIA tạo Skills cho AI Agent. 
+/// Identifies the Skill Factory prompt in generation.json; bump when the wording changes
+const SKILL_PROMPT_TEMPLATE_ID: &str = "skill-factory-vi/v1";
+
+/// Skill Factory prompt; `{name}`, `{description}`, `{purpose}` and `{context}` are filled in
+const SKILL_PROMPT_TEMPLATE: &str = r#"Bạn là CHUYÊN GIA tạo Skills cho AI Agent.
 
 ⚠️ CHỈ TRẢ LỜI BẰNG TIẾNG VIỆT. KHÔNG DÙNG TIẾNG ANH.
 
 Hãy tạo nội dung SKILL.md CHI TIẾT và CHUYÊN NGHIỆP cho:
 
 ## Thông tin Skill:
-- Tên skill: {}
-- Mô tả chi tiết: {}
-- Mục đích sử dụng: {}
-- Ngữ cảnh bổ sung: {}
+- Tên skill: {name}
+- Mô tả chi tiết: {description}
+- Mục đích sử dụng: {purpose}
+- Ngữ cảnh bổ sung: {context}
 
 ## Yêu cầu output:
 Trả về JSON (KHÔNG bao gồm markdown fences):
-{{
+{
   "best_practices": ["phương pháp 1", "phương pháp 2", ...], 
   "tools": ["công cụ 1", "công cụ 2", ...],
   "patterns": ["quy trình 1", "quy trình 2", ...],
   "overview": "Mô tả tổng quan chi tiết 2-3 đoạn văn TIẾNG VIỆT",
   "use_cases": ["tình huống sử dụng 1", "tình huống 2", ...],
   "implementation_steps": ["bước 1", "bước 2", ...]
-}}
+}
 
 ## QUAN TRỌNG - Yêu cầu nội dung:
-1. PHẢI liên quan TRỰC TIẾP đến "{}" - KHÔNG dùng nội dung chung chung
-2. best_practices: 6-8 phương pháp TỐT NHẤT cho "{}" cụ thể
+1. PHẢI liên quan TRỰC TIẾP đến "{name}" - KHÔNG dùng nội dung chung chung
+2. best_practices: 6-8 phương pháp TỐT NHẤT cho "{name}" cụ thể
 3. tools: 5-7 công cụ/phần mềm THỰC SỰ DÙNG ĐƯỢC cho lĩnh vực này
 4. patterns: 4-6 quy trình/mô hình có thể ÁP DỤNG NGAY
 5. overview: Giải thích CHI TIẾT skill này làm gì, ai cần, tại sao quan trọng
@@ -1371,99 +1756,168 @@ VÍ DỤ nếu skill là "Phân tích tài chính":
 - tools: ["Excel/Google Sheets", "Power BI", "Python Pandas", "QuickBooks"]  
 - KHÔNG phải: ["Git", "VS Code", "Docker"] (không liên quan)
 
-TẤT CẢ NỘI DUNG PHẢI BẰNG TIẾNG VIỆT!"#,
-        intent.name, intent.description, intent.purpose, context_text,
-        intent.name, intent.name
-    );
-    
-    // Call Gemini API
-    let client = reqwest::Client::new();
-    let api_url = format!(
-        "https://generativelanguage.googleapis.com/v1beta/models/gemini-1.5-flash:generateContent?key={}",
-        api_key
-    );
-    
-    let request_body = serde_json::json!({
-        "contents": [{
-            "parts": [{
-                "text": prompt
-            }]
-        }],
-        "generationConfig": {
-            "temperature": 0.7,
-            "topP": 0.9,
-            "maxOutputTokens": 4096
+TẤT CẢ NỘI DUNG PHẢI BẰNG TIẾNG VIỆT!"#;
+
+/// Fill `{key}` placeholders in one pass, so user text is never re-substituted
+fn render_prompt(template: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let tail = &rest[open..];
+        let value = tail[1..].find('}').and_then(|close| {
+            let key = &tail[1..1 + close];
+            values.iter().find(|(k, _)| *k == key).map(|(_, v)| (*v, close + 2))
+        });
+        match value {
+            Some((value, consumed)) => {
+                rendered.push_str(value);
+                rest = &tail[consumed..];
+            }
+            None => {
+                rendered.push('{');
+                rest = &tail[1..];
+            }
         }
-    });
-    
-    let response = client.post(&api_url)
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to call Gemini API: {}", e))?;
-    
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("Gemini API error: {}", error_text));
     }
-    
-    let response_json: serde_json::Value = response.json().await
-        .map_err(|e| format!("Failed to parse Gemini response: {}", e))?;
-    
-    // Extract text from Gemini response
-    let generated_text = response_json["candidates"][0]["content"]["parts"][0]["text"]
-        .as_str()
-        .unwrap_or("{}");
-    
-    // Clean and parse JSON from response
-    let clean_json = generated_text
-        .trim()
-        .trim_start_matches("```json")
-        .trim_start_matches("```")
-        .trim_end_matches("```")
-        .trim();
-    
-    let ai_result: serde_json::Value = serde_json::from_str(clean_json)
-        .unwrap_or_else(|_| serde_json::json!({
-            "best_practices": ["Không thể parse kết quả AI"],
-            "tools": [],
-            "patterns": [],
-            "overview": intent.description.clone(),
-            "use_cases": [],
-            "implementation_steps": []
-        }));
-    
-    // Extract arrays from AI result
-    let best_practices: Vec<String> = ai_result["best_practices"]
-        .as_array()
-        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-        .unwrap_or_default();
-    
-    let tools: Vec<String> = ai_result["tools"]
-        .as_array()
-        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-        .unwrap_or_default();
-    
-    let patterns: Vec<String> = ai_result["patterns"]
-        .as_array()
-        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-        .unwrap_or_default();
-    
-    let overview = ai_result["overview"].as_str().unwrap_or(&intent.description);
-    let use_cases: Vec<String> = ai_result["use_cases"]
-        .as_array()
-        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-        .unwrap_or_default();
-    let impl_steps: Vec<String> = ai_result["implementation_steps"]
-        .as_array()
-        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
-        .unwrap_or_default();
-    
-    // Generate complete SKILL.md content
-    let timestamp = chrono::Local::now().format("%Y-%m-%d").to_string();
-    
-    let skill_content = format!(r#"---
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Generate skill with Gemini AI - creates intelligent, context-aware content
+#[tauri::command]
+async fn generate_skill_with_gemini(app: tauri::AppHandle, intent: SkillIntent) -> Result<GeminiSkillResult, gemini_budget::GeminiError> {
+    command_metrics::timed("generate_skill_with_gemini", async move {
+        use tauri_plugin_store::StoreExt;
+
+        // Read GEMINI_API_KEY from Tauri Store (set via Settings page)
+        let store = app.store("settings.json")
+            .map_err(|e| format!("Lỗi khởi tạo Store: {}", e))?;
+
+        let api_key = store.get("gemini_api_key")
+            .and_then(|v| v.as_str().map(String::from))
+            .ok_or("⚠️ Gemini API Key chưa được cấu hình.\n\nVào Settings → Nhập Gemini API Key để sử dụng AI.\n\nLấy key tại: https://aistudio.google.com/apikey")?;
+
+        if api_key.trim().is_empty() {
+            return Err("⚠️ Gemini API Key trống. Vào Settings để nhập key.".into());
+        }
+
+        // Build improved Vietnamese prompt
+        let context_text = intent.context.clone().unwrap_or_default();
+        let build_prompt = |context_text: &str| render_prompt(SKILL_PROMPT_TEMPLATE, &[
+            ("name", intent.name.as_str()),
+            ("description", intent.description.as_str()),
+            ("purpose", intent.purpose.as_str()),
+            ("context", context_text),
+        ]);
+
+        // Fit the pasted context into the model's input budget
+        let client = http_client::client()?;
+        let budget = gemini_budget::GeminiBudgetSettings::load();
+        let (prompt, input_budget) =
+            gemini_budget::fit_prompt(&client, &api_key, &budget, &context_text, build_prompt).await?;
+
+        // Call Gemini API
+        let api_url = format!("{}:generateContent?key={}", gemini_budget::GEMINI_MODEL_URL, api_key);
+
+        let request_body = serde_json::json!({
+            "contents": [{
+                "parts": [{
+                    "text": prompt
+                }]
+            }],
+            "generationConfig": {
+                "temperature": 0.7,
+                "topP": 0.9,
+                "maxOutputTokens": 4096
+            }
+        });
+
+        let response = client.post(&api_url)
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to call Gemini API: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let error_text = response.text().await.unwrap_or_default();
+            let measured = input_budget.counted_tokens.unwrap_or(input_budget.estimated_tokens);
+            return Err(gemini_budget::map_api_error(status, &error_text, measured, budget.max_input_tokens));
+        }
+
+        let response_json: serde_json::Value = response.json().await
+            .map_err(|e| format!("Failed to parse Gemini response: {}", e))?;
+
+        let provenance = skill_provenance::GenerationRecord {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            provider: "gemini".to_string(),
+            model: gemini_budget::model_name().to_string(),
+            language: "vi".to_string(),
+            prompt_template_id: SKILL_PROMPT_TEMPLATE_ID.to_string(),
+            prompt_template_hash: skill_provenance::template_hash(SKILL_PROMPT_TEMPLATE),
+            domain: detect_skill_domain(&intent),
+            // The Gemini path answers from the model alone, no MCP research yet
+            research_sources: Vec::new(),
+            token_usage: skill_provenance::TokenUsage::from_response(&response_json),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            section: None,
+        };
+
+        // Extract text from Gemini response
+        let generated_text = response_json["candidates"][0]["content"]["parts"][0]["text"]
+            .as_str()
+            .unwrap_or("{}");
+
+        // Clean and parse JSON from response
+        let clean_json = generated_text
+            .trim()
+            .trim_start_matches("```json")
+            .trim_start_matches("```")
+            .trim_end_matches("```")
+            .trim();
+
+        let ai_result: serde_json::Value = serde_json::from_str(clean_json)
+            .unwrap_or_else(|_| serde_json::json!({
+                "best_practices": ["Không thể parse kết quả AI"],
+                "tools": [],
+                "patterns": [],
+                "overview": intent.description.clone(),
+                "use_cases": [],
+                "implementation_steps": []
+            }));
+
+        // Extract arrays from AI result
+        let best_practices: Vec<String> = ai_result["best_practices"]
+            .as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+
+        let tools: Vec<String> = ai_result["tools"]
+            .as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+
+        let patterns: Vec<String> = ai_result["patterns"]
+            .as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+
+        let overview = ai_result["overview"].as_str().unwrap_or(&intent.description);
+        let use_cases: Vec<String> = ai_result["use_cases"]
+            .as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+        let impl_steps: Vec<String> = ai_result["implementation_steps"]
+            .as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+
+        // Generate complete SKILL.md content
+        let timestamp = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+        let skill_content = format!(r#"---
 name: {}
 description: {}
 version: 1.0.0
@@ -1522,34 +1976,78 @@ Các công cụ được khuyến nghị cho skill này:
 
 ---
 
-**Generated by:** Vibecode AI Skill Factory (Gemini 1.5 Flash)  
+{}  
 **Generated on:** {}
 "#,
-        intent.name,
-        intent.description,
-        timestamp,
-        timestamp,
-        intent.name,
-        overview,
-        intent.purpose,
-        intent.context.as_ref().map(|c| format!("**Context bổ sung:** {}", c)).unwrap_or_default(),
-        intent.purpose,
-        use_cases.iter().map(|c| format!("- {}", c)).collect::<Vec<_>>().join("\n"),
-        tools.iter().map(|t| format!("- **{}**", t)).collect::<Vec<_>>().join("\n"),
-        best_practices.iter().enumerate().map(|(i, p)| format!("{}. {}", i+1, p)).collect::<Vec<_>>().join("\n"),
-        patterns.iter().map(|p| format!("- {}", p)).collect::<Vec<_>>().join("\n"),
-        impl_steps.iter().enumerate().map(|(i, s)| format!("### Bước {}: {}", i+1, s)).collect::<Vec<_>>().join("\n\n"),
-        chrono::Local::now().format("%H:%M:%S %d/%m/%Y")
-    );
-    
-    Ok(GeminiSkillResult {
-        success: true,
-        skill_content,
-        best_practices,
-        tools,
-        patterns,
-        error: None,
-    })
+            intent.name,
+            intent.description,
+            timestamp,
+            timestamp,
+            intent.name,
+            overview,
+            intent.purpose,
+            intent.context.as_ref().map(|c| format!("**Context bổ sung:** {}", c)).unwrap_or_default(),
+            intent.purpose,
+            use_cases.iter().map(|c| format!("- {}", c)).collect::<Vec<_>>().join("\n"),
+            tools.iter().map(|t| format!("- **{}**", t)).collect::<Vec<_>>().join("\n"),
+            best_practices.iter().enumerate().map(|(i, p)| format!("{}. {}", i+1, p)).collect::<Vec<_>>().join("\n"),
+            patterns.iter().map(|p| format!("- {}", p)).collect::<Vec<_>>().join("\n"),
+            impl_steps.iter().enumerate().map(|(i, s)| format!("### Bước {}: {}", i+1, s)).collect::<Vec<_>>().join("\n\n"),
+            skill_provenance::footer_reference(),
+            chrono::Local::now().format("%H:%M:%S %d/%m/%Y")
+        );
+
+        Ok(GeminiSkillResult {
+            success: true,
+            skill_content,
+            best_practices,
+            tools,
+            patterns,
+            error: None,
+            input_budget: Some(input_budget),
+            provenance: Some(provenance),
+        })
+    }).await
+}
+
+/// Save a Skill Factory result as a new skill, with its generation.json
+#[tauri::command]
+async fn save_generated_skill(
+    app: tauri::AppHandle,
+    name: String,
+    content: String,
+    provenance: skill_provenance::GenerationRecord,
+) -> Result<Skill, String> {
+    command_metrics::timed("save_generated_skill", async move {
+        let skills_path = get_skills_path();
+        std::fs::create_dir_all(&skills_path)
+            .map_err(|e| format!("Failed to create skills directory: {}", e))?;
+
+        let skill_id = new_skill_id(&name)?;
+        let skill_folder = skills_path.join(&skill_id);
+
+        if skill_folder.exists() {
+            return Err(format!("Skill '{}' already exists", skill_id));
+        }
+
+        let name_warnings = name_lint::check_new_name(
+            name_lint::KIND_SKILL,
+            name_lint::current_skill_scope(),
+            &skill_id,
+            Some(&name),
+        );
+
+        std::fs::create_dir_all(skill_folder.join("scripts"))
+            .map_err(|e| format!("Failed to create skill folder: {}", e))?;
+        std::fs::write(skill_folder.join("SKILL.md"), content)
+            .map_err(|e| format!("Failed to create SKILL.md: {}", e))?;
+        skill_provenance::append(&skill_folder, provenance)?;
+
+        skill_index::invalidate();
+        name_lint::emit_warnings(&app, name_lint::KIND_SKILL, &skill_id, &name_warnings);
+
+        get_skill(skill_id).await
+    }).await
 }
 
 // ============================================================================
@@ -1610,155 +2108,157 @@ fn detect_skill_domain(intent: &SkillIntent) -> SkillDomain {
 /// Phase 2.1: Simulated implementation - will be connected to real MCPs later
 #[tauri::command]
 async fn research_skill_with_mcp(intent: SkillIntent) -> Result<EnhancedResearch, String> {
-    // Step 1: Detect domain for intelligent content
-    let domain = detect_skill_domain(&intent);
-
-    // Step 2: Simulate MCP research (placeholder - will call real MCPs in Phase 2.2)
-    // TODO: Replace with real Perplexity MCP stdio call
-    // TODO: Replace with real NotebookLM MCP stdio call
-
-    let (best_practices, tools, patterns) = match domain {
-        SkillDomain::DigitalMarketing => (
-            vec![
-                "Follow 80/20 rule: 80% value content, 20% promotional".to_string(),
-                "Hook in first 3 seconds to capture attention".to_string(),
-                "Mobile-first design - 90% users browse on mobile".to_string(),
-                "Include clear Call-to-Action (CTA) in every post".to_string(),
-                "Use 1080x1080px square images for optimal Facebook feed".to_string(),
-                "Keep text overlay below 20% to avoid algorithm penalty".to_string(),
-                "Post timing: Peak engagement 1-4 PM weekdays".to_string(),
-                "Use 3-5 relevant hashtags maximum".to_string(),
-            ],
-            vec![
-                "Canva Pro".to_string(),
-                "Adobe Creative Cloud".to_string(),
-                "Meta Business Suite".to_string(),
-                "Hootsuite".to_string(),
-                "Buffer".to_string(),
-                "ChatGPT/Claude".to_string(),
-                "Midjourney/DALL-E".to_string(),
-                "Google Analytics".to_string(),
-                "SEMrush".to_string(),
-            ],
-            vec![
-                "Content Calendar Pattern - Monthly theme planning, weekly batch creation".to_string(),
-                "Template-Based Generation - Maintain brand consistency with templates".to_string(),
-                "Multi-Stage Approval Flow - AI Draft → Human Review → Client Approval → Publishing".to_string(),
-                "Performance Feedback Loop - Analytics → Insights → Adjustment → Testing".to_string(),
-            ],
-        ),
-        SkillDomain::SoftwareDevelopment => (
-            vec![
-                "Follow SOLID design principles for maintainability".to_string(),
-                "Implement comprehensive error handling and logging".to_string(),
-                "Write modular, reusable code with clear separation of concerns".to_string(),
-                "Add thorough documentation and inline comments".to_string(),
-                "Use TypeScript/type hints for type safety".to_string(),
-                "Implement unit tests with high coverage".to_string(),
-                "Follow consistent code formatting standards".to_string(),
-                "Apply security best practices (input validation, sanitization)".to_string(),
-            ],
-            vec![
-                "Git".to_string(),
-                "VS Code".to_string(),
-                "Docker".to_string(),
-                "Jest".to_string(),
-                "ESLint".to_string(),
-                "Prettier".to_string(),
-                "GitHub Actions".to_string(),
-            ],
-            vec![
-                "Factory Pattern for object creation".to_string(),
-                "Strategy Pattern for algorithm selection".to_string(),
-                "Observer Pattern for event handling".to_string(),
-                "Repository Pattern for data access".to_string(),
-                "Dependency Injection for loose coupling".to_string(),
-            ],
-        ),
-        SkillDomain::DataScience => (
-            vec![
-                "Document data sources and assumptions clearly".to_string(),
-                "Validate data quality before analysis".to_string(),
-                "Use reproducible workflows (Jupyter notebooks, version control)".to_string(),
-                "Apply proper train/test/validation splits".to_string(),
-                "Monitor model performance and drift in production".to_string(),
-            ],
-            vec![
-                "Python".to_string(),
-                "Pandas".to_string(),
-                "NumPy".to_string(),
-                "Scikit-learn".to_string(),
-                "Jupyter Notebook".to_string(),
-                "MLflow".to_string(),
-            ],
-            vec![
-                "ETL Pipeline Pattern - Extract, Transform, Load data workflows".to_string(),
-                "Feature Engineering Pipeline - Systematic feature creation and selection".to_string(),
-                "Model Training Pipeline - Automated hyperparameter tuning and evaluation".to_string(),
-            ],
-        ),
-        SkillDomain::ProjectManagement => (
-            vec![
-                "Define clear project scope and deliverables upfront".to_string(),
-                "Break work into manageable sprints (2-week iterations)".to_string(),
-                "Maintain transparent communication with stakeholders".to_string(),
-                "Track progress with visual boards (Kanban/Scrum)".to_string(),
-                "Conduct regular retrospectives for continuous improvement".to_string(),
-            ],
-            vec![
-                "Jira".to_string(),
-                "Asana".to_string(),
-                "Trello".to_string(),
-                "Monday.com".to_string(),
-                "Notion".to_string(),
-                "Slack".to_string(),
-            ],
-            vec![
-                "Agile/Scrum Framework - Sprint planning, daily standups, retrospectives".to_string(),
-                "Kanban Flow - Visual workflow management with WIP limits".to_string(),
-                "Critical Path Method - Identify task dependencies and bottlenecks".to_string(),
-            ],
-        ),
-        SkillDomain::General => (
-            vec![
-                "Start with clear objectives and success criteria".to_string(),
-                "Break complex tasks into smaller, manageable steps".to_string(),
-                "Document processes for consistency and knowledge sharing".to_string(),
-                "Iterate based on feedback and lessons learned".to_string(),
-            ],
-            vec![
-                "Productivity tools (Notion, Evernote)".to_string(),
-                "Communication tools (Slack, Teams)".to_string(),
-                "Collaboration tools (Google Workspace)".to_string(),
-            ],
-            vec![
-                "Systematic Workflow Pattern - Define, Execute, Review, Improve".to_string(),
-                "Documentation-First Approach - Write it down before implementation".to_string(),
-            ],
-        ),
-    };
-
-    // Step 3: Create research sources (simulated - will be real Perplexity citations later)
-    let sources = vec![
-        ResearchSource {
-            title: format!("Best practices for {}", intent.name),
-            url: "#".to_string(), // TODO: Replace with real Perplexity URL
-            source_type: "perplexity".to_string(),
-        },
-        ResearchSource {
-            title: format!("Industry standards for {:?}", domain),
-            url: "#".to_string(), // TODO: Replace with NotebookLM citation
-            source_type: "notebooklm".to_string(),
-        },
-    ];
+    command_metrics::timed("research_skill_with_mcp", async move {
+        // Step 1: Detect domain for intelligent content
+        let domain = detect_skill_domain(&intent);
+
+        // Step 2: Simulate MCP research (placeholder - will call real MCPs in Phase 2.2)
+        // TODO: Replace with real Perplexity MCP stdio call
+        // TODO: Replace with real NotebookLM MCP stdio call
+
+        let (best_practices, tools, patterns) = match domain {
+            SkillDomain::DigitalMarketing => (
+                vec![
+                    "Follow 80/20 rule: 80% value content, 20% promotional".to_string(),
+                    "Hook in first 3 seconds to capture attention".to_string(),
+                    "Mobile-first design - 90% users browse on mobile".to_string(),
+                    "Include clear Call-to-Action (CTA) in every post".to_string(),
+                    "Use 1080x1080px square images for optimal Facebook feed".to_string(),
+                    "Keep text overlay below 20% to avoid algorithm penalty".to_string(),
+                    "Post timing: Peak engagement 1-4 PM weekdays".to_string(),
+                    "Use 3-5 relevant hashtags maximum".to_string(),
+                ],
+                vec![
+                    "Canva Pro".to_string(),
+                    "Adobe Creative Cloud".to_string(),
+                    "Meta Business Suite".to_string(),
+                    "Hootsuite".to_string(),
+                    "Buffer".to_string(),
+                    "ChatGPT/Claude".to_string(),
+                    "Midjourney/DALL-E".to_string(),
+                    "Google Analytics".to_string(),
+                    "SEMrush".to_string(),
+                ],
+                vec![
+                    "Content Calendar Pattern - Monthly theme planning, weekly batch creation".to_string(),
+                    "Template-Based Generation - Maintain brand consistency with templates".to_string(),
+                    "Multi-Stage Approval Flow - AI Draft → Human Review → Client Approval → Publishing".to_string(),
+                    "Performance Feedback Loop - Analytics → Insights → Adjustment → Testing".to_string(),
+                ],
+            ),
+            SkillDomain::SoftwareDevelopment => (
+                vec![
+                    "Follow SOLID design principles for maintainability".to_string(),
+                    "Implement comprehensive error handling and logging".to_string(),
+                    "Write modular, reusable code with clear separation of concerns".to_string(),
+                    "Add thorough documentation and inline comments".to_string(),
+                    "Use TypeScript/type hints for type safety".to_string(),
+                    "Implement unit tests with high coverage".to_string(),
+                    "Follow consistent code formatting standards".to_string(),
+                    "Apply security best practices (input validation, sanitization)".to_string(),
+                ],
+                vec![
+                    "Git".to_string(),
+                    "VS Code".to_string(),
+                    "Docker".to_string(),
+                    "Jest".to_string(),
+                    "ESLint".to_string(),
+                    "Prettier".to_string(),
+                    "GitHub Actions".to_string(),
+                ],
+                vec![
+                    "Factory Pattern for object creation".to_string(),
+                    "Strategy Pattern for algorithm selection".to_string(),
+                    "Observer Pattern for event handling".to_string(),
+                    "Repository Pattern for data access".to_string(),
+                    "Dependency Injection for loose coupling".to_string(),
+                ],
+            ),
+            SkillDomain::DataScience => (
+                vec![
+                    "Document data sources and assumptions clearly".to_string(),
+                    "Validate data quality before analysis".to_string(),
+                    "Use reproducible workflows (Jupyter notebooks, version control)".to_string(),
+                    "Apply proper train/test/validation splits".to_string(),
+                    "Monitor model performance and drift in production".to_string(),
+                ],
+                vec![
+                    "Python".to_string(),
+                    "Pandas".to_string(),
+                    "NumPy".to_string(),
+                    "Scikit-learn".to_string(),
+                    "Jupyter Notebook".to_string(),
+                    "MLflow".to_string(),
+                ],
+                vec![
+                    "ETL Pipeline Pattern - Extract, Transform, Load data workflows".to_string(),
+                    "Feature Engineering Pipeline - Systematic feature creation and selection".to_string(),
+                    "Model Training Pipeline - Automated hyperparameter tuning and evaluation".to_string(),
+                ],
+            ),
+            SkillDomain::ProjectManagement => (
+                vec![
+                    "Define clear project scope and deliverables upfront".to_string(),
+                    "Break work into manageable sprints (2-week iterations)".to_string(),
+                    "Maintain transparent communication with stakeholders".to_string(),
+                    "Track progress with visual boards (Kanban/Scrum)".to_string(),
+                    "Conduct regular retrospectives for continuous improvement".to_string(),
+                ],
+                vec![
+                    "Jira".to_string(),
+                    "Asana".to_string(),
+                    "Trello".to_string(),
+                    "Monday.com".to_string(),
+                    "Notion".to_string(),
+                    "Slack".to_string(),
+                ],
+                vec![
+                    "Agile/Scrum Framework - Sprint planning, daily standups, retrospectives".to_string(),
+                    "Kanban Flow - Visual workflow management with WIP limits".to_string(),
+                    "Critical Path Method - Identify task dependencies and bottlenecks".to_string(),
+                ],
+            ),
+            SkillDomain::General => (
+                vec![
+                    "Start with clear objectives and success criteria".to_string(),
+                    "Break complex tasks into smaller, manageable steps".to_string(),
+                    "Document processes for consistency and knowledge sharing".to_string(),
+                    "Iterate based on feedback and lessons learned".to_string(),
+                ],
+                vec![
+                    "Productivity tools (Notion, Evernote)".to_string(),
+                    "Communication tools (Slack, Teams)".to_string(),
+                    "Collaboration tools (Google Workspace)".to_string(),
+                ],
+                vec![
+                    "Systematic Workflow Pattern - Define, Execute, Review, Improve".to_string(),
+                    "Documentation-First Approach - Write it down before implementation".to_string(),
+                ],
+            ),
+        };
 
-    Ok(EnhancedResearch {
-        best_practices,
-        tools,
-        patterns,
-        sources,
-        domain,
-    })
+        // Step 3: Create research sources (simulated - will be real Perplexity citations later)
+        let sources = vec![
+            ResearchSource {
+                title: format!("Best practices for {}", intent.name),
+                url: "#".to_string(), // TODO: Replace with real Perplexity URL
+                source_type: "perplexity".to_string(),
+            },
+            ResearchSource {
+                title: format!("Industry standards for {:?}", domain),
+                url: "#".to_string(), // TODO: Replace with NotebookLM citation
+                source_type: "notebooklm".to_string(),
+            },
+        ];
+
+        Ok(EnhancedResearch {
+            best_practices,
+            tools,
+            patterns,
+            sources,
+            domain,
+        })
+    }).await
 }
 
 // ============================================================================
@@ -1776,28 +2276,44 @@ use services::{AccountService, SavedAccount, OAuthService, OAuthTokens, GoogleAp
 /// Detect Antigravity IDE server process
 #[tauri::command]
 async fn detect_antigravity_server() -> Result<antigravity::types::LanguageServerInfo, String> {
-    use antigravity::process_finder::ProcessFinder;
-    use antigravity::types::DetectOptions;
-    
-    let options = DetectOptions {
-        attempts: 3,
-        base_delay: 1000,
-        verbose: false,
-    };
-    
-    let mut finder = ProcessFinder::new();
-    finder.detect(options).await
+    command_metrics::timed("detect_antigravity_server", async move {
+        use antigravity::process_finder::ProcessFinder;
+        use antigravity::types::DetectOptions;
+
+        let options = DetectOptions {
+            attempts: 3,
+            base_delay: 1000,
+            verbose: false,
+        };
+
+        let mut finder = ProcessFinder::new();
+        finder.detect(options).await
+    }).await
 }
 
 /// Fetch quota data from Antigravity server
 #[tauri::command]
 async fn fetch_quota(
-    server_info: antigravity::types::LanguageServerInfo
+    server_info: antigravity::types::LanguageServerInfo,
+    model: Option<String>,
 ) -> Result<antigravity::quota_service::QuotaSnapshot, String> {
-    use antigravity::quota_service::QuotaService;
-    
-    let service = QuotaService::new();
-    service.fetch_quota(&server_info).await
+    command_metrics::timed("fetch_quota", async move {
+        use antigravity::model_match;
+        use antigravity::quota_service::QuotaService;
+
+        let service = QuotaService::new();
+        let mut snapshot = service.fetch_quota(&server_info).await?;
+
+        // Optional model filter: keep only the entry the requested name resolves to
+        if let Some(model) = model {
+            let matched = model_match::find_model_quota(&snapshot, &model, &model_match::load_aliases())
+                .map(|m| m.model.clone())
+                .map_err(|available| format!("No quota entry matches '{}'; available models: {}", model, available.join(", ")))?;
+            snapshot.models = vec![matched];
+        }
+
+        Ok(snapshot)
+    }).await
 }
 
 // ============================================================================
@@ -1809,7 +2325,9 @@ async fn fetch_quota(
 fn get_saved_accounts(
     app: tauri::AppHandle,
 ) -> Result<Vec<SavedAccount>, String> {
-    AccountService::get_accounts(&app)
+    command_metrics::timed_sync("get_saved_accounts", move || {
+        AccountService::get_accounts(&app)
+    })
 }
 
 /// Add or update a saved account
@@ -1818,7 +2336,9 @@ fn add_saved_account(
     app: tauri::AppHandle,
     account: SavedAccount,
 ) -> Result<(), String> {
-    AccountService::add_account(&app, account)
+    command_metrics::timed_sync("add_saved_account", move || {
+        AccountService::add_account(&app, account)
+    })
 }
 
 /// Remove a saved account by ID
@@ -1827,7 +2347,11 @@ fn remove_saved_account(
     app: tauri::AppHandle,
     account_id: String,
 ) -> Result<(), String> {
-    AccountService::remove_account(&app, &account_id)
+    command_metrics::timed_sync("remove_saved_account", move || {
+        let result = AccountService::remove_account(&app, &account_id);
+        audit::record("remove_saved_account", serde_json::json!({ "account_id": account_id }), audit::ORIGIN_COMMAND, &result);
+        result
+    })
 }
 
 /// Sync currently active account (upsert/// Sync the current account (updates or adds)
@@ -1836,7 +2360,9 @@ fn sync_current_account(
     app: tauri::AppHandle,
     account: SavedAccount,
 ) -> Result<(), String> {
-    AccountService::sync_current_account(&app, account)
+    command_metrics::timed_sync("sync_current_account", move || {
+        AccountService::sync_current_account(&app, account).map(|_| ())
+    })
 }
 
 // ============================================================================
@@ -1859,66 +2385,68 @@ const OAUTH_TIMEOUT_SECS: u64 = 300; // 5 minutes
 async fn start_google_oauth(
     app: tauri::AppHandle,
 ) -> Result<SavedAccount, String> {
-    // 1. Generate PKCE challenge
-    let pkce = OAuthService::generate_pkce();
-    
-    // 2. Build OAuth authorization URL
-    let auth_url = format!(
-        "https://accounts.google.com/o/oauth2/v2/auth?\
-         client_id={}&\
-         redirect_uri={}&\
-         response_type=code&\
-         scope={}&\
-         access_type=offline&\
-         code_challenge={}&\
-         code_challenge_method=S256&\
-         prompt=consent",
-        GOOGLE_CLIENT_ID,
-        urlencoding::encode(OAUTH_REDIRECT_URI),
-        urlencoding::encode("email profile openid"),
-        pkce.challenge,
-    );
-    
-    // 3. Open browser
-    open::that(&auth_url)
-        .map_err(|e| format!("Failed to open browser: {}", e))?;
-    
-    // 4. Start local callback server and wait for code
-    let callback = OAuthServer::start_and_wait(OAUTH_CALLBACK_PORT, OAUTH_TIMEOUT_SECS)
-        .map_err(|e| format!("OAuth callback failed: {}", e))?;
-    
-    // 5. Exchange authorization code for tokens
-    let tokens = exchange_code_for_tokens(&callback.code, &pkce.verifier).await?;
-    
-    // 6. Fetch user info
-    let google_api = GoogleApiService::new();
-    let user_info = google_api
-        .get_user_info(&tokens.access_token)
-        .await?;
-    
-    // 7. Detect tier from scopes
-    let tier = GoogleApiService::detect_tier_from_scopes(tokens.scope.as_deref());
-    
-    // 8. Encrypt and save tokens
-    let encryption_key = OAuthService::generate_device_key()?;
-    let encrypted_tokens = OAuthService::encrypt_tokens(&tokens, &encryption_key)?;
-    save_encrypted_tokens(&app, &user_info.email, &encrypted_tokens)?;
-    
-    // 9. Create SavedAccount
-    let account = SavedAccount {
-        id: uuid::Uuid::new_v4().to_string(),
-        email: user_info.email.clone(),
-        picture: user_info.picture,
-        name: user_info.name,
-        tier,
-        plan_name: Some("Google Account".to_string()),
-        last_seen: chrono::Utc::now().timestamp_millis(),
-    };
-    
-    // 10. Save account
-    AccountService::add_account(&app, account.clone())?;
-    
-    Ok(account)
+    command_metrics::timed("start_google_oauth", async move {
+        // 1. Generate PKCE challenge
+        let pkce = OAuthService::generate_pkce();
+
+        // 2. Build OAuth authorization URL
+        let auth_url = format!(
+            "https://accounts.google.com/o/oauth2/v2/auth?\
+             client_id={}&\
+             redirect_uri={}&\
+             response_type=code&\
+             scope={}&\
+             access_type=offline&\
+             code_challenge={}&\
+             code_challenge_method=S256&\
+             prompt=consent",
+            GOOGLE_CLIENT_ID,
+            urlencoding::encode(OAUTH_REDIRECT_URI),
+            urlencoding::encode("email profile openid"),
+            pkce.challenge,
+        );
+
+        // 3. Open browser
+        open::that(&auth_url)
+            .map_err(|e| format!("Failed to open browser: {}", e))?;
+
+        // 4. Start local callback server and wait for code
+        let callback = OAuthServer::start_and_wait(OAUTH_CALLBACK_PORT, OAUTH_TIMEOUT_SECS)
+            .map_err(|e| format!("OAuth callback failed: {}", e))?;
+
+        // 5. Exchange authorization code for tokens
+        let tokens = exchange_code_for_tokens(&callback.code, &pkce.verifier).await?;
+
+        // 6. Fetch user info
+        let google_api = GoogleApiService::new()?;
+        let user_info = google_api
+            .get_user_info(&tokens.access_token)
+            .await?;
+
+        // 7. Detect tier from scopes
+        let tier = GoogleApiService::detect_tier_from_scopes(tokens.scope.as_deref());
+
+        // 8. Encrypt and save tokens
+        let encryption_key = OAuthService::generate_device_key()?;
+        let encrypted_tokens = OAuthService::encrypt_tokens(&tokens, &encryption_key)?;
+        save_encrypted_tokens(&app, &user_info.email, &encrypted_tokens)?;
+
+        // 9. Create SavedAccount
+        let account = SavedAccount {
+            id: uuid::Uuid::new_v4().to_string(),
+            email: user_info.email.clone(),
+            picture: user_info.picture,
+            name: user_info.name,
+            tier,
+            plan_name: Some("Google Account".to_string()),
+            last_seen: chrono::Utc::now().timestamp_millis(),
+        };
+
+        // 10. Save account
+        AccountService::add_account(&app, account.clone())?;
+
+        Ok(account)
+    }).await
 }
 
 /// Exchange authorization code for access/refresh tokens
@@ -1926,7 +2454,7 @@ async fn exchange_code_for_tokens(
     code: &str,
     code_verifier: &str,
 ) -> Result<OAuthTokens, String> {
-    let client = reqwest::Client::new();
+    let client = http_client::client()?;
     
     let params = [
         ("code", code),
@@ -1979,19 +2507,15 @@ fn save_encrypted_tokens(
     email: &str,
     encrypted_tokens: &[u8],
 ) -> Result<(), String> {
-    use tauri_plugin_store::StoreExt;
-    
-    let store = app.store("store.json")
-        .map_err(|e| format!("Failed to get store: {}", e))?;
-    
     let key = format!("oauth_tokens_{}", email);
     use base64::Engine;
     let encoded = base64::engine::general_purpose::STANDARD.encode(encrypted_tokens);
     
-    store.set(key, serde_json::Value::String(encoded));
-    store.save().map_err(|e| format!("Failed to save tokens: {}", e))?;
-    
-    Ok(())
+    instance_lock::update_store(app, |store| {
+        store.set(key, serde_json::Value::String(encoded));
+        Ok(())
+    })
+    .map_err(|e| format!("Failed to save tokens: {}", e))
 }
 
 /// Refresh OAuth tokens for an account
@@ -2000,40 +2524,42 @@ async fn refresh_google_token(
     app: tauri::AppHandle,
     email: String,
 ) -> Result<(), String> {
-    // 1. Load encrypted tokens
-    let encrypted_tokens = load_encrypted_tokens(&app, &email)?;
-    
-    // 2. Decrypt tokens
-    let encryption_key = OAuthService::generate_device_key()?;
-    let mut tokens = OAuthService::decrypt_tokens(&encrypted_tokens, &encryption_key)?;
-    
-    // 3. Check if refresh needed
-    if !OAuthService::will_expire_soon(&tokens, 300) {
-        return Ok(()); // Still valid
-    }
-    
-    // 4. Refresh
-    let refresh_token = tokens.refresh_token
-        .as_ref()
-        .ok_or("No refresh token available")?;
-    
-    let google_api = GoogleApiService::new();
-    tokens = google_api
-        .refresh_access_token(GOOGLE_CLIENT_ID, GOOGLE_CLIENT_SECRET, refresh_token)
-        .await?;
-    
-    // 5. Re-encrypt and save
-    let encrypted = OAuthService::encrypt_tokens(&tokens, &encryption_key)?;
-    save_encrypted_tokens(&app, &email, &encrypted)?;
-    
-    // 6. Update lastSeen for account
-    let mut accounts = AccountService::get_accounts(&app)?;
-    if let Some(account) = accounts.iter_mut().find(|a| a.email == email) {
-        account.last_seen = chrono::Utc::now().timestamp_millis();
-        AccountService::add_account(&app, account.clone())?;
-    }
-    
-    Ok(())
+    command_metrics::timed("refresh_google_token", async move {
+        // 1. Load encrypted tokens
+        let encrypted_tokens = load_encrypted_tokens(&app, &email)?;
+
+        // 2. Decrypt tokens
+        let encryption_key = OAuthService::generate_device_key()?;
+        let mut tokens = OAuthService::decrypt_tokens(&encrypted_tokens, &encryption_key)?;
+
+        // 3. Check if refresh needed
+        if !OAuthService::will_expire_soon(&tokens, 300) {
+            return Ok(()); // Still valid
+        }
+
+        // 4. Refresh
+        let refresh_token = tokens.refresh_token
+            .as_ref()
+            .ok_or("No refresh token available")?;
+
+        let google_api = GoogleApiService::new()?;
+        tokens = google_api
+            .refresh_access_token(GOOGLE_CLIENT_ID, GOOGLE_CLIENT_SECRET, refresh_token)
+            .await?;
+
+        // 5. Re-encrypt and save
+        let encrypted = OAuthService::encrypt_tokens(&tokens, &encryption_key)?;
+        save_encrypted_tokens(&app, &email, &encrypted)?;
+
+        // 6. Update lastSeen for account
+        let mut accounts = AccountService::get_accounts(&app)?;
+        if let Some(account) = accounts.iter_mut().find(|a| a.email == email) {
+            account.last_seen = chrono::Utc::now().timestamp_millis();
+            AccountService::add_account(&app, account.clone())?;
+        }
+
+        Ok(())
+    }).await
 }
 
 /// Load encrypted tokens from store
@@ -2064,22 +2590,29 @@ async fn revoke_google_account(
     app: tauri::AppHandle,
     email: String,
 ) -> Result<(), String> {
+    command_metrics::timed("revoke_google_account", async move {
+        let result = revoke_and_remove_google_account(app, email.clone()).await;
+        audit::record("revoke_google_account", serde_json::json!({ "email": email }), audit::ORIGIN_COMMAND, &result);
+        result
+    }).await
+}
+
+async fn revoke_and_remove_google_account(app: tauri::AppHandle, email: String) -> Result<(), String> {
     // 1. Load and decrypt tokens
     let encrypted_tokens = load_encrypted_tokens(&app, &email)?;
     let encryption_key = OAuthService::generate_device_key()?;
     let tokens = OAuthService::decrypt_tokens(&encrypted_tokens, &encryption_key)?;
     
     // 2. Revoke tokens with Google
-    let google_api = GoogleApiService::new();
+    let google_api = GoogleApiService::new()?;
     google_api.revoke_token(&tokens.access_token).await?;
     
     // 3. Remove from store
-    use tauri_plugin_store::StoreExt;
-    let store = app.store("store.json")
-        .map_err(|e| format!("Failed to get store: {}", e))?;
     let key = format!("oauth_tokens_{}", email);
-    store.delete(&key);
-    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    instance_lock::update_store(&app, |store| {
+        store.delete(&key);
+        Ok(())
+    })?;
     
     // 4. Remove account
     let accounts = AccountService::get_accounts(&app)?;
@@ -2101,49 +2634,155 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_fs::init())
+        .manage(task_manager::TaskManager::default())
+        .manage(workflow_run::WorkflowRuns::default())
         .setup(|app| {
-            // Start REST API server in background for Extension communication
-            let app_handle = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                api_server::start_server(app_handle).await;
-            });
+            // Initialize subsystems in order (settings → project → watchers → API server → pollers)
+            startup::run(app);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             greet,
+            startup::get_startup_status,
+            antigravity::watcher::get_antigravity_connection,
+            antigravity::quota_cache::get_cached_quota,
             execute_task,
+            task_process::cancel_task,
+            task_process::kill_all_tasks,
+            task_queue::enqueue_task,
+            task_queue::get_task_queue,
+            task_history::get_task_history,
+            task_history::search_task_history,
+            task_history::get_task_changed_files,
+            task_history::clear_task_history,
+            project_env::set_project_env,
+            project_env::get_project_env,
+            task_templates::save_task_template,
+            task_templates::list_task_templates,
+            task_templates::run_task_template,
+            task_input::send_task_input,
+            task_manager::get_task_status,
+            task_manager::list_active_tasks,
+            scheduler::create_schedule,
+            scheduler::list_schedules,
+            scheduler::toggle_schedule,
+            scheduler::delete_schedule,
+            workflow_templates::list_workflow_templates,
+            scheduler::schedule_workflow,
+            scheduler::list_workflow_schedules,
+            scheduler::delete_workflow_schedule,
+            task_log::read_task_log,
+            workflow_validation::validate_workflow,
+            workflow_variables::get_workflow_variables,
             list_workflows,
             run_workflow,
+            workflow_run::get_workflow_run,
+            workflow_run::run_workflow_step,
+            workflow_run::abort_workflow,
+            workflow_history::get_workflow_runs,
+            workflow_history::get_workflow_run_detail,
+            workflow_snapshot::restore_snapshot,
+            line_stats::compute_line_stats,
+            merge_conflicts::get_conflicted_files,
+            git_push::git_push,
+            gitignore::suggest_gitignore,
+            gitignore::apply_gitignore,
             get_context,
             get_stats,
+            agent_stats::get_agent_breakdown,
+            retention::get_storage_usage,
+            retention::run_cleanup,
             open_workflows_folder,
             create_workflow,
+            workflow_files::get_workflow_content,
+            workflow_files::update_workflow_content,
+            workflow_files::delete_workflow,
+            workflow_files::rename_workflow,
+            workflow_files::duplicate_workflow,
+            workflow_archive::export_workflow,
+            workflow_archive::import_workflow,
             set_project_path,
             get_project_path,
             open_project_dialog,
             load_saved_project,
-            list_directory,
-            read_file_content,
+            file_explorer::list_directory,
+            file_explorer::list_directory_page,
+            file_explorer::list_directory_tree,
+            file_search::search_files,
+            content_search::search_in_files,
+            fs_watcher::pause_fs_watcher,
+            fs_watcher::resume_fs_watcher,
+            file_content::read_file_content,
+            file_content::get_file_info,
+            file_content::read_file_range,
+            file_content::write_file_content,
+            path_metadata::get_path_metadata,
+            git_diff::get_file_diff,
+            directory_size::get_directory_size,
+            ignore_rules::get_ignore_patterns,
+            ignore_rules::set_ignore_patterns,
+            recent_files::get_recent_files,
+            recent_files::clear_recent_files,
+            recent_projects::get_recent_projects,
+            recent_projects::pin_project,
+            recent_projects::remove_recent_project,
+            bookmarks::list_bookmarks,
+            bookmarks::add_bookmark,
+            bookmarks::remove_bookmark,
+            bookmarks::reorder_bookmarks,
             add_changed_file,
             get_changed_files,
             clear_changed_files,
-            get_settings,
-            save_settings,
+            git_status::refresh_changed_files,
+            review_diff::get_changed_file_diff,
+            review_diff::get_all_diffs,
+            git_commit::get_git_identity,
+            git_commit::commit_changes,
+            git_repository::get_git_status,
+            git_log::get_git_log,
+            git_log::get_commit_diff,
+            settings::get_settings,
+            settings::save_settings,
+            config_bundle::export_configuration,
+            config_bundle::import_configuration,
             test_python_connection,
+            python_env::get_python_env,
+            python_env::set_python_env,
+            backend_paths::get_backend_paths,
+            http_client::test_network,
             // Skills Ecosystem Commands
             list_skills,
+            skill_index::get_skill_facets,
+            audit::get_audit_log,
             get_skill,
             create_skill,
             update_skill,
+            update_skill_metadata,
+            bump_skill_version,
+            update_skill_section,
+            skill_provenance::get_skill_provenance,
             delete_skill,
             read_skill_content,
             list_skill_scripts,
-            run_skill_script,
+            script_runner::run_skill_script,
             test_skill,
-            export_skill,
+            skill_archive::export_skill,
+            skill_archive::export_skills,
+            operations::list_operations,
+            operations::cancel_operation,
+            git_import::import_from_git,
+            skill_archive::import_skill,
+            skill_preview::preview_skill_delete,
+            skill_preview::preview_skill_import,
+            version_info::get_version_info,
+            temp_sweep::clean_temp_artifacts,
+            command_metrics::get_command_metrics,
+            name_lint::lint_names,
+            environment_check::run_environment_check,
             // AI-Powered Skill Generation (Gemini)
             save_gemini_api_key,
             generate_skill_with_gemini,
+            save_generated_skill,
             // MCP Research Commands (Phase 2)
             research_skill_with_mcp,
             // Antigravity Integration Commands
@@ -2163,6 +2802,48 @@ pub fn run() {
             workflow_generator::save_workflow,
             workflow_generator::list_agents
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            // Children must not outlive the app and keep editing files headless
+            if let tauri::RunEvent::Exit = event {
+                let grace = std::time::Duration::from_secs(task_process::TaskProcessSettings::load().kill_grace_secs);
+                let killed = task_process::kill_all(grace);
+                if killed > 0 {
+                    eprintln!("Stopped {} child process(es) on exit", killed);
+                }
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_skill_frontmatter_round_trips() {
+        let root = std::env::temp_dir().join(format!("vibecode-new-skill-{}", uuid::Uuid::new_v4()));
+
+        for category in [None, Some("Data")] {
+            let folder = root.join(category.unwrap_or("plain"));
+            write_new_skill(&folder, "PDF Tools", "Read and fill PDFs", category).unwrap();
+
+            let content = std::fs::read_to_string(folder.join("SKILL.md")).unwrap();
+            let doc = skill_frontmatter::SkillDocument::parse(&content);
+            assert_eq!(doc.get("name").as_deref(), Some("PDF Tools"));
+            assert_eq!(doc.get("description").as_deref(), Some("Read and fill PDFs"));
+            assert_eq!(doc.get("version").as_deref(), Some("1.0.0"));
+            assert_eq!(doc.get("category").as_deref(), category);
+            assert_eq!(doc.render(), content);
+            assert!(doc.body().contains("\n# PDF Tools\n"));
+            assert!(content.lines().all(|line| !line.starts_with("    ")));
+
+            let guardrails = std::fs::read_to_string(folder.join("guardrails.md")).unwrap();
+            assert!(guardrails.starts_with("# Guardrails for PDF Tools\n\n## Rules\n"));
+            assert!(guardrails.lines().all(|line| !line.starts_with("    ")));
+            assert!(folder.join("scripts").is_dir());
+        }
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 }
@@ -0,0 +1,288 @@
+/// Line Stats - Lines added and removed per changed file, computed here
+///
+/// Whoever reports a change (vibe.py, the fs watcher, `add_changed_file`),
+/// its counts come from comparing the file with what it was before, line by
+/// line with `similar`. In a git repository "before" is the HEAD blob, read
+/// for all files at once with `git cat-file --batch`; otherwise it is the
+/// copy `begin` caches when a task starts (the project's files that are not
+/// ignored, up to 1 MB each and 64 MB in all), kept until a task starts in
+/// another project while none runs in this one. A file that did not exist before is all added, a
+/// deleted one all removed. Binary files (a NUL byte on either side) and
+/// files with nothing to compare with count None rather than 0.
+/// `compute_line_stats(path)` recomputes one entry of the changed files list
+/// and emits the list as `changed-files-updated`.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Mutex, MutexGuard};
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+use tauri::Emitter;
+
+use crate::git_diff;
+use crate::git_status::CHANGED_FILES_UPDATED_EVENT;
+
+/// Largest file cached as a baseline
+const MAX_CACHED_FILE_BYTES: u64 = 1024 * 1024;
+/// Baseline cache size per project
+const MAX_CACHED_BYTES: u64 = 64 * 1024 * 1024;
+
+/// What a file was before the task
+enum Before {
+    Content(Vec<u8>),
+    /// It did not exist
+    Absent,
+    /// Nothing to compare with
+    Unknown,
+}
+
+/// Files of a project not under git as they were when a task started
+struct Baseline {
+    /// Tasks running in the project
+    running: usize,
+    /// None: the file existed but was too large to cache
+    files: HashMap<PathBuf, Option<Vec<u8>>>,
+}
+
+/// Baselines by project
+static BASELINES: Mutex<Option<HashMap<PathBuf, Baseline>>> = Mutex::new(None);
+
+fn baselines() -> MutexGuard<'static, Option<HashMap<PathBuf, Baseline>>> {
+    BASELINES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Counts of a change reported with `compute_line_stats`
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LineStats {
+    pub lines_added: Option<u32>,
+    pub lines_removed: Option<u32>,
+}
+
+/// Lines added and removed from `before` to `after` (None: missing); None
+/// when either side is binary
+pub(crate) fn diff_counts(before: Option<&[u8]>, after: Option<&[u8]>) -> Option<(u32, u32)> {
+    if before.into_iter().chain(after).any(|bytes| bytes.contains(&0)) {
+        return None;
+    }
+    let before = String::from_utf8_lossy(before.unwrap_or_default());
+    let after = String::from_utf8_lossy(after.unwrap_or_default());
+    let diff = TextDiff::from_lines(before.as_ref(), after.as_ref());
+    let (mut added, mut removed) = (0, 0);
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => added += 1,
+            ChangeTag::Delete => removed += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+    Some((added, removed))
+}
+
+/// HEAD blobs of `paths` (relative to the repository `root`); paths HEAD
+/// does not have are left out
+fn head_blobs(root: &Path, paths: &[String]) -> std::io::Result<HashMap<String, Vec<u8>>> {
+    // The batch protocol is line based
+    let paths: Vec<&String> = paths.iter().filter(|path| !path.contains('\n')).collect();
+    let mut child = crate::child_env::system_command("git")
+        .arg("-C")
+        .arg(root)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let requests: String = paths.iter().map(|path| format!("HEAD:{}\n", path)).collect();
+    let mut stdin = child.stdin.take().ok_or_else(|| std::io::Error::other("no stdin"))?;
+    // Written from a thread so a full stdout pipe cannot block both sides
+    let writer = std::thread::spawn(move || stdin.write_all(requests.as_bytes()));
+    let mut stdout = BufReader::new(child.stdout.take().ok_or_else(|| std::io::Error::other("no stdout"))?);
+
+    let mut blobs = HashMap::new();
+    for path in paths {
+        let mut header = String::new();
+        if stdout.read_line(&mut header)? == 0 {
+            break;
+        }
+        // "<object> <type> <size>", or "<name> missing"
+        let mut fields = header.trim_end().rsplitn(3, ' ');
+        let (Some(size), Some(kind)) = (fields.next().and_then(|s| s.parse::<usize>().ok()), fields.next()) else { continue };
+        let mut content = vec![0; size + 1];
+        stdout.read_exact(&mut content)?;
+        content.pop();
+        if kind == "blob" {
+            blobs.insert(path.clone(), content);
+        }
+    }
+    let _ = writer.join();
+    let _ = child.wait();
+    Ok(blobs)
+}
+
+/// What each of `files` (absolute) was before, in `project`
+fn before(project: &Path, files: &[&Path]) -> Vec<Before> {
+    if let Ok(root) = git_diff::repository_root(project) {
+        let relative: Vec<Option<String>> =
+            files.iter().map(|file| git_diff::repository_path(&root, project, &file.to_string_lossy()).ok()).collect();
+        let wanted: Vec<String> = relative.iter().flatten().cloned().collect();
+        let Ok(mut blobs) = head_blobs(&root, &wanted) else {
+            return files.iter().map(|_| Before::Unknown).collect();
+        };
+        return relative
+            .into_iter()
+            .map(|path| match path {
+                Some(path) => blobs.remove(&path).map_or(Before::Absent, Before::Content),
+                None => Before::Unknown,
+            })
+            .collect();
+    }
+    let cached = baselines();
+    files
+        .iter()
+        .map(|file| match cached.as_ref().and_then(|b| b.get(project)) {
+            Some(baseline) => match baseline.files.get(*file) {
+                Some(Some(content)) => Before::Content(content.clone()),
+                Some(None) => Before::Unknown,
+                None => Before::Absent,
+            },
+            None => Before::Unknown,
+        })
+        .collect()
+}
+
+/// Counts of each of `files` in `project`, each given as its path and the
+/// path it had before (renames)
+pub(crate) fn counts(project: &Path, files: &[(&Path, Option<&Path>)]) -> Vec<Option<(u32, u32)>> {
+    let sources: Vec<&Path> = files.iter().map(|(path, from)| from.unwrap_or(path)).collect();
+    before(project, &sources)
+        .into_iter()
+        .zip(files)
+        .map(|(before, (path, _))| {
+            let after = std::fs::read(path).ok();
+            match before {
+                Before::Content(content) => diff_counts(Some(&content), after.as_deref()),
+                Before::Absent => diff_counts(None, after.as_deref()),
+                Before::Unknown => None,
+            }
+        })
+        .collect()
+}
+
+/// Counts of one file of the open project (absolute, or relative to it)
+pub(crate) fn counts_in_current_project(path: &str, from: Option<&str>) -> Option<(u32, u32)> {
+    let project = PathBuf::from(crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone())?);
+    let (path, from) = (project.join(path), from.map(|from| project.join(from)));
+    counts(&project, &[(&path, from.as_deref())]).pop().flatten()
+}
+
+/// Cache what `project` holds, unless it is under git or a task running
+/// there already did
+pub(crate) fn begin(project: &Path) {
+    if let Some(baseline) = baselines().as_mut().and_then(|b| b.get_mut(project)).filter(|b| b.running > 0) {
+        baseline.running += 1;
+        return;
+    }
+    if git_diff::repository_root(project).is_ok() {
+        return;
+    }
+    let mut budget = MAX_CACHED_BYTES;
+    let matcher = crate::ignore_rules::current_matcher(project);
+    let files = crate::workflow_snapshot::project_files(project, &matcher)
+        .into_iter()
+        .map(|(relative, size)| {
+            let path = project.join(relative);
+            let fits = size <= MAX_CACHED_FILE_BYTES && size <= budget;
+            let content = fits.then(|| std::fs::read(&path).ok()).flatten();
+            budget -= content.as_ref().map_or(0, |c| c.len() as u64).min(budget);
+            (path, content)
+        })
+        .collect();
+    let mut baselines = baselines();
+    let baselines = baselines.get_or_insert_with(HashMap::new);
+    baselines.retain(|_, baseline| baseline.running > 0);
+    baselines.insert(project.to_path_buf(), Baseline { running: 1, files });
+}
+
+/// A task that `begin` was called for in `project` is done
+pub(crate) fn finish(project: &Path) {
+    if let Some(baseline) = baselines().as_mut().and_then(|b| b.get_mut(project)) {
+        baseline.running = baseline.running.saturating_sub(1);
+    }
+}
+
+/// Recompute the counts of `path` (absolute, or relative to the project) and
+/// update its entry of the changed files list
+#[tauri::command]
+pub async fn compute_line_stats(app: tauri::AppHandle, path: String) -> Result<LineStats, String> {
+    crate::command_metrics::timed("compute_line_stats", async move {
+        let project = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()).ok_or("No project is open")?;
+        let absolute = Path::new(&project).join(&path).to_string_lossy().to_string();
+        let from = crate::CHANGED_FILES
+            .read()
+            .map_err(|e| format!("Lock error: {}", e))?
+            .iter()
+            .find(|f| f.path == absolute || f.path == path)
+            .and_then(|f| f.renamed_from.clone());
+        let lookup = path.clone();
+        let counts = tauri::async_runtime::spawn_blocking(move || counts_in_current_project(&lookup, from.as_deref()))
+            .await
+            .map_err(|e| format!("Line stats failed: {}", e))?;
+        let stats = LineStats { lines_added: counts.map(|c| c.0), lines_removed: counts.map(|c| c.1) };
+
+        let mut files = crate::CHANGED_FILES.write().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(file) = files.iter_mut().find(|f| f.path == absolute || f.path == path) {
+            file.lines_added = stats.lines_added;
+            file.lines_removed = stats.lines_removed;
+            let _ = app.emit(CHANGED_FILES_UPDATED_EVENT, &*files);
+        }
+        Ok(stats)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_against_cached_copy_and_head() {
+        assert_eq!(diff_counts(Some(b"a\nb\nc\n"), Some(b"a\nB\nc\nd\n")), Some((2, 1)));
+        assert_eq!(diff_counts(None, Some(b"one\ntwo")), Some((2, 0)));
+        assert_eq!(diff_counts(Some(b"gone\n"), None), Some((0, 1)));
+        assert_eq!(diff_counts(Some(b"text\n"), Some(b"\x00\x01")), None);
+
+        let project = std::env::temp_dir().join(format!("vibecode-line-stats-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join("a.txt"), "1\n2\n3\n").unwrap();
+        std::fs::write(project.join("old.txt"), "x\ny\n").unwrap();
+        begin(&project);
+        std::fs::write(project.join("a.txt"), "1\n3\n4\n5\n").unwrap();
+        std::fs::remove_file(project.join("old.txt")).unwrap();
+        std::fs::write(project.join("new.txt"), "n\n").unwrap();
+        let (a, old, new) = (project.join("a.txt"), project.join("old.txt"), project.join("new.txt"));
+        let elsewhere = std::env::temp_dir().join("vibecode-line-stats-elsewhere");
+        assert_eq!(
+            counts(&project, &[(&a, None), (&old, None), (&new, None)]),
+            vec![Some((2, 1)), Some((0, 2)), Some((1, 0))]
+        );
+        assert_eq!(counts(&elsewhere, &[(&elsewhere.join("a.txt"), None)]), vec![None]);
+        finish(&project);
+
+        if git_diff::git(&std::env::temp_dir(), &["--version"]).is_ok() {
+            let git = |args: &[&str]| assert!(git_diff::git(&project, args).unwrap().status.success());
+            git(&["init", "--quiet"]);
+            git(&["add", "a.txt", "new.txt"]);
+            git(&["-c", "user.name=Ada", "-c", "user.email=ada@example.com", "commit", "--quiet", "-m", "init"]);
+            std::fs::write(project.join("a.txt"), "1\n3\n").unwrap();
+            std::fs::rename(project.join("new.txt"), project.join("renamed.txt")).unwrap();
+            std::fs::write(project.join("logo.png"), b"\x89PNG\x00").unwrap();
+            let (renamed, logo) = (project.join("renamed.txt"), project.join("logo.png"));
+            assert_eq!(
+                counts(&project, &[(&a, None), (&renamed, Some(&new)), (&logo, None)]),
+                vec![Some((0, 2)), Some((0, 0)), None]
+            );
+        }
+
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+}
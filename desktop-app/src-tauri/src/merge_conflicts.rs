@@ -0,0 +1,234 @@
+/// Merge Conflicts - Unresolved conflicts in the open project's repository
+///
+/// `get_conflicted_files` lists the unmerged entries of `git status` (the
+/// part of the repository inside the project), each with the conflict
+/// regions (`<<<<<<<` ... `=======` ... `>>>>>>>`) still in the file.
+/// `execute_task` and `run_workflow` call `check` first and refuse to start
+/// while there are any, since an agent working on half-merged files makes a
+/// mess of them, unless `allow_conflicts` is set. A project that is not under
+/// git has no conflicts. The fs watcher also passes every burst to `detect`,
+/// which emits `conflicts-detected` with the tracked files that did not have
+/// conflict regions before and have some now.
+
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::Serialize;
+
+use crate::fs_watcher::FsChanged;
+use crate::git_diff::{self, GitDiffError};
+use crate::git_status;
+
+pub const CONFLICTS_DETECTED_EVENT: &str = "conflicts-detected";
+
+/// Larger files are not searched for conflict regions
+const MAX_SCANNED_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Files named in a refusal
+const LISTED_FILES: usize = 5;
+
+/// A file with a merge conflict
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ConflictedFile {
+    pub path: String,
+    /// Relative to the repository root, with `/` separators
+    pub relative_path: String,
+    /// Conflict regions left in the file (0 once resolved but not yet added)
+    pub markers: usize,
+}
+
+/// Payload of `conflicts-detected`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ConflictsDetected {
+    pub root: String,
+    pub files: Vec<ConflictedFile>,
+}
+
+/// Files the watcher last saw with conflict regions
+static MARKED: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
+
+/// Complete conflict regions in `content`: a `<<<<<<<` line, then a
+/// `=======` line, then a `>>>>>>>` line
+fn count_markers(content: &[u8]) -> usize {
+    let mut regions = 0;
+    // 0: outside, 1: after <<<<<<<, 2: after =======
+    let mut state = 0;
+    for line in content.split(|&b| b == b'\n') {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        state = match state {
+            _ if line.starts_with(b"<<<<<<<") => 1,
+            1 if line == b"=======" => 2,
+            2 if line.starts_with(b">>>>>>>") => {
+                regions += 1;
+                0
+            }
+            state => state,
+        };
+    }
+    regions
+}
+
+/// Conflict regions in the file at `path` (0 when unreadable, too large or binary)
+fn markers_in(path: &Path) -> usize {
+    let Ok(file) = std::fs::File::open(path) else { return 0 };
+    let mut content = Vec::new();
+    if file.take(MAX_SCANNED_BYTES + 1).read_to_end(&mut content).is_err()
+        || content.len() as u64 > MAX_SCANNED_BYTES
+        || content.contains(&0)
+    {
+        return 0;
+    }
+    count_markers(&content)
+}
+
+/// Unmerged files of `project` (the part of its repository inside it)
+pub(crate) fn conflicted_files(project: &Path) -> Result<Vec<ConflictedFile>, GitDiffError> {
+    let root = git_diff::repository_root(project)?;
+    Ok(git_status::status(project, false)?
+        .into_iter()
+        .filter(|entry| entry.unmerged)
+        .map(|entry| {
+            let path = root.join(&entry.path);
+            ConflictedFile { markers: markers_in(&path), path: path.to_string_lossy().to_string(), relative_path: entry.path }
+        })
+        .collect())
+}
+
+/// Refusal to start work in `project` while it has conflicts
+fn refusal(files: &[ConflictedFile]) -> Option<String> {
+    if files.is_empty() {
+        return None;
+    }
+    let mut listed: Vec<&str> = files.iter().take(LISTED_FILES).map(|f| f.relative_path.as_str()).collect();
+    let more = files.len().saturating_sub(LISTED_FILES);
+    let others = format!("and {} more", more);
+    if more > 0 {
+        listed.push(&others);
+    }
+    Some(format!(
+        "The project has unresolved merge conflicts in {} file(s): {}. Resolve them first, or pass allow_conflicts to start anyway",
+        files.len(),
+        listed.join(", ")
+    ))
+}
+
+/// Err when the open project has merge conflicts and `allow` is not set
+pub async fn check(allow: bool) -> Result<(), String> {
+    let Some(project) = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()) else { return Ok(()) };
+    if allow {
+        return Ok(());
+    }
+    let files = tauri::async_runtime::spawn_blocking(move || conflicted_files(Path::new(&project)))
+        .await
+        .map_err(|e| format!("Conflict check failed: {}", e))?;
+    // Not a repository, or git is missing: nothing to merge
+    match files.ok().as_deref().and_then(refusal) {
+        Some(refusal) => Err(refusal),
+        None => Ok(()),
+    }
+}
+
+/// Tracked files of `change` that gained conflict regions since the watcher
+/// last looked at them
+pub fn detect(root: &Path, change: &FsChanged) -> Option<ConflictsDetected> {
+    let paths: Vec<PathBuf> = change.created.iter().chain(&change.modified).map(PathBuf::from).collect();
+    let mut marked = MARKED.lock().unwrap_or_else(|e| e.into_inner());
+    let marked = marked.get_or_insert_with(HashSet::new);
+    for path in &change.deleted {
+        marked.remove(Path::new(path));
+    }
+
+    let mut found = Vec::new();
+    for path in paths {
+        let markers = markers_in(&path);
+        if markers == 0 {
+            marked.remove(&path);
+        } else if marked.insert(path.clone()) {
+            found.push((path, markers));
+        }
+    }
+    if found.is_empty() {
+        return None;
+    }
+    let repo = git_diff::repository_root(root).ok()?;
+    let files: Vec<ConflictedFile> = found
+        .into_iter()
+        .filter(|(path, _)| git_diff::is_tracked(path) == Some(true))
+        .filter_map(|(path, markers)| {
+            let relative = git_diff::repository_path(&repo, root, &path.to_string_lossy()).ok()?;
+            Some(ConflictedFile { path: path.to_string_lossy().to_string(), relative_path: relative, markers })
+        })
+        .collect();
+    (!files.is_empty()).then(|| ConflictsDetected { root: root.to_string_lossy().to_string(), files })
+}
+
+/// Files with merge conflicts in the open project
+#[tauri::command]
+pub async fn get_conflicted_files() -> Result<Vec<ConflictedFile>, GitDiffError> {
+    crate::command_metrics::timed("get_conflicted_files", async move {
+        let project = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()).ok_or(GitDiffError::NoProject)?;
+        tauri::async_runtime::spawn_blocking(move || conflicted_files(Path::new(&project)))
+            .await
+            .map_err(|e| GitDiffError::Git(format!("Conflict check failed: {}", e)))?
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflicts_are_listed_and_refused() {
+        let region = "<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\n";
+        assert_eq!(count_markers(format!("a\n{}b\r\n{}", region, region.replace('\n', "\r\n")).as_bytes()), 2);
+        assert_eq!(count_markers(b"<<<<<<< HEAD\nonly the start\n"), 0);
+        assert_eq!(count_markers(b"=======\n>>>>>>> x\n"), 0);
+        assert!(refusal(&[]).is_none());
+        let many: Vec<ConflictedFile> = (0..7)
+            .map(|i| ConflictedFile { path: format!("/p/{}.rs", i), relative_path: format!("{}.rs", i), markers: 1 })
+            .collect();
+        let refused = refusal(&many).unwrap();
+        assert!(refused.contains("7 file(s): 0.rs, 1.rs, 2.rs, 3.rs, 4.rs, and 2 more"), "{}", refused);
+
+        if git_diff::git(&std::env::temp_dir(), &["--version"]).is_err() {
+            return;
+        }
+        let repo = std::env::temp_dir().join(format!("vibecode-merge-conflicts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        let git = |args: &[&str]| {
+            let mut all = vec!["-c", "user.name=Ada", "-c", "user.email=ada@example.com"];
+            all.extend(args);
+            git_diff::git(&repo, &all).unwrap()
+        };
+        git(&["init", "--quiet", "-b", "main"]);
+        std::fs::write(repo.join("a.txt"), "base\n").unwrap();
+        git(&["add", "a.txt"]);
+        git(&["commit", "--quiet", "-m", "base"]);
+        git(&["checkout", "--quiet", "-b", "feature"]);
+        std::fs::write(repo.join("a.txt"), "theirs\n").unwrap();
+        git(&["commit", "--quiet", "-am", "theirs"]);
+        git(&["checkout", "--quiet", "main"]);
+        std::fs::write(repo.join("a.txt"), "ours\n").unwrap();
+        git(&["commit", "--quiet", "-am", "ours"]);
+        assert!(conflicted_files(&repo).unwrap().is_empty());
+        assert!(!git(&["merge", "--quiet", "feature"]).status.success());
+
+        let files = conflicted_files(&repo).unwrap();
+        assert_eq!(files.iter().map(|f| (f.relative_path.as_str(), f.markers)).collect::<Vec<_>>(), vec![("a.txt", 1)]);
+        std::fs::write(repo.join("notes.txt"), region).unwrap();
+        let a = repo.join("a.txt").to_string_lossy().to_string();
+        let change = FsChanged {
+            root: repo.to_string_lossy().to_string(),
+            created: vec![repo.join("notes.txt").to_string_lossy().to_string()],
+            modified: vec![a.clone()],
+            ..Default::default()
+        };
+        let detected = detect(&repo, &change).unwrap();
+        assert_eq!(detected.files.iter().map(|f| f.relative_path.as_str()).collect::<Vec<_>>(), vec!["a.txt"]);
+        // Only reported when the regions appear
+        assert!(detect(&repo, &change).is_none());
+
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+}
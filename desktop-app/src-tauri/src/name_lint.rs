@@ -0,0 +1,391 @@
+/// Name Lint - Skill and workflow name collisions across scopes
+///
+/// Skills and workflows live in several places (the open project, the app's
+/// own folders, `.agent/workflows`), so the same name can resolve to different
+/// things. `lint_names` reports exact collisions, near-duplicates (differences
+/// only in case, separators or diacritics), ids that don't match their
+/// declared names, and reserved names, each with a suggested fix.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+pub const KIND_SKILL: &str = "skill";
+pub const KIND_WORKFLOW: &str = "workflow";
+
+/// Names that clash with vibe.py subcommands or Windows device names
+const RESERVED_NAMES: &[&str] = &[
+    "list", "run", "new", "create", "help", "task", "workflow", "context", "stats",
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "lpt1", "lpt2", "lpt3",
+];
+
+/// A skill or workflow found in one scope
+#[derive(Debug, Clone, Serialize)]
+pub struct NamedItem {
+    pub kind: String,
+    pub scope: String,
+    pub id: String,
+    pub name: Option<String>,
+    pub path: String,
+}
+
+/// One lint finding
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct NameIssue {
+    /// "collision" | "near_duplicate" | "id_name_mismatch" | "reserved"
+    pub rule: String,
+    /// "error" | "warning"
+    pub severity: String,
+    /// `kind:scope/id` of every item involved
+    pub items: Vec<String>,
+    pub message: String,
+    pub suggestion: String,
+}
+
+/// Result of `lint_names`
+#[derive(Debug, Serialize)]
+pub struct NameLintReport {
+    pub scanned: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub issues: Vec<NameIssue>,
+}
+
+impl NamedItem {
+    fn label(&self) -> String {
+        format!("{}:{}/{}", self.kind, self.scope, self.id)
+    }
+}
+
+/// Strip common Latin (including Vietnamese) diacritics
+fn fold_diacritic(c: char) -> char {
+    const TABLE: &[(&str, char)] = &[
+        ("àáảãạăằắẳẵặâầấẩẫậäåā", 'a'),
+        ("èéẻẽẹêềếểễệëē", 'e'),
+        ("ìíỉĩịïî", 'i'),
+        ("òóỏõọôồốổỗộơờớởỡợöø", 'o'),
+        ("ùúủũụưừứửữựüû", 'u'),
+        ("ỳýỷỹỵÿ", 'y'),
+        ("đ", 'd'),
+        ("ç", 'c'),
+        ("ñ", 'n'),
+    ];
+    TABLE
+        .iter()
+        .find(|(chars, _)| chars.contains(c))
+        .map(|(_, plain)| *plain)
+        .unwrap_or(c)
+}
+
+/// Key under which near-duplicates compare equal
+pub fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(fold_diacritic)
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect()
+}
+
+/// Kebab-case id the app would create for a display name
+pub fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(fold_diacritic)
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn issue(rule: &str, severity: &str, items: &[&NamedItem], message: String, suggestion: String) -> NameIssue {
+    NameIssue {
+        rule: rule.to_string(),
+        severity: severity.to_string(),
+        items: items.iter().map(|i| i.label()).collect(),
+        message,
+        suggestion,
+    }
+}
+
+/// Lint a set of items
+pub fn lint(items: &[NamedItem]) -> Vec<NameIssue> {
+    let mut issues = Vec::new();
+
+    // Group by normalized id to find collisions and near-duplicates
+    let mut groups: BTreeMap<String, Vec<&NamedItem>> = BTreeMap::new();
+    for item in items {
+        groups.entry(normalize_name(&item.id)).or_default().push(item);
+    }
+
+    for group in groups.values().filter(|g| g.len() > 1) {
+        let mut exact: BTreeMap<&str, Vec<&NamedItem>> = BTreeMap::new();
+        for item in group {
+            exact.entry(item.id.as_str()).or_default().push(item);
+        }
+
+        for (id, same) in exact.iter().filter(|(_, same)| same.len() > 1) {
+            let same_kind = same.iter().all(|i| i.kind == same[0].kind);
+            let scopes: Vec<&str> = same.iter().map(|i| i.scope.as_str()).collect();
+            issues.push(issue(
+                "collision",
+                if same_kind { "error" } else { "warning" },
+                same,
+                format!("'{}' exists in more than one place ({})", id, scopes.join(", ")),
+                if same_kind {
+                    format!("Rename one of them, e.g. '{}-{}'", id, same[1].scope)
+                } else {
+                    format!("Rename the {} to make it clear which '{}' runs", same[1].kind, id)
+                },
+            ));
+        }
+
+        if exact.len() > 1 {
+            let ids: Vec<&str> = exact.keys().copied().collect();
+            issues.push(issue(
+                "near_duplicate",
+                "warning",
+                group,
+                format!("Names differ only in case, separators or accents: {}", ids.join(", ")),
+                format!("Merge them or rename so they are clearly distinct (keep '{}'?)", slugify(ids[0])),
+            ));
+        }
+    }
+
+    for item in items {
+        if let Some(name) = item.name.as_deref().filter(|n| !n.trim().is_empty()) {
+            if slugify(name) != item.id {
+                issues.push(issue(
+                    "id_name_mismatch",
+                    "warning",
+                    &[item],
+                    format!("{} '{}' declares the name '{}'", item.kind, item.id, name),
+                    format!("Rename the folder/file to '{}' or update the declared name", slugify(name)),
+                ));
+            }
+        }
+
+        if RESERVED_NAMES.contains(&item.id.to_lowercase().as_str()) || item.id.starts_with('.') {
+            issues.push(issue(
+                "reserved",
+                "error",
+                &[item],
+                format!("'{}' is a reserved name", item.id),
+                format!("Rename it, e.g. '{}-{}'", item.id.trim_start_matches('.'), item.kind),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Top-level `name:` of a workflow YAML or markdown frontmatter
+fn declared_name(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let body = content.strip_prefix("---").map(|rest| rest.split("---").next().unwrap_or(""));
+    body.unwrap_or(&content)
+        .lines()
+        .find_map(|line| line.strip_prefix("name:"))
+        .map(|name| name.trim().trim_matches('"').trim_matches('\'').to_string())
+}
+
+fn scan_skills(dir: &Path, scope: &str, items: &mut Vec<NamedItem>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() || !path.join("SKILL.md").exists() {
+            continue;
+        }
+        let name = crate::parse_skill_frontmatter(&path.join("SKILL.md"))
+            .ok()
+            .map(|m| m.name)
+            .filter(|n| !n.is_empty());
+        items.push(NamedItem {
+            kind: KIND_SKILL.to_string(),
+            scope: scope.to_string(),
+            id: entry.file_name().to_string_lossy().to_string(),
+            name,
+            path: path.to_string_lossy().to_string(),
+        });
+    }
+}
+
+fn scan_workflows(dir: &Path, scope: &str, extensions: &[&str], items: &mut Vec<NamedItem>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let matches = path
+            .extension()
+            .is_some_and(|ext| extensions.iter().any(|e| ext == *e));
+        if !path.is_file() || !matches {
+            continue;
+        }
+        let Some(id) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else { continue };
+        items.push(NamedItem {
+            kind: KIND_WORKFLOW.to_string(),
+            scope: scope.to_string(),
+            id,
+            name: declared_name(&path),
+            path: path.to_string_lossy().to_string(),
+        });
+    }
+}
+
+/// Collect skills and workflows from every scope
+pub fn collect_items() -> Vec<NamedItem> {
+    let mut items = Vec::new();
+    let project = crate::CURRENT_PROJECT
+        .read()
+        .ok()
+        .and_then(|p| p.clone())
+        .map(PathBuf::from);
+
+    let app_skills = crate::get_default_skills_path();
+    let app_workflows = crate::get_workflows_path();
+
+    if let Some(project) = &project {
+        let skills = project.join(".agent").join("skills");
+        if skills != app_skills {
+            scan_skills(&skills, "project", &mut items);
+        }
+        let workflows = project.join("workflows");
+        if workflows != app_workflows {
+            scan_workflows(&workflows, "project", &["yaml", "yml"], &mut items);
+        }
+        scan_workflows(&project.join(".agent").join("workflows"), "agent", &["md"], &mut items);
+    }
+
+    scan_skills(&app_skills, "app", &mut items);
+    scan_workflows(&app_workflows, "app", &["yaml", "yml"], &mut items);
+    if project.is_none() {
+        if let Some(root) = app_skills.parent() {
+            scan_workflows(&root.join("workflows"), "agent", &["md"], &mut items);
+        }
+    }
+
+    items
+}
+
+/// Lint findings that involve a name about to be created or imported
+///
+/// Exact id collisions in the target folder are still rejected by the caller;
+/// everything reported here is advisory.
+pub fn check_new_name(kind: &str, scope: &str, id: &str, name: Option<&str>) -> Vec<NameIssue> {
+    let candidate = NamedItem {
+        kind: kind.to_string(),
+        scope: format!("{} (new)", scope),
+        id: id.to_string(),
+        name: name.map(String::from),
+        path: String::new(),
+    };
+    let label = candidate.label();
+
+    let mut items = collect_items();
+    items.push(candidate);
+
+    let mut issues: Vec<NameIssue> = lint(&items)
+        .into_iter()
+        .filter(|i| i.items.contains(&label))
+        .collect();
+    for issue in &mut issues {
+        issue.severity = "warning".to_string();
+    }
+    if !issues.is_empty() {
+        eprintln!("Name lint: {} warning(s) for new {} '{}'", issues.len(), kind, id);
+    }
+    issues
+}
+
+/// Scope new skills are created in
+pub fn current_skill_scope() -> &'static str {
+    match crate::CURRENT_PROJECT.read() {
+        Ok(project) if project.is_some() => "project",
+        _ => "app",
+    }
+}
+
+/// Notify the frontend about advisory findings for a newly created item
+pub fn emit_warnings(app: &tauri::AppHandle, kind: &str, id: &str, issues: &[NameIssue]) {
+    use tauri::Emitter;
+
+    if issues.is_empty() {
+        return;
+    }
+    let _ = app.emit(
+        "name-lint-warnings",
+        serde_json::json!({ "kind": kind, "id": id, "issues": issues }),
+    );
+}
+
+/// Scan all skills and workflows and report naming problems
+#[tauri::command]
+pub async fn lint_names() -> Result<NameLintReport, String> {
+    crate::command_metrics::timed("lint_names", async move {
+        let items = collect_items();
+        let issues = lint(&items);
+
+        Ok(NameLintReport {
+            scanned: items.len(),
+            errors: issues.iter().filter(|i| i.severity == "error").count(),
+            warnings: issues.iter().filter(|i| i.severity == "warning").count(),
+            issues,
+        })
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(kind: &str, scope: &str, id: &str, name: Option<&str>) -> NamedItem {
+        NamedItem {
+            kind: kind.to_string(),
+            scope: scope.to_string(),
+            id: id.to_string(),
+            name: name.map(String::from),
+            path: String::new(),
+        }
+    }
+
+    fn rules(issues: &[NameIssue]) -> Vec<(&str, &str)> {
+        issues.iter().map(|i| (i.rule.as_str(), i.severity.as_str())).collect()
+    }
+
+    #[test]
+    fn test_collisions_and_near_duplicates() {
+        let items = vec![
+            item(KIND_SKILL, "project", "seo-audit", Some("Site SEO Audit")),
+            item(KIND_SKILL, "app", "seo-audit", None),
+            item(KIND_SKILL, "app", "SEO_Audit", None),
+            item(KIND_WORKFLOW, "app", "bao-cao", Some("Báo cáo")),
+            item(KIND_SKILL, "project", "báo-cáo", None),
+        ];
+
+        let issues = lint(&items);
+
+        assert_eq!(
+            rules(&issues),
+            vec![
+                ("near_duplicate", "warning"),
+                ("collision", "error"),
+                ("near_duplicate", "warning"),
+                ("id_name_mismatch", "warning"),
+            ]
+        );
+        assert_eq!(issues[1].items, vec!["skill:project/seo-audit", "skill:app/seo-audit"]);
+        assert_eq!(issues[2].items.len(), 3);
+        assert_eq!(issues[3].suggestion, "Rename the folder/file to 'site-seo-audit' or update the declared name");
+    }
+
+    #[test]
+    fn test_reserved_and_slug_rules() {
+        assert_eq!(slugify("Tạo Báo Cáo  Tuần"), "tao-bao-cao-tuan");
+        assert_eq!(normalize_name("Đặt-Lịch"), normalize_name("dat_lich"));
+
+        let issues = lint(&[item(KIND_WORKFLOW, "app", "list", None)]);
+        assert_eq!(rules(&issues), vec![("reserved", "error")]);
+
+        assert!(lint(&[item(KIND_SKILL, "app", "tao-bao-cao", Some("Tạo báo cáo"))]).is_empty());
+    }
+}
@@ -86,7 +86,7 @@ async fn exchange_code_for_tokens(
     code: &str,
     code_verifier: &str,
 ) -> Result<OAuthTokens, String> {
-    let client = reqwest::Client::new();
+    let client = crate::http_client::client()?;
     
     let params = [
         ("code", code),
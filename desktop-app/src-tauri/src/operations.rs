@@ -0,0 +1,233 @@
+/// Operations - Shared progress reporting and cancellation for long-running work
+///
+/// A project-wide operation (bulk export, content search, ...) registers a
+/// `ProgressReporter` under a generated id. Progress goes out as normalized
+/// `operation-progress` events `{ id, kind, done, total, message }`, the
+/// operation shows up in `list_operations` until the reporter is dropped, and
+/// `cancel_operation(id)` raises a flag the operation polls between units of
+/// work. A cancelled operation returns whatever it finished so far.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use serde::Serialize;
+use tauri::Emitter;
+
+pub const PROGRESS_EVENT: &str = "operation-progress";
+
+pub const KIND_SKILL_EXPORT: &str = "skill_export";
+
+/// Lifecycle of an operation as reported to the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationState {
+    Running,
+    /// Cancellation was requested but the operation has not stopped yet
+    Cancelling,
+    Finished,
+    Cancelled,
+}
+
+/// Payload of `operation-progress` and entry of `list_operations`
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationProgress {
+    pub id: String,
+    pub kind: String,
+    pub done: u64,
+    /// None while the amount of work is still unknown
+    pub total: Option<u64>,
+    pub message: Option<String>,
+    pub state: OperationState,
+    pub started_at: String, // ISO 8601
+}
+
+struct Entry {
+    cancelled: AtomicBool,
+    progress: Mutex<OperationProgress>,
+}
+
+impl Entry {
+    fn snapshot(&self) -> OperationProgress {
+        let mut progress = self.progress.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if progress.state == OperationState::Running && self.cancelled.load(Ordering::Relaxed) {
+            progress.state = OperationState::Cancelling;
+        }
+        progress
+    }
+}
+
+static OPERATIONS: OnceLock<Mutex<BTreeMap<String, Arc<Entry>>>> = OnceLock::new();
+
+fn operations() -> &'static Mutex<BTreeMap<String, Arc<Entry>>> {
+    OPERATIONS.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+type Sink = Box<dyn Fn(&OperationProgress) + Send + Sync>;
+
+/// Handle held by a running operation; unregisters it when dropped
+pub struct ProgressReporter {
+    id: String,
+    entry: Arc<Entry>,
+    sink: Sink,
+}
+
+impl ProgressReporter {
+    /// Register an operation whose progress is emitted to the frontend
+    pub fn start(app: &tauri::AppHandle, kind: &str) -> Self {
+        let app = app.clone();
+        Self::with_sink(kind, Box::new(move |progress| {
+            let _ = app.emit(PROGRESS_EVENT, progress);
+        }))
+    }
+
+    /// Register an operation that emits no events (listed and cancellable only)
+    pub fn detached(kind: &str) -> Self {
+        Self::with_sink(kind, Box::new(|_| {}))
+    }
+
+    fn with_sink(kind: &str, sink: Sink) -> Self {
+        let id = uuid::Uuid::new_v4().to_string();
+        let entry = Arc::new(Entry {
+            cancelled: AtomicBool::new(false),
+            progress: Mutex::new(OperationProgress {
+                id: id.clone(),
+                kind: kind.to_string(),
+                done: 0,
+                total: None,
+                message: None,
+                state: OperationState::Running,
+                started_at: chrono::Utc::now().to_rfc3339(),
+            }),
+        });
+        operations()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id.clone(), entry.clone());
+
+        let reporter = Self { id, entry, sink };
+        reporter.emit();
+        reporter
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn emit(&self) {
+        (self.sink)(&self.entry.snapshot());
+    }
+
+    /// Update the counters and emit a progress event
+    pub fn report(&self, done: u64, total: Option<u64>, message: Option<String>) {
+        {
+            let mut progress = self.entry.progress.lock().unwrap_or_else(|e| e.into_inner());
+            progress.done = done;
+            progress.total = total;
+            progress.message = message;
+        }
+        self.emit();
+    }
+
+    /// Whether `cancel_operation` was called for this operation
+    pub fn is_cancelled(&self) -> bool {
+        self.entry.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for ProgressReporter {
+    fn drop(&mut self) {
+        operations()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.id);
+        {
+            let mut progress = self.entry.progress.lock().unwrap_or_else(|e| e.into_inner());
+            progress.state = if self.is_cancelled() {
+                OperationState::Cancelled
+            } else {
+                OperationState::Finished
+            };
+        }
+        self.emit();
+    }
+}
+
+/// Request cancellation; false when no such operation is running
+pub fn cancel(id: &str) -> bool {
+    let entry = operations()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(id)
+        .cloned();
+    match entry {
+        Some(entry) => {
+            entry.cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Running operations, oldest first
+pub fn list() -> Vec<OperationProgress> {
+    let entries: Vec<Arc<Entry>> = operations()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .cloned()
+        .collect();
+    let mut list: Vec<OperationProgress> = entries.iter().map(|entry| entry.snapshot()).collect();
+    list.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    list
+}
+
+/// List running background operations
+#[tauri::command]
+pub async fn list_operations() -> Result<Vec<OperationProgress>, String> {
+    crate::command_metrics::timed("list_operations", async move {
+        Ok(list())
+    }).await
+}
+
+/// Ask a running operation to stop (it returns its partial result)
+#[tauri::command]
+pub async fn cancel_operation(id: String) -> Result<(), String> {
+    crate::command_metrics::timed("cancel_operation", async move {
+        if cancel(&id) {
+            Ok(())
+        } else {
+            Err(format!("Operation '{}' is not running", id))
+        }
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reporter_registers_cancels_and_unregisters() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let reporter = {
+            let events = events.clone();
+            ProgressReporter::with_sink("test", Box::new(move |p| events.lock().unwrap().push(p.clone())))
+        };
+        let id = reporter.id().to_string();
+        reporter.report(1, Some(4), Some("walking".to_string()));
+
+        assert!(list().iter().any(|op| op.id == id && op.done == 1 && op.total == Some(4)));
+        assert!(!reporter.is_cancelled());
+        assert!(cancel(&id));
+        assert!(reporter.is_cancelled());
+        assert!(list().iter().any(|op| op.id == id && op.state == OperationState::Cancelling));
+
+        drop(reporter);
+        assert!(!list().iter().any(|op| op.id == id));
+        assert!(!cancel(&id));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.first().unwrap().state, OperationState::Running);
+        assert_eq!(events.last().unwrap().state, OperationState::Cancelled);
+        assert!(events.iter().all(|e| e.id == id && e.kind == "test"));
+    }
+}
@@ -0,0 +1,144 @@
+/// Path Metadata - What an editor status bar shows about a file
+///
+/// `get_path_metadata` returns a path's size, modified and created times as
+/// RFC 3339 (created is None where the file system does not record it), the
+/// read-only flag, unix mode bits on unix, and whether git tracks it. For a
+/// text file it also reports the line endings of its first 1 MB: `lf`,
+/// `crlf`, `mixed`, or `none` when there is no line break. `rfc3339` is the
+/// one place file times are formatted.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::time::SystemTime;
+use serde::Serialize;
+
+use crate::text_encoding;
+
+/// Bytes read to judge line endings
+const LINE_ENDING_SAMPLE: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEndings {
+    Lf,
+    Crlf,
+    Mixed,
+    None,
+}
+
+/// Result of `get_path_metadata`
+#[derive(Debug, Serialize)]
+pub struct PathMetadata {
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    /// RFC 3339
+    pub modified: Option<String>,
+    pub created: Option<String>,
+    pub readonly: bool,
+    /// Permission bits (`0o644`), on unix only
+    pub mode: Option<u32>,
+    /// None for folders and binary files
+    pub line_endings: Option<LineEndings>,
+    /// None when the path is not in a git repository
+    pub git_tracked: Option<bool>,
+}
+
+/// `time` as RFC 3339, in UTC
+pub fn rfc3339(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339()
+}
+
+/// Line endings of `text`
+fn line_endings(text: &str) -> LineEndings {
+    let crlf = text.matches("\r\n").count();
+    let lf = text.matches('\n').count() - crlf;
+    match (lf, crlf) {
+        (0, 0) => LineEndings::None,
+        (_, 0) => LineEndings::Lf,
+        (0, _) => LineEndings::Crlf,
+        _ => LineEndings::Mixed,
+    }
+}
+
+/// Line endings of the start of the file at `path`, if it is text
+fn file_line_endings(path: &Path) -> Option<LineEndings> {
+    let mut sample = Vec::new();
+    File::open(path).ok()?.take(LINE_ENDING_SAMPLE).read_to_end(&mut sample).ok()?;
+    let detected = text_encoding::detect(&sample, false)?;
+    Some(line_endings(&text_encoding::decode(detected.encoding, &sample).0))
+}
+
+fn metadata(path: &Path) -> Result<PathMetadata, String> {
+    if !path.exists() {
+        return Err(format!("Path does not exist: {}", path.display()));
+    }
+    let meta = std::fs::metadata(path).map_err(|e| format!("Failed to read metadata: {}", e))?;
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::PermissionsExt;
+        Some(meta.permissions().mode() & 0o7777)
+    };
+    #[cfg(not(unix))]
+    let mode = None;
+
+    Ok(PathMetadata {
+        path: path.to_string_lossy().to_string(),
+        is_dir: meta.is_dir(),
+        size: meta.len(),
+        modified: meta.modified().ok().map(rfc3339),
+        created: meta.created().ok().map(rfc3339),
+        readonly: meta.permissions().readonly(),
+        mode,
+        line_endings: if meta.is_file() { file_line_endings(path) } else { None },
+        git_tracked: crate::git_diff::is_tracked(path),
+    })
+}
+
+/// Times, permissions, line endings and git status of a path
+#[tauri::command]
+pub async fn get_path_metadata(path: String) -> Result<PathMetadata, String> {
+    crate::command_metrics::timed("get_path_metadata", async move {
+        tauri::async_runtime::spawn_blocking(move || metadata(Path::new(&path)))
+            .await
+            .map_err(|e| format!("Reading metadata failed: {}", e))?
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_endings_times_and_permissions() {
+        assert_eq!(line_endings("a\nb\n"), LineEndings::Lf);
+        assert_eq!(line_endings("a\r\nb\r\n"), LineEndings::Crlf);
+        assert_eq!(line_endings("a\r\nb\nc"), LineEndings::Mixed);
+        assert_eq!(line_endings("one line"), LineEndings::None);
+        assert_eq!(rfc3339(SystemTime::UNIX_EPOCH), "1970-01-01T00:00:00+00:00");
+
+        let dir = std::env::temp_dir().join(format!("vibecode-path-metadata-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("notes.txt");
+        std::fs::write(&file, "one\r\ntwo\r\n").unwrap();
+        let mut permissions = std::fs::metadata(&file).unwrap().permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&file, permissions).unwrap();
+
+        let meta = metadata(&file).unwrap();
+        assert_eq!((meta.is_dir, meta.size, meta.readonly), (false, 10, true));
+        assert_eq!(meta.line_endings, Some(LineEndings::Crlf));
+        assert!(meta.modified.is_some_and(|m| chrono::DateTime::parse_from_rfc3339(&m).is_ok()));
+        #[cfg(unix)]
+        assert_eq!(meta.mode.map(|m| m & 0o222), Some(0));
+        assert_eq!(metadata(&dir).unwrap().line_endings, None);
+        assert!(metadata(&dir.join("missing")).unwrap_err().starts_with("Path does not exist"));
+
+        let mut permissions = std::fs::metadata(&file).unwrap().permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        std::fs::set_permissions(&file, permissions).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+/// Project Env - Per-project environment variables for task runs
+///
+/// Variables set with `set_project_env` are saved in config.json next to
+/// `last_project`, under `project_env.<project path>`, and injected into every
+/// vibe.py task and workflow spawned while that project is open (see
+/// `child_env`). Values are secrets as far as the UI is concerned:
+/// `get_project_env` only returns them masked, and task output is scrubbed of
+/// them before it is returned.
+
+use std::collections::BTreeMap;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::instance_lock;
+
+/// config.json key holding every project's variables
+const CONFIG_KEY: &str = "project_env";
+
+/// Entry of `get_project_env`
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectEnvVar {
+    pub name: String,
+    pub masked: String,
+}
+
+/// Show only the last characters of a long value
+fn mask(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() < 12 {
+        return "•".repeat(chars.len().max(4));
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("••••••••{}", tail)
+}
+
+/// Same rules as POSIX shells: letters, digits and `_`, not starting with a digit
+fn validate_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid environment variable name: '{}'", name))
+    }
+}
+
+fn vars_in(config: &Value, project: &str) -> BTreeMap<String, String> {
+    config
+        .get(CONFIG_KEY)
+        .and_then(|all| all.get(project))
+        .and_then(|vars| serde_json::from_value(vars.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Set (or with `None`, remove) one variable of `project` in a config.json value
+fn set_in(config: &mut Value, project: &str, name: &str, value: Option<String>) {
+    if !config.is_object() {
+        *config = serde_json::json!({});
+    }
+    if !config[CONFIG_KEY].is_object() {
+        config[CONFIG_KEY] = serde_json::json!({});
+    }
+    let all = &mut config[CONFIG_KEY];
+    if !all[project].is_object() {
+        all[project] = serde_json::json!({});
+    }
+
+    let vars = all[project].as_object_mut().expect("set to an object above");
+    match value {
+        Some(value) => {
+            vars.insert(name.to_string(), Value::String(value));
+        }
+        None => {
+            vars.remove(name);
+        }
+    }
+    if vars.is_empty() {
+        all.as_object_mut().expect("set to an object above").remove(project);
+    }
+}
+
+/// Variables stored for `project` (empty when none are saved)
+pub fn load(project: &str) -> BTreeMap<String, String> {
+    std::fs::read_to_string(crate::get_config_path())
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .map(|config| vars_in(&config, project))
+        .unwrap_or_default()
+}
+
+fn require_project() -> Result<String, String> {
+    crate::CURRENT_PROJECT
+        .read()
+        .ok()
+        .and_then(|p| p.clone())
+        .ok_or_else(|| "No project is open".to_string())
+}
+
+/// Set an environment variable for the current project's tasks (an empty value removes it)
+#[tauri::command]
+pub async fn set_project_env(key: String, value: String) -> Result<(), String> {
+    crate::command_metrics::timed("set_project_env", async move {
+        validate_name(&key)?;
+        let project = require_project()?;
+        let value = (!value.is_empty()).then_some(value);
+
+        instance_lock::update_json_file(&crate::get_config_path(), |config| {
+            set_in(config, &project, &key, value);
+            Ok(())
+        })
+        .map_err(|e| format!("Failed to save config: {}", e))
+    }).await
+}
+
+/// Get the current project's environment variables, values masked
+#[tauri::command]
+pub async fn get_project_env() -> Result<Vec<ProjectEnvVar>, String> {
+    crate::command_metrics::timed("get_project_env", async move {
+        let project = require_project()?;
+        Ok(load(&project)
+            .into_iter()
+            .map(|(name, value)| ProjectEnvVar { masked: mask(&value), name })
+            .collect())
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vars_are_stored_per_project_and_masked() {
+        let mut config = serde_json::json!({ "last_project": "/projects/alpha" });
+
+        set_in(&mut config, "/projects/alpha", "API_TOKEN", Some("sk-live-abcdef123456".to_string()));
+        set_in(&mut config, "/projects/alpha", "REGION", Some("eu".to_string()));
+        set_in(&mut config, "/projects/beta", "REGION", Some("us".to_string()));
+
+        let alpha = vars_in(&config, "/projects/alpha");
+        assert_eq!(alpha.get("REGION").map(String::as_str), Some("eu"));
+        assert_eq!(vars_in(&config, "/projects/beta").len(), 1);
+        assert_eq!(config["last_project"], "/projects/alpha");
+
+        assert_eq!(mask("sk-live-abcdef123456"), "••••••••3456");
+        assert_eq!(mask("eu"), "••••");
+
+        set_in(&mut config, "/projects/beta", "REGION", None);
+        assert!(config[CONFIG_KEY].get("/projects/beta").is_none());
+
+        assert!(validate_name("DATA_DIR").is_ok());
+        assert!(validate_name("1BAD").is_err());
+        assert!(validate_name("BAD-NAME").is_err());
+        assert!(validate_name("").is_err());
+    }
+}
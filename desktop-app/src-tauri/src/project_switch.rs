@@ -0,0 +1,251 @@
+/// Project Switch - Guarded change of the current project
+///
+/// Switching projects replaces the review context (changed-files list) and
+/// orphans any pending autosave journal. Unless forced, a switch with either
+/// pending returns `UnsavedChanges` listing the affected files. A forced switch
+/// stashes the outgoing project's changed files under
+/// `<config>/changed-files/` and restores the incoming project's stash, so
+/// nothing is lost. Every switch emits `project-changed` and moves the
+/// project file watcher (`fs_watcher`) onto the new project.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use tauri::Emitter;
+
+use crate::retention::{artifact_dir, ArtifactClass};
+use crate::ChangedFile;
+
+/// Error returned by project switches
+#[derive(Debug)]
+pub enum ProjectSwitchError {
+    /// Changed files or autosave journals would be left behind
+    UnsavedChanges { target: String, files: Vec<String> },
+    InvalidPath(String),
+    Io(String),
+}
+
+impl ProjectSwitchError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::UnsavedChanges { .. } => "unsaved_changes",
+            Self::InvalidPath(_) => "invalid_path",
+            Self::Io(_) => "io",
+        }
+    }
+}
+
+impl fmt::Display for ProjectSwitchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsavedChanges { files, .. } => {
+                write!(f, "{} file(s) have unsaved or uncommitted changes", files.len())
+            }
+            Self::InvalidPath(msg) => write!(f, "{}", msg),
+            Self::Io(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<String> for ProjectSwitchError {
+    fn from(msg: String) -> Self {
+        Self::Io(msg)
+    }
+}
+
+/// Serialized as `{ kind, message, target?, files? }` so the frontend can
+/// confirm and retry with `force`
+impl Serialize for ProjectSwitchError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::UnsavedChanges { target, files } => {
+                let mut state = serializer.serialize_struct("ProjectSwitchError", 4)?;
+                state.serialize_field("kind", self.kind())?;
+                state.serialize_field("message", &self.to_string())?;
+                state.serialize_field("target", target)?;
+                state.serialize_field("files", files)?;
+                state.end()
+            }
+            _ => {
+                let mut state = serializer.serialize_struct("ProjectSwitchError", 2)?;
+                state.serialize_field("kind", self.kind())?;
+                state.serialize_field("message", &self.to_string())?;
+                state.end()
+            }
+        }
+    }
+}
+
+/// Payload of `project-changed`
+#[derive(Debug, Clone, Serialize)]
+struct ProjectChangedEvent {
+    old_path: Option<String>,
+    new_path: String,
+    /// Changed files restored from the incoming project's stash
+    restored_files: usize,
+}
+
+fn stash_dir() -> PathBuf {
+    crate::get_app_config_dir().join("changed-files")
+}
+
+fn stash_path(dir: &Path, project: &str) -> PathBuf {
+    let digest = Sha256::digest(project.as_bytes());
+    let name: String = digest.iter().take(8).map(|b| format!("{:02x}", b)).collect();
+    dir.join(format!("{}.json", name))
+}
+
+/// Save a project's changed files (removes the stash when there are none)
+fn stash_changed_files(dir: &Path, project: &str, files: &[ChangedFile]) -> Result<(), String> {
+    let path = stash_path(dir, project);
+    if files.is_empty() {
+        let _ = std::fs::remove_file(&path);
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create changed-files directory: {}", e))?;
+    let content = serde_json::to_string_pretty(&serde_json::json!({
+        "project": project,
+        "files": files,
+    }))
+    .map_err(|e| format!("Failed to serialize changed files: {}", e))?;
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to save changed files: {}", e))
+}
+
+/// Take a project's stashed changed files, if any
+fn take_stash(dir: &Path, project: &str) -> Vec<ChangedFile> {
+    let path = stash_path(dir, project);
+    let files = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .filter(|stash| stash["project"].as_str() == Some(project))
+        .and_then(|stash| serde_json::from_value(stash["files"].clone()).ok())
+        .unwrap_or_default();
+    let _ = std::fs::remove_file(&path);
+    files
+}
+
+/// Files with a pending autosave journal for `project`
+///
+/// Journal index entries look like `{"path": "<artifact>", "project": "...",
+/// "file": "<edited file>"}`; entries whose artifact is gone are ignored.
+fn pending_journal_files(journals: &Path, project: &str) -> Vec<String> {
+    let content = std::fs::read_to_string(journals.join("index.jsonl")).unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|entry| entry["project"].as_str() == Some(project))
+        .filter(|entry| entry["path"].as_str().is_some_and(|p| journals.join(p).exists()))
+        .filter_map(|entry| entry["file"].as_str().or(entry["path"].as_str()).map(String::from))
+        .collect()
+}
+
+/// Everything that a switch away from `project` would leave behind
+fn unsaved_files(project: &str, changed: &[ChangedFile]) -> Vec<String> {
+    let mut files: Vec<String> = changed.iter().map(|f| f.path.clone()).collect();
+    for file in pending_journal_files(&artifact_dir(ArtifactClass::Journals), project) {
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+    files
+}
+
+/// Switch the current project, refusing when work would be lost unless `force`
+pub fn switch_project(app: &tauri::AppHandle, path: &str, force: bool) -> Result<String, ProjectSwitchError> {
+    let path_buf = PathBuf::from(path);
+    if !path_buf.exists() {
+        return Err(ProjectSwitchError::InvalidPath(format!("Path does not exist: {}", path)));
+    }
+    if !path_buf.is_dir() {
+        return Err(ProjectSwitchError::InvalidPath(format!("Path is not a directory: {}", path)));
+    }
+
+    let old_path = crate::CURRENT_PROJECT
+        .read()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .clone();
+    if old_path.as_deref() == Some(path) {
+        return Ok(path.to_string());
+    }
+
+    let mut changed = crate::CHANGED_FILES.write().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(old) = &old_path {
+        let files = unsaved_files(old, &changed);
+        if !files.is_empty() && !force {
+            return Err(ProjectSwitchError::UnsavedChanges { target: path.to_string(), files });
+        }
+        stash_changed_files(&stash_dir(), old, &changed)?;
+    }
+
+    // Bring back whatever was stashed when this project was last left
+    *changed = take_stash(&stash_dir(), path);
+    let restored_files = changed.len();
+    drop(changed);
+
+    *crate::CURRENT_PROJECT.write().map_err(|e| format!("Lock error: {}", e))? = Some(path.to_string());
+    crate::save_project_path(path)?;
+    crate::workflow_watcher::sync();
+    crate::fs_watcher::sync();
+    crate::git_repository::refresh_in_background(app);
+
+    let _ = app.emit(
+        "project-changed",
+        ProjectChangedEvent {
+            old_path,
+            new_path: path.to_string(),
+            restored_files,
+        },
+    );
+
+    Ok(path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changed(path: &str) -> ChangedFile {
+        ChangedFile {
+            path: path.to_string(),
+            status: "modified".to_string(),
+            lines_added: Some(1),
+            lines_removed: Some(0),
+            renamed_from: None,
+            detected_during_task: false,
+        }
+    }
+
+    #[test]
+    fn test_stash_round_trip_and_journal_lookup() {
+        let root = std::env::temp_dir().join(format!("vibecode-project-switch-{}", uuid::Uuid::new_v4()));
+        let stash = root.join("changed-files");
+
+        stash_changed_files(&stash, "/work/a", &[changed("src/main.rs")]).unwrap();
+        assert!(take_stash(&stash, "/work/b").is_empty());
+        let restored = take_stash(&stash, "/work/a");
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].path, "src/main.rs");
+        assert!(take_stash(&stash, "/work/a").is_empty(), "stash is consumed");
+
+        let journals = root.join("journals");
+        std::fs::create_dir_all(&journals).unwrap();
+        std::fs::write(journals.join("j1.json"), "{}").unwrap();
+        std::fs::write(
+            journals.join("index.jsonl"),
+            concat!(
+                "{\"path\":\"j1.json\",\"project\":\"/work/a\",\"file\":\"README.md\"}\n",
+                "{\"path\":\"gone.json\",\"project\":\"/work/a\",\"file\":\"old.md\"}\n",
+                "{\"path\":\"j1.json\",\"project\":\"/work/b\",\"file\":\"other.md\"}\n",
+            ),
+        )
+        .unwrap();
+        assert_eq!(pending_journal_files(&journals, "/work/a"), vec!["README.md"]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,389 @@
+/// Python Env - Where the Python interpreter and vibe.py are, decided once
+///
+/// Every command that runs python (tasks, workflows, `context`, `stats`,
+/// skill scripts, the environment check) takes the interpreter and vibe.py
+/// from the `PythonEnv` resolved at startup and held in managed state, instead
+/// of guessing from the current directory, which in a packaged app is
+/// wherever it was launched from. Each of the two is taken from, in order:
+/// the settings (`pythonPath`, see `python_interpreter`, and `vibePath`), the
+/// app's bundled resources (`python/` and vibe.py), or auto-detection
+/// (python3 or python on PATH; vibe.py in a checkout, see `backend_paths`). `set_python_env` stores a new
+/// choice only once the interpreter answers `--version` and vibe.py `--help`.
+/// Settings saved any other way re-resolve the env through `settings-changed`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{OnceLock, RwLock};
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::backend_paths::{self, SearchRoots, FOUND_IN_RESOURCES, VIBE_PY};
+use crate::child_env::{build_child_env, ChildContext};
+use crate::python_interpreter::{self, Interpreter};
+
+pub const FROM_SETTINGS: &str = "settings";
+pub const FROM_BUNDLED: &str = "bundled-resource";
+pub const FROM_AUTO_DETECT: &str = "auto-detect";
+
+/// Interpreters tried on PATH when none is configured or bundled
+const DETECTED: &[&str] = if cfg!(windows) { &["python", "py"] } else { &["python3", "python"] };
+
+/// Interpreters looked for in the bundled `python/` resource
+const BUNDLED: &[&str] = &["python.exe", "bin/python3", "bin/python"];
+
+static APP: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+/// Interpreter and vibe.py the app runs, and where each came from
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PythonEnv {
+    /// Full path of the interpreter (as written when it can't be found)
+    pub interpreter: String,
+    /// Options from `pythonPath`, passed before the script
+    pub interpreter_args: Vec<String>,
+    pub vibe_path: String,
+    /// `--version` output, None when the interpreter doesn't run
+    pub version: Option<String>,
+    /// "settings" | "bundled-resource" | "auto-detect"
+    pub interpreter_from: String,
+    pub vibe_path_from: String,
+    /// Why the interpreter can't be used
+    pub interpreter_error: Option<String>,
+    /// Why vibe.py can't be used
+    pub vibe_path_error: Option<String>,
+}
+
+/// Managed state: the env python commands run with
+pub struct PythonEnvState(RwLock<PythonEnv>);
+
+impl PythonEnvState {
+    fn get(&self) -> PythonEnv {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn set(&self, env: PythonEnv) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = env;
+    }
+}
+
+/// What the env is resolved from
+struct Sources {
+    python_path: String,
+    vibe_path: String,
+    roots: SearchRoots,
+}
+
+impl Sources {
+    fn new(python_path: &str, vibe_path: &str) -> Self {
+        Self {
+            python_path: python_path.to_string(),
+            vibe_path: vibe_path.to_string(),
+            roots: SearchRoots::current(),
+        }
+    }
+
+    /// The stored settings (detecting everything when they can't be read)
+    fn from_settings() -> Self {
+        match crate::settings::load() {
+            Ok(settings) => Self::new(&settings.python_path, &settings.vibe_path),
+            Err(_) => Self::new("", ""),
+        }
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// Interpreter, its options, where it came from and why it can't be used
+fn locate_interpreter(sources: &Sources) -> (String, Vec<String>, &'static str, Option<String>) {
+    if !sources.python_path.trim().is_empty() {
+        return match python_interpreter::parse(&sources.python_path) {
+            Ok(interpreter) => match interpreter.resolve() {
+                Ok(path) => (path_string(&path), interpreter.args, FROM_SETTINGS, None),
+                Err(e) => (interpreter.program, interpreter.args, FROM_SETTINGS, Some(e)),
+            },
+            Err(e) => (sources.python_path.clone(), Vec::new(), FROM_SETTINGS, Some(e)),
+        };
+    }
+
+    let bundled = sources.roots.resource_dir.as_ref().and_then(|dir| {
+        BUNDLED.iter().map(|name| dir.join("python").join(name)).find(|path| path.is_file())
+    });
+    if let Some(path) = bundled {
+        return (path_string(&path), Vec::new(), FROM_BUNDLED, None);
+    }
+
+    let detected = DETECTED.iter().find_map(|program| {
+        Interpreter { program: program.to_string(), args: Vec::new() }.resolve().ok()
+    });
+    match detected {
+        Some(path) => (path_string(&path), Vec::new(), FROM_AUTO_DETECT, None),
+        None => (
+            DETECTED[0].to_string(),
+            Vec::new(),
+            FROM_AUTO_DETECT,
+            Some(format!("No Python interpreter found on PATH (tried {}); set one in Settings", DETECTED.join(", "))),
+        ),
+    }
+}
+
+/// vibe.py, where it came from and why it can't be used
+fn locate_vibe(sources: &Sources) -> (PathBuf, &'static str, Option<String>) {
+    if !sources.vibe_path.trim().is_empty() {
+        let path = PathBuf::from(sources.vibe_path.trim());
+        let error = (!path.is_file()).then(|| format!("{} not found at {} (vibePath in Settings)", VIBE_PY.name, path.display()));
+        return (path, FROM_SETTINGS, error);
+    }
+
+    let located = backend_paths::locate_in(VIBE_PY, &sources.roots);
+    match located.path {
+        Some(path) if located.found_in.as_deref() == Some(FOUND_IN_RESOURCES) => (PathBuf::from(path), FROM_BUNDLED, None),
+        Some(path) => (PathBuf::from(path), FROM_AUTO_DETECT, None),
+        None => (
+            PathBuf::from(VIBE_PY.path),
+            FROM_AUTO_DETECT,
+            Some(format!(
+                "{} not found in the app's resources or a checkout; set its location in Settings (looked in {})",
+                VIBE_PY.name,
+                located.searched.join("; ")
+            )),
+        ),
+    }
+}
+
+/// The env `sources` describe, without running anything
+fn locate(sources: &Sources) -> PythonEnv {
+    let (interpreter, interpreter_args, interpreter_from, interpreter_error) = locate_interpreter(sources);
+    let (vibe_path, vibe_path_from, vibe_path_error) = locate_vibe(sources);
+    PythonEnv {
+        interpreter,
+        interpreter_args,
+        vibe_path: path_string(&vibe_path),
+        version: None,
+        interpreter_from: interpreter_from.to_string(),
+        vibe_path_from: vibe_path_from.to_string(),
+        interpreter_error,
+        vibe_path_error,
+    }
+}
+
+/// The env `sources` describe, with the interpreter's version
+fn resolve(sources: &Sources) -> PythonEnv {
+    let mut env = locate(sources);
+    match env.run_version() {
+        Ok(version) => env.version = Some(version),
+        Err(e) => env.interpreter_error = env.interpreter_error.take().or(Some(e)),
+    }
+    env
+}
+
+impl PythonEnv {
+    /// A command running the interpreter with its options
+    pub fn command(&self) -> Result<Command, String> {
+        if let Some(e) = &self.interpreter_error {
+            return Err(e.clone());
+        }
+        let mut cmd = Command::new(&self.interpreter);
+        cmd.args(&self.interpreter_args);
+        Ok(cmd)
+    }
+
+    pub fn vibe_path(&self) -> PathBuf {
+        PathBuf::from(&self.vibe_path)
+    }
+
+    /// `--version` of the interpreter (Python 2 printed it to stderr)
+    fn run_version(&self) -> Result<String, String> {
+        let mut cmd = self.command()?;
+        let output = build_child_env(&ChildContext::Task)
+            .apply(&mut cmd)
+            .arg("--version")
+            .output()
+            .map_err(|e| format!("Failed to execute Python: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("Python error: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        let version = if output.stdout.is_empty() { output.stderr } else { output.stdout };
+        Ok(String::from_utf8_lossy(&version).trim().to_string())
+    }
+
+    /// Check that the interpreter runs and can load vibe.py (`--help` imports
+    /// its dependencies), returning the interpreter's version
+    pub fn probe(&self) -> Result<String, String> {
+        let version = self.run_version()?;
+        if let Some(e) = &self.vibe_path_error {
+            return Err(e.clone());
+        }
+
+        let vibe_path = self.vibe_path();
+        let mut cmd = self.command()?;
+        build_child_env(&ChildContext::Task).apply(&mut cmd).arg(&vibe_path).arg("--help");
+        if let Some(parent) = vibe_path.parent() {
+            cmd.current_dir(parent);
+        }
+        let output = cmd.output().map_err(|e| format!("Failed to execute Python: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{} cannot run {}: {}",
+                version,
+                vibe_path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(version)
+    }
+
+    /// One line for the startup status
+    fn summary(&self) -> Result<String, String> {
+        let errors: Vec<&str> = [&self.interpreter_error, &self.vibe_path_error].into_iter().flatten().map(String::as_str).collect();
+        if !errors.is_empty() {
+            return Err(errors.join("; "));
+        }
+        Ok(format!(
+            "{} ({}), {} ({})",
+            self.version.as_deref().unwrap_or(&self.interpreter),
+            self.interpreter_from,
+            self.vibe_path,
+            self.vibe_path_from
+        ))
+    }
+}
+
+/// The env python commands run with (located on the spot before startup)
+pub fn current() -> PythonEnv {
+    match APP.get() {
+        Some(app) => app.state::<PythonEnvState>().get(),
+        None => locate(&Sources::from_settings()),
+    }
+}
+
+/// A command running the current interpreter
+pub fn command() -> Result<Command, String> {
+    current().command()
+}
+
+/// The current vibe.py
+pub fn vibe_path() -> PathBuf {
+    current().vibe_path()
+}
+
+/// Directory of the current vibe.py, which holds the app's own workflows and skills
+pub fn vibe_dir() -> PathBuf {
+    vibe_path().parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// Check a `pythonPath` as `set_python_env` would, with the current vibe.py
+pub fn probe_python_path(python_path: &str) -> Result<(String, PathBuf), String> {
+    let env = locate(&Sources::new(python_path, &Sources::from_settings().vibe_path));
+    Ok((env.probe()?, env.vibe_path()))
+}
+
+/// Resolve the env, keep it in managed state, and re-resolve it when the
+/// settings change (called once at startup); the result describes it
+pub fn init(app: &tauri::AppHandle) -> Result<Option<String>, String> {
+    let env = resolve(&Sources::from_settings());
+    let summary = env.summary();
+    app.manage(PythonEnvState(RwLock::new(env)));
+    let _ = APP.set(app.clone());
+
+    crate::settings_events::listen(&["/pythonPath", "/vibePath"], |change| {
+        let env = resolve(&Sources::new(&change.settings.python_path, &change.settings.vibe_path));
+        if let Some(app) = APP.get() {
+            app.state::<PythonEnvState>().set(env);
+        }
+    });
+    summary.map(Some)
+}
+
+/// The interpreter and vibe.py commands run with
+#[tauri::command]
+pub async fn get_python_env(state: tauri::State<'_, PythonEnvState>) -> Result<PythonEnv, String> {
+    crate::command_metrics::timed("get_python_env", async move {
+        Ok(state.get())
+    }).await
+}
+
+/// Use `interpreter` (a `pythonPath` command line) and `vibe_path`, empty to
+/// detect them, once they pass the `--version` and `--help` probes
+#[tauri::command]
+pub async fn set_python_env(
+    state: tauri::State<'_, PythonEnvState>,
+    interpreter: String,
+    vibe_path: String,
+) -> Result<PythonEnv, String> {
+    crate::command_metrics::timed("set_python_env", async move {
+        let (interpreter, vibe_path) = (interpreter.trim().to_string(), vibe_path.trim().to_string());
+        let mut env = locate(&Sources::new(&interpreter, &vibe_path));
+        let result = env.probe().and_then(|version| {
+            env.version = Some(version);
+            let mut settings = crate::settings::load().map_err(|e| e.message)?;
+            settings.python_path = interpreter.clone();
+            settings.vibe_path = vibe_path.clone();
+            crate::settings::save(settings).map_err(|e| e.message)
+        });
+        crate::audit::record(
+            "set_python_env",
+            serde_json::json!({ "interpreter": interpreter, "vibe_path": vibe_path }),
+            crate::audit::ORIGIN_COMMAND,
+            &result,
+        );
+        result?;
+
+        state.set(env.clone());
+        Ok(env)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_then_bundled_then_detected() {
+        let root = std::env::temp_dir().join(format!("vibecode-python-env-{}", uuid::Uuid::new_v4()));
+        let (resources, repo) = (root.join("resources"), root.join("repo"));
+        let launched_in = repo.join("desktop-app").join("src-tauri");
+        std::fs::create_dir_all(resources.join("python").join("bin")).unwrap();
+        std::fs::create_dir_all(&launched_in).unwrap();
+        std::fs::write(repo.join(VIBE_PY.path), "").unwrap();
+        let sources = |python_path: &str, vibe_path: &str, resource_dir: Option<&Path>| Sources {
+            python_path: python_path.to_string(),
+            vibe_path: vibe_path.to_string(),
+            roots: SearchRoots {
+                resource_dir: resource_dir.map(Path::to_path_buf),
+                checkout_from: vec![launched_in.clone()],
+            },
+        };
+
+        // Nothing bundled: vibe.py is found above the directory the app runs in
+        let env = locate(&sources("", "", Some(&resources)));
+        assert_eq!((env.vibe_path_from.as_str(), env.vibe_path_error.as_ref()), (FROM_AUTO_DETECT, None));
+        assert_eq!(env.vibe_path(), repo.join(VIBE_PY.path));
+        assert_eq!(env.interpreter_from, FROM_AUTO_DETECT);
+
+        std::fs::write(resources.join(VIBE_PY.path), "").unwrap();
+        std::fs::write(resources.join("python").join("bin").join("python3"), "").unwrap();
+        let env = locate(&sources("", "", Some(&resources)));
+        assert_eq!((env.interpreter_from.as_str(), env.vibe_path_from.as_str()), (FROM_BUNDLED, FROM_BUNDLED));
+        assert_eq!(PathBuf::from(&env.interpreter), resources.join("python").join("bin").join("python3"));
+        assert_eq!(env.vibe_path(), resources.join(VIBE_PY.path));
+
+        // Settings win, and what they name must exist
+        let configured = path_string(&repo.join(VIBE_PY.path));
+        let env = locate(&sources("vibecode-no-such-python -X utf8", &configured, Some(&resources)));
+        assert_eq!((env.interpreter_from.as_str(), env.vibe_path_from.as_str()), (FROM_SETTINGS, FROM_SETTINGS));
+        assert_eq!((env.interpreter.as_str(), env.interpreter_args.clone()), ("vibecode-no-such-python", vec!["-X".to_string(), "utf8".to_string()]));
+        assert!(env.interpreter_error.as_ref().unwrap().contains("pythonPath"));
+        assert!(env.command().is_err());
+        assert_eq!(env.vibe_path, configured);
+        let env = locate(&sources("", &path_string(&root.join(VIBE_PY.path)), None));
+        assert!(env.vibe_path_error.unwrap().contains("vibePath"));
+
+        std::fs::remove_file(repo.join(VIBE_PY.path)).unwrap();
+        let env = locate(&sources("", "", None));
+        assert_eq!(env.vibe_path_from, FROM_AUTO_DETECT);
+        assert!(env.vibe_path_error.is_some());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
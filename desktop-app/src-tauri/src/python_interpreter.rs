@@ -0,0 +1,153 @@
+/// Python Interpreter - Reading the `pythonPath` setting
+///
+/// `pythonPath` holds a command line such as `python3`, `py -3` or
+/// `"C:\Program Files\Python312\python.exe" -X utf8`. The first word is the
+/// interpreter, later words are passed to it, and a trailing `.py` script is
+/// dropped (vibe.py is located by `python_env`).
+/// Quotes group words containing spaces; backslashes are literal so Windows
+/// paths need no escaping. `python_env` resolves the setting once, and an
+/// interpreter that can't be found is an error naming the setting instead of
+/// a bare "program not found".
+
+use std::path::{Path, PathBuf};
+
+/// Used when `pythonPath` is unset or blank
+pub const DEFAULT_INTERPRETER: &str = "python";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interpreter {
+    pub program: String,
+    /// Interpreter options (`-X utf8`, `-3` for the Windows launcher)
+    pub args: Vec<String>,
+}
+
+/// Split a command line into words, honoring single and double quotes
+pub fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if let Some(q) = quote {
+        return Err(format!("Unclosed {} in Python path: {}", q, line));
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Words back into a command line `split_words` reads the same way
+pub fn join_words(words: &[String]) -> String {
+    words
+        .iter()
+        .map(|word| {
+            if word.is_empty() || word.contains(|c: char| c.is_whitespace() || c == '\'' || c == '"') {
+                let quote = if word.contains('"') { '\'' } else { '"' };
+                format!("{}{}{}", quote, word, quote)
+            } else {
+                word.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse a `pythonPath` value
+pub fn parse(python_path: &str) -> Result<Interpreter, String> {
+    let mut words = split_words(python_path)?;
+    if words.last().is_some_and(|w| w.to_lowercase().ends_with(".py")) {
+        words.pop();
+    }
+    if words.is_empty() {
+        return Ok(Interpreter { program: DEFAULT_INTERPRETER.to_string(), args: Vec::new() });
+    }
+    let program = words.remove(0);
+    Ok(Interpreter { program, args: words })
+}
+
+/// Executable candidates for `name` inside `dir` (PATHEXT variants on Windows)
+fn candidates(dir: &Path, name: &str) -> Vec<PathBuf> {
+    let mut paths = vec![dir.join(name)];
+    if cfg!(windows) && Path::new(name).extension().is_none() {
+        let exts = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+        paths.extend(exts.split(';').filter(|e| !e.is_empty()).map(|ext| dir.join(format!("{}{}", name, ext))));
+    }
+    paths
+}
+
+impl Interpreter {
+    /// Full path of the interpreter, looked up on PATH unless it is a path itself
+    pub fn resolve(&self) -> Result<PathBuf, String> {
+        let program = Path::new(&self.program);
+        let found = if program.components().count() > 1 || program.is_absolute() {
+            candidates(program.parent().unwrap_or(Path::new("")), &self.program_name())
+                .into_iter()
+                .find(|p| p.is_file())
+        } else {
+            std::env::var_os("PATH").and_then(|path| {
+                std::env::split_paths(&path)
+                    .flat_map(|dir| candidates(&dir, &self.program))
+                    .find(|p| p.is_file())
+            })
+        };
+        found.ok_or_else(|| {
+            format!(
+                "Python interpreter '{}' not found. Check the Python path in Settings (pythonPath).",
+                self.program
+            )
+        })
+    }
+
+    fn program_name(&self) -> String {
+        Path::new(&self.program)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_handles_quotes_and_drops_the_script() {
+        assert_eq!(parse("python ../vibe.py").unwrap(), Interpreter { program: "python".into(), args: vec![] });
+        assert_eq!(parse("  ").unwrap().program, DEFAULT_INTERPRETER);
+        assert_eq!(
+            parse(r#""C:\Program Files\Python312\python.exe" -X utf8 "D:\my app\vibe.py""#).unwrap(),
+            Interpreter {
+                program: r"C:\Program Files\Python312\python.exe".into(),
+                args: vec!["-X".into(), "utf8".into()],
+            }
+        );
+        assert_eq!(parse("'/opt/my env/bin/python3'").unwrap().program, "/opt/my env/bin/python3");
+        assert!(parse("\"/usr/bin/python").is_err());
+        let words = split_words(r#""C:\Program Files\py.exe" -X utf8 'say "hi"'"#).unwrap();
+        assert_eq!(split_words(&join_words(&words)).unwrap(), words);
+
+        let missing = Interpreter { program: "vibecode-no-such-python".into(), args: vec![] };
+        assert!(missing.resolve().unwrap_err().contains("pythonPath"));
+    }
+}
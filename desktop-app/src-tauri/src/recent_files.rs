@@ -0,0 +1,144 @@
+/// Recent Files - Files opened in the viewer, per project
+///
+/// Every file `read_file_content` returns is moved to the front of the open
+/// project's list, which keeps the 50 most recent. The lists live in
+/// recent-files.json in the config dir, keyed by project path, so switching
+/// projects switches lists. Files opened outside the project (or with none
+/// open) are not recorded. Entries whose files are gone are dropped when the
+/// list is read.
+
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::instance_lock;
+
+const RECENT_FILE: &str = "recent-files.json";
+
+/// Entries kept per project
+const MAX_RECENT: usize = 50;
+
+/// Entry of `get_recent_files`, most recent first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecentFile {
+    pub path: String,
+    /// RFC 3339
+    pub opened_at: String,
+}
+
+fn store_path() -> PathBuf {
+    crate::get_app_config_dir().join(RECENT_FILE)
+}
+
+fn current_project() -> Option<String> {
+    crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone())
+}
+
+fn list_in(store: &Value, project: &str) -> Vec<RecentFile> {
+    store
+        .get(project)
+        .and_then(|list| serde_json::from_value(list.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn set_list(store: &mut Value, project: &str, list: Vec<RecentFile>) {
+    if !store.is_object() {
+        *store = serde_json::json!({});
+    }
+    if let Some(store) = store.as_object_mut() {
+        if list.is_empty() {
+            store.remove(project);
+        } else {
+            store.insert(project.to_string(), serde_json::json!(list));
+        }
+    }
+}
+
+/// `list` with `path` moved (or added) to the front, at most `MAX_RECENT` long
+fn push(mut list: Vec<RecentFile>, path: &str, opened_at: String) -> Vec<RecentFile> {
+    list.retain(|entry| entry.path != path);
+    list.insert(0, RecentFile { path: path.to_string(), opened_at });
+    list.truncate(MAX_RECENT);
+    list
+}
+
+/// Record that `path` was opened (failures are only logged)
+pub fn record(path: &Path) {
+    let Some(project) = current_project() else { return };
+    if !path.starts_with(&project) {
+        return;
+    }
+    let path = path.to_string_lossy().to_string();
+    let result = instance_lock::update_json_file(&store_path(), |store| {
+        let list = push(list_in(store, &project), &path, chrono::Utc::now().to_rfc3339());
+        set_list(store, &project, list);
+        Ok(())
+    });
+    if let Err(e) = result {
+        eprintln!("Recent files: failed to record {}: {}", path, e);
+    }
+}
+
+/// Files recently opened in the current project, most recent first
+#[tauri::command]
+pub async fn get_recent_files() -> Result<Vec<RecentFile>, String> {
+    crate::command_metrics::timed("get_recent_files", async move {
+        let Some(project) = current_project() else { return Ok(Vec::new()) };
+        let store: Value = std::fs::read_to_string(store_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let list = list_in(&store, &project);
+        if list.iter().all(|entry| Path::new(&entry.path).exists()) {
+            return Ok(list);
+        }
+
+        // Prune under the lock, against the list as it is now
+        instance_lock::update_json_file(&store_path(), |store| {
+            let mut list = list_in(store, &project);
+            list.retain(|entry| Path::new(&entry.path).exists());
+            set_list(store, &project, list.clone());
+            Ok(list)
+        })
+    }).await
+}
+
+/// Forget the current project's recent files
+#[tauri::command]
+pub async fn clear_recent_files() -> Result<(), String> {
+    crate::command_metrics::timed("clear_recent_files", async move {
+        let Some(project) = current_project() else { return Ok(()) };
+        instance_lock::update_json_file(&store_path(), |store| {
+            set_list(store, &project, Vec::new());
+            Ok(())
+        })
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_are_per_project_deduplicated_and_capped() {
+        let mut store = Value::Null;
+        let mut list = Vec::new();
+        for i in 0..60 {
+            list = push(list, &format!("/a/file-{}.rs", i), format!("t{}", i));
+        }
+        list = push(list, "/a/file-55.rs", "later".to_string());
+        assert_eq!(list.len(), MAX_RECENT);
+        assert_eq!(list[0], RecentFile { path: "/a/file-55.rs".to_string(), opened_at: "later".to_string() });
+        assert_eq!(list.iter().filter(|e| e.path == "/a/file-55.rs").count(), 1);
+        assert_eq!(list.last().map(|e| e.path.as_str()), Some("/a/file-10.rs"));
+
+        set_list(&mut store, "/a", list);
+        set_list(&mut store, "/b", push(Vec::new(), "/b/main.rs", "t".to_string()));
+        assert_eq!(list_in(&store, "/a").len(), MAX_RECENT);
+        assert_eq!(list_in(&store, "/b").len(), 1);
+        assert!(list_in(&store, "/c").is_empty());
+
+        set_list(&mut store, "/a", Vec::new());
+        assert!(store.get("/a").is_none() && store.get("/b").is_some());
+    }
+}
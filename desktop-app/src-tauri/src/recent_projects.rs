@@ -0,0 +1,193 @@
+/// Recent Projects - Projects opened before, for switching back quickly
+///
+/// Every project switch (`set_project_path`, `open_project_dialog`) moves the
+/// project to the front of `recent_projects` in config.json, next to
+/// `last_project`, which the list is seeded from when it doesn't exist yet.
+/// Entries are unique by path. Pinned entries come first and are never
+/// pushed out; of the rest the 20 most recent are kept. Projects whose
+/// directories are gone stay in the list flagged `missing`, so that the user
+/// removes them (`remove_recent_project`) instead of seeing them vanish.
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::instance_lock;
+
+const RECENT_KEY: &str = "recent_projects";
+
+/// Unpinned entries kept
+const MAX_RECENT: usize = 20;
+
+/// Entry of `get_recent_projects`, pinned first, then most recent first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecentProject {
+    pub path: String,
+    /// Directory name shown in the project picker
+    pub name: String,
+    /// RFC 3339 (None for the project carried over from `last_project`)
+    pub last_opened: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    /// The directory no longer exists (computed when listed, ignored when read back)
+    #[serde(default, skip_deserializing)]
+    pub missing: bool,
+}
+
+impl RecentProject {
+    fn new(path: &str, last_opened: Option<String>) -> Self {
+        let name = Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        Self { path: path.to_string(), name, last_opened, pinned: false, missing: false }
+    }
+}
+
+/// The stored list (seeded from `last_project` when there is none yet)
+fn list_in(config: &Value) -> Vec<RecentProject> {
+    match config.get(RECENT_KEY) {
+        Some(list) => serde_json::from_value(list.clone()).unwrap_or_default(),
+        None => config
+            .get("last_project")
+            .and_then(Value::as_str)
+            .map(|path| vec![RecentProject::new(path, None)])
+            .unwrap_or_default(),
+    }
+}
+
+fn set_list(config: &mut Value, list: &[RecentProject]) {
+    if !config.is_object() {
+        *config = serde_json::json!({});
+    }
+    config[RECENT_KEY] = serde_json::json!(list);
+}
+
+/// Pinned entries first (each group in its stored order)
+fn ordered(mut list: Vec<RecentProject>) -> Vec<RecentProject> {
+    list.sort_by_key(|entry| !entry.pinned);
+    list
+}
+
+/// `list` with `path` moved (or added) to the front, keeping its pin; the
+/// oldest unpinned entries beyond `MAX_RECENT` are dropped
+fn push(mut list: Vec<RecentProject>, path: &str, opened_at: String) -> Vec<RecentProject> {
+    let pinned = list.iter().any(|entry| entry.path == path && entry.pinned);
+    list.retain(|entry| entry.path != path);
+    let mut entry = RecentProject::new(path, Some(opened_at));
+    entry.pinned = pinned;
+    list.insert(0, entry);
+
+    let mut unpinned = 0;
+    list.retain(|entry| {
+        unpinned += usize::from(!entry.pinned);
+        entry.pinned || unpinned <= MAX_RECENT
+    });
+    list
+}
+
+/// Record in `config` that the project at `path` was opened
+pub fn record_in(config: &mut Value, path: &str) {
+    let list = push(list_in(config), path, chrono::Utc::now().to_rfc3339());
+    set_list(config, &list);
+}
+
+/// Change the stored list, returning it as `get_recent_projects` would
+fn update(change: impl FnOnce(&mut Vec<RecentProject>) -> Result<(), String>) -> Result<Vec<RecentProject>, String> {
+    let list = instance_lock::update_json_file(&crate::get_config_path(), |config| {
+        let mut list = list_in(config);
+        change(&mut list)?;
+        set_list(config, &list);
+        Ok(list)
+    })
+    .map_err(|e| format!("Failed to save config: {}", e))?;
+    Ok(flag_missing(ordered(list)))
+}
+
+fn flag_missing(mut list: Vec<RecentProject>) -> Vec<RecentProject> {
+    for entry in &mut list {
+        entry.missing = !Path::new(&entry.path).is_dir();
+    }
+    list
+}
+
+fn find<'a>(list: &'a mut [RecentProject], path: &str) -> Result<&'a mut RecentProject, String> {
+    list.iter_mut()
+        .find(|entry| entry.path == path)
+        .ok_or_else(|| format!("Not a recent project: {}", path))
+}
+
+/// Recently opened projects, pinned first, missing directories flagged
+#[tauri::command]
+pub async fn get_recent_projects() -> Result<Vec<RecentProject>, String> {
+    crate::command_metrics::timed("get_recent_projects", async move {
+        let config: Value = std::fs::read_to_string(crate::get_config_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Ok(flag_missing(ordered(list_in(&config))))
+    }).await
+}
+
+/// Pin a recent project to the top of the list (`pinned: false` unpins it)
+#[tauri::command]
+pub async fn pin_project(path: String, pinned: Option<bool>) -> Result<Vec<RecentProject>, String> {
+    crate::command_metrics::timed("pin_project", async move {
+        update(|list| {
+            find(list, &path)?.pinned = pinned.unwrap_or(true);
+            Ok(())
+        })
+    }).await
+}
+
+/// Remove a project from the recent list (its directory is left alone)
+#[tauri::command]
+pub async fn remove_recent_project(path: String) -> Result<Vec<RecentProject>, String> {
+    crate::command_metrics::timed("remove_recent_project", async move {
+        update(|list| {
+            find(list, &path)?;
+            list.retain(|entry| entry.path != path);
+            Ok(())
+        })
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_is_deduplicated_pinned_first_and_capped() {
+        let mut config = serde_json::json!({ "last_project": "/projects/alpha" });
+        assert_eq!(list_in(&config), vec![RecentProject::new("/projects/alpha", None)]);
+        assert_eq!(list_in(&config)[0].name, "alpha");
+
+        record_in(&mut config, "/projects/beta");
+        record_in(&mut config, "/projects/alpha");
+        let list = list_in(&config);
+        assert_eq!(list.iter().map(|e| e.path.as_str()).collect::<Vec<_>>(), vec!["/projects/alpha", "/projects/beta"]);
+        assert!(list.iter().all(|e| e.last_opened.is_some()));
+
+        let mut list = list;
+        find(&mut list, "/projects/beta").unwrap().pinned = true;
+        for i in 0..30 {
+            list = push(list, &format!("/projects/p{}", i), format!("t{}", i));
+        }
+        list = push(list, "/projects/beta", "later".to_string());
+        assert_eq!(list.len(), MAX_RECENT + 1);
+        assert_eq!((list[0].path.as_str(), list[0].pinned), ("/projects/beta", true));
+        assert_eq!(list.last().map(|e| e.path.as_str()), Some("/projects/p10"));
+        assert!(find(&mut list, "/projects/alpha").is_err());
+
+        let list = ordered(push(list, "/projects/p29", "now".to_string()));
+        assert_eq!(list[0].path, "/projects/beta");
+        assert_eq!(list[1].path, "/projects/p29");
+
+        let dir = std::env::temp_dir().join(format!("vibecode-recent-projects-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.to_string_lossy().to_string();
+        let flagged = flag_missing(vec![RecentProject::new(&path, None), RecentProject::new(&format!("{}-gone", path), None)]);
+        assert_eq!(flagged.iter().map(|e| e.missing).collect::<Vec<_>>(), vec![false, true]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,564 @@
+/// Retention - Size- and age-based pruning of app-generated artifacts
+///
+/// Every artifact class (task output, workflow run logs, snapshots, autosave
+/// journals, quota history) lives in its own folder under
+/// `<config>/artifacts/<class>/`. Each top-level file or folder in a class
+/// directory is one artifact. A class directory may carry an `index.jsonl`
+/// whose entries reference artifacts by `path`; entries flagged
+/// `"pinned": true` protect their artifact from pruning.
+///
+/// Pruning is crash-safe: artifact files are deleted first and index entries
+/// whose artifact no longer exists are dropped afterwards, so an interrupted
+/// run is simply completed by the next one.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+
+const INDEX_FILE: &str = "index.jsonl";
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const MB: u64 = 1024 * 1024;
+
+/// Classes of app-generated artifacts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArtifactClass {
+    TaskOutput,
+    WorkflowRuns,
+    Snapshots,
+    Journals,
+    QuotaHistory,
+}
+
+impl ArtifactClass {
+    pub const ALL: [ArtifactClass; 5] = [
+        ArtifactClass::TaskOutput,
+        ArtifactClass::WorkflowRuns,
+        ArtifactClass::Snapshots,
+        ArtifactClass::Journals,
+        ArtifactClass::QuotaHistory,
+    ];
+
+    /// Folder name under the artifacts root
+    pub fn dir_name(&self) -> &'static str {
+        match self {
+            ArtifactClass::TaskOutput => "task-output",
+            ArtifactClass::WorkflowRuns => "workflow-runs",
+            ArtifactClass::Snapshots => "snapshots",
+            ArtifactClass::Journals => "journals",
+            ArtifactClass::QuotaHistory => "quota-history",
+        }
+    }
+}
+
+/// Limits for one artifact class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    pub max_total_bytes: u64,
+    pub max_age_days: u64,
+}
+
+impl RetentionPolicy {
+    const fn new(max_total_mb: u64, max_age_days: u64) -> Self {
+        Self {
+            max_total_bytes: max_total_mb * MB,
+            max_age_days,
+        }
+    }
+}
+
+/// `retention` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionSettings {
+    pub task_output: RetentionPolicy,
+    pub workflow_runs: RetentionPolicy,
+    pub snapshots: RetentionPolicy,
+    pub journals: RetentionPolicy,
+    pub quota_history: RetentionPolicy,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            task_output: RetentionPolicy::new(200, 30),
+            workflow_runs: RetentionPolicy::new(50, 90),
+            snapshots: RetentionPolicy::new(1024, 14),
+            journals: RetentionPolicy::new(20, 7),
+            quota_history: RetentionPolicy::new(20, 180),
+        }
+    }
+}
+
+impl RetentionSettings {
+    pub fn policy(&self, class: ArtifactClass) -> &RetentionPolicy {
+        match class {
+            ArtifactClass::TaskOutput => &self.task_output,
+            ArtifactClass::WorkflowRuns => &self.workflow_runs,
+            ArtifactClass::Snapshots => &self.snapshots,
+            ArtifactClass::Journals => &self.journals,
+            ArtifactClass::QuotaHistory => &self.quota_history,
+        }
+    }
+
+    /// Load the retention block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("retention").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Root folder holding all artifact classes
+pub fn artifacts_root() -> PathBuf {
+    crate::get_app_config_dir().join("artifacts")
+}
+
+/// Folder for one artifact class
+pub fn artifact_dir(class: ArtifactClass) -> PathBuf {
+    artifacts_root().join(class.dir_name())
+}
+
+/// One artifact found on disk
+#[derive(Debug, Clone)]
+struct Artifact {
+    path: PathBuf,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+/// Artifact selected for deletion
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedDeletion {
+    pub class: ArtifactClass,
+    pub path: String,
+    pub bytes: u64,
+    pub reason: String, // "age" | "size"
+}
+
+/// Storage usage of one artifact class
+#[derive(Debug, Serialize)]
+pub struct ClassUsage {
+    pub class: ArtifactClass,
+    pub path: String,
+    pub bytes: u64,
+    pub artifact_count: usize,
+    pub max_total_bytes: u64,
+    pub max_age_days: u64,
+}
+
+/// Response of `get_storage_usage`
+#[derive(Debug, Serialize)]
+pub struct StorageUsage {
+    pub total_bytes: u64,
+    pub classes: Vec<ClassUsage>,
+}
+
+/// Response of `run_cleanup`
+#[derive(Debug, Serialize)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    pub deleted: Vec<PlannedDeletion>,
+    pub freed_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+/// Recursive size of a file or folder
+fn path_size(path: &Path) -> u64 {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(m) => m,
+        Err(_) => return 0,
+    };
+
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| path_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// List the artifacts of a class directory (oldest first)
+fn scan_artifacts(dir: &Path) -> Vec<Artifact> {
+    let mut artifacts: Vec<Artifact> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|e| e.file_name() != INDEX_FILE)
+            .filter_map(|e| {
+                let path = e.path();
+                let modified = e.metadata().ok()?.modified().ok()?;
+                Some(Artifact {
+                    bytes: path_size(&path),
+                    path,
+                    modified,
+                })
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    artifacts.sort_by(|a, b| a.modified.cmp(&b.modified).then_with(|| a.path.cmp(&b.path)));
+    artifacts
+}
+
+/// Artifacts referenced by pinned index entries of a class directory
+fn pinned_artifacts(dir: &Path) -> HashSet<PathBuf> {
+    let content = std::fs::read_to_string(dir.join(INDEX_FILE)).unwrap_or_default();
+
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|entry| entry["pinned"].as_bool().unwrap_or(false))
+        .filter_map(|entry| entry["path"].as_str().map(|p| dir.join(p)))
+        .collect()
+}
+
+/// Decide which artifacts of one class to delete
+///
+/// Everything older than `max_age_days` goes first, then the oldest remaining
+/// artifacts until the class fits in `max_total_bytes`. Pinned artifacts are
+/// never selected (but still count towards the size).
+fn plan_class(class: ArtifactClass, dir: &Path, policy: &RetentionPolicy, now: SystemTime) -> Vec<PlannedDeletion> {
+    let artifacts = scan_artifacts(dir);
+    let pinned = pinned_artifacts(dir);
+    let max_age = Duration::from_secs(policy.max_age_days * 24 * 60 * 60);
+
+    let mut planned = Vec::new();
+    let mut remaining_bytes: u64 = artifacts.iter().map(|a| a.bytes).sum();
+    let mut kept = Vec::new();
+
+    for artifact in artifacts {
+        let age = now.duration_since(artifact.modified).unwrap_or_default();
+        if age > max_age && !pinned.contains(&artifact.path) {
+            remaining_bytes -= artifact.bytes;
+            planned.push(PlannedDeletion {
+                class,
+                path: artifact.path.to_string_lossy().to_string(),
+                bytes: artifact.bytes,
+                reason: "age".to_string(),
+            });
+        } else {
+            kept.push(artifact);
+        }
+    }
+
+    for artifact in kept {
+        if remaining_bytes <= policy.max_total_bytes {
+            break;
+        }
+        if pinned.contains(&artifact.path) {
+            continue;
+        }
+        remaining_bytes -= artifact.bytes;
+        planned.push(PlannedDeletion {
+            class,
+            path: artifact.path.to_string_lossy().to_string(),
+            bytes: artifact.bytes,
+            reason: "size".to_string(),
+        });
+    }
+
+    planned
+}
+
+/// Drop index entries whose artifact no longer exists
+fn compact_index(dir: &Path) -> Result<(), String> {
+    let index_path = dir.join(INDEX_FILE);
+    let content = match std::fs::read_to_string(&index_path) {
+        Ok(c) => c,
+        Err(_) => return Ok(()),
+    };
+
+    let kept: Vec<&str> = content
+        .lines()
+        .filter(|line| {
+            // Keep lines we don't understand rather than losing data
+            match serde_json::from_str::<serde_json::Value>(line) {
+                Ok(entry) => match entry["path"].as_str() {
+                    Some(p) => dir.join(p).exists(),
+                    None => true,
+                },
+                Err(_) => !line.trim().is_empty(),
+            }
+        })
+        .collect();
+
+    // Write to a temp file and rename so a crash never leaves a torn index
+    let tmp_path = dir.join(format!("{}.tmp", INDEX_FILE));
+    let mut file = std::fs::File::create(&tmp_path)
+        .map_err(|e| format!("Failed to write index: {}", e))?;
+    for line in kept {
+        writeln!(file, "{}", line).map_err(|e| format!("Failed to write index: {}", e))?;
+    }
+    drop(file);
+
+    std::fs::rename(&tmp_path, &index_path)
+        .map_err(|e| format!("Failed to replace index: {}", e))
+}
+
+/// Plan (and unless `dry_run`, apply) cleanup of every class under `root`
+fn cleanup(root: &Path, settings: &RetentionSettings, now: SystemTime, dry_run: bool) -> CleanupReport {
+    let mut deleted = Vec::new();
+    let mut errors = Vec::new();
+
+    for class in ArtifactClass::ALL {
+        let dir = root.join(class.dir_name());
+        if !dir.exists() {
+            continue;
+        }
+
+        for planned in plan_class(class, &dir, settings.policy(class), now) {
+            if !dry_run {
+                let path = PathBuf::from(&planned.path);
+                let result = if path.is_dir() {
+                    std::fs::remove_dir_all(&path)
+                } else {
+                    std::fs::remove_file(&path)
+                };
+                if let Err(e) = result {
+                    // Already gone (e.g. interrupted prior run) is fine
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        errors.push(format!("Failed to delete {}: {}", planned.path, e));
+                        continue;
+                    }
+                }
+            }
+            deleted.push(planned);
+        }
+
+        if !dry_run {
+            if let Err(e) = compact_index(&dir) {
+                errors.push(format!("{}: {}", class.dir_name(), e));
+            }
+        }
+    }
+
+    CleanupReport {
+        dry_run,
+        freed_bytes: deleted.iter().map(|d| d.bytes).sum(),
+        deleted,
+        errors,
+    }
+}
+
+/// Run one maintenance pass with the configured settings
+pub fn run_maintenance() -> CleanupReport {
+    cleanup(&artifacts_root(), &RetentionSettings::load(), SystemTime::now(), false)
+}
+
+/// Audit a purge that actually deleted something or failed
+fn audit_cleanup(report: &CleanupReport, origin: &str) {
+    if report.dry_run || (report.deleted.is_empty() && report.errors.is_empty()) {
+        return;
+    }
+    let args = serde_json::json!({
+        "deleted": report.deleted.len(),
+        "freed_bytes": report.freed_bytes,
+    });
+    let result = if report.errors.is_empty() { Ok(()) } else { Err(report.errors.join("; ")) };
+    crate::audit::record("run_cleanup", args, origin, &result);
+}
+
+/// Prune on startup and then once a day
+pub fn spawn_maintenance() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            let report = tauri::async_runtime::spawn_blocking(run_maintenance).await;
+            match report {
+                Ok(report) => {
+                    audit_cleanup(&report, crate::audit::ORIGIN_SCHEDULED);
+                    if !report.deleted.is_empty() {
+                        eprintln!("Retention: pruned {} artifacts ({} bytes)", report.deleted.len(), report.freed_bytes);
+                    }
+                    for error in report.errors {
+                        eprintln!("Retention: {}", error);
+                    }
+                }
+                Err(e) => eprintln!("Retention: maintenance task failed: {}", e),
+            }
+
+            // The audit log lives outside the artifact classes and is only
+            // trimmed once it exceeds its minimum retained size
+            for segment in crate::audit::prune() {
+                eprintln!("Retention: pruned audit segment {}", segment.display());
+            }
+            tokio::time::sleep(MAINTENANCE_INTERVAL).await;
+        }
+    });
+}
+
+/// Get per-class storage usage of app-generated artifacts
+#[tauri::command]
+pub async fn get_storage_usage() -> Result<StorageUsage, String> {
+    crate::command_metrics::timed("get_storage_usage", async move {
+        let settings = RetentionSettings::load();
+
+        let classes: Vec<ClassUsage> = ArtifactClass::ALL
+            .iter()
+            .map(|&class| {
+                let dir = artifact_dir(class);
+                let artifacts = scan_artifacts(&dir);
+                let policy = settings.policy(class);
+                ClassUsage {
+                    class,
+                    path: dir.to_string_lossy().to_string(),
+                    bytes: artifacts.iter().map(|a| a.bytes).sum(),
+                    artifact_count: artifacts.len(),
+                    max_total_bytes: policy.max_total_bytes,
+                    max_age_days: policy.max_age_days,
+                }
+            })
+            .collect();
+
+        Ok(StorageUsage {
+            total_bytes: classes.iter().map(|c| c.bytes).sum(),
+            classes,
+        })
+    }).await
+}
+
+/// Prune artifacts now, or report what would be pruned when `dry_run` is set
+#[tauri::command]
+pub async fn run_cleanup(dry_run: bool) -> Result<CleanupReport, String> {
+    crate::command_metrics::timed("run_cleanup", async move {
+        let settings = RetentionSettings::load();
+        let report = cleanup(&artifacts_root(), &settings, SystemTime::now(), dry_run);
+        audit_cleanup(&report, crate::audit::ORIGIN_COMMAND);
+        Ok(report)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn temp_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("vibecode-retention-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn write_artifact(dir: &Path, name: &str, bytes: usize, age_days: u64) -> PathBuf {
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, vec![b'x'; bytes]).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - DAY * age_days as u32).unwrap();
+        path
+    }
+
+    fn settings_with(class_policy: RetentionPolicy) -> RetentionSettings {
+        RetentionSettings {
+            task_output: class_policy,
+            ..RetentionSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_prunes_expired_artifacts() {
+        let root = temp_root();
+        let dir = root.join(ArtifactClass::TaskOutput.dir_name());
+        let old = write_artifact(&dir, "old.log", 10, 40);
+        let fresh = write_artifact(&dir, "fresh.log", 10, 1);
+
+        let settings = settings_with(RetentionPolicy { max_total_bytes: MB, max_age_days: 30 });
+        let report = cleanup(&root, &settings, SystemTime::now(), false);
+
+        assert_eq!(report.deleted.len(), 1);
+        assert_eq!(report.deleted[0].reason, "age");
+        assert!(!old.exists());
+        assert!(fresh.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_prunes_oldest_first_until_under_size_limit() {
+        let root = temp_root();
+        let dir = root.join(ArtifactClass::TaskOutput.dir_name());
+        let oldest = write_artifact(&dir, "a.log", 100, 3);
+        let middle = write_artifact(&dir, "b.log", 100, 2);
+        let newest = write_artifact(&dir, "c.log", 100, 1);
+
+        let settings = settings_with(RetentionPolicy { max_total_bytes: 150, max_age_days: 30 });
+        let report = cleanup(&root, &settings, SystemTime::now(), false);
+
+        assert_eq!(report.freed_bytes, 200);
+        assert!(!oldest.exists());
+        assert!(!middle.exists());
+        assert!(newest.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_dry_run_deletes_nothing() {
+        let root = temp_root();
+        let dir = root.join(ArtifactClass::TaskOutput.dir_name());
+        let old = write_artifact(&dir, "old.log", 10, 40);
+
+        let settings = settings_with(RetentionPolicy { max_total_bytes: MB, max_age_days: 30 });
+        let report = cleanup(&root, &settings, SystemTime::now(), true);
+
+        assert!(report.dry_run);
+        assert_eq!(report.deleted.len(), 1);
+        assert!(old.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_pinned_artifacts_are_kept_and_index_is_compacted() {
+        let root = temp_root();
+        let dir = root.join(ArtifactClass::TaskOutput.dir_name());
+        let pinned = write_artifact(&dir, "pinned.log", 10, 40);
+        let expired = write_artifact(&dir, "expired.log", 10, 40);
+        std::fs::write(
+            dir.join(INDEX_FILE),
+            concat!(
+                "{\"path\":\"pinned.log\",\"pinned\":true}\n",
+                "{\"path\":\"expired.log\"}\n",
+                "{\"path\":\"already-deleted.log\"}\n",
+            ),
+        )
+        .unwrap();
+
+        let settings = settings_with(RetentionPolicy { max_total_bytes: MB, max_age_days: 30 });
+        let report = cleanup(&root, &settings, SystemTime::now(), false);
+
+        assert!(report.errors.is_empty());
+        assert!(pinned.exists());
+        assert!(!expired.exists());
+
+        let index = std::fs::read_to_string(dir.join(INDEX_FILE)).unwrap();
+        assert_eq!(index.lines().count(), 1);
+        assert!(index.contains("pinned.log"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_directory_artifacts_count_recursively() {
+        let root = temp_root();
+        let dir = root.join(ArtifactClass::Snapshots.dir_name());
+        let snapshot = dir.join("snap-1");
+        write_artifact(&snapshot.join("nested"), "file.txt", 64, 0);
+        write_artifact(&snapshot, "other.txt", 36, 0);
+
+        let artifacts = scan_artifacts(&dir);
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].bytes, 100);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,291 @@
+/// Review Diff - What each changed file's diff is, staged and unstaged apart
+///
+/// For reviewing agent edits before accepting them. `get_all_diffs` takes
+/// the open project's git status (renames paired up) and runs `git diff
+/// --cached` (index against HEAD) and `git diff` (work tree against index)
+/// once each, splitting their output per file by its `diff --git` line. A
+/// file's `staged` and `unstaged` parts hold parsed hunks (see `git_diff`);
+/// an untracked file is all `unstaged`, read from disk. Renamed files carry
+/// the `old_path` they were renamed from. Hunks stop after 1,000 lines per
+/// file, with `truncated` set on the part that was cut (line counts still
+/// cover the whole diff); `get_changed_file_diff` returns one file, in full
+/// when asked. Errors are `GitDiffError`s. Splitting and cutting diffs is
+/// shared with `git_log`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use serde::Serialize;
+
+use crate::git_diff::{self, DiffHunk, DiffLine, GitDiffError, LineKind};
+use crate::git_status::{self, StatusEntry};
+
+/// Hunk lines returned per file unless the full diff is asked for
+pub(crate) const MAX_LINES_PER_FILE: usize = 1000;
+
+/// Changes on one side of the index
+#[derive(Debug, Serialize)]
+pub struct DiffPart {
+    /// Git found the file binary; there are no hunks
+    pub binary: bool,
+    pub hunks: Vec<DiffHunk>,
+    pub lines_added: u32,
+    pub lines_removed: u32,
+    /// Hunks were cut at the per-file limit; `get_changed_file_diff` with
+    /// `full` has them all
+    pub truncated: bool,
+}
+
+/// Result of `get_changed_file_diff`, entry of `get_all_diffs`
+#[derive(Debug, Serialize)]
+pub struct ChangedFileDiff {
+    pub path: String,
+    /// Relative to the repository root, with `/` separators
+    pub relative_path: String,
+    /// Where a renamed file was, relative to the repository root
+    pub old_path: Option<String>,
+    /// "added" | "modified" | "deleted" | "renamed" | "conflicted"
+    pub status: String,
+    /// Index against HEAD (None: nothing staged)
+    pub staged: Option<DiffPart>,
+    /// Work tree against the index (None: nothing unstaged)
+    pub unstaged: Option<DiffPart>,
+}
+
+/// Unified diff output of several files, by `diff --git` line
+pub(crate) fn split_files(raw: &str) -> HashMap<&str, &str> {
+    let mut files = HashMap::new();
+    let starts: Vec<usize> = raw.match_indices("diff --git ").map(|(i, _)| i).filter(|&i| i == 0 || raw.as_bytes()[i - 1] == b'\n').collect();
+    for (n, &start) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).copied().unwrap_or(raw.len());
+        let chunk = &raw[start..end];
+        let header = chunk.lines().next().unwrap_or_default();
+        files.insert(header, chunk);
+    }
+    files
+}
+
+fn part(binary: bool, hunks: Vec<DiffHunk>) -> DiffPart {
+    let count = |kind| hunks.iter().flat_map(|h| &h.lines).filter(|l| l.kind == kind).count() as u32;
+    DiffPart {
+        binary,
+        lines_added: count(LineKind::Added),
+        lines_removed: count(LineKind::Removed),
+        hunks,
+        truncated: false,
+    }
+}
+
+/// Part of one file's chunk of `git diff` output
+pub(crate) fn chunk_part(chunk: &str) -> DiffPart {
+    let binary = chunk.lines().any(|line| line.starts_with("Binary files ") && line.ends_with(" differ"));
+    part(binary, git_diff::parse_hunks(chunk))
+}
+
+/// An untracked file as one hunk adding all of it
+fn untracked_part(path: &Path) -> DiffPart {
+    let bytes = std::fs::read(path).unwrap_or_default();
+    if bytes.contains(&0) {
+        return part(true, Vec::new());
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let lines: Vec<DiffLine> = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| DiffLine { kind: LineKind::Added, old_line: None, new_line: Some(i as u32 + 1), text: line.to_string() })
+        .collect();
+    if lines.is_empty() {
+        return part(false, Vec::new());
+    }
+    let hunk = DiffHunk {
+        old_start: 0,
+        old_lines: 0,
+        new_start: 1,
+        new_lines: lines.len() as u32,
+        section: String::new(),
+        lines,
+    };
+    part(false, vec![hunk])
+}
+
+/// Keep at most `budget` hunk lines of `part`, taking them from the budget
+pub(crate) fn truncate(part: &mut DiffPart, budget: &mut usize) {
+    part.hunks.retain_mut(|hunk| {
+        if *budget == 0 {
+            part.truncated = true;
+            return false;
+        }
+        if hunk.lines.len() > *budget {
+            hunk.lines.truncate(*budget);
+            part.truncated = true;
+        }
+        *budget -= hunk.lines.len();
+        true
+    });
+}
+
+fn review_status(entry: &StatusEntry) -> &'static str {
+    if entry.unmerged {
+        "conflicted"
+    } else if entry.old_path.is_some() {
+        "renamed"
+    } else {
+        entry.change()
+    }
+}
+
+/// `git diff` output in `project` for `pathspecs`, staged or not
+fn raw_diff(project: &Path, staged: bool, pathspecs: &[String]) -> Result<String, GitDiffError> {
+    let mut args = vec!["-c", "core.quotePath=false", "diff", "--no-color", "--find-renames"];
+    if staged {
+        args.push("--cached");
+    }
+    args.push("--");
+    args.extend(pathspecs.iter().map(String::as_str));
+    let output = git_diff::git(project, &args)?;
+    if !output.status.success() {
+        return Err(git_diff::git_failure(&output));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Diffs of the project's changed files, or only of the one at the
+/// repository path `only`; cut per file unless `full`
+fn diffs(project: &Path, only: Option<&str>, full: bool) -> Result<Vec<ChangedFileDiff>, GitDiffError> {
+    let root = git_diff::repository_root(project)?;
+    let entries: Vec<StatusEntry> = git_status::status(project, true)?
+        .into_iter()
+        .filter(|entry| only.is_none_or(|path| entry.path == path || entry.old_path.as_deref() == Some(path)))
+        .collect();
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+    // Paths from the top of the repository, whatever folder git runs in
+    let pathspecs: Vec<String> = match only {
+        Some(_) => entries
+            .iter()
+            .flat_map(|entry| std::iter::once(&entry.path).chain(&entry.old_path))
+            .map(|path| format!(":(top,literal){}", path))
+            .collect(),
+        None => vec![".".to_string()],
+    };
+    let staged_raw = raw_diff(project, true, &pathspecs)?;
+    let unstaged_raw = raw_diff(project, false, &pathspecs)?;
+    let staged = split_files(&staged_raw);
+    let unstaged = split_files(&unstaged_raw);
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let from = entry.old_path.as_deref().unwrap_or(&entry.path);
+            let staged_part = (!matches!(entry.index, '.' | '?')).then(|| {
+                let header = format!("diff --git a/{} b/{}", from, entry.path);
+                staged.get(header.as_str()).map_or_else(|| part(false, Vec::new()), |chunk| chunk_part(chunk))
+            });
+            let unstaged_part = match entry.worktree {
+                '.' => None,
+                '?' => Some(untracked_part(&root.join(&entry.path))),
+                _ => {
+                    let header = format!("diff --git a/{} b/{}", entry.path, entry.path);
+                    Some(unstaged.get(header.as_str()).map_or_else(|| part(false, Vec::new()), |chunk| chunk_part(chunk)))
+                }
+            };
+            let mut diff = ChangedFileDiff {
+                path: root.join(&entry.path).to_string_lossy().to_string(),
+                relative_path: entry.path.clone(),
+                status: review_status(&entry).to_string(),
+                old_path: entry.old_path,
+                staged: staged_part,
+                unstaged: unstaged_part,
+            };
+            if !full {
+                let mut budget = MAX_LINES_PER_FILE;
+                for part in diff.staged.iter_mut().chain(diff.unstaged.iter_mut()) {
+                    truncate(part, &mut budget);
+                }
+            }
+            diff
+        })
+        .collect())
+}
+
+fn current_project() -> Result<String, GitDiffError> {
+    crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()).ok_or(GitDiffError::NoProject)
+}
+
+/// Staged and unstaged diff of one changed file (absolute, or relative to
+/// the project); with `full`, not cut at the per-file limit
+#[tauri::command]
+pub async fn get_changed_file_diff(path: String, full: Option<bool>) -> Result<ChangedFileDiff, GitDiffError> {
+    crate::command_metrics::timed("get_changed_file_diff", async move {
+        let project = current_project()?;
+        tauri::async_runtime::spawn_blocking(move || {
+            let project = Path::new(&project);
+            let root = git_diff::repository_root(project)?;
+            let relative = git_diff::repository_path(&root, project, &path)?;
+            diffs(project, Some(&relative), full.unwrap_or(false))?
+                .into_iter()
+                .next()
+                .ok_or_else(|| GitDiffError::Git(format!("{} has no changes", relative)))
+        })
+        .await
+        .map_err(|e| GitDiffError::Git(format!("Diff failed: {}", e)))?
+    }).await
+}
+
+/// Diffs of all changed files of the project, each cut at the per-file limit
+#[tauri::command]
+pub async fn get_all_diffs() -> Result<Vec<ChangedFileDiff>, GitDiffError> {
+    crate::command_metrics::timed("get_all_diffs", async move {
+        let project = current_project()?;
+        tauri::async_runtime::spawn_blocking(move || diffs(Path::new(&project), None, false))
+            .await
+            .map_err(|e| GitDiffError::Git(format!("Diff failed: {}", e)))?
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_output_is_split_per_file_and_truncated() {
+        let raw = "diff --git a/bin.dat b/bin.dat\n\
+                   index bdc955b..8835708 100644\n\
+                   Binary files a/bin.dat and b/bin.dat differ\n\
+                   diff --git a/old name.txt b/new name.txt\n\
+                   similarity index 83%\n\
+                   rename from old name.txt\n\
+                   rename to new name.txt\n\
+                   --- a/old name.txt\t\n\
+                   +++ b/new name.txt\t\n\
+                   @@ -3,3 +3,4 @@\n \
+                   3\n \
+                   4\n \
+                   5\n\
+                   +6\n";
+        let files = split_files(raw);
+        assert_eq!(files.len(), 2);
+        let binary = chunk_part(files["diff --git a/bin.dat b/bin.dat"]);
+        assert!(binary.binary && binary.hunks.is_empty());
+
+        let mut renamed = chunk_part(files["diff --git a/old name.txt b/new name.txt"]);
+        assert_eq!((renamed.binary, renamed.lines_added, renamed.lines_removed), (false, 1, 0));
+        assert_eq!(renamed.hunks[0].lines.len(), 4);
+
+        let mut budget = 3;
+        truncate(&mut renamed, &mut budget);
+        assert_eq!((renamed.hunks[0].lines.len(), renamed.truncated, budget), (3, true, 0));
+        assert_eq!(renamed.lines_added, 1);
+        let mut rest = chunk_part(files["diff --git a/old name.txt b/new name.txt"]);
+        truncate(&mut rest, &mut budget);
+        assert!(rest.hunks.is_empty() && rest.truncated);
+
+        let dir = std::env::temp_dir().join(format!("vibecode-review-diff-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("new.rs"), "fn a() {}\nfn b() {}").unwrap();
+        let untracked = untracked_part(&dir.join("new.rs"));
+        assert_eq!((untracked.lines_added, untracked.hunks[0].new_lines), (2, 2));
+        assert_eq!(untracked.hunks[0].lines[1].new_line, Some(2));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,607 @@
+/// Scheduler - Recurring tasks and workflows
+///
+/// Schedules live in `<config>/schedules.json`. Each one runs a task (the
+/// same way `execute_task` does, so it lands in the task history and the
+/// TaskManager) or a workflow, on a five-field cron expression in local time
+/// (`0 2 * * *` = nightly at 02:00) or every `every_secs` seconds. The loop
+/// started with the app checks for due schedules every `POLL_INTERVAL`. Next
+/// run times are computed from the moment the loop first sees a schedule, so
+/// runs missed while the app was closed are skipped, not replayed, and a
+/// schedule whose previous run is still going skips its turn. Runs are
+/// announced as `schedule-run-started` and `schedule-run-finished`.
+///
+/// Workflow schedules (`schedule_workflow`) pass their variables to the run
+/// and are recorded in the workflow history as `triggered_by: "schedule"`. A
+/// workflow whose file is gone is not started, and neither is one with a run
+/// still going, whichever schedule or button started it.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+use crate::workflow_run::{RunRequest, WorkflowRuns};
+use crate::TaskRun;
+
+pub const SCHEDULE_RUN_STARTED_EVENT: &str = "schedule-run-started";
+pub const SCHEDULE_RUN_FINISHED_EVENT: &str = "schedule-run-finished";
+
+const SCHEDULES_FILE: &str = "schedules.json";
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Shortest interval a schedule may use
+const MIN_INTERVAL_SECS: u64 = 60;
+/// Label of task runs started by a schedule
+const SCHEDULED_LABEL: &str = "scheduled";
+/// `triggered_by` of workflow runs started by a schedule
+const TRIGGERED_BY_SCHEDULE: &str = "schedule";
+/// How far ahead a cron expression is searched (Feb 29 recurs within 8 years)
+const CRON_SEARCH_DAYS: i64 = 8 * 366;
+
+/// Schedules (workflows, for workflow schedules) whose run has not finished yet
+static RUNNING: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// When a schedule fires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Trigger {
+    /// "minute hour day-of-month month day-of-week", or @hourly / @daily / @weekly / @monthly
+    Cron { expression: String },
+    Interval { every_secs: u64 },
+}
+
+/// What a schedule runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleTarget {
+    Task { task: String },
+    Workflow {
+        name: String,
+        /// Overrides of the workflow's `variables:` defaults
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        variables: HashMap<String, String>,
+    },
+}
+
+/// Stored schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    pub id: String,
+    pub name: String,
+    pub trigger: Trigger,
+    pub target: ScheduleTarget,
+    /// Agent of task runs ("auto" | "api" | "cli" | "antigravity")
+    pub agent: String,
+    pub enabled: bool,
+    pub created_at: String, // ISO 8601
+    #[serde(default)]
+    pub last_run_at: Option<String>, // ISO 8601
+}
+
+/// Entry of `list_schedules`
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleInfo {
+    #[serde(flatten)]
+    pub schedule: Schedule,
+    /// None while disabled
+    pub next_run_at: Option<String>, // ISO 8601
+}
+
+/// Payload of `schedule-run-started` and `schedule-run-finished`
+#[derive(Debug, Clone, Serialize)]
+struct ScheduleRun<'a> {
+    schedule_id: &'a str,
+    name: &'a str,
+    task_id: &'a str,
+    /// Set once finished
+    success: Option<bool>,
+    error: Option<&'a str>,
+}
+
+/// Parsed cron expression; each field is a bitset of allowed values
+#[derive(Debug, Clone, PartialEq)]
+struct Cron {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Both day fields restricted: either one matching is enough (as in cron)
+    either_day: bool,
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1u64 << value) != 0
+}
+
+/// Parse one field ("*", "5", "1-5", "*/15", "0-30/10", lists of those)
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid {} field in cron expression: '{}'", name, field);
+    let number = |s: &str| s.parse::<u32>().map_err(|_| invalid());
+
+    let mut bits: u64 = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, number(step)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (number(start)?, number(end)?),
+            // "5/15" runs from 5 to the end of the range
+            None if step > 1 => (number(range)?, max),
+            None => (number(range)?, number(range)?),
+        };
+        if step == 0 || start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1u64 << value;
+        }
+    }
+    Ok(bits)
+}
+
+impl Cron {
+    fn parse(expression: &str) -> Result<Self, String> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("Cron expression needs 5 fields, got {}: '{}'", fields.len(), expression));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7, "day-of-week")?;
+        // 7 is Sunday too
+        if has(weekdays, 7) {
+            weekdays = (weekdays | 1) & !(1u64 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")?,
+            days: parse_field(day, 1, 31, "day-of-month")?,
+            months: parse_field(month, 1, 12, "month")?,
+            weekdays,
+            either_day: day != "*" && weekday != "*",
+        })
+    }
+
+    fn matches_day(&self, date: chrono::NaiveDate) -> bool {
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        let day_ok = if self.either_day { day || weekday } else { day && weekday };
+        has(self.months, date.month()) && day_ok
+    }
+
+    /// First matching minute strictly after `after`
+    fn next_naive(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        let limit = t + chrono::Duration::days(CRON_SEARCH_DAYS);
+        while t < limit {
+            if !self.matches_day(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !has(self.hours, t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + chrono::Duration::hours(1);
+            } else if !has(self.minutes, t.minute()) {
+                t += chrono::Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    /// Next run in local time, skipping minutes a DST change leaves out
+    fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut t = after.naive_local();
+        loop {
+            t = self.next_naive(t)?;
+            if let Some(local) = Local.from_local_datetime(&t).earliest() {
+                return Some(local);
+            }
+        }
+    }
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Next run after `now`; an interval run that fell due in the past is skipped
+fn next_run(trigger: &Trigger, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    match trigger {
+        Trigger::Cron { expression } => {
+            let next = Cron::parse(expression).ok()?.next_after(now.with_timezone(&Local))?;
+            Some(next.with_timezone(&Utc))
+        }
+        Trigger::Interval { every_secs } => {
+            let every = chrono::Duration::seconds(i64::try_from(*every_secs).ok()?);
+            match last_run.map(|last| last + every) {
+                Some(next) if next > now => Some(next),
+                _ => Some(now + every),
+            }
+        }
+    }
+}
+
+impl Schedule {
+    fn next_run(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let last_run = self.last_run_at.as_deref().or(Some(&self.created_at)).and_then(parse_time);
+        next_run(&self.trigger, last_run, now)
+    }
+
+    /// What must not run twice at once: the schedule, or the workflow it runs
+    fn running_key(&self) -> String {
+        match &self.target {
+            ScheduleTarget::Task { .. } => self.id.clone(),
+            ScheduleTarget::Workflow { name, .. } => format!("workflow:{}", name),
+        }
+    }
+}
+
+fn validate(trigger: &Trigger, target: &ScheduleTarget) -> Result<(), String> {
+    match trigger {
+        Trigger::Cron { expression } => {
+            Cron::parse(expression)?;
+        }
+        Trigger::Interval { every_secs } if *every_secs < MIN_INTERVAL_SECS => {
+            return Err(format!("Interval must be at least {} seconds", MIN_INTERVAL_SECS));
+        }
+        Trigger::Interval { .. } => {}
+    }
+    match target {
+        ScheduleTarget::Task { task } if task.trim().is_empty() => Err("Scheduled task is empty".to_string()),
+        ScheduleTarget::Workflow { name, .. } if name.trim().is_empty() => Err("Workflow name is empty".to_string()),
+        _ => Ok(()),
+    }
+}
+
+fn schedules_path() -> PathBuf {
+    crate::get_app_config_dir().join(SCHEDULES_FILE)
+}
+
+fn load(path: &Path) -> Vec<Schedule> {
+    let Ok(content) = std::fs::read_to_string(path) else { return Vec::new() };
+    serde_json::from_str::<serde_json::Value>(&content)
+        .ok()
+        .and_then(|value| value.get("schedules").cloned())
+        .and_then(|schedules| {
+            serde_json::from_value(schedules)
+                .map_err(|e| eprintln!("Scheduler: invalid {}: {}", SCHEDULES_FILE, e))
+                .ok()
+        })
+        .unwrap_or_default()
+}
+
+/// Read-modify-write the stored schedules
+fn update<R>(change: impl FnOnce(&mut Vec<Schedule>) -> Result<R, String>) -> Result<R, String> {
+    crate::instance_lock::update_json_file(&schedules_path(), |value| {
+        let mut schedules: Vec<Schedule> = match value.get("schedules") {
+            Some(schedules) => serde_json::from_value(schedules.clone())
+                .map_err(|e| format!("Invalid {}: {}", SCHEDULES_FILE, e))?,
+            None => Vec::new(),
+        };
+        let result = change(&mut schedules)?;
+        value["schedules"] = serde_json::to_value(&schedules)
+            .map_err(|e| format!("Failed to serialize schedules: {}", e))?;
+        Ok(result)
+    })
+}
+
+fn running() -> std::sync::MutexGuard<'static, Option<HashSet<String>>> {
+    RUNNING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Start a schedule's run in the background (skipped while its last run is going)
+fn fire(app: &tauri::AppHandle, schedule: Schedule) {
+    if let ScheduleTarget::Workflow { name, .. } = &schedule.target {
+        if let Err(e) = crate::workflow_validation::resolve(name) {
+            eprintln!("Scheduler: '{}' not started: {}", schedule.name, e);
+            return;
+        }
+        if app.state::<WorkflowRuns>().is_running(name) {
+            eprintln!("Scheduler: workflow '{}' is still running, skipping this run of '{}'", name, schedule.name);
+            return;
+        }
+    }
+    let key = schedule.running_key();
+    if !running().get_or_insert_with(HashSet::new).insert(key.clone()) {
+        eprintln!("Scheduler: '{}' is still running, skipping this run", schedule.name);
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let task_id = uuid::Uuid::new_v4().to_string();
+        let event = |success: Option<bool>, error: Option<&str>| {
+            let run = ScheduleRun { schedule_id: &schedule.id, name: &schedule.name, task_id: &task_id, success, error };
+            serde_json::to_value(run).unwrap_or_default()
+        };
+        let _ = app.emit(SCHEDULE_RUN_STARTED_EVENT, event(None, None));
+
+        let now = Utc::now().to_rfc3339();
+        let id = schedule.id.clone();
+        if let Err(e) = update(|schedules| {
+            if let Some(stored) = schedules.iter_mut().find(|s| s.id == id) {
+                stored.last_run_at = Some(now);
+            }
+            Ok(())
+        }) {
+            eprintln!("Scheduler: failed to record run of '{}': {}", schedule.name, e);
+        }
+
+        let result = match &schedule.target {
+            ScheduleTarget::Task { task } => {
+                let run = TaskRun {
+                    task: task.clone(),
+                    agent: schedule.agent.clone(),
+                    task_id: task_id.clone(),
+                    timeout: crate::task_process::TaskProcessSettings::load().timeout(None),
+                    env: None,
+                    retry: None,
+                    labels: vec![SCHEDULED_LABEL.to_string()],
+                    auto_fallback: false,
+                };
+                crate::run_task(&app, run).await.map(|result| result.success)
+            }
+            ScheduleTarget::Workflow { name, variables } => {
+                let request = RunRequest {
+                    name: name.clone(),
+                    dry_run: false,
+                    variables: variables.clone(),
+                    env: None,
+                    task_id: task_id.clone(),
+                    triggered_by: Some(TRIGGERED_BY_SCHEDULE.to_string()),
+                    scope: None,
+                    step: None,
+                    snapshot: None,
+                };
+                crate::workflow_run::run_if_valid(&app, request, false).await.map(|result| result.success)
+            }
+        };
+        if let Some(running) = running().as_mut() {
+            running.remove(&key);
+        }
+
+        let payload = match &result {
+            Ok(success) => event(Some(*success), None),
+            Err(e) => event(Some(false), Some(e.as_str())),
+        };
+        let _ = app.emit(SCHEDULE_RUN_FINISHED_EVENT, payload);
+    });
+}
+
+/// Check for due schedules every `POLL_INTERVAL` for the lifetime of the app
+pub fn spawn(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // Next run per schedule id, computed when a schedule is first seen
+        let mut due: HashMap<String, Option<DateTime<Utc>>> = HashMap::new();
+        loop {
+            let now = Utc::now();
+            let schedules: Vec<Schedule> = load(&schedules_path()).into_iter().filter(|s| s.enabled).collect();
+            due.retain(|id, _| schedules.iter().any(|s| &s.id == id));
+
+            for schedule in schedules {
+                let next = *due.entry(schedule.id.clone()).or_insert_with(|| schedule.next_run(now));
+                if next.is_some_and(|next| next <= now) {
+                    due.insert(schedule.id.clone(), next_run(&schedule.trigger, Some(now), now));
+                    fire(&app, schedule);
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Create a schedule; `agent` defaults to "auto", `enabled` to true
+#[tauri::command]
+pub async fn create_schedule(
+    name: String,
+    trigger: Trigger,
+    target: ScheduleTarget,
+    agent: Option<String>,
+    enabled: Option<bool>,
+) -> Result<ScheduleInfo, String> {
+    crate::command_metrics::timed("create_schedule", async move {
+        create(&name, trigger, target, agent.unwrap_or_else(|| "auto".to_string()), enabled.unwrap_or(true))
+    }).await
+}
+
+fn create(name: &str, trigger: Trigger, target: ScheduleTarget, agent: String, enabled: bool) -> Result<ScheduleInfo, String> {
+    if name.trim().is_empty() {
+        return Err("Schedule name is empty".to_string());
+    }
+    validate(&trigger, &target)?;
+
+    let schedule = Schedule {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.trim().to_string(),
+        trigger,
+        target,
+        agent,
+        enabled,
+        created_at: Utc::now().to_rfc3339(),
+        last_run_at: None,
+    };
+    let stored = schedule.clone();
+    update(move |schedules| {
+        schedules.push(stored);
+        Ok(())
+    })?;
+    Ok(info(schedule, Utc::now()))
+}
+
+fn info(schedule: Schedule, now: DateTime<Utc>) -> ScheduleInfo {
+    let next_run_at = if schedule.enabled { schedule.next_run(now).map(|t| t.to_rfc3339()) } else { None };
+    ScheduleInfo { schedule, next_run_at }
+}
+
+/// List schedules with their next run
+#[tauri::command]
+pub async fn list_schedules() -> Result<Vec<ScheduleInfo>, String> {
+    crate::command_metrics::timed("list_schedules", async move {
+        let now = Utc::now();
+        Ok(load(&schedules_path()).into_iter().map(|s| info(s, now)).collect())
+    }).await
+}
+
+/// Enable or disable a schedule
+#[tauri::command]
+pub async fn toggle_schedule(id: String, enabled: bool) -> Result<ScheduleInfo, String> {
+    crate::command_metrics::timed("toggle_schedule", async move {
+        let schedule = update(|schedules| {
+            let schedule = schedules
+                .iter_mut()
+                .find(|s| s.id == id)
+                .ok_or_else(|| format!("Schedule '{}' not found", id))?;
+            schedule.enabled = enabled;
+            Ok(schedule.clone())
+        })?;
+        Ok(info(schedule, Utc::now()))
+    }).await
+}
+
+/// Delete a schedule (a run in progress finishes normally)
+#[tauri::command]
+pub async fn delete_schedule(id: String) -> Result<(), String> {
+    crate::command_metrics::timed("delete_schedule", async move {
+        let result = update(|schedules| {
+            let before = schedules.len();
+            schedules.retain(|s| s.id != id);
+            if schedules.len() == before {
+                return Err(format!("Schedule '{}' not found", id));
+            }
+            Ok(())
+        });
+        crate::audit::record("delete_schedule", serde_json::json!({ "id": id }), crate::audit::ORIGIN_COMMAND, &result);
+        result
+    }).await
+}
+
+/// Run workflow `name` on a cron expression, with `variables` overriding its defaults
+#[tauri::command]
+pub async fn schedule_workflow(
+    name: String,
+    cron_expr: String,
+    variables: Option<HashMap<String, String>>,
+) -> Result<ScheduleInfo, String> {
+    crate::command_metrics::timed("schedule_workflow", async move {
+        let path = crate::workflow_validation::resolve(&name)?;
+        let yaml = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read workflow: {}", e))?;
+        let variables = variables.unwrap_or_default();
+        crate::workflow_variables::check_overrides(&crate::workflow_variables::declared(&yaml)?, &variables)?;
+
+        let trigger = Trigger::Cron { expression: cron_expr };
+        create(&name, trigger, ScheduleTarget::Workflow { name: name.clone(), variables }, "auto".to_string(), true)
+    }).await
+}
+
+/// Schedules that run a workflow, with their next run
+#[tauri::command]
+pub async fn list_workflow_schedules() -> Result<Vec<ScheduleInfo>, String> {
+    crate::command_metrics::timed("list_workflow_schedules", async move {
+        let now = Utc::now();
+        Ok(load(&schedules_path())
+            .into_iter()
+            .filter(|s| matches!(s.target, ScheduleTarget::Workflow { .. }))
+            .map(|s| info(s, now))
+            .collect())
+    }).await
+}
+
+/// Delete a workflow schedule (a run in progress finishes normally)
+#[tauri::command]
+pub async fn delete_workflow_schedule(id: String) -> Result<(), String> {
+    crate::command_metrics::timed("delete_workflow_schedule", async move {
+        let result = update(|schedules| {
+            let before = schedules.len();
+            schedules.retain(|s| s.id != id || !matches!(s.target, ScheduleTarget::Workflow { .. }));
+            if schedules.len() == before {
+                return Err(format!("Workflow schedule '{}' not found", id));
+            }
+            Ok(())
+        });
+        crate::audit::record("delete_workflow_schedule", serde_json::json!({ "id": id }), crate::audit::ORIGIN_COMMAND, &result);
+        result
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_cron_finds_the_next_matching_minute() {
+        let nightly = Cron::parse("0 2 * * *").unwrap();
+        assert_eq!(nightly.next_naive(at("2026-03-10 01:59")), Some(at("2026-03-10 02:00")));
+        assert_eq!(nightly.next_naive(at("2026-03-10 02:00")), Some(at("2026-03-11 02:00")));
+
+        // Every 15 minutes during working hours on weekdays (2026-03-14 is a Saturday)
+        let busy = Cron::parse("*/15 9-17 * * 1-5").unwrap();
+        assert_eq!(busy.next_naive(at("2026-03-13 17:50")), Some(at("2026-03-16 09:00")));
+        assert_eq!(busy.next_naive(at("2026-03-16 09:01")), Some(at("2026-03-16 09:15")));
+
+        // Day-of-month and day-of-week together: either one (the 1st, or Sundays)
+        let either = Cron::parse("0 0 1 * 7").unwrap();
+        assert_eq!(either.next_naive(at("2026-03-10 00:00")), Some(at("2026-03-15 00:00")));
+
+        assert_eq!(Cron::parse("@daily").unwrap(), Cron::parse("0 0 * * *").unwrap());
+        assert!(Cron::parse("0 24 * * *").is_err());
+        assert!(Cron::parse("*/0 * * * *").is_err());
+        assert!(Cron::parse("0 2 * *").is_err());
+    }
+
+    #[test]
+    fn test_missed_interval_runs_are_skipped() {
+        let trigger = Trigger::Interval { every_secs: 3600 };
+        let now = Utc::now();
+        let hour = chrono::Duration::hours(1);
+
+        // Ran 20 minutes ago: next in 40 minutes
+        let last = now - chrono::Duration::minutes(20);
+        assert_eq!(next_run(&trigger, Some(last), now), Some(last + hour));
+        // App closed for a day: one run an interval from now, no catch-up
+        assert_eq!(next_run(&trigger, Some(now - chrono::Duration::days(1)), now), Some(now + hour));
+
+        assert!(validate(&Trigger::Interval { every_secs: 5 }, &ScheduleTarget::Task { task: "lint".into() }).is_err());
+        assert!(validate(&trigger, &ScheduleTarget::Workflow { name: " ".into(), variables: HashMap::new() }).is_err());
+    }
+
+    #[test]
+    fn test_workflow_schedules_never_overlap_a_workflow() {
+        let schedule = |id: &str, target: ScheduleTarget| Schedule {
+            id: id.to_string(),
+            name: id.to_string(),
+            trigger: Trigger::Cron { expression: "0 18 * * 1-5".to_string() },
+            target,
+            agent: "auto".to_string(),
+            enabled: true,
+            created_at: Utc::now().to_rfc3339(),
+            last_run_at: None,
+        };
+        let backup = |variables: HashMap<String, String>| ScheduleTarget::Workflow { name: "backup".to_string(), variables };
+        let nightly = schedule("nightly", backup(HashMap::new()));
+        let weekdays = schedule("weekdays", backup(HashMap::from([("target".to_string(), "db".to_string())])));
+        let lint = schedule("lint", ScheduleTarget::Task { task: "lint".to_string() });
+
+        assert_eq!(nightly.running_key(), weekdays.running_key());
+        assert_eq!(lint.running_key(), "lint");
+
+        // Stored without variables, schedules from before they existed still load
+        let stored = serde_json::to_value(&nightly.target).unwrap();
+        assert_eq!(stored, serde_json::json!({ "type": "workflow", "name": "backup" }));
+        assert_eq!(serde_json::from_value::<ScheduleTarget>(stored).unwrap(), nightly.target);
+        let stored = serde_json::to_value(&weekdays.target).unwrap();
+        assert_eq!(stored["variables"]["target"], "db");
+    }
+}
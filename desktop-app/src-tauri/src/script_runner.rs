@@ -0,0 +1,385 @@
+/// Script Runner - Concurrency limits and isolation for skill script runs
+///
+/// Runs are admitted through a per-skill semaphore and a global one (sizes from
+/// the `scriptRuns` settings block, read once at first use). Each run gets its
+/// own temp directory, exposed as `VIBECODE_RUN_DIR` and also as TMPDIR/TEMP/
+/// TMP so well-behaved scripts stop sharing temp files. Start and finish are
+/// emitted as `skill-script-started` / `skill-script-finished` events keyed by
+/// the run's execution id.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex, OnceLock};
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::child_env::{self, ChildContext};
+use crate::ScriptResult;
+
+/// Env var pointing at the run's private temp directory
+pub const RUN_DIR_VAR: &str = "VIBECODE_RUN_DIR";
+
+/// `scriptRuns` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScriptRunSettings {
+    /// Concurrent runs per skill
+    pub per_skill: usize,
+    /// Concurrent runs across all skills
+    pub global: usize,
+    /// Retry-After sent with 429 responses
+    pub retry_after_secs: u64,
+}
+
+impl Default for ScriptRunSettings {
+    fn default() -> Self {
+        Self {
+            per_skill: 1,
+            global: 4,
+            retry_after_secs: 5,
+        }
+    }
+}
+
+impl ScriptRunSettings {
+    /// Load the scriptRuns block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("scriptRuns").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Semaphores shared by every run
+struct Limits {
+    settings: ScriptRunSettings,
+    global: Arc<Semaphore>,
+    per_skill: Mutex<HashMap<String, Arc<Semaphore>>>,
+    /// Runs waiting for a permit, per skill
+    waiting: Mutex<HashMap<String, usize>>,
+}
+
+static LIMITS: OnceLock<Limits> = OnceLock::new();
+
+fn limits() -> &'static Limits {
+    LIMITS.get_or_init(|| {
+        let settings = ScriptRunSettings::load();
+        Limits {
+            global: Arc::new(Semaphore::new(settings.global.max(1))),
+            per_skill: Mutex::new(HashMap::new()),
+            waiting: Mutex::new(HashMap::new()),
+            settings,
+        }
+    })
+}
+
+impl Limits {
+    fn skill_semaphore(&self, skill_id: &str) -> Arc<Semaphore> {
+        let mut per_skill = self.per_skill.lock().unwrap_or_else(|e| e.into_inner());
+        per_skill
+            .entry(skill_id.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.settings.per_skill.max(1))))
+            .clone()
+    }
+
+    /// Take both permits now, or none
+    fn try_admit(&self, skill_id: &str) -> Option<RunPermit> {
+        let skill = self.skill_semaphore(skill_id).try_acquire_owned().ok()?;
+        let global = self.global.clone().try_acquire_owned().ok()?;
+        Some(RunPermit { _skill: skill, _global: global })
+    }
+
+    /// Wait for both permits (skill first, so one busy skill can't hold global slots)
+    async fn admit(&self, skill_id: &str) -> Result<RunPermit, String> {
+        let skill = self
+            .skill_semaphore(skill_id)
+            .acquire_owned()
+            .await
+            .map_err(|e| format!("Failed to acquire run slot: {}", e))?;
+        let global = self
+            .global
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| format!("Failed to acquire run slot: {}", e))?;
+        Ok(RunPermit { _skill: skill, _global: global })
+    }
+
+    /// Register a queued run; returns its 1-based position in the skill's queue
+    fn enqueue(&self, skill_id: &str) -> usize {
+        let mut waiting = self.waiting.lock().unwrap_or_else(|e| e.into_inner());
+        let count = waiting.entry(skill_id.to_string()).or_insert(0);
+        *count += 1;
+        *count
+    }
+
+    fn dequeue(&self, skill_id: &str) {
+        let mut waiting = self.waiting.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = waiting.get_mut(skill_id) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                waiting.remove(skill_id);
+            }
+        }
+    }
+}
+
+/// Held for the duration of a run
+struct RunPermit {
+    _skill: OwnedSemaphorePermit,
+    _global: OwnedSemaphorePermit,
+}
+
+/// Response to a run submitted over the REST API
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmittedRun {
+    pub execution_id: String,
+    /// "started" | "queued"
+    pub status: String,
+    pub queue_position: Option<usize>,
+}
+
+/// Submission refused because the limits are reached
+#[derive(Debug, Clone, Serialize)]
+pub struct RunRejected {
+    pub error: String,
+    pub retry_after_secs: u64,
+}
+
+/// Payload of `skill-script-started` / `skill-script-finished`
+#[derive(Debug, Clone, Serialize)]
+struct RunEvent<'a> {
+    execution_id: &'a str,
+    skill_id: &'a str,
+    script_name: &'a str,
+    result: Option<&'a ScriptResult>,
+    error: Option<&'a str>,
+}
+
+/// Path of a skill script, if it exists (ids and names that would leave the skill folder are refused)
+pub fn script_path(skill_id: &str, script_name: &str) -> Result<PathBuf, String> {
+    let not_found = || format!("Script '{}' not found in skill '{}'", script_name, skill_id);
+    if !crate::skill_archive::is_valid_skill_id(skill_id) || !crate::skill_archive::is_valid_skill_id(script_name) {
+        return Err(not_found());
+    }
+    let script_path = crate::get_skills_path().join(skill_id).join("scripts").join(script_name);
+    if !script_path.is_file() {
+        return Err(not_found());
+    }
+    Ok(script_path)
+}
+
+/// Run a script synchronously inside `run_dir`
+fn execute(skill_id: &str, script_name: &str, execution_id: &str, run_dir: &Path) -> Result<ScriptResult, String> {
+    use std::time::Instant;
+
+    let script_path = script_path(skill_id, script_name)?;
+    let skill_folder = crate::get_skills_path().join(skill_id);
+
+    // Determine script type by extension
+    let extension = script_path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+
+    let (mut cmd, runtime) = match extension {
+        "py" => (crate::python_env::command()?, "Python"),
+        "js" | "mjs" => (Command::new("node"), "Node.js"),
+        _ => {
+            return Err(format!("Unsupported script type: .{}", extension));
+        }
+    };
+
+    let start_time = Instant::now();
+    let run_dir_value = run_dir.to_string_lossy().to_string();
+    let child_env = child_env::build_child_env(&ChildContext::SkillScript(&skill_folder))
+        .with_run_var(RUN_DIR_VAR, run_dir_value.clone())
+        .with_run_var("TMPDIR", run_dir_value.clone())
+        .with_run_var("TEMP", run_dir_value.clone())
+        .with_run_var("TMP", run_dir_value);
+
+    child_env.apply(&mut cmd)
+        .arg(&script_path)
+        .current_dir(&skill_folder);
+    let invocation = child_env::Invocation::capture(&cmd);
+
+    let output = crate::task_process::output(&format!("{}/{}", skill_id, script_name), &mut cmd)
+        .map_err(|e| format!("Failed to execute {} script: {}", runtime, e))?;
+
+    let execution_time = start_time.elapsed().as_secs_f64();
+
+    let mut result = script_result(&output, execution_time, execution_id);
+    result.env = child_env.provided();
+    result.invocation = Some(invocation);
+    Ok(result)
+}
+
+/// Streams and exit status of a finished script
+fn script_result(output: &std::process::Output, execution_time: f64, execution_id: &str) -> ScriptResult {
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+    ScriptResult {
+        success: output.status.success(),
+        output: stdout,
+        error: if stderr.is_empty() { None } else { Some(stderr) },
+        exit_code: output.status.code(),
+        execution_time,
+        env: Vec::new(),
+        invocation: None,
+        execution_id: execution_id.to_string(),
+    }
+}
+
+/// Run with a fresh temp directory, emitting start/finish events
+async fn run_isolated(app: &tauri::AppHandle, skill_id: &str, script_name: &str, execution_id: &str) -> Result<ScriptResult, String> {
+    let _ = app.emit(
+        "skill-script-started",
+        RunEvent { execution_id, skill_id, script_name, result: None, error: None },
+    );
+
+    let run_dir = std::env::temp_dir().join("vibecode-runs").join(execution_id);
+    let result = match std::fs::create_dir_all(&run_dir) {
+        Ok(()) => {
+            let (skill_id, script_name, execution_id, dir) =
+                (skill_id.to_string(), script_name.to_string(), execution_id.to_string(), run_dir.clone());
+            tauri::async_runtime::spawn_blocking(move || execute(&skill_id, &script_name, &execution_id, &dir))
+                .await
+                .map_err(|e| format!("Script task failed: {}", e))
+                .and_then(|r| r)
+        }
+        Err(e) => Err(format!("Failed to create run directory: {}", e)),
+    };
+    let _ = std::fs::remove_dir_all(&run_dir);
+
+    let _ = app.emit(
+        "skill-script-finished",
+        RunEvent {
+            execution_id,
+            skill_id,
+            script_name,
+            result: result.as_ref().ok(),
+            error: result.as_ref().err().map(String::as_str),
+        },
+    );
+    result
+}
+
+/// Submit a run from the REST API: start now, queue, or (with `reject_when_busy`) refuse
+pub fn submit(app: tauri::AppHandle, skill_id: String, script_name: String, reject_when_busy: bool) -> Result<SubmittedRun, RunRejected> {
+    let limits = limits();
+    let execution_id = uuid::Uuid::new_v4().to_string();
+
+    let (permit, queue_position) = match limits.try_admit(&skill_id) {
+        Some(permit) => (Some(permit), None),
+        None if reject_when_busy => {
+            return Err(RunRejected {
+                error: format!("Too many concurrent runs for skill '{}'", skill_id),
+                retry_after_secs: limits.settings.retry_after_secs,
+            });
+        }
+        None => (None, Some(limits.enqueue(&skill_id))),
+    };
+
+    let submitted = SubmittedRun {
+        execution_id: execution_id.clone(),
+        status: if permit.is_some() { "started" } else { "queued" }.to_string(),
+        queue_position,
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let permit = match permit {
+            Some(permit) => Ok(permit),
+            None => {
+                let permit = limits.admit(&skill_id).await;
+                limits.dequeue(&skill_id);
+                permit
+            }
+        };
+        match permit {
+            Ok(_permit) => {
+                if let Err(e) = run_isolated(&app, &skill_id, &script_name, &execution_id).await {
+                    eprintln!("Skill script run {} failed: {}", execution_id, e);
+                }
+            }
+            Err(e) => eprintln!("Skill script run {} failed: {}", execution_id, e),
+        }
+    });
+
+    Ok(submitted)
+}
+
+/// Run a skill script (Python, Node.js, etc.), waiting for a free run slot
+#[tauri::command]
+pub async fn run_skill_script(app: tauri::AppHandle, skill_id: String, script_name: String) -> Result<ScriptResult, String> {
+    crate::command_metrics::timed("run_skill_script", async move {
+        script_path(&skill_id, &script_name)?;
+
+        let limits = limits();
+        limits.enqueue(&skill_id);
+        let permit = limits.admit(&skill_id).await;
+        limits.dequeue(&skill_id);
+        let _permit = permit?;
+
+        let execution_id = uuid::Uuid::new_v4().to_string();
+        run_isolated(&app, &skill_id, &script_name, &execution_id).await
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_limits(per_skill: usize, global: usize) -> Limits {
+        Limits {
+            settings: ScriptRunSettings { per_skill, global, retry_after_secs: 1 },
+            global: Arc::new(Semaphore::new(global)),
+            per_skill: Mutex::new(HashMap::new()),
+            waiting: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[test]
+    fn test_admission_respects_skill_and_global_caps() {
+        let limits = test_limits(1, 2);
+
+        let first = limits.try_admit("alpha").expect("first alpha run");
+        assert!(limits.try_admit("alpha").is_none(), "per-skill cap");
+        let _beta = limits.try_admit("beta").expect("other skill still runs");
+        assert!(limits.try_admit("gamma").is_none(), "global cap");
+
+        drop(first);
+        assert!(limits.try_admit("alpha").is_some());
+
+        assert_eq!(limits.enqueue("alpha"), 1);
+        assert_eq!(limits.enqueue("alpha"), 2);
+        limits.dequeue("alpha");
+        assert_eq!(limits.enqueue("alpha"), 2);
+        assert_eq!(limits.enqueue("beta"), 1);
+    }
+
+    #[test]
+    fn test_paths_outside_the_skill_are_refused() {
+        for (skill_id, script_name) in [("..", "run.py"), ("../../tmp", "run.py"), ("a/b", "run.py"), ("skill", "../run.py"), ("skill", "..")] {
+            let err = script_path(skill_id, script_name).unwrap_err();
+            assert!(err.contains("not found"), "{}", err);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_script_reports_exit_code_and_stderr() {
+        let output = Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]).output().unwrap();
+
+        let result = script_result(&output, 0.1, "run-1");
+
+        assert!(!result.success);
+        assert_eq!(result.exit_code, Some(3));
+        assert_eq!(result.output, "out\n");
+        assert_eq!(result.error.as_deref(), Some("err\n"));
+    }
+}
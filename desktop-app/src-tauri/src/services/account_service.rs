@@ -59,9 +59,13 @@ impl AccountService {
 
     /// Add or update a saved account
     /// Uses email as unique key for upsert logic
-    pub fn add_account(app: &tauri::AppHandle, mut account: SavedAccount) -> Result<(), String> {
-        let mut accounts = Self::get_accounts(app)?;
+    pub fn add_account(app: &tauri::AppHandle, account: SavedAccount) -> Result<(), String> {
+        Self::update_accounts(app, |accounts| {
+            Self::upsert(accounts, account);
+        })
+    }
 
+    fn upsert(accounts: &mut Vec<SavedAccount>, mut account: SavedAccount) {
         // Generate UUID if not provided
         if account.id.is_empty() {
             account.id = Uuid::new_v4().to_string();
@@ -87,25 +91,22 @@ impl AccountService {
             // Add new account
             accounts.push(account);
         }
-
-        // Save to store
-        Self::save_accounts(app, &accounts)?;
-
-        Ok(())
     }
 
     /// Remove a saved account by ID
     pub fn remove_account(app: &tauri::AppHandle, account_id: &str) -> Result<(), String> {
-        let mut accounts = Self::get_accounts(app)?;
-        accounts.retain(|a| a.id != account_id);
-        Self::save_accounts(app, &accounts)?;
-        Ok(())
+        Self::update_accounts(app, |accounts| accounts.retain(|a| a.id != account_id))
     }
 
     /// Sync the currently active account
     /// Updates tier, planName, lastSeen; adds if doesn't exist
-    pub fn sync_current_account(app: &tauri::AppHandle, account: SavedAccount) -> Result<(), String> {
-        let mut accounts = Self::get_accounts(app)?;
+    /// Returns true when the account was newly added
+    pub fn sync_current_account(app: &tauri::AppHandle, account: SavedAccount) -> Result<bool, String> {
+        Self::update_accounts(app, |accounts| Self::sync_into(accounts, account))
+    }
+
+    fn sync_into(accounts: &mut Vec<SavedAccount>, account: SavedAccount) -> bool {
+        let mut added = false;
 
         if let Some(index) = accounts.iter().position(|a| a.email == account.email) {
             // Update existing account
@@ -135,18 +136,24 @@ impl AccountService {
                 last_seen: chrono::Utc::now().timestamp_millis(),
             };
             accounts.push(new_account);
+            added = true;
         }
 
-        Self::save_accounts(app, &accounts)?;
-        Ok(())
+        added
     }
 
-    /// Internal: Save accounts to store
-    fn save_accounts(app: &tauri::AppHandle, accounts: &[SavedAccount]) -> Result<(), String> {
-        let store: std::sync::Arc<tauri_plugin_store::Store<tauri::Wry>> = Self::get_store(app)?;
-        let json_value = serde_json::to_value(accounts).map_err(|e| e.to_string())?;
-        store.set(ACCOUNTS_KEY.to_string(), json_value);
-        store.save().map_err(|e| e.to_string())?;
-        Ok(())
+    /// Internal: Read-modify-write the account list under the store lock, so
+    /// a second app instance's changes are reloaded rather than overwritten
+    fn update_accounts<R>(app: &tauri::AppHandle, update: impl FnOnce(&mut Vec<SavedAccount>) -> R) -> Result<R, String> {
+        crate::instance_lock::update_store(app, |store| {
+            let mut accounts: Vec<SavedAccount> = store
+                .get(ACCOUNTS_KEY)
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default();
+            let result = update(&mut accounts);
+            let json_value = serde_json::to_value(&accounts).map_err(|e| e.to_string())?;
+            store.set(ACCOUNTS_KEY.to_string(), json_value);
+            Ok(result)
+        })
     }
 }
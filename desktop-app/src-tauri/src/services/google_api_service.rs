@@ -1,6 +1,7 @@
 /// Google API Service - Interact with Google OAuth and User Info APIs
 ///
-/// Handles user profile fetching and token refresh operations
+/// Handles user profile fetching and token refresh operations, through the
+/// shared HTTP client (see `http_client`)
 
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -42,10 +43,10 @@ pub struct GoogleApiService {
 
 impl GoogleApiService {
     /// Create new Google API service instance
-    pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-        }
+    pub fn new() -> Result<Self, String> {
+        Ok(Self {
+            client: crate::http_client::client()?,
+        })
     }
 
     /// Fetch user profile information from Google
@@ -177,12 +178,6 @@ impl GoogleApiService {
     }
 }
 
-impl Default for GoogleApiService {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
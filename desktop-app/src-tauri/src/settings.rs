@@ -0,0 +1,643 @@
+/// Settings - Typed app settings and settings.json validation
+///
+/// `AppSettings` is the schema for settings.json. `get_settings` returns it
+/// typed and `save_settings` takes it, checking values before anything
+/// touches disk (the API server port, a Python path that isn't only blanks, proxy URLs, API keys with
+/// a service and a key, one per service). Loading settings.json checks it
+/// against the schema too: wrong types and a theme other than dark or light
+/// are errors. Errors come as `{ message, errors }`, each error located by
+/// its JSON pointer. Unknown keys are only warnings, and are kept in
+/// `extra`, so settings from newer app versions survive a round-trip. A save
+/// that changes anything is announced with `settings-changed` (see
+/// `settings_events`).
+/// Files written with an older schema are migrated on load (see
+/// `settings_migration`); a failed migration is reported in `migration`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::agent_fallback::AgentFallbackSettings;
+use crate::antigravity::model_match::ModelAliases;
+use crate::antigravity::watcher::WatcherSettings;
+use crate::api_server::ApiSyncSettings;
+use crate::child_env::ChildEnvSettings;
+use crate::command_metrics::CommandMetricsSettings;
+use crate::gemini_budget::GeminiBudgetSettings;
+use crate::http_client::NetworkSettings;
+use crate::retention::RetentionSettings;
+use crate::script_runner::ScriptRunSettings;
+use crate::settings_migration::MigrationFailure;
+use crate::skill_archive::SkillArchiveLimits;
+use crate::task_process::TaskProcessSettings;
+use crate::task_progress::ProgressMarkerSettings;
+use crate::task_queue::TaskQueueSettings;
+use crate::temp_sweep::TempSweepSettings;
+use crate::workflow_history::WorkflowHistorySettings;
+use crate::workflow_snapshot::WorkflowSnapshotSettings;
+
+/// Version of the `AppSettings` layout; bump when a block is added, removed or retyped
+pub const SCHEMA_VERSION: u32 = 15;
+
+/// Lowest `apiServerPort` (below are privileged ports)
+const MIN_API_PORT: u16 = 1024;
+
+/// Stored API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyEntry {
+    pub service: String,
+    pub key: String,
+    #[serde(default)]
+    pub masked: String,
+}
+
+/// UI theme
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+pub(crate) const THEMES: &[&str] = &["dark", "light"];
+
+/// settings.json layout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    /// `SCHEMA_VERSION` the file was written with
+    pub schema_version: u32,
+    /// Interpreter command line (see `python_interpreter`), empty to detect one
+    pub python_path: String,
+    /// Location of vibe.py, empty for the bundled one or the one found (see `python_env`)
+    pub vibe_path: String,
+    pub theme: Theme,
+    pub api_keys: Vec<ApiKeyEntry>,
+    /// Port of the REST API server (see `api_server`), which moves when it changes
+    pub api_server_port: u16,
+    pub retention: RetentionSettings,
+    pub skill_archive: SkillArchiveLimits,
+    pub child_env: ChildEnvSettings,
+    pub antigravity_watcher: WatcherSettings,
+    pub api_sync: ApiSyncSettings,
+    pub model_aliases: ModelAliases,
+    pub script_runs: ScriptRunSettings,
+    pub temp_sweep: TempSweepSettings,
+    pub command_metrics: CommandMetricsSettings,
+    pub gemini_budget: GeminiBudgetSettings,
+    pub network: NetworkSettings,
+    /// Gitignore-style patterns hidden from the file explorer (on top of .gitignore files)
+    pub ignore_patterns: Vec<String>,
+    pub task_queue: TaskQueueSettings,
+    pub tasks: TaskProcessSettings,
+    pub agent_fallback: AgentFallbackSettings,
+    pub progress_markers: ProgressMarkerSettings,
+    pub workflow_history: WorkflowHistorySettings,
+    pub workflow_snapshots: WorkflowSnapshotSettings,
+    /// Keys this version doesn't know about, preserved as-is
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            python_path: String::new(),
+            vibe_path: String::new(),
+            theme: Theme::Dark,
+            api_keys: Vec::new(),
+            api_server_port: crate::api_server::API_PORT,
+            retention: RetentionSettings::default(),
+            skill_archive: SkillArchiveLimits::default(),
+            child_env: ChildEnvSettings::default(),
+            antigravity_watcher: WatcherSettings::default(),
+            api_sync: ApiSyncSettings::default(),
+            model_aliases: ModelAliases::new(),
+            script_runs: ScriptRunSettings::default(),
+            temp_sweep: TempSweepSettings::default(),
+            command_metrics: CommandMetricsSettings::default(),
+            gemini_budget: GeminiBudgetSettings::default(),
+            network: NetworkSettings::default(),
+            ignore_patterns: crate::ignore_rules::DEFAULT_IGNORE_PATTERNS.iter().map(|s| s.to_string()).collect(),
+            task_queue: TaskQueueSettings::default(),
+            tasks: TaskProcessSettings::default(),
+            agent_fallback: AgentFallbackSettings::default(),
+            progress_markers: ProgressMarkerSettings::default(),
+            workflow_history: WorkflowHistorySettings::default(),
+            workflow_snapshots: WorkflowSnapshotSettings::default(),
+            extra: BTreeMap::new(),
+        }
+    }
+}
+
+/// Problem found while validating settings, located by JSON pointer
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SettingsIssue {
+    pub pointer: String,
+    pub message: String,
+}
+
+/// Error of `get_settings` and `save_settings`, serialized as `{ message, errors }`
+#[derive(Debug, Serialize)]
+pub struct SettingsError {
+    pub message: String,
+    /// Invalid values, empty when the failure is not about one
+    pub errors: Vec<SettingsIssue>,
+    /// Set when settings.json could not be migrated to the current schema
+    pub migration: Option<MigrationFailure>,
+}
+
+impl SettingsError {
+    fn invalid(errors: Vec<SettingsIssue>) -> Self {
+        Self { message: format!("Invalid settings: {}", format_issues(&errors)), errors, migration: None }
+    }
+}
+
+impl std::fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<String> for SettingsError {
+    fn from(message: String) -> Self {
+        Self { message, errors: Vec::new(), migration: None }
+    }
+}
+
+impl From<MigrationFailure> for SettingsError {
+    fn from(failure: MigrationFailure) -> Self {
+        Self {
+            message: format!(
+                "Failed to migrate settings.json from v{} to v{}: {}. The file was left unchanged",
+                failure.from_version, failure.to_version, failure.message
+            ),
+            errors: Vec::new(),
+            migration: Some(failure),
+        }
+    }
+}
+
+/// Result of `save_settings`
+#[derive(Debug, Serialize)]
+pub struct SavedSettings {
+    /// What was actually stored (defaults filled in)
+    pub settings: AppSettings,
+    pub warnings: Vec<SettingsIssue>,
+}
+
+/// Expected shape of a settings value
+enum Shape {
+    String,
+    /// A string among these
+    OneOf(&'static [&'static str]),
+    Bool,
+    UInt,
+    Array(Box<Shape>),
+    /// Object with arbitrary keys and uniform values
+    Map(Box<Shape>),
+    Object(Vec<(&'static str, Shape)>),
+}
+
+impl Shape {
+    fn describe(&self) -> String {
+        match self {
+            Shape::String => "string".to_string(),
+            Shape::OneOf(values) => format!("one of {}", values.join(", ")),
+            Shape::Bool => "boolean".to_string(),
+            Shape::UInt => "non-negative integer".to_string(),
+            Shape::Array(_) => "array".to_string(),
+            Shape::Map(_) | Shape::Object(_) => "object".to_string(),
+        }
+    }
+}
+
+fn retention_policy_shape() -> Shape {
+    Shape::Object(vec![
+        ("maxTotalBytes", Shape::UInt),
+        ("maxAgeDays", Shape::UInt),
+    ])
+}
+
+/// Schema mirroring `AppSettings`
+fn settings_shape() -> Shape {
+    Shape::Object(vec![
+        ("schemaVersion", Shape::UInt),
+        ("pythonPath", Shape::String),
+        ("vibePath", Shape::String),
+        ("theme", Shape::OneOf(THEMES)),
+        (
+            "apiKeys",
+            Shape::Array(Box::new(Shape::Object(vec![
+                ("service", Shape::String),
+                ("key", Shape::String),
+                ("masked", Shape::String),
+            ]))),
+        ),
+        ("apiServerPort", Shape::UInt),
+        (
+            "retention",
+            Shape::Object(vec![
+                ("taskOutput", retention_policy_shape()),
+                ("workflowRuns", retention_policy_shape()),
+                ("snapshots", retention_policy_shape()),
+                ("journals", retention_policy_shape()),
+                ("quotaHistory", retention_policy_shape()),
+            ]),
+        ),
+        (
+            "skillArchive",
+            Shape::Object(vec![
+                ("maxFileBytes", Shape::UInt),
+                ("maxArchiveBytes", Shape::UInt),
+                ("maxEntries", Shape::UInt),
+            ]),
+        ),
+        (
+            "childEnv",
+            Shape::Object(vec![
+                ("vars", Shape::Map(Box::new(Shape::String))),
+                ("inherit", Shape::Array(Box::new(Shape::String))),
+            ]),
+        ),
+        (
+            "antigravityWatcher",
+            Shape::Object(vec![
+                ("enabled", Shape::Bool),
+                ("intervalSecs", Shape::UInt),
+                ("autoSyncOnConnect", Shape::Bool),
+            ]),
+        ),
+        (
+            "apiSync",
+            Shape::Object(vec![
+                ("deadlineSecs", Shape::UInt),
+                ("detectAttempts", Shape::UInt),
+                ("detectBaseDelayMs", Shape::UInt),
+                ("fetchAttempts", Shape::UInt),
+                ("fetchRetryDelayMs", Shape::UInt),
+            ]),
+        ),
+        ("modelAliases", Shape::Map(Box::new(Shape::Array(Box::new(Shape::String))))),
+        (
+            "scriptRuns",
+            Shape::Object(vec![
+                ("perSkill", Shape::UInt),
+                ("global", Shape::UInt),
+                ("retryAfterSecs", Shape::UInt),
+            ]),
+        ),
+        (
+            "tempSweep",
+            Shape::Object(vec![("maxAgeHours", Shape::UInt)]),
+        ),
+        (
+            "commandMetrics",
+            Shape::Object(vec![
+                ("slowThresholdMs", Shape::UInt),
+                ("window", Shape::UInt),
+            ]),
+        ),
+        (
+            "geminiBudget",
+            Shape::Object(vec![
+                ("maxInputTokens", Shape::UInt),
+                ("countTokens", Shape::Bool),
+                ("summarizeOverflow", Shape::Bool),
+                ("maxSummaryInputTokens", Shape::UInt),
+            ]),
+        ),
+        (
+            "network",
+            Shape::Object(vec![
+                ("httpProxy", Shape::String),
+                ("httpsProxy", Shape::String),
+                ("noProxy", Shape::String),
+                ("caCertPath", Shape::String),
+            ]),
+        ),
+        ("ignorePatterns", Shape::Array(Box::new(Shape::String))),
+        (
+            "taskQueue",
+            Shape::Object(vec![("maxConcurrency", Shape::UInt)]),
+        ),
+        (
+            "tasks",
+            Shape::Object(vec![
+                ("timeoutSecs", Shape::UInt),
+                ("inputTimeoutSecs", Shape::UInt),
+                ("combinedOutput", Shape::Bool),
+                ("maxOutputBytes", Shape::UInt),
+                ("killGraceSecs", Shape::UInt),
+            ]),
+        ),
+        (
+            "agentFallback",
+            Shape::Object(vec![
+                ("chain", Shape::Array(Box::new(Shape::String))),
+                ("patterns", Shape::Array(Box::new(Shape::String))),
+            ]),
+        ),
+        (
+            "progressMarkers",
+            Shape::Object(vec![
+                ("steps", Shape::Array(Box::new(Shape::String))),
+                ("percent", Shape::Array(Box::new(Shape::String))),
+                ("fileChanged", Shape::Array(Box::new(Shape::String))),
+            ]),
+        ),
+        (
+            "workflowHistory",
+            Shape::Object(vec![("maxRunsPerWorkflow", Shape::UInt)]),
+        ),
+        (
+            "workflowSnapshots",
+            Shape::Object(vec![("enabled", Shape::Bool)]),
+        ),
+    ])
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Escape a key for use in a JSON pointer (RFC 6901)
+fn pointer_segment(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn check(value: &Value, shape: &Shape, pointer: &str, errors: &mut Vec<SettingsIssue>, warnings: &mut Vec<SettingsIssue>) {
+    let matches = match (shape, value) {
+        (Shape::String, Value::String(_)) => true,
+        (Shape::OneOf(values), Value::String(s)) => values.contains(&s.as_str()),
+        (Shape::Bool, Value::Bool(_)) => true,
+        (Shape::UInt, Value::Number(n)) => n.is_u64(),
+        (Shape::Array(item), Value::Array(items)) => {
+            for (i, v) in items.iter().enumerate() {
+                check(v, item, &format!("{}/{}", pointer, i), errors, warnings);
+            }
+            true
+        }
+        (Shape::Map(item), Value::Object(map)) => {
+            for (key, v) in map {
+                check(v, item, &format!("{}/{}", pointer, pointer_segment(key)), errors, warnings);
+            }
+            true
+        }
+        (Shape::Object(fields), Value::Object(map)) => {
+            for (key, v) in map {
+                let child = format!("{}/{}", pointer, pointer_segment(key));
+                match fields.iter().find(|(name, _)| name == key) {
+                    Some((_, field_shape)) => check(v, field_shape, &child, errors, warnings),
+                    None => warnings.push(SettingsIssue {
+                        pointer: child,
+                        message: "Unknown key".to_string(),
+                    }),
+                }
+            }
+            true
+        }
+        _ => false,
+    };
+
+    if !matches {
+        let found = match value {
+            Value::String(s) if matches!(shape, Shape::OneOf(_)) => format!("\"{}\"", s),
+            _ => json_type(value).to_string(),
+        };
+        errors.push(SettingsIssue {
+            pointer: if pointer.is_empty() { "/".to_string() } else { pointer.to_string() },
+            message: format!("Expected {}, found {}", shape.describe(), found),
+        });
+    }
+}
+
+/// Validate a settings value, returning (errors, warnings)
+pub fn validate(value: &Value) -> (Vec<SettingsIssue>, Vec<SettingsIssue>) {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    check(value, &settings_shape(), "", &mut errors, &mut warnings);
+    (errors, warnings)
+}
+
+/// Values the types allow but the app can't use
+fn check_values(settings: &AppSettings) -> Vec<SettingsIssue> {
+    let issue = |pointer: String, message: &str| SettingsIssue { pointer, message: message.to_string() };
+    let mut errors = Vec::new();
+    if !settings.python_path.is_empty() && settings.python_path.trim().is_empty() {
+        errors.push(issue("/pythonPath".to_string(), "Must not be blank (leave it empty to detect Python)"));
+    }
+    if !settings.vibe_path.is_empty() && !Path::new(&settings.vibe_path).is_absolute() {
+        errors.push(issue("/vibePath".to_string(), "Must be an absolute path"));
+    }
+    for (pointer, proxy) in [("/network/httpProxy", &settings.network.http_proxy), ("/network/httpsProxy", &settings.network.https_proxy)] {
+        if !proxy.trim().is_empty() && crate::http_client::check_proxy_url(proxy).is_err() {
+            errors.push(issue(pointer.to_string(), "Not a valid proxy URL"));
+        }
+    }
+    let ca_cert_path = settings.network.ca_cert_path.trim();
+    if !ca_cert_path.is_empty() && !Path::new(ca_cert_path).is_absolute() {
+        errors.push(issue("/network/caCertPath".to_string(), "Must be an absolute path"));
+    }
+    if settings.api_server_port < MIN_API_PORT {
+        errors.push(issue("/apiServerPort".to_string(), &format!("Must be between {} and {}", MIN_API_PORT, u16::MAX)));
+    }
+    for (i, key) in settings.api_keys.iter().enumerate() {
+        if key.service.trim().is_empty() {
+            errors.push(issue(format!("/apiKeys/{}/service", i), "Must not be empty"));
+        } else if settings.api_keys[..i].iter().any(|other| other.service == key.service) {
+            errors.push(issue(format!("/apiKeys/{}/service", i), "Duplicate service"));
+        }
+        if key.key.trim().is_empty() {
+            errors.push(issue(format!("/apiKeys/{}/key", i), "Must not be empty"));
+        }
+    }
+    errors
+}
+
+fn format_issues(issues: &[SettingsIssue]) -> String {
+    issues
+        .iter()
+        .map(|i| format!("{}: {}", i.pointer, i.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Parse and validate settings.json content into normalized settings
+pub fn parse(content: &str) -> Result<(AppSettings, Vec<SettingsIssue>), SettingsError> {
+    let value: Value = serde_json::from_str(content)
+        .map_err(|e| format!("Invalid settings JSON: {}", e))?;
+    parse_value(value)
+}
+
+/// Validate parsed settings.json into normalized settings
+fn parse_value(value: Value) -> Result<(AppSettings, Vec<SettingsIssue>), SettingsError> {
+    let (errors, warnings) = validate(&value);
+    if !errors.is_empty() {
+        return Err(SettingsError::invalid(errors));
+    }
+
+    let settings: AppSettings = serde_json::from_value(value)
+        .map_err(|e| format!("Invalid settings: {}", e))?;
+
+    let errors = check_values(&settings);
+    if !errors.is_empty() {
+        return Err(SettingsError::invalid(errors));
+    }
+    Ok((settings, warnings))
+}
+
+/// Check settings before they are saved; the unknown keys as warnings
+pub fn check_settings(settings: &AppSettings) -> Result<Vec<SettingsIssue>, SettingsError> {
+    let value = serde_json::to_value(settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let (mut errors, warnings) = validate(&value);
+    errors.extend(check_values(settings));
+    if !errors.is_empty() {
+        return Err(SettingsError::invalid(errors));
+    }
+    Ok(warnings)
+}
+
+/// The settings stored at `path` as they are (defaults when unreadable)
+pub fn stored(path: &Path) -> AppSettings {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// The stored settings, migrated and validated (defaults when none are saved)
+pub fn load() -> Result<AppSettings, SettingsError> {
+    match crate::settings_migration::load(&crate::get_settings_path())? {
+        Some(value) => Ok(parse_value(value)?.0),
+        None => Ok(AppSettings::default()),
+    }
+}
+
+/// Get app settings (defaults for what settings.json doesn't set)
+#[tauri::command]
+pub async fn get_settings() -> Result<AppSettings, SettingsError> {
+    crate::command_metrics::timed("get_settings", async move { load() }).await
+}
+
+/// Validate and store `settings`, keeping the previous file as settings.json.bak
+/// (or as settings.json.bak.<version> when it was never migrated)
+pub fn save(settings: AppSettings) -> Result<SavedSettings, SettingsError> {
+    let warnings = check_settings(&settings)?;
+    let mut normalized = settings;
+    // Keep the version of a file written by a newer app
+    normalized.schema_version = normalized.schema_version.max(SCHEMA_VERSION);
+    for warning in &warnings {
+        eprintln!("Settings warning at {}: {}", warning.pointer, warning.message);
+    }
+
+    let settings_path = crate::get_settings_path();
+
+    // Create directory if it doesn't exist
+    if let Some(parent) = settings_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    // Another instance may be saving at the same time
+    let _lock = crate::instance_lock::lock("settings.json")?;
+
+    // What the subsystems run with now, to tell them what changed
+    let previous = stored(&settings_path);
+
+    if settings_path.exists() {
+        let backup = crate::settings_migration::pending_backup(&settings_path)
+            .unwrap_or_else(|| settings_path.with_extension("json.bak"));
+        std::fs::copy(&settings_path, backup)
+            .map_err(|e| format!("Failed to back up settings: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(&normalized)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    // Write to a temp file first so a crash never leaves a truncated settings.json
+    let tmp_path = settings_path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    std::fs::rename(&tmp_path, &settings_path)
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    crate::settings_events::publish(&previous, &normalized);
+
+    Ok(SavedSettings {
+        settings: normalized,
+        warnings,
+    })
+}
+
+/// Save app settings (see `save`)
+#[tauri::command]
+pub async fn save_settings(settings: AppSettings) -> Result<SavedSettings, SettingsError> {
+    crate::command_metrics::timed("save_settings", async move { save(settings) }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrong_types_are_rejected_with_pointer() {
+        let err = parse(r#"{"apiKeys": "oops", "retention": {"journals": {"maxAgeDays": -1}}}"#).unwrap_err().message;
+        assert!(err.contains("/apiKeys: Expected array, found string"), "{}", err);
+        assert!(err.contains("/retention/journals/maxAgeDays: Expected non-negative integer"), "{}", err);
+
+        let err = parse(r#"{"apiKeys": [{"service": "openai", "key": 42}]}"#).unwrap_err().message;
+        assert!(err.contains("/apiKeys/0/key: Expected string, found number"), "{}", err);
+    }
+
+    #[test]
+    fn test_unknown_keys_warn_and_defaults_are_filled() {
+        let (settings, warnings) = parse(r#"{"theme": "light", "fontSize": 14}"#).unwrap();
+
+        assert_eq!(warnings, vec![SettingsIssue {
+            pointer: "/fontSize".to_string(),
+            message: "Unknown key".to_string(),
+        }]);
+        assert_eq!(settings.theme, Theme::Light);
+        assert_eq!((settings.python_path.as_str(), settings.vibe_path.as_str()), ("", ""));
+        assert_eq!(settings.extra.get("fontSize"), Some(&Value::from(14)));
+
+        let normalized = serde_json::to_value(&settings).unwrap();
+        assert_eq!(normalized["fontSize"], 14);
+        assert!(normalized["retention"]["taskOutput"]["maxAgeDays"].is_u64());
+    }
+
+    #[test]
+    fn test_values_are_checked_field_by_field() {
+        let err = parse(r#"{"theme": "solarized", "apiServerPort": 80}"#).unwrap_err();
+        assert_eq!(err.errors, vec![SettingsIssue {
+            pointer: "/theme".to_string(),
+            message: "Expected one of dark, light, found \"solarized\"".to_string(),
+        }]);
+        let pointers = |err: SettingsError| err.errors.into_iter().map(|e| e.pointer).collect::<Vec<_>>();
+        let err = parse(r#"{"theme": "light", "apiServerPort": 80, "pythonPath": " "}"#).unwrap_err();
+        assert_eq!(pointers(err), vec!["/pythonPath", "/apiServerPort"]);
+        let err = parse(r#"{"network": {"httpProxy": "proxy.corp:8080", "httpsProxy": "http://bad host", "caCertPath": "ca.pem"}}"#).unwrap_err();
+        assert_eq!(pointers(err), vec!["/network/httpsProxy", "/network/caCertPath"]);
+
+        let (mut settings, _) = parse(r#"{"theme": "light", "newerAppOption": {"a": [1]}}"#).unwrap();
+        assert_eq!((settings.theme, settings.api_server_port), (Theme::Light, crate::api_server::API_PORT));
+        let key = |service: &str, key: &str| ApiKeyEntry { service: service.to_string(), key: key.to_string(), masked: String::new() };
+        settings.api_keys = vec![key("openai", "sk-1"), key("openai", "sk-2"), key("", "")];
+        assert_eq!(pointers(check_settings(&settings).unwrap_err()), vec!["/apiKeys/1/service", "/apiKeys/2/service", "/apiKeys/2/key"]);
+        settings.api_keys.truncate(1);
+        let warnings = check_settings(&settings).unwrap();
+        assert_eq!(warnings.iter().map(|w| w.pointer.as_str()).collect::<Vec<_>>(), vec!["/newerAppOption"]);
+        assert_eq!(serde_json::to_value(&settings).unwrap()["newerAppOption"]["a"][0], 1);
+    }
+}
@@ -0,0 +1,145 @@
+/// Settings Events - Tell long-lived subsystems that settings.json changed
+///
+/// After `save_settings` has written the file, `publish` compares the old and
+/// new settings and, when anything differs, emits `settings-changed` to the
+/// UI with the JSON pointers of the changed values (`/ignorePatterns`,
+/// `/tasks/timeoutSecs`; arrays count as one value, and no values are sent,
+/// so API keys never leave the backend) and sends the same change, with the new settings, on
+/// an internal broadcast channel. Subsystems that hold on to settings `listen`
+/// for the pointers they care about: the ignore rules rebuild the shared
+/// matcher (which is what the fs watcher filters with), the API server moves
+/// to a new `apiServerPort`, the Antigravity watcher re-reads its block
+/// without waiting out its interval, and the task queue starts tasks a higher
+/// `maxConcurrency` allows. Everything else, the scheduler included, loads
+/// its settings each time it uses them.
+
+use std::sync::{Arc, OnceLock};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::Emitter;
+use tokio::sync::broadcast;
+
+use crate::settings::AppSettings;
+
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// Changes a slow listener may fall behind by before it misses some
+const CHANNEL_CAPACITY: usize = 16;
+
+static APP: OnceLock<tauri::AppHandle> = OnceLock::new();
+static CHANNEL: OnceLock<broadcast::Sender<SettingsChanged>> = OnceLock::new();
+
+/// Payload of `settings-changed`, and what listeners receive
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsChanged {
+    /// JSON pointers of the values that changed
+    pub changed: Vec<String>,
+    /// The settings as saved (not sent to the UI)
+    #[serde(skip)]
+    pub settings: Arc<AppSettings>,
+}
+
+impl SettingsChanged {
+    /// Whether the value at `pointer`, or one inside it, changed
+    pub fn touches(&self, pointer: &str) -> bool {
+        self.changed.iter().any(|changed| {
+            changed.strip_prefix(pointer).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
+
+fn channel() -> &'static broadcast::Sender<SettingsChanged> {
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+/// Pointers of the values that differ between `old` and `new`
+fn diff(old: &Value, new: &Value, pointer: &str, changed: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                diff(old.get(key).unwrap_or(&Value::Null), new.get(key).unwrap_or(&Value::Null), &child, changed);
+            }
+        }
+        (old, new) if old != new => changed.push(pointer.to_string()),
+        _ => {}
+    }
+}
+
+/// Receive every settings change from now on
+pub fn subscribe() -> broadcast::Receiver<SettingsChanged> {
+    channel().subscribe()
+}
+
+/// Announce that the settings went from `old` to `new` (nothing when equal)
+pub fn publish(old: &AppSettings, new: &AppSettings) {
+    let (Ok(old_value), Ok(new_value)) = (serde_json::to_value(old), serde_json::to_value(new)) else { return };
+    let mut changed = Vec::new();
+    diff(&old_value, &new_value, "", &mut changed);
+    if changed.is_empty() {
+        return;
+    }
+
+    let change = SettingsChanged { changed, settings: Arc::new(new.clone()) };
+    if let Some(app) = APP.get() {
+        let _ = app.emit(SETTINGS_CHANGED_EVENT, &change);
+    }
+    // No receivers is not an error: nothing is listening yet
+    let _ = channel().send(change);
+}
+
+/// Call `handle` on the async runtime for every change touching one of `pointers`
+pub fn listen(pointers: &'static [&'static str], mut handle: impl FnMut(&SettingsChanged) + Send + 'static) {
+    let mut changes = subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match changes.recv().await {
+                Ok(change) if pointers.iter().any(|pointer| change.touches(pointer)) => handle(&change),
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    eprintln!("Settings events: a listener missed {} change(s)", missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Emit `settings-changed` to the UI from now on (called once at startup)
+pub fn init(app: tauri::AppHandle) {
+    let _ = APP.set(app);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_values_are_listed_by_pointer() {
+        let old = AppSettings::default();
+        let mut new = old.clone();
+        new.tasks.timeout_secs += 1;
+        new.ignore_patterns.push("*.log".to_string());
+        new.extra.insert("newer/option".to_string(), Value::from(true));
+
+        let mut changes = subscribe();
+        publish(&old, &new);
+        // Other tests publish on the same channel
+        let change = std::iter::from_fn(|| changes.try_recv().ok())
+            .find(|change| change.touches("/tasks"))
+            .unwrap();
+        assert_eq!(change.changed, vec!["/ignorePatterns", "/newer~1option", "/tasks/timeoutSecs"]);
+        assert!(change.touches("/tasks") && change.touches("/ignorePatterns"));
+        assert!(!change.touches("/task") && !change.touches("/tasks/killGraceSecs"));
+        assert_eq!(change.settings.ignore_patterns.last().map(String::as_str), Some("*.log"));
+        assert_eq!(serde_json::to_value(&change).unwrap(), serde_json::json!({ "changed": change.changed }));
+
+        let mut unchanged = Vec::new();
+        let value = serde_json::to_value(&old).unwrap();
+        diff(&value, &value, "", &mut unchanged);
+        assert!(unchanged.is_empty());
+    }
+}
@@ -0,0 +1,332 @@
+/// Settings Migration - Bring older settings.json files up to the current schema
+///
+/// settings.json records the schema it was written with in `schemaVersion`
+/// (files from before versioning have none and count as v0). Loading a file
+/// older than `settings::SCHEMA_VERSION` runs the pending steps of the
+/// registry in order on the parsed JSON, copies the original file to
+/// `settings.json.bak.<old version>` and writes the result back, all under the
+/// settings.json lock. Versions that only added blocks need no step; serde
+/// defaults fill those in. When a step fails nothing is written: the original
+/// stays as it was and the failure is returned with the versions involved, so
+/// the settings page can show it instead of quietly using defaults. A file
+/// from a newer app version is loaded as-is.
+
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::settings::{SettingsError, SCHEMA_VERSION};
+
+/// Key of the schema version in settings.json
+pub const VERSION_KEY: &str = "schemaVersion";
+
+/// Rewrites settings JSON of the previous version into the layout of the next
+pub type MigrationFn = fn(Value) -> Result<Value, String>;
+
+/// One step of the registry
+pub struct Migration {
+    /// Version the step produces; it runs for files older than this
+    pub version: u32,
+    pub run: MigrationFn,
+}
+
+/// Migration steps, oldest first
+fn migrations() -> Vec<Migration> {
+    vec![
+        Migration { version: 13, run: normalize_unversioned },
+        Migration { version: 14, run: split_vibe_path },
+    ]
+}
+
+/// A migration step that failed (nothing was written)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationFailure {
+    /// Version of the file on disk
+    pub from_version: u32,
+    /// Version of the step that failed
+    pub to_version: u32,
+    pub message: String,
+}
+
+/// Settings JSON brought up to date
+#[derive(Debug, PartialEq)]
+pub struct Migrated {
+    pub value: Value,
+    pub from_version: u32,
+    /// Versions of the steps that ran
+    pub applied: Vec<u32>,
+}
+
+/// `schemaVersion` of a settings value (0 when absent)
+pub fn version_of(value: &Value) -> Result<u32, String> {
+    match value.get(VERSION_KEY) {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| format!("Invalid {}: {}", VERSION_KEY, version)),
+    }
+}
+
+/// Where the file at `path` is kept before being migrated from `version`
+pub fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}.bak.{}", name, version))
+}
+
+/// Run the steps `value` is missing and stamp it with the current version
+pub fn migrate(value: Value) -> Result<Migrated, MigrationFailure> {
+    let from_version = version_of(&value).map_err(|message| MigrationFailure {
+        from_version: 0,
+        to_version: SCHEMA_VERSION,
+        message,
+    })?;
+    if from_version >= SCHEMA_VERSION {
+        return Ok(Migrated { value, from_version, applied: Vec::new() });
+    }
+
+    let mut value = value;
+    let mut applied = Vec::new();
+    for step in migrations().into_iter().filter(|m| m.version > from_version) {
+        value = (step.run)(value).map_err(|message| MigrationFailure {
+            from_version,
+            to_version: step.version,
+            message,
+        })?;
+        applied.push(step.version);
+    }
+    match value.as_object_mut() {
+        Some(settings) => settings.insert(VERSION_KEY.to_string(), Value::from(SCHEMA_VERSION)),
+        None => {
+            return Err(MigrationFailure {
+                from_version,
+                to_version: SCHEMA_VERSION,
+                message: "Settings are not an object".to_string(),
+            })
+        }
+    };
+    Ok(Migrated { value, from_version, applied })
+}
+
+fn read(path: &Path) -> Result<Option<Value>, SettingsError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read settings: {}", e).into()),
+    };
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Invalid settings.json: {}", e).into())
+}
+
+/// The versioned backup to take before overwriting the file at `path`, when
+/// it was never migrated (a failed migration, or written by an older app)
+pub fn pending_backup(path: &Path) -> Option<PathBuf> {
+    let version = read(path).ok().flatten().and_then(|value| version_of(&value).ok())?;
+    (version < SCHEMA_VERSION).then(|| backup_path(path, version))
+}
+
+fn load_in(lock_dir: &Path, path: &Path, writable: bool) -> Result<Option<Value>, SettingsError> {
+    let Some(value) = read(path)? else { return Ok(None) };
+    if version_of(&value).is_ok_and(|version| version >= SCHEMA_VERSION) {
+        return Ok(Some(value));
+    }
+    if !writable {
+        // A secondary instance uses the migrated settings without storing them
+        return Ok(Some(migrate(value)?.value));
+    }
+
+    // The other instance may have migrated the file in the meantime
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let _lock = crate::instance_lock::lock_in(lock_dir, &name)?;
+    let Some(value) = read(path)? else { return Ok(None) };
+    let migrated = migrate(value)?;
+    if migrated.from_version >= SCHEMA_VERSION {
+        return Ok(Some(migrated.value));
+    }
+
+    let backup = backup_path(path, migrated.from_version);
+    std::fs::copy(path, &backup)
+        .map_err(|e| format!("Failed to back up settings: {}", e))?;
+    let content = serde_json::to_string_pretty(&migrated.value)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    eprintln!(
+        "Settings: migrated {} from v{} to v{} (original kept as {})",
+        name,
+        migrated.from_version,
+        SCHEMA_VERSION,
+        backup.display()
+    );
+    Ok(Some(migrated.value))
+}
+
+/// Read settings.json at `path`, migrating it first when it is older than
+/// the current schema (None when no settings have been saved yet)
+pub fn load(path: &Path) -> Result<Option<Value>, SettingsError> {
+    let writable = crate::instance_lock::ensure_writable().is_ok();
+    load_in(&crate::get_app_config_dir(), path, writable)
+}
+
+/// v0 → v13: files written before settings were validated. Theme names are
+/// lowercased (unknown ones dropped for the default), an empty Python path
+/// is dropped for the default, and API keys without a service or key are
+/// removed; of several keys for one service the last one added is kept.
+fn normalize_unversioned(mut value: Value) -> Result<Value, String> {
+    let settings = value.as_object_mut().ok_or("Settings are not an object")?;
+
+    if let Some(theme) = settings.get("theme") {
+        let theme = theme.as_str().ok_or("theme is not a string")?.trim().to_lowercase();
+        if crate::settings::THEMES.contains(&theme.as_str()) {
+            settings.insert("theme".to_string(), Value::from(theme));
+        } else {
+            settings.remove("theme");
+        }
+    }
+
+    if settings.get("pythonPath").and_then(Value::as_str).is_some_and(|p| p.trim().is_empty()) {
+        settings.remove("pythonPath");
+    }
+
+    if let Some(keys) = settings.get_mut("apiKeys") {
+        let keys = keys.as_array_mut().ok_or("apiKeys is not a list")?;
+        let filled = |entry: &Value, field: &str| {
+            entry.get(field).and_then(Value::as_str).is_some_and(|s| !s.trim().is_empty())
+        };
+        let mut kept: Vec<Value> = Vec::new();
+        for entry in keys.drain(..) {
+            if !filled(&entry, "service") || !filled(&entry, "key") {
+                continue;
+            }
+            kept.retain(|other| other["service"] != entry["service"]);
+            kept.push(entry);
+        }
+        *keys = kept;
+    }
+    Ok(value)
+}
+
+/// v13 → v14: vibe.py has its own `vibePath`. A script at the end of
+/// `pythonPath` is taken off it; an absolute one moves to `vibePath`, a
+/// relative one, which depended on where the app was started, is dropped so
+/// that vibe.py is found instead. The old default, `python ../vibe.py`,
+/// becomes empty, so the interpreter is detected as well.
+fn split_vibe_path(mut value: Value) -> Result<Value, String> {
+    let settings = value.as_object_mut().ok_or("Settings are not an object")?;
+    let Some(python_path) = settings.get("pythonPath") else { return Ok(value) };
+    let python_path = python_path.as_str().ok_or("pythonPath is not a string")?.to_string();
+    let mut words = crate::python_interpreter::split_words(&python_path)?;
+    if !words.last().is_some_and(|word| word.to_lowercase().ends_with(".py")) {
+        return Ok(value);
+    }
+    let script = words.pop().unwrap_or_default();
+
+    if Path::new(&script).is_absolute() && !settings.contains_key("vibePath") {
+        settings.insert("vibePath".to_string(), Value::from(script.as_str()));
+    }
+    let interpreter = if words == ["python"] && script == "../vibe.py" {
+        String::new()
+    } else {
+        crate::python_interpreter::join_words(&words)
+    };
+    settings.insert("pythonPath".to_string(), Value::from(interpreter));
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// settings.json as the settings page wrote it before versioning
+    const V0_FIXTURE: &str = r#"{
+        "pythonPath": "  ",
+        "theme": "Light",
+        "apiKeys": [
+            {"service": "claude", "key": "sk-old", "masked": "sk-old...-old"},
+            {"service": "openai", "key": "", "masked": ""},
+            {"service": "claude", "key": "sk-new", "masked": "sk-new...-new"}
+        ],
+        "fontSize": 14
+    }"#;
+
+    #[test]
+    fn test_v0_fixture_migrates_to_current_schema() {
+        let migrated = migrate(serde_json::from_str(V0_FIXTURE).unwrap()).unwrap();
+        assert_eq!((migrated.from_version, migrated.applied.clone()), (0, vec![13, 14]));
+        assert_eq!(migrated.value[VERSION_KEY], SCHEMA_VERSION);
+        assert_eq!(migrated.value["theme"], "light");
+        assert!(migrated.value.get("pythonPath").is_none());
+        assert_eq!(migrated.value["apiKeys"].as_array().unwrap().len(), 1);
+        assert_eq!(migrated.value["apiKeys"][0]["key"], "sk-new");
+        assert_eq!(migrated.value["fontSize"], 14);
+
+        // The result loads cleanly, and migrating again changes nothing
+        let (settings, _) = crate::settings::parse(&migrated.value.to_string()).unwrap();
+        assert_eq!(settings.schema_version, SCHEMA_VERSION);
+        assert_eq!(settings.python_path, "");
+        let again = migrate(migrated.value.clone()).unwrap();
+        assert_eq!((again.value, again.applied), (migrated.value, Vec::new()));
+
+        let failure = migrate(serde_json::json!({"apiKeys": "sk-1"})).unwrap_err();
+        assert_eq!((failure.from_version, failure.to_version), (0, 13));
+        assert_eq!(failure.message, "apiKeys is not a list");
+        assert!(migrate(serde_json::json!({"schemaVersion": "two"})).unwrap_err().message.contains("Invalid schemaVersion"));
+    }
+
+    #[test]
+    fn test_script_is_split_off_the_python_path() {
+        let split = |python_path: &str| {
+            let value = serde_json::json!({ "schemaVersion": 13, "pythonPath": python_path });
+            let value = migrate(value).unwrap().value;
+            (value["pythonPath"].as_str().unwrap().to_string(), value.get("vibePath").cloned())
+        };
+        assert_eq!(split("python ../vibe.py"), (String::new(), None));
+        assert_eq!(split("python3 -X utf8 scripts/vibe.py"), ("python3 -X utf8".to_string(), None));
+        let script = if cfg!(windows) { r"D:\my app\vibe.py" } else { "/opt/my app/vibe.py" };
+        assert_eq!(
+            split(&format!(r#""/opt/py 3/bin/python" "{}""#, script)),
+            (r#""/opt/py 3/bin/python""#.to_string(), Some(Value::from(script)))
+        );
+        assert_eq!(split("python3"), ("python3".to_string(), None));
+    }
+
+    #[test]
+    fn test_file_is_backed_up_or_left_untouched() {
+        let dir = std::env::temp_dir().join(format!("vibecode-settings-migration-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.json");
+        assert_eq!(load_in(&dir, &path, true).unwrap(), None);
+
+        std::fs::write(&path, V0_FIXTURE).unwrap();
+        assert_eq!(pending_backup(&path), Some(dir.join("settings.json.bak.0")));
+        let loaded = load_in(&dir, &path, true).unwrap().unwrap();
+        assert_eq!(loaded[VERSION_KEY], SCHEMA_VERSION);
+        assert_eq!(std::fs::read_to_string(dir.join("settings.json.bak.0")).unwrap(), V0_FIXTURE);
+        let stored: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(stored, loaded);
+        assert_eq!(pending_backup(&path), None);
+
+        let broken = r#"{"schemaVersion": 3, "apiKeys": {"claude": "sk-1"}}"#;
+        std::fs::write(&path, broken).unwrap();
+        let err = load_in(&dir, &path, true).unwrap_err();
+        assert_eq!(err.migration, Some(MigrationFailure {
+            from_version: 3,
+            to_version: 13,
+            message: "apiKeys is not a list".to_string(),
+        }));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), broken);
+        assert!(!dir.join("settings.json.bak.3").exists());
+
+        // Read-only instances migrate in memory only
+        std::fs::write(&path, V0_FIXTURE).unwrap();
+        assert_eq!(load_in(&dir, &path, false).unwrap().unwrap()[VERSION_KEY], SCHEMA_VERSION);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), V0_FIXTURE);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
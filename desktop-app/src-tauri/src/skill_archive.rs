@@ -0,0 +1,783 @@
+/// Skill Archive - Streaming skill export/import as ZIP packages
+///
+/// File contents are copied through a fixed-size buffer in both directions, so
+/// skills carrying datasets or model files never have to fit in memory.
+/// Per-file, total size and entry-count limits come from the `skillArchive` settings block
+/// and are enforced on actual bytes copied, not only on the sizes an archive
+/// claims. Progress is emitted as `skill-archive-progress` events; exports
+/// also report through `operations::ProgressReporter` and can be cancelled.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use tauri::Emitter;
+
+const MB: u64 = 1024 * 1024;
+
+/// Copy buffer size
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Emit a progress event at least this often while copying a large file
+const PROGRESS_INTERVAL_BYTES: u64 = 8 * MB;
+
+/// `skillArchive` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SkillArchiveLimits {
+    pub max_file_bytes: u64,
+    pub max_archive_bytes: u64,
+    /// Most entries (files and folders) an archive may hold
+    pub max_entries: usize,
+}
+
+impl Default for SkillArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: 1024 * MB,
+            max_archive_bytes: 4096 * MB,
+            max_entries: 100_000,
+        }
+    }
+}
+
+impl SkillArchiveLimits {
+    /// Load the limits from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("skillArchive").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Errors from skill export/import
+#[derive(Debug)]
+pub enum SkillArchiveError {
+    NotFound(String),
+    AlreadyExists(String),
+    InvalidPackage(String),
+    UnsafePath(String),
+    FileTooLarge { path: String, limit: u64 },
+    ArchiveTooLarge { limit: u64 },
+    /// The package or destination changed since `preview_skill_import`
+    StalePreview(String),
+    /// Stopped by `cancel_operation`
+    Cancelled,
+    Io(String),
+}
+
+impl SkillArchiveError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "not_found",
+            Self::AlreadyExists(_) => "already_exists",
+            Self::InvalidPackage(_) => "invalid_package",
+            Self::UnsafePath(_) => "unsafe_path",
+            Self::FileTooLarge { .. } => "file_too_large",
+            Self::ArchiveTooLarge { .. } => "archive_too_large",
+            Self::StalePreview(_) => "stale_preview",
+            Self::Cancelled => "cancelled",
+            Self::Io(_) => "io",
+        }
+    }
+}
+
+impl fmt::Display for SkillArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(id) => write!(f, "Skill '{}' not found", id),
+            Self::AlreadyExists(id) => write!(f, "Skill '{}' already exists", id),
+            Self::InvalidPackage(msg) => write!(f, "Invalid skill package: {}", msg),
+            Self::UnsafePath(path) => write!(f, "Unsafe path in skill package: {}", path),
+            Self::FileTooLarge { path, limit } => {
+                write!(f, "File '{}' exceeds the per-file limit of {} MB", path, limit / MB)
+            }
+            Self::ArchiveTooLarge { limit } => {
+                write!(f, "Skill package exceeds the total size limit of {} MB", limit / MB)
+            }
+            Self::StalePreview(id) => {
+                write!(f, "Skill package for '{}' changed since it was previewed; preview it again", id)
+            }
+            Self::Cancelled => write!(f, "Operation cancelled"),
+            Self::Io(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<String> for SkillArchiveError {
+    fn from(msg: String) -> Self {
+        Self::Io(msg)
+    }
+}
+
+/// Serialized as `{ kind, message }` so the frontend can branch on `kind`
+impl Serialize for SkillArchiveError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SkillArchiveError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+/// Progress counters for one export/import
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ArchiveProgress {
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+/// Payload of `skill-archive-progress`
+#[derive(Debug, Clone, Serialize)]
+struct ArchiveProgressEvent<'a> {
+    operation: &'static str,
+    skill_id: &'a str,
+    #[serde(flatten)]
+    progress: &'a ArchiveProgress,
+}
+
+/// Export result containing file path
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportResult {
+    pub success: bool,
+    pub export_path: String,
+    pub file_size: u64,
+    pub skill_name: String,
+    pub version: String,
+}
+
+/// One skill that `export_skills` could not export
+#[derive(Debug, Serialize)]
+pub struct BulkExportFailure {
+    pub skill_id: String,
+    pub error: SkillArchiveError,
+}
+
+/// Bulk export result (partial when cancelled)
+#[derive(Debug, Serialize)]
+pub struct BulkExportResult {
+    pub operation_id: String,
+    pub exported: Vec<ExportResult>,
+    pub failed: Vec<BulkExportFailure>,
+    pub cancelled: bool,
+}
+
+/// Import result
+#[derive(Debug, Serialize)]
+pub struct ImportResult {
+    pub skill_id: String,
+    pub path: String,
+    pub files: usize,
+    pub bytes: u64,
+    /// Advisory naming findings (near-duplicates, cross-scope collisions)
+    pub warnings: Vec<crate::name_lint::NameIssue>,
+}
+
+/// Copy `reader` into `writer` through a fixed buffer, enforcing the limits
+///
+/// `progress.bytes_done` is advanced as bytes are copied; the per-file limit is
+/// checked against this file's bytes and the archive limit against the total.
+/// `cancelled` is polled once per chunk.
+fn copy_chunked(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    name: &str,
+    limits: &SkillArchiveLimits,
+    progress: &mut ArchiveProgress,
+    on_progress: &mut dyn FnMut(&ArchiveProgress),
+    cancelled: &dyn Fn() -> bool,
+) -> Result<u64, SkillArchiveError> {
+    let mut buffer = [0u8; CHUNK_SIZE];
+    let mut copied: u64 = 0;
+    let mut since_emit: u64 = 0;
+
+    loop {
+        if cancelled() {
+            return Err(SkillArchiveError::Cancelled);
+        }
+        let n = reader
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read '{}': {}", name, e))?;
+        if n == 0 {
+            break;
+        }
+
+        copied += n as u64;
+        progress.bytes_done += n as u64;
+        if copied > limits.max_file_bytes {
+            return Err(SkillArchiveError::FileTooLarge {
+                path: name.to_string(),
+                limit: limits.max_file_bytes,
+            });
+        }
+        if progress.bytes_done > limits.max_archive_bytes {
+            return Err(SkillArchiveError::ArchiveTooLarge {
+                limit: limits.max_archive_bytes,
+            });
+        }
+
+        writer
+            .write_all(&buffer[..n])
+            .map_err(|e| format!("Failed to write '{}': {}", name, e))?;
+
+        since_emit += n as u64;
+        if since_emit >= PROGRESS_INTERVAL_BYTES {
+            since_emit = 0;
+            on_progress(progress);
+        }
+    }
+
+    Ok(copied)
+}
+
+/// Collect every file under `dir` with its size, in a stable order
+pub fn collect_files(base: &Path, dir: &Path, files: &mut Vec<(PathBuf, String, u64)>) -> Result<(), String> {
+    let mut entries: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        .flatten()
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, files)?;
+        } else {
+            let relative = path
+                .strip_prefix(base)
+                .map_err(|e| e.to_string())?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            files.push((path, relative, size));
+        }
+    }
+    Ok(())
+}
+
+/// Write `skill_folder` into a ZIP at `archive_path`, stopping when `cancelled` returns true
+pub fn write_archive(
+    skill_folder: &Path,
+    archive_path: &Path,
+    limits: &SkillArchiveLimits,
+    on_progress: &mut dyn FnMut(&ArchiveProgress),
+    cancelled: &dyn Fn() -> bool,
+) -> Result<ArchiveProgress, SkillArchiveError> {
+    let mut files = Vec::new();
+    collect_files(skill_folder, skill_folder, &mut files)?;
+
+    // Reject early when the folder is already known to be over the limits
+    for (_, relative, size) in &files {
+        if *size > limits.max_file_bytes {
+            return Err(SkillArchiveError::FileTooLarge {
+                path: relative.clone(),
+                limit: limits.max_file_bytes,
+            });
+        }
+    }
+
+    let mut progress = ArchiveProgress {
+        files_total: files.len(),
+        bytes_total: files.iter().map(|(_, _, size)| size).sum(),
+        ..Default::default()
+    };
+    if progress.bytes_total > limits.max_archive_bytes {
+        return Err(SkillArchiveError::ArchiveTooLarge {
+            limit: limits.max_archive_bytes,
+        });
+    }
+    on_progress(&progress);
+
+    let file = File::create(archive_path)
+        .map_err(|e| format!("Failed to create export file: {}", e))?;
+    let mut zip = zip::ZipWriter::new(BufWriter::new(file));
+
+    for (path, relative, size) in &files {
+        let options = zip::write::FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .large_file(*size >= u32::MAX as u64);
+
+        zip.start_file(relative.as_str(), options)
+            .map_err(|e| format!("Failed to add '{}': {}", relative, e))?;
+
+        let mut reader = BufReader::new(
+            File::open(path).map_err(|e| format!("Failed to open '{}': {}", relative, e))?,
+        );
+        copy_chunked(&mut reader, &mut zip, relative, limits, &mut progress, on_progress, cancelled)?;
+
+        progress.files_done += 1;
+        on_progress(&progress);
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize ZIP: {}", e))?
+        .flush()
+        .map_err(|e| format!("Failed to finalize ZIP: {}", e))?;
+
+    Ok(progress)
+}
+
+/// Extract a skill ZIP into `dest` (which must not exist yet)
+pub fn extract_archive(
+    archive_path: &Path,
+    dest: &Path,
+    limits: &SkillArchiveLimits,
+    on_progress: &mut dyn FnMut(&ArchiveProgress),
+) -> Result<ArchiveProgress, SkillArchiveError> {
+    let file = File::open(archive_path)
+        .map_err(|e| format!("Failed to open skill package: {}", e))?;
+    let mut archive = zip::ZipArchive::new(BufReader::new(file))
+        .map_err(|e| SkillArchiveError::InvalidPackage(e.to_string()))?;
+    if archive.len() > limits.max_entries {
+        return Err(SkillArchiveError::InvalidPackage(format!("more than {} entries", limits.max_entries)));
+    }
+
+    // Validate entry names and declared sizes before writing anything
+    let mut progress = ArchiveProgress::default();
+    let mut has_skill_md = false;
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| SkillArchiveError::InvalidPackage(e.to_string()))?;
+        if entry.enclosed_name().is_none() {
+            return Err(SkillArchiveError::UnsafePath(entry.name().to_string()));
+        }
+        if entry.is_dir() {
+            continue;
+        }
+        if entry.size() > limits.max_file_bytes {
+            return Err(SkillArchiveError::FileTooLarge {
+                path: entry.name().to_string(),
+                limit: limits.max_file_bytes,
+            });
+        }
+        has_skill_md |= entry.name() == "SKILL.md";
+        progress.files_total += 1;
+        progress.bytes_total += entry.size();
+    }
+
+    if !has_skill_md {
+        return Err(SkillArchiveError::InvalidPackage("missing SKILL.md".to_string()));
+    }
+    if progress.bytes_total > limits.max_archive_bytes {
+        return Err(SkillArchiveError::ArchiveTooLarge {
+            limit: limits.max_archive_bytes,
+        });
+    }
+    on_progress(&progress);
+
+    std::fs::create_dir_all(dest)
+        .map_err(|e| format!("Failed to create skill folder: {}", e))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| SkillArchiveError::InvalidPackage(e.to_string()))?;
+        let name = entry.name().to_string();
+        let relative = entry
+            .enclosed_name()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| SkillArchiveError::UnsafePath(name.clone()))?;
+        let target = dest.join(relative);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)
+                .map_err(|e| format!("Failed to create '{}': {}", name, e))?;
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create '{}': {}", name, e))?;
+        }
+        let mut writer = BufWriter::new(
+            File::create(&target).map_err(|e| format!("Failed to create '{}': {}", name, e))?,
+        );
+        copy_chunked(&mut entry, &mut writer, &name, limits, &mut progress, on_progress, &|| false)?;
+        writer
+            .flush()
+            .map_err(|e| format!("Failed to write '{}': {}", name, e))?;
+
+        progress.files_done += 1;
+        on_progress(&progress);
+    }
+
+    Ok(progress)
+}
+
+/// Derive the skill id from an exported package name (`<id>_v<version>.zip`)
+pub fn skill_id_from_archive(archive_path: &Path) -> Option<String> {
+    let stem = archive_path.file_stem()?.to_string_lossy().to_string();
+    let id = match stem.rfind("_v") {
+        Some(idx) if stem[idx + 2..].starts_with(|c: char| c.is_ascii_digit()) => stem[..idx].to_string(),
+        _ => stem,
+    };
+    is_valid_skill_id(&id).then_some(id)
+}
+
+/// A skill id must be a single, non-hidden folder name
+pub fn is_valid_skill_id(id: &str) -> bool {
+    !id.is_empty() && !id.starts_with('.') && !id.contains(['/', '\\'])
+}
+
+fn emitter<'a>(app: &'a tauri::AppHandle, operation: &'static str, skill_id: &'a str) -> impl FnMut(&ArchiveProgress) + 'a {
+    move |progress| {
+        let _ = app.emit(
+            "skill-archive-progress",
+            ArchiveProgressEvent {
+                operation,
+                skill_id,
+                progress,
+            },
+        );
+    }
+}
+
+/// Export one skill to `<id>_v<version>.zip` in `skills_path` (blocking)
+///
+/// A failed or cancelled export leaves no ZIP behind.
+fn export_one(
+    skills_path: &Path,
+    skill_id: &str,
+    limits: &SkillArchiveLimits,
+    on_progress: &mut dyn FnMut(&ArchiveProgress),
+    cancelled: &dyn Fn() -> bool,
+) -> Result<ExportResult, SkillArchiveError> {
+    let skill_folder = skills_path.join(skill_id);
+    if !is_valid_skill_id(skill_id) || !skill_folder.exists() {
+        return Err(SkillArchiveError::NotFound(skill_id.to_string()));
+    }
+
+    // Get skill metadata
+    let skill_md = skill_folder.join("SKILL.md");
+    let (skill_name, version) = match crate::parse_skill_frontmatter(&skill_md) {
+        Ok(metadata) if !metadata.name.is_empty() => (metadata.name, metadata.version),
+        Ok(metadata) => (skill_id.to_string(), metadata.version),
+        Err(_) => (skill_id.to_string(), "1.0.0".to_string()),
+    };
+
+    let export_path = skills_path.join(format!("{}_v{}.zip", skill_id, version));
+    if let Err(e) = write_archive(&skill_folder, &export_path, limits, on_progress, cancelled) {
+        let _ = std::fs::remove_file(&export_path);
+        return Err(e);
+    }
+
+    let file_size = std::fs::metadata(&export_path)
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    Ok(ExportResult {
+        success: true,
+        export_path: export_path.to_string_lossy().to_string(),
+        file_size,
+        skill_name,
+        version,
+    })
+}
+
+/// Export skills one after another, reporting `done`/`total` in skills
+///
+/// Stops at the first cancellation check after `cancel_operation`; skills
+/// exported before that are kept and returned.
+fn export_many(
+    skills_path: &Path,
+    skill_ids: &[String],
+    limits: &SkillArchiveLimits,
+    reporter: &crate::operations::ProgressReporter,
+    on_progress: &mut dyn FnMut(&str, &ArchiveProgress),
+) -> BulkExportResult {
+    let mut result = BulkExportResult {
+        operation_id: reporter.id().to_string(),
+        exported: Vec::new(),
+        failed: Vec::new(),
+        cancelled: false,
+    };
+    let total = Some(skill_ids.len() as u64);
+
+    for (done, skill_id) in skill_ids.iter().enumerate() {
+        if reporter.is_cancelled() {
+            result.cancelled = true;
+            break;
+        }
+        reporter.report(done as u64, total, Some(format!("Exporting {}", skill_id)));
+
+        let exported = export_one(
+            skills_path,
+            skill_id,
+            limits,
+            &mut |progress| on_progress(skill_id, progress),
+            &|| reporter.is_cancelled(),
+        );
+        match exported {
+            Ok(exported) => result.exported.push(exported),
+            Err(SkillArchiveError::Cancelled) => {
+                result.cancelled = true;
+                break;
+            }
+            Err(error) => result.failed.push(BulkExportFailure {
+                skill_id: skill_id.clone(),
+                error,
+            }),
+        }
+    }
+
+    let done = (result.exported.len() + result.failed.len()) as u64;
+    reporter.report(done, total, Some(if result.cancelled { "Cancelled" } else { "Done" }.to_string()));
+    result
+}
+
+/// Export a skill as a ZIP package for sharing
+#[tauri::command]
+pub async fn export_skill(app: tauri::AppHandle, skill_id: String) -> Result<ExportResult, SkillArchiveError> {
+    crate::command_metrics::timed("export_skill", async move {
+        let skills_path = crate::get_skills_path();
+        if !skills_path.join(&skill_id).exists() {
+            return Err(SkillArchiveError::NotFound(skill_id));
+        }
+        let limits = SkillArchiveLimits::load();
+
+        tauri::async_runtime::spawn_blocking(move || {
+            let reporter = crate::operations::ProgressReporter::start(&app, crate::operations::KIND_SKILL_EXPORT);
+            let mut on_archive_progress = emitter(&app, "export", &skill_id);
+            let mut on_progress = |progress: &ArchiveProgress| {
+                on_archive_progress(progress);
+                reporter.report(progress.bytes_done, Some(progress.bytes_total), Some(skill_id.clone()));
+            };
+            export_one(&skills_path, &skill_id, &limits, &mut on_progress, &|| reporter.is_cancelled())
+        })
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))?
+    }).await
+}
+
+/// Export several skills as ZIP packages in one background operation
+///
+/// Progress is reported as `operation-progress` events; after `cancel_operation`
+/// the skills exported so far are returned with `cancelled: true`.
+#[tauri::command]
+pub async fn export_skills(app: tauri::AppHandle, skill_ids: Vec<String>) -> Result<BulkExportResult, SkillArchiveError> {
+    crate::command_metrics::timed("export_skills", async move {
+        let skills_path = crate::get_skills_path();
+        let limits = SkillArchiveLimits::load();
+
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            let reporter = crate::operations::ProgressReporter::start(&app, crate::operations::KIND_SKILL_EXPORT);
+            export_many(&skills_path, &skill_ids, &limits, &reporter, &mut |skill_id, progress| {
+                emitter(&app, "export", skill_id)(progress)
+            })
+        })
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))?;
+
+        Ok(result)
+    }).await
+}
+
+/// Import a skill ZIP package into the skills folder (refused if `preview_token` is stale)
+#[tauri::command]
+pub async fn import_skill(
+    app: tauri::AppHandle,
+    archive_path: String,
+    skill_id: Option<String>,
+    preview_token: Option<String>,
+) -> Result<ImportResult, SkillArchiveError> {
+    crate::command_metrics::timed("import_skill", async move {
+        let archive_path = PathBuf::from(archive_path);
+        let skill_id = match skill_id {
+            Some(id) if is_valid_skill_id(&id) => id,
+            Some(id) => return Err(SkillArchiveError::UnsafePath(id)),
+            None => skill_id_from_archive(&archive_path)
+                .ok_or_else(|| SkillArchiveError::InvalidPackage("cannot derive skill id from file name".to_string()))?,
+        };
+
+        let skills_path = crate::get_skills_path();
+        let skill_folder = skills_path.join(&skill_id);
+        if let Some(token) = &preview_token {
+            if !crate::skill_preview::import_token_matches(&archive_path, &skill_folder, token) {
+                return Err(SkillArchiveError::StalePreview(skill_id));
+            }
+        }
+        if skill_folder.exists() {
+            return Err(SkillArchiveError::AlreadyExists(skill_id));
+        }
+        let warnings = crate::name_lint::check_new_name(
+            crate::name_lint::KIND_SKILL,
+            crate::name_lint::current_skill_scope(),
+            &skill_id,
+            None,
+        );
+
+        // Extract next to the destination and rename, so a failed import leaves nothing behind
+        let staging = skills_path.join(format!(".{}.importing-{}", skill_id, uuid::Uuid::new_v4()));
+        let limits = SkillArchiveLimits::load();
+
+        let result = tauri::async_runtime::spawn_blocking({
+            let staging = staging.clone();
+            let skill_id = skill_id.clone();
+            move || {
+                let mut on_progress = emitter(&app, "import", &skill_id);
+                extract_archive(&archive_path, &staging, &limits, &mut on_progress)
+            }
+        })
+        .await
+        .map_err(|e| format!("Import task failed: {}", e))?;
+
+        let progress = match result {
+            Ok(progress) => progress,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Err(e);
+            }
+        };
+
+        if let Err(e) = std::fs::rename(&staging, &skill_folder) {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(format!("Failed to install skill: {}", e).into());
+        }
+
+        crate::skill_index::invalidate();
+
+        Ok(ImportResult {
+            skill_id,
+            path: skill_folder.to_string_lossy().to_string(),
+            files: progress.files_done,
+            bytes: progress.bytes_done,
+            warnings,
+        })
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(label: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("vibecode-skill-archive-{}-{}", label, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_round_trip_reports_progress() {
+        let root = temp_root("roundtrip");
+        let skill = root.join("demo");
+        std::fs::create_dir_all(skill.join("scripts")).unwrap();
+        std::fs::write(skill.join("SKILL.md"), "---\nname: demo\n---\n").unwrap();
+        std::fs::write(skill.join("scripts").join("run.py"), "print('hi')\n").unwrap();
+
+        let archive = root.join("demo_v1.0.0.zip");
+        let mut events = Vec::new();
+        let exported = write_archive(&skill, &archive, &SkillArchiveLimits::default(), &mut |p| events.push(p.clone()), &|| false).unwrap();
+        assert_eq!(exported.files_done, 2);
+        assert_eq!(events.last().unwrap().bytes_done, exported.bytes_total);
+
+        let dest = root.join("imported");
+        let imported = extract_archive(&archive, &dest, &SkillArchiveLimits::default(), &mut |_| {}).unwrap();
+        assert_eq!(imported.files_done, 2);
+        assert_eq!(
+            std::fs::read_to_string(dest.join("scripts").join("run.py")).unwrap(),
+            "print('hi')\n"
+        );
+        assert_eq!(skill_id_from_archive(&archive).as_deref(), Some("demo"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_limits_are_enforced() {
+        let root = temp_root("limits");
+        let skill = root.join("big");
+        std::fs::create_dir_all(&skill).unwrap();
+        std::fs::write(skill.join("SKILL.md"), "---\nname: big\n---\n").unwrap();
+        std::fs::write(skill.join("data.bin"), vec![7u8; 4096]).unwrap();
+
+        let archive = root.join("big.zip");
+        let tight = SkillArchiveLimits { max_file_bytes: 1024, max_archive_bytes: 1024 * 1024, ..Default::default() };
+        let err = write_archive(&skill, &archive, &tight, &mut |_| {}, &|| false).unwrap_err();
+        assert!(matches!(err, SkillArchiveError::FileTooLarge { ref path, .. } if path == "data.bin"));
+
+        write_archive(&skill, &archive, &SkillArchiveLimits::default(), &mut |_| {}, &|| false).unwrap();
+        let tight = SkillArchiveLimits { max_file_bytes: 1024 * 1024, max_archive_bytes: 2048, ..Default::default() };
+        let err = extract_archive(&archive, &root.join("out"), &tight, &mut |_| {}).unwrap_err();
+        assert!(matches!(err, SkillArchiveError::ArchiveTooLarge { .. }));
+        let tight = SkillArchiveLimits { max_entries: 1, ..Default::default() };
+        let err = extract_archive(&archive, &root.join("out"), &tight, &mut |_| {}).unwrap_err();
+        assert!(matches!(err, SkillArchiveError::InvalidPackage(_)));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_cancelled_bulk_export_stops_mid_zip() {
+        let root = temp_root("cancel");
+        for (id, size) in [("small", 1024), ("big", 64 * MB)] {
+            let skill = root.join(id);
+            std::fs::create_dir_all(&skill).unwrap();
+            std::fs::write(skill.join("SKILL.md"), format!("---\nname: {}\nversion: 1.0.0\n---\n", id)).unwrap();
+            File::create(skill.join("data.bin")).unwrap().set_len(size).unwrap();
+        }
+
+        let reporter = crate::operations::ProgressReporter::detached(crate::operations::KIND_SKILL_EXPORT);
+        let ids = vec!["small".to_string(), "big".to_string(), "missing".to_string()];
+        let mut cancelled_at = None;
+        let mut last_big = ArchiveProgress::default();
+        let result = export_many(&root, &ids, &SkillArchiveLimits::default(), &reporter, &mut |skill_id, progress| {
+            if skill_id == "big" && progress.bytes_done > 0 {
+                last_big = progress.clone();
+                if cancelled_at.is_none() {
+                    assert!(crate::operations::cancel(reporter.id()));
+                    cancelled_at = Some(std::time::Instant::now());
+                }
+            }
+        });
+
+        assert!(result.cancelled);
+        assert!(cancelled_at.unwrap().elapsed() < std::time::Duration::from_secs(2));
+        assert!(last_big.bytes_done < last_big.bytes_total);
+        assert_eq!(result.exported.iter().map(|e| e.skill_name.as_str()).collect::<Vec<_>>(), vec!["small"]);
+        assert!(result.failed.is_empty());
+        assert!(root.join("small_v1.0.0.zip").exists());
+        assert!(!root.join("big_v1.0.0.zip").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Peak resident set size in bytes (Linux only)
+    fn peak_rss() -> Option<u64> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+        let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kb * 1024)
+    }
+
+    /// Run with `cargo test -- --ignored`: exports and imports a 512 MB sparse file
+    #[test]
+    #[ignore]
+    fn test_large_skill_memory_stays_bounded() {
+        let root = temp_root("large");
+        let skill = root.join("dataset");
+        std::fs::create_dir_all(&skill).unwrap();
+        let skill_md = "---\nname: dataset\n---\n";
+        std::fs::write(skill.join("SKILL.md"), skill_md).unwrap();
+        File::create(skill.join("model.bin")).unwrap().set_len(512 * MB).unwrap();
+
+        let before = peak_rss();
+        let archive = root.join("dataset.zip");
+        write_archive(&skill, &archive, &SkillArchiveLimits::default(), &mut |_| {}, &|| false).unwrap();
+        let imported = extract_archive(&archive, &root.join("out"), &SkillArchiveLimits::default(), &mut |_| {}).unwrap();
+        assert_eq!(imported.bytes_done, 512 * MB + skill_md.len() as u64);
+
+        if let (Some(before), Some(after)) = (before, peak_rss()) {
+            assert!(after - before < 64 * MB, "peak RSS grew by {} MB", (after - before) / MB);
+        }
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,426 @@
+/// Skill Frontmatter - Lossless SKILL.md editing
+///
+/// Regenerating frontmatter from `SkillMetadata` drops every key the app does
+/// not know about (`owner:`, `compliance:`, ...). `SkillDocument` keeps the
+/// original lines instead: only the entries that are explicitly changed are
+/// rewritten, everything else (key order, comments, quoting, line endings and
+/// the markdown body) is written back byte for byte.
+
+use std::path::Path;
+use serde::Deserialize;
+
+/// One top-level frontmatter entry and the raw lines it spans
+#[derive(Debug, Clone)]
+struct Entry {
+    /// None for comment and blank lines between keys
+    key: Option<String>,
+    /// Raw lines including their line endings
+    lines: Vec<String>,
+}
+
+/// SKILL.md split into frontmatter entries and body
+#[derive(Debug, Clone)]
+pub struct SkillDocument {
+    /// None when the file has no frontmatter block
+    entries: Option<Vec<Entry>>,
+    /// Everything after the closing `---` line (or the whole file)
+    body: String,
+    newline: &'static str,
+}
+
+/// Key of a top-level `key: value` line
+fn top_level_key(line: &str) -> Option<&str> {
+    if line.starts_with(|c: char| c.is_whitespace() || c == '#' || c == '-') {
+        return None;
+    }
+    let (key, _) = line.split_once(':')?;
+    let valid = !key.is_empty()
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    valid.then_some(key)
+}
+
+fn is_delimiter(line: &str) -> bool {
+    line.trim_end() == "---"
+}
+
+/// Unquoted scalar that round-trips without quotes
+fn is_plain(value: &str) -> bool {
+    !value.is_empty()
+        && value.trim() == value
+        && !value.starts_with(|c: char| "\"'[]{}&*!|>%@`#-?:,".contains(c))
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.contains('\n')
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn unquote(raw: &str) -> String {
+    let raw = raw.trim();
+    if let Some(inner) = raw.strip_prefix('"').and_then(|r| r.strip_suffix('"')) {
+        inner.replace("\\\"", "\"").replace("\\\\", "\\")
+    } else if let Some(inner) = raw.strip_prefix('\'').and_then(|r| r.strip_suffix('\'')) {
+        inner.replace("''", "'")
+    } else {
+        // Unquoted values may carry a trailing comment
+        raw.split(" #").next().unwrap_or_default().trim().to_string()
+    }
+}
+
+impl SkillDocument {
+    pub fn parse(content: &str) -> Self {
+        let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+        let lines: Vec<&str> = content.split_inclusive('\n').collect();
+
+        let close = match lines.first() {
+            Some(first) if is_delimiter(first) => lines.iter().skip(1).position(|l| is_delimiter(l)).map(|i| i + 1),
+            _ => None,
+        };
+        let close = match close {
+            Some(close) => close,
+            None => {
+                return Self {
+                    entries: None,
+                    body: content.to_string(),
+                    newline,
+                }
+            }
+        };
+
+        let mut entries: Vec<Entry> = Vec::new();
+        for line in &lines[1..close] {
+            if let Some(key) = top_level_key(line) {
+                entries.push(Entry {
+                    key: Some(key.to_string()),
+                    lines: vec![line.to_string()],
+                });
+                continue;
+            }
+
+            // Indented lines and list items continue the previous key's value
+            let continues = line.starts_with([' ', '\t', '-']);
+            match entries.last_mut() {
+                Some(entry) if continues && entry.key.is_some() => entry.lines.push(line.to_string()),
+                _ => entries.push(Entry {
+                    key: None,
+                    lines: vec![line.to_string()],
+                }),
+            }
+        }
+
+        // The closing delimiter stays with the body so its exact form survives
+        Self {
+            entries: Some(entries),
+            body: lines[close..].concat(),
+            newline,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read SKILL.md: {}", e))?;
+        Ok(Self::parse(&content))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.render())
+            .map_err(|e| format!("Failed to update SKILL.md: {}", e))
+    }
+
+    pub fn render(&self) -> String {
+        match &self.entries {
+            Some(entries) => {
+                let mut out = format!("---{}", self.newline);
+                for entry in entries {
+                    out.extend(entry.lines.iter().map(String::as_str));
+                }
+                out.push_str(&self.body);
+                out
+            }
+            None => self.body.clone(),
+        }
+    }
+
+    fn entry(&self, key: &str) -> Option<&Entry> {
+        self.entries.iter().flatten().find(|e| e.key.as_deref() == Some(key))
+    }
+
+    /// Scalar value of a top-level key
+    pub fn get(&self, key: &str) -> Option<String> {
+        let entry = self.entry(key)?;
+        let value = entry.lines[0].split_once(':')?.1;
+        Some(unquote(value))
+    }
+
+    /// Set a scalar value, keeping the key's position and quoting style
+    pub fn set(&mut self, key: &str, value: &str) {
+        let newline = self.newline;
+        let existing_quote = self
+            .entry(key)
+            .and_then(|e| e.lines[0].split_once(':'))
+            .map(|(_, raw)| raw.trim_start().chars().next());
+
+        let rendered = match existing_quote {
+            Some(Some('\'')) => format!("'{}'", value.replace('\'', "''")),
+            Some(Some('"')) | None => quote(value),
+            _ if is_plain(value) => value.to_string(),
+            _ => quote(value),
+        };
+        let line = format!("{}: {}{}", key, rendered, newline);
+
+        if self.entries.is_none() {
+            self.entries = Some(Vec::new());
+            self.body = format!("---{}{}", newline, self.body);
+        }
+        let entries = self.entries.get_or_insert_with(Vec::new);
+        match entries.iter_mut().find(|e| e.key.as_deref() == Some(key)) {
+            Some(entry) => entry.lines = vec![line],
+            None => {
+                // Make sure the previous last line is terminated before appending
+                if let Some(last) = entries.last_mut().and_then(|e| e.lines.last_mut()) {
+                    if !last.ends_with('\n') {
+                        last.push_str(newline);
+                    }
+                }
+                entries.push(Entry {
+                    key: Some(key.to_string()),
+                    lines: vec![line],
+                });
+            }
+        }
+    }
+
+    /// Remove a top-level key and its value lines
+    pub fn remove(&mut self, key: &str) {
+        if let Some(entries) = &mut self.entries {
+            entries.retain(|e| e.key.as_deref() != Some(key));
+        }
+    }
+
+    /// Markdown body after the frontmatter
+    pub fn body(&self) -> &str {
+        match self.entries {
+            // Skip the closing delimiter line kept at the start of `body`
+            Some(_) => self.body.split_once('\n').map(|(_, rest)| rest).unwrap_or_default(),
+            None => &self.body,
+        }
+    }
+
+    fn set_body(&mut self, body: String) {
+        self.body = match self.entries {
+            Some(_) => {
+                let delimiter = self.body.split_inclusive('\n').next().unwrap_or("---\n").to_string();
+                delimiter + &body
+            }
+            None => body,
+        };
+    }
+
+    /// Replace the content under a markdown heading (e.g. "## Usage"), up to
+    /// the next heading of the same or a higher level; appended when missing
+    pub fn set_section(&mut self, heading: &str, content: &str) {
+        let newline = self.newline;
+        let level = heading.chars().take_while(|c| *c == '#').count();
+        let is_heading_at_most = |line: &str| {
+            let hashes = line.chars().take_while(|c| *c == '#').count();
+            hashes > 0 && hashes <= level && line[hashes..].starts_with(' ')
+        };
+
+        let lines: Vec<&str> = self.body().split_inclusive('\n').collect();
+        let mut section = format!("{}{}", heading, newline);
+        section.push_str(newline);
+        for line in content.trim_end().lines() {
+            section.push_str(line);
+            section.push_str(newline);
+        }
+
+        let body = match lines.iter().position(|l| l.trim_end() == heading) {
+            Some(start) => {
+                let end = lines[start + 1..]
+                    .iter()
+                    .position(|l| is_heading_at_most(l))
+                    .map(|i| start + 1 + i)
+                    .unwrap_or(lines.len());
+                if end < lines.len() {
+                    section.push_str(newline);
+                }
+                format!("{}{}{}", lines[..start].concat(), section, lines[end..].concat())
+            }
+            None => {
+                let mut body = lines.concat();
+                if !body.is_empty() {
+                    if !body.ends_with('\n') {
+                        body.push_str(newline);
+                    }
+                    body.push_str(newline);
+                }
+                body + &section
+            }
+        };
+        self.set_body(body);
+    }
+}
+
+/// Partial metadata update; absent fields are left untouched
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillMetadataPatch {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub category: Option<String>,
+}
+
+/// Apply a patch, also retitling the `# Name` heading when it matched the old name
+pub fn apply_patch(doc: &mut SkillDocument, patch: &SkillMetadataPatch) {
+    if let Some(name) = &patch.name {
+        let old_title = doc.get("name").map(|old| format!("# {}", old));
+        doc.set("name", name);
+
+        if let Some(old_title) = old_title {
+            let mut lines: Vec<String> = doc.body().split_inclusive('\n').map(String::from).collect();
+            if let Some(line) = lines.iter_mut().find(|l| l.trim_end() == old_title) {
+                let ending = &line[line.trim_end().len()..];
+                *line = format!("# {}{}", name, ending);
+                doc.set_body(lines.concat());
+            }
+        }
+    }
+    if let Some(description) = &patch.description {
+        doc.set("description", description);
+    }
+    if let Some(version) = &patch.version {
+        doc.set("version", version);
+    }
+    match patch.category.as_deref() {
+        Some("") => doc.remove("category"),
+        Some(category) => doc.set("category", category),
+        None => {}
+    }
+}
+
+/// Bump "major", "minor" or "patch" of a semantic version
+pub fn bump_version(version: &str, part: &str) -> Result<String, String> {
+    let mut numbers = version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .map(|n| n.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("Invalid version '{}'", version))?;
+    numbers.resize(3, 0);
+
+    match part {
+        "major" => numbers = vec![numbers[0] + 1, 0, 0],
+        "minor" => numbers = vec![numbers[0], numbers[1] + 1, 0],
+        "patch" => numbers[2] += 1,
+        _ => return Err(format!("Unknown version part '{}'", part)),
+    }
+
+    Ok(format!("{}.{}.{}", numbers[0], numbers[1], numbers[2]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXTRA_KEYS: &[&str] = &[
+        "owner: team-growth\n",
+        "compliance:\n  - gdpr\n  - soc2\n",
+        "# reviewed quarterly\n",
+        "reviewers: [\"an\", 'binh']\n",
+        "notes: >\n  multi-line\n  folded text\n",
+        "x-internal_id: 'abc: def'\n",
+        "\n",
+        "metadata:\n  nested:\n    depth: 2\n",
+    ];
+
+    /// Deterministic pseudo-random pick of extra keys around the known ones
+    fn generate(seed: u64) -> String {
+        let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let mut next = |n: usize| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) as usize % n
+        };
+
+        let mut frontmatter = String::new();
+        for known in ["name: \"Demo Skill\"\n", "description: Does things\n", "version: \"1.2.3\"\n"] {
+            for _ in 0..next(3) {
+                frontmatter.push_str(EXTRA_KEYS[next(EXTRA_KEYS.len())]);
+            }
+            frontmatter.push_str(known);
+        }
+        for _ in 0..next(3) {
+            frontmatter.push_str(EXTRA_KEYS[next(EXTRA_KEYS.len())]);
+        }
+
+        format!("---\n{}---\n\n# Demo Skill\n\nIntro.\n\n## Usage\n\nOld usage.\n\n## Examples\n\nKeep me.\n", frontmatter)
+    }
+
+    /// Lines that are not one of the known keys, in order
+    fn untouched_lines(content: &str) -> Vec<String> {
+        let doc = SkillDocument::parse(content);
+        doc.entries
+            .unwrap()
+            .into_iter()
+            .filter(|e| !matches!(e.key.as_deref(), Some("name" | "version")))
+            .flat_map(|e| e.lines)
+            .collect()
+    }
+
+    #[test]
+    fn test_extra_keys_survive_edits() {
+        for seed in 0..200 {
+            let original = generate(seed);
+            assert_eq!(SkillDocument::parse(&original).render(), original, "seed {}", seed);
+            let expected = untouched_lines(&original);
+
+            // Name change
+            let mut doc = SkillDocument::parse(&original);
+            apply_patch(&mut doc, &SkillMetadataPatch { name: Some("Renamed".to_string()), ..Default::default() });
+            let renamed = doc.render();
+            assert_eq!(untouched_lines(&renamed), expected, "seed {}", seed);
+            assert_eq!(SkillDocument::parse(&renamed).get("name").as_deref(), Some("Renamed"));
+            assert!(renamed.contains("\n# Renamed\n"));
+
+            // Version bump
+            let mut doc = SkillDocument::parse(&renamed);
+            let bumped = bump_version(&doc.get("version").unwrap(), "minor").unwrap();
+            doc.set("version", &bumped);
+            let bumped = doc.render();
+            assert_eq!(untouched_lines(&bumped), expected, "seed {}", seed);
+            assert!(bumped.contains("version: \"1.3.0\"\n"));
+
+            // Section regeneration
+            let mut doc = SkillDocument::parse(&bumped);
+            doc.set_section("## Usage", "New usage.");
+            let regenerated = doc.render();
+            assert_eq!(untouched_lines(&regenerated), expected, "seed {}", seed);
+            assert!(regenerated.contains("## Usage\n\nNew usage.\n\n## Examples\n\nKeep me.\n"));
+            assert!(!regenerated.contains("Old usage."));
+        }
+    }
+
+    #[test]
+    fn test_set_keeps_quoting_and_line_endings() {
+        let mut doc = SkillDocument::parse("---\r\nname: demo\r\nowner: 'ops'\r\n---\r\nBody\r\n");
+        doc.set("name", "demo two");
+        doc.set("owner", "it's me");
+        doc.set("version", "2.0.0");
+        assert_eq!(
+            doc.render(),
+            "---\r\nname: demo two\r\nowner: 'it''s me'\r\nversion: \"2.0.0\"\r\n---\r\nBody\r\n"
+        );
+        assert_eq!(doc.get("owner").as_deref(), Some("it's me"));
+
+        let mut doc = SkillDocument::parse("# No frontmatter\n");
+        doc.set("name", "x");
+        assert_eq!(doc.render(), "---\nname: \"x\"\n---\n# No frontmatter\n");
+
+        assert_eq!(bump_version("1.9.3", "major").unwrap(), "2.0.0");
+        assert_eq!(bump_version("1.9", "patch").unwrap(), "1.9.1");
+        assert!(bump_version("beta", "patch").is_err());
+    }
+}
@@ -0,0 +1,256 @@
+/// Skill Index - Cached skill list and sidebar facets
+///
+/// Parsing every SKILL.md on each `list_skills` call is wasteful, so the parsed
+/// list is cached per skills directory. The cache is invalidated explicitly by
+/// mutating commands and implicitly when the directory fingerprint (skill
+/// folders plus SKILL.md modification times) changes, so edits made outside
+/// the app are still picked up.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+use serde::Serialize;
+
+use crate::Skill;
+
+/// Cached skill list for one skills directory
+struct CachedSkills {
+    skills_path: PathBuf,
+    fingerprint: Vec<(String, Option<SystemTime>)>,
+    skills: Vec<Skill>,
+}
+
+static SKILLS_CACHE: RwLock<Option<CachedSkills>> = RwLock::new(None);
+
+/// Facet bucket with its count
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FacetCount {
+    pub name: String,
+    pub count: usize,
+}
+
+/// Aggregations for the Skills Manager sidebar
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillFacets {
+    pub total: usize,
+    pub categories: Vec<FacetCount>,
+    pub uncategorized: usize,
+    pub tags: Vec<FacetCount>,
+    pub untagged: usize,
+    pub with_scripts: usize,
+    pub without_scripts: usize,
+    pub archived: usize,
+    pub active: usize,
+}
+
+/// Cheap change detector: skill folder names and SKILL.md modification times
+fn fingerprint(skills_path: &Path) -> Vec<(String, Option<SystemTime>)> {
+    let mut entries: Vec<(String, Option<SystemTime>)> = std::fs::read_dir(skills_path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| {
+                    let modified = std::fs::metadata(e.path().join("SKILL.md"))
+                        .and_then(|m| m.modified())
+                        .ok();
+                    (e.file_name().to_string_lossy().to_string(), modified)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    entries.sort();
+    entries
+}
+
+/// Get the skill list for a directory, scanning only when the cache is stale
+pub fn get_or_scan<F>(skills_path: &Path, scan: F) -> Result<Vec<Skill>, String>
+where
+    F: FnOnce(&Path) -> Result<Vec<Skill>, String>,
+{
+    let current = fingerprint(skills_path);
+
+    if let Ok(cache) = SKILLS_CACHE.read() {
+        if let Some(cached) = cache.as_ref() {
+            if cached.skills_path == skills_path && cached.fingerprint == current {
+                return Ok(cached.skills.clone());
+            }
+        }
+    }
+
+    let skills = scan(skills_path)?;
+
+    let mut cache = SKILLS_CACHE.write().map_err(|e| format!("Lock error: {}", e))?;
+    *cache = Some(CachedSkills {
+        skills_path: skills_path.to_path_buf(),
+        fingerprint: current,
+        skills: skills.clone(),
+    });
+
+    Ok(skills)
+}
+
+/// Drop the cached skill list (and with it the facets)
+pub fn invalidate() {
+    if let Ok(mut cache) = SKILLS_CACHE.write() {
+        *cache = None;
+    }
+}
+
+/// Sort buckets by descending count, then name, so the UI doesn't jitter
+fn sorted_counts(counts: HashMap<String, usize>) -> Vec<FacetCount> {
+    let mut facets: Vec<FacetCount> = counts
+        .into_iter()
+        .map(|(name, count)| FacetCount { name, count })
+        .collect();
+    facets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    facets
+}
+
+/// Compute all facets in a single pass over the skill list
+pub fn compute_facets(skills: &[Skill]) -> SkillFacets {
+    let mut categories: HashMap<String, usize> = HashMap::new();
+    let mut tags: HashMap<String, usize> = HashMap::new();
+    let mut uncategorized = 0;
+    let mut untagged = 0;
+    let mut with_scripts = 0;
+    let mut archived = 0;
+
+    for skill in skills {
+        match skill.category.as_deref().map(str::trim) {
+            Some(category) if !category.is_empty() => {
+                *categories.entry(category.to_string()).or_insert(0) += 1;
+            }
+            _ => uncategorized += 1,
+        }
+
+        let mut tagged = false;
+        for tag in skill.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            *tags.entry(tag.to_string()).or_insert(0) += 1;
+            tagged = true;
+        }
+        if !tagged {
+            untagged += 1;
+        }
+
+        if skill.has_scripts {
+            with_scripts += 1;
+        }
+        if skill.archived {
+            archived += 1;
+        }
+    }
+
+    SkillFacets {
+        total: skills.len(),
+        categories: sorted_counts(categories),
+        uncategorized,
+        tags: sorted_counts(tags),
+        untagged,
+        with_scripts,
+        without_scripts: skills.len() - with_scripts,
+        archived,
+        active: skills.len() - archived,
+    }
+}
+
+/// Get category, tag, script and archive counts for the Skills Manager sidebar
+#[tauri::command]
+pub async fn get_skill_facets() -> Result<SkillFacets, String> {
+    crate::command_metrics::timed("get_skill_facets", async move {
+        let skills = crate::current_skills()?;
+        Ok(compute_facets(&skills))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(id: &str, category: Option<&str>, tags: &[&str], has_scripts: bool, archived: bool) -> Skill {
+        Skill {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            path: String::new(),
+            version: "1.0.0".to_string(),
+            category: category.map(String::from),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            archived,
+            has_scripts,
+            has_guardrails: false,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_facets_are_counted_and_stably_ordered() {
+        let skills = vec![
+            skill("a", Some("Marketing"), &["seo", "content"], true, false),
+            skill("b", Some("Marketing"), &["seo"], false, false),
+            skill("c", Some("Dev"), &[], true, true),
+            skill("d", None, &["content"], false, false),
+            skill("e", Some("Data"), &[], false, false),
+        ];
+
+        let facets = compute_facets(&skills);
+
+        assert_eq!(facets.total, 5);
+        assert_eq!(
+            facets.categories,
+            vec![
+                FacetCount { name: "Marketing".to_string(), count: 2 },
+                FacetCount { name: "Data".to_string(), count: 1 },
+                FacetCount { name: "Dev".to_string(), count: 1 },
+            ]
+        );
+        assert_eq!(facets.uncategorized, 1);
+        assert_eq!(
+            facets.tags,
+            vec![
+                FacetCount { name: "content".to_string(), count: 2 },
+                FacetCount { name: "seo".to_string(), count: 2 },
+            ]
+        );
+        assert_eq!(facets.untagged, 2);
+        assert_eq!(facets.with_scripts, 2);
+        assert_eq!(facets.without_scripts, 3);
+        assert_eq!(facets.archived, 1);
+        assert_eq!(facets.active, 4);
+    }
+
+    #[test]
+    fn test_cache_rescans_only_when_stale() {
+        let root = std::env::temp_dir().join(format!("vibecode-skill-index-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("alpha")).unwrap();
+        std::fs::write(root.join("alpha").join("SKILL.md"), "---\nname: alpha\n---\n").unwrap();
+
+        let mut scans = 0;
+        let mut scan = |_: &Path| {
+            scans += 1;
+            Ok(vec![skill("alpha", Some("Dev"), &["x"], false, false)])
+        };
+
+        let first = get_or_scan(&root, &mut scan).unwrap();
+        let second = get_or_scan(&root, &mut scan).unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 1);
+        assert_eq!(compute_facets(&second).categories[0].name, "Dev");
+
+        // A new skill folder changes the fingerprint
+        std::fs::create_dir_all(root.join("beta")).unwrap();
+        get_or_scan(&root, &mut scan).unwrap();
+
+        // Explicit invalidation forces a rescan too
+        invalidate();
+        get_or_scan(&root, &mut scan).unwrap();
+
+        drop(scan);
+        assert_eq!(scans, 3);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,430 @@
+/// Skill Preview - Dry runs for skill deletion and import
+///
+/// `preview_skill_delete` and `preview_skill_import` report exactly what the
+/// matching mutating command would touch, without writing anything. Each
+/// preview carries a `preview_token`: a digest of the source state it was
+/// computed from (file list, sizes and modification times). `delete_skill` and
+/// `import_skill` accept that token and refuse to run if the source changed
+/// since the preview, so the action always matches what the user saw.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::skill_archive::{self, SkillArchiveLimits};
+use crate::skill_frontmatter::SkillDocument;
+
+/// SKILL.md larger than this is not read for validation
+const MAX_SKILL_MD_BYTES: u64 = 1024 * 1024;
+
+/// One file that would be removed or written
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewFile {
+    /// Path relative to the skill folder, `/`-separated
+    pub path: String,
+    pub size: u64,
+}
+
+/// A skill or workflow that mentions the skill being deleted
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillReference {
+    /// "skill" | "workflow"
+    pub kind: String,
+    pub id: String,
+    pub path: String,
+}
+
+/// Validation result for an import preview
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewFinding {
+    /// "error" | "warning"
+    pub severity: String,
+    pub message: String,
+}
+
+/// Result of `preview_skill_delete`
+#[derive(Debug, Serialize)]
+pub struct DeletePreview {
+    pub skill_id: String,
+    pub path: String,
+    pub files: Vec<PreviewFile>,
+    pub total_bytes: u64,
+    pub references: Vec<SkillReference>,
+    pub preview_token: String,
+}
+
+/// Result of `preview_skill_import`
+#[derive(Debug, Serialize)]
+pub struct ImportPreview {
+    pub skill_id: Option<String>,
+    pub destination: Option<String>,
+    pub files: Vec<PreviewFile>,
+    pub total_bytes: u64,
+    /// Files that already exist at the destination
+    pub conflicts: Vec<String>,
+    pub findings: Vec<PreviewFinding>,
+    /// Advisory naming findings (near-duplicates, cross-scope collisions)
+    pub warnings: Vec<crate::name_lint::NameIssue>,
+    /// False when any finding is an error
+    pub can_import: bool,
+    pub preview_token: String,
+}
+
+fn finding(severity: &str, message: String) -> PreviewFinding {
+    PreviewFinding {
+        severity: severity.to_string(),
+        message,
+    }
+}
+
+fn modified_nanos(path: &Path) -> u128 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+fn digest(parts: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.finalize().iter().take(16).map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Files of a skill folder plus the token describing their current state
+fn scan_skill(skill_folder: &Path) -> Result<(Vec<PreviewFile>, String), String> {
+    let mut entries = Vec::new();
+    skill_archive::collect_files(skill_folder, skill_folder, &mut entries)?;
+
+    let mut parts = vec!["delete".to_string(), skill_folder.to_string_lossy().to_string()];
+    let files = entries
+        .into_iter()
+        .map(|(path, relative, size)| {
+            parts.push(format!("{}:{}:{}", relative, size, modified_nanos(&path)));
+            PreviewFile { path: relative, size }
+        })
+        .collect();
+    Ok((files, digest(&parts)))
+}
+
+/// True when `text` mentions `id` as a whole word (ids may contain `-` and `_`)
+fn mentions(text: &str, id: &str) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+    text.match_indices(id).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + id.len()..].chars().next();
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+/// Other skills (by SKILL.md) and workflows (by YAML) that mention `skill_id`
+fn find_references(skills_path: &Path, workflows_path: &Path, skill_id: &str) -> Vec<SkillReference> {
+    let mut references = Vec::new();
+
+    let mut skill_dirs: Vec<_> = std::fs::read_dir(skills_path)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect())
+        .unwrap_or_default();
+    skill_dirs.sort();
+    for dir in skill_dirs {
+        let id = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let skill_md = dir.join("SKILL.md");
+        if id == skill_id || id.starts_with('.') {
+            continue;
+        }
+        if std::fs::read_to_string(&skill_md).is_ok_and(|content| mentions(&content, skill_id)) {
+            references.push(SkillReference {
+                kind: "skill".to_string(),
+                id,
+                path: skill_md.to_string_lossy().to_string(),
+            });
+        }
+    }
+
+    let mut workflows: Vec<_> = std::fs::read_dir(workflows_path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "yaml" || ext == "yml"))
+                .collect()
+        })
+        .unwrap_or_default();
+    workflows.sort();
+    for path in workflows {
+        if std::fs::read_to_string(&path).is_ok_and(|content| mentions(&content, skill_id)) {
+            references.push(SkillReference {
+                kind: "workflow".to_string(),
+                id: path.file_stem().unwrap_or_default().to_string_lossy().to_string(),
+                path: path.to_string_lossy().to_string(),
+            });
+        }
+    }
+
+    references
+}
+
+/// Token for importing `archive_path` into `dest`
+fn import_token(archive_path: &Path, dest: Option<&Path>) -> String {
+    let size = std::fs::metadata(archive_path).map(|m| m.len()).unwrap_or(0);
+    digest(&[
+        "import".to_string(),
+        archive_path.to_string_lossy().to_string(),
+        size.to_string(),
+        modified_nanos(archive_path).to_string(),
+        dest.map(|d| format!("{}:{}", d.to_string_lossy(), d.exists())).unwrap_or_default(),
+    ])
+}
+
+/// Dry-run `import_skill` against `skills_path`
+fn preview_import(
+    archive_path: &Path,
+    skill_id: Option<String>,
+    skills_path: &Path,
+    limits: &SkillArchiveLimits,
+) -> ImportPreview {
+    let mut findings = Vec::new();
+    let skill_id = match skill_id {
+        Some(id) if skill_archive::is_valid_skill_id(&id) => Some(id),
+        Some(id) => {
+            findings.push(finding("error", format!("Invalid skill id '{}'", id)));
+            None
+        }
+        None => {
+            let derived = skill_archive::skill_id_from_archive(archive_path);
+            if derived.is_none() {
+                findings.push(finding("error", "Cannot derive skill id from file name".to_string()));
+            }
+            derived
+        }
+    };
+    let destination = skill_id.as_ref().map(|id| skills_path.join(id));
+
+    let mut files = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut total_bytes = 0;
+    let mut skill_md = None;
+    let mut opened = false;
+
+    let archive = File::open(archive_path)
+        .map_err(|e| format!("Failed to open skill package: {}", e))
+        .and_then(|f| zip::ZipArchive::new(BufReader::new(f)).map_err(|e| format!("Invalid skill package: {}", e)));
+    match archive {
+        Ok(mut archive) => {
+            opened = true;
+            for i in 0..archive.len() {
+                let mut entry = match archive.by_index(i) {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        findings.push(finding("error", format!("Invalid skill package: {}", e)));
+                        continue;
+                    }
+                };
+                let Some(relative) = entry.enclosed_name().map(Path::to_path_buf) else {
+                    findings.push(finding("error", format!("Unsafe path in skill package: {}", entry.name())));
+                    continue;
+                };
+                if entry.is_dir() {
+                    continue;
+                }
+
+                let name = entry.name().to_string();
+                if entry.size() > limits.max_file_bytes {
+                    findings.push(finding(
+                        "error",
+                        format!("File '{}' exceeds the per-file limit of {} bytes", name, limits.max_file_bytes),
+                    ));
+                }
+                if destination.as_ref().is_some_and(|dest| dest.join(&relative).exists()) {
+                    conflicts.push(name.clone());
+                }
+                if name == "SKILL.md" && entry.size() <= MAX_SKILL_MD_BYTES {
+                    let mut content = String::new();
+                    skill_md = Some(entry.read_to_string(&mut content).map(|_| content).ok());
+                }
+                total_bytes += entry.size();
+                files.push(PreviewFile { path: name, size: entry.size() });
+            }
+        }
+        Err(e) => findings.push(finding("error", e)),
+    }
+
+    match skill_md {
+        None if opened => findings.push(finding("error", "Invalid skill package: missing SKILL.md".to_string())),
+        Some(None) => findings.push(finding("warning", "SKILL.md is not valid UTF-8".to_string())),
+        Some(Some(content)) => {
+            let doc = SkillDocument::parse(&content);
+            for key in ["name", "description"] {
+                if doc.get(key).is_none_or(|v| v.trim().is_empty()) {
+                    findings.push(finding("warning", format!("SKILL.md frontmatter has no '{}'", key)));
+                }
+            }
+        }
+        None => {}
+    }
+    if total_bytes > limits.max_archive_bytes {
+        findings.push(finding(
+            "error",
+            format!("Skill package exceeds the total size limit of {} bytes", limits.max_archive_bytes),
+        ));
+    }
+    if let (Some(id), Some(dest)) = (&skill_id, &destination) {
+        if dest.exists() {
+            findings.push(finding("error", format!("Skill '{}' already exists", id)));
+        }
+    }
+
+    ImportPreview {
+        can_import: !findings.iter().any(|f| f.severity == "error"),
+        preview_token: import_token(archive_path, destination.as_deref()),
+        destination: destination.map(|d| d.to_string_lossy().to_string()),
+        skill_id,
+        files,
+        total_bytes,
+        conflicts,
+        findings,
+        warnings: Vec::new(),
+    }
+}
+
+/// Fail unless `token` still matches the skill folder's current state
+pub fn verify_delete_token(skill_folder: &Path, token: &str) -> Result<(), String> {
+    let (_, current) = scan_skill(skill_folder)?;
+    if current != token {
+        return Err("Skill changed since it was previewed; preview it again".to_string());
+    }
+    Ok(())
+}
+
+/// True when `token` still matches the archive and destination state
+pub fn import_token_matches(archive_path: &Path, dest: &Path, token: &str) -> bool {
+    import_token(archive_path, Some(dest)) == token
+}
+
+/// Preview what `delete_skill` would remove
+#[tauri::command]
+pub async fn preview_skill_delete(skill_id: String) -> Result<DeletePreview, String> {
+    crate::command_metrics::timed("preview_skill_delete", async move {
+        let skill_folder = crate::get_skills_path().join(&skill_id);
+        if !skill_archive::is_valid_skill_id(&skill_id) || !skill_folder.is_dir() {
+            return Err(format!("Skill '{}' not found", skill_id));
+        }
+
+        let (files, preview_token) = scan_skill(&skill_folder)?;
+        Ok(DeletePreview {
+            references: find_references(&crate::get_skills_path(), &crate::get_workflows_path(), &skill_id),
+            path: skill_folder.to_string_lossy().to_string(),
+            total_bytes: files.iter().map(|f| f.size).sum(),
+            skill_id,
+            files,
+            preview_token,
+        })
+    }).await
+}
+
+/// Preview what `import_skill` would write
+#[tauri::command]
+pub async fn preview_skill_import(archive_path: String, skill_id: Option<String>) -> Result<ImportPreview, String> {
+    crate::command_metrics::timed("preview_skill_import", async move {
+        let archive_path = std::path::PathBuf::from(archive_path);
+        let skills_path = crate::get_skills_path();
+        let limits = SkillArchiveLimits::load();
+
+        let mut preview = tauri::async_runtime::spawn_blocking(move || {
+            preview_import(&archive_path, skill_id, &skills_path, &limits)
+        })
+        .await
+        .map_err(|e| format!("Preview task failed: {}", e))?;
+
+        if let Some(id) = &preview.skill_id {
+            preview.warnings = crate::name_lint::check_new_name(
+                crate::name_lint::KIND_SKILL,
+                crate::name_lint::current_skill_scope(),
+                id,
+                None,
+            );
+        }
+        Ok(preview)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_root(label: &str) -> std::path::PathBuf {
+        let root = std::env::temp_dir().join(format!("vibecode-preview-{}-{}", label, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_delete_preview_lists_files_references_and_detects_changes() {
+        let root = temp_root("delete");
+        let skills = root.join("skills");
+        let workflows = root.join("workflows");
+        std::fs::create_dir_all(skills.join("pdf-tools/scripts")).unwrap();
+        std::fs::create_dir_all(skills.join("reporter")).unwrap();
+        std::fs::create_dir_all(&workflows).unwrap();
+        std::fs::write(skills.join("pdf-tools/SKILL.md"), "---\nname: PDF Tools\n---\n").unwrap();
+        std::fs::write(skills.join("pdf-tools/scripts/run.py"), "print(1)").unwrap();
+        std::fs::write(skills.join("reporter/SKILL.md"), "Uses pdf-tools for output").unwrap();
+        std::fs::write(workflows.join("report.yaml"), "skill: pdf-tools-extra\n").unwrap();
+
+        let (files, token) = scan_skill(&skills.join("pdf-tools")).unwrap();
+        assert_eq!(files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(), vec!["SKILL.md", "scripts/run.py"]);
+        assert_eq!(files[1].size, 8);
+
+        let references = find_references(&skills, &workflows, "pdf-tools");
+        assert_eq!(references.len(), 1, "pdf-tools-extra is a different id");
+        assert_eq!((references[0].kind.as_str(), references[0].id.as_str()), ("skill", "reporter"));
+
+        assert!(verify_delete_token(&skills.join("pdf-tools"), &token).is_ok());
+        std::fs::write(skills.join("pdf-tools/notes.txt"), "new").unwrap();
+        assert!(verify_delete_token(&skills.join("pdf-tools"), &token).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_import_preview_reports_conflicts_and_findings() {
+        let root = temp_root("import");
+        let skills = root.join("skills");
+        std::fs::create_dir_all(skills.join("charts")).unwrap();
+        std::fs::write(skills.join("charts/SKILL.md"), "old").unwrap();
+
+        let archive_path = root.join("charts_v1.0.0.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&archive_path).unwrap());
+        let options = zip::write::FileOptions::default();
+        zip.start_file("SKILL.md", options).unwrap();
+        zip.write_all(b"---\nname: Charts\n---\n# Charts\n").unwrap();
+        zip.start_file("data/points.csv", options).unwrap();
+        zip.write_all(b"1,2\n").unwrap();
+        zip.finish().unwrap();
+
+        let limits = SkillArchiveLimits::default();
+        let preview = preview_import(&archive_path, None, &skills, &limits);
+        assert_eq!(preview.skill_id.as_deref(), Some("charts"));
+        assert_eq!(preview.files.len(), 2);
+        assert_eq!(preview.conflicts, vec!["SKILL.md"]);
+        assert!(!preview.can_import);
+        assert!(preview.findings.iter().any(|f| f.message.contains("no 'description'")));
+        assert!(skills.join("charts/SKILL.md").exists(), "preview never writes");
+
+        let fresh = preview_import(&archive_path, Some("charts2".to_string()), &skills, &limits);
+        assert!(fresh.can_import && fresh.conflicts.is_empty());
+        let dest = skills.join("charts2");
+        assert!(import_token_matches(&archive_path, &dest, &fresh.preview_token));
+        std::fs::create_dir_all(&dest).unwrap();
+        assert!(!import_token_matches(&archive_path, &dest, &fresh.preview_token));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
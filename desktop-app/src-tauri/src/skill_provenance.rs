@@ -0,0 +1,160 @@
+/// Skill Provenance - Which settings, template and provider produced a skill
+///
+/// Every Skill Factory generation is recorded in `generation.json` next to
+/// SKILL.md: provider, model, output language, prompt template id and hash,
+/// detected domain, research sources, token usage and the app version.
+/// Records are only ever appended, so regenerating a section adds an entry
+/// tagged with its heading and the file becomes the skill's generation
+/// history. The SKILL.md footer points here instead of naming a model.
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{ResearchSource, SkillDomain};
+
+/// File written next to SKILL.md
+pub const PROVENANCE_FILE: &str = "generation.json";
+
+/// Token counts from Gemini's `usageMetadata`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Read `usageMetadata` from a generateContent response (None when absent)
+    pub fn from_response(response: &serde_json::Value) -> Option<Self> {
+        let usage = response.get("usageMetadata")?;
+        let count = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        Some(Self {
+            prompt_tokens: count("promptTokenCount"),
+            output_tokens: count("candidatesTokenCount"),
+            total_tokens: count("totalTokenCount"),
+        })
+    }
+}
+
+/// One generation (whole skill or a single section)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationRecord {
+    pub generated_at: String, // ISO 8601
+    pub provider: String,
+    pub model: String,
+    pub language: String,
+    pub prompt_template_id: String,
+    pub prompt_template_hash: String,
+    pub domain: SkillDomain,
+    #[serde(default)]
+    pub research_sources: Vec<ResearchSource>,
+    pub token_usage: Option<TokenUsage>,
+    pub app_version: String,
+    /// Heading of a regenerated section, None for a whole-skill generation
+    #[serde(default)]
+    pub section: Option<String>,
+}
+
+/// `generation.json` layout, oldest generation first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillProvenance {
+    #[serde(default)]
+    pub generations: Vec<GenerationRecord>,
+}
+
+/// Short sha256 of a prompt template's source text
+pub fn template_hash(template: &str) -> String {
+    Sha256::digest(template.as_bytes()).iter().take(8).map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SKILL.md footer line pointing at the provenance file
+pub fn footer_reference() -> String {
+    format!("**Generated by:** Vibecode AI Skill Factory (chi tiết provider/model: `{}`)", PROVENANCE_FILE)
+}
+
+/// Load a skill's provenance; a skill without one has an empty history
+pub fn load(skill_folder: &Path) -> Result<SkillProvenance, String> {
+    let path = skill_folder.join(PROVENANCE_FILE);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(SkillProvenance::default()),
+        Err(e) => return Err(format!("Failed to read {}: {}", PROVENANCE_FILE, e)),
+    };
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid {}: {}", PROVENANCE_FILE, e))
+}
+
+/// Append a record to the skill's history (temp file + rename)
+pub fn append(skill_folder: &Path, record: GenerationRecord) -> Result<(), String> {
+    let mut provenance = load(skill_folder)?;
+    provenance.generations.push(record);
+
+    let content = serde_json::to_string_pretty(&provenance)
+        .map_err(|e| format!("Failed to serialize {}: {}", PROVENANCE_FILE, e))?;
+    let path = skill_folder.join(PROVENANCE_FILE);
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to save {}: {}", PROVENANCE_FILE, e))?;
+    std::fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Failed to save {}: {}", PROVENANCE_FILE, e))
+}
+
+/// Get the generation history of a skill (empty for hand-written skills)
+#[tauri::command]
+pub async fn get_skill_provenance(skill_id: String) -> Result<SkillProvenance, String> {
+    crate::command_metrics::timed("get_skill_provenance", async move {
+        let skill_folder = crate::get_skills_path().join(&skill_id);
+
+        if !skill_folder.exists() {
+            return Err(format!("Skill '{}' not found", skill_id));
+        }
+
+        load(&skill_folder)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(section: Option<&str>) -> GenerationRecord {
+        GenerationRecord {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            provider: "gemini".to_string(),
+            model: "gemini-1.5-flash".to_string(),
+            language: "vi".to_string(),
+            prompt_template_id: "skill-factory/v1".to_string(),
+            prompt_template_hash: template_hash("{name}"),
+            domain: SkillDomain::General,
+            research_sources: Vec::new(),
+            token_usage: TokenUsage::from_response(&serde_json::json!({
+                "usageMetadata": { "promptTokenCount": 120, "candidatesTokenCount": 800, "totalTokenCount": 920 }
+            })),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            section: section.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_regenerations_append_to_the_history() {
+        let dir = std::env::temp_dir().join(format!("vibecode-provenance-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert!(load(&dir).unwrap().generations.is_empty());
+
+        append(&dir, record(None)).unwrap();
+        append(&dir, record(Some("## Usage"))).unwrap();
+
+        let history = load(&dir).unwrap().generations;
+        assert_eq!(history.len(), 2);
+        assert!(history[0].section.is_none());
+        assert_eq!(history[1].section.as_deref(), Some("## Usage"));
+        assert_eq!(history[0].token_usage.as_ref().map(|u| u.total_tokens), Some(920));
+        assert_eq!(history[0].prompt_template_hash.len(), 16);
+        assert!(!dir.join("generation.json.tmp").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,244 @@
+/// Startup Orchestrator - Ordered initialization of app subsystems
+///
+/// Runs settings → Python env → project restore → watchers → API server →
+/// background pollers in order, records the outcome of each step in managed
+/// state, and emits an `app-ready` event once everything has been attempted.
+/// A failing subsystem never blocks the ones after it; the failure is kept in
+/// the status instead.
+///
+/// When another instance already holds the primary lock this one starts as a
+/// read-only secondary: watchers, the API server and pollers are skipped.
+
+use std::sync::RwLock;
+use std::time::Instant;
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+use crate::instance_lock::InstanceMode;
+
+pub const SUBSYSTEM_SETTINGS: &str = "settings";
+pub const SUBSYSTEM_PYTHON: &str = "python";
+pub const SUBSYSTEM_PROJECT: &str = "project";
+pub const SUBSYSTEM_WATCHERS: &str = "watchers";
+pub const SUBSYSTEM_API_SERVER: &str = "api_server";
+pub const SUBSYSTEM_POLLERS: &str = "background_pollers";
+
+/// Initialization order
+const SUBSYSTEMS: [&str; 6] = [
+    SUBSYSTEM_SETTINGS,
+    SUBSYSTEM_PYTHON,
+    SUBSYSTEM_PROJECT,
+    SUBSYSTEM_WATCHERS,
+    SUBSYSTEM_API_SERVER,
+    SUBSYSTEM_POLLERS,
+];
+
+/// Lifecycle state of a single subsystem
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubsystemState {
+    Pending,
+    Ready,
+    Failed,
+    Skipped,
+}
+
+/// Status of one subsystem for the splash screen
+#[derive(Debug, Clone, Serialize)]
+pub struct SubsystemStatus {
+    pub name: String,
+    pub state: SubsystemState,
+    pub message: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Overall startup status (payload of `app-ready`)
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupStatus {
+    pub ready: bool,
+    pub instance_mode: InstanceMode,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub subsystems: Vec<SubsystemStatus>,
+}
+
+/// Managed state holding the startup status
+pub struct StartupState {
+    status: RwLock<StartupStatus>,
+}
+
+impl StartupState {
+    pub fn new(instance_mode: InstanceMode) -> Self {
+        let subsystems = SUBSYSTEMS
+            .iter()
+            .map(|name| SubsystemStatus {
+                name: name.to_string(),
+                state: SubsystemState::Pending,
+                message: None,
+                duration_ms: 0,
+            })
+            .collect();
+
+        Self {
+            status: RwLock::new(StartupStatus {
+                ready: false,
+                instance_mode,
+                started_at: chrono::Utc::now().to_rfc3339(),
+                finished_at: None,
+                subsystems,
+            }),
+        }
+    }
+
+    /// Snapshot of the current status
+    pub fn snapshot(&self) -> StartupStatus {
+        self.status
+            .read()
+            .map(|s| s.clone())
+            .unwrap_or_else(|e| e.into_inner().clone())
+    }
+
+    /// Record the outcome of a subsystem
+    pub fn record(&self, name: &str, state: SubsystemState, message: Option<String>, started: Instant) {
+        if let Ok(mut status) = self.status.write() {
+            if let Some(entry) = status.subsystems.iter_mut().find(|s| s.name == name) {
+                entry.state = state;
+                entry.message = message;
+                entry.duration_ms = started.elapsed().as_millis() as u64;
+            }
+        }
+    }
+
+    fn mark_ready(&self) -> StartupStatus {
+        if let Ok(mut status) = self.status.write() {
+            status.ready = true;
+            status.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        }
+        self.snapshot()
+    }
+}
+
+impl Default for StartupState {
+    fn default() -> Self {
+        Self::new(InstanceMode::Primary)
+    }
+}
+
+/// Record a step result, logging failures to stderr as well
+fn record_result(state: &StartupState, name: &str, result: Result<Option<String>, String>, started: Instant) {
+    match result {
+        Ok(message) => state.record(name, SubsystemState::Ready, message, started),
+        Err(e) => {
+            eprintln!("Startup: {} failed: {}", name, e);
+            state.record(name, SubsystemState::Failed, Some(e), started);
+        }
+    }
+}
+
+/// Run the startup sequence (called from the Tauri setup hook)
+///
+/// Settings and project restore run synchronously so that commands issued by
+/// the frontend on first render already see the restored project. The rest
+/// runs on the async runtime and finishes with the `app-ready` event.
+pub fn run(app: &tauri::App) {
+    let instance_mode = crate::instance_lock::acquire_primary();
+    app.manage(StartupState::new(instance_mode));
+    let state = app.state::<StartupState>();
+
+    // 1. Settings
+    let started = Instant::now();
+    crate::settings_events::init(app.handle().clone());
+    crate::http_client::init();
+    let result = crate::load_settings_file().map(|settings| match settings {
+        Some(_) => Some("Loaded settings.json".to_string()),
+        None => Some("Using default settings".to_string()),
+    });
+    record_result(&state, SUBSYSTEM_SETTINGS, result, started);
+
+    // 2. Python env, before anything runs vibe.py
+    let started = Instant::now();
+    crate::backend_paths::init(app.handle());
+    let result = crate::python_env::init(app.handle());
+    record_result(&state, SUBSYSTEM_PYTHON, result, started);
+
+    // 3. Project restore
+    let started = Instant::now();
+    let result = crate::restore_saved_project().map(|project| match project {
+        Some(path) => Some(format!("Restored {}", path)),
+        None => Some("No saved project".to_string()),
+    });
+    record_result(&state, SUBSYSTEM_PROJECT, result, started);
+
+    // Shared API state, also used by the watcher's automatic quota sync
+    let api_state = crate::api_server::create_state(app.handle().clone());
+    app.manage(api_state.clone());
+
+    let app_handle = app.handle().clone();
+
+    if instance_mode == InstanceMode::Secondary {
+        eprintln!("Startup: another instance holds the primary lock; running read-only");
+        let started = Instant::now();
+        for name in [SUBSYSTEM_WATCHERS, SUBSYSTEM_API_SERVER, SUBSYSTEM_POLLERS] {
+            state.record(name, SubsystemState::Skipped, Some("Secondary instance (read-only)".to_string()), started);
+        }
+        let status = state.mark_ready();
+        if let Err(e) = app_handle.emit("app-ready", status) {
+            eprintln!("Startup: failed to emit app-ready: {}", e);
+        }
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<StartupState>();
+
+        // 4. Watchers
+        let started = Instant::now();
+        let watcher = crate::antigravity::watcher::WatcherSettings::load();
+        crate::antigravity::watcher::spawn(app_handle.clone());
+        crate::ignore_rules::spawn(app_handle.clone());
+        crate::workflow_watcher::spawn(app_handle.clone());
+        crate::fs_watcher::spawn(app_handle.clone());
+        state.record(
+            SUBSYSTEM_WATCHERS,
+            SubsystemState::Ready,
+            Some(if watcher.enabled {
+                format!("Antigravity watcher polling every {}s; ignore rules, workflows and project files watched", watcher.interval_secs)
+            } else {
+                "Antigravity watcher paused in settings".to_string()
+            }),
+            started,
+        );
+
+        // 5. REST API server for Extension communication
+        let started = Instant::now();
+        let result = crate::api_server::start_server(api_state)
+            .await
+            .map(|addr| Some(format!("Listening on http://{}", addr)));
+        record_result(&state, SUBSYSTEM_API_SERVER, result, started);
+
+        // 6. Background pollers
+        let started = Instant::now();
+        crate::retention::spawn_maintenance();
+        crate::temp_sweep::spawn_startup_sweep();
+        crate::scheduler::spawn(app_handle.clone());
+        crate::task_queue::spawn(app_handle.clone());
+        state.record(
+            SUBSYSTEM_POLLERS,
+            SubsystemState::Ready,
+            Some("Retention maintenance, temp sweep, task schedules and the task queue running".to_string()),
+            started,
+        );
+
+        let status = state.mark_ready();
+        if let Err(e) = app_handle.emit("app-ready", status) {
+            eprintln!("Startup: failed to emit app-ready: {}", e);
+        }
+    });
+}
+
+/// Get the startup status (for the splash screen)
+#[tauri::command]
+pub fn get_startup_status(state: tauri::State<'_, StartupState>) -> Result<StartupStatus, String> {
+    crate::command_metrics::timed_sync("get_startup_status", move || {
+        Ok(state.snapshot())
+    })
+}
@@ -0,0 +1,201 @@
+/// Task Changes - Files a running task changed, as the fs watcher saw them
+///
+/// vibe.py only reports what it wrote itself, not what the tools it shells
+/// out to did. So every task and workflow run is registered with `begin`
+/// for the open project, and every burst the fs watcher sees in that project
+/// (ignored paths and `.git` already left out) is attributed to all runs
+/// going on there; up to 10,000 paths per run. `finish` waits out the
+/// watcher's debounce, then turns the paths into `ChangedFile`s: "added"
+/// when created during the run, "deleted" when gone, "modified" otherwise
+/// (created and removed again: nothing), counted by `line_stats` (a deleted
+/// path with no lines to remove is dropped). Paths vibe.py did not report
+/// are `detected_during_task`, since the user may have edited them during
+/// the run. All of them go on the run's own list (reported ones under the
+/// path vibe.py gave) and replace their entries in `CHANGED_FILES`, which is
+/// emitted as `changed-files-updated`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::Emitter;
+
+use crate::fs_watcher::FsChanged;
+use crate::git_status::CHANGED_FILES_UPDATED_EVENT;
+use crate::{line_stats, task_manager, ChangedFile};
+
+/// Paths kept per run
+const MAX_PATHS_PER_RUN: usize = 10_000;
+
+/// Time for the watcher to report the run's last writes
+const SETTLE: Duration = Duration::from_millis(200);
+
+/// What the watcher saw during one run
+struct Watched {
+    project: PathBuf,
+    /// Whether each path was created (first seen as created)
+    paths: BTreeMap<PathBuf, bool>,
+}
+
+static RUNS: Mutex<Option<HashMap<String, Watched>>> = Mutex::new(None);
+
+fn runs() -> MutexGuard<'static, Option<HashMap<String, Watched>>> {
+    RUNS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn begin_in(task_id: &str, project: &Path) {
+    let watched = Watched { project: project.to_path_buf(), paths: BTreeMap::new() };
+    runs().get_or_insert_with(HashMap::new).insert(task_id.to_string(), watched);
+}
+
+/// Attribute what the watcher sees in the open project to `task_id`, and
+/// keep what its files hold for their line counts (see `line_stats`)
+pub async fn begin(task_id: &str) {
+    let Some(project) = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone()) else { return };
+    begin_in(task_id, Path::new(&project));
+    let _ = tauri::async_runtime::spawn_blocking(move || line_stats::begin(Path::new(&project))).await;
+}
+
+/// A burst of changes in the watched project `root`
+pub fn observe(root: &Path, change: &FsChanged) {
+    let mut runs = runs();
+    let Some(runs) = runs.as_mut() else { return };
+    let paths = change.created.iter().map(|p| (p, true)).chain(change.modified.iter().chain(&change.deleted).map(|p| (p, false)));
+    for (path, created) in paths {
+        for run in runs.values_mut().filter(|run| run.project == root) {
+            if run.paths.len() < MAX_PATHS_PER_RUN || run.paths.contains_key(Path::new(path)) {
+                *run.paths.entry(PathBuf::from(path)).or_default() |= created;
+            }
+        }
+    }
+}
+
+fn take(task_id: &str) -> Option<Watched> {
+    runs().as_mut()?.remove(task_id)
+}
+
+/// Changed files of a run; those not in `reported` are `detected_during_task`
+fn changed_files(run: &Watched, reported: &[PathBuf]) -> Vec<ChangedFile> {
+    let touched: Vec<(&Path, &str)> = run
+        .paths
+        .iter()
+        .filter(|(path, _)| !path.is_dir())
+        .filter_map(|(path, &created)| {
+            let status = match (path.exists(), created) {
+                (true, true) => "added",
+                (true, false) => "modified",
+                (false, true) => return None,
+                (false, false) => "deleted",
+            };
+            Some((path.as_path(), status))
+        })
+        .collect();
+    let paths: Vec<(&Path, Option<&Path>)> = touched.iter().map(|(path, _)| (*path, None)).collect();
+    let counts = line_stats::counts(&run.project, &paths);
+
+    touched
+        .into_iter()
+        .zip(counts)
+        // Nothing removed: a folder, or a file git never had
+        .filter(|((_, status), counts)| !(*status == "deleted" && *counts == Some((0, 0))))
+        .map(|((path, status), counts)| ChangedFile {
+            path: path.to_string_lossy().to_string(),
+            status: status.to_string(),
+            lines_added: counts.map(|c| c.0),
+            lines_removed: counts.map(|c| c.1),
+            renamed_from: None,
+            detected_during_task: !reported.iter().any(|r| r == path),
+        })
+        .collect()
+}
+
+/// Stop watching for `task_id` and record what it changed (see the module docs)
+pub async fn finish(app: &tauri::AppHandle, task_id: &str) {
+    let tasks = task_manager::manager(app);
+    let reported: Vec<String> = tasks
+        .get(task_id)
+        .map(|task| task.changed_files.into_iter().map(|f| f.path).collect())
+        .unwrap_or_default();
+    let id = task_id.to_string();
+    let watched = tauri::async_runtime::spawn_blocking(move || {
+        std::thread::sleep(crate::fs_watcher::DEBOUNCE + SETTLE);
+        let run = take(&id)?;
+        // vibe.py may report paths relative to the project
+        let joined: Vec<PathBuf> = reported.iter().map(|path| run.project.join(path)).collect();
+        let files = changed_files(&run, &joined);
+        line_stats::finish(&run.project);
+        Some((files, run.project, reported.into_iter().zip(joined).collect::<Vec<_>>()))
+    })
+    .await
+    .ok()
+    .flatten();
+    let Some((files, project, reported)) = watched else { return };
+
+    // Reported entries keep the path vibe.py gave, with the counts seen now
+    for file in &files {
+        let path = reported.iter().find(|(_, joined)| Path::new(&file.path) == joined).map(|(path, _)| path.clone());
+        let _ = tasks.record_file(task_id, ChangedFile { path: path.unwrap_or_else(|| file.path.clone()), ..file.clone() });
+    }
+    let current = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone());
+    if files.is_empty() || current.as_deref().map(Path::new) != Some(project.as_path()) {
+        return;
+    }
+    if let Ok(mut changed) = crate::CHANGED_FILES.write() {
+        changed.retain(|old| !files.iter().any(|file| file.path == old.path));
+        changed.extend(files);
+        let _ = app.emit(CHANGED_FILES_UPDATED_EVENT, &*changed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watched_paths_become_changed_files() {
+        let project = std::env::temp_dir().join(format!("vibecode-task-changes-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(project.join("src").join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(project.join("old.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        line_stats::begin(&project);
+        std::fs::write(project.join("src").join("new.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        std::fs::write(project.join("src").join("main.rs"), "fn main() {\n}\n").unwrap();
+        std::fs::remove_file(project.join("old.rs")).unwrap();
+        let path = |rel: &str| project.join(rel).to_string_lossy().to_string();
+
+        let task_id = uuid::Uuid::new_v4().to_string();
+        begin_in(&task_id, &project);
+        let other = FsChanged { root: "elsewhere".to_string(), created: vec!["/elsewhere/x".to_string()], ..Default::default() };
+        observe(Path::new("/elsewhere"), &other);
+        let change = FsChanged {
+            root: path(""),
+            created: vec![path("src/new.rs"), path("scratch.tmp"), path("src")],
+            modified: vec![path("src/main.rs")],
+            deleted: vec![path("old.rs"), path("build")],
+            rescan: false,
+        };
+        observe(&project, &change);
+
+        let run = take(&task_id).unwrap();
+        assert!(take(&task_id).is_none());
+        let files = changed_files(&run, &[project.join("src/main.rs")]);
+        line_stats::finish(&project);
+        let listed: Vec<_> = files
+            .iter()
+            .map(|f| {
+                let relative = Path::new(&f.path).strip_prefix(&project).unwrap().to_path_buf();
+                (relative, f.status.as_str(), f.lines_added, f.lines_removed, f.detected_during_task)
+            })
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                (PathBuf::from("old.rs"), "deleted", Some(0), Some(2), true),
+                (Path::new("src").join("main.rs"), "modified", Some(2), Some(1), false),
+                (Path::new("src").join("new.rs"), "added", Some(2), Some(0), true),
+            ]
+        );
+
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+}
@@ -0,0 +1,451 @@
+/// Task History - Completed vibe.py runs, persisted across restarts
+///
+/// Every finished task (immediate or queued) appends one JSON line to
+/// `<config>/task_history.jsonl`, next to config.json: task text, agent,
+/// outcome, duration, timestamp and the output cut to `MAX_OUTPUT_CHARS`.
+/// Entries are keyed by the project that was open when the task started, so
+/// `get_task_history` shows the runs of the current project only. Each
+/// project keeps its newest `MAX_ENTRIES_PER_PROJECT` runs; older ones are
+/// dropped when the next run is recorded. Runs can carry labels ("bugfix",
+/// "client-x"); `search_task_history` filters by labels, text, outcome, date
+/// and changed path. Each entry keeps the run's changed files as they were
+/// when it finished (up to `MAX_CHANGED_FILES`), independent of the live
+/// list `clear_changed_files` empties; `get_task_changed_files` returns them.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::{ChangedFile, TaskResult};
+
+const HISTORY_FILE: &str = "task_history.jsonl";
+/// Runs kept per project
+const MAX_ENTRIES_PER_PROJECT: usize = 500;
+/// Output characters kept per run
+const MAX_OUTPUT_CHARS: usize = 4000;
+/// Changed files kept per run
+const MAX_CHANGED_FILES: usize = 1000;
+
+/// Serializes appends, compaction and clearing within the process
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// One completed task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskHistoryEntry {
+    pub task_id: String,
+    /// Project open when the task started, None without a project
+    pub project: Option<String>,
+    pub task: String,
+    pub agent: String,
+    pub success: bool,
+    pub execution_time: f64,
+    pub timestamp: String, // ISO 8601
+    pub output: String,
+    /// Output was cut to `MAX_OUTPUT_CHARS`
+    #[serde(default)]
+    pub output_truncated: bool,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Files the run changed, when it finished
+    #[serde(default)]
+    pub changed_files: Vec<ChangedFile>,
+    /// Changed files were cut to `MAX_CHANGED_FILES`
+    #[serde(default)]
+    pub changed_files_truncated: bool,
+}
+
+/// Trimmed, non-empty labels without duplicates, in their original order
+pub fn normalize_labels(labels: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for label in labels.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        if !normalized.iter().any(|n| n.eq_ignore_ascii_case(label)) {
+            normalized.push(label.to_string());
+        }
+    }
+    normalized
+}
+
+impl TaskHistoryEntry {
+    pub fn new(task_id: &str, project: Option<String>, task: &str, labels: &[String], result: &TaskResult) -> Self {
+        let output_truncated = result.output.chars().count() > MAX_OUTPUT_CHARS;
+        Self {
+            task_id: task_id.to_string(),
+            project,
+            task: task.to_string(),
+            agent: result.agent_used.clone(),
+            success: result.success,
+            execution_time: result.execution_time,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            output: if output_truncated {
+                result.output.chars().take(MAX_OUTPUT_CHARS).collect()
+            } else {
+                result.output.clone()
+            },
+            output_truncated,
+            labels: normalize_labels(labels),
+            changed_files: Vec::new(),
+            changed_files_truncated: false,
+        }
+    }
+
+    pub fn with_changed_files(mut self, mut files: Vec<ChangedFile>) -> Self {
+        self.changed_files_truncated = files.len() > MAX_CHANGED_FILES;
+        files.truncate(MAX_CHANGED_FILES);
+        self.changed_files = files;
+        self
+    }
+}
+
+fn history_path() -> PathBuf {
+    crate::get_app_config_dir().join(HISTORY_FILE)
+}
+
+/// Project a task started now is recorded under
+pub fn current_project() -> Option<String> {
+    crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone())
+}
+
+fn read_all(path: &Path) -> Vec<TaskHistoryEntry> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Replace the file with `entries` (temp file + rename)
+fn write_all(path: &Path, entries: &[TaskHistoryEntry]) -> Result<(), String> {
+    let mut content = String::new();
+    for entry in entries {
+        let line = serde_json::to_string(entry)
+            .map_err(|e| format!("Failed to serialize task history: {}", e))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+    let tmp_path = path.with_extension("jsonl.tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to save task history: {}", e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to save task history: {}", e))
+}
+
+fn append_to(path: &Path, entry: &TaskHistoryEntry, max_per_project: usize) -> Result<(), String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize task history: {}", e))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open task history: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write task history: {}", e))?;
+    drop(file);
+
+    // Drop this project's oldest runs once it is over its cap
+    let mut entries = read_all(path);
+    let count = entries.iter().filter(|e| e.project == entry.project).count();
+    if count > max_per_project {
+        let mut excess = count - max_per_project;
+        entries.retain(|e| {
+            if excess > 0 && e.project == entry.project {
+                excess -= 1;
+                return false;
+            }
+            true
+        });
+        write_all(path, &entries)?;
+    }
+    Ok(())
+}
+
+/// `project`'s entries, newest first
+fn read_page(path: &Path, project: &Option<String>, limit: usize, offset: usize) -> Vec<TaskHistoryEntry> {
+    read_all(path)
+        .into_iter()
+        .rev()
+        .filter(|e| &e.project == project)
+        .skip(offset)
+        .take(limit)
+        .collect()
+}
+
+/// Filters of `search_task_history`
+#[derive(Debug, Default)]
+struct HistoryQuery {
+    /// Matched case-insensitively against the task text and the output
+    text: Option<String>,
+    /// Entries must carry all of these (case-insensitive)
+    labels: Vec<String>,
+    success_only: bool,
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Part of the path of a file the run changed (case-insensitive)
+    path: Option<String>,
+}
+
+impl HistoryQuery {
+    fn matches(&self, entry: &TaskHistoryEntry) -> bool {
+        if self.success_only && !entry.success {
+            return false;
+        }
+        if let Some(since) = self.since {
+            let at = chrono::DateTime::parse_from_rfc3339(&entry.timestamp);
+            if !at.is_ok_and(|at| at.with_timezone(&chrono::Utc) >= since) {
+                return false;
+            }
+        }
+        if !self.labels.iter().all(|l| entry.labels.iter().any(|e| e.eq_ignore_ascii_case(l))) {
+            return false;
+        }
+        if let Some(path) = &self.path {
+            let path = path.to_lowercase();
+            if !entry.changed_files.iter().any(|f| f.path.to_lowercase().contains(&path)) {
+                return false;
+            }
+        }
+        match &self.text {
+            Some(text) => {
+                let text = text.to_lowercase();
+                entry.task.to_lowercase().contains(&text) || entry.output.to_lowercase().contains(&text)
+            }
+            None => true,
+        }
+    }
+}
+
+/// Page of `search_task_history`
+#[derive(Debug, Serialize)]
+pub struct TaskHistoryPage {
+    pub entries: Vec<TaskHistoryEntry>,
+    /// Matching entries across all pages
+    pub total: usize,
+}
+
+/// `project`'s entries matching `query`, newest first
+fn search_in(path: &Path, project: &Option<String>, query: &HistoryQuery, limit: usize, offset: usize) -> TaskHistoryPage {
+    let matching: Vec<TaskHistoryEntry> = read_all(path)
+        .into_iter()
+        .rev()
+        .filter(|e| &e.project == project && query.matches(e))
+        .collect();
+    TaskHistoryPage {
+        total: matching.len(),
+        entries: matching.into_iter().skip(offset).take(limit).collect(),
+    }
+}
+
+/// Remove `project`'s entries; returns how many were removed
+fn clear_in(path: &Path, project: &Option<String>) -> Result<usize, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if !path.exists() {
+        return Ok(0);
+    }
+    let mut entries = read_all(path);
+    let before = entries.len();
+    entries.retain(|e| &e.project != project);
+    write_all(path, &entries)?;
+    Ok(before - entries.len())
+}
+
+/// Changed files of the recorded run `task_id`, in any project
+fn changed_files_in(path: &Path, task_id: &str) -> Option<Vec<ChangedFile>> {
+    read_all(path).into_iter().rev().find(|e| e.task_id == task_id).map(|e| e.changed_files)
+}
+
+/// Record a finished task with the files it changed (failures to record are
+/// reported, never fatal)
+pub fn record(
+    task_id: &str,
+    project: Option<String>,
+    task: &str,
+    labels: &[String],
+    result: &TaskResult,
+    changed_files: Vec<ChangedFile>,
+) {
+    let entry = TaskHistoryEntry::new(task_id, project, task, labels, result).with_changed_files(changed_files);
+    if let Err(e) = append_to(&history_path(), &entry, MAX_ENTRIES_PER_PROJECT) {
+        eprintln!("Task history: {}", e);
+    }
+}
+
+/// Get the current project's completed tasks, newest first
+#[tauri::command]
+pub async fn get_task_history(limit: Option<usize>, offset: Option<usize>) -> Result<Vec<TaskHistoryEntry>, String> {
+    crate::command_metrics::timed("get_task_history", async move {
+        Ok(read_page(&history_path(), &current_project(), limit.unwrap_or(50), offset.unwrap_or(0)))
+    }).await
+}
+
+/// Search the current project's completed tasks, newest first
+///
+/// `query` matches the task text or output, every one of `labels` must be on
+/// the run, `since` (ISO 8601) drops older runs and `path` keeps the runs
+/// that changed a file whose path contains it.
+#[tauri::command]
+pub async fn search_task_history(
+    query: Option<String>,
+    labels: Option<Vec<String>>,
+    success_only: Option<bool>,
+    since: Option<String>,
+    path: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<TaskHistoryPage, String> {
+    crate::command_metrics::timed("search_task_history", async move {
+        let since = since
+            .map(|since| {
+                chrono::DateTime::parse_from_rfc3339(&since)
+                    .map(|at| at.with_timezone(&chrono::Utc))
+                    .map_err(|e| format!("Invalid date '{}': {}", since, e))
+            })
+            .transpose()?;
+        let query = HistoryQuery {
+            text: query.map(|q| q.trim().to_string()).filter(|q| !q.is_empty()),
+            labels: normalize_labels(&labels.unwrap_or_default()),
+            success_only: success_only.unwrap_or(false),
+            since,
+            path: path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()),
+        };
+        Ok(search_in(&history_path(), &current_project(), &query, limit.unwrap_or(50), offset.unwrap_or(0)))
+    }).await
+}
+
+/// Files a recorded task changed, as they were when it finished
+#[tauri::command]
+pub async fn get_task_changed_files(task_id: String) -> Result<Vec<ChangedFile>, String> {
+    crate::command_metrics::timed("get_task_changed_files", async move {
+        changed_files_in(&history_path(), &task_id).ok_or_else(|| format!("No recorded task '{}'", task_id))
+    }).await
+}
+
+/// Delete the current project's task history; returns how many runs were removed
+#[tauri::command]
+pub async fn clear_task_history() -> Result<usize, String> {
+    crate::command_metrics::timed("clear_task_history", async move {
+        let project = current_project();
+        let result = clear_in(&history_path(), &project);
+        crate::audit::record(
+            "clear_task_history",
+            serde_json::json!({ "project": project }),
+            crate::audit::ORIGIN_COMMAND,
+            &result,
+        );
+        result
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(output: &str) -> TaskResult {
+        TaskResult {
+            task_id: String::new(),
+            success: true,
+            output: output.to_string(),
+            stderr: String::new(),
+            agent_used: "cli".to_string(),
+            agent_resolution: "explicit".to_string(),
+            execution_time: 1.5,
+            env: Vec::new(),
+            invocation: None,
+            files_changed: Vec::new(),
+            exit_code: Some(0),
+            attempts: 1,
+            agent_attempts: Vec::new(),
+            output_truncated: false,
+            full_output_path: None,
+        }
+    }
+
+    #[test]
+    fn test_history_is_per_project_capped_and_clearable() {
+        let dir = std::env::temp_dir().join(format!("vibecode-task-history-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(HISTORY_FILE);
+        let alpha = Some("/projects/alpha".to_string());
+        let beta = Some("/projects/beta".to_string());
+
+        for i in 0..5 {
+            let entry = TaskHistoryEntry::new(&i.to_string(), alpha.clone(), &format!("task {}", i), &[], &result("ok"));
+            append_to(&path, &entry, 3).unwrap();
+        }
+        let long = TaskHistoryEntry::new("b", beta.clone(), "big", &[], &result(&"é".repeat(MAX_OUTPUT_CHARS + 10)));
+        assert!(long.output_truncated);
+        assert_eq!(long.output.chars().count(), MAX_OUTPUT_CHARS);
+        append_to(&path, &long, 3).unwrap();
+
+        let tasks: Vec<String> = read_page(&path, &alpha, 10, 0).into_iter().map(|e| e.task).collect();
+        assert_eq!(tasks, vec!["task 4", "task 3", "task 2"]);
+        assert_eq!(read_page(&path, &alpha, 1, 1)[0].task, "task 3");
+        assert_eq!(read_page(&path, &beta, 10, 0).len(), 1);
+        assert!(read_page(&path, &None, 10, 0).is_empty());
+
+        assert_eq!(clear_in(&path, &alpha).unwrap(), 3);
+        assert!(read_page(&path, &alpha, 10, 0).is_empty());
+        assert_eq!(read_page(&path, &beta, 10, 0).len(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_search_filters_by_labels_text_outcome_and_date() {
+        let dir = std::env::temp_dir().join(format!("vibecode-task-history-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(HISTORY_FILE);
+        let project = Some("/projects/alpha".to_string());
+        let labels = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        let mut failed = result("panic in parser");
+        failed.success = false;
+        let runs = [
+            ("fix login", labels(&["bugfix", " client-x ", "BUGFIX"]), result("ok")),
+            ("try new router", labels(&["experiment"]), result("routes ok")),
+            ("fix parser", labels(&["bugfix"]), failed),
+        ];
+        for (i, (task, labels, result)) in runs.iter().enumerate() {
+            let entry = TaskHistoryEntry::new(&i.to_string(), project.clone(), task, labels, result);
+            append_to(&path, &entry, 10).unwrap();
+        }
+        assert_eq!(read_page(&path, &project, 10, 0)[2].labels, vec!["bugfix", "client-x"]);
+
+        let tasks = |query: &HistoryQuery| -> Vec<String> {
+            search_in(&path, &project, query, 10, 0).entries.into_iter().map(|e| e.task).collect()
+        };
+        let bugfix = HistoryQuery { labels: labels(&["Bugfix"]), ..Default::default() };
+        assert_eq!(tasks(&bugfix), vec!["fix parser", "fix login"]);
+        assert_eq!(tasks(&HistoryQuery { success_only: true, ..bugfix }), vec!["fix login"]);
+        assert_eq!(tasks(&HistoryQuery { text: Some("PARSER".into()), ..Default::default() }), vec!["fix parser"]);
+        assert_eq!(tasks(&HistoryQuery { text: Some("routes".into()), ..Default::default() }), vec!["try new router"]);
+        let future = chrono::Utc::now() + chrono::Duration::hours(1);
+        assert!(tasks(&HistoryQuery { since: Some(future), ..Default::default() }).is_empty());
+
+        let page = search_in(&path, &project, &HistoryQuery::default(), 1, 1);
+        assert_eq!((page.total, page.entries[0].task.as_str()), (3, "try new router"));
+
+        let changed = |path: &str| ChangedFile {
+            path: path.to_string(),
+            status: "modified".to_string(),
+            lines_added: Some(3),
+            lines_removed: Some(1),
+            renamed_from: None,
+            detected_during_task: false,
+        };
+        let entry = TaskHistoryEntry::new("auth", project.clone(), "harden auth", &[], &result("ok"))
+            .with_changed_files(vec![changed("/projects/alpha/src/Auth.rs"), changed("/projects/alpha/Cargo.toml")]);
+        append_to(&path, &entry, 10).unwrap();
+        assert_eq!(tasks(&HistoryQuery { path: Some("auth.RS".into()), ..Default::default() }), vec!["harden auth"]);
+        let files = changed_files_in(&path, "auth").unwrap();
+        assert_eq!((files.len(), files[0].lines_added), (2, Some(3)));
+        assert_eq!(changed_files_in(&path, "0").unwrap().len(), 0);
+        assert!(changed_files_in(&path, "missing").is_none());
+        let many = TaskHistoryEntry::new("many", project.clone(), "many", &[], &result("ok"))
+            .with_changed_files((0..=MAX_CHANGED_FILES).map(|i| changed(&i.to_string())).collect());
+        assert!(many.changed_files_truncated && many.changed_files.len() == MAX_CHANGED_FILES);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
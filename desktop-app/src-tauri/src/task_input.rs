@@ -0,0 +1,139 @@
+/// Task Input - Answering vibe.py prompts while a task runs
+///
+/// Tasks and workflows run with a piped stdin. Their output is scanned as it
+/// streams in; a line that looks like a question ("Apply these changes?
+/// [y/N]", "Enter a name: ") raises a `task-input-request` event and
+/// `send_task_input(task_id, text)` writes the answer to the child's stdin.
+/// When nobody answers within `tasks.inputTimeoutSecs` the task gets
+/// `DEFAULT_ANSWER`, so an unattended run never hangs on a prompt (0 waits
+/// for an answer indefinitely).
+
+use std::sync::Arc;
+use std::time::Duration;
+use serde::Serialize;
+use tauri::Emitter;
+
+pub const TASK_INPUT_REQUEST_EVENT: &str = "task-input-request";
+
+/// Sent when a prompt goes unanswered (declines confirmations)
+pub const DEFAULT_ANSWER: &str = "n";
+
+/// Markers of yes/no confirmations (compared lowercase)
+const CONFIRM_MARKERS: &[&str] = &["[y/n]", "(y/n)", "[yes/no]", "(yes/no)"];
+
+/// Called with the prompt text when a child waits for input
+pub type PromptHandler = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// How prompts of one run are handled
+#[derive(Clone)]
+pub struct Prompts {
+    pub on_prompt: PromptHandler,
+    /// Wait before answering `DEFAULT_ANSWER` (None = never answer for the user)
+    pub answer_after: Option<Duration>,
+}
+
+/// Payload of `task-input-request`
+#[derive(Debug, Clone, Serialize)]
+pub struct InputRequest<'a> {
+    pub task_id: &'a str,
+    pub prompt: &'a str,
+    pub default_answer: &'a str,
+    /// Seconds until `default_answer` is sent (None = it never is)
+    pub timeout_secs: Option<u64>,
+}
+
+impl Prompts {
+    /// Prompts raised as `task-input-request` events for `task_id`
+    pub fn emitting(app: &tauri::AppHandle, task_id: &str) -> Self {
+        let answer_after = crate::task_process::TaskProcessSettings::load().input_timeout();
+        let (app, task_id) = (app.clone(), task_id.to_string());
+        Self {
+            on_prompt: Arc::new(move |prompt| {
+                let _ = app.emit(
+                    TASK_INPUT_REQUEST_EVENT,
+                    InputRequest {
+                        task_id: &task_id,
+                        prompt,
+                        default_answer: DEFAULT_ANSWER,
+                        timeout_secs: answer_after.map(|after| after.as_secs()),
+                    },
+                );
+            }),
+            answer_after,
+        }
+    }
+}
+
+/// Whether `line` asks for input; `partial` lines have no newline yet
+fn is_prompt(line: &str, partial: bool) -> bool {
+    let lower = line.to_lowercase();
+    if CONFIRM_MARKERS.iter().any(|m| lower.contains(m)) {
+        return true;
+    }
+    // input("Name: ") leaves the cursor after the question, with no newline
+    partial && (line.ends_with("? ") || line.ends_with(": "))
+}
+
+/// Finds prompts in streamed output, each one once
+#[derive(Default)]
+pub struct PromptScanner {
+    /// Output after the last newline (or the last prompt found)
+    line: String,
+}
+
+impl PromptScanner {
+    /// Feed the next chunk of output; returns the prompt it ends on, if any
+    pub fn feed(&mut self, chunk: &str) -> Option<String> {
+        let mut found = None;
+        let mut lines = chunk.split('\n').peekable();
+        while let Some(piece) = lines.next() {
+            self.line.push_str(piece);
+            let partial = lines.peek().is_none();
+            let line = self.line.trim_end_matches('\r');
+            if line.trim().is_empty() {
+                if !partial {
+                    self.line.clear();
+                }
+                continue;
+            }
+            if is_prompt(line, partial) {
+                found = Some(line.trim().to_string());
+                self.line.clear();
+            } else {
+                // Output after a prompt means it was already answered
+                found = None;
+                if !partial {
+                    self.line.clear();
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Answer a task that is waiting for input
+#[tauri::command]
+pub async fn send_task_input(task_id: String, text: String) -> Result<(), String> {
+    crate::command_metrics::timed("send_task_input", async move {
+        crate::task_process::send_input(&task_id, &text)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scanner_finds_prompts_once() {
+        let mut scanner = PromptScanner::default();
+        assert_eq!(scanner.feed("Planning changes...\nApply these"), None);
+        assert_eq!(scanner.feed(" changes? [y/N] "), Some("Apply these changes? [y/N]".to_string()));
+        // The answer's output continues after the prompt without re-triggering it
+        assert_eq!(scanner.feed("Skipped.\n"), None);
+
+        assert_eq!(scanner.feed("Enter a branch name: "), Some("Enter a branch name:".to_string()));
+        assert_eq!(scanner.feed("Processing: 3 files\nDone: ok\n"), None);
+        assert_eq!(scanner.feed("Continue? (y/n)\r\n"), Some("Continue? (y/n)".to_string()));
+        assert_eq!(scanner.feed("Overwrite? [y/N]\nNo changes made\n"), None, "no longer waiting");
+    }
+}
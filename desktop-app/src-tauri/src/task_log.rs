@@ -0,0 +1,171 @@
+/// Task Log - Full output of runs too large to return over IPC
+///
+/// A `TaskResult` whose output or stderr exceeds `tasks.maxOutputBytes`
+/// (default 256 KB, 0 = no limit) is cut to that size before it reaches the
+/// frontend. The complete text is written to `<task_id>.log` in the
+/// task-output artifact folder (pruned by the retention policy), and
+/// `read_task_log` pages through it on demand. Each step of a workflow run is
+/// capped on its own, as `<task_id>-step-<n>.log`.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+use crate::retention::{artifact_dir, ArtifactClass};
+use crate::workflow_run::StepResult;
+use crate::TaskResult;
+
+/// Largest chunk `read_task_log` returns
+const MAX_CHUNK_BYTES: u64 = 1024 * 1024;
+
+/// Page of `read_task_log`
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskLogChunk {
+    pub content: String,
+    pub offset: u64,
+    /// Where the next page starts (after the last whole character returned)
+    pub next_offset: u64,
+    pub total_bytes: u64,
+    pub eof: bool,
+}
+
+fn log_path(dir: &Path, task_id: &str) -> Result<PathBuf, String> {
+    let valid = !task_id.is_empty() && task_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("Invalid task id: '{}'", task_id));
+    }
+    Ok(dir.join(format!("{}.log", task_id)))
+}
+
+/// Longest prefix of `text` within `limit` bytes that ends on a character boundary
+fn prefix(text: &str, limit: usize) -> &str {
+    let mut end = limit.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// Cut `result`'s output and stderr to `limit` bytes, keeping the full text in `dir`
+fn cap_in(dir: &Path, result: &mut TaskResult, limit: usize) {
+    if limit == 0 || (result.output.len() <= limit && result.stderr.len() <= limit) {
+        return;
+    }
+
+    let mut full = result.output.clone();
+    // In --json mode stderr is the console log itself
+    if !result.stderr.is_empty() && result.stderr != result.output {
+        full.push_str("\n--- stderr ---\n");
+        full.push_str(&result.stderr);
+    }
+    result.full_output_path = write_log(dir, &result.task_id, &full);
+    result.output = prefix(&result.output, limit).to_string();
+    result.stderr = prefix(&result.stderr, limit).to_string();
+    result.output_truncated = true;
+}
+
+/// Save `full` as `<log_id>.log` in `dir`; the path, or None (logged) when that failed
+fn write_log(dir: &Path, log_id: &str, full: &str) -> Option<String> {
+    let written = log_path(dir, log_id).and_then(|path| {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create task log directory: {}", e))?;
+        let tmp_path = path.with_extension("log.tmp");
+        std::fs::write(&tmp_path, full).map_err(|e| format!("Failed to save task log: {}", e))?;
+        std::fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to save task log: {}", e))?;
+        Ok(path)
+    });
+    match written {
+        Ok(path) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            eprintln!("Task log: {}", e);
+            None
+        }
+    }
+}
+
+/// Apply the `tasks.maxOutputBytes` limit to a finished run
+pub fn cap(result: &mut TaskResult) {
+    let limit = crate::task_process::TaskProcessSettings::load().max_output_bytes;
+    cap_in(&artifact_dir(ArtifactClass::TaskOutput), result, limit);
+}
+
+/// Apply the same limit to one step of workflow run `task_id`, logged as `<task_id>-step-<n>.log`
+pub fn cap_step(task_id: &str, step: &mut StepResult) {
+    let limit = crate::task_process::TaskProcessSettings::load().max_output_bytes;
+    if limit == 0 || step.output.len() <= limit {
+        return;
+    }
+    let log_id = format!("{}-step-{}", task_id, step.index);
+    step.full_output_path = write_log(&artifact_dir(ArtifactClass::TaskOutput), &log_id, &step.output);
+    step.output = prefix(&step.output, limit).to_string();
+    step.output_truncated = true;
+}
+
+fn read_in(dir: &Path, task_id: &str, offset: u64, length: u64) -> Result<TaskLogChunk, String> {
+    let path = log_path(dir, task_id)?;
+    let mut file = std::fs::File::open(&path).map_err(|_| format!("No log for task '{}'", task_id))?;
+    let total_bytes = file.metadata().map_err(|e| format!("Failed to read task log: {}", e))?.len();
+
+    let offset = offset.min(total_bytes);
+    let length = length.clamp(1, MAX_CHUNK_BYTES).min(total_bytes - offset);
+    let mut bytes = vec![0; length as usize];
+    file.seek(SeekFrom::Start(offset))
+        .and_then(|_| file.read_exact(&mut bytes))
+        .map_err(|e| format!("Failed to read task log: {}", e))?;
+
+    // A character cut by the end of the page is returned whole with the next one
+    let consumed = match std::str::from_utf8(&bytes) {
+        Err(e) if e.error_len().is_none() && e.valid_up_to() > 0 => e.valid_up_to(),
+        _ => bytes.len(),
+    };
+    let content = String::from_utf8_lossy(&bytes[..consumed]).to_string();
+    let next_offset = offset + consumed as u64;
+
+    Ok(TaskLogChunk { content, offset, next_offset, total_bytes, eof: next_offset >= total_bytes })
+}
+
+/// Read part of a task's full log (`length` bytes from `offset`, at most 1 MB)
+#[tauri::command]
+pub async fn read_task_log(task_id: String, offset: Option<u64>, length: Option<u64>) -> Result<TaskLogChunk, String> {
+    crate::command_metrics::timed("read_task_log", async move {
+        read_in(&artifact_dir(ArtifactClass::TaskOutput), &task_id, offset.unwrap_or(0), length.unwrap_or(MAX_CHUNK_BYTES))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_large_output_is_capped_and_paged() {
+        let dir = std::env::temp_dir().join(format!("vibecode-task-log-{}", uuid::Uuid::new_v4()));
+        let mut result: TaskResult = serde_json::from_value(serde_json::json!({
+            "task_id": "build-1",
+            "success": false,
+            "output": "é".repeat(10),
+            "stderr": "error: linker failed",
+            "agent_used": "cli",
+            "agent_resolution": "explicit",
+            "execution_time": 1.0,
+        }))
+        .unwrap();
+
+        cap_in(&dir, &mut result, 7);
+
+        assert!(result.output_truncated);
+        assert_eq!(result.output, "ééé", "cut on a character boundary");
+        assert_eq!(result.stderr, "error: ");
+        let path = result.full_output_path.clone().unwrap();
+        let full = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(full, format!("{}\n--- stderr ---\nerror: linker failed", "é".repeat(10)));
+
+        // 5 bytes end inside the third "é": it comes with the next page
+        let first = read_in(&dir, "build-1", 0, 5).unwrap();
+        assert_eq!((first.content.as_str(), first.next_offset, first.eof), ("éé", 4, false));
+        let rest = read_in(&dir, "build-1", first.next_offset, MAX_CHUNK_BYTES).unwrap();
+        assert!(rest.eof);
+        assert_eq!(format!("{}{}", first.content, rest.content), full);
+        assert!(read_in(&dir, "../secrets", 0, 10).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
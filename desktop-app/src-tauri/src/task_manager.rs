@@ -0,0 +1,202 @@
+/// Task Manager - Status of every task and workflow run
+///
+/// Each `execute_task`, `run_workflow` and `enqueue_task` invocation is
+/// tracked under its task id (a UUID unless the caller supplies one) from
+/// `queued` or `running` to `succeeded`, `failed` or `cancelled`. The manager
+/// lives in Tauri managed state; `get_task_status` and `list_active_tasks`
+/// read it. Files changed by a run are kept on the run's own entry, so two
+/// concurrent runs never mix their lists. Only the last `MAX_FINISHED`
+/// finished runs are kept.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::ChangedFile;
+
+/// Finished runs kept for `get_task_status`
+const MAX_FINISHED: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl TaskStatus {
+    /// Final status of a run that returned a result
+    pub fn finished(success: bool, cancelled: bool) -> Self {
+        match (cancelled, success) {
+            (true, _) => Self::Cancelled,
+            (false, true) => Self::Succeeded,
+            (false, false) => Self::Failed,
+        }
+    }
+
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::Queued | Self::Running)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    Task,
+    Workflow,
+}
+
+/// Entry of `get_task_status` and `list_active_tasks`
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackedTask {
+    pub task_id: String,
+    pub kind: TaskKind,
+    /// Task description or workflow name
+    pub label: String,
+    pub status: TaskStatus,
+    pub created_at: String,          // ISO 8601
+    pub finished_at: Option<String>, // ISO 8601
+    pub changed_files: Vec<ChangedFile>,
+}
+
+#[derive(Default)]
+pub struct TaskManager {
+    tasks: Mutex<HashMap<String, TrackedTask>>,
+}
+
+impl TaskManager {
+    fn tasks(&self) -> MutexGuard<'_, HashMap<String, TrackedTask>> {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn set(&self, task_id: &str, kind: TaskKind, label: &str, status: TaskStatus) {
+        let mut tasks = self.tasks();
+        let task = tasks.entry(task_id.to_string()).or_insert_with(|| TrackedTask {
+            task_id: task_id.to_string(),
+            kind,
+            label: label.to_string(),
+            status,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+            changed_files: Vec::new(),
+        });
+        task.status = status;
+        task.finished_at = None;
+    }
+
+    /// Track a run waiting in the task queue
+    pub fn queue(&self, task_id: &str, kind: TaskKind, label: &str) {
+        self.set(task_id, kind, label, TaskStatus::Queued);
+    }
+
+    /// Mark a run as started (tracking it if it was never queued)
+    pub fn start(&self, task_id: &str, kind: TaskKind, label: &str) {
+        self.set(task_id, kind, label, TaskStatus::Running);
+    }
+
+    pub fn finish(&self, task_id: &str, status: TaskStatus) {
+        let mut tasks = self.tasks();
+        if let Some(task) = tasks.get_mut(task_id) {
+            task.status = status;
+            task.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        }
+        prune(&mut tasks, MAX_FINISHED);
+    }
+
+    /// Record a file changed by a run, replacing an earlier entry for the same path
+    pub fn record_file(&self, task_id: &str, file: ChangedFile) -> Result<(), String> {
+        let mut tasks = self.tasks();
+        let task = tasks.get_mut(task_id).ok_or_else(|| format!("Unknown task '{}'", task_id))?;
+        task.changed_files.retain(|f| f.path != file.path);
+        task.changed_files.push(file);
+        Ok(())
+    }
+
+    pub fn get(&self, task_id: &str) -> Option<TrackedTask> {
+        self.tasks().get(task_id).cloned()
+    }
+
+    /// Queued and running runs, oldest first
+    pub fn active(&self) -> Vec<TrackedTask> {
+        let mut active: Vec<TrackedTask> = self.tasks().values().filter(|t| t.status.is_active()).cloned().collect();
+        active.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        active
+    }
+}
+
+/// Drop the oldest finished runs beyond `keep`
+fn prune(tasks: &mut HashMap<String, TrackedTask>, keep: usize) {
+    let mut finished: Vec<(String, String)> = tasks
+        .values()
+        .filter_map(|t| Some((t.finished_at.clone()?, t.task_id.clone())))
+        .collect();
+    if finished.len() <= keep {
+        return;
+    }
+    finished.sort();
+    for (_, task_id) in &finished[..finished.len() - keep] {
+        tasks.remove(task_id);
+    }
+}
+
+/// The manager in the app's managed state
+pub fn manager(app: &tauri::AppHandle) -> tauri::State<'_, TaskManager> {
+    app.state::<TaskManager>()
+}
+
+/// Status of one task or workflow run
+#[tauri::command]
+pub async fn get_task_status(state: tauri::State<'_, TaskManager>, task_id: String) -> Result<TrackedTask, String> {
+    crate::command_metrics::timed("get_task_status", async move {
+        state.get(&task_id).ok_or_else(|| format!("Unknown task '{}'", task_id))
+    }).await
+}
+
+/// Runs that are queued or running, oldest first
+#[tauri::command]
+pub async fn list_active_tasks(state: tauri::State<'_, TaskManager>) -> Result<Vec<TrackedTask>, String> {
+    crate::command_metrics::timed("list_active_tasks", async move {
+        Ok(state.active())
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, status: &str) -> ChangedFile {
+        ChangedFile { path: path.to_string(), status: status.to_string(), lines_added: Some(0), lines_removed: Some(0), renamed_from: None, detected_during_task: false }
+    }
+
+    #[test]
+    fn test_concurrent_runs_keep_their_own_status_and_files() {
+        let manager = TaskManager::default();
+        manager.queue("a", TaskKind::Task, "add tests");
+        manager.start("b", TaskKind::Workflow, "review");
+        manager.start("a", TaskKind::Task, "add tests");
+
+        manager.record_file("a", file("src/lib.rs", "modified")).unwrap();
+        manager.record_file("b", file("README.md", "added")).unwrap();
+        manager.record_file("a", file("src/lib.rs", "deleted")).unwrap();
+        assert!(manager.record_file("missing", file("x", "added")).is_err());
+
+        let a = manager.get("a").unwrap();
+        assert_eq!(a.changed_files.len(), 1);
+        assert_eq!(a.changed_files[0].status, "deleted");
+        assert_eq!(manager.get("b").unwrap().changed_files[0].path, "README.md");
+
+        manager.finish("a", TaskStatus::finished(true, false));
+        assert_eq!(manager.get("a").unwrap().status, TaskStatus::Succeeded);
+        assert_eq!(manager.active().iter().map(|t| t.task_id.as_str()).collect::<Vec<_>>(), vec!["b"]);
+
+        manager.finish("b", TaskStatus::finished(false, true));
+        assert_eq!(manager.get("b").unwrap().status, TaskStatus::Cancelled);
+        let mut tasks = manager.tasks();
+        prune(&mut tasks, 1);
+        assert_eq!(tasks.len(), 1);
+    }
+}
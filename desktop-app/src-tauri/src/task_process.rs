@@ -0,0 +1,656 @@
+/// Task Process - Cancellable, time-limited vibe.py runs
+///
+/// `execute_task` spawns python through `run`, which registers the child
+/// under the task id until it exits. `cancel_task(task_id)` kills the whole
+/// process tree (python may start sub-processes of its own): the child is
+/// placed in its own process group on Unix and killed with `taskkill /T` on
+/// Windows. The run then ends as `Ending::Cancelled` with whatever output it
+/// produced, instead of an error. A run that outlives its timeout (the
+/// `tasks.timeoutSecs` setting unless the caller passes one) is killed the
+/// same way and ends as `Ending::TimedOut`. `terminate` is the gentler form
+/// used to abort a workflow: SIGTERM first, SIGKILL only after a grace
+/// period. Between two attempts of a retried
+/// task (`wait_before_retry`) cancelling skips the remaining attempts. Runs
+/// started with `Prompts` get a piped stdin for `send_input` (see `task_input`).
+/// Short-lived helpers (skill scripts, the workflow generator's node scripts)
+/// go through `output`, which registers them too. When the app exits, or on
+/// `kill_all_tasks`, every registered tree gets SIGTERM (`taskkill /T` without
+/// `/F` on Windows) and whatever still runs after `tasks.killGraceSecs` is
+/// force-killed, so no python keeps editing files headless.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::{Child, ChildStdin, Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+use crate::task_input::{PromptScanner, Prompts, DEFAULT_ANSWER};
+use crate::task_progress::{LineHandler, LineSplitter};
+
+/// How often a running child is checked for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// `tasks` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskProcessSettings {
+    /// Default limit for one task run in seconds (0 = no limit)
+    pub timeout_secs: u64,
+    /// Seconds a prompt waits for an answer before the default one is sent (0 = wait indefinitely)
+    pub input_timeout_secs: u64,
+    /// Append stderr to `TaskResult.output` like older versions did
+    pub combined_output: bool,
+    /// Bytes of output returned to the frontend; longer output goes to a task log (0 = no limit)
+    pub max_output_bytes: usize,
+    /// Seconds children get to exit after SIGTERM before they are force-killed
+    pub kill_grace_secs: u64,
+}
+
+impl Default for TaskProcessSettings {
+    fn default() -> Self {
+        Self { timeout_secs: 600, input_timeout_secs: 60, combined_output: false, max_output_bytes: 256 * 1024, kill_grace_secs: 5 }
+    }
+}
+
+impl TaskProcessSettings {
+    /// Load the tasks block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("tasks").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+
+    /// Limit for a run: the caller's `timeout_secs`, else the setting (0 = none)
+    pub fn timeout(&self, timeout_secs: Option<u64>) -> Option<Duration> {
+        match timeout_secs.unwrap_or(self.timeout_secs) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    /// Wait before an unanswered prompt gets the default answer (None = never)
+    pub fn input_timeout(&self) -> Option<Duration> {
+        match self.input_timeout_secs {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+}
+
+/// How a run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ending {
+    Exited,
+    Cancelled,
+    TimedOut(Duration),
+}
+
+/// Output of a finished run
+#[derive(Debug)]
+pub struct TaskOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// Exit status was success (always false for cancelled runs)
+    pub success: bool,
+    /// None when the process was killed by a signal
+    pub exit_code: Option<i32>,
+    pub ending: Ending,
+}
+
+impl TaskOutput {
+    /// Text for `TaskResult.output`: stdout, followed by stderr on failure when `combined`
+    pub fn console(&self, combined: bool) -> String {
+        if combined && !self.success {
+            format!("{}\n{}", self.stdout, self.stderr)
+        } else {
+            self.stdout.clone()
+        }
+    }
+}
+
+/// A spawned child, as seen by `cancel_task` and `send_input`
+struct RunningTask {
+    pid: u32,
+    cancelled: AtomicBool,
+    /// None unless the run was started with `Prompts`
+    stdin: Mutex<Option<ChildStdin>>,
+    /// When the prompt currently waiting for an answer appeared
+    prompt_since: Mutex<Option<Instant>>,
+}
+
+impl RunningTask {
+    fn write_input(&self, text: &str) -> Result<(), String> {
+        let mut stdin = self.stdin.lock().unwrap_or_else(|e| e.into_inner());
+        let stdin = stdin.as_mut().ok_or("Task does not accept input")?;
+        writeln!(stdin, "{}", text)
+            .and_then(|_| stdin.flush())
+            .map_err(|e| format!("Failed to write task input: {}", e))?;
+        *self.prompt_since.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        Ok(())
+    }
+
+    /// A prompt has waited longer than `after`
+    fn prompt_expired(&self, after: Duration) -> bool {
+        self.prompt_since
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some_and(|since| since.elapsed() >= after)
+    }
+}
+
+static RUNNING: OnceLock<Mutex<HashMap<String, Arc<RunningTask>>>> = OnceLock::new();
+
+fn running() -> &'static Mutex<HashMap<String, Arc<RunningTask>>> {
+    RUNNING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Tasks waiting for their next attempt, with their cancel flag
+static WAITING: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+
+fn waiting() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    WAITING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Helper children started through `output`, by pid
+static HELPERS: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+
+fn helpers() -> &'static Mutex<HashMap<u32, String>> {
+    HELPERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Unregisters the task when the run ends, however it ends
+struct Registration<'a> {
+    task_id: &'a str,
+}
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        running().lock().unwrap_or_else(|e| e.into_inner()).remove(self.task_id);
+    }
+}
+
+/// Unregisters a helper child once its output is collected
+struct HelperRegistration {
+    pid: u32,
+}
+
+impl Drop for HelperRegistration {
+    fn drop(&mut self) {
+        helpers().lock().unwrap_or_else(|e| e.into_inner()).remove(&self.pid);
+    }
+}
+
+/// Signal `pid` and everything it started: SIGTERM, or SIGKILL when `force`
+fn signal_tree(pid: u32, force: bool) {
+    #[cfg(windows)]
+    let result = {
+        let pid = pid.to_string();
+        let mut args = vec!["/PID", pid.as_str(), "/T"];
+        if force {
+            args.push("/F");
+        }
+        Command::new("taskkill").args(args).stdout(Stdio::null()).stderr(Stdio::null()).status()
+    };
+
+    // The child leads its own process group (see `spawn`)
+    #[cfg(not(windows))]
+    let result = Command::new("kill")
+        .args([if force { "-KILL" } else { "-TERM" }, "--", &format!("-{}", pid)])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    if let Err(e) = result {
+        eprintln!("Failed to kill process tree {}: {}", pid, e);
+    }
+}
+
+/// Kill `pid` and everything it started
+fn kill_tree(pid: u32) {
+    signal_tree(pid, true);
+}
+
+/// Put the child in its own process group so its whole tree can be signalled
+fn own_group(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+fn spawn(cmd: &mut Command, interactive: bool) -> std::io::Result<Child> {
+    own_group(cmd);
+    cmd.stdin(if interactive { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+}
+
+/// The complete UTF-8 text at the start of `pending`, leaving a character
+/// split across reads for the next one (invalid bytes become U+FFFD)
+fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let mut text = String::new();
+    let mut rest: &[u8] = pending;
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(valid) => {
+                text.push_str(valid);
+                rest = &[];
+                break;
+            }
+            Err(e) => {
+                let (valid, after) = rest.split_at(e.valid_up_to());
+                text.push_str(&String::from_utf8_lossy(valid));
+                match e.error_len() {
+                    Some(invalid) => {
+                        text.push(char::REPLACEMENT_CHARACTER);
+                        rest = &after[invalid..];
+                    }
+                    // Incomplete character at the end: wait for the next read
+                    None => {
+                        rest = after;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    *pending = rest.to_vec();
+    text
+}
+
+/// Drain a pipe on its own thread so a chatty child never blocks on a full pipe,
+/// reporting prompts and complete lines as they stream in
+fn collect<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    watch: Option<(Arc<RunningTask>, Prompts)>,
+    on_line: Option<LineHandler>,
+) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let Some(mut pipe) = pipe else { return buf };
+        let mut scanner = PromptScanner::default();
+        let mut lines = LineSplitter::default();
+        let mut pending = Vec::new();
+        let mut chunk = [0u8; 8192];
+        loop {
+            let n = match pipe.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            };
+            buf.extend_from_slice(&chunk[..n]);
+            pending.extend_from_slice(&chunk[..n]);
+            let text = take_utf8(&mut pending);
+            if let Some((task, prompts)) = &watch {
+                let mut prompt_since = task.prompt_since.lock().unwrap_or_else(|e| e.into_inner());
+                match scanner.feed(&text) {
+                    Some(prompt) => {
+                        *prompt_since = Some(Instant::now());
+                        drop(prompt_since);
+                        (prompts.on_prompt)(&prompt);
+                    }
+                    // Any output after a prompt means it is no longer waiting
+                    None if !text.trim().is_empty() => *prompt_since = None,
+                    None => {}
+                }
+            }
+            if let Some(on_line) = &on_line {
+                lines.feed(&text, on_line.as_ref());
+            }
+        }
+        if let Some(on_line) = &on_line {
+            lines.feed(&String::from_utf8_lossy(&pending), on_line.as_ref());
+            lines.finish(on_line.as_ref());
+        }
+        buf
+    })
+}
+
+/// Spawn `cmd` under `task_id` and wait for it (blocking), at most `timeout`
+///
+/// With `prompts` the child gets a piped stdin: prompts in its output are
+/// reported and answered with `DEFAULT_ANSWER` once `answer_after` passes.
+/// `on_line` sees every line of stdout and stderr (see `task_progress`).
+pub fn run(
+    task_id: &str,
+    cmd: &mut Command,
+    timeout: Option<Duration>,
+    prompts: Option<Prompts>,
+    on_line: Option<LineHandler>,
+) -> Result<TaskOutput, String> {
+    let mut child = spawn(cmd, prompts.is_some()).map_err(|e| format!("Failed to execute: {}", e))?;
+    let deadline = timeout.map(|t| Instant::now() + t);
+
+    let task = Arc::new(RunningTask {
+        pid: child.id(),
+        cancelled: AtomicBool::new(false),
+        stdin: Mutex::new(child.stdin.take()),
+        prompt_since: Mutex::new(None),
+    });
+    running()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(task_id.to_string(), task.clone());
+    let _registration = Registration { task_id };
+
+    // vibe.py's console output moves to stderr in --json mode, so watch both
+    let watch = prompts.as_ref().map(|p| (task.clone(), p.clone()));
+    let stdout = collect(child.stdout.take(), watch.clone(), on_line.clone());
+    let stderr = collect(child.stderr.take(), watch, on_line);
+
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if deadline.is_some_and(|d| Instant::now() >= d) && !timed_out => {
+                timed_out = true;
+                kill_tree(task.pid);
+            }
+            Ok(None) if prompts.as_ref().and_then(|p| p.answer_after).is_some_and(|after| task.prompt_expired(after)) => {
+                eprintln!("Task {}: no answer to prompt, sending '{}'", task_id, DEFAULT_ANSWER);
+                if let Err(e) = task.write_input(DEFAULT_ANSWER) {
+                    eprintln!("Task {}: {}", task_id, e);
+                    *task.prompt_since.lock().unwrap_or_else(|e| e.into_inner()) = None;
+                }
+            }
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                eprintln!("Failed to wait for task {}: {}", task_id, e);
+                kill_tree(task.pid);
+                break child.wait().ok();
+            }
+        }
+    };
+
+    let ending = match (task.cancelled.load(Ordering::SeqCst), timeout) {
+        (true, _) => Ending::Cancelled,
+        (false, Some(limit)) if timed_out => Ending::TimedOut(limit),
+        _ => Ending::Exited,
+    };
+    Ok(TaskOutput {
+        stdout: String::from_utf8_lossy(&stdout.join().unwrap_or_default()).to_string(),
+        stderr: String::from_utf8_lossy(&stderr.join().unwrap_or_default()).to_string(),
+        success: ending == Ending::Exited && status.is_some_and(|s| s.success()),
+        exit_code: status.and_then(|s| s.code()),
+        ending,
+    })
+}
+
+/// `Command::output` for helper scripts, registered so `kill_all` reaches them
+pub fn output(label: &str, cmd: &mut Command) -> std::io::Result<Output> {
+    own_group(cmd);
+    let child = cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let pid = child.id();
+    helpers().lock().unwrap_or_else(|e| e.into_inner()).insert(pid, label.to_string());
+    let _registration = HelperRegistration { pid };
+    child.wait_with_output()
+}
+
+/// Pids of every registered child
+fn registered_pids() -> Vec<u32> {
+    let tasks = running().lock().unwrap_or_else(|e| e.into_inner());
+    let helpers = helpers().lock().unwrap_or_else(|e| e.into_inner());
+    tasks.values().map(|t| t.pid).chain(helpers.keys().copied()).collect()
+}
+
+/// Stop every running task, pending retry and helper script
+///
+/// Trees get SIGTERM first; those still registered after `grace` (their
+/// output pipes are still open) are force-killed. Blocks until then and
+/// returns how many children were signalled.
+pub fn kill_all(grace: Duration) -> usize {
+    for task in running().lock().unwrap_or_else(|e| e.into_inner()).values() {
+        task.cancelled.store(true, Ordering::SeqCst);
+    }
+    for cancelled in waiting().lock().unwrap_or_else(|e| e.into_inner()).values() {
+        cancelled.store(true, Ordering::SeqCst);
+    }
+
+    let pids = registered_pids();
+    for &pid in &pids {
+        signal_tree(pid, false);
+    }
+    let deadline = Instant::now() + grace;
+    loop {
+        let remaining: Vec<u32> = registered_pids().into_iter().filter(|pid| pids.contains(pid)).collect();
+        if remaining.is_empty() {
+            break;
+        }
+        if Instant::now() >= deadline {
+            for pid in remaining {
+                kill_tree(pid);
+            }
+            break;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    pids.len()
+}
+
+/// Stop a running task gently: SIGTERM to its tree, then SIGKILL if it still
+/// runs after `grace`. Blocks until then; false when no such task is running
+pub fn terminate(task_id: &str, grace: Duration) -> bool {
+    let Some(task) = running().lock().unwrap_or_else(|e| e.into_inner()).get(task_id).cloned() else {
+        return false;
+    };
+    task.cancelled.store(true, Ordering::SeqCst);
+    signal_tree(task.pid, false);
+
+    // The registration drops once the child is gone and its pipes are closed
+    let still_running = || {
+        running()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(task_id)
+            .is_some_and(|t| t.pid == task.pid)
+    };
+    let deadline = Instant::now() + grace;
+    while still_running() && Instant::now() < deadline {
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    if still_running() {
+        kill_tree(task.pid);
+    }
+    true
+}
+
+/// Write a line to a running task's stdin (answering its prompt)
+pub fn send_input(task_id: &str, text: &str) -> Result<(), String> {
+    let task = running()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(task_id)
+        .cloned()
+        .ok_or_else(|| format!("Task '{}' is not running", task_id))?;
+    task.write_input(text)
+}
+
+/// Wait `delay` before a task's next attempt; false when it was cancelled meanwhile
+pub async fn wait_before_retry(task_id: &str, delay: Duration) -> bool {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    waiting()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(task_id.to_string(), cancelled.clone());
+
+    let deadline = Instant::now() + delay;
+    while Instant::now() < deadline && !cancelled.load(Ordering::SeqCst) {
+        tokio::time::sleep(POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now()))).await;
+    }
+
+    waiting().lock().unwrap_or_else(|e| e.into_inner()).remove(task_id);
+    !cancelled.load(Ordering::SeqCst)
+}
+
+/// Kill a running task's process tree (or stop its retries); false when no such task is running
+pub fn cancel(task_id: &str) -> bool {
+    let task = running()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(task_id)
+        .cloned();
+    if let Some(task) = task {
+        task.cancelled.store(true, Ordering::SeqCst);
+        kill_tree(task.pid);
+        return true;
+    }
+
+    match waiting().lock().unwrap_or_else(|e| e.into_inner()).get(task_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Stop a task: a queued one never starts, a running one resolves with `success: false`
+#[tauri::command]
+pub async fn cancel_task(app: tauri::AppHandle, task_id: String) -> Result<(), String> {
+    crate::command_metrics::timed("cancel_task", async move {
+        if crate::task_queue::cancel_pending(&app, &task_id) || cancel(&task_id) {
+            Ok(())
+        } else {
+            Err(format!("Task '{}' is not running", task_id))
+        }
+    }).await
+}
+
+/// Result of `kill_all_tasks`
+#[derive(Debug, Clone, Serialize)]
+pub struct KilledTasks {
+    /// Queued tasks dropped before they started
+    pub dequeued: usize,
+    /// Process trees signalled
+    pub killed: usize,
+}
+
+/// Stop everything: drop queued tasks and kill every running task, workflow and helper script
+#[tauri::command]
+pub async fn kill_all_tasks(app: tauri::AppHandle) -> Result<KilledTasks, String> {
+    crate::command_metrics::timed("kill_all_tasks", async move {
+        let dequeued = crate::task_queue::cancel_all_pending(&app);
+        let grace = Duration::from_secs(TaskProcessSettings::load().kill_grace_secs);
+        let result = tauri::async_runtime::spawn_blocking(move || kill_all(grace))
+            .await
+            .map(|killed| KilledTasks { dequeued, killed })
+            .map_err(|e| format!("Failed to kill tasks: {}", e));
+        crate::audit::record("kill_all_tasks", serde_json::json!({}), crate::audit::ORIGIN_COMMAND, &result);
+        result
+    }).await
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_kills_the_process_tree() {
+        let task_id = uuid::Uuid::new_v4().to_string();
+        let waiter = {
+            let task_id = task_id.clone();
+            std::thread::spawn(move || {
+                // The grandchild keeps the pipes open unless the whole group dies
+                run(&task_id, Command::new("sh").args(["-c", "echo started; sleep 30 & sleep 30"]), None, None, None)
+            })
+        };
+
+        let started = std::time::Instant::now();
+        while !cancel(&task_id) {
+            assert!(started.elapsed() < Duration::from_secs(5), "task never registered");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        let output = waiter.join().unwrap().unwrap();
+        assert_eq!(output.ending, Ending::Cancelled);
+        assert!(!output.success);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!cancel(&task_id));
+    }
+
+    #[test]
+    fn test_timeout_kills_the_process_tree() {
+        let started = std::time::Instant::now();
+        let output = run(
+            "timeout-test",
+            Command::new("sh").args(["-c", "echo working; sleep 30 & sleep 30"]),
+            Some(Duration::from_millis(300)),
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(output.ending, Ending::TimedOut(Duration::from_millis(300)));
+        assert!(!output.success);
+        assert!(output.stdout.contains("working"));
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(TaskProcessSettings { timeout_secs: 0, ..Default::default() }.timeout(None), None);
+        assert_eq!(TaskProcessSettings::default().timeout(Some(5)), Some(Duration::from_secs(5)));
+        assert_eq!(TaskProcessSettings { input_timeout_secs: 0, ..Default::default() }.input_timeout(), None);
+    }
+
+    #[test]
+    fn test_failed_run_keeps_streams_and_exit_code_apart() {
+        let output = run("exit-test", Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]), None, None, None).unwrap();
+
+        assert_eq!((output.success, output.exit_code, output.ending), (false, Some(3), Ending::Exited));
+        assert_eq!((output.stdout.as_str(), output.stderr.as_str()), ("out\n", "err\n"));
+        assert_eq!(output.console(false), "out\n");
+        assert_eq!(output.console(true), "out\n\nerr\n");
+    }
+
+    #[test]
+    fn test_characters_split_across_reads_stay_whole() {
+        let mut pending = "Tiếp tục".as_bytes().to_vec();
+        let split = pending.len() - 2;
+        let tail = pending.split_off(split);
+        assert_eq!(take_utf8(&mut pending), "Tiếp t");
+        assert_eq!(pending, vec![0xe1, 0xbb]);
+        pending.extend_from_slice(&tail);
+        assert_eq!(take_utf8(&mut pending), "ục");
+        assert!(pending.is_empty());
+
+        let mut pending = vec![b'a', 0xff, b'b'];
+        assert_eq!(take_utf8(&mut pending), "a\u{fffd}b");
+    }
+
+    #[test]
+    fn test_unanswered_prompt_gets_the_default_answer() {
+        let prompts = Prompts {
+            on_prompt: Arc::new(|prompt: &str| assert_eq!(prompt, "Apply these changes? [y/N]")),
+            answer_after: Some(Duration::from_millis(200)),
+        };
+        let script = "printf 'Apply these changes? [y/N] '; read answer; echo \"answer=$answer\"";
+
+        let output = run("prompt-test", Command::new("sh").args(["-c", script]), Some(Duration::from_secs(10)), Some(prompts), None).unwrap();
+
+        assert_eq!(output.ending, Ending::Exited);
+        assert!(output.stdout.ends_with("answer=n\n"), "{:?}", output.stdout);
+        assert!(send_input("prompt-test", "y").is_err(), "finished tasks take no input");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_a_retry_wait() {
+        let started = std::time::Instant::now();
+        let waiter = tokio::spawn(async { wait_before_retry("retry-test", Duration::from_secs(30)).await });
+
+        while !cancel("retry-test") {
+            assert!(started.elapsed() < Duration::from_secs(5), "wait never registered");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        assert!(!waiter.await.unwrap(), "cancelled wait reports false");
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(wait_before_retry("retry-test", Duration::from_millis(10)).await);
+    }
+}
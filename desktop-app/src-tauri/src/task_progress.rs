@@ -0,0 +1,271 @@
+/// Task Progress - Progress markers in streamed task output
+///
+/// Every line a task or workflow prints is matched against the templates of
+/// the `progressMarkers` settings block while the run streams. A step marker
+/// (`[STEP 2/5] Generating tests`) or a percentage (`Progress: 40%`) is
+/// emitted as a `task-progress` event; a file marker (`FILE CHANGED: src/a.rs`)
+/// adds the file to the run's changed files like `add_changed_file`.
+///
+/// Templates are plain text with placeholders: `{step}`, `{total}` and
+/// `{percent}` match a number, `{message}` and `{path}` match text up to the
+/// template's next literal (or the end of the line). A template may match
+/// anywhere in a line, so log prefixes need no special handling.
+
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::child_env::ChildEnv;
+
+pub const TASK_PROGRESS_EVENT: &str = "task-progress";
+
+/// Called with each complete line of a run's output
+pub type LineHandler = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// `progressMarkers` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProgressMarkerSettings {
+    /// Templates with `{step}` and `{total}` (and optionally `{message}`)
+    pub steps: Vec<String>,
+    /// Templates with `{percent}` (and optionally `{message}`)
+    pub percent: Vec<String>,
+    /// Templates with `{path}`
+    pub file_changed: Vec<String>,
+}
+
+impl Default for ProgressMarkerSettings {
+    fn default() -> Self {
+        Self {
+            steps: vec!["[STEP {step}/{total}] {message}".to_string()],
+            percent: vec!["[{percent}%] {message}".to_string(), "Progress: {percent}%".to_string()],
+            file_changed: vec!["FILE CHANGED: {path}".to_string()],
+        }
+    }
+}
+
+impl ProgressMarkerSettings {
+    /// Load the progressMarkers block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("progressMarkers").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Literal(String),
+    Field(String),
+}
+
+fn is_number_field(name: &str) -> bool {
+    matches!(name, "step" | "total" | "percent")
+}
+
+/// Split a template into literals and `{name}` placeholders
+fn pieces(template: &str) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|i| open + i) else { break };
+        if open > 0 {
+            pieces.push(Piece::Literal(rest[..open].to_string()));
+        }
+        pieces.push(Piece::Field(rest[open + 1..close].to_string()));
+        rest = &rest[close + 1..];
+    }
+    if !rest.is_empty() {
+        pieces.push(Piece::Literal(rest.to_string()));
+    }
+    pieces
+}
+
+/// Match `pieces` at the start of `text`, collecting field values
+fn match_at(pieces: &[Piece], text: &str) -> Option<Vec<(String, String)>> {
+    let Some((first, rest)) = pieces.split_first() else { return Some(Vec::new()) };
+    match first {
+        Piece::Literal(literal) => match_at(rest, text.strip_prefix(literal.as_str())?),
+        Piece::Field(name) if is_number_field(name) => {
+            let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if digits == 0 {
+                return None;
+            }
+            let mut fields = match_at(rest, &text[digits..])?;
+            fields.push((name.clone(), text[..digits].to_string()));
+            Some(fields)
+        }
+        Piece::Field(name) => {
+            let value_ends: Vec<usize> = match rest.first() {
+                None => vec![text.len()],
+                Some(Piece::Literal(next)) => text.match_indices(next.as_str()).map(|(i, _)| i).collect(),
+                // Two fields in a row: the text one takes nothing
+                Some(Piece::Field(_)) => vec![0],
+            };
+            value_ends.into_iter().find_map(|end| {
+                let mut fields = match_at(rest, &text[end..])?;
+                fields.push((name.clone(), text[..end].trim().to_string()));
+                Some(fields)
+            })
+        }
+    }
+}
+
+/// Match a template anywhere in `line` (never starting inside a number)
+fn find(pieces: &[Piece], line: &str) -> Option<Vec<(String, String)>> {
+    if pieces.is_empty() {
+        return None;
+    }
+    line.char_indices()
+        .filter(|&(i, _)| !line[..i].ends_with(|c: char| c.is_ascii_digit()))
+        .find_map(|(i, _)| match_at(pieces, &line[i..]))
+}
+
+fn field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    fields.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+}
+
+/// What a line reported
+#[derive(Debug, Clone, PartialEq)]
+pub enum Marker {
+    Progress { step: Option<u32>, total_steps: Option<u32>, percent: Option<u32>, message: String },
+    FileChanged(String),
+}
+
+/// The configured templates, parsed once per run
+pub struct MarkerParser {
+    steps: Vec<Vec<Piece>>,
+    percent: Vec<Vec<Piece>>,
+    file_changed: Vec<Vec<Piece>>,
+}
+
+impl MarkerParser {
+    pub fn new(settings: &ProgressMarkerSettings) -> Self {
+        let parse = |templates: &[String]| -> Vec<Vec<Piece>> { templates.iter().map(|t| pieces(t)).collect() };
+        Self {
+            steps: parse(&settings.steps),
+            percent: parse(&settings.percent),
+            file_changed: parse(&settings.file_changed),
+        }
+    }
+
+    /// The marker on `line`, if any
+    pub fn parse(&self, line: &str) -> Option<Marker> {
+        let first = |templates: &[Vec<Piece>]| templates.iter().find_map(|t| find(t, line));
+        let message = |fields: &[(String, String)]| field(fields, "message").unwrap_or_default().to_string();
+
+        if let Some(fields) = first(&self.file_changed) {
+            let path = field(&fields, "path").unwrap_or_default();
+            if !path.is_empty() {
+                return Some(Marker::FileChanged(path.to_string()));
+            }
+        }
+        if let Some(fields) = first(&self.steps) {
+            let step = field(&fields, "step").and_then(|v| v.parse::<u32>().ok());
+            let total_steps = field(&fields, "total").and_then(|v| v.parse::<u32>().ok());
+            let percent = match (step, total_steps) {
+                (Some(step), Some(total)) if total > 0 => Some((step.min(total) * 100) / total),
+                _ => None,
+            };
+            return Some(Marker::Progress { step, total_steps, percent, message: message(&fields) });
+        }
+        let fields = first(&self.percent)?;
+        let percent = field(&fields, "percent").and_then(|v| v.parse::<u32>().ok()).map(|p| p.min(100));
+        Some(Marker::Progress { step: None, total_steps: None, percent, message: message(&fields) })
+    }
+}
+
+/// Payload of `task-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskProgress<'a> {
+    pub task_id: &'a str,
+    pub step: Option<u32>,
+    pub total_steps: Option<u32>,
+    pub percent: Option<u32>,
+    pub message: &'a str,
+}
+
+/// Line handler emitting `task-progress` and recording changed files for `task_id`
+///
+/// Lines are masked with `env` first, so provided secrets never reach an event.
+pub fn watcher(app: &tauri::AppHandle, task_id: &str, env: ChildEnv) -> LineHandler {
+    let parser = MarkerParser::new(&ProgressMarkerSettings::load());
+    let (app, task_id) = (app.clone(), task_id.to_string());
+    Arc::new(move |line| match parser.parse(&env.mask_values(line)) {
+        Some(Marker::Progress { step, total_steps, percent, message }) => {
+            let _ = app.emit(
+                TASK_PROGRESS_EVENT,
+                TaskProgress { task_id: &task_id, step, total_steps, percent, message: &message },
+            );
+        }
+        Some(Marker::FileChanged(path)) => crate::record_reported_file(&app, &task_id, &path),
+        None => {}
+    })
+}
+
+/// Cuts streamed output into complete lines
+#[derive(Default)]
+pub struct LineSplitter {
+    partial: String,
+}
+
+impl LineSplitter {
+    /// Feed the next chunk; `on_line` gets every line it completes
+    pub fn feed(&mut self, chunk: &str, on_line: &dyn Fn(&str)) {
+        self.partial.push_str(chunk);
+        while let Some(newline) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=newline).collect();
+            on_line(line.trim_end_matches(['\n', '\r']));
+        }
+    }
+
+    /// The last line, when output ended without a newline
+    pub fn finish(&mut self, on_line: &dyn Fn(&str)) {
+        if !self.partial.is_empty() {
+            on_line(&std::mem::take(&mut self.partial));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_markers() {
+        let parser = MarkerParser::new(&ProgressMarkerSettings::default());
+        assert_eq!(
+            parser.parse("12:00:01 [STEP 2/5] Generating tests"),
+            Some(Marker::Progress { step: Some(2), total_steps: Some(5), percent: Some(40), message: "Generating tests".into() })
+        );
+        assert_eq!(
+            parser.parse("Progress: 75%"),
+            Some(Marker::Progress { step: None, total_steps: None, percent: Some(75), message: String::new() })
+        );
+        assert_eq!(parser.parse("FILE CHANGED: src/main.rs"), Some(Marker::FileChanged("src/main.rs".into())));
+        assert_eq!(parser.parse("Auto-routed: api (85% confidence)"), None);
+        assert_eq!(parser.parse("[STEP x/5] nothing"), None);
+
+        let custom = ProgressMarkerSettings {
+            steps: vec!["==> ({step} of {total}) {message} ...".into()],
+            percent: Vec::new(),
+            file_changed: vec!["wrote '{path}'".into()],
+        };
+        let parser = MarkerParser::new(&custom);
+        assert_eq!(
+            parser.parse("==> (3 of 4) Running lint ..."),
+            Some(Marker::Progress { step: Some(3), total_steps: Some(4), percent: Some(75), message: "Running lint".into() })
+        );
+        assert_eq!(parser.parse("wrote 'docs/a b.md'"), Some(Marker::FileChanged("docs/a b.md".into())));
+
+        let lines = std::cell::RefCell::new(Vec::new());
+        let mut splitter = LineSplitter::default();
+        splitter.feed("[STEP 1/2] a\r\n[ST", &|l| lines.borrow_mut().push(l.to_string()));
+        splitter.feed("EP 2/2] b", &|l| lines.borrow_mut().push(l.to_string()));
+        splitter.finish(&|l| lines.borrow_mut().push(l.to_string()));
+        assert_eq!(lines.into_inner(), vec!["[STEP 1/2] a", "[STEP 2/2] b"]);
+    }
+}
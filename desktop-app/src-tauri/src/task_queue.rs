@@ -0,0 +1,259 @@
+/// Task Queue - Serialized vibe.py runs with configurable concurrency
+///
+/// `enqueue_task` appends a task and returns its place in line; a worker
+/// starts queued tasks in order while fewer than `taskQueue.maxConcurrency`
+/// (default 1) are running, so two tasks fired from the UI no longer edit the
+/// same files at once. Each finished run is emitted as `task-finished`.
+/// `cancel_task` drops a task that is still queued before python is ever
+/// spawned. `execute_task` stays the immediate path and bypasses the queue.
+/// Queued tasks also show up in the `TaskManager` as `queued`. Raising
+/// `maxConcurrency` in the settings starts the tasks it allows right away.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::task_manager::{self, TaskKind, TaskStatus};
+use crate::TaskResult;
+
+pub const TASK_FINISHED_EVENT: &str = "task-finished";
+
+/// `taskQueue` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskQueueSettings {
+    /// Queued tasks running at the same time
+    pub max_concurrency: usize,
+}
+
+impl Default for TaskQueueSettings {
+    fn default() -> Self {
+        Self { max_concurrency: 1 }
+    }
+}
+
+impl TaskQueueSettings {
+    /// Load the taskQueue block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("taskQueue").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueState {
+    Pending,
+    Running,
+}
+
+/// Entry of `get_task_queue`
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedTask {
+    pub task_id: String,
+    pub task: String,
+    pub agent: String,
+    pub state: QueueState,
+    /// 1-based place among pending tasks, None once running
+    pub position: Option<usize>,
+    pub enqueued_at: String, // ISO 8601
+}
+
+/// Payload of `task-finished`
+#[derive(Debug, Clone, Serialize)]
+struct TaskFinished<'a> {
+    task_id: &'a str,
+    result: Option<&'a TaskResult>,
+    error: Option<&'a str>,
+}
+
+#[derive(Default)]
+struct Queue {
+    pending: VecDeque<QueuedTask>,
+    running: Vec<QueuedTask>,
+}
+
+impl Queue {
+    fn push(&mut self, task_id: String, task: String, agent: String) -> usize {
+        self.pending.push_back(QueuedTask {
+            task_id,
+            task,
+            agent,
+            state: QueueState::Pending,
+            position: None,
+            enqueued_at: chrono::Utc::now().to_rfc3339(),
+        });
+        self.pending.len()
+    }
+
+    /// Move the next pending tasks to running while under `max_concurrency`
+    fn start_ready(&mut self, max_concurrency: usize) -> Vec<QueuedTask> {
+        let mut started = Vec::new();
+        while self.running.len() < max_concurrency.max(1) {
+            let Some(mut next) = self.pending.pop_front() else { break };
+            next.state = QueueState::Running;
+            self.running.push(next.clone());
+            started.push(next);
+        }
+        started
+    }
+
+    fn finish(&mut self, task_id: &str) {
+        self.running.retain(|t| t.task_id != task_id);
+    }
+
+    /// Drop a task that has not started; false when it is not pending
+    fn remove_pending(&mut self, task_id: &str) -> bool {
+        let before = self.pending.len();
+        self.pending.retain(|t| t.task_id != task_id);
+        self.pending.len() != before
+    }
+
+    fn snapshot(&self) -> Vec<QueuedTask> {
+        let running = self.running.iter().cloned();
+        let pending = self.pending.iter().enumerate().map(|(i, t)| QueuedTask {
+            position: Some(i + 1),
+            ..t.clone()
+        });
+        running.chain(pending).collect()
+    }
+}
+
+static QUEUE: OnceLock<Mutex<Queue>> = OnceLock::new();
+
+fn queue() -> std::sync::MutexGuard<'static, Queue> {
+    QUEUE
+        .get_or_init(|| Mutex::new(Queue::default()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Start whatever the concurrency limit allows
+fn pump(app: &tauri::AppHandle) {
+    let started = queue().start_ready(TaskQueueSettings::load().max_concurrency);
+
+    for entry in started {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let run = crate::TaskRun {
+                task: entry.task,
+                agent: entry.agent,
+                task_id: entry.task_id.clone(),
+                timeout: crate::task_process::TaskProcessSettings::load().timeout(None),
+                env: None,
+                retry: None,
+                labels: Vec::new(),
+                auto_fallback: false,
+            };
+            let result = crate::run_task(&app, run).await;
+            queue().finish(&entry.task_id);
+
+            let _ = app.emit(
+                TASK_FINISHED_EVENT,
+                TaskFinished {
+                    task_id: &entry.task_id,
+                    result: result.as_ref().ok(),
+                    error: result.as_ref().err().map(String::as_str),
+                },
+            );
+            pump(&app);
+        });
+    }
+}
+
+/// Remove a queued task before it starts; false when it is not queued
+pub fn cancel_pending(app: &tauri::AppHandle, task_id: &str) -> bool {
+    if !queue().remove_pending(task_id) {
+        return false;
+    }
+    task_manager::manager(app).finish(task_id, TaskStatus::Cancelled);
+    let _ = app.emit(
+        TASK_FINISHED_EVENT,
+        TaskFinished {
+            task_id,
+            result: None,
+            error: Some("Task cancelled before it started"),
+        },
+    );
+    true
+}
+
+/// Remove every queued task that has not started; returns how many were removed
+pub fn cancel_all_pending(app: &tauri::AppHandle) -> usize {
+    let pending: Vec<String> = queue().pending.iter().map(|t| t.task_id.clone()).collect();
+    pending.iter().filter(|task_id| cancel_pending(app, task_id)).count()
+}
+
+/// Result of `enqueue_task`
+#[derive(Debug, Clone, Serialize)]
+pub struct EnqueuedTask {
+    pub task_id: String,
+    /// 1-based place in line when queued; 0 when it started right away
+    pub position: usize,
+}
+
+/// Start queued tasks when `maxConcurrency` changes (called once at startup)
+pub fn spawn(app: tauri::AppHandle) {
+    crate::settings_events::listen(&["/taskQueue/maxConcurrency"], move |_| pump(&app));
+}
+
+/// Queue a task; it runs once earlier tasks leave a free slot
+#[tauri::command]
+pub async fn enqueue_task(app: tauri::AppHandle, task: String, agent: String) -> Result<EnqueuedTask, String> {
+    crate::command_metrics::timed("enqueue_task", async move {
+        let task_id = uuid::Uuid::new_v4().to_string();
+        task_manager::manager(&app).queue(&task_id, TaskKind::Task, &task);
+        queue().push(task_id.clone(), task, agent);
+        pump(&app);
+
+        let position = queue()
+            .pending
+            .iter()
+            .position(|t| t.task_id == task_id)
+            .map_or(0, |i| i + 1);
+        Ok(EnqueuedTask { task_id, position })
+    }).await
+}
+
+/// Running tasks, then pending tasks in the order they will start
+#[tauri::command]
+pub async fn get_task_queue() -> Result<Vec<QueuedTask>, String> {
+    crate::command_metrics::timed("get_task_queue", async move {
+        Ok(queue().snapshot())
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_respects_concurrency_and_cancels_pending() {
+        let mut q = Queue::default();
+        assert_eq!(q.push("a".into(), "first".into(), "auto".into()), 1);
+        assert_eq!(q.push("b".into(), "second".into(), "cli".into()), 2);
+        assert_eq!(q.push("c".into(), "third".into(), "api".into()), 3);
+
+        let started = q.start_ready(1);
+        assert_eq!(started.iter().map(|t| t.task_id.as_str()).collect::<Vec<_>>(), vec!["a"]);
+        assert!(q.start_ready(1).is_empty(), "slot still taken");
+
+        assert!(q.remove_pending("b"));
+        assert!(!q.remove_pending("a"), "running tasks are not pending");
+
+        let snapshot = q.snapshot();
+        assert_eq!(snapshot[0].state, QueueState::Running);
+        assert_eq!(snapshot[0].position, None);
+        assert_eq!((snapshot[1].task_id.as_str(), snapshot[1].position), ("c", Some(1)));
+
+        q.finish("a");
+        let started = q.start_ready(0);
+        assert_eq!(started.iter().map(|t| t.task_id.as_str()).collect::<Vec<_>>(), vec!["c"]);
+        assert!(q.pending.is_empty());
+    }
+}
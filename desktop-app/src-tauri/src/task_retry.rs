@@ -0,0 +1,88 @@
+/// Task Retry - Re-running tasks that failed on a transient error
+///
+/// `execute_task` takes an optional `RetryPolicy`. A run that exits with a
+/// failure whose output looks transient (rate limit, HTTP 429, connection
+/// reset) is run again after an exponential backoff, the same scheme
+/// `ProcessFinder::detect` uses: `base_delay_ms * 2^n`, capped at
+/// `MAX_DELAY_MS`. Every retry is announced as a `task-retry` event, and
+/// cancelled or timed-out runs are never retried.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const TASK_RETRY_EVENT: &str = "task-retry";
+
+/// Longest wait between two attempts
+const MAX_DELAY_MS: u64 = 60_000;
+
+/// Output fragments (lowercase) of errors worth another attempt
+const RETRYABLE_PATTERNS: &[&str] = &[
+    "rate limit",
+    "rate_limit",
+    "ratelimit",
+    "too many requests",
+    "connection reset",
+    "econnreset",
+    "overloaded",
+];
+
+/// How often a failed task is attempted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first run included
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+}
+
+impl RetryPolicy {
+    /// Wait before attempt `attempt + 1`, after `attempt` (1-based) failed
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2_u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(self.base_delay_ms.saturating_mul(factor).min(MAX_DELAY_MS))
+    }
+}
+
+/// Payload of `task-retry`
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskRetry<'a> {
+    pub task_id: &'a str,
+    /// Attempt about to start (2 for the first retry)
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub delay_ms: u64,
+    /// Pattern that made the failure retryable
+    pub reason: &'a str,
+}
+
+/// The pattern that marks `output` as a transient failure, if any
+pub fn retryable_reason(output: &str) -> Option<&'static str> {
+    let lower = output.to_lowercase();
+    if let Some(pattern) = RETRYABLE_PATTERNS.iter().copied().find(|p| lower.contains(p)) {
+        return Some(pattern);
+    }
+    // "429" only as a number of its own, not inside ids or timings
+    lower
+        .split(|c: char| !c.is_ascii_digit())
+        .any(|number| number == "429")
+        .then_some("429")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryable_output_and_backoff() {
+        assert_eq!(retryable_reason("Error: Rate limit exceeded, slow down"), Some("rate limit"));
+        assert_eq!(retryable_reason("anthropic.APIStatusError: 429 Too Many Requests"), Some("too many requests"));
+        assert_eq!(retryable_reason("HTTP status 429"), Some("429"));
+        assert_eq!(retryable_reason("[Errno 104] Connection reset by peer"), Some("connection reset"));
+        assert_eq!(retryable_reason("request id 84291 failed: invalid prompt"), None);
+        assert_eq!(retryable_reason("SyntaxError: invalid syntax"), None);
+
+        let policy = RetryPolicy { max_attempts: 4, base_delay_ms: 1000 };
+        assert_eq!(policy.delay(1), Duration::from_millis(1000));
+        assert_eq!(policy.delay(3), Duration::from_millis(4000));
+        assert_eq!(policy.delay(40), Duration::from_millis(MAX_DELAY_MS));
+    }
+}
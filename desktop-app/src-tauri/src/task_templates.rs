@@ -0,0 +1,267 @@
+/// Task Templates - Saved prompts with `{placeholder}` substitution
+///
+/// A template is one JSON file named after its slug. Global templates live in
+/// `<config>/task_templates/`; a project can override any of them (or add its
+/// own) in `<project>/.agent/task_templates/`, and the project copy wins in
+/// `list_task_templates` and `run_task_template`. Placeholders are
+/// `{identifier}` tokens; other braces are left alone, and substituted values
+/// are never scanned again.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::{name_lint, TaskResult};
+
+const TEMPLATES_DIR: &str = "task_templates";
+
+pub const SCOPE_GLOBAL: &str = "global";
+pub const SCOPE_PROJECT: &str = "project";
+
+/// Stored template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskTemplate {
+    pub name: String,
+    pub template: String,
+    pub default_agent: String,
+    pub updated_at: String, // ISO 8601
+}
+
+/// Entry of `list_task_templates`
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskTemplateInfo {
+    #[serde(flatten)]
+    pub template: TaskTemplate,
+    /// Placeholder names in order of first use
+    pub placeholders: Vec<String>,
+    /// "global" | "project"
+    pub scope: String,
+}
+
+fn global_dir() -> PathBuf {
+    crate::get_app_config_dir().join(TEMPLATES_DIR)
+}
+
+fn project_dir() -> Option<PathBuf> {
+    let project = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone())?;
+    Some(PathBuf::from(project).join(".agent").join(TEMPLATES_DIR))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split a template into literal text and `{identifier}` placeholders
+fn tokens(template: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|i| open + i) else { break };
+        let name = &rest[open + 1..close];
+        if is_identifier(name) {
+            tokens.push(Token::Text(&rest[..open]));
+            tokens.push(Token::Placeholder(name));
+            rest = &rest[close + 1..];
+        } else {
+            // Not a placeholder ("{ }", JSON): keep the brace as text
+            tokens.push(Token::Text(&rest[..open + 1]));
+            rest = &rest[open + 1..];
+        }
+    }
+    tokens.push(Token::Text(rest));
+    tokens
+}
+
+/// Placeholder names in order of first use
+pub fn placeholders(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for token in tokens(template) {
+        if let Token::Placeholder(name) = token {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Fill every placeholder; errors name all variables that are missing
+pub fn render(template: &str, variables: &HashMap<String, String>) -> Result<String, String> {
+    let missing: Vec<String> = placeholders(template)
+        .into_iter()
+        .filter(|name| !variables.contains_key(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("Missing template variables: {}", missing.join(", ")));
+    }
+
+    Ok(tokens(template)
+        .into_iter()
+        .map(|token| match token {
+            Token::Text(text) => text,
+            Token::Placeholder(name) => variables[name].as_str(),
+        })
+        .collect())
+}
+
+fn read_dir(dir: &Path) -> Vec<TaskTemplate> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            serde_json::from_str(&content)
+                .map_err(|e| eprintln!("Task templates: skipping {}: {}", path.display(), e))
+                .ok()
+        })
+        .collect()
+}
+
+/// Templates by slug, project ones replacing global ones of the same slug
+fn load_all(global: &Path, project: Option<&Path>) -> BTreeMap<String, TaskTemplateInfo> {
+    let mut templates = BTreeMap::new();
+    let scopes = [(Some(global), SCOPE_GLOBAL), (project, SCOPE_PROJECT)];
+    for (dir, scope) in scopes {
+        for template in dir.map(read_dir).unwrap_or_default() {
+            templates.insert(
+                name_lint::slugify(&template.name),
+                TaskTemplateInfo {
+                    placeholders: placeholders(&template.template),
+                    scope: scope.to_string(),
+                    template,
+                },
+            );
+        }
+    }
+    templates
+}
+
+fn save_in(dir: &Path, template: &TaskTemplate) -> Result<PathBuf, String> {
+    let slug = name_lint::slugify(&template.name);
+    if slug.is_empty() {
+        return Err(format!("Invalid template name: '{}'", template.name));
+    }
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create templates directory: {}", e))?;
+
+    let content = serde_json::to_string_pretty(template)
+        .map_err(|e| format!("Failed to serialize template: {}", e))?;
+    let path = dir.join(format!("{}.json", slug));
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to save template: {}", e))?;
+    std::fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Failed to save template: {}", e))?;
+    Ok(path)
+}
+
+/// Save a task template; `scope: "project"` stores it as an override for the open project
+#[tauri::command]
+pub async fn save_task_template(
+    name: String,
+    template: String,
+    default_agent: String,
+    scope: Option<String>,
+) -> Result<TaskTemplateInfo, String> {
+    crate::command_metrics::timed("save_task_template", async move {
+        let scope = scope.unwrap_or_else(|| SCOPE_GLOBAL.to_string());
+        let dir = match scope.as_str() {
+            SCOPE_GLOBAL => global_dir(),
+            SCOPE_PROJECT => project_dir().ok_or("No project is open")?,
+            other => return Err(format!("Unknown template scope: '{}'", other)),
+        };
+        if template.trim().is_empty() {
+            return Err("Template is empty".to_string());
+        }
+
+        let template = TaskTemplate {
+            name: name.trim().to_string(),
+            template,
+            default_agent,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        };
+        save_in(&dir, &template)?;
+        Ok(TaskTemplateInfo { placeholders: placeholders(&template.template), scope, template })
+    }).await
+}
+
+/// List task templates; a project override replaces the global template of the same name
+#[tauri::command]
+pub async fn list_task_templates() -> Result<Vec<TaskTemplateInfo>, String> {
+    crate::command_metrics::timed("list_task_templates", async move {
+        Ok(load_all(&global_dir(), project_dir().as_deref()).into_values().collect())
+    }).await
+}
+
+/// Fill a template's placeholders and execute it with the template's agent
+#[tauri::command]
+pub async fn run_task_template(
+    app: tauri::AppHandle,
+    name: String,
+    variables: HashMap<String, String>,
+) -> Result<TaskResult, String> {
+    crate::command_metrics::timed("run_task_template", async move {
+        let info = load_all(&global_dir(), project_dir().as_deref())
+            .remove(&name_lint::slugify(&name))
+            .ok_or_else(|| format!("Task template '{}' not found", name))?;
+        let task = render(&info.template.template, &variables)?;
+
+        crate::execute_task(app, task, info.template.default_agent, None, None, None, None, None, None).await
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_render_substitutes_once_and_reports_missing() {
+        let template = "Refactor {module} to async, keep {module}'s API; json: { \"a\": 1 }";
+        assert_eq!(placeholders(template), vec!["module"]);
+        assert_eq!(
+            render(template, &vars(&[("module", "{file}")])).unwrap(),
+            "Refactor {file} to async, keep {file}'s API; json: { \"a\": 1 }"
+        );
+
+        let err = render("Add tests for {file} in {crate_name}", &vars(&[])).unwrap_err();
+        assert_eq!(err, "Missing template variables: file, crate_name");
+    }
+
+    #[test]
+    fn test_project_templates_override_global_ones() {
+        let root = std::env::temp_dir().join(format!("vibecode-templates-{}", uuid::Uuid::new_v4()));
+        let (global, project) = (root.join("global"), root.join("project"));
+        let template = |name: &str, text: &str| TaskTemplate {
+            name: name.to_string(),
+            template: text.to_string(),
+            default_agent: "cli".to_string(),
+            updated_at: String::new(),
+        };
+
+        save_in(&global, &template("Add Tests", "add tests for {file}")).unwrap();
+        save_in(&global, &template("Docs", "document {module}")).unwrap();
+        save_in(&project, &template("Add Tests", "add pytest tests for {file}")).unwrap();
+
+        let all = load_all(&global, Some(&project));
+        assert_eq!(all.len(), 2);
+        let add_tests = &all[&name_lint::slugify("Add Tests")];
+        assert_eq!(add_tests.scope, SCOPE_PROJECT);
+        assert_eq!(add_tests.template.template, "add pytest tests for {file}");
+        assert_eq!(all[&name_lint::slugify("docs")].scope, SCOPE_GLOBAL);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,325 @@
+/// Temp Sweep - Removal of litter left by interrupted exports, imports and runs
+///
+/// Only names the app itself creates are ever considered:
+/// - `.<id>.importing-<uuid>` staging folders in a skills directory
+/// - `<id>_v<version>.zip` export packages that are not readable ZIPs (an
+///   export that died mid-write)
+/// - `vibecode-runs/<uuid>` script run folders in the OS temp directory
+/// - `vibecode-git-import/<uuid>` checkouts left by a crash during `import_from_git`
+///
+/// A candidate is removed only when it is older than `tempSweep.maxAgeHours`
+/// and no audit entry mentions it. Anything else in those directories is left
+/// alone. Every removal is recorded in the audit log.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+
+/// Marker in import staging folder names (see `skill_archive::import_skill`)
+const IMPORT_STAGING_MARKER: &str = ".importing-";
+/// Parent of per-run temp folders (see `script_runner`)
+const RUN_DIRS: &str = "vibecode-runs";
+/// Parent of per-import checkout folders (see `git_import`)
+const GIT_IMPORT_DIRS: &str = "vibecode-git-import";
+
+/// `tempSweep` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TempSweepSettings {
+    /// Litter younger than this is assumed to belong to a running operation
+    pub max_age_hours: u64,
+}
+
+impl Default for TempSweepSettings {
+    fn default() -> Self {
+        Self { max_age_hours: 24 }
+    }
+}
+
+impl TempSweepSettings {
+    /// Load the tempSweep block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("tempSweep").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// One removed (or failed) candidate
+#[derive(Debug, Clone, Serialize)]
+pub struct SweptArtifact {
+    pub path: String,
+    /// "import_staging" | "partial_export" | "run_dir" | "git_checkout"
+    pub kind: String,
+    pub bytes: u64,
+}
+
+/// Result of one sweep
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TempSweepReport {
+    pub removed: Vec<SweptArtifact>,
+    pub reclaimed_bytes: u64,
+    /// Old candidates kept because an audit entry mentions them
+    pub kept_referenced: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Where to look
+struct SweepRoots {
+    skills_dirs: Vec<PathBuf>,
+    temp_dir: PathBuf,
+}
+
+fn is_uuid(name: &str) -> bool {
+    uuid::Uuid::parse_str(name).is_ok()
+}
+
+/// `.<id>.importing-<uuid>`
+fn is_import_staging(name: &str) -> bool {
+    name.starts_with('.')
+        && name
+            .rsplit_once(IMPORT_STAGING_MARKER)
+            .is_some_and(|(id, suffix)| id.len() > 1 && is_uuid(suffix))
+}
+
+/// `<id>_v<digit>....zip`
+fn is_export_name(name: &str) -> bool {
+    name.strip_suffix(".zip")
+        .and_then(|stem| stem.rfind("_v").map(|idx| &stem[idx + 2..]))
+        .is_some_and(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+fn is_readable_zip(path: &Path) -> bool {
+    File::open(path)
+        .ok()
+        .is_some_and(|f| zip::ZipArchive::new(BufReader::new(f)).is_ok())
+}
+
+fn path_bytes(path: &Path) -> u64 {
+    if path.is_dir() {
+        std::fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|e| path_bytes(&e.path())).sum())
+            .unwrap_or(0)
+    } else {
+        std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+/// Known litter under `roots`, as (path, kind)
+fn find_candidates(roots: &SweepRoots) -> Vec<(PathBuf, &'static str)> {
+    let mut candidates = Vec::new();
+
+    for dir in &roots.skills_dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_dir() && is_import_staging(&name) {
+                candidates.push((path, "import_staging"));
+            } else if path.is_file() && is_export_name(&name) && !is_readable_zip(&path) {
+                candidates.push((path, "partial_export"));
+            }
+        }
+    }
+
+    for (parent, kind) in [(RUN_DIRS, "run_dir"), (GIT_IMPORT_DIRS, "git_checkout")] {
+        let Ok(entries) = std::fs::read_dir(roots.temp_dir.join(parent)) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() && is_uuid(&entry.file_name().to_string_lossy()) {
+                candidates.push((path, kind));
+            }
+        }
+    }
+
+    candidates.sort();
+    candidates
+}
+
+/// Whether `name` is a whole component of any referenced path
+fn is_referenced(name: &str, references: &[String]) -> bool {
+    references.iter().any(|r| r.split(['/', '\\']).any(|component| component == name))
+}
+
+/// Remove old, unreferenced candidates; `on_removed` is called for every attempt
+fn sweep(
+    roots: &SweepRoots,
+    max_age: Duration,
+    now: SystemTime,
+    references: &[String],
+    on_removed: &mut dyn FnMut(&SweptArtifact, &Result<(), String>),
+) -> TempSweepReport {
+    let mut report = TempSweepReport::default();
+
+    for (path, kind) in find_candidates(roots) {
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).unwrap_or(now);
+        if now.duration_since(modified).unwrap_or_default() < max_age {
+            continue;
+        }
+
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if is_referenced(&name, references) {
+            report.kept_referenced.push(path.to_string_lossy().to_string());
+            continue;
+        }
+
+        let artifact = SweptArtifact {
+            path: path.to_string_lossy().to_string(),
+            kind: kind.to_string(),
+            bytes: path_bytes(&path),
+        };
+        let result = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        }
+        .map_err(|e| format!("Failed to remove {}: {}", artifact.path, e));
+
+        on_removed(&artifact, &result);
+        match result {
+            Ok(()) => {
+                report.reclaimed_bytes += artifact.bytes;
+                report.removed.push(artifact);
+            }
+            Err(e) => report.errors.push(e),
+        }
+    }
+
+    report
+}
+
+/// Sweep the current and default skills folders and the OS temp dir
+pub fn run(origin: &str) -> TempSweepReport {
+    let mut skills_dirs = vec![crate::get_skills_path(), crate::get_default_skills_path()];
+    skills_dirs.dedup();
+    let roots = SweepRoots {
+        skills_dirs,
+        temp_dir: std::env::temp_dir(),
+    };
+    let max_age = Duration::from_secs(TempSweepSettings::load().max_age_hours * 60 * 60);
+
+    sweep(
+        &roots,
+        max_age,
+        SystemTime::now(),
+        // Earlier sweeps record the paths they failed to remove; those are not references
+        &crate::audit::referenced_paths("clean_temp_artifacts"),
+        &mut |artifact, result| {
+            crate::audit::record(
+                "clean_temp_artifacts",
+                serde_json::json!({ "path": artifact.path, "kind": artifact.kind, "bytes": artifact.bytes }),
+                origin,
+                result,
+            );
+        },
+    )
+}
+
+/// Sweep once in the background after startup
+pub fn spawn_startup_sweep() {
+    tauri::async_runtime::spawn(async {
+        match tauri::async_runtime::spawn_blocking(|| run(crate::audit::ORIGIN_SCHEDULED)).await {
+            Ok(report) => {
+                if !report.removed.is_empty() {
+                    eprintln!(
+                        "Temp sweep: removed {} items ({} bytes)",
+                        report.removed.len(),
+                        report.reclaimed_bytes
+                    );
+                }
+                for error in report.errors {
+                    eprintln!("Temp sweep: {}", error);
+                }
+            }
+            Err(e) => eprintln!("Temp sweep: task failed: {}", e),
+        }
+    });
+}
+
+/// Remove orphaned import staging folders, partial exports and run folders now
+#[tauri::command]
+pub async fn clean_temp_artifacts() -> Result<TempSweepReport, String> {
+    crate::command_metrics::timed("clean_temp_artifacts", async move {
+        tauri::async_runtime::spawn_blocking(|| run(crate::audit::ORIGIN_COMMAND))
+            .await
+            .map_err(|e| format!("Temp sweep failed: {}", e))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    #[test]
+    fn test_only_old_unreferenced_litter_is_removed() {
+        let root = std::env::temp_dir().join(format!("vibecode-sweep-{}", uuid::Uuid::new_v4()));
+        let skills = root.join("skills");
+        let temp = root.join("tmp");
+        let staging = format!(".pdf-tools.importing-{}", uuid::Uuid::new_v4());
+        let referenced_staging = format!(".charts.importing-{}", uuid::Uuid::new_v4());
+        let run_dir = uuid::Uuid::new_v4().to_string();
+
+        // Litter
+        std::fs::create_dir_all(skills.join(&staging)).unwrap();
+        std::fs::write(skills.join(&staging).join("SKILL.md"), "partial").unwrap();
+        std::fs::create_dir_all(skills.join(&referenced_staging)).unwrap();
+        std::fs::write(skills.join("pdf-tools_v1.2.0.zip"), "not a zip").unwrap();
+        std::fs::create_dir_all(temp.join(RUN_DIRS).join(&run_dir)).unwrap();
+        std::fs::create_dir_all(temp.join(GIT_IMPORT_DIRS).join(&run_dir)).unwrap();
+
+        // Things that must survive
+        std::fs::create_dir_all(skills.join("pdf-tools")).unwrap();
+        std::fs::create_dir_all(skills.join(".importing-notes")).unwrap();
+        std::fs::write(skills.join("backup.zip"), "user file").unwrap();
+        std::fs::write(skills.join("notes_v2.txt"), "user file").unwrap();
+        std::fs::create_dir_all(temp.join(RUN_DIRS).join("keep-me")).unwrap();
+        let mut zip = zip::ZipWriter::new(File::create(skills.join("charts_v1.0.0.zip")).unwrap());
+        zip.start_file("SKILL.md", zip::write::FileOptions::default()).unwrap();
+        zip.finish().unwrap();
+
+        let roots = SweepRoots { skills_dirs: vec![skills.clone()], temp_dir: temp.clone() };
+        let references = vec![format!("/x/{}", referenced_staging)];
+        let mut audited = Vec::new();
+
+        // Too young for a 24h threshold
+        let now = SystemTime::now();
+        let report = sweep(&roots, 24 * HOUR, now, &references, &mut |a, _| audited.push(a.path.clone()));
+        assert!(report.removed.is_empty());
+
+        let report = sweep(&roots, 24 * HOUR, now + 48 * HOUR, &references, &mut |a, _| audited.push(a.path.clone()));
+        let mut kinds: Vec<&str> = report.removed.iter().map(|a| a.kind.as_str()).collect();
+        kinds.sort();
+        assert_eq!(kinds, vec!["git_checkout", "import_staging", "partial_export", "run_dir"]);
+        assert_eq!(report.reclaimed_bytes, 7 + 9);
+        assert_eq!(report.kept_referenced.len(), 1);
+        assert_eq!(audited.len(), 4);
+
+        assert!(!skills.join(&staging).exists());
+        assert!(!skills.join("pdf-tools_v1.2.0.zip").exists());
+        assert!(!temp.join(RUN_DIRS).join(&run_dir).exists());
+        for kept in ["pdf-tools", ".importing-notes", "backup.zip", "notes_v2.txt", "charts_v1.0.0.zip"] {
+            assert!(skills.join(kept).exists(), "{} must survive", kept);
+        }
+        assert!(skills.join(&referenced_staging).exists());
+        assert!(temp.join(RUN_DIRS).join("keep-me").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_references_match_whole_path_components() {
+        let run_dir = "0b5c6a38-3f3e-4a55-9a8e-2d7a4c1e9f10";
+        let refs = |r: &str| vec![r.to_string()];
+        assert!(is_referenced(run_dir, &refs(&format!("/tmp/vibecode-runs/{}/out.txt", run_dir))));
+        assert!(is_referenced(run_dir, &refs(&format!("C:\\Temp\\{}", run_dir))));
+        assert!(!is_referenced(run_dir, &refs(&format!("/tmp/{}.bak", run_dir))));
+        assert!(!is_referenced("pdf-tools_v1.2.0.zip", &refs("/skills/old-pdf-tools_v1.2.0.zip")));
+    }
+}
@@ -0,0 +1,190 @@
+/// Text Encoding - Which encoding a text file is in, and converting to it
+///
+/// `detect` looks at a file's bytes. A byte order mark names UTF-8 or UTF-16
+/// outright; otherwise a null byte means binary and valid UTF-8 means UTF-8.
+/// Anything else is decoded as windows-1252, Shift_JIS and EUC-JP, and each
+/// decoding that works is scored on how much it reads like text: kana, and
+/// kanji next to other Japanese characters, for the Japanese encodings;
+/// accented letters between plain ones for windows-1252 (a Japanese file read
+/// as windows-1252 comes out as runs of accented letters and symbols). The
+/// best score wins, windows-1252 on a tie; C1 and other control characters
+/// count against a decoding, and with no score of at least zero the bytes are
+/// binary. `decode` and `encode` convert with a known encoding, for reading
+/// a file and writing it back the way it was.
+
+use encoding_rs::{DecoderResult, Encoding, EUC_JP, SHIFT_JIS, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+
+/// Encodings tried when bytes are not UTF-8, ties going to the first
+const CANDIDATES: [&Encoding; 3] = [WINDOWS_1252, SHIFT_JIS, EUC_JP];
+
+/// Score of a control character, in any candidate
+const CONTROL_SCORE: f64 = -4.0;
+
+/// Encoding of some text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detected {
+    pub encoding: &'static Encoding,
+    /// The text starts with a byte order mark for `encoding`
+    pub bom: bool,
+}
+
+/// Encoding for a label such as "shift_jis", "latin1" or "utf-16le"
+pub fn for_label(label: &str) -> Result<&'static Encoding, String> {
+    Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| format!("Unknown encoding: {}", label))
+}
+
+/// `bytes` taken to be in `encoding`, whatever they look like
+pub fn with_encoding(encoding: &'static Encoding, bytes: &[u8]) -> Detected {
+    let bom = Encoding::for_bom(bytes).is_some_and(|(found, _)| found == encoding);
+    Detected { encoding, bom }
+}
+
+/// `bytes` decoded with `encoding`, or None where they are malformed in it;
+/// `complete` is false for the start of a file, which may end inside a
+/// character
+fn decode_strict(encoding: &'static Encoding, bytes: &[u8], complete: bool) -> Option<String> {
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut text = String::with_capacity(decoder.max_utf8_buffer_length_without_replacement(bytes.len())?);
+    let (result, _) = decoder.decode_to_string_without_replacement(bytes, &mut text, complete);
+    matches!(result, DecoderResult::InputEmpty).then_some(text)
+}
+
+fn is_control(c: char) -> bool {
+    c.is_control() && !matches!(c, '\t' | '\n' | '\r' | '\x0c' | '\x1b')
+}
+
+fn is_japanese(c: char) -> bool {
+    matches!(c, '\u{3000}'..='\u{30ff}' | '\u{4e00}'..='\u{9fff}' | '\u{ff01}'..='\u{ff60}')
+}
+
+/// How much `text`, decoded from bytes in `encoding`, reads like text in it
+fn score(encoding: &'static Encoding, text: &str) -> f64 {
+    let chars: Vec<char> = text.chars().collect();
+    let mut score = 0.0;
+    for (i, &c) in chars.iter().enumerate() {
+        if is_control(c) {
+            score += CONTROL_SCORE;
+            continue;
+        }
+        if c.is_ascii() {
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1).copied();
+        score += if encoding == WINDOWS_1252 {
+            // One accented letter or typographic mark at a time
+            let alone = prev.is_none_or(|p| p.is_ascii());
+            let plausible = c.is_alphabetic() || matches!(c, '\u{a0}'..='\u{bf}' | '\u{2010}'..='\u{2027}' | '€' | '™');
+            if alone && plausible { 1.0 } else { 0.0 }
+        } else if ('\u{3040}'..='\u{30ff}').contains(&c) {
+            // Kana: two bytes each
+            2.0
+        } else if is_japanese(c) && (prev.is_some_and(is_japanese) || next.is_some_and(is_japanese)) {
+            2.0
+        } else if ('\u{e000}'..='\u{f8ff}').contains(&c) {
+            // User-defined characters
+            CONTROL_SCORE
+        } else {
+            0.0
+        };
+    }
+    score
+}
+
+/// Encoding of `bytes` (see the module docs), or None when they are binary;
+/// `complete` is false when they are the start of a file
+pub fn detect(bytes: &[u8], complete: bool) -> Option<Detected> {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return Some(Detected { encoding, bom: true });
+    }
+    if bytes.contains(&0) {
+        return None;
+    }
+    let utf8 = match std::str::from_utf8(bytes) {
+        Ok(_) => true,
+        // Only the last character is incomplete: the sniff cut it
+        Err(e) => !complete && e.error_len().is_none(),
+    };
+    if utf8 {
+        return Some(Detected { encoding: UTF_8, bom: false });
+    }
+
+    let non_ascii = bytes.iter().filter(|b| !b.is_ascii()).count() as f64;
+    let mut best: Option<(&'static Encoding, f64)> = None;
+    for encoding in CANDIDATES {
+        let Some(text) = decode_strict(encoding, bytes, complete) else { continue };
+        let score = score(encoding, &text) / non_ascii;
+        if best.is_none_or(|(_, best)| score > best) {
+            best = Some((encoding, score));
+        }
+    }
+    best.filter(|(_, score)| *score >= 0.0).map(|(encoding, _)| Detected { encoding, bom: false })
+}
+
+/// `bytes` decoded with `encoding`, less a byte order mark for it, and
+/// whether malformed bytes were replaced with U+FFFD
+pub fn decode(encoding: &'static Encoding, bytes: &[u8]) -> (String, bool) {
+    let (text, lossy) = encoding.decode_with_bom_removal(bytes);
+    (text.into_owned(), lossy)
+}
+
+/// `text` in `encoding`, after a byte order mark with `bom` when it is UTF-8
+/// or UTF-16; an error when the encoding has no way to write a character
+pub fn encode(encoding: &'static Encoding, text: &str, bom: bool) -> Result<Vec<u8>, String> {
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        let units = bom.then_some(0xfeff).into_iter().chain(text.encode_utf16());
+        return Ok(units
+            .flat_map(|unit| if encoding == UTF_16LE { unit.to_le_bytes() } else { unit.to_be_bytes() })
+            .collect());
+    }
+
+    let (bytes, output, unmappable) = encoding.encode(text);
+    if unmappable {
+        let mut buf = [0; 4];
+        let c = text.chars().find(|c| encoding.encode(c.encode_utf8(&mut buf)).2).unwrap_or('\u{fffd}');
+        return Err(format!("{} cannot encode {:?}", encoding.name(), c));
+    }
+    let mut encoded = Vec::with_capacity(bytes.len() + 3);
+    if bom && output == UTF_8 {
+        encoded.extend_from_slice(b"\xef\xbb\xbf");
+    }
+    encoded.extend_from_slice(&bytes);
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_encodings_are_detected_and_round_trip() {
+        let japanese = "// 日本語のコメント\nfn main() {}\n";
+        let western = "// Café, naïve, Straße – “quoted”\n";
+        let cases = [
+            (japanese.as_bytes().to_vec(), UTF_8, false),
+            (encode(SHIFT_JIS, japanese, false).unwrap(), SHIFT_JIS, false),
+            (encode(EUC_JP, japanese, false).unwrap(), EUC_JP, false),
+            (encode(WINDOWS_1252, western, false).unwrap(), WINDOWS_1252, false),
+            (encode(UTF_16LE, japanese, true).unwrap(), UTF_16LE, true),
+            (encode(UTF_8, western, true).unwrap(), UTF_8, true),
+        ];
+        for (bytes, encoding, bom) in cases {
+            let detected = detect(&bytes, true);
+            assert_eq!(detected, Some(Detected { encoding, bom }), "{}", encoding.name());
+            let (text, lossy) = decode(encoding, &bytes);
+            assert!(!lossy);
+            assert_eq!(encode(encoding, &text, bom).unwrap(), bytes, "{}", encoding.name());
+        }
+
+        // Cut inside the last character, as a sniff may be
+        let sjis = encode(SHIFT_JIS, japanese, false).unwrap();
+        assert_eq!(detect(&sjis[..8], false).map(|d| d.encoding), Some(SHIFT_JIS));
+        assert_eq!(detect(b"caf\xe9", true).map(|d| d.encoding), Some(WINDOWS_1252));
+
+        assert_eq!(detect(b"SQLite format 3\x00", true), None);
+        assert_eq!(detect(b"\x01\x02\x03\x81\x8d\x05", true), None);
+        assert!(encode(WINDOWS_1252, "日本", false).unwrap_err().contains("'日'"));
+        assert_eq!(for_label("latin1").unwrap(), WINDOWS_1252);
+        assert!(for_label("klingon").is_err());
+    }
+}
@@ -0,0 +1,117 @@
+/// Version Info - Build and subsystem versions for bug reports
+///
+/// One block answering "which build was this": crate version, the git commit
+/// embedded by build.rs, Tauri and webview versions, the settings schema
+/// version, and content hashes of the agents registry and workflow prompt
+/// templates the generator loads at runtime. Served by `get_version_info` and
+/// `GET /api/version`, and placed at the top of diagnostics reports.
+
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Agents registry used by the workflow generator, relative to the repo root
+const AGENTS_REGISTRY: &str = "tools/workflow-generator/src/data/agent-registry.ts";
+/// Prompt templates used by the workflow generator, relative to the repo root
+const TEMPLATES_DIR: &str = "tools/workflow-generator/src/data/templates";
+
+/// Content hash of one prompt template
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateHash {
+    pub name: String,
+    pub sha256: String,
+}
+
+/// Result of `get_version_info`
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionInfo {
+    pub app_version: String,
+    /// Short commit hash, None for builds outside a git checkout
+    pub git_commit: Option<String>,
+    pub tauri_version: String,
+    pub webview_version: Option<String>,
+    pub settings_schema_version: u32,
+    /// None when the registry file is not present
+    pub agents_registry_hash: Option<String>,
+    pub prompt_templates: Vec<TemplateHash>,
+    /// Rust target triple the app was built for
+    pub target: String,
+    pub os: String,
+    pub arch: String,
+}
+
+/// Short sha256 of a file's content
+fn file_hash(path: &Path) -> Option<String> {
+    let content = std::fs::read(path).ok()?;
+    Some(Sha256::digest(&content).iter().take(8).map(|b| format!("{:02x}", b)).collect())
+}
+
+fn template_hashes(dir: &Path) -> Vec<TemplateHash> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_file()).collect())
+        .unwrap_or_default();
+    paths.sort();
+
+    paths
+        .iter()
+        .filter_map(|path| {
+            Some(TemplateHash {
+                name: path.file_name()?.to_string_lossy().to_string(),
+                sha256: file_hash(path)?,
+            })
+        })
+        .collect()
+}
+
+/// Collect version information (reads the registry and templates from disk)
+pub fn collect() -> VersionInfo {
+    let repo_root = crate::python_env::vibe_dir();
+
+    VersionInfo {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        git_commit: option_env!("VIBECODE_GIT_COMMIT")
+            .filter(|c| !c.is_empty())
+            .map(String::from),
+        tauri_version: tauri::VERSION.to_string(),
+        webview_version: tauri::webview_version().ok(),
+        settings_schema_version: crate::settings::SCHEMA_VERSION,
+        agents_registry_hash: file_hash(&repo_root.join(AGENTS_REGISTRY)),
+        prompt_templates: template_hashes(&repo_root.join(TEMPLATES_DIR)),
+        target: option_env!("VIBECODE_TARGET").unwrap_or_default().to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+    }
+}
+
+/// Get app and subsystem version information
+#[tauri::command]
+pub async fn get_version_info() -> Result<VersionInfo, String> {
+    crate::command_metrics::timed("get_version_info", async move {
+        Ok(collect())
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_hashes_track_content() {
+        let dir = std::env::temp_dir().join(format!("vibecode-version-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("workflow.hbs"), "{{name}}").unwrap();
+        std::fs::write(dir.join("agent.hbs"), "{{agent}}").unwrap();
+
+        let before = template_hashes(&dir);
+        assert_eq!(before.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["agent.hbs", "workflow.hbs"]);
+        assert_eq!(before[0].sha256.len(), 16);
+
+        std::fs::write(dir.join("workflow.hbs"), "{{name}} v2").unwrap();
+        let after = template_hashes(&dir);
+        assert_eq!(before[0].sha256, after[0].sha256);
+        assert_ne!(before[1].sha256, after[1].sha256);
+        assert!(file_hash(&dir.join("missing.hbs")).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
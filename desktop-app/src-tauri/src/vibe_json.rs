@@ -0,0 +1,198 @@
+/// Vibe JSON - Structured output of vibe.py's `--json` mode
+///
+/// Every vibe.py invocation passes `--json` after its command arguments:
+/// vibe.py then writes its usual human-readable output to stderr and a single
+/// JSON object to stdout, which is deserialized into the structs below. A
+/// vibe.py that predates `--json` ignores the trailing flag and prints text to
+/// stdout; `parse` then returns None and each caller falls back to the text it
+/// used to scrape.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::WorkflowInfo;
+
+pub const JSON_FLAG: &str = "--json";
+
+/// The report on the last non-empty stdout line (None for text output)
+pub fn parse<T: DeserializeOwned>(stdout: &str) -> Option<T> {
+    let line = stdout.lines().rev().find(|line| !line.trim().is_empty())?;
+    serde_json::from_str(line.trim()).ok()
+}
+
+/// Report of `task` and `workflow <name>`
+#[derive(Debug, Deserialize)]
+pub struct RunReport {
+    #[serde(default)]
+    pub agent_used: Option<String>,
+    #[serde(default)]
+    pub files_changed: Vec<String>,
+}
+
+/// Report of `workflow <name> --step <n>`
+#[derive(Debug, Deserialize)]
+pub struct StepReport {
+    pub success: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// `save_output` name and value of a step that saves its result
+    #[serde(default)]
+    pub outputs: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    pub files_changed: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkflowList {
+    workflows: Vec<WorkflowEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkflowEntry {
+    name: String,
+    #[serde(default)]
+    description: String,
+    steps_count: Option<usize>,
+    file_path: Option<String>,
+    /// The YAML failed to load
+    error: Option<String>,
+}
+
+/// Workflows of `workflow list`
+pub fn workflows(stdout: &str) -> Vec<WorkflowInfo> {
+    let Some(list) = parse::<WorkflowList>(stdout) else {
+        return workflows_from_text(stdout);
+    };
+    list.workflows
+        .into_iter()
+        .map(|entry| WorkflowInfo {
+            name: entry.name,
+            description: match entry.error {
+                Some(error) => format!("⚠️ {}", error),
+                None => entry.description,
+            },
+            steps_count: entry.steps_count,
+            file_path: entry.file_path,
+            scope: crate::workflow_scope::SCOPE_GLOBAL.to_string(),
+            yaml_error: None,
+        })
+        .collect()
+}
+
+/// Text fallback: "• name - description" lines
+fn workflows_from_text(stdout: &str) -> Vec<WorkflowInfo> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (name, description) = line.split_once(" - ")?;
+            let name = name.trim().trim_start_matches(['•', '*', '-']).trim();
+            if name.is_empty() {
+                return None;
+            }
+            Some(WorkflowInfo {
+                name: name.to_string(),
+                description: description.trim().to_string(),
+                steps_count: None,
+                file_path: None,
+                scope: crate::workflow_scope::SCOPE_GLOBAL.to_string(),
+                yaml_error: None,
+            })
+        })
+        .collect()
+}
+
+/// Session statistics of `stats`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionStats {
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Seconds since the orchestrator started
+    #[serde(default)]
+    pub session_duration: Option<f64>,
+    #[serde(default)]
+    pub tasks_executed: Option<u64>,
+    #[serde(default)]
+    pub api_cost: Option<f64>,
+    #[serde(default)]
+    pub api_requests: Option<u64>,
+    /// Human-readable rendering for the dashboard terminal
+    #[serde(default)]
+    pub text: String,
+}
+
+impl SessionStats {
+    fn render(&self) -> String {
+        let mut lines = vec![format!("Project: {}", self.project.as_deref().unwrap_or("Unknown"))];
+        if let Some(duration) = self.session_duration {
+            lines.push(format!("Session Duration: {:.0}s", duration));
+        }
+        if let Some(tasks) = self.tasks_executed {
+            lines.push(format!("Tasks Executed: {}", tasks));
+        }
+        if let Some(cost) = self.api_cost {
+            lines.push(format!("API Cost: ${:.4}", cost));
+        }
+        if let Some(requests) = self.api_requests {
+            lines.push(format!("API Requests: {}", requests));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Statistics of `stats`; older vibe.py output is kept as text only
+pub fn stats(stdout: &str) -> SessionStats {
+    match parse::<SessionStats>(stdout) {
+        Some(mut stats) => {
+            stats.text = stats.render();
+            stats
+        }
+        None => SessionStats { text: stdout.to_string(), ..Default::default() },
+    }
+}
+
+/// Project context of `context`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectContext {
+    /// Project the context was read from (None from older vibe.py)
+    #[serde(default)]
+    pub project_path: Option<String>,
+    pub content: String,
+}
+
+/// Context of `context`; older vibe.py output becomes the content as-is
+pub fn context(stdout: &str) -> ProjectContext {
+    parse(stdout).unwrap_or_else(|| ProjectContext { project_path: None, content: stdout.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_reports_with_text_fallback() {
+        let report = serde_json::json!({ "workflows": [
+            { "name": "feature", "title": "Feature", "description": "Build it", "steps_count": 4, "file_path": "/w/feature.yaml", "error": null },
+            { "name": "broken", "description": "", "steps_count": null, "file_path": "/w/broken.yaml", "error": "bad yaml" },
+        ]});
+        let listed = workflows(&report.to_string());
+        assert_eq!(listed.len(), 2);
+        assert_eq!((listed[0].name.as_str(), listed[0].steps_count), ("feature", Some(4)));
+        assert_eq!(listed[1].description, "⚠️ bad yaml");
+
+        // Older vibe.py: UTF-8 bullets must not be cut mid-character
+        let listed = workflows("Available:\n• feature - Build a feature\n  • review - Review code\n");
+        let names: Vec<&str> = listed.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, vec!["feature", "review"]);
+        assert!(listed[0].file_path.is_none());
+
+        let session = stats("Starting...\n{\"project\": \"demo\", \"session_duration\": 12.4, \"tasks_executed\": 3}\n");
+        assert_eq!(session.tasks_executed, Some(3));
+        assert!(session.text.contains("Tasks Executed: 3"));
+        assert_eq!(stats("Session Statistics\n  Tasks  3\n").text, "Session Statistics\n  Tasks  3\n");
+
+        let report: RunReport = parse("{\"success\": true, \"agent_used\": \"cli\", \"files_changed\": [\"src/a.rs\"]}").unwrap();
+        assert_eq!(report.files_changed, vec!["src/a.rs"]);
+        assert!(parse::<RunReport>("✅ Result (1.2s)").is_none());
+        assert_eq!(context("plain text").content, "plain text");
+    }
+}
@@ -0,0 +1,375 @@
+/// Workflow Archive - Sharing workflows as ZIP packages
+///
+/// `export_workflow` writes `<stem>.zip` next to the workflow, holding the
+/// YAML as `workflow.yaml` and a generated `manifest.json`: name,
+/// description, declared variables, the agents its steps need and the step
+/// count. `import_workflow` accepts exactly those two entries at the top of
+/// the archive (`..`, nested paths and any other file are refused), checks
+/// that the manifest describes the YAML and that every agent is known, and
+/// installs the YAML as `<stem>.yaml` in `get_workflows_path()`. A workflow
+/// with the same file name is only replaced with `overwrite`, and is moved to
+/// `.trash` first (see `workflow_files`).
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::workflow_files;
+use crate::workflow_validation::{self, WorkflowValidation, KNOWN_AGENTS};
+use crate::workflow_variables::{self, WorkflowVariable};
+use crate::workflow_yaml::{self, Node};
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const WORKFLOW_FILE: &str = "workflow.yaml";
+
+/// Package layout written to (and required in) the manifest
+const PACKAGE_FORMAT: u32 = 1;
+
+/// Largest entry accepted on import; workflows are small text files
+const MAX_ENTRY_BYTES: u64 = 1024 * 1024;
+
+/// `manifest.json` of a workflow package
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkflowManifest {
+    pub format: u32,
+    /// The workflow's `name:` field
+    pub name: String,
+    /// File name without extension, as installed
+    pub file_stem: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub variables: Vec<WorkflowVariable>,
+    /// Agents the steps run on, in first-use order
+    #[serde(default)]
+    pub agents: Vec<String>,
+    pub steps: usize,
+}
+
+/// Result of `export_workflow`
+#[derive(Debug, Serialize)]
+pub struct WorkflowExport {
+    pub export_path: String,
+    pub file_size: u64,
+    pub manifest: WorkflowManifest,
+}
+
+/// Result of `import_workflow`
+#[derive(Debug, Serialize)]
+pub struct WorkflowImport {
+    /// File stem the workflow was installed under
+    pub name: String,
+    pub path: String,
+    /// Where the replaced workflow went in `.trash`, with `overwrite`
+    pub replaced: Option<String>,
+    pub manifest: WorkflowManifest,
+    /// Checks of the installed file, as `validate_workflow` reports them
+    pub validation: WorkflowValidation,
+    /// Advisory naming findings (near-duplicates, cross-scope collisions)
+    pub warnings: Vec<crate::name_lint::NameIssue>,
+}
+
+/// Manifest describing `yaml`, installed as `<file_stem>.yaml`
+pub fn manifest_for(file_stem: &str, yaml: &str) -> Result<WorkflowManifest, String> {
+    let root = workflow_yaml::parse(yaml).map_err(|e| e.to_string())?;
+    let text = |key: &str| root.get(key).and_then(Node::as_str).map(str::trim).unwrap_or_default().to_string();
+    let name = text("name");
+    if name.is_empty() {
+        return Err("Workflow has no `name`".to_string());
+    }
+
+    let steps = crate::workflow_run::plan(yaml)?;
+    let mut agents: Vec<String> = Vec::new();
+    for step in &steps {
+        if !agents.contains(&step.agent) {
+            agents.push(step.agent.clone());
+        }
+    }
+    Ok(WorkflowManifest {
+        format: PACKAGE_FORMAT,
+        name,
+        file_stem: file_stem.to_string(),
+        description: text("description"),
+        variables: workflow_variables::declared(yaml)?,
+        agents,
+        steps: steps.len(),
+    })
+}
+
+/// Write the package for `yaml` to `archive_path`
+fn write_package(archive_path: &Path, manifest: &WorkflowManifest, yaml: &str) -> Result<(), String> {
+    let manifest_json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize workflow manifest: {}", e))?;
+    let file = File::create(archive_path).map_err(|e| format!("Failed to create export file: {}", e))?;
+    let mut zip = zip::ZipWriter::new(BufWriter::new(file));
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for (entry, content) in [(MANIFEST_FILE, manifest_json.as_str()), (WORKFLOW_FILE, yaml)] {
+        zip.start_file(entry, options)
+            .map_err(|e| format!("Failed to add '{}': {}", entry, e))?;
+        zip.write_all(content.as_bytes())
+            .map_err(|e| format!("Failed to write '{}': {}", entry, e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize ZIP: {}", e))?
+        .flush()
+        .map_err(|e| format!("Failed to finalize ZIP: {}", e))
+}
+
+/// Manifest and YAML of the package at `archive_path`, refusing any other entry
+fn read_package(archive_path: &Path) -> Result<(WorkflowManifest, String), String> {
+    let file = File::open(archive_path).map_err(|e| format!("Failed to open workflow package: {}", e))?;
+    let mut archive = zip::ZipArchive::new(BufReader::new(file))
+        .map_err(|e| format!("Invalid workflow package: {}", e))?;
+
+    let mut manifest = None;
+    let mut yaml = None;
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(|e| format!("Invalid workflow package: {}", e))?;
+        let name = entry.name().to_string();
+        if entry.enclosed_name().is_none() {
+            return Err(format!("Unsafe path in workflow package: {}", name));
+        }
+        let slot = match name.as_str() {
+            MANIFEST_FILE => &mut manifest,
+            WORKFLOW_FILE => &mut yaml,
+            _ => {
+                return Err(format!(
+                    "Unexpected entry in workflow package: {} (only {} and {} are allowed)",
+                    name, MANIFEST_FILE, WORKFLOW_FILE
+                ))
+            }
+        };
+        if slot.is_some() {
+            return Err(format!("Workflow package contains {} twice", name));
+        }
+
+        let mut content = String::new();
+        entry
+            .take(MAX_ENTRY_BYTES + 1)
+            .read_to_string(&mut content)
+            .map_err(|e| format!("Failed to read '{}': {}", name, e))?;
+        if content.len() as u64 > MAX_ENTRY_BYTES {
+            return Err(format!("'{}' exceeds the limit of {} KB", name, MAX_ENTRY_BYTES / 1024));
+        }
+        *slot = Some(content);
+    }
+
+    let manifest = manifest.ok_or_else(|| format!("Invalid workflow package: missing {}", MANIFEST_FILE))?;
+    let yaml = yaml.ok_or_else(|| format!("Invalid workflow package: missing {}", WORKFLOW_FILE))?;
+    let manifest = serde_json::from_str(&manifest).map_err(|e| format!("Invalid workflow manifest: {}", e))?;
+    Ok((manifest, yaml))
+}
+
+/// Check that `manifest` is one this version installs and that it describes `yaml`
+fn check_manifest(manifest: &WorkflowManifest, yaml: &str) -> Result<(), String> {
+    if manifest.format != PACKAGE_FORMAT {
+        return Err(format!("Unsupported workflow package format {}", manifest.format));
+    }
+    if workflow_files::file_stem(&manifest.file_stem)? != manifest.file_stem {
+        return Err(format!("Invalid file name '{}' in workflow manifest", manifest.file_stem));
+    }
+
+    let actual = manifest_for(&manifest.file_stem, yaml)?;
+    let variable_names = |m: &WorkflowManifest| m.variables.iter().map(|v| v.name.clone()).collect::<Vec<_>>();
+    let mismatch = if actual.name != manifest.name {
+        Some("name")
+    } else if actual.agents != manifest.agents {
+        Some("agents")
+    } else if variable_names(&actual) != variable_names(manifest) {
+        Some("variables")
+    } else if actual.steps != manifest.steps {
+        Some("steps")
+    } else {
+        None
+    };
+    if let Some(field) = mismatch {
+        return Err(format!("Workflow manifest does not match {} (`{}` differs)", WORKFLOW_FILE, field));
+    }
+
+    let unknown: Vec<&str> = actual
+        .agents
+        .iter()
+        .map(String::as_str)
+        .filter(|agent| !KNOWN_AGENTS.contains(agent))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!(
+            "Workflow needs unknown agent(s): {}; expected one of {}",
+            unknown.join(", "),
+            KNOWN_AGENTS.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Export workflow `name` in `folder` to `<stem>.zip` beside it
+pub fn export_in(folder: &Path, name: &str) -> Result<WorkflowExport, String> {
+    let source = workflow_files::workflow_file(folder, name)?;
+    let stem = source.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let yaml = std::fs::read_to_string(&source).map_err(|e| format!("Failed to read workflow: {}", e))?;
+    let manifest = manifest_for(&stem, &yaml)?;
+
+    // A failed export leaves no ZIP behind
+    let export_path = folder.join(format!("{}.zip", stem));
+    if let Err(e) = write_package(&export_path, &manifest, &yaml) {
+        let _ = std::fs::remove_file(&export_path);
+        return Err(e);
+    }
+    Ok(WorkflowExport {
+        export_path: export_path.to_string_lossy().to_string(),
+        file_size: std::fs::metadata(&export_path).map(|m| m.len()).unwrap_or(0),
+        manifest,
+    })
+}
+
+/// Install the package at `archive_path` into `folder`
+pub fn import_in(folder: &Path, archive_path: &Path, overwrite: bool) -> Result<WorkflowImport, String> {
+    let (manifest, yaml) = read_package(archive_path)?;
+    check_manifest(&manifest, &yaml)?;
+    let stem = manifest.file_stem.clone();
+
+    let replaced = match workflow_files::existing(folder, &stem) {
+        Some(path) if !overwrite => {
+            return Err(format!("Workflow '{}' already exists ({}); import with overwrite to replace it", stem, path.display()))
+        }
+        Some(_) => Some(workflow_files::delete_in(folder, &stem)?),
+        None => None,
+    };
+
+    std::fs::create_dir_all(folder).map_err(|e| format!("Failed to create workflows folder: {}", e))?;
+    let target: PathBuf = folder.join(format!("{}.yaml", stem));
+    workflow_files::write_atomic(&target, &yaml)?;
+
+    Ok(WorkflowImport {
+        name: stem,
+        path: target.to_string_lossy().to_string(),
+        replaced: replaced.map(|path| path.to_string_lossy().to_string()),
+        manifest,
+        validation: workflow_validation::validate_file(&target)?,
+        warnings: Vec::new(),
+    })
+}
+
+/// Export a workflow as a ZIP package for sharing
+#[tauri::command]
+pub async fn export_workflow(name: String) -> Result<WorkflowExport, String> {
+    crate::command_metrics::timed("export_workflow", async move {
+        export_in(&crate::get_workflows_path(), &name)
+    }).await
+}
+
+/// Install a workflow package into the workflows folder (`overwrite` replaces a workflow of the same file name)
+#[tauri::command]
+pub async fn import_workflow(app: tauri::AppHandle, zip_path: String, overwrite: bool) -> Result<WorkflowImport, String> {
+    crate::command_metrics::timed("import_workflow", async move {
+        let folder = crate::get_workflows_path();
+        let result = import_in(&folder, Path::new(&zip_path), overwrite);
+        crate::audit::record(
+            "import_workflow",
+            serde_json::json!({ "zip_path": zip_path, "overwrite": overwrite, "folder": folder }),
+            crate::audit::ORIGIN_COMMAND,
+            &result,
+        );
+        let mut imported = result?;
+
+        imported.warnings = crate::name_lint::check_new_name(
+            crate::name_lint::KIND_WORKFLOW,
+            "app",
+            &imported.name,
+            Some(&imported.manifest.name),
+        );
+        crate::name_lint::emit_warnings(&app, crate::name_lint::KIND_WORKFLOW, &imported.name, &imported.warnings);
+        Ok(imported)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = "\
+# Shared review
+name: Review
+description: Review a change
+variables:
+  target: src
+steps:
+  - id: plan
+    agent: api
+    prompt: Plan ${target}
+  - id: apply
+    agent: cli
+    prompt: Apply
+";
+
+    fn zip_with(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, zip::write::FileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_export_import_round_trip_and_collisions() {
+        let root = std::env::temp_dir().join(format!("vibecode-workflow-archive-{}", uuid::Uuid::new_v4()));
+        let (source, dest) = (root.join("source"), root.join("dest"));
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::create_dir_all(&dest).unwrap();
+        std::fs::write(source.join("review.yaml"), YAML).unwrap();
+
+        let export = export_in(&source, "review").unwrap();
+        assert_eq!(export.manifest.agents, vec!["api", "cli"]);
+        assert_eq!(export.manifest.variables[0].name, "target");
+        assert_eq!(export.manifest.steps, 2);
+
+        let package = PathBuf::from(&export.export_path);
+        let imported = import_in(&dest, &package, false).unwrap();
+        assert_eq!(imported.name, "review");
+        assert!(imported.validation.is_valid);
+        assert_eq!(std::fs::read_to_string(dest.join("review.yaml")).unwrap(), YAML);
+
+        let err = import_in(&dest, &package, false).unwrap_err();
+        assert!(err.starts_with("Workflow 'review' already exists"), "{}", err);
+        let replaced = import_in(&dest, &package, true).unwrap().replaced.unwrap();
+        assert!(Path::new(&replaced).starts_with(dest.join(workflow_files::TRASH_DIR)));
+        assert!(dest.join("review.yaml").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_unsafe_or_mismatched_packages_are_refused() {
+        let root = std::env::temp_dir().join(format!("vibecode-workflow-package-{}", uuid::Uuid::new_v4()));
+        let dest = root.join("workflows");
+        std::fs::create_dir_all(&dest).unwrap();
+        let manifest = serde_json::to_string(&manifest_for("review", YAML).unwrap()).unwrap();
+        let package = root.join("package.zip");
+
+        zip_with(&package, &[(MANIFEST_FILE, &manifest), (WORKFLOW_FILE, YAML), ("../evil.yaml", "name: x\n")]);
+        assert!(import_in(&dest, &package, true).unwrap_err().starts_with("Unsafe path"));
+        assert!(!root.join("evil.yaml").exists());
+
+        zip_with(&package, &[(MANIFEST_FILE, &manifest), ("scripts/run.sh", "rm -rf /"), (WORKFLOW_FILE, YAML)]);
+        assert!(import_in(&dest, &package, true).unwrap_err().starts_with("Unexpected entry"));
+
+        zip_with(&package, &[(MANIFEST_FILE, &manifest), (WORKFLOW_FILE, &YAML.replace("agent: cli", "agent: robot"))]);
+        assert_eq!(
+            import_in(&dest, &package, true).unwrap_err(),
+            "Workflow manifest does not match workflow.yaml (`agents` differs)"
+        );
+
+        let traversal = manifest.replace("\"file_stem\":\"review\"", "\"file_stem\":\"../review\"");
+        zip_with(&package, &[(MANIFEST_FILE, &traversal), (WORKFLOW_FILE, YAML)]);
+        assert!(import_in(&dest, &package, true).unwrap_err().starts_with("Invalid workflow name"));
+
+        zip_with(&package, &[(WORKFLOW_FILE, YAML)]);
+        assert_eq!(import_in(&dest, &package, true).unwrap_err(), "Invalid workflow package: missing manifest.json");
+        assert_eq!(std::fs::read_dir(&dest).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,185 @@
+/// Workflow Condition - `when:` expressions of workflow steps
+///
+/// A step with `when:` only runs if its expression holds when the step's
+/// turn comes. An expression compares two values with `==`, `!=`,
+/// `contains` or `not contains`, or tests a single value, which holds unless
+/// it is empty, `false`, `no`, `off` or `0`. Values are quoted or bare
+/// literals and references written `{{name}}` (or `${name}`, as in prompts):
+/// a variable, `outputs.<name>` saved by an earlier step, or
+/// `steps.<id>.status` / `steps.<id>.success` of an earlier step. A reference
+/// with no value yet reads as empty. There is no templating beyond that:
+/// `when: "{{env}} == 'production'"`, `when: "{{steps.build.success}}"`.
+
+/// Comparison between the two sides of an expression
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Equals,
+    NotEquals,
+    Contains,
+    NotContains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Reference(String),
+    Literal(String),
+}
+
+/// A parsed `when:` expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    /// The expression as written
+    pub source: String,
+    left: Operand,
+    test: Option<(Op, Operand)>,
+}
+
+#[derive(Debug)]
+enum Token {
+    Operand(Operand),
+    Op(Op),
+}
+
+/// Text up to `end` in `rest`, and what follows it
+fn until<'a>(rest: &'a str, end: &str, what: &str) -> Result<(&'a str, &'a str), String> {
+    rest.find(end)
+        .map(|i| (&rest[..i], &rest[i + end.len()..]))
+        .ok_or_else(|| format!("unclosed {}", what))
+}
+
+/// What follows `word` at the start of `rest`, if it is a whole word there
+fn keyword<'a>(rest: &'a str, word: &str) -> Option<&'a str> {
+    rest.strip_prefix(word).filter(|after| after.is_empty() || after.starts_with(char::is_whitespace))
+}
+
+fn tokens(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source.trim_start();
+    while !rest.is_empty() {
+        let (token, after) = if let Some(inner) = rest.strip_prefix("{{") {
+            let (name, after) = until(inner, "}}", "`{{`")?;
+            (Token::Operand(Operand::Reference(name.trim().to_string())), after)
+        } else if let Some(inner) = rest.strip_prefix("${") {
+            let (name, after) = until(inner, "}", "`${`")?;
+            (Token::Operand(Operand::Reference(name.trim().to_string())), after)
+        } else if let Some(quote) = rest.chars().next().filter(|c| *c == '\'' || *c == '"') {
+            let (text, after) = until(&rest[1..], &quote.to_string(), "quote")?;
+            (Token::Operand(Operand::Literal(text.to_string())), after)
+        } else if let Some(after) = rest.strip_prefix("==") {
+            (Token::Op(Op::Equals), after)
+        } else if let Some(after) = rest.strip_prefix("!=") {
+            (Token::Op(Op::NotEquals), after)
+        } else if let Some(after) = keyword(rest, "not").and_then(|after| keyword(after.trim_start(), "contains")) {
+            (Token::Op(Op::NotContains), after)
+        } else if let Some(after) = keyword(rest, "contains") {
+            (Token::Op(Op::Contains), after)
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            (Token::Operand(Operand::Literal(rest[..end].to_string())), &rest[end..])
+        };
+        tokens.push(token);
+        rest = after.trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Parse a `when:` expression
+pub fn parse(source: &str) -> Result<Condition, String> {
+    let syntax = "expected `<value>`, or `<value>` followed by `==`, `!=`, `contains` or `not contains` and another value";
+    let mut tokens = tokens(source)?.into_iter();
+    let (left, test) = match (tokens.next(), tokens.next(), tokens.next(), tokens.next()) {
+        (Some(Token::Operand(left)), None, None, None) => (left, None),
+        (Some(Token::Operand(left)), Some(Token::Op(op)), Some(Token::Operand(right)), None) => (left, Some((op, right))),
+        _ => return Err(syntax.to_string()),
+    };
+    for operand in std::iter::once(&left).chain(test.as_ref().map(|(_, right)| right)) {
+        if matches!(operand, Operand::Reference(name) if name.is_empty()) {
+            return Err("empty reference `{{}}`".to_string());
+        }
+    }
+    Ok(Condition { source: source.trim().to_string(), left, test })
+}
+
+impl Condition {
+    /// Names the expression refers to
+    pub fn references(&self) -> Vec<&str> {
+        std::iter::once(&self.left)
+            .chain(self.test.as_ref().map(|(_, right)| right))
+            .filter_map(|operand| match operand {
+                Operand::Reference(name) => Some(name.as_str()),
+                Operand::Literal(_) => None,
+            })
+            .collect()
+    }
+
+    /// Whether the expression holds, reading references through `lookup`
+    pub fn holds(&self, lookup: &dyn Fn(&str) -> Option<String>) -> bool {
+        let value = |operand: &Operand| match operand {
+            Operand::Reference(name) => lookup(name).unwrap_or_default(),
+            Operand::Literal(text) => text.clone(),
+        };
+        let left = value(&self.left);
+        match &self.test {
+            None => !matches!(left.trim().to_lowercase().as_str(), "" | "false" | "no" | "off" | "0"),
+            Some((op, right)) => {
+                let right = value(right);
+                match op {
+                    Op::Equals => left == right,
+                    Op::NotEquals => left != right,
+                    Op::Contains => left.contains(&right),
+                    Op::NotContains => !left.contains(&right),
+                }
+            }
+        }
+    }
+
+    /// Why a step with this condition does not run, with the values it saw
+    pub fn skip_reason(&self, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+        let values: Vec<String> = self
+            .references()
+            .iter()
+            .map(|name| format!("{} = '{}'", name, lookup(name).unwrap_or_default()))
+            .collect();
+        if values.is_empty() {
+            format!("Skipped: `when: {}` is false", self.source)
+        } else {
+            format!("Skipped: `when: {}` is false ({})", self.source, values.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_evaluate() {
+        let lookup = |name: &str| match name {
+            "env" => Some("production".to_string()),
+            "outputs.review" => Some("2 BLOCKER issues".to_string()),
+            "steps.build.success" => Some("false".to_string()),
+            _ => None,
+        };
+        let holds = |source: &str| parse(source).unwrap().holds(&lookup);
+
+        assert!(holds("{{env}} == 'production'"));
+        assert!(holds("${env} != \"staging\""));
+        assert!(holds("{{ outputs.review }} contains BLOCKER"));
+        assert!(!holds("{{outputs.review}} not contains 'BLOCKER'"));
+        assert!(!holds("{{steps.build.success}}"));
+        assert!(!holds("{{missing}}"));
+        assert!(holds("{{missing}} == ''"));
+
+        let condition = parse("{{env}} == 'staging'").unwrap();
+        assert_eq!(condition.references(), vec!["env"]);
+        assert_eq!(
+            condition.skip_reason(&lookup),
+            "Skipped: `when: {{env}} == 'staging'` is false (env = 'production')"
+        );
+
+        assert!(parse("{{env}} ==").is_err());
+        assert!(parse("{{env}} = 'x'").is_err());
+        assert_eq!(parse("'production").unwrap_err(), "unclosed quote");
+        assert_eq!(parse("{{env == 'x'").unwrap_err(), "unclosed `{{`");
+    }
+}
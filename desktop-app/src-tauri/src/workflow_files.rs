@@ -0,0 +1,409 @@
+/// Workflow Files - Editing, deleting, renaming and duplicating workflow YAML files
+///
+/// These commands act only on `<name>.yaml` (or `.yml`) directly inside
+/// `get_workflows_path()`: names with path separators or `..` are refused,
+/// and so is a file that resolves outside the folder through a symlink.
+/// `delete_workflow` moves the file to the folder's `.trash` subfolder
+/// (which `workflow list` does not scan) so it can be restored by hand.
+/// `rename_workflow` refuses a new name that is already taken, and rewrites
+/// the workflow's top-level `name:` line in place, leaving the rest of the
+/// file (comments included) as it was. `duplicate_workflow` copies a file
+/// the same way, with " (copy)" added to its description, and picks the
+/// next free file name (`deploy-2.yaml`, ...) when the requested one is taken.
+/// `get_workflow_content` and `update_workflow_content` read and replace a
+/// file's raw YAML for the in-app editor; content that `workflow_yaml` cannot
+/// parse is refused with the line of the error, and the file is replaced
+/// through a temporary file so an interrupted save leaves the old one intact.
+
+use std::path::{Path, PathBuf};
+
+use crate::workflow_validation::WorkflowValidation;
+use crate::workflow_yaml;
+use crate::WorkflowInfo;
+
+/// Subfolder of the workflows folder holding deleted workflows
+pub const TRASH_DIR: &str = ".trash";
+
+const EXTENSIONS: [&str; 2] = ["yaml", "yml"];
+
+/// File stem for a workflow name, as `create_workflow` derives it
+pub fn file_stem(name: &str) -> Result<String, String> {
+    let stem = name.trim().to_lowercase().replace(' ', "-");
+    let valid = !stem.is_empty()
+        && !stem.starts_with('.')
+        && stem.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!("Invalid workflow name '{}': use letters, digits, spaces, '-', '_' or '.'", name));
+    }
+    Ok(stem)
+}
+
+/// The file of workflow `name` inside `folder`, refusing anything that leads outside it
+pub fn workflow_file(folder: &Path, name: &str) -> Result<PathBuf, String> {
+    let stem = file_stem(name)?;
+    let path = EXTENSIONS
+        .iter()
+        .map(|ext| folder.join(format!("{}.{}", stem, ext)))
+        .find(|p| p.is_file())
+        .ok_or_else(|| format!("Workflow '{}' not found in {}", name, folder.display()))?;
+
+    let canonical_folder = folder.canonicalize().map_err(|e| format!("Failed to resolve workflows folder: {}", e))?;
+    let canonical = path.canonicalize().map_err(|e| format!("Failed to resolve workflow: {}", e))?;
+    if canonical.parent() != Some(canonical_folder.as_path()) {
+        return Err(format!("Workflow '{}' resolves outside {}", name, folder.display()));
+    }
+    Ok(path)
+}
+
+/// A workflow file named `stem` already in `folder`, with either extension
+pub fn existing(folder: &Path, stem: &str) -> Option<PathBuf> {
+    EXTENSIONS.iter().map(|ext| folder.join(format!("{}.{}", stem, ext))).find(|p| p.exists())
+}
+
+/// `value` as a YAML scalar, quoted when plain style would change its meaning
+pub fn yaml_scalar(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value.trim() == value
+        && !value.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`".contains(c))
+        && !value.contains(": ")
+        && !value.contains('\n')
+        && !value.contains(" #")
+        && !value.ends_with(':')
+        && !matches!(value.to_lowercase().as_str(), "true" | "false" | "yes" | "no" | "on" | "off" | "null" | "~")
+        && value.parse::<f64>().is_err();
+    if plain {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+    }
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// `yaml` with top-level `key` set to `value`, touching only that key's lines
+///
+/// This is a textual edit, so comments and formatting survive. A missing key
+/// is added before the first line that is neither blank nor a comment, so
+/// header comments stay on top.
+pub fn set_top_level(yaml: &str, key: &str, value: &str) -> String {
+    let lines: Vec<&str> = yaml.lines().collect();
+    let content = |l: &str| !l.trim().is_empty() && !l.trim_start().starts_with('#');
+    let root_indent = lines.iter().find(|l| content(l)).map_or(0, |l| indent_of(l));
+    let line_of_key = lines.iter().position(|l| {
+        indent_of(l) == root_indent && l.trim_start().strip_prefix(key).is_some_and(|rest| rest.trim_start().starts_with(':'))
+    });
+
+    let (start, end, indent) = match line_of_key {
+        Some(start) => {
+            let indent = indent_of(lines[start]);
+            // A block or multi-line value continues on more indented lines
+            let mut end = start + 1;
+            while end < lines.len() && (lines[end].trim().is_empty() || indent_of(lines[end]) > indent) {
+                end += 1;
+            }
+            while end > start + 1 && lines[end - 1].trim().is_empty() {
+                end -= 1;
+            }
+            (start, end, indent)
+        }
+        None => {
+            let first = lines.iter().position(|l| content(l)).unwrap_or(lines.len());
+            (first, first, root_indent)
+        }
+    };
+
+    let mut out: Vec<String> = lines[..start].iter().map(|l| l.to_string()).collect();
+    out.push(format!("{}{}: {}", " ".repeat(indent), key, yaml_scalar(value)));
+    out.extend(lines[end..].iter().map(|l| l.to_string()));
+    let mut text = out.join("\n");
+    if yaml.ends_with('\n') || yaml.is_empty() {
+        text.push('\n');
+    }
+    text
+}
+
+/// Write `content` to `path` through a temporary file
+pub fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, content).map_err(|e| format!("Failed to write workflow: {}", e))?;
+    std::fs::rename(&tmp_path, path).map_err(|e| format!("Failed to write workflow: {}", e))
+}
+
+/// Raw YAML of workflow `name` in `folder`
+pub fn content_in(folder: &Path, name: &str) -> Result<String, String> {
+    let path = workflow_file(folder, name)?;
+    std::fs::read_to_string(&path).map_err(|e| format!("Failed to read workflow: {}", e))
+}
+
+/// Replace the YAML of workflow `name` in `folder`, unless it does not parse
+pub fn update_in(folder: &Path, name: &str, content: &str) -> Result<PathBuf, String> {
+    let path = workflow_file(folder, name)?;
+    workflow_yaml::parse(content).map_err(|e| e.to_string())?;
+    write_atomic(&path, content)?;
+    Ok(path)
+}
+
+/// Move workflow `name` from `folder` into its trash; returns where it went
+pub fn delete_in(folder: &Path, name: &str) -> Result<PathBuf, String> {
+    let path = workflow_file(folder, name)?;
+    let trash = folder.join(TRASH_DIR);
+    std::fs::create_dir_all(&trash).map_err(|e| format!("Failed to create {}: {}", trash.display(), e))?;
+
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let mut target = trash.join(&file_name);
+    if target.exists() {
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let ext = path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        target = (1..)
+            .map(|n| match n {
+                1 => trash.join(format!("{}.{}.{}", stem, stamp, ext)),
+                n => trash.join(format!("{}.{}-{}.{}", stem, stamp, n, ext)),
+            })
+            .find(|p| !p.exists())
+            .unwrap_or(target);
+    }
+    std::fs::rename(&path, &target).map_err(|e| format!("Failed to move workflow to trash: {}", e))?;
+    Ok(target)
+}
+
+/// Rename workflow `old_name` in `folder` to `new_name`; returns the new file
+pub fn rename_in(folder: &Path, old_name: &str, new_name: &str) -> Result<PathBuf, String> {
+    let source = workflow_file(folder, old_name)?;
+    let stem = file_stem(new_name)?;
+    let ext = source.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_else(|| "yaml".to_string());
+    let target = folder.join(format!("{}.{}", stem, ext));
+
+    // Only the file itself may already carry the new stem (a change of case or of `name:`)
+    if let Some(taken) = existing(folder, &stem) {
+        let same_file = taken.canonicalize().ok() == source.canonicalize().ok();
+        if !same_file {
+            return Err(format!("Workflow '{}' already exists ({})", new_name, taken.display()));
+        }
+    }
+
+    let yaml = std::fs::read_to_string(&source).map_err(|e| format!("Failed to read workflow: {}", e))?;
+    let renamed = set_top_level(&yaml, "name", new_name.trim());
+    if source != target {
+        std::fs::rename(&source, &target).map_err(|e| format!("Failed to rename workflow: {}", e))?;
+    }
+    write_atomic(&target, &renamed)?;
+    Ok(target)
+}
+
+/// First of `stem`, `stem-2`, `stem-3`, ... that no workflow in `folder` uses
+fn free_stem(folder: &Path, stem: &str) -> String {
+    (1..)
+        .map(|n| if n == 1 { stem.to_string() } else { format!("{}-{}", stem, n) })
+        .find(|candidate| existing(folder, candidate).is_none())
+        .unwrap_or_else(|| stem.to_string())
+}
+
+/// Copy workflow `source_name` in `folder` as `new_name`; returns the copy's entry
+pub fn duplicate_in(folder: &Path, source_name: &str, new_name: &str) -> Result<WorkflowInfo, String> {
+    let source = workflow_file(folder, source_name)?;
+    let stem = free_stem(folder, &file_stem(new_name)?);
+    let ext = source.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_else(|| "yaml".to_string());
+    let target = folder.join(format!("{}.{}", stem, ext));
+
+    let mut yaml = std::fs::read_to_string(&source).map_err(|e| format!("Failed to read workflow: {}", e))?;
+    let root = workflow_yaml::parse(&yaml).ok();
+    let description = root
+        .as_ref()
+        .and_then(|root| root.get("description")?.as_str().map(|d| format!("{} (copy)", d.trim_end())));
+    let steps_count = root.as_ref().and_then(|root| root.get("steps")?.as_seq().map(|steps| steps.len()));
+
+    yaml = set_top_level(&yaml, "name", new_name.trim());
+    if let Some(description) = &description {
+        yaml = set_top_level(&yaml, "description", description);
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+    std::io::Write::write_all(&mut file, yaml.as_bytes()).map_err(|e| format!("Failed to write workflow: {}", e))?;
+
+    Ok(WorkflowInfo {
+        name: stem,
+        description: description.unwrap_or_default(),
+        steps_count,
+        file_path: Some(target.to_string_lossy().to_string()),
+        scope: crate::workflow_scope::SCOPE_GLOBAL.to_string(),
+        yaml_error: None,
+    })
+}
+
+/// Raw YAML of a workflow, for the editor
+#[tauri::command]
+pub async fn get_workflow_content(name: String) -> Result<String, String> {
+    crate::command_metrics::timed("get_workflow_content", async move {
+        content_in(&crate::get_workflows_path(), &name)
+    }).await
+}
+
+/// Save a workflow's YAML from the editor
+///
+/// Returns the saved file's validation, so the editor can show what would
+/// still stop `run_workflow`.
+#[tauri::command]
+pub async fn update_workflow_content(name: String, content: String) -> Result<WorkflowValidation, String> {
+    crate::command_metrics::timed("update_workflow_content", async move {
+        let folder = crate::get_workflows_path();
+        let result = update_in(&folder, &name, &content);
+        crate::audit::record(
+            "update_workflow_content",
+            serde_json::json!({ "name": name, "folder": folder, "bytes": content.len() }),
+            crate::audit::ORIGIN_COMMAND,
+            &result,
+        );
+        crate::workflow_validation::validate_file(&result?)
+    }).await
+}
+
+/// Move a workflow to the `.trash` subfolder of the workflows folder
+#[tauri::command]
+pub async fn delete_workflow(name: String) -> Result<String, String> {
+    crate::command_metrics::timed("delete_workflow", async move {
+        let folder = crate::get_workflows_path();
+        let result = delete_in(&folder, &name).map(|path| path.to_string_lossy().to_string());
+        crate::audit::record(
+            "delete_workflow",
+            serde_json::json!({ "name": name, "folder": folder }),
+            crate::audit::ORIGIN_COMMAND,
+            &result,
+        );
+        result
+    }).await
+}
+
+/// Rename a workflow's file and its `name:` field
+#[tauri::command]
+pub async fn rename_workflow(app: tauri::AppHandle, old_name: String, new_name: String) -> Result<String, String> {
+    crate::command_metrics::timed("rename_workflow", async move {
+        let folder = crate::get_workflows_path();
+        let result = rename_in(&folder, &old_name, &new_name).map(|path| path.to_string_lossy().to_string());
+        crate::audit::record(
+            "rename_workflow",
+            serde_json::json!({ "old_name": old_name, "new_name": new_name, "folder": folder }),
+            crate::audit::ORIGIN_COMMAND,
+            &result,
+        );
+        let path = result?;
+
+        let stem = file_stem(&new_name)?;
+        let warnings = crate::name_lint::check_new_name(crate::name_lint::KIND_WORKFLOW, "app", &stem, Some(&stem));
+        crate::name_lint::emit_warnings(&app, crate::name_lint::KIND_WORKFLOW, &stem, &warnings);
+        Ok(path)
+    }).await
+}
+
+/// Copy a workflow under a new name, as a starting point for a variant
+#[tauri::command]
+pub async fn duplicate_workflow(app: tauri::AppHandle, source_name: String, new_name: String) -> Result<WorkflowInfo, String> {
+    crate::command_metrics::timed("duplicate_workflow", async move {
+        let info = duplicate_in(&crate::get_workflows_path(), &source_name, &new_name)?;
+        let warnings = crate::name_lint::check_new_name(crate::name_lint::KIND_WORKFLOW, "app", &info.name, Some(&info.name));
+        crate::name_lint::emit_warnings(&app, crate::name_lint::KIND_WORKFLOW, &info.name, &warnings);
+        Ok(info)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_and_rename_stay_in_the_folder() {
+        let root = std::env::temp_dir().join(format!("vibecode-workflow-files-{}", uuid::Uuid::new_v4()));
+        let folder = root.join("workflows");
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(root.join("outside.yaml"), "name: Outside\n").unwrap();
+        let yaml = "# Deploy workflow\n\nname: Deploy\ndescription: Ship it # keep\nsteps:\n  - name: Build\n";
+        std::fs::write(folder.join("deploy.yaml"), yaml).unwrap();
+        std::fs::write(folder.join("review.yaml"), "name: Review\nsteps: []\n").unwrap();
+
+        assert!(workflow_file(&folder, "../outside").is_err());
+        assert!(delete_in(&folder, "../outside").is_err());
+        assert!(root.join("outside.yaml").exists());
+
+        let err = rename_in(&folder, "deploy", "Review").unwrap_err();
+        assert!(err.starts_with("Workflow 'Review' already exists"), "{}", err);
+
+        let renamed = rename_in(&folder, "deploy", "Ship Prod").unwrap();
+        assert_eq!(renamed, folder.join("ship-prod.yaml"));
+        assert!(!folder.join("deploy.yaml").exists());
+        assert_eq!(
+            std::fs::read_to_string(&renamed).unwrap(),
+            "# Deploy workflow\n\nname: Ship Prod\ndescription: Ship it # keep\nsteps:\n  - name: Build\n"
+        );
+
+        let first = delete_in(&folder, "review").unwrap();
+        assert_eq!(first, folder.join(TRASH_DIR).join("review.yaml"));
+        std::fs::write(folder.join("review.yaml"), "name: Review\n").unwrap();
+        let second = delete_in(&folder, "review").unwrap();
+        assert_ne!(second, first);
+        assert!(first.exists() && second.exists());
+        assert!(workflow_file(&folder, "review").is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_duplicate_keeps_comments_and_picks_a_free_name() {
+        let folder = std::env::temp_dir().join(format!("vibecode-workflow-copy-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&folder).unwrap();
+        let yaml = "# Deploy\nname: Deploy\ndescription: Ship to prod  # reviewed\nsteps:\n  - name: Build # first\n  - name: Push\n";
+        std::fs::write(folder.join("deploy.yaml"), yaml).unwrap();
+
+        let copy = duplicate_in(&folder, "deploy", "deploy").unwrap();
+        assert_eq!(copy.name, "deploy-2");
+        assert_eq!(copy.description, "Ship to prod (copy)");
+        assert_eq!(copy.steps_count, Some(2));
+        assert_eq!(
+            std::fs::read_to_string(folder.join("deploy-2.yaml")).unwrap(),
+            "# Deploy\nname: deploy\ndescription: Ship to prod (copy)\nsteps:\n  - name: Build # first\n  - name: Push\n"
+        );
+        assert_eq!(duplicate_in(&folder, "deploy", "deploy").unwrap().name, "deploy-3");
+        assert!(duplicate_in(&folder, "missing", "x").is_err());
+
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn test_update_refuses_broken_yaml() {
+        let root = std::env::temp_dir().join(format!("vibecode-workflow-edit-{}", uuid::Uuid::new_v4()));
+        let folder = root.join("workflows");
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(root.join("outside.yaml"), "name: Outside\n").unwrap();
+        std::fs::write(folder.join("deploy.yml"), "name: Deploy\nsteps: []\n").unwrap();
+
+        assert_eq!(content_in(&folder, "deploy").unwrap(), "name: Deploy\nsteps: []\n");
+        assert!(content_in(&folder, "../outside").is_err());
+        assert!(update_in(&folder, "../outside", "name: x\n").is_err());
+        assert_eq!(std::fs::read_to_string(root.join("outside.yaml")).unwrap(), "name: Outside\n");
+
+        let err = update_in(&folder, "deploy", "name: Deploy\n\tsteps: []\n").unwrap_err();
+        assert!(err.starts_with("Invalid workflow YAML (line 2, column 1)"), "{}", err);
+        assert_eq!(content_in(&folder, "deploy").unwrap(), "name: Deploy\nsteps: []\n");
+
+        let saved = update_in(&folder, "deploy", "name: Deploy\nsteps:\n  - name: Build\n").unwrap();
+        assert_eq!(saved, folder.join("deploy.yml"));
+        assert_eq!(content_in(&folder, "deploy").unwrap(), "name: Deploy\nsteps:\n  - name: Build\n");
+        assert!(!folder.join("deploy.tmp").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_set_top_level_rewrites_only_that_key() {
+        assert_eq!(set_top_level("name: |\n  Old\n  title\n\nsteps: []\n", "name", "New: one"), "name: \"New: one\"\n\nsteps: []\n");
+        assert_eq!(set_top_level("# header\nsteps: []\n", "name", "x"), "# header\nname: x\nsteps: []\n");
+        assert_eq!(set_top_level("steps:\n  - name: a\n", "name", "b"), "name: b\nsteps:\n  - name: a\n");
+        assert_eq!(yaml_scalar("true"), "\"true\"");
+        assert_eq!(yaml_scalar("Bug Fix"), "Bug Fix");
+    }
+}
@@ -0,0 +1,300 @@
+/// Workflow History - Finished workflow runs, persisted across restarts
+///
+/// Every `run_workflow` run appends one JSON line to
+/// `<config>/workflow_history.jsonl`: workflow name, the variables it was
+/// given, the project it ran on, when it started, total time and each step's
+/// outcome and duration. Only the newest `workflowHistory.maxRunsPerWorkflow`
+/// runs of each workflow are kept; older ones are dropped when the next run
+/// of that workflow is recorded. Runs a schedule started say so in
+/// `triggered_by`, and single-step runs of `run_workflow_step` are flagged
+/// `partial`, and the safety snapshot taken before a run is kept for
+/// `restore_snapshot` (see `workflow_snapshot`). `get_workflow_runs` lists
+/// runs newest first (for "last run" badges) and `get_workflow_run_detail`
+/// returns one by id.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::workflow_run::{StepStatus, WorkflowRunResult};
+use crate::workflow_snapshot::Snapshot;
+
+const HISTORY_FILE: &str = "workflow_history.jsonl";
+
+/// Serializes appends and compaction within the process
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// `workflowHistory` block of settings.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WorkflowHistorySettings {
+    /// Runs kept per workflow
+    pub max_runs_per_workflow: usize,
+}
+
+impl Default for WorkflowHistorySettings {
+    fn default() -> Self {
+        Self { max_runs_per_workflow: 50 }
+    }
+}
+
+impl WorkflowHistorySettings {
+    /// Load the workflowHistory block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("workflowHistory").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// One step of a recorded run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepRecord {
+    pub index: usize,
+    pub name: String,
+    pub agent: String,
+    pub status: StepStatus,
+    pub success: bool,
+    pub duration: f64,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// One finished workflow run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRunRecord {
+    /// Task id of the run
+    pub run_id: String,
+    pub workflow: String,
+    /// Project the run worked on, None without a project
+    pub project: Option<String>,
+    /// Variable overrides the run was given
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    #[serde(default)]
+    pub dry_run: bool,
+    pub success: bool,
+    #[serde(default)]
+    pub cancelled: bool,
+    /// Stopped by `abort_workflow`
+    #[serde(default)]
+    pub aborted: bool,
+    /// What started the run (`schedule`), None when started by hand
+    #[serde(default)]
+    pub triggered_by: Option<String>,
+    /// Only one step ran (`run_workflow_step`)
+    #[serde(default)]
+    pub partial: bool,
+    /// First step that failed (1-based)
+    #[serde(default)]
+    pub failed_step: Option<usize>,
+    pub execution_time: f64,
+    pub started_at: String, // ISO 8601
+    pub steps: Vec<StepRecord>,
+    /// Taken before the first step, None when the run asked for none
+    #[serde(default)]
+    pub snapshot: Option<Snapshot>,
+}
+
+impl WorkflowRunRecord {
+    pub fn new(
+        run: &WorkflowRunResult,
+        project: Option<String>,
+        variables: &HashMap<String, String>,
+        dry_run: bool,
+        started_at: chrono::DateTime<chrono::Utc>,
+        triggered_by: Option<String>,
+    ) -> Self {
+        Self {
+            run_id: run.task_id.clone(),
+            workflow: run.workflow.clone(),
+            project,
+            variables: variables.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            dry_run,
+            success: run.success,
+            cancelled: run.cancelled,
+            aborted: run.aborted,
+            triggered_by,
+            partial: run.partial,
+            failed_step: run.steps.iter().find(|s| s.status == StepStatus::Failed).map(|s| s.index),
+            execution_time: run.execution_time,
+            started_at: started_at.to_rfc3339(),
+            steps: run
+                .steps
+                .iter()
+                .map(|s| StepRecord {
+                    index: s.index,
+                    name: s.name.clone(),
+                    agent: s.agent.clone(),
+                    status: s.status,
+                    success: s.success,
+                    duration: s.duration,
+                    error: s.error.clone(),
+                })
+                .collect(),
+            snapshot: run.snapshot.clone(),
+        }
+    }
+}
+
+fn history_path() -> PathBuf {
+    crate::get_app_config_dir().join(HISTORY_FILE)
+}
+
+fn read_all(path: &Path) -> Vec<WorkflowRunRecord> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Replace the file with `records` (temp file + rename)
+fn write_all(path: &Path, records: &[WorkflowRunRecord]) -> Result<(), String> {
+    let mut content = String::new();
+    for record in records {
+        let line = serde_json::to_string(record)
+            .map_err(|e| format!("Failed to serialize workflow history: {}", e))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+    let tmp_path = path.with_extension("jsonl.tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to save workflow history: {}", e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to save workflow history: {}", e))
+}
+
+fn append_to(path: &Path, record: &WorkflowRunRecord, max_per_workflow: usize) -> Result<(), String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let line = serde_json::to_string(record)
+        .map_err(|e| format!("Failed to serialize workflow history: {}", e))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open workflow history: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write workflow history: {}", e))?;
+    drop(file);
+
+    // Drop this workflow's oldest runs once it is over its cap
+    let mut records = read_all(path);
+    let count = records.iter().filter(|r| r.workflow == record.workflow).count();
+    if count > max_per_workflow {
+        let mut excess = count - max_per_workflow;
+        records.retain(|r| {
+            if excess > 0 && r.workflow == record.workflow {
+                excess -= 1;
+                return false;
+            }
+            true
+        });
+        write_all(path, &records)?;
+    }
+    Ok(())
+}
+
+/// Runs of `workflow` (of every workflow when None), newest first
+fn read_runs(path: &Path, workflow: Option<&str>, limit: usize) -> Vec<WorkflowRunRecord> {
+    read_all(path)
+        .into_iter()
+        .rev()
+        .filter(|r| workflow.is_none() || workflow == Some(r.workflow.as_str()))
+        .take(limit)
+        .collect()
+}
+
+/// Record a finished run (failures to record are reported, never fatal)
+pub fn record(record: &WorkflowRunRecord) {
+    let max = WorkflowHistorySettings::load().max_runs_per_workflow;
+    if let Err(e) = append_to(&history_path(), record, max) {
+        eprintln!("Workflow history: {}", e);
+    }
+}
+
+/// Recorded runs of workflow `name` (of all workflows when omitted), newest first
+#[tauri::command]
+pub async fn get_workflow_runs(name: Option<String>, limit: Option<usize>) -> Result<Vec<WorkflowRunRecord>, String> {
+    crate::command_metrics::timed("get_workflow_runs", async move {
+        Ok(read_runs(&history_path(), name.as_deref(), limit.unwrap_or(20)))
+    }).await
+}
+
+/// The newest recorded run with id `run_id`
+pub fn find(run_id: &str) -> Option<WorkflowRunRecord> {
+    read_all(&history_path()).into_iter().rev().find(|r| r.run_id == run_id)
+}
+
+/// One recorded run, by the run id (task id) `run_workflow` used
+#[tauri::command]
+pub async fn get_workflow_run_detail(run_id: String) -> Result<WorkflowRunRecord, String> {
+    crate::command_metrics::timed("get_workflow_run_detail", async move {
+        find(&run_id).ok_or_else(|| format!("No recorded workflow run '{}'", run_id))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_record(run_id: &str, workflow: &str, failed_step: Option<usize>) -> WorkflowRunRecord {
+        WorkflowRunRecord {
+            run_id: run_id.to_string(),
+            workflow: workflow.to_string(),
+            project: Some("/projects/alpha".to_string()),
+            variables: BTreeMap::from([("target".to_string(), "src".to_string())]),
+            dry_run: false,
+            success: failed_step.is_none(),
+            cancelled: false,
+            aborted: false,
+            partial: false,
+            triggered_by: Some("schedule".to_string()),
+            failed_step,
+            execution_time: 12.5,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            steps: vec![StepRecord {
+                index: 1,
+                name: "Plan".to_string(),
+                agent: "api".to_string(),
+                status: StepStatus::Succeeded,
+                success: true,
+                duration: 4.0,
+                error: None,
+            }],
+            snapshot: None,
+        }
+    }
+
+    #[test]
+    fn test_runs_are_capped_per_workflow_newest_first() {
+        let dir = std::env::temp_dir().join(format!("vibecode-workflow-history-{}", uuid::Uuid::new_v4()));
+        let path = dir.join(HISTORY_FILE);
+
+        for i in 0..4 {
+            append_to(&path, &run_record(&format!("deploy-{}", i), "deploy", None), 2).unwrap();
+        }
+        append_to(&path, &run_record("review-0", "review", Some(3)), 2).unwrap();
+
+        let ids = |runs: Vec<WorkflowRunRecord>| runs.into_iter().map(|r| r.run_id).collect::<Vec<_>>();
+        assert_eq!(ids(read_runs(&path, Some("deploy"), 10)), vec!["deploy-3", "deploy-2"]);
+        assert_eq!(ids(read_runs(&path, None, 2)), vec!["review-0", "deploy-3"]);
+
+        let review = &read_runs(&path, Some("review"), 1)[0];
+        assert_eq!(review.failed_step, Some(3));
+        assert_eq!(review.triggered_by.as_deref(), Some("schedule"));
+        assert_eq!(review.variables["target"], "src");
+        assert_eq!(review.steps[0].status, StepStatus::Succeeded);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
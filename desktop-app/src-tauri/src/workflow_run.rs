@@ -0,0 +1,851 @@
+/// Workflow Run - Step-by-step workflow execution
+///
+/// `run_workflow` runs a workflow one step at a time: every step is its own
+/// `vibe.py workflow run <file> --step <n>` process, handed the outputs saved by
+/// earlier steps through an `--outputs` JSON file in the workflow-runs
+/// artifact folder. Each step is announced with `workflow-step-started` and
+/// `workflow-step-finished`, and the run so far is kept in managed state for
+/// `get_workflow_run`. Steps run in file order, except that a step waits for
+/// the steps it `depends_on` (a dependency cycle is refused up front). A
+/// failed step stops the run unless it sets `continue_on_error: true`; a step
+/// is also skipped when a step it `depends_on` did not succeed, or when its
+/// `when:` condition (see `workflow_condition`) is false at its turn, which a
+/// dry run reports the same way. Cancelling the run's task id kills the step
+/// that is running and skips the rest. `abort_workflow` stops it more gently:
+/// the running step gets SIGTERM and `tasks.killGraceSecs` to clean up before
+/// it is killed, the remaining steps are skipped, `workflow-aborted` is
+/// emitted right away and the run is recorded as aborted. The run id is the
+/// task id `run_workflow` was given (and returns). Every finished run is
+/// recorded in `workflow_history`. With `snapshot` the project is saved
+/// before the first step (see `workflow_snapshot`); dry runs never are.
+///
+/// `run_workflow_step` runs a single step, chosen by name or id, the same
+/// way. The steps it `depends_on` do not run first and their outputs are
+/// missing, so the step runs without waiting for them and carries a warning
+/// saying so; its `when:` still applies. Such runs are recorded with
+/// `partial: true`.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+use crate::retention::{artifact_dir, ArtifactClass};
+use crate::workflow_condition::{self, Condition};
+use crate::workflow_snapshot::{self, Snapshot, WorkflowSnapshotSettings};
+use crate::workflow_yaml::{self, Node, Value};
+use crate::{
+    child_env, python_env, task_input, task_log, task_manager, task_process, task_progress, vibe_json, workflow_history,
+};
+
+pub const WORKFLOW_STEP_STARTED_EVENT: &str = "workflow-step-started";
+pub const WORKFLOW_STEP_FINISHED_EVENT: &str = "workflow-step-finished";
+pub const WORKFLOW_ABORTED_EVENT: &str = "workflow-aborted";
+
+/// Finished runs kept for `get_workflow_run`
+const MAX_FINISHED: usize = 50;
+
+/// Why the remaining steps are skipped
+const STOP_FAILED: &str = "an earlier step failed";
+const STOP_CANCELLED: &str = "the run was cancelled";
+const STOP_ABORTED: &str = "the run was aborted";
+
+/// A step as declared in the workflow file
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedStep {
+    /// 1-based position, as passed to `--step`
+    pub index: usize,
+    pub id: Option<String>,
+    pub name: String,
+    pub agent: String,
+    pub depends_on: Vec<String>,
+    pub continue_on_error: bool,
+    /// Runs only when this holds
+    pub when: Option<Condition>,
+    /// Reported on the step's result
+    pub warnings: Vec<String>,
+}
+
+/// Positions of steps in run order: file order, except that a step waits
+/// for the steps it `depends_on` (unknown ids are left to validation)
+///
+/// Err holds the ids of a dependency cycle, its first id repeated at the end.
+pub fn run_order(ids: &[Option<String>], depends_on: &[Vec<String>]) -> Result<Vec<usize>, Vec<String>> {
+    let position = |id: &String| ids.iter().position(|i| i.as_ref() == Some(id));
+    let waits_for: Vec<Vec<usize>> = depends_on.iter().map(|d| d.iter().filter_map(position).collect()).collect();
+    let mut placed = vec![false; ids.len()];
+    let mut order = Vec::with_capacity(ids.len());
+
+    while order.len() < ids.len() {
+        if let Some(next) = (0..ids.len()).find(|&i| !placed[i] && waits_for[i].iter().all(|&d| placed[d])) {
+            placed[next] = true;
+            order.push(next);
+            continue;
+        }
+        // Every step left waits for another one left: follow the waits until one repeats
+        let mut path = vec![(0..ids.len()).find(|&i| !placed[i]).unwrap_or_default()];
+        loop {
+            let last = path[path.len() - 1];
+            let next = waits_for[last].iter().copied().find(|&d| !placed[d]).unwrap_or(last);
+            if let Some(start) = path.iter().position(|&p| p == next) {
+                let label = |i: usize| ids[i].clone().unwrap_or_else(|| format!("Step {}", i + 1));
+                return Err(path[start..].iter().chain([&next]).map(|&i| label(i)).collect());
+            }
+            path.push(next);
+        }
+    }
+    Ok(order)
+}
+
+/// Error for a dependency cycle found by `run_order`
+pub fn cycle_error(cycle: &[String]) -> String {
+    format!("Steps depend on each other in a cycle: {}", cycle.join(" -> "))
+}
+
+/// Steps of a workflow's YAML, in run order
+pub fn plan(yaml: &str) -> Result<Vec<PlannedStep>, String> {
+    let root = workflow_yaml::parse(yaml).map_err(|e| e.to_string())?;
+    let steps = root.get("steps").and_then(Node::as_seq).unwrap_or_default();
+    let planned = steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let text = |key: &str| step.get(key).and_then(Node::as_str).map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+            let id = text("id");
+            let depends_on = match step.get("depends_on").map(|n| &n.value) {
+                Some(Value::Seq(items)) => items.iter().filter_map(|n| n.as_str().map(str::to_string)).collect(),
+                Some(Value::Scalar(dependency)) => vec![dependency.clone()],
+                _ => Vec::new(),
+            };
+            let name = text("name").or_else(|| id.clone()).unwrap_or_else(|| format!("Step {}", i + 1));
+            let when = text("when")
+                .map(|when| workflow_condition::parse(&when).map_err(|e| format!("Step '{}': invalid `when` ({})", name, e)))
+                .transpose()?;
+            Ok(PlannedStep {
+                index: i + 1,
+                name,
+                id,
+                agent: text("agent").unwrap_or_else(|| "auto".to_string()),
+                depends_on,
+                continue_on_error: matches!(text("continue_on_error").as_deref(), Some("true" | "yes" | "on")),
+                when,
+                warnings: Vec::new(),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let ids: Vec<Option<String>> = planned.iter().map(|s| s.id.clone()).collect();
+    let depends_on: Vec<Vec<String>> = planned.iter().map(|s| s.depends_on.clone()).collect();
+    let order = run_order(&ids, &depends_on).map_err(|cycle| cycle_error(&cycle))?;
+    Ok(order.into_iter().map(|position| planned[position].clone()).collect())
+}
+
+/// Step `wanted` (a name or id) of `steps`, set to run without the steps it `depends_on`
+pub fn isolate(steps: Vec<PlannedStep>, workflow: &str, wanted: &str) -> Result<PlannedStep, String> {
+    let wanted = wanted.trim();
+    let mut step = steps
+        .into_iter()
+        .find(|s| s.name == wanted || s.id.as_deref() == Some(wanted))
+        .ok_or_else(|| format!("Workflow '{}' has no step '{}'", workflow, wanted))?;
+    if !step.depends_on.is_empty() {
+        step.warnings.push(format!(
+            "Ran in isolation: the steps it depends on ({}) did not run, so their outputs are missing",
+            step.depends_on.join(", ")
+        ));
+        step.depends_on.clear();
+    }
+    Ok(step)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Succeeded,
+    Failed,
+    Skipped,
+    Cancelled,
+}
+
+/// Outcome of one step
+#[derive(Debug, Clone, Serialize)]
+pub struct StepResult {
+    pub index: usize,
+    pub id: Option<String>,
+    pub name: String,
+    pub agent: String,
+    pub status: StepStatus,
+    pub success: bool,
+    /// Seconds the step ran (0 when skipped)
+    pub duration: f64,
+    /// Console output of the step's vibe.py run
+    pub output: String,
+    /// Why the step failed or was skipped
+    pub error: Option<String>,
+    pub exit_code: Option<i32>,
+    pub files_changed: Vec<String>,
+    /// Program, arguments and working directory actually spawned
+    pub invocation: Option<child_env::Invocation>,
+    /// Output was cut at `tasks.maxOutputBytes`
+    pub output_truncated: bool,
+    /// Complete output when truncated (page through it with `read_task_log`)
+    pub full_output_path: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl StepResult {
+    fn new(step: &PlannedStep, status: StepStatus) -> Self {
+        Self {
+            index: step.index,
+            id: step.id.clone(),
+            name: step.name.clone(),
+            agent: step.agent.clone(),
+            status,
+            success: status == StepStatus::Succeeded,
+            duration: 0.0,
+            output: String::new(),
+            error: None,
+            exit_code: None,
+            files_changed: Vec::new(),
+            invocation: None,
+            output_truncated: false,
+            full_output_path: None,
+            warnings: step.warnings.clone(),
+        }
+    }
+}
+
+/// Result of `run_workflow`, and the state of a run in progress for `get_workflow_run`
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowRunResult {
+    pub task_id: String,
+    pub workflow: String,
+    pub success: bool,
+    pub cancelled: bool,
+    /// Stopped by `abort_workflow` (also counts as cancelled)
+    pub aborted: bool,
+    /// Only one step ran (`run_workflow_step`)
+    pub partial: bool,
+    pub finished: bool,
+    pub total_steps: usize,
+    /// Step running right now (1-based)
+    pub current_step: Option<usize>,
+    /// Steps that finished or were skipped so far
+    pub steps: Vec<StepResult>,
+    pub execution_time: f64,
+    /// Files changed by any step
+    pub files_changed: Vec<String>,
+    /// Variables provided to the steps (names and sources only)
+    pub env: Vec<child_env::ProvidedVar>,
+    /// Safety snapshot taken before the first step
+    pub snapshot: Option<Snapshot>,
+}
+
+/// Why `step` will not run after `done`, if it won't (`stopped` says why the
+/// run stopped; `lookup` reads the references of the step's `when:`)
+fn skip_reason(
+    step: &PlannedStep,
+    done: &[StepResult],
+    stopped: Option<&str>,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Option<String> {
+    if let Some(reason) = stopped {
+        return Some(format!("Skipped: {}", reason));
+    }
+    let failed_dependency = step.depends_on.iter().find(|dependency| {
+        !done.iter().any(|d| d.id.as_deref() == Some(dependency.as_str()) && d.status == StepStatus::Succeeded)
+    });
+    if let Some(dependency) = failed_dependency {
+        return Some(format!("Skipped: depends on '{}', which did not succeed", dependency));
+    }
+    step.when
+        .as_ref()
+        .filter(|when| !when.holds(lookup))
+        .map(|when| when.skip_reason(lookup))
+}
+
+/// Value of a `when:` reference: a variable, an output saved so far, or
+/// `steps.<id>.status` / `steps.<id>.success` of a step in `done`
+fn reference_value(
+    name: &str,
+    variables: &HashMap<String, String>,
+    outputs: &serde_json::Map<String, serde_json::Value>,
+    done: &[StepResult],
+) -> Option<String> {
+    if let Some(output) = name.strip_prefix("outputs.") {
+        return outputs.get(output).map(|value| match value {
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Null => String::new(),
+            other => other.to_string(),
+        });
+    }
+    if let Some((id, field)) = name.strip_prefix("steps.").and_then(|rest| rest.rsplit_once('.')) {
+        let step = done.iter().find(|d| d.id.as_deref() == Some(id))?;
+        return match field {
+            "status" => serde_json::to_value(step.status).ok()?.as_str().map(str::to_string),
+            "success" => Some(step.success.to_string()),
+            _ => None,
+        };
+    }
+    variables.get(name).cloned()
+}
+
+/// Whether a run whose steps ended as `done` succeeded: every step did, was
+/// skipped, or failed with `continue_on_error`
+fn succeeded(steps: &[PlannedStep], done: &[StepResult]) -> bool {
+    steps.iter().zip(done).all(|(planned, result)| {
+        result.success || result.status == StepStatus::Skipped || (planned.continue_on_error && result.status == StepStatus::Failed)
+    })
+}
+
+/// Payload of `workflow-step-started`
+#[derive(Debug, Clone, Serialize)]
+pub struct StepStarted<'a> {
+    pub task_id: &'a str,
+    pub workflow: &'a str,
+    pub index: usize,
+    pub total_steps: usize,
+    pub name: &'a str,
+    pub agent: &'a str,
+}
+
+/// Payload of `workflow-step-finished`
+#[derive(Debug, Clone, Serialize)]
+pub struct StepFinished<'a> {
+    pub task_id: &'a str,
+    pub workflow: &'a str,
+    pub total_steps: usize,
+    #[serde(flatten)]
+    pub step: &'a StepResult,
+}
+
+/// Payload of `workflow-aborted`
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowAborted {
+    pub task_id: String,
+    pub workflow: String,
+    /// Step that was running when the run was aborted
+    pub current_step: Option<usize>,
+}
+
+/// Runs in progress and the last `MAX_FINISHED` finished ones
+#[derive(Default)]
+pub struct WorkflowRuns {
+    runs: Mutex<HashMap<String, WorkflowRunResult>>,
+    finished: Mutex<VecDeque<String>>,
+}
+
+impl WorkflowRuns {
+    fn runs(&self) -> MutexGuard<'_, HashMap<String, WorkflowRunResult>> {
+        self.runs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update(&self, task_id: &str, change: impl FnOnce(&mut WorkflowRunResult)) {
+        if let Some(run) = self.runs().get_mut(task_id) {
+            change(run);
+        }
+    }
+
+    fn finish(&self, run: &WorkflowRunResult) {
+        self.runs().insert(run.task_id.clone(), run.clone());
+        let mut finished = self.finished.lock().unwrap_or_else(|e| e.into_inner());
+        finished.retain(|id| id != &run.task_id);
+        finished.push_back(run.task_id.clone());
+        while finished.len() > MAX_FINISHED {
+            if let Some(oldest) = finished.pop_front() {
+                self.runs().remove(&oldest);
+            }
+        }
+    }
+
+    pub fn get(&self, task_id: &str) -> Option<WorkflowRunResult> {
+        self.runs().get(task_id).cloned()
+    }
+
+    /// Mark a run in progress as aborted, returning its state
+    fn abort(&self, task_id: &str) -> Result<WorkflowRunResult, String> {
+        match self.runs().get_mut(task_id) {
+            Some(run) if !run.finished => {
+                run.aborted = true;
+                Ok(run.clone())
+            }
+            _ => Err(format!("Workflow run '{}' is not running", task_id)),
+        }
+    }
+
+    fn is_aborted(&self, task_id: &str) -> bool {
+        self.runs().get(task_id).is_some_and(|run| run.aborted)
+    }
+
+    /// Whether a run of `workflow` is in progress
+    pub fn is_running(&self, workflow: &str) -> bool {
+        self.runs().values().any(|run| run.workflow == workflow && !run.finished)
+    }
+}
+
+/// Outputs saved so far, written where the next step's `--outputs` reads them
+fn write_outputs(path: &Path, outputs: &serde_json::Map<String, serde_json::Value>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create workflow run directory: {}", e))?;
+    }
+    let json = serde_json::to_string(outputs).map_err(|e| format!("Failed to save step outputs: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to save step outputs: {}", e))
+}
+
+/// `vibe.py workflow run <path> --step <n>`, without the child environment
+///
+/// By path, since vibe.py only finds the global workflows by name and a
+/// project workflow may shadow one of them (see `workflow_scope`).
+/// `variables` were checked against the file by `run`.
+fn step_command(
+    vibe_path: &Path,
+    workflow_path: &Path,
+    index: usize,
+    outputs_path: &Path,
+    dry_run: bool,
+    variables: &HashMap<String, String>,
+) -> Result<std::process::Command, String> {
+    let mut cmd = python_env::command()?;
+    cmd.arg(vibe_path)
+       .arg("workflow")
+       .arg("run")
+       .arg(workflow_path)
+       .arg(vibe_json::JSON_FLAG)
+       .arg("--step")
+       .arg(index.to_string())
+       .arg("--outputs")
+       .arg(outputs_path);
+    if dry_run {
+        cmd.arg("--dry-run");
+    }
+    crate::workflow_variables::push_args(&mut cmd, variables);
+    crate::target_project(&mut cmd, vibe_path);
+    Ok(cmd)
+}
+
+/// Arguments shared by every step's command
+pub struct RunRequest {
+    pub name: String,
+    pub dry_run: bool,
+    pub variables: HashMap<String, String>,
+    pub env: Option<HashMap<String, String>>,
+    pub task_id: String,
+    /// What started the run (`schedule`), None when started by hand
+    pub triggered_by: Option<String>,
+    /// Folder `name` is looked up in (see `workflow_scope`)
+    pub scope: Option<String>,
+    /// Run only this step, by name or id (see `isolate`)
+    pub step: Option<String>,
+    /// Snapshot the project first (None: `workflowSnapshots.enabled`)
+    pub snapshot: Option<bool>,
+}
+
+/// Run `request.name` unless it fails `validate_workflow` (run anyway with `force`)
+pub async fn run_if_valid(app: &tauri::AppHandle, request: RunRequest, force: bool) -> Result<WorkflowRunResult, String> {
+    let path = crate::workflow_scope::resolve(&request.name, request.scope.as_deref())?;
+    let validation = crate::workflow_validation::validate_file(&path)?;
+    if !validation.is_valid && !force {
+        return Err(format!("Workflow '{}' is invalid:\n{}", request.name, validation.summary()));
+    }
+    run(app, request).await
+}
+
+/// Run `request.name` step by step (see the module docs)
+pub async fn run(app: &tauri::AppHandle, request: RunRequest) -> Result<WorkflowRunResult, String> {
+    let RunRequest { name, dry_run, variables, env, task_id, triggered_by, scope, step, snapshot } = request;
+    let path = crate::workflow_scope::resolve(&name, scope.as_deref())?;
+    let yaml = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read workflow: {}", e))?;
+    let partial = step.is_some();
+    let steps = match step {
+        Some(wanted) => vec![isolate(plan(&yaml)?, &name, &wanted)?],
+        None => plan(&yaml)?,
+    };
+    let declared = crate::workflow_variables::declared(&yaml)?;
+    crate::workflow_variables::check_overrides(&declared, &variables)?;
+
+    // What `when:` conditions see: the declared defaults, overridden for this run
+    let mut values: HashMap<String, String> =
+        declared.into_iter().map(|v| (v.name, v.default.unwrap_or_default())).collect();
+    values.extend(variables.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    let vibe_path = python_env::vibe_path();
+    let child_env = child_env::build_child_env(&child_env::ChildContext::Workflow(&path)).with_request_vars(env.as_ref());
+    let outputs_path = artifact_dir(ArtifactClass::WorkflowRuns).join(format!("{}.outputs.json", task_id));
+    let start = std::time::Instant::now();
+    let started_at = chrono::Utc::now();
+    let project = crate::task_history::current_project();
+    let wants_snapshot = snapshot.unwrap_or_else(|| WorkflowSnapshotSettings::load().enabled);
+    let snapshot = match project.clone().filter(|_| wants_snapshot && !dry_run) {
+        Some(dir) => {
+            let (id, workflow) = (task_id.clone(), name.clone());
+            let taken = tauri::async_runtime::spawn_blocking(move || workflow_snapshot::take(Path::new(&dir), &id, &workflow))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|taken| taken)
+                .map_err(|e| format!("Snapshot failed, the workflow did not run: {}", e))?;
+            Some(taken)
+        }
+        None => None,
+    };
+
+    let mut run = WorkflowRunResult {
+        task_id: task_id.clone(),
+        workflow: name.clone(),
+        success: false,
+        cancelled: false,
+        aborted: false,
+        partial,
+        finished: false,
+        total_steps: steps.len(),
+        current_step: None,
+        steps: Vec::new(),
+        execution_time: 0.0,
+        files_changed: Vec::new(),
+        env: child_env.provided(),
+        snapshot,
+    };
+    let runs = app.state::<WorkflowRuns>();
+    runs.runs().insert(task_id.clone(), run.clone());
+    let tasks = task_manager::manager(app);
+    tasks.start(&task_id, task_manager::TaskKind::Workflow, &name);
+    crate::task_changes::begin(&task_id).await;
+
+    let prompts = task_input::Prompts::emitting(app, &task_id);
+    let progress = task_progress::watcher(app, &task_id, child_env.clone());
+    let mut outputs = serde_json::Map::new();
+    let mut stopped = None;
+
+    for step in &steps {
+        if runs.is_aborted(&task_id) {
+            stopped = Some(STOP_ABORTED);
+        }
+        let lookup = |name: &str| reference_value(name, &values, &outputs, &run.steps);
+        if let Some(reason) = skip_reason(step, &run.steps, stopped, &lookup) {
+            let mut skipped = StepResult::new(step, StepStatus::Skipped);
+            skipped.error = Some(reason);
+            let _ = app.emit(
+                WORKFLOW_STEP_FINISHED_EVENT,
+                StepFinished { task_id: &task_id, workflow: &name, total_steps: steps.len(), step: &skipped },
+            );
+            run.steps.push(skipped.clone());
+            runs.update(&task_id, |r| r.steps.push(skipped));
+            continue;
+        }
+
+        let _ = app.emit(
+            WORKFLOW_STEP_STARTED_EVENT,
+            StepStarted {
+                task_id: &task_id,
+                workflow: &name,
+                index: step.index,
+                total_steps: steps.len(),
+                name: &step.name,
+                agent: &step.agent,
+            },
+        );
+        runs.update(&task_id, |r| r.current_step = Some(step.index));
+        let step_start = std::time::Instant::now();
+
+        let mut result = StepResult::new(step, StepStatus::Failed);
+        let output = match step_command(&vibe_path, &path, step.index, &outputs_path, dry_run, &variables) {
+            Ok(mut cmd) => {
+                child_env.apply(&mut cmd);
+                result.invocation = Some(child_env::Invocation::capture(&cmd));
+                let (run_id, prompts, progress) = (task_id.clone(), prompts.clone(), progress.clone());
+                match write_outputs(&outputs_path, &outputs) {
+                    Ok(()) => tauri::async_runtime::spawn_blocking(move || {
+                        task_process::run(&run_id, &mut cmd, None, Some(prompts), Some(progress))
+                    })
+                    .await
+                    .map_err(|e| format!("Failed to run step: {}", e))
+                    .and_then(|output| output.map_err(|e| format!("Failed to run step: {}", e))),
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        };
+
+        match output {
+            Ok(output) => {
+                let stdout = child_env.mask_values(&output.stdout);
+                let report = vibe_json::parse::<vibe_json::StepReport>(&stdout);
+                let cancelled = output.ending == task_process::Ending::Cancelled;
+                let success = output.success && report.as_ref().is_some_and(|r| r.success);
+
+                result.status = match (cancelled, success) {
+                    (true, _) => StepStatus::Cancelled,
+                    (false, true) => StepStatus::Succeeded,
+                    (false, false) => StepStatus::Failed,
+                };
+                result.success = success;
+                result.output = child_env.mask_values(&output.stderr);
+                result.exit_code = output.exit_code;
+                if let Some(report) = report {
+                    result.error = report.error.filter(|_| !success);
+                    result.files_changed = report.files_changed;
+                    outputs.extend(report.outputs);
+                } else if !success {
+                    let exit_code = output.exit_code.map_or("?".to_string(), |c| c.to_string());
+                    result.error = Some(format!("Step produced no report (exit code {})", exit_code));
+                }
+                run.cancelled |= cancelled;
+            }
+            Err(e) => result.error = Some(e),
+        }
+        result.duration = step_start.elapsed().as_secs_f64();
+        task_log::cap_step(&task_id, &mut result);
+
+        for path in &result.files_changed {
+            crate::record_reported_file(app, &task_id, path);
+            if !run.files_changed.contains(path) {
+                run.files_changed.push(path.clone());
+            }
+        }
+        let _ = app.emit(
+            WORKFLOW_STEP_FINISHED_EVENT,
+            StepFinished { task_id: &task_id, workflow: &name, total_steps: steps.len(), step: &result },
+        );
+        stopped = if runs.is_aborted(&task_id) {
+            Some(STOP_ABORTED)
+        } else if run.cancelled {
+            Some(STOP_CANCELLED)
+        } else if !result.success && !step.continue_on_error {
+            Some(STOP_FAILED)
+        } else {
+            None
+        };
+        run.steps.push(result.clone());
+        runs.update(&task_id, |r| {
+            r.current_step = None;
+            r.steps.push(result);
+            r.files_changed = run.files_changed.clone();
+            r.cancelled = run.cancelled;
+        });
+    }
+    let _ = std::fs::remove_file(&outputs_path);
+
+    run.aborted = runs.is_aborted(&task_id);
+    run.cancelled |= run.aborted;
+    run.success = !run.cancelled && succeeded(&steps, &run.steps);
+    run.finished = true;
+    run.execution_time = start.elapsed().as_secs_f64();
+    runs.finish(&run);
+    crate::task_changes::finish(app, &task_id).await;
+    tasks.finish(&task_id, task_manager::TaskStatus::finished(run.success, run.cancelled));
+    workflow_history::record(&workflow_history::WorkflowRunRecord::new(&run, project, &variables, dry_run, started_at, triggered_by));
+    Ok(run)
+}
+
+/// A workflow run, finished or still in progress
+#[tauri::command]
+pub async fn get_workflow_run(state: tauri::State<'_, WorkflowRuns>, task_id: String) -> Result<WorkflowRunResult, String> {
+    crate::command_metrics::timed("get_workflow_run", async move {
+        state.get(&task_id).ok_or_else(|| format!("Unknown workflow run '{}'", task_id))
+    }).await
+}
+
+/// Run one step of workflow `workflow_name`, chosen by name or id, without
+/// the steps before it (see the module docs). `task_id` works as for
+/// `run_workflow`; `scope` picks the folder the workflow is looked up in.
+#[tauri::command]
+pub async fn run_workflow_step(
+    app: tauri::AppHandle,
+    workflow_name: String,
+    step_name: String,
+    variables: Option<HashMap<String, String>>,
+    task_id: Option<String>,
+    scope: Option<String>,
+) -> Result<StepResult, String> {
+    crate::command_metrics::timed("run_workflow_step", async move {
+        let request = RunRequest {
+            name: workflow_name,
+            dry_run: false,
+            variables: variables.unwrap_or_default(),
+            env: None,
+            task_id: task_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            triggered_by: None,
+            scope,
+            step: Some(step_name),
+            snapshot: None,
+        };
+        let mut finished = run(&app, request).await?;
+        finished.steps.pop().ok_or_else(|| "The step did not run".to_string())
+    }).await
+}
+
+/// Abort a workflow run: the running step gets `tasks.killGraceSecs` to
+/// stop after SIGTERM before it is killed, and the remaining steps are skipped
+#[tauri::command]
+pub async fn abort_workflow(app: tauri::AppHandle, run_id: String) -> Result<(), String> {
+    crate::command_metrics::timed("abort_workflow", async move {
+        let result = app.state::<WorkflowRuns>().abort(&run_id);
+        crate::audit::record("abort_workflow", serde_json::json!({ "run_id": run_id }), crate::audit::ORIGIN_COMMAND, &result);
+        let run = result?;
+        let _ = app.emit(
+            WORKFLOW_ABORTED_EVENT,
+            WorkflowAborted { task_id: run.task_id, workflow: run.workflow, current_step: run.current_step },
+        );
+
+        // The step may be between its started event and its process spawning,
+        // so keep stopping whatever runs under the id until the run is over
+        let grace = Duration::from_secs(task_process::TaskProcessSettings::load().kill_grace_secs);
+        tauri::async_runtime::spawn_blocking(move || {
+            while app.state::<WorkflowRuns>().get(&run_id).is_some_and(|run| !run.finished) {
+                if !task_process::terminate(&run_id, grace) {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+            }
+        });
+        Ok(())
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_and_skipped_steps() {
+        let yaml = "\
+name: Demo
+steps:
+  - id: lint
+    agent: cli
+    prompt: Lint
+    continue_on_error: true
+  - id: test
+    name: Run tests
+    prompt: Test
+  - name: Report
+    agent: api
+    depends_on: [lint, test]
+    prompt: Report
+";
+        let steps = plan(yaml).unwrap();
+        assert_eq!(steps.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), vec!["lint", "Run tests", "Report"]);
+        assert_eq!(steps[1].agent, "auto");
+        assert_eq!(steps[2].id, None);
+        assert_eq!(steps[2].depends_on, vec!["lint", "test"]);
+        assert_eq!(steps.iter().map(|s| s.continue_on_error).collect::<Vec<_>>(), vec![true, false, false]);
+
+        // A tolerated failure keeps the run going, but not the steps depending on it
+        let lint = StepResult::new(&steps[0], StepStatus::Failed);
+        let none = |_: &str| None;
+        assert_eq!(skip_reason(&steps[1], &[lint.clone()], None, &none), None);
+        let test = StepResult::new(&steps[1], StepStatus::Succeeded);
+        let done = vec![lint, test];
+        assert_eq!(
+            skip_reason(&steps[2], &done, None, &none).as_deref(),
+            Some("Skipped: depends on 'lint', which did not succeed")
+        );
+        assert_eq!(
+            skip_reason(&steps[1], &[], Some(STOP_ABORTED), &none).as_deref(),
+            Some("Skipped: the run was aborted")
+        );
+
+        let mut done = done;
+        done.push(StepResult::new(&steps[2], StepStatus::Skipped));
+        assert!(succeeded(&steps, &done));
+        done[1] = StepResult::new(&steps[1], StepStatus::Failed);
+        assert!(!succeeded(&steps, &done));
+    }
+
+    #[test]
+    fn test_dependencies_order_steps_and_conditions_skip_them() {
+        let yaml = "\
+name: Demo
+steps:
+  - id: deploy
+    depends_on: [test]
+    when: \"{{env}} == 'production'\"
+    prompt: Deploy
+  - id: build
+    prompt: Build
+  - id: test
+    depends_on: build
+    when: \"{{steps.build.status}} == succeeded\"
+    prompt: Test
+";
+        let steps = plan(yaml).unwrap();
+        assert_eq!(steps.iter().map(|s| s.index).collect::<Vec<_>>(), vec![2, 3, 1]);
+
+        let done = vec![StepResult::new(&steps[0], StepStatus::Succeeded)];
+        let variables = HashMap::from([("env".to_string(), "staging".to_string())]);
+        let outputs = serde_json::Map::new();
+        let lookup = |name: &str| reference_value(name, &variables, &outputs, &done);
+        assert_eq!(lookup("steps.build.status").as_deref(), Some("succeeded"));
+        assert_eq!(skip_reason(&steps[1], &done, None, &lookup), None);
+
+        let mut done = done;
+        done.push(StepResult::new(&steps[1], StepStatus::Succeeded));
+        let lookup = |name: &str| reference_value(name, &variables, &outputs, &done);
+        assert_eq!(
+            skip_reason(&steps[2], &done, None, &lookup).as_deref(),
+            Some("Skipped: `when: {{env}} == 'production'` is false (env = 'staging')")
+        );
+
+        let cycle = "name: Loop\nsteps:\n  - id: a\n    depends_on: c\n  - id: b\n    depends_on: a\n  - id: c\n    depends_on: b\n";
+        assert_eq!(plan(cycle).unwrap_err(), "Steps depend on each other in a cycle: a -> c -> b -> a");
+        assert!(plan("name: x\nsteps:\n  - id: a\n    when: \"{{env}} =\"\n").unwrap_err().starts_with("Step 'a': invalid `when`"));
+    }
+
+    #[test]
+    fn test_isolated_step_runs_without_its_dependencies() {
+        let yaml = "\
+name: Demo
+steps:
+  - id: build
+    prompt: Build
+  - id: test
+    name: Run tests
+    depends_on: build
+    prompt: Test
+";
+        let build = isolate(plan(yaml).unwrap(), "demo", "build").unwrap();
+        assert!(build.warnings.is_empty());
+
+        let test = isolate(plan(yaml).unwrap(), "demo", " Run tests ").unwrap();
+        assert_eq!(test.index, 2);
+        assert!(test.depends_on.is_empty());
+        assert_eq!(skip_reason(&test, &[], None, &|_: &str| None), None);
+        let result = StepResult::new(&test, StepStatus::Succeeded);
+        assert_eq!(
+            result.warnings,
+            vec!["Ran in isolation: the steps it depends on (build) did not run, so their outputs are missing"]
+        );
+        assert_eq!(isolate(plan(yaml).unwrap(), "demo", "test").unwrap().name, "Run tests");
+        assert_eq!(isolate(plan(yaml).unwrap(), "demo", "deploy").unwrap_err(), "Workflow 'demo' has no step 'deploy'");
+    }
+
+    #[test]
+    fn test_only_runs_in_progress_can_be_aborted() {
+        let runs = WorkflowRuns::default();
+        let run = WorkflowRunResult {
+            task_id: "run-1".to_string(),
+            workflow: "deploy".to_string(),
+            success: false,
+            cancelled: false,
+            aborted: false,
+            partial: false,
+            finished: false,
+            total_steps: 2,
+            current_step: Some(1),
+            steps: Vec::new(),
+            execution_time: 0.0,
+            files_changed: Vec::new(),
+            env: Vec::new(),
+            snapshot: None,
+        };
+        runs.runs().insert(run.task_id.clone(), run.clone());
+
+        assert!(!runs.is_aborted("run-1"));
+        assert_eq!(runs.abort("run-1").unwrap().current_step, Some(1));
+        assert!(runs.is_aborted("run-1"));
+
+        runs.finish(&WorkflowRunResult { finished: true, ..run });
+        assert!(runs.abort("run-1").is_err());
+        assert!(runs.abort("run-2").is_err());
+    }
+}
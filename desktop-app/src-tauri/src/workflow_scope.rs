@@ -0,0 +1,193 @@
+/// Workflow Scope - Global workflows and the open project's own
+///
+/// Global workflows live in `get_workflows_path()` and are shared by every
+/// project. With a project open, its `.agent/workflows` folder holds
+/// workflows of that project only (YAML files; the Markdown workflows other
+/// tools keep there are left alone). A project workflow shadows the global
+/// one with the same name: `list_workflows` lists it once, as
+/// `scope: "project"`, and a name given without a scope resolves to the
+/// project file first. Listed files the workflow YAML reader cannot parse
+/// carry the error's position in `yaml_error`.
+
+use std::path::{Path, PathBuf};
+
+use crate::workflow_yaml::{self, YamlDiagnostic};
+use crate::WorkflowInfo;
+
+pub const SCOPE_GLOBAL: &str = "global";
+pub const SCOPE_PROJECT: &str = "project";
+
+const EXTENSIONS: [&str; 2] = ["yaml", "yml"];
+
+/// `.agent/workflows` of the open project (None without a project)
+pub fn project_folder() -> Option<PathBuf> {
+    let project = crate::CURRENT_PROJECT.read().ok().and_then(|p| p.clone())?;
+    Some(PathBuf::from(project).join(".agent").join("workflows"))
+}
+
+/// Folders of `scope` with their scope, in lookup order (project first when None)
+pub fn folders(scope: Option<&str>) -> Result<Vec<(&'static str, PathBuf)>, String> {
+    scoped_folders(scope, project_folder(), crate::get_workflows_path())
+}
+
+fn scoped_folders(
+    scope: Option<&str>,
+    project: Option<PathBuf>,
+    global: PathBuf,
+) -> Result<Vec<(&'static str, PathBuf)>, String> {
+    let project = project.map(|folder| (SCOPE_PROJECT, folder));
+    match scope {
+        None => Ok(project.into_iter().chain([(SCOPE_GLOBAL, global)]).collect()),
+        Some(SCOPE_GLOBAL) => Ok(vec![(SCOPE_GLOBAL, global)]),
+        Some(SCOPE_PROJECT) => project
+            .map(|project| vec![project])
+            .ok_or_else(|| "No project is open; project workflows need one".to_string()),
+        Some(other) => Err(format!(
+            "Unknown workflow scope '{}' (expected '{}' or '{}')",
+            other, SCOPE_PROJECT, SCOPE_GLOBAL
+        )),
+    }
+}
+
+/// Folder new workflows of `scope` are created in (global when omitted)
+pub fn folder(scope: Option<&str>) -> Result<PathBuf, String> {
+    let mut folders = folders(Some(scope.unwrap_or(SCOPE_GLOBAL)))?;
+    Ok(folders.remove(0).1)
+}
+
+/// File of workflow `name` in the first of `folders` that has one (None for
+/// names that could lead outside them)
+fn find_in(folders: &[(&'static str, PathBuf)], name: &str) -> Option<PathBuf> {
+    crate::workflow_files::file_stem(name).ok()?;
+    folders
+        .iter()
+        .flat_map(|(_, folder)| EXTENSIONS.iter().map(move |ext| folder.join(format!("{}.{}", name, ext))))
+        .find(|path| path.is_file())
+}
+
+/// File of workflow `name` in `scope` (the project's, else the global one, when None)
+pub fn resolve(name: &str, scope: Option<&str>) -> Result<PathBuf, String> {
+    crate::workflow_files::file_stem(name)?;
+    let folders = folders(scope)?;
+    find_in(&folders, name).ok_or_else(|| {
+        let searched: Vec<String> = folders.iter().map(|(_, folder)| folder.display().to_string()).collect();
+        format!("Workflow '{}' not found in {}", name, searched.join(" or "))
+    })
+}
+
+/// Workflows directly inside `folder`, read with the app's YAML parser
+pub fn list_in(folder: &Path, scope: &str) -> Vec<WorkflowInfo> {
+    let Ok(entries) = std::fs::read_dir(folder) else { return Vec::new() };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| EXTENSIONS.contains(&ext))
+        })
+        .collect();
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|path| {
+            let mut yaml_error = None;
+            let (description, steps_count) = match std::fs::read_to_string(&path) {
+                Ok(yaml) => match workflow_yaml::parse(&yaml) {
+                    Ok(root) => (
+                        root.get("description").and_then(|d| d.as_str()).unwrap_or_default().to_string(),
+                        root.get("steps").and_then(|steps| steps.as_seq()).map(|steps| steps.len()),
+                    ),
+                    Err(e) => {
+                        yaml_error = Some(e.diagnostic(&yaml));
+                        (format!("⚠️ {}", e), None)
+                    }
+                },
+                Err(e) => (format!("⚠️ Failed to read workflow: {}", e), None),
+            };
+            WorkflowInfo {
+                name: path.file_stem().unwrap_or_default().to_string_lossy().to_string(),
+                description,
+                steps_count,
+                file_path: Some(path.to_string_lossy().to_string()),
+                scope: scope.to_string(),
+                yaml_error,
+            }
+        })
+        .collect()
+}
+
+/// Syntax error of the workflow file at `path` (None when it parses or cannot be read)
+fn yaml_error(path: &Path) -> Option<YamlDiagnostic> {
+    let yaml = std::fs::read_to_string(path).ok()?;
+    workflow_yaml::parse(&yaml).err().map(|e| e.diagnostic(&yaml))
+}
+
+/// Add `yaml_error` to workflows listed by vibe.py, whose own errors have no position
+pub fn with_yaml_errors(mut workflows: Vec<WorkflowInfo>) -> Vec<WorkflowInfo> {
+    for workflow in &mut workflows {
+        workflow.yaml_error = workflow.file_path.as_deref().and_then(|path| yaml_error(Path::new(path)));
+        if let Some(e) = &workflow.yaml_error {
+            workflow.description = format!("⚠️ Invalid workflow YAML (line {}, column {}): {}", e.line, e.column, e.message);
+        }
+    }
+    workflows
+}
+
+/// Project workflows, then the global ones they do not shadow
+pub fn merge(project: Vec<WorkflowInfo>, global: Vec<WorkflowInfo>) -> Vec<WorkflowInfo> {
+    let shadowed: Vec<String> = project.iter().map(|w| w.name.clone()).collect();
+    project
+        .into_iter()
+        .chain(global.into_iter().filter(|w| !shadowed.contains(&w.name)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_workflows_shadow_global_ones() {
+        let root = std::env::temp_dir().join(format!("vibecode-workflow-scope-{}", uuid::Uuid::new_v4()));
+        let project = root.join("project").join(".agent").join("workflows");
+        let global = root.join("workflows");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::create_dir_all(&global).unwrap();
+        std::fs::write(project.join("deploy.yaml"), "name: Deploy\ndescription: Project deploy\nsteps:\n  - name: a\n").unwrap();
+        std::fs::write(project.join("broken.yml"), "name: x\n\tsteps:\n").unwrap();
+        std::fs::write(project.join("notes.md"), "# Not a workflow\n").unwrap();
+        std::fs::write(global.join("deploy.yaml"), "name: Deploy\ndescription: Global deploy\n").unwrap();
+        std::fs::write(global.join("review.yaml"), "name: Review\n").unwrap();
+
+        let both = scoped_folders(None, Some(project.clone()), global.clone()).unwrap();
+        assert_eq!(find_in(&both, "deploy"), Some(project.join("deploy.yaml")));
+        assert_eq!(find_in(&both, "review"), Some(global.join("review.yaml")));
+        let global_only = scoped_folders(Some(SCOPE_GLOBAL), Some(project.clone()), global.clone()).unwrap();
+        assert_eq!(find_in(&global_only, "deploy"), Some(global.join("deploy.yaml")));
+
+        // Names never lead outside the workflow folders
+        std::fs::write(root.join("x.yaml"), "name: Outside\n").unwrap();
+        assert_eq!(find_in(&both, "../x"), None);
+        assert_eq!(find_in(&both, "..\\x"), None);
+        assert!(scoped_folders(Some(SCOPE_PROJECT), None, global.clone()).is_err());
+        assert!(scoped_folders(Some("team"), None, global.clone()).is_err());
+
+        let listed = merge(list_in(&project, SCOPE_PROJECT), list_in(&global, SCOPE_GLOBAL));
+        let entries: Vec<(&str, &str, &str)> =
+            listed.iter().map(|w| (w.name.as_str(), w.scope.as_str(), w.description.as_str())).collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("broken", SCOPE_PROJECT, "⚠️ Invalid workflow YAML (line 2, column 1): Found a tab character that violates indentation"),
+                ("deploy", SCOPE_PROJECT, "Project deploy"),
+                ("review", SCOPE_GLOBAL, ""),
+            ]
+        );
+        assert_eq!(listed[1].steps_count, Some(1));
+        assert_eq!(listed[0].yaml_error.as_ref().map(|e| (e.line, e.column)), Some((2, 1)));
+        assert!(listed[1].yaml_error.is_none());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
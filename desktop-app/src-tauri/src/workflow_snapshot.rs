@@ -0,0 +1,348 @@
+/// Workflow Snapshot - Safety net taken before a workflow run, and restoring it
+///
+/// With `snapshot: true` on `run_workflow` (default: `workflowSnapshots.enabled`
+/// in settings.json) the project is saved before the first step runs, and the
+/// run is refused when that fails. In a git repository the whole work tree,
+/// untracked files included and ignored ones not, is committed on
+/// `refs/vibecode/snapshots/<run id>` through a copy of the index, so the
+/// staging area, HEAD and the stash stay as they were; a repository with
+/// nothing uncommitted is skipped (HEAD already holds it). A project not
+/// under git is copied, without ignored files and up to 20,000 files or
+/// 200 MB, to `<config>/artifacts/snapshots/<run id>/`, where `retention`
+/// prunes it like other snapshots. The snapshot is part of the run result
+/// and of its `workflow_history` record. `restore_snapshot(run_id)` puts the
+/// work tree back: every file of the snapshot is written again and files it
+/// did not have (ignored ones aside) are deleted. HEAD, the index and any
+/// commits the run made are left alone, so those show up as changes.
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use serde::{Deserialize, Serialize};
+
+use crate::git_diff::{self, GitDiffError};
+use crate::ignore_rules::{self, IgnoreMatcher};
+use crate::retention::{artifact_dir, ArtifactClass};
+
+/// Where snapshot commits are kept, one ref per run
+const SNAPSHOT_REFS: &str = "refs/vibecode/snapshots";
+/// Largest project copied when it is not under git
+const MAX_BACKUP_FILES: usize = 20_000;
+const MAX_BACKUP_BYTES: u64 = 200 * 1024 * 1024;
+/// Identity of snapshot commits, so they work without `user.name` set
+const SNAPSHOT_IDENTITY: [(&str, &str); 4] = [
+    ("GIT_AUTHOR_NAME", "VibeCode"),
+    ("GIT_AUTHOR_EMAIL", "vibecode@localhost"),
+    ("GIT_COMMITTER_NAME", "VibeCode"),
+    ("GIT_COMMITTER_EMAIL", "vibecode@localhost"),
+];
+
+/// `workflowSnapshots` block of settings.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WorkflowSnapshotSettings {
+    /// Snapshot before runs that don't say otherwise
+    pub enabled: bool,
+}
+
+impl WorkflowSnapshotSettings {
+    /// Load the workflowSnapshots block from settings.json (defaults when absent or invalid)
+    pub fn load() -> Self {
+        crate::load_settings_file()
+            .ok()
+            .flatten()
+            .and_then(|settings| settings.get("workflowSnapshots").cloned())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Snapshot taken before a workflow run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Snapshot {
+    /// The repository at `root` had nothing uncommitted
+    Skipped { root: String },
+    /// Commit holding the work tree of the repository at `root`
+    Git { root: String, commit: String },
+    /// Copy of the files of `project` (not under git) in `dir`
+    Backup { project: String, dir: String, files: usize },
+}
+
+/// Result of `restore_snapshot`
+#[derive(Debug, Serialize, PartialEq)]
+pub struct RestoredSnapshot {
+    /// Files written back from the snapshot
+    pub restored: usize,
+    /// Files deleted because the snapshot did not have them
+    pub removed: usize,
+}
+
+/// `run_id` as a ref or folder name
+fn safe_name(run_id: &str) -> String {
+    run_id.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
+}
+
+fn run_git(root: &Path, env: &[(&str, &OsStr)], args: &[&str]) -> Result<Output, String> {
+    let output = git_diff::git_with_env(root, env, args).map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(git_diff::git_failure(&output).to_string());
+    }
+    Ok(output)
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Paths of `git ls-files -z` output
+fn split_paths(output: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stdout).split('\0').filter(|p| !p.is_empty()).map(str::to_string).collect()
+}
+
+/// Run `work` with `GIT_INDEX_FILE` on a throwaway index (a copy of the
+/// repository's own with `copy`, so file stats need not be read again)
+fn with_temp_index<T>(root: &Path, copy: bool, work: impl FnOnce(&[(&str, &OsStr)]) -> Result<T, String>) -> Result<T, String> {
+    let index = std::env::temp_dir().join(format!("vibecode-snapshot-index-{}", uuid::Uuid::new_v4()));
+    if copy {
+        let own = root.join(stdout(&run_git(root, &[], &["rev-parse", "--git-path", "index"])?));
+        if own.exists() {
+            std::fs::copy(&own, &index).map_err(|e| format!("Failed to copy the git index: {}", e))?;
+        }
+    }
+    let result = work(&[("GIT_INDEX_FILE", index.as_os_str())]);
+    let _ = std::fs::remove_file(&index);
+    result
+}
+
+fn take_git(root: &Path, run_id: &str, workflow: &str) -> Result<Snapshot, String> {
+    let status = run_git(root, &[], &["status", "--porcelain", "-z", "--untracked-files=all"])?;
+    if status.stdout.is_empty() {
+        return Ok(Snapshot::Skipped { root: root.to_string_lossy().to_string() });
+    }
+    let commit = with_temp_index(root, true, |index| {
+        run_git(root, index, &["add", "-A", "--", ":/"])?;
+        let tree = stdout(&run_git(root, index, &["write-tree"])?);
+        let head = git_diff::git(root, &["rev-parse", "--verify", "--quiet", "HEAD"]).map_err(|e| e.to_string())?;
+        let head = head.status.success().then(|| stdout(&head));
+        let message = format!("Snapshot before workflow '{}' (run {})", workflow, run_id);
+        let mut args = vec!["commit-tree", tree.as_str(), "-m", message.as_str()];
+        if let Some(head) = &head {
+            args.extend(["-p", head.as_str()]);
+        }
+        let identity: Vec<(&str, &OsStr)> = SNAPSHOT_IDENTITY.iter().map(|(k, v)| (*k, OsStr::new(*v))).collect();
+        Ok(stdout(&run_git(root, &identity, &args)?))
+    })?;
+    let reference = format!("{}/{}", SNAPSHOT_REFS, safe_name(run_id));
+    run_git(root, &[], &["update-ref", &reference, &commit])?;
+    Ok(Snapshot::Git { root: root.to_string_lossy().to_string(), commit })
+}
+
+fn restore_git(root: &Path, commit: &str) -> Result<RestoredSnapshot, String> {
+    if !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid snapshot commit '{}'", commit));
+    }
+    with_temp_index(root, false, |index| {
+        run_git(root, index, &["read-tree", commit])?;
+        let saved: HashSet<String> = split_paths(&run_git(root, index, &["ls-files", "-z"])?).into_iter().collect();
+        let current = split_paths(&run_git(root, &[], &["ls-files", "-z", "--cached", "--others", "--exclude-standard"])?);
+        let removed = current
+            .iter()
+            .filter(|path| !saved.contains(*path))
+            .filter(|path| std::fs::remove_file(root.join(path)).is_ok())
+            .count();
+        run_git(root, index, &["checkout-index", "--all", "--force"])?;
+        Ok(RestoredSnapshot { restored: saved.len(), removed })
+    })
+}
+
+/// Files under `root` that are not ignored (no symlinks, no `.git`), with
+/// their sizes, relative to `root`
+pub(crate) fn project_files(root: &Path, matcher: &IgnoreMatcher) -> Vec<(PathBuf, u64)> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(meta) = std::fs::symlink_metadata(&path) else { continue };
+            if meta.is_dir() {
+                if entry.file_name() != ".git" && !matcher.is_ignored(&path, true) {
+                    dirs.push(path);
+                }
+            } else if meta.is_file() && !matcher.is_ignored(&path, false) {
+                if let Ok(relative) = path.strip_prefix(root) {
+                    files.push((relative.to_path_buf(), meta.len()));
+                }
+            }
+        }
+    }
+    files
+}
+
+fn copy_file(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::copy(from, to).map(|_| ()).map_err(|e| format!("Failed to copy {}: {}", from.display(), e))
+}
+
+fn take_backup(project: &Path, dir: &Path) -> Result<Snapshot, String> {
+    let files = project_files(project, &ignore_rules::current_matcher(project));
+    let bytes: u64 = files.iter().map(|(_, size)| size).sum();
+    if files.len() > MAX_BACKUP_FILES || bytes > MAX_BACKUP_BYTES {
+        return Err(format!(
+            "{} has {} files ({} MB), more than a backup copies ({} files, {} MB)",
+            project.display(),
+            files.len(),
+            bytes / (1024 * 1024),
+            MAX_BACKUP_FILES,
+            MAX_BACKUP_BYTES / (1024 * 1024)
+        ));
+    }
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    for (relative, _) in &files {
+        copy_file(&project.join(relative), &dir.join(relative))?;
+    }
+    Ok(Snapshot::Backup {
+        project: project.to_string_lossy().to_string(),
+        dir: dir.to_string_lossy().to_string(),
+        files: files.len(),
+    })
+}
+
+fn restore_backup(project: &Path, dir: &Path) -> Result<RestoredSnapshot, String> {
+    if !dir.is_dir() {
+        return Err(format!("The backup in {} no longer exists", dir.display()));
+    }
+    let saved: Vec<PathBuf> = project_files(dir, &ignore_rules::nothing_ignored(dir)).into_iter().map(|(path, _)| path).collect();
+    let kept: HashSet<&PathBuf> = saved.iter().collect();
+    let removed = project_files(project, &ignore_rules::current_matcher(project))
+        .into_iter()
+        .filter(|(path, _)| !kept.contains(path))
+        .filter(|(path, _)| std::fs::remove_file(project.join(path)).is_ok())
+        .count();
+    for relative in &saved {
+        copy_file(&dir.join(relative), &project.join(relative))?;
+    }
+    Ok(RestoredSnapshot { restored: saved.len(), removed })
+}
+
+/// Snapshot `project` before run `run_id` of `workflow` (see the module docs)
+pub fn take(project: &Path, run_id: &str, workflow: &str) -> Result<Snapshot, String> {
+    match git_diff::repository_root(project) {
+        Ok(root) => take_git(&root, run_id, workflow),
+        Err(GitDiffError::NotARepository(_)) | Err(GitDiffError::GitNotFound) => {
+            take_backup(project, &artifact_dir(ArtifactClass::Snapshots).join(safe_name(run_id)))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn restore(snapshot: &Snapshot) -> Result<RestoredSnapshot, String> {
+    match snapshot {
+        Snapshot::Skipped { .. } => Err("Nothing was uncommitted when the run started, so there is no snapshot to restore".to_string()),
+        Snapshot::Git { root, commit } => restore_git(Path::new(root), commit),
+        Snapshot::Backup { project, dir, .. } => restore_backup(Path::new(project), Path::new(dir)),
+    }
+}
+
+/// Put the work tree back as it was before workflow run `run_id`
+#[tauri::command]
+pub async fn restore_snapshot(app: tauri::AppHandle, run_id: String) -> Result<RestoredSnapshot, String> {
+    crate::command_metrics::timed("restore_snapshot", async move {
+        let record = crate::workflow_history::find(&run_id).ok_or_else(|| format!("No recorded workflow run '{}'", run_id))?;
+        let snapshot = record.snapshot.ok_or_else(|| format!("Workflow run '{}' has no snapshot", run_id))?;
+        let result = tauri::async_runtime::spawn_blocking(move || restore(&snapshot))
+            .await
+            .map_err(|e| format!("Restore failed: {}", e))
+            .and_then(|restored| restored);
+        crate::audit::record(
+            "restore_snapshot",
+            serde_json::json!({ "run_id": run_id }),
+            crate::audit::ORIGIN_COMMAND,
+            &result,
+        );
+        crate::git_status::refresh_after_run(&app);
+        result
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vibecode-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_git_snapshot_restores_work_tree_and_leaves_index() {
+        if git_diff::git(&std::env::temp_dir(), &["--version"]).is_err() {
+            return;
+        }
+        let repo = temp_dir("workflow-snapshot");
+        let git = |args: &[&str]| run_git(&repo, &[], args).unwrap();
+        git(&["init", "--quiet"]);
+        std::fs::write(repo.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(repo.join("a.txt"), "one\n").unwrap();
+        git(&["add", "."]);
+        git(&["-c", "user.name=Ada", "-c", "user.email=ada@example.com", "commit", "--quiet", "-m", "init"]);
+        let snapshot = take(&repo, "run/1", "deploy").unwrap();
+        assert!(matches!(snapshot, Snapshot::Skipped { .. }));
+        assert!(restore(&snapshot).is_err());
+
+        std::fs::write(repo.join("a.txt"), "two\n").unwrap();
+        std::fs::write(repo.join("notes.md"), "draft\n").unwrap();
+        git(&["add", "notes.md"]);
+        std::fs::write(repo.join("todo.txt"), "untracked\n").unwrap();
+        let staged = stdout(&git(&["diff", "--cached", "--name-only"]));
+        let snapshot = take(&repo, "run/1", "deploy").unwrap();
+        let Snapshot::Git { commit, .. } = &snapshot else { panic!("expected a git snapshot: {:?}", snapshot) };
+        assert_eq!(stdout(&git(&["rev-parse", "refs/vibecode/snapshots/run_1"])), *commit);
+        assert_eq!(stdout(&git(&["diff", "--cached", "--name-only"])), staged);
+        assert_eq!(stdout(&git(&["stash", "list"])), "");
+
+        // What a workflow might do
+        std::fs::write(repo.join("a.txt"), "three\n").unwrap();
+        std::fs::remove_file(repo.join("todo.txt")).unwrap();
+        std::fs::write(repo.join("generated.rs"), "fn x() {}\n").unwrap();
+        std::fs::write(repo.join("build.log"), "ignored\n").unwrap();
+
+        assert_eq!(restore(&snapshot).unwrap(), RestoredSnapshot { restored: 4, removed: 1 });
+        assert_eq!(std::fs::read_to_string(repo.join("a.txt")).unwrap(), "two\n");
+        assert_eq!(std::fs::read_to_string(repo.join("todo.txt")).unwrap(), "untracked\n");
+        assert!(!repo.join("generated.rs").exists());
+        assert!(repo.join("build.log").exists());
+
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn test_backup_of_a_project_outside_git() {
+        let project = temp_dir("workflow-snapshot");
+        let backup = temp_dir("workflow-snapshot-backup").join("run");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(project.join("src").join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(project.join("README.md"), "hello\n").unwrap();
+
+        let snapshot = take_backup(&project, &backup).unwrap();
+        assert!(matches!(snapshot, Snapshot::Backup { files: 2, .. }));
+
+        std::fs::write(project.join("src").join("main.rs"), "fn main() { changed() }\n").unwrap();
+        std::fs::remove_file(project.join("README.md")).unwrap();
+        std::fs::write(project.join("src").join("new.rs"), "\n").unwrap();
+
+        assert_eq!(restore(&snapshot).unwrap(), RestoredSnapshot { restored: 2, removed: 1 });
+        assert_eq!(std::fs::read_to_string(project.join("src").join("main.rs")).unwrap(), "fn main() {}\n");
+        assert_eq!(std::fs::read_to_string(project.join("README.md")).unwrap(), "hello\n");
+        assert!(!project.join("src").join("new.rs").exists());
+
+        std::fs::remove_dir_all(&project).unwrap();
+        std::fs::remove_dir_all(backup.parent().unwrap()).unwrap();
+        assert!(restore(&snapshot).is_err());
+    }
+}
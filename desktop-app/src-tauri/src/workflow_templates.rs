@@ -0,0 +1,230 @@
+/// Workflow Templates - Starting points for `create_workflow`
+///
+/// Five templates are compiled into the app: CI fix loop, feature
+/// implementation, code review, release notes and data pipeline. More can be
+/// dropped into `<config>/workflow-templates/` as YAML files; one with the
+/// file name of a built-in template replaces it. A template is an ordinary
+/// workflow: its `name:` and `description:` describe it in
+/// `list_workflow_templates`, and its `variables:` block lists what
+/// `create_workflow` may fill in. Rendering sets the new workflow's `name:`
+/// and the defaults of the given variables in place, so the template's
+/// comments and layout carry over to the new file.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+use crate::workflow_variables::{self, WorkflowVariable};
+use crate::workflow_yaml;
+
+const TEMPLATES_DIR: &str = "workflow-templates";
+
+pub const SOURCE_BUILTIN: &str = "builtin";
+pub const SOURCE_CUSTOM: &str = "custom";
+
+const BUILTIN: [(&str, &str); 5] = [
+    ("ci-fix-loop", include_str!("../workflow-templates/ci-fix-loop.yaml")),
+    ("feature", include_str!("../workflow-templates/feature.yaml")),
+    ("code-review", include_str!("../workflow-templates/code-review.yaml")),
+    ("release-notes", include_str!("../workflow-templates/release-notes.yaml")),
+    ("data-pipeline", include_str!("../workflow-templates/data-pipeline.yaml")),
+];
+
+/// Entry of `list_workflow_templates`
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowTemplateInfo {
+    /// What `create_workflow` takes as `template_id`
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Variables `create_workflow` may set, with the template's defaults
+    pub variables: Vec<WorkflowVariable>,
+    /// "builtin" | "custom"
+    pub source: String,
+    /// File of a custom template
+    pub file_path: Option<String>,
+}
+
+/// A template's YAML and where it came from
+struct Template {
+    id: String,
+    yaml: String,
+    path: Option<PathBuf>,
+}
+
+fn custom_dir() -> PathBuf {
+    crate::get_app_config_dir().join(TEMPLATES_DIR)
+}
+
+/// Custom templates in `dir`, by file name
+fn custom_in(dir: &Path) -> Vec<Template> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "yaml" || ext == "yml"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let yaml = std::fs::read_to_string(&path)
+                .map_err(|e| eprintln!("Workflow templates: skipping {}: {}", path.display(), e))
+                .ok()?;
+            let id = path.file_stem()?.to_string_lossy().to_string();
+            Some(Template { id, yaml, path: Some(path) })
+        })
+        .collect()
+}
+
+/// Built-in templates, each replaced by a custom one of the same id, then the other custom ones
+fn all(dir: &Path) -> Vec<Template> {
+    let mut templates: Vec<Template> = BUILTIN
+        .iter()
+        .map(|(id, yaml)| Template { id: id.to_string(), yaml: yaml.to_string(), path: None })
+        .collect();
+    for custom in custom_in(dir) {
+        match templates.iter_mut().find(|t| t.id == custom.id) {
+            Some(builtin) => *builtin = custom,
+            None => templates.push(custom),
+        }
+    }
+    templates
+}
+
+fn info(template: &Template) -> Result<WorkflowTemplateInfo, String> {
+    let root = workflow_yaml::parse(&template.yaml).map_err(|e| e.to_string())?;
+    let text = |key: &str| root.get(key).and_then(|node| node.as_str()).unwrap_or_default().to_string();
+    Ok(WorkflowTemplateInfo {
+        id: template.id.clone(),
+        name: text("name"),
+        description: text("description"),
+        variables: workflow_variables::declared(&template.yaml)?,
+        source: if template.path.is_some() { SOURCE_CUSTOM } else { SOURCE_BUILTIN }.to_string(),
+        file_path: template.path.as_ref().map(|p| p.to_string_lossy().to_string()),
+    })
+}
+
+fn list_in(dir: &Path) -> Vec<WorkflowTemplateInfo> {
+    all(dir)
+        .iter()
+        .filter_map(|template| {
+            info(template)
+                .map_err(|e| eprintln!("Workflow templates: skipping '{}': {}", template.id, e))
+                .ok()
+        })
+        .collect()
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// `yaml` with the default of `variable` set to `value`, touching only its lines
+fn set_default(yaml: &str, variable: &WorkflowVariable, value: &str) -> String {
+    let lines: Vec<&str> = yaml.lines().collect();
+    let start = variable.line - 1;
+    let indent = indent_of(lines[start]);
+    // A block value continues on more indented lines
+    let mut end = start + 1;
+    while end < lines.len() && (lines[end].trim().is_empty() || indent_of(lines[end]) > indent) {
+        end += 1;
+    }
+    while end > start + 1 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+
+    let mut out: Vec<String> = lines[..start].iter().map(|l| l.to_string()).collect();
+    out.push(format!("{}{}: {}", " ".repeat(indent), variable.name, crate::workflow_files::yaml_scalar(value)));
+    out.extend(lines[end..].iter().map(|l| l.to_string()));
+    let mut text = out.join("\n");
+    if yaml.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+/// Workflow `name` made from `template`, with `variables` as the new defaults
+fn render(template: &str, name: &str, variables: &HashMap<String, String>) -> Result<String, String> {
+    let declared = workflow_variables::declared(template)?;
+    workflow_variables::check_overrides(&declared, variables)?;
+
+    // Bottom-up, so the lines of the variables still to set do not move
+    let mut yaml = template.to_string();
+    for variable in declared.iter().rev() {
+        if let Some(value) = variables.get(&variable.name) {
+            yaml = set_default(&yaml, variable, value);
+        }
+    }
+    Ok(crate::workflow_files::set_top_level(&yaml, "name", name.trim()))
+}
+
+/// Content of a new workflow `name` from template `id`
+pub fn create_content(id: &str, name: &str, variables: &HashMap<String, String>) -> Result<String, String> {
+    let templates = all(&custom_dir());
+    let template = templates.iter().find(|t| t.id == id).ok_or_else(|| {
+        let ids: Vec<&str> = templates.iter().map(|t| t.id.as_str()).collect();
+        format!("Unknown workflow template '{}'. Available: {}", id, ids.join(", "))
+    })?;
+    render(&template.yaml, name, variables)
+}
+
+/// Built-in and custom workflow templates
+#[tauri::command]
+pub async fn list_workflow_templates() -> Result<Vec<WorkflowTemplateInfo>, String> {
+    crate::command_metrics::timed("list_workflow_templates", async move {
+        Ok(list_in(&custom_dir()))
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_templates_are_valid_workflows() {
+        for (id, yaml) in BUILTIN {
+            let (errors, warnings) = crate::workflow_validation::validate_yaml(yaml);
+            assert!(errors.is_empty() && warnings.is_empty(), "{}: {:?} {:?}", id, errors, warnings);
+        }
+    }
+
+    #[test]
+    fn test_custom_templates_and_rendering() {
+        let dir = std::env::temp_dir().join(format!("vibecode-workflow-templates-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("code-review.yaml"), "name: Team Review\ndescription: Ours\nsteps: []\n").unwrap();
+        std::fs::write(dir.join("nightly.yml"), "name: Nightly\nvariables:\n  branch: main\nsteps: []\n").unwrap();
+        std::fs::write(dir.join("broken.yaml"), "name: x\n\tsteps:\n").unwrap();
+
+        let listed = list_in(&dir);
+        let ids: Vec<(&str, &str)> = listed.iter().map(|t| (t.id.as_str(), t.source.as_str())).collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("ci-fix-loop", SOURCE_BUILTIN),
+                ("feature", SOURCE_BUILTIN),
+                ("code-review", SOURCE_CUSTOM),
+                ("release-notes", SOURCE_BUILTIN),
+                ("data-pipeline", SOURCE_BUILTIN),
+                ("nightly", SOURCE_CUSTOM),
+            ]
+        );
+        assert_eq!(listed[2].name, "Team Review");
+        assert_eq!(listed[5].variables[0].default.as_deref(), Some("main"));
+
+        let template = "# Header\nname: Template\nvariables:\n  notes: |\n    line one\n    line two\n  target: src\nsteps: []\n";
+        let variables = HashMap::from([
+            ("target".to_string(), "lib: core".to_string()),
+            ("notes".to_string(), "short".to_string()),
+        ]);
+        assert_eq!(
+            render(template, " My Flow ", &variables).unwrap(),
+            "# Header\nname: My Flow\nvariables:\n  notes: short\n  target: \"lib: core\"\nsteps: []\n"
+        );
+        let unknown = HashMap::from([("targt".to_string(), "x".to_string())]);
+        assert!(render(template, "x", &unknown).unwrap_err().contains("Unknown workflow variable(s): targt"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
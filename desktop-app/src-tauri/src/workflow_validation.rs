@@ -0,0 +1,436 @@
+/// Workflow Validation - Pre-flight checks of workflow YAML
+///
+/// `validate_workflow` reads a workflow with `workflow_yaml` and reports what
+/// vibe.py would trip over, each problem with its line: missing `name` or
+/// `steps`, steps without a name, agent or prompt, unknown agents,
+/// `depends_on` naming no step, steps depending on each other in a cycle,
+/// `when:` expressions that do not parse or refer to nothing known, `${var}`
+/// not declared under `variables` and `${outputs.x}` not saved by an earlier
+/// step. Unknown step fields and a
+/// missing description are only warnings. `run_workflow` runs the same check
+/// first and refuses an invalid workflow unless called with `force`.
+
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+use crate::workflow_yaml::{self, Node, Value, YamlDiagnostic};
+
+/// Agents a step may name
+pub const KNOWN_AGENTS: &[&str] = &["auto", "api", "cli", "antigravity"];
+
+/// Step fields vibe.py reads
+const STEP_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "description",
+    "agent",
+    "prompt",
+    "depends_on",
+    "condition",
+    "timeout",
+    "retry",
+    "save_output",
+    "continue_on_error",
+    "when",
+];
+
+/// Problem found in a workflow, at a 1-based line
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Result of `validate_workflow`
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowValidation {
+    pub is_valid: bool,
+    /// File that was checked
+    pub path: String,
+    pub errors: Vec<Diagnostic>,
+    pub warnings: Vec<Diagnostic>,
+    /// Line, column and surrounding lines of a YAML syntax error
+    pub yaml_error: Option<YamlDiagnostic>,
+}
+
+impl WorkflowValidation {
+    /// Errors as "line N: message" lines
+    pub fn summary(&self) -> String {
+        self.errors
+            .iter()
+            .map(|d| format!("line {}: {}", d.line, d.message))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[derive(Default)]
+struct Report {
+    errors: Vec<Diagnostic>,
+    warnings: Vec<Diagnostic>,
+}
+
+impl Report {
+    fn error(&mut self, line: usize, message: String) {
+        self.errors.push(Diagnostic { line, message });
+    }
+
+    fn warn(&mut self, line: usize, message: String) {
+        self.warnings.push(Diagnostic { line, message });
+    }
+}
+
+/// Line of `offset` inside a string value starting at `node`
+///
+/// Multi-line values are block scalars, whose text starts on the next line.
+fn line_of(node: &Node, text: &str, offset: usize) -> usize {
+    if text.contains('\n') {
+        node.line + 1 + text[..offset].matches('\n').count()
+    } else {
+        node.line
+    }
+}
+
+/// `${...}` references in `text`, with their byte offset
+fn references(text: &str) -> Vec<(usize, &str)> {
+    let mut refs = Vec::new();
+    let mut from = 0;
+    while let Some(start) = text[from..].find("${").map(|i| from + i) {
+        let Some(end) = text[start..].find('}').map(|i| start + i) else { break };
+        refs.push((start, text[start + 2..end].trim()));
+        from = end + 1;
+    }
+    refs
+}
+
+fn scalar_list(node: &Node) -> Vec<&Node> {
+    match &node.value {
+        Value::Seq(items) => items.iter().collect(),
+        Value::Scalar(_) => vec![node],
+        _ => Vec::new(),
+    }
+}
+
+/// Check what a `when:` reference names: a variable, an output saved by an
+/// earlier step or `steps.<id>.status` / `steps.<id>.success`
+fn check_when_reference(reference: &str, ids: &[&str], variables: &[String], saved: &[String]) -> Option<String> {
+    if let Some(output) = reference.strip_prefix("outputs.") {
+        return (!saved.iter().any(|s| s == output))
+            .then(|| format!("`{{{{{}}}}}` is not saved by an earlier step (save_output)", reference));
+    }
+    if let Some(rest) = reference.strip_prefix("steps.") {
+        return match rest.rsplit_once('.') {
+            Some((id, "status" | "success")) if ids.contains(&id) => None,
+            Some((id, "status" | "success")) => Some(format!("`{{{{{}}}}}` names unknown step '{}'", reference, id)),
+            _ => Some(format!("`{{{{{}}}}}` must be `steps.<id>.status` or `steps.<id>.success`", reference)),
+        };
+    }
+    (!variables.iter().any(|v| v == reference))
+        .then(|| format!("`{{{{{}}}}}` is not declared under `variables`", reference))
+}
+
+fn check_step(step: &Node, index: usize, ids: &[&str], variables: &[String], saved: &[String], report: &mut Report) {
+    if step.as_map().is_none() {
+        report.error(step.line, format!("Step {} must be a mapping of fields", index));
+        return;
+    }
+    let text = |key: &str| step.get(key).and_then(Node::as_str).map(str::trim).filter(|s| !s.is_empty());
+    let label = text("name").or_else(|| text("id")).map_or_else(|| format!("Step {}", index), |l| format!("Step '{}'", l));
+
+    if text("name").is_none() && text("id").is_none() {
+        report.error(step.line, format!("{} has no `name`", label));
+    }
+    match step.get("agent") {
+        None => report.error(step.line, format!("{} has no `agent` (one of {})", label, KNOWN_AGENTS.join(", "))),
+        Some(agent) => match agent.as_str() {
+            Some(a) if KNOWN_AGENTS.contains(&a) => {}
+            _ => report.error(
+                agent.line,
+                format!("{} uses unknown agent '{}'; expected one of {}", label, agent.as_str().unwrap_or("?"), KNOWN_AGENTS.join(", ")),
+            ),
+        },
+    }
+    match step.get("prompt") {
+        Some(prompt) if prompt.as_str().is_some_and(|p| !p.trim().is_empty()) => {
+            let body = prompt.as_str().unwrap_or_default();
+            for (offset, reference) in references(body) {
+                let line = line_of(prompt, body, offset);
+                match reference.strip_prefix("outputs.") {
+                    Some(output) if !saved.iter().any(|s| s == output) => report.error(
+                        line,
+                        format!("{}: `${{{}}}` is not saved by an earlier step (save_output)", label, reference),
+                    ),
+                    Some(_) => {}
+                    None if !variables.iter().any(|v| v == reference) => report.error(
+                        line,
+                        format!("{}: `${{{}}}` is not declared under `variables`", label, reference),
+                    ),
+                    None => {}
+                }
+            }
+        }
+        Some(prompt) => report.error(prompt.line, format!("{} has an empty `prompt`", label)),
+        None => report.error(step.line, format!("{} has no `prompt`", label)),
+    }
+    if let Some(when) = step.get("when") {
+        match when.as_str().map(crate::workflow_condition::parse) {
+            Some(Ok(condition)) => {
+                for reference in condition.references() {
+                    if let Some(problem) = check_when_reference(reference, ids, variables, saved) {
+                        report.error(when.line, format!("{}: `when` {}", label, problem));
+                    }
+                }
+            }
+            Some(Err(e)) => report.error(when.line, format!("{}: invalid `when` ({})", label, e)),
+            None => report.error(when.line, format!("{}: `when` must be a string expression", label)),
+        }
+    }
+    for key in ["timeout", "retry"] {
+        if let Some(node) = step.get(key) {
+            if !node.as_str().is_some_and(|v| v.parse::<u64>().is_ok()) {
+                report.error(node.line, format!("{}: `{}` must be a whole number", label, key));
+            }
+        }
+    }
+    for (key, node) in step.as_map().unwrap_or_default() {
+        if !STEP_FIELDS.contains(&key.as_str()) {
+            report.warn(node.line, format!("{}: unknown field `{}` is ignored", label, key));
+        }
+    }
+}
+
+/// Check a workflow's YAML
+pub fn validate_yaml(yaml: &str) -> (Vec<Diagnostic>, Vec<Diagnostic>) {
+    let mut report = Report::default();
+    let root = match workflow_yaml::parse(yaml) {
+        Ok(root) => root,
+        Err(e) => return (vec![Diagnostic { line: e.line, message: format!("{} (column {})", e.message, e.column) }], Vec::new()),
+    };
+    if root.as_map().is_none() {
+        report.error(root.line, "A workflow must be a mapping with `name` and `steps`".to_string());
+        return (report.errors, report.warnings);
+    }
+
+    match root.get("name") {
+        Some(name) if name.as_str().is_some_and(|n| !n.trim().is_empty()) => {}
+        Some(name) => report.error(name.line, "`name` must be a non-empty string".to_string()),
+        None => report.error(1, "Missing required field `name`".to_string()),
+    }
+    if root.get("description").is_none() {
+        report.warn(1, "No `description`; the workflow list will show none".to_string());
+    }
+
+    let variables: Vec<String> = match root.get("variables") {
+        Some(node) if node.is_null() => Vec::new(),
+        Some(node) => match node.as_map() {
+            Some(entries) => entries.iter().map(|(key, _)| key.clone()).collect(),
+            None => {
+                report.error(node.line, "`variables` must map names to default values".to_string());
+                Vec::new()
+            }
+        },
+        None => Vec::new(),
+    };
+
+    let steps = match root.get("steps") {
+        None => {
+            report.error(1, "Missing required field `steps`".to_string());
+            return (report.errors, report.warnings);
+        }
+        Some(node) => match node.as_seq() {
+            Some(steps) if !steps.is_empty() => steps,
+            Some(_) => {
+                report.error(node.line, "`steps` is empty".to_string());
+                return (report.errors, report.warnings);
+            }
+            None if node.is_null() => {
+                report.error(node.line, "`steps` is empty".to_string());
+                return (report.errors, report.warnings);
+            }
+            None => {
+                report.error(node.line, "`steps` must be a list".to_string());
+                return (report.errors, report.warnings);
+            }
+        },
+    };
+
+    let ids: Vec<&str> = steps.iter().filter_map(|s| s.get("id")?.as_str()).collect();
+    let mut seen: Vec<&str> = Vec::new();
+    let mut saved: Vec<String> = Vec::new();
+    for (i, step) in steps.iter().enumerate() {
+        check_step(step, i + 1, &ids, &variables, &saved, &mut report);
+
+        if let Some(id) = step.get("id").and_then(Node::as_str) {
+            if seen.contains(&id) {
+                report.error(step.line, format!("Duplicate step id '{}'", id));
+            }
+            seen.push(id);
+        }
+        for dependency in step.get("depends_on").map(scalar_list).unwrap_or_default() {
+            let name = dependency.as_str().unwrap_or_default();
+            if !ids.contains(&name) {
+                report.error(dependency.line, format!("`depends_on` names unknown step '{}'", name));
+            }
+        }
+        if let Some(output) = step.get("save_output").and_then(Node::as_str) {
+            saved.push(output.to_string());
+        }
+    }
+
+    let step_ids: Vec<Option<String>> = steps.iter().map(|s| s.get("id").and_then(Node::as_str).map(str::to_string)).collect();
+    let depends_on: Vec<Vec<String>> = steps
+        .iter()
+        .map(|s| s.get("depends_on").map(scalar_list).unwrap_or_default())
+        .map(|list| list.iter().filter_map(|n| n.as_str().map(str::to_string)).collect())
+        .collect();
+    if let Err(cycle) = crate::workflow_run::run_order(&step_ids, &depends_on) {
+        let line = steps
+            .iter()
+            .find(|s| s.get("id").and_then(Node::as_str) == cycle.first().map(String::as_str))
+            .map_or(1, |s| s.line);
+        report.error(line, crate::workflow_run::cycle_error(&cycle));
+    }
+    (report.errors, report.warnings)
+}
+
+/// File of a workflow given by name (the project's, else the global one) or path
+pub fn resolve(name_or_path: &str) -> Result<PathBuf, String> {
+    let path = Path::new(name_or_path);
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+    crate::workflow_scope::resolve(name_or_path, None)
+}
+
+/// Check the workflow file at `path`
+pub fn validate_file(path: &Path) -> Result<WorkflowValidation, String> {
+    let yaml = std::fs::read_to_string(path).map_err(|e| format!("Failed to read workflow: {}", e))?;
+    let (errors, warnings) = validate_yaml(&yaml);
+    Ok(WorkflowValidation {
+        is_valid: errors.is_empty(),
+        path: path.to_string_lossy().to_string(),
+        errors,
+        warnings,
+        yaml_error: workflow_yaml::parse(&yaml).err().map(|e| e.diagnostic(&yaml)),
+    })
+}
+
+/// Check a workflow (by name or file path) before running it
+#[tauri::command]
+pub async fn validate_workflow(name_or_path: String) -> Result<WorkflowValidation, String> {
+    crate::command_metrics::timed("validate_workflow", async move {
+        validate_file(&resolve(&name_or_path)?)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics_point_at_lines() {
+        let yaml = "\
+name: Demo
+variables:
+  target: src
+steps:
+  - id: plan
+    agent: api
+    prompt: |
+      Plan ${target}
+      using ${outputs.review}
+    save_output: plan
+  - id: build
+    agent: robot
+    depends_on: [plan, deploy]
+    prompt: Build ${outputs.plan} in ${missing}
+    retries: 2
+  - id: plan
+    agent: cli
+";
+        let (errors, warnings) = validate_yaml(yaml);
+        let lines: Vec<(usize, &str)> = errors.iter().map(|d| (d.line, d.message.as_str())).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (9, "Step 'plan': `${outputs.review}` is not saved by an earlier step (save_output)"),
+                (12, "Step 'build' uses unknown agent 'robot'; expected one of auto, api, cli, antigravity"),
+                (14, "Step 'build': `${missing}` is not declared under `variables`"),
+                (13, "`depends_on` names unknown step 'deploy'"),
+                (16, "Step 'plan' has no `prompt`"),
+                (16, "Duplicate step id 'plan'"),
+            ]
+        );
+        let warned: Vec<usize> = warnings.iter().map(|d| d.line).collect();
+        assert_eq!(warned, vec![1, 15]);
+
+        let (errors, _) = validate_yaml("description: nothing\n");
+        assert_eq!(errors.iter().map(|d| d.message.as_str()).collect::<Vec<_>>(), vec![
+            "Missing required field `name`",
+            "Missing required field `steps`",
+        ]);
+        assert_eq!(validate_yaml("name: x\n\tsteps:\n").0[0].line, 2);
+    }
+
+    #[test]
+    fn test_bad_types_corpus() {
+        let step = "  - name: a\n    agent: api\n    prompt: p\n";
+        // (source, line, first error)
+        let corpus = [
+            ("- a\n- b\n".to_string(), 1, "A workflow must be a mapping with `name` and `steps`"),
+            ("name: [a]\ndescription: d\nsteps: x\n".to_string(), 1, "`name` must be a non-empty string"),
+            ("name: a\ndescription: d\nsteps: x\n".to_string(), 3, "`steps` must be a list"),
+            ("name: a\ndescription: d\nsteps: {a: b}\n".to_string(), 3, "`steps` must be a list"),
+            (format!("name: a\ndescription: d\nvariables: [a]\nsteps:\n{}", step), 3, "`variables` must map names to default values"),
+            ("name: a\ndescription: d\nsteps:\n  - just text\n".to_string(), 4, "Step 1 must be a mapping of fields"),
+            (format!("name: a\ndescription: d\nsteps:\n{}    timeout: soon\n", step), 7, "Step 'a': `timeout` must be a whole number"),
+            (format!("name: a\ndescription: d\nsteps:\n{}    retry: -1\n", step), 7, "Step 'a': `retry` must be a whole number"),
+            (format!("name: a\ndescription: d\nsteps:\n{}    when: [a]\n", step), 7, "Step 'a': `when` must be a string expression"),
+            ("name: a\ndescription: d\nsteps:\n  - name: a\n    agent: gpt\n    prompt: p\n".to_string(), 5, "Step 'a' uses unknown agent 'gpt'; expected one of auto, api, cli, antigravity"),
+        ];
+        for (source, line, message) in &corpus {
+            let (errors, _) = validate_yaml(source);
+            let first = errors.first().map(|d| (d.line, d.message.as_str()));
+            assert_eq!(first, Some((*line, *message)), "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn test_when_and_dependency_cycles() {
+        let yaml = "\
+name: Demo
+description: Conditions
+variables:
+  env: staging
+steps:
+  - id: build
+    agent: cli
+    prompt: Build
+    depends_on: deploy
+  - id: deploy
+    agent: cli
+    prompt: Deploy
+    depends_on: build
+    when: \"{{env}} == 'production' \"
+  - id: notify
+    agent: api
+    prompt: Notify
+    when: \"{{steps.ship.success}}\"
+  - id: report
+    agent: api
+    prompt: Report
+    when: \"{{region}} ==\"
+";
+        let (errors, warnings) = validate_yaml(yaml);
+        let lines: Vec<(usize, &str)> = errors.iter().map(|d| (d.line, d.message.as_str())).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (18, "Step 'notify': `when` `{{steps.ship.success}}` names unknown step 'ship'"),
+                (22, "Step 'report': invalid `when` (expected `<value>`, or `<value>` followed by `==`, `!=`, `contains` or `not contains` and another value)"),
+                (6, "Steps depend on each other in a cycle: build -> deploy -> build"),
+            ]
+        );
+        assert!(warnings.is_empty());
+    }
+}
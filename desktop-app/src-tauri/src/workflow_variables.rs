@@ -0,0 +1,106 @@
+/// Workflow Variables - Declared variables and run-time overrides
+///
+/// A workflow's `variables:` block names the `${var}` values its prompts use,
+/// each with a default. `get_workflow_variables` lists them for the UI's
+/// input form; `run_workflow` checks the overrides it is given against them
+/// and passes each one to vibe.py as `--var key=value`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use serde::{Deserialize, Serialize};
+
+use crate::workflow_yaml;
+
+/// Entry of `get_workflow_variables`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkflowVariable {
+    pub name: String,
+    /// None when the default is empty or not a plain value
+    pub default: Option<String>,
+    pub line: usize,
+}
+
+/// Variables declared in a workflow's YAML, in file order
+pub fn declared(yaml: &str) -> Result<Vec<WorkflowVariable>, String> {
+    let root = workflow_yaml::parse(yaml).map_err(|e| e.to_string())?;
+    let Some(variables) = root.get("variables") else { return Ok(Vec::new()) };
+    Ok(variables
+        .as_map()
+        .unwrap_or_default()
+        .iter()
+        .map(|(name, value)| WorkflowVariable {
+            name: name.clone(),
+            default: value.as_str().map(str::to_string),
+            line: value.line,
+        })
+        .collect())
+}
+
+fn declared_in(path: &Path) -> Result<Vec<WorkflowVariable>, String> {
+    let yaml = std::fs::read_to_string(path).map_err(|e| format!("Failed to read workflow: {}", e))?;
+    declared(&yaml)
+}
+
+/// Reject override names the workflow does not declare
+pub fn check_overrides(declared: &[WorkflowVariable], overrides: &HashMap<String, String>) -> Result<(), String> {
+    let mut unknown: Vec<&str> = overrides
+        .keys()
+        .filter(|name| !declared.iter().any(|v| &v.name == *name))
+        .map(String::as_str)
+        .collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    unknown.sort();
+    let valid: Vec<&str> = declared.iter().map(|v| v.name.as_str()).collect();
+    Err(format!(
+        "Unknown workflow variable(s): {}. Valid names: {}",
+        unknown.join(", "),
+        if valid.is_empty() { "(none declared)".to_string() } else { valid.join(", ") }
+    ))
+}
+
+/// Add `overrides`, checked with `check_overrides`, to `cmd` as `--var key=value` in name order
+pub fn push_args(cmd: &mut Command, overrides: &HashMap<String, String>) {
+    let mut names: Vec<&String> = overrides.keys().collect();
+    names.sort();
+    for name in names {
+        cmd.arg("--var").arg(format!("{}={}", name, overrides[name]));
+    }
+}
+
+/// Variables a workflow declares, with their defaults
+#[tauri::command]
+pub async fn get_workflow_variables(name: String) -> Result<Vec<WorkflowVariable>, String> {
+    crate::command_metrics::timed("get_workflow_variables", async move {
+        declared_in(&crate::workflow_validation::resolve(&name)?)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declared_variables_and_unknown_overrides() {
+        let yaml = "name: Demo\nvariables:\n  target: src\n  scope: \"full review\"\n  notes:\nsteps: []\n";
+        let variables = declared(yaml).unwrap();
+        assert_eq!(
+            variables,
+            vec![
+                WorkflowVariable { name: "target".into(), default: Some("src".into()), line: 3 },
+                WorkflowVariable { name: "scope".into(), default: Some("full review".into()), line: 4 },
+                WorkflowVariable { name: "notes".into(), default: None, line: 5 },
+            ]
+        );
+
+        let overrides = |pairs: &[(&str, &str)]| pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        assert!(check_overrides(&variables, &overrides(&[("target", "lib")])).is_ok());
+        assert_eq!(
+            check_overrides(&variables, &overrides(&[("targt", "lib"), ("Scope", "x")])).unwrap_err(),
+            "Unknown workflow variable(s): Scope, targt. Valid names: target, scope, notes"
+        );
+        assert!(declared("name: x\nsteps: []\n").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+/// Workflow Watcher - Notifies the UI of workflow files edited outside the app
+///
+/// A `notify` watcher on `get_workflows_path()` collects file events until
+/// none has arrived for 500 ms, then lists the folder again and emits
+/// `workflows-changed` with the YAML files added, modified or removed since
+/// the last look. While the folder does not exist yet the nearest existing
+/// parent is watched instead, and the watcher moves onto the folder once it
+/// appears. `sync` recreates the watcher whenever `get_workflows_path()`
+/// points somewhere else than the folder being watched; it runs after every
+/// project switch, so workflows that follow the project stay watched.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::Emitter;
+
+pub const WORKFLOWS_CHANGED_EVENT: &str = "workflows-changed";
+
+/// Quiet period that ends a burst of events (editors save in several steps)
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+const EXTENSIONS: [&str; 2] = ["yaml", "yml"];
+
+/// Payload of `workflows-changed`: file names directly inside `folder`
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct WorkflowsChanged {
+    pub folder: String,
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl WorkflowsChanged {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// The watcher in use and what it watches
+struct Active {
+    folder: PathBuf,
+    /// The folder, or its nearest existing parent while it is missing
+    target: PathBuf,
+    _watcher: notify::RecommendedWatcher,
+}
+
+static APP: OnceLock<tauri::AppHandle> = OnceLock::new();
+static ACTIVE: Mutex<Option<Active>> = Mutex::new(None);
+
+/// Workflow files seen at the last look (None before the first one)
+static KNOWN: Mutex<Option<BTreeSet<String>>> = Mutex::new(None);
+
+fn is_workflow_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| EXTENSIONS.contains(&ext))
+}
+
+/// Names of the workflow files directly inside `folder` (none when it is missing)
+fn workflow_files(folder: &Path) -> BTreeSet<String> {
+    std::fs::read_dir(folder)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && is_workflow_file(path))
+                .filter_map(|path| path.file_name().map(|n| n.to_string_lossy().to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// What changed between two listings; `touched` are files events were seen for
+fn diff(folder: &Path, known: &BTreeSet<String>, now: &BTreeSet<String>, touched: &BTreeSet<String>) -> WorkflowsChanged {
+    WorkflowsChanged {
+        folder: folder.to_string_lossy().to_string(),
+        added: now.difference(known).cloned().collect(),
+        modified: now.intersection(known).filter(|name| touched.contains(*name)).cloned().collect(),
+        removed: known.difference(now).cloned().collect(),
+    }
+}
+
+/// The folder itself, or its nearest existing parent while it is missing
+fn watch_target(folder: &Path) -> Option<PathBuf> {
+    folder.ancestors().find(|path| path.is_dir()).map(Path::to_path_buf)
+}
+
+/// List `folder` again and emit what changed since the last look
+fn flush(folder: &Path, touched: &BTreeSet<String>) {
+    let now = workflow_files(folder);
+    let mut known = KNOWN.lock().unwrap_or_else(|e| e.into_inner());
+    let change = known.as_ref().map(|known| diff(folder, known, &now, touched));
+    *known = Some(now);
+    drop(known);
+
+    if let (Some(change), Some(app)) = (change.filter(|c| !c.is_empty()), APP.get()) {
+        let _ = app.emit(WORKFLOWS_CHANGED_EVENT, change);
+    }
+}
+
+/// Start watching `target` for changes to `folder`
+fn start(folder: &Path, target: &Path) -> notify::Result<notify::RecommendedWatcher> {
+    let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })?;
+    watcher.watch(target, RecursiveMode::NonRecursive)?;
+
+    // Ends when the watcher is dropped, which closes the channel
+    let folder = folder.to_path_buf();
+    std::thread::spawn(move || {
+        while let Ok(first) = rx.recv() {
+            let mut touched = BTreeSet::new();
+            let mut folder_touched = false;
+            let mut note = |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else { return };
+                for path in &event.paths {
+                    if path == &folder {
+                        folder_touched = true;
+                    } else if path.parent() == Some(folder.as_path()) && is_workflow_file(path) {
+                        touched.insert(path.file_name().unwrap_or_default().to_string_lossy().to_string());
+                    }
+                }
+            };
+            note(first);
+            while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+                note(event);
+            }
+
+            // The folder may have been created or removed: move the watcher
+            // first so files written right after are not missed
+            if folder_touched {
+                sync();
+            }
+            if folder_touched || !touched.is_empty() {
+                flush(&folder, &touched);
+            }
+        }
+    });
+    Ok(watcher)
+}
+
+/// Watch the current workflows folder, recreating the watcher when the
+/// folder (or, while it is missing, the parent watched in its place) changed
+pub fn sync() {
+    let folder = crate::get_workflows_path();
+    let target = watch_target(&folder);
+    let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+    if active.as_ref().is_some_and(|a| a.folder == folder && Some(&a.target) == target.as_ref()) {
+        return;
+    }
+    let moved = active.as_ref().is_some_and(|a| a.folder != folder);
+    *active = None;
+
+    match target {
+        Some(target) => match start(&folder, &target) {
+            Ok(watcher) => *active = Some(Active { folder: folder.clone(), target, _watcher: watcher }),
+            Err(e) => eprintln!("Workflow watcher: failed to watch {}: {}", target.display(), e),
+        },
+        None => eprintln!("Workflow watcher: no existing folder above {}", folder.display()),
+    }
+    drop(active);
+
+    // A different folder lists different workflows
+    if moved {
+        flush(&folder, &BTreeSet::new());
+    } else {
+        let mut known = KNOWN.lock().unwrap_or_else(|e| e.into_inner());
+        if known.is_none() {
+            *known = Some(workflow_files(&folder));
+        }
+    }
+}
+
+/// Start the workflows folder watcher (called once at startup)
+pub fn spawn(app: tauri::AppHandle) {
+    let _ = APP.set(app);
+    std::thread::spawn(sync);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> BTreeSet<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_diff_and_listing() {
+        let folder = std::env::temp_dir().join(format!("vibecode-workflow-watch-{}", uuid::Uuid::new_v4()));
+        assert!(workflow_files(&folder).is_empty());
+        assert_eq!(watch_target(&folder), Some(std::env::temp_dir()));
+
+        std::fs::create_dir_all(folder.join(".trash")).unwrap();
+        for file in ["deploy.yaml", "review.yml", "notes.md", "deploy.tmp", ".trash/old.yaml"] {
+            std::fs::write(folder.join(file), "name: x\n").unwrap();
+        }
+        assert_eq!(workflow_files(&folder), names(&["deploy.yaml", "review.yml"]));
+        assert_eq!(watch_target(&folder), Some(folder.clone()));
+
+        let change = diff(
+            &folder,
+            &names(&["deploy.yaml", "review.yml", "old.yaml"]),
+            &names(&["deploy.yaml", "review.yml", "new.yaml"]),
+            &names(&["deploy.yaml", "new.yaml"]),
+        );
+        assert_eq!(change.added, vec!["new.yaml"]);
+        assert_eq!(change.modified, vec!["deploy.yaml"]);
+        assert_eq!(change.removed, vec!["old.yaml"]);
+        assert!(diff(&folder, &names(&["a.yaml"]), &names(&["a.yaml"]), &BTreeSet::new()).is_empty());
+
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
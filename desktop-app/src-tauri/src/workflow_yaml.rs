@@ -0,0 +1,362 @@
+/// Workflow YAML - Workflow files read with serde_yaml, keeping line numbers
+///
+/// `parse` reads a workflow with serde_yaml into `Node`s that remember their
+/// line, so workflow checks can point at the line of each problem. serde_yaml
+/// keeps no positions, so afterwards each mapping key and block list item is
+/// matched to its source line in document order; values written inline
+/// (`[a, b]`, `{a: b}`) take the line of their key. Syntax errors carry
+/// serde_yaml's `Location`, and `YamlError::diagnostic` adds the lines around
+/// them for display.
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value as Yaml;
+
+/// Parsed value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Scalar(String),
+    Seq(Vec<Node>),
+    /// Entries in file order
+    Map(Vec<(String, Node)>),
+}
+
+/// A value and the 1-based line it starts on (the key's line for mapping values)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub line: usize,
+    pub value: Value,
+}
+
+impl Node {
+    /// Value of `key` when this is a mapping
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        self.as_map()?.iter().find(|(k, _)| k == key).map(|(_, node)| node)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            Value::Scalar(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_seq(&self) -> Option<&[Node]> {
+        match &self.value {
+            Value::Seq(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&[(String, Node)]> {
+        match &self.value {
+            Value::Map(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.value == Value::Null
+    }
+}
+
+/// Syntax error at a 1-based line and column (in characters)
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for YamlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid workflow YAML (line {}, column {}): {}", self.line, self.column, self.message)
+    }
+}
+
+/// Syntax error of a workflow file, for editors to underline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct YamlDiagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    /// The line before, the offending line and a `^` under the column, each
+    /// prefixed with its line number
+    pub snippet: String,
+}
+
+impl YamlError {
+    /// A serde_yaml error at its `Location`, without the position in the message
+    fn from_serde(e: &serde_yaml::Error, source: &str) -> Self {
+        let (mut line, mut column) = e.location().map_or((1, 1), |l| (l.line(), l.column()));
+        // The end of the input is reported on the line after the last one
+        let last = source.lines().count().max(1);
+        if line > last {
+            line = last;
+            column = source.lines().last().map_or(0, |l| l.chars().count()) + 1;
+        }
+
+        let mut message = e.to_string();
+        if let Some(at) = message.find(" at line ") {
+            message.truncate(at);
+        }
+        let mut chars = message.chars();
+        let message = chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default();
+        YamlError { line, column, message }
+    }
+
+    /// This error with the lines of `source` around it
+    pub fn diagnostic(&self, source: &str) -> YamlDiagnostic {
+        let lines: Vec<&str> = source.lines().collect();
+        let width = self.line.to_string().len();
+        let mut snippet: Vec<String> = (self.line.saturating_sub(1).max(1)..=self.line)
+            .filter_map(|no| Some(format!("{:>width$} | {}", no, lines.get(no - 1)?)))
+            .collect();
+        // Tabs before the column stay tabs so the caret lines up
+        let offending = lines.get(self.line - 1).copied().unwrap_or_default();
+        let pad: String = offending
+            .chars()
+            .chain(std::iter::repeat(' '))
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        snippet.push(format!("{:>width$} | {}^", "", pad));
+        YamlDiagnostic { line: self.line, column: self.column, message: self.message.clone(), snippet: snippet.join("\n") }
+    }
+}
+
+/// Plain text of a scalar (numbers and booleans as written in canonical form)
+fn scalar(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::Bool(b) => Some(b.to_string()),
+        Yaml::Number(n) => Some(n.to_string()),
+        Yaml::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+/// What follows `key:` at the start of `text` (the key may be quoted)
+fn after_key<'t>(text: &'t str, key: &str) -> Option<&'t str> {
+    let quoted = |q: char| text.strip_prefix(q)?.strip_prefix(key)?.strip_prefix(q);
+    let rest = text.strip_prefix(key).or_else(|| quoted('"')).or_else(|| quoted('\''))?;
+    let rest = rest.trim_start_matches(' ').strip_prefix(':')?;
+    (rest.is_empty() || rest.starts_with([' ', '\t'])).then_some(rest)
+}
+
+fn is_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Whether a value starting with `text` is written inline as a flow collection
+fn is_flow(text: &str) -> bool {
+    text.trim_start().starts_with(['[', '{'])
+}
+
+/// Where content can start on `line`: after the indentation and after each
+/// `- ` of items opened on it, as 0-based columns
+fn starts(line: &str) -> Vec<(usize, &str)> {
+    let mut text = line.trim_start_matches(' ');
+    let mut column = line.len() - text.len();
+    let mut starts = vec![(column, text)];
+    while is_item(text) {
+        let rest = text[1..].trim_start_matches(' ');
+        column += text.len() - rest.len();
+        text = rest;
+        starts.push((column, text));
+    }
+    starts
+}
+
+fn is_content(line: &str) -> bool {
+    let text = line.trim();
+    !text.is_empty() && !text.starts_with('#') && text != "---"
+}
+
+/// Source lines of keys and list items, found in document order
+struct Locator<'a> {
+    lines: Vec<&'a str>,
+    /// Index of the first line not claimed yet
+    next: usize,
+}
+
+impl<'a> Locator<'a> {
+    /// First unclaimed line with a start at `column` (any column from `min`
+    /// when None) whose text `matches`, before the block indented from `min`
+    /// ends; its index, column and text
+    fn find(&self, min: usize, column: Option<usize>, matches: impl Fn(&str) -> bool) -> Option<(usize, usize, &'a str)> {
+        for (i, &line) in self.lines.iter().enumerate().skip(self.next) {
+            if !is_content(line) {
+                continue;
+            }
+            let found = starts(line)
+                .into_iter()
+                .find(|&(c, text)| c >= min && column.is_none_or(|col| col == c) && matches(text));
+            if let Some((c, text)) = found {
+                return Some((i, c, text));
+            }
+            if line.len() - line.trim_start_matches(' ').len() < min {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// `value` as a node on `line`, its block content at columns from `min`
+    /// (`flow` when it was written inline, so everything in it is on `line`)
+    fn node(&mut self, value: Yaml, line: usize, min: usize, flow: bool) -> Result<Node, YamlError> {
+        let value = match value {
+            Yaml::Null => Value::Null,
+            Yaml::Tagged(tagged) => return self.node(tagged.value, line, min, flow),
+            Yaml::Sequence(items) => {
+                let mut nodes = Vec::new();
+                let mut column = None;
+                for item in items {
+                    let found = if flow { None } else { self.find(min, column, is_item) };
+                    let Some((i, c, text)) = found else {
+                        nodes.push(self.node(item, line, min, true)?);
+                        continue;
+                    };
+                    // Content may start on the item's own line
+                    column = Some(c);
+                    self.next = i;
+                    nodes.push(self.node(item, i + 1, c + 1, is_flow(&text[1..]))?);
+                    self.next = self.next.max(i + 1);
+                }
+                Value::Seq(nodes)
+            }
+            Yaml::Mapping(mapping) => {
+                let mut entries = Vec::new();
+                let mut column = None;
+                for (key, item) in mapping {
+                    let Some(key) = scalar(&key) else {
+                        return Err(YamlError { line, column: 1, message: "Mapping keys must be plain values".to_string() });
+                    };
+                    let found = if flow { None } else { self.find(min, column, |text| after_key(text, &key).is_some()) };
+                    let node = match found {
+                        Some((i, c, text)) => {
+                            column = Some(c);
+                            self.next = i + 1;
+                            let flow = after_key(text, &key).is_some_and(is_flow);
+                            // A list may start at the key's own column
+                            let item_min = if matches!(item, Yaml::Sequence(_)) { c } else { c + 1 };
+                            self.node(item, i + 1, item_min, flow)?
+                        }
+                        None => self.node(item, line, min, true)?,
+                    };
+                    entries.push((key, node));
+                }
+                Value::Map(entries)
+            }
+            scalar_value => Value::Scalar(scalar(&scalar_value).unwrap_or_default()),
+        };
+        Ok(Node { line, value })
+    }
+}
+
+/// Parse a workflow file's YAML
+pub fn parse(source: &str) -> Result<Node, YamlError> {
+    let lines: Vec<&str> = source.lines().collect();
+    let Some(first) = lines.iter().position(|line| is_content(line)) else {
+        return Ok(Node { line: 1, value: Value::Null });
+    };
+    let value: Yaml = serde_yaml::from_str(source).map_err(|e| YamlError::from_serde(&e, source))?;
+    let flow = is_flow(lines[first]);
+    Locator { lines, next: 0 }.node(value, first + 1, 0, flow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_workflow_with_lines() {
+        let yaml = "\
+# Demo
+name: \"Demo: flow\"
+tags: [a, 'b c']
+variables:
+  target: src   # default
+steps:
+  - id: plan
+    agent: api
+    prompt: |
+      Plan ${target}
+
+      Don't skip tests
+    save_output: plan
+  - id: build
+    depends_on:
+    - plan
+    prompt: >-
+      Build
+      it
+    timeout: 30
+";
+        let root = parse(yaml).unwrap();
+        assert_eq!(root.line, 2);
+        assert_eq!(root.get("name").unwrap().as_str(), Some("Demo: flow"));
+        let tags: Vec<_> = root.get("tags").unwrap().as_seq().unwrap().iter().map(|t| (t.line, t.as_str().unwrap())).collect();
+        assert_eq!(tags, vec![(3, "a"), (3, "b c")]);
+        let target = root.get("variables").unwrap().get("target").unwrap();
+        assert_eq!((target.line, target.as_str()), (5, Some("src")));
+
+        let steps = root.get("steps").unwrap().as_seq().unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!((steps[0].line, steps[1].line), (7, 14));
+        assert_eq!(steps[0].get("id").unwrap().line, 7);
+        let prompt = steps[0].get("prompt").unwrap();
+        assert_eq!((prompt.line, prompt.as_str()), (9, Some("Plan ${target}\n\nDon't skip tests\n")));
+        assert_eq!(steps[0].get("save_output").unwrap().line, 13);
+        assert_eq!(steps[1].get("depends_on").unwrap().as_seq().unwrap()[0].line, 16);
+        assert_eq!(steps[1].get("prompt").unwrap().as_str(), Some("Build it"));
+        let timeout = steps[1].get("timeout").unwrap();
+        assert_eq!((timeout.line, timeout.as_str()), (20, Some("30")));
+
+        let flow = parse("steps:\n  - {id: a, agent: api}\n  - id: b\n    depends_on: [a]\n").unwrap();
+        let steps = flow.get("steps").unwrap().as_seq().unwrap();
+        assert_eq!((steps[0].get("agent").unwrap().line, steps[1].get("depends_on").unwrap().as_seq().unwrap()[0].line), (2, 4));
+        assert!(parse("# nothing yet\n").unwrap().is_null());
+
+        assert_eq!(parse("name: a\n  bad: indent\n").unwrap_err().line, 2);
+        let duplicate = parse("name: a\nname: b\n").unwrap_err();
+        assert!(duplicate.message.to_lowercase().contains("duplicate entry with key \"name\""), "{}", duplicate.message);
+        assert_eq!(parse("steps:\n  - id: \"open\n").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn test_broken_yaml_corpus_reports_serde_yaml_locations() {
+        // (source, line, column, message); errors at the end of the input
+        // point just past the last line
+        let corpus = [
+            ("name: a\n\tsteps: []\n", 2, 1, "Found a tab character that violates indentation"),
+            ("steps:\n  - id: a\n  \tagent: api\n", 3, 3, "Found a tab character that violates indentation"),
+            ("name: a\n  description: b\n", 2, 14, "Mapping values are not allowed in this context"),
+            ("steps:\n  - id: a\n      agent: api\n", 3, 12, "Mapping values are not allowed in this context"),
+            ("  name: a\nsteps: []\n", 2, 1, "Did not find expected <document start>"),
+            ("name: a\ndescription b\n", 2, 14, "Could not find expected ':'"),
+            ("variables:\n  target: src\n  - lib\n", 3, 3, "Did not find expected key"),
+            ("name: \"Demo\n", 1, 12, "Found unexpected end of stream"),
+            ("tags: [a, 'b\n", 1, 13, "Found unexpected end of stream"),
+            ("tags: [a, 'b]\n", 1, 14, "Found unexpected end of stream"),
+            ("steps:\n  - {id: a, name: 'x}\n", 2, 22, "Found unexpected end of stream"),
+            ("steps:\n  - prompt: [unclosed\n    agent: api\n", 3, 10, "Did not find expected ',' or ']'"),
+        ];
+        for (source, line, column, message) in corpus {
+            let e = parse(source).unwrap_err();
+            assert_eq!((e.line, e.column), (line, column), "{:?}", source);
+            assert!(e.message.starts_with(message), "{:?}: {}", source, e.message);
+            assert!(!e.message.contains(" at line "), "{}", e.message);
+        }
+
+        let source = "name: a\nsteps:\n  - id: a\n\t  agent: api\n";
+        let diagnostic = parse(source).unwrap_err().diagnostic(source);
+        assert_eq!((diagnostic.line, diagnostic.column), (4, 1));
+        assert_eq!(diagnostic.snippet, "3 |   - id: a\n4 | \t  agent: api\n  | ^");
+        let diagnostic = parse(corpus[9].0).unwrap_err().diagnostic(corpus[9].0);
+        assert_eq!(diagnostic.snippet, "1 | tags: [a, 'b]\n  |              ^");
+        assert_eq!(
+            parse(corpus[0].0).unwrap_err().to_string(),
+            "Invalid workflow YAML (line 2, column 1): Found a tab character that violates indentation"
+        );
+    }
+}
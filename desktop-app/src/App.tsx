@@ -5,6 +5,8 @@ import Sidebar from './components/Sidebar/Sidebar';
 import FileExplorer from './components/FileExplorer/FileExplorer';
 import FileViewer from './components/FileViewer/FileViewer';
 import ChangesPanel from './components/ChangesPanel/ChangesPanel';
+import FindInFiles from './components/FindInFiles/FindInFiles';
+import GitHistory from './components/GitHistory/GitHistory';
 import Dashboard from './pages/Dashboard';
 import TaskExecutor from './pages/TaskExecutor';
 import WorkflowRunner from './pages/WorkflowRunner';
@@ -123,7 +125,9 @@ function App() {
             projectPath={currentProject}
             onFileSelect={handleFileSelect}
           />
+          <FindInFiles projectPath={currentProject} onFileSelect={handleFileSelect} />
           <ChangesPanel onFileSelect={handleFileSelect} />
+          <GitHistory projectPath={currentProject} />
         </div>
       )}
 
@@ -1,14 +1,86 @@
 import React, { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { ConflictedFile } from '../../services/mergeConflicts';
 import './ChangesPanel.css';
 
 interface ChangedFile {
     path: string;
     status: string; // "added", "modified", "deleted"
+    lines_added: number | null; // null for binary files
+    lines_removed: number | null;
+    renamed_from?: string | null; // on the "added" entry of a rename
+    detected_during_task?: boolean; // seen on disk during a run, not reported by it
+}
+
+interface DiffLine {
+    kind: 'context' | 'added' | 'removed';
+    old_line: number | null;
+    new_line: number | null;
+    text: string;
+}
+
+interface DiffHunk {
+    old_start: number;
+    old_lines: number;
+    new_start: number;
+    new_lines: number;
+    section: string;
+    lines: DiffLine[];
+}
+
+// One side of the index in a ChangedFileDiff
+interface DiffPart {
+    binary: boolean;
+    hunks: DiffHunk[];
     lines_added: number;
     lines_removed: number;
+    truncated: boolean;
+}
+
+// Returned by get_changed_file_diff
+interface ChangedFileDiff {
+    path: string;
+    relative_path: string;
+    old_path: string | null;
+    status: string;
+    staged: DiffPart | null;
+    unstaged: DiffPart | null;
+}
+
+// Returned by get_git_identity
+interface GitIdentity {
+    name: string | null;
+    email: string | null;
+    configured: boolean;
 }
 
+// Returned by get_git_status and sent as git-status-updated
+interface GitRepoStatus {
+    branch: string | null;
+    detached: boolean;
+    ahead: number;
+    behind: number;
+    dirty: boolean;
+    remote_url: string | null;
+    last_commit: { hash: string; message: string; author: string; time: string } | null;
+}
+
+// Returned by git_push
+interface PushResult {
+    remote: string;
+    branch: string;
+    upstream_set: boolean;
+    refs: { local: string; remote: string; status: string; summary: string }[];
+}
+
+// What to do about a failed push, by error kind
+const PUSH_HINTS: Record<string, string> = {
+    auth_failed: 'Set up a git credential helper or an SSH key, then push again.',
+    non_fast_forward: 'Pull the remote changes first, then push again.',
+    network: 'Check your connection and the remote URL, then push again.',
+};
+
 interface ChangesPanelProps {
     onFileSelect?: (path: string) => void;
 }
@@ -47,14 +119,55 @@ const getFileName = (path: string): string => {
 const ChangesPanel: React.FC<ChangesPanelProps> = ({ onFileSelect }) => {
     const [changedFiles, setChangedFiles] = useState<ChangedFile[]>([]);
     const [isCollapsed, setIsCollapsed] = useState(false);
+    // File whose diff is shown under it, with the diff or why it failed
+    const [openDiff, setOpenDiff] = useState<{ path: string; diff?: ChangedFileDiff; error?: string } | null>(null);
+    const [commitMessage, setCommitMessage] = useState('');
+    const [author, setAuthor] = useState('');
+    const [identity, setIdentity] = useState<GitIdentity | null>(null);
+    const [commitStatus, setCommitStatus] = useState<{ ok: boolean; text: string } | null>(null);
+    const [isCommitting, setIsCommitting] = useState(false);
+    // Null hides the branch line (no project, or not under git)
+    const [gitStatus, setGitStatus] = useState<GitRepoStatus | null>(null);
+    const [conflicts, setConflicts] = useState<ConflictedFile[]>([]);
+    // Last progress line while a push runs
+    const [pushProgress, setPushProgress] = useState<string | null>(null);
+    const [isPushing, setIsPushing] = useState(false);
 
     useEffect(() => {
         loadChangedFiles();
         // Poll for changes every 5 seconds
         const interval = setInterval(loadChangedFiles, 5000);
-        return () => clearInterval(interval);
+        // Rebuilt from git after each task and workflow run
+        const unlisten = listen<ChangedFile[]>('changed-files-updated', (event) => setChangedFiles(event.payload));
+        return () => {
+            clearInterval(interval);
+            unlisten.then((fn) => fn());
+        };
     }, []);
 
+    useEffect(() => {
+        invoke<GitRepoStatus>('get_git_status')
+            .then(setGitStatus)
+            .catch(() => setGitStatus(null));
+        loadConflicts();
+        // Resolving a conflict updates the index, which refreshes the git status
+        const unlisten = listen<GitRepoStatus | null>('git-status-updated', (event) => {
+            setGitStatus(event.payload);
+            loadConflicts();
+        });
+        const unlistenConflicts = listen('conflicts-detected', () => loadConflicts());
+        return () => {
+            unlisten.then((fn) => fn());
+            unlistenConflicts.then((fn) => fn());
+        };
+    }, []);
+
+    const loadConflicts = () => {
+        invoke<ConflictedFile[]>('get_conflicted_files')
+            .then(setConflicts)
+            .catch(() => setConflicts([]));
+    };
+
     const loadChangedFiles = async () => {
         try {
             const files = await invoke<ChangedFile[]>('get_changed_files');
@@ -64,6 +177,65 @@ const ChangesPanel: React.FC<ChangesPanelProps> = ({ onFileSelect }) => {
         }
     };
 
+    const handleRefresh = async () => {
+        try {
+            setChangedFiles(await invoke<ChangedFile[]>('refresh_changed_files'));
+        } catch (error) {
+            console.error('Failed to refresh changes from git:', error);
+        }
+    };
+
+    const loadIdentity = async () => {
+        try {
+            setIdentity(await invoke<GitIdentity>('get_git_identity'));
+        } catch {
+            // Not a git project: there is nothing to commit to
+            setIdentity(null);
+        }
+    };
+
+    useEffect(() => {
+        if (changedFiles.length > 0 && !identity) loadIdentity();
+    }, [changedFiles.length]);
+
+    const handlePush = async () => {
+        setIsPushing(true);
+        setCommitStatus(null);
+        const unlisten = await listen<{ line: string }>('git-push-progress', (event) => setPushProgress(event.payload.line));
+        try {
+            const result = await invoke<PushResult>('git_push', { remote: null, branch: null, setUpstream: true });
+            const upToDate = result.refs.every((r) => r.status === 'up_to_date');
+            setCommitStatus({ ok: true, text: upToDate ? 'Everything up to date' : `Pushed ${result.branch} to ${result.remote}` });
+        } catch (error) {
+            const { kind, message } = error as { kind?: string; message?: string };
+            const hint = kind ? PUSH_HINTS[kind] : undefined;
+            setCommitStatus({ ok: false, text: [message ?? String(error), hint].filter(Boolean).join(' ') });
+        } finally {
+            unlisten();
+            setPushProgress(null);
+            setIsPushing(false);
+        }
+    };
+
+    const handleCommit = async () => {
+        setIsCommitting(true);
+        setCommitStatus(null);
+        try {
+            const hash = await invoke<string>('commit_changes', {
+                paths: changedFiles.map((f) => f.path),
+                message: commitMessage,
+                authorOverride: author.trim() || null,
+            });
+            setCommitMessage('');
+            setCommitStatus({ ok: true, text: `Committed ${hash.slice(0, 7)}` });
+        } catch (error) {
+            const message = (error as { message?: string })?.message ?? String(error);
+            setCommitStatus({ ok: false, text: message });
+        } finally {
+            setIsCommitting(false);
+        }
+    };
+
     const handleClearChanges = async () => {
         try {
             await invoke('clear_changed_files');
@@ -73,8 +245,136 @@ const ChangesPanel: React.FC<ChangesPanelProps> = ({ onFileSelect }) => {
         }
     };
 
-    const totalAdded = changedFiles.reduce((sum, f) => sum + f.lines_added, 0);
-    const totalRemoved = changedFiles.reduce((sum, f) => sum + f.lines_removed, 0);
+    const loadDiff = async (path: string, full: boolean) => {
+        try {
+            const diff = await invoke<ChangedFileDiff>('get_changed_file_diff', { path, full });
+            setOpenDiff({ path, diff });
+        } catch (error) {
+            const message = (error as { message?: string })?.message ?? String(error);
+            setOpenDiff({ path, error: message });
+        }
+    };
+
+    // The list comes back through changed-files-updated
+    const recountLines = async (path: string) => {
+        try {
+            await invoke('compute_line_stats', { path });
+        } catch (error) {
+            console.error('Failed to recount lines:', error);
+        }
+    };
+
+    const toggleDiff = (path: string) => {
+        if (openDiff?.path === path) {
+            setOpenDiff(null);
+            return;
+        }
+        setOpenDiff({ path });
+        loadDiff(path, false);
+    };
+
+    const renderPart = (title: string, part: DiffPart) => (
+        <div className="diff-part">
+            <div className="diff-part-title">
+                {title}
+                <span className="stat-added">+{part.lines_added}</span>
+                <span className="stat-removed">-{part.lines_removed}</span>
+            </div>
+            {part.binary ? (
+                <div className="diff-message">Binary file changed</div>
+            ) : (
+                part.hunks.map((hunk, h) => (
+                    <div key={h} className="diff-hunk">
+                        <div className="diff-hunk-header">
+                            @@ -{hunk.old_start},{hunk.old_lines} +{hunk.new_start},{hunk.new_lines} @@ {hunk.section}
+                        </div>
+                        {hunk.lines.map((line, i) => (
+                            <div key={i} className={`diff-line diff-${line.kind}`}>
+                                <span className="diff-number">{line.old_line ?? ''}</span>
+                                <span className="diff-number">{line.new_line ?? ''}</span>
+                                <span className="diff-text">
+                                    {line.kind === 'added' ? '+' : line.kind === 'removed' ? '-' : ' '}
+                                    {line.text}
+                                </span>
+                            </div>
+                        ))}
+                    </div>
+                ))
+            )}
+        </div>
+    );
+
+    const renderDiff = () => {
+        if (!openDiff) return null;
+        const { diff, error } = openDiff;
+        if (error) return <div className="diff-message">{error}</div>;
+        if (!diff) return <div className="diff-message">Loading diff...</div>;
+        if (!diff.staged && !diff.unstaged) return <div className="diff-message">No changes against HEAD</div>;
+        const truncated = diff.staged?.truncated || diff.unstaged?.truncated;
+        return (
+            <div className="diff-view">
+                {diff.old_path && <div className="diff-message">Renamed from {diff.old_path}</div>}
+                {diff.staged && renderPart('Staged', diff.staged)}
+                {diff.unstaged && renderPart('Unstaged', diff.unstaged)}
+                {truncated && (
+                    <button className="diff-full" onClick={() => loadDiff(openDiff.path, true)}>
+                        Show full diff
+                    </button>
+                )}
+            </div>
+        );
+    };
+
+    const renderFile = (file: ChangedFile) => (
+        <React.Fragment key={file.path}>
+            <div
+                className="change-item"
+                onClick={() => onFileSelect?.(file.path)}
+            >
+                <span className={`change-status ${getStatusClass(file.status)}`}>
+                    {getStatusIcon(file.status)}
+                </span>
+                <span
+                    className="change-name"
+                    title={file.renamed_from ? `${file.path}\nrenamed from ${file.renamed_from}` : file.path}
+                >
+                    {getFileName(file.path)}
+                    {file.renamed_from && (
+                        <span className="change-renamed"> ← {getFileName(file.renamed_from)}</span>
+                    )}
+                </span>
+                <span
+                    className="change-stats"
+                    onClick={(e) => { e.stopPropagation(); recountLines(file.path); }}
+                    title="Recount lines"
+                >
+                    {file.lines_added === null || file.lines_removed === null ? (
+                        <span className="stat-binary">binary</span>
+                    ) : (
+                        <>
+                            {file.lines_added > 0 && (
+                                <span className="stat-added">+{file.lines_added}</span>
+                            )}
+                            {file.lines_removed > 0 && (
+                                <span className="stat-removed">-{file.lines_removed}</span>
+                            )}
+                        </>
+                    )}
+                </span>
+                <button
+                    className={`change-diff ${openDiff?.path === file.path ? 'active' : ''}`}
+                    onClick={(e) => { e.stopPropagation(); toggleDiff(file.path); }}
+                    title="Show staged and unstaged changes"
+                >
+                    ±
+                </button>
+            </div>
+            {openDiff?.path === file.path && renderDiff()}
+        </React.Fragment>
+    );
+
+    const totalAdded = changedFiles.reduce((sum, f) => sum + (f.lines_added ?? 0), 0);
+    const totalRemoved = changedFiles.reduce((sum, f) => sum + (f.lines_removed ?? 0), 0);
 
     return (
         <div className={`changes-panel ${isCollapsed ? 'collapsed' : ''}`}>
@@ -82,6 +382,13 @@ const ChangesPanel: React.FC<ChangesPanelProps> = ({ onFileSelect }) => {
                 <span className={`collapse-arrow ${isCollapsed ? '' : 'expanded'}`}>▶</span>
                 <span className="changes-title">CHANGES</span>
                 <span className="changes-count">{changedFiles.length}</span>
+                <button
+                    className="changes-clear"
+                    onClick={(e) => { e.stopPropagation(); handleRefresh(); }}
+                    title="Refresh from git status"
+                >
+                    ⟳
+                </button>
                 {changedFiles.length > 0 && (
                     <button
                         className="changes-clear"
@@ -95,6 +402,45 @@ const ChangesPanel: React.FC<ChangesPanelProps> = ({ onFileSelect }) => {
 
             {!isCollapsed && (
                 <div className="changes-content">
+                    {gitStatus && (
+                        <div
+                            className="changes-branch"
+                            title={[
+                                gitStatus.remote_url,
+                                gitStatus.last_commit &&
+                                    `${gitStatus.last_commit.hash.slice(0, 7)} ${gitStatus.last_commit.message} (${gitStatus.last_commit.author}, ${new Date(gitStatus.last_commit.time).toLocaleString()})`,
+                            ].filter(Boolean).join('\n')}
+                        >
+                            <span className="branch-name">
+                                ⎇ {gitStatus.detached ? `detached ${gitStatus.last_commit?.hash.slice(0, 7) ?? ''}` : gitStatus.branch}
+                            </span>
+                            {gitStatus.dirty && <span className="branch-dirty" title="Uncommitted changes">●</span>}
+                            {gitStatus.ahead > 0 && <span className="branch-ahead">↑{gitStatus.ahead}</span>}
+                            {gitStatus.behind > 0 && <span className="branch-behind">↓{gitStatus.behind}</span>}
+                            {gitStatus.remote_url && gitStatus.branch && !gitStatus.detached && (
+                                <button
+                                    className="branch-push"
+                                    onClick={handlePush}
+                                    disabled={isPushing}
+                                    title={`Push ${gitStatus.branch} to its remote`}
+                                >
+                                    {isPushing ? 'Pushing...' : 'Push'}
+                                </button>
+                            )}
+                        </div>
+                    )}
+                    {conflicts.length > 0 && (
+                        <div
+                            className="changes-conflicts"
+                            title={conflicts.map(f => `${f.relative_path} (${f.markers} conflict regions)`).join('\n')}
+                        >
+                            ⚠ {conflicts.length} file(s) with merge conflicts; tasks won't start until they're resolved
+                        </div>
+                    )}
+                    {pushProgress && <div className="push-progress">{pushProgress}</div>}
+                    {commitStatus && (
+                        <div className={`commit-status ${commitStatus.ok ? 'ok' : 'error'}`}>{commitStatus.text}</div>
+                    )}
                     {changedFiles.length === 0 ? (
                         <div className="changes-empty">
                             <span>No pending changes</span>
@@ -106,29 +452,43 @@ const ChangesPanel: React.FC<ChangesPanelProps> = ({ onFileSelect }) => {
                                 <span className="summary-removed">-{totalRemoved}</span>
                             </div>
                             <div className="changes-list">
-                                {changedFiles.map((file, index) => (
-                                    <div
-                                        key={index}
-                                        className="change-item"
-                                        onClick={() => onFileSelect?.(file.path)}
-                                    >
-                                        <span className={`change-status ${getStatusClass(file.status)}`}>
-                                            {getStatusIcon(file.status)}
-                                        </span>
-                                        <span className="change-name" title={file.path}>
-                                            {getFileName(file.path)}
-                                        </span>
-                                        <span className="change-stats">
-                                            {file.lines_added > 0 && (
-                                                <span className="stat-added">+{file.lines_added}</span>
-                                            )}
-                                            {file.lines_removed > 0 && (
-                                                <span className="stat-removed">-{file.lines_removed}</span>
-                                            )}
-                                        </span>
+                                {changedFiles.filter((f) => !f.detected_during_task).map(renderFile)}
+                                {changedFiles.some((f) => f.detected_during_task) && (
+                                    <div className="changes-detected" title="Changed on disk while a task ran, but not reported by it">
+                                        Detected during run (may include your own edits)
                                     </div>
-                                ))}
+                                )}
+                                {changedFiles.filter((f) => f.detected_during_task).map(renderFile)}
                             </div>
+                            {identity && (
+                                <div className="changes-commit">
+                                    <textarea
+                                        className="commit-message"
+                                        value={commitMessage}
+                                        onChange={(e) => setCommitMessage(e.target.value)}
+                                        placeholder="Commit message"
+                                        rows={2}
+                                    />
+                                    <input
+                                        className="commit-author"
+                                        value={author}
+                                        onChange={(e) => setAuthor(e.target.value)}
+                                        placeholder={
+                                            identity.configured
+                                                ? `${identity.name} <${identity.email}>`
+                                                : 'Name <email> (no git identity configured)'
+                                        }
+                                        title="Author of the commit"
+                                    />
+                                    <button
+                                        className="commit-button"
+                                        onClick={handleCommit}
+                                        disabled={isCommitting || !commitMessage.trim()}
+                                    >
+                                        {isCommitting ? 'Committing...' : `Commit ${changedFiles.length} file${changedFiles.length === 1 ? '' : 's'}`}
+                                    </button>
+                                </div>
+                            )}
                         </>
                     )}
                 </div>
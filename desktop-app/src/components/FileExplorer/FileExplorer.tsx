@@ -1,5 +1,6 @@
-import React, { useState, useEffect } from 'react';
+import React, { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import './FileExplorer.css';
 
 interface FileEntry {
@@ -9,8 +10,140 @@ interface FileEntry {
     extension: string | null;
     size: number | null;
     children: FileEntry[] | null;
+    truncated?: boolean;
+    is_symlink?: boolean;
+    symlink_target?: string | null;
+    broken?: boolean;
 }
 
+// Matches DirectoryListing in src-tauri/src/file_explorer.rs
+interface DirectoryListing {
+    generation: number;
+    entries: FileEntry[];
+    offset: number;
+    total_count: number;
+    truncated: boolean;
+}
+
+// Matches FileMatch in src-tauri/src/file_search.rs
+interface FileMatch extends FileEntry {
+    relative_path: string;
+    kind: 'exact' | 'prefix' | 'substring' | 'fuzzy';
+    positions: number[];
+}
+
+// Matches FileSearchResults in src-tauri/src/file_search.rs
+interface FileSearchResults {
+    generation: number;
+    results: FileMatch[];
+    truncated: boolean;
+    cancelled: boolean;
+}
+
+// Matches DirectorySize in src-tauri/src/directory_size.rs
+interface DirectorySize {
+    path: string;
+    total_bytes: number;
+    file_count: number;
+    largest_entries: { path: string; bytes: number; is_dir: boolean }[];
+    cancelled: boolean;
+    cached: boolean;
+}
+
+// Matches IgnorePatterns in src-tauri/src/ignore_rules.rs
+interface IgnorePatterns {
+    patterns: string[];
+    defaults: string[];
+    generation: number;
+}
+
+// Matches GitignoreSuggestion in src-tauri/src/gitignore.rs
+interface GitignoreSuggestion {
+    content: string;
+    sections: { id: string; title: string }[];
+    exists: boolean;
+    missing: number;
+}
+
+// Matches BookmarkEntry in src-tauri/src/bookmarks.rs
+interface BookmarkEntry {
+    id: string;
+    label: string;
+    relative_path: string;
+    path: string;
+    exists: boolean;
+    is_dir: boolean;
+}
+
+// Matches RecentFile in src-tauri/src/recent_files.rs
+interface RecentFile {
+    path: string;
+    opened_at: string;
+}
+
+// Entries fetched per list_directory_page call
+const PAGE_SIZE = 2000;
+
+const formatBytes = (bytes: number): string => {
+    const units = ['B', 'KB', 'MB', 'GB', 'TB'];
+    let value = bytes;
+    let unit = 0;
+    while (value >= 1024 && unit < units.length - 1) {
+        value /= 1024;
+        unit += 1;
+    }
+    return `${unit === 0 ? value : value.toFixed(1)} ${units[unit]}`;
+};
+
+// Folder size with its largest entries in the tooltip
+const sizeTitle = (size: DirectorySize): string => {
+    const largest = size.largest_entries.map((e) => `${formatBytes(e.bytes)}  ${e.path.split(/[\\/]/).pop()}${e.is_dir ? '/' : ''}`);
+    const note = size.cancelled ? ' (stopped early)' : '';
+    return [`${size.file_count.toLocaleString()} files, ${formatBytes(size.total_bytes)}${note}`, ...largest].join('\n');
+};
+
+// `path` relative to the project folder, for lists of project files
+const relativeToProject = (path: string, projectPath: string): string =>
+    path.startsWith(projectPath) ? path.slice(projectPath.length).replace(/^[\\/]+/, '') : path;
+
+// File name with the characters the query matched in bold
+const HighlightedName: React.FC<{ name: string; positions: number[] }> = ({ name, positions }) => {
+    const matched = new Set(positions);
+    return (
+        <>
+            {Array.from(name).map((char, index) =>
+                matched.has(index) ? <b key={index} className="search-hit">{char}</b> : char
+            )}
+        </>
+    );
+};
+
+// Fetches the next page of a truncated listing once scrolled into view
+const LoadMoreRow: React.FC<{ level: number; remaining: number; onLoadMore: () => void }> = ({ level, remaining, onLoadMore }) => {
+    const ref = useRef<HTMLDivElement>(null);
+
+    useEffect(() => {
+        if (!ref.current) return;
+        const observer = new IntersectionObserver((items) => {
+            if (items.some((item) => item.isIntersecting)) {
+                onLoadMore();
+            }
+        });
+        observer.observe(ref.current);
+        return () => observer.disconnect();
+    }, [onLoadMore]);
+
+    return (
+        <div ref={ref} className="tree-empty" style={{ paddingLeft: `${level * 16 + 8}px` }}>
+            … {remaining} more
+        </div>
+    );
+};
+
+// Append the next page of `path` after `loaded` entries
+const fetchNextPage = (path: string, loaded: number, showIgnored: boolean) =>
+    invoke<DirectoryListing>('list_directory_page', { path, offset: loaded, limit: PAGE_SIZE, showIgnored });
+
 interface FileExplorerProps {
     projectPath: string | null;
     onFileSelect?: (path: string) => void;
@@ -60,9 +193,17 @@ const FileTreeNode: React.FC<{
     onFileSelect?: (path: string) => void;
     expandedDirs: Set<string>;
     onToggleDir: (path: string) => void;
-}> = ({ entry, level, onFileSelect, expandedDirs, onToggleDir }) => {
+    showIgnored: boolean;
+    onPin?: (entry: FileEntry) => void;
+    // Buttons at the end of this node's row (not its children's)
+    actions?: React.ReactNode;
+}> = ({ entry, level, onFileSelect, expandedDirs, onToggleDir, showIgnored, onPin, actions }) => {
     const [children, setChildren] = useState<FileEntry[] | null>(null);
+    const [totalCount, setTotalCount] = useState(0);
     const [isLoading, setIsLoading] = useState(false);
+    const [size, setSize] = useState<DirectorySize | null>(null);
+    // Operation id of a running size walk, to cancel it
+    const [sizingId, setSizingId] = useState<string | null>(null);
     const isExpanded = expandedDirs.has(entry.path);
 
     const loadChildren = async () => {
@@ -70,8 +211,9 @@ const FileTreeNode: React.FC<{
 
         setIsLoading(true);
         try {
-            const result = await invoke<FileEntry[]>('list_directory', { path: entry.path });
-            setChildren(result);
+            const result = await invoke<DirectoryListing>('list_directory', { path: entry.path, showIgnored });
+            setChildren(result.entries);
+            setTotalCount(result.total_count);
         } catch (error) {
             console.error('Failed to load directory:', error);
             setChildren([]);
@@ -80,6 +222,52 @@ const FileTreeNode: React.FC<{
         }
     };
 
+    const loadMoreChildren = async () => {
+        if (!children || isLoading) return;
+
+        setIsLoading(true);
+        try {
+            const result = await fetchNextPage(entry.path, children.length, showIgnored);
+            setChildren([...children, ...result.entries]);
+            setTotalCount(result.total_count);
+        } catch (error) {
+            console.error('Failed to load more entries:', error);
+        } finally {
+            setIsLoading(false);
+        }
+    };
+
+    // Nodes are remounted when the ignore rules change; reload open folders
+    useEffect(() => {
+        if (isExpanded && children === null) {
+            loadChildren();
+        }
+    }, []);
+
+    // Sum the folder's size; clicked again while summing, stop early
+    const handleSize = async (e: React.MouseEvent) => {
+        e.stopPropagation();
+        if (sizingId !== null) {
+            if (sizingId) invoke('cancel_operation', { id: sizingId }).catch(() => {});
+            return;
+        }
+        setSizingId('');
+        const unlisten = listen<{ id: string; kind: string; message: string | null }>('operation-progress', (event) => {
+            const { id, kind, message } = event.payload;
+            if (kind === 'directory_size' && message === `Sizing ${entry.path}`) {
+                setSizingId(id);
+            }
+        });
+        try {
+            setSize(await invoke<DirectorySize>('get_directory_size', { path: entry.path, respectIgnore: !showIgnored }));
+        } catch (error) {
+            console.error('Failed to size directory:', error);
+        } finally {
+            unlisten.then((fn) => fn());
+            setSizingId(null);
+        }
+    };
+
     const handleClick = async () => {
         if (entry.is_dir) {
             onToggleDir(entry.path);
@@ -104,7 +292,38 @@ const FileTreeNode: React.FC<{
                     </span>
                 )}
                 <span className="tree-icon">{getFileIcon(entry)}</span>
-                <span className="tree-name">{entry.name}</span>
+                <span
+                    className={`tree-name ${entry.broken ? 'broken' : ''}`}
+                    title={entry.is_symlink ? `→ ${entry.symlink_target ?? '?'}${entry.broken ? ' (missing)' : ''}` : undefined}
+                >
+                    {entry.name}
+                </span>
+                {entry.is_symlink && <span className="tree-link">{entry.broken ? '⚠' : '↪'}</span>}
+                {entry.is_dir && size && sizingId === null && (
+                    <span className="tree-size" title={sizeTitle(size)}>{formatBytes(size.total_bytes)}</span>
+                )}
+                {entry.is_dir && (
+                    <button
+                        className={`tree-size-button ${sizingId !== null ? 'busy' : ''}`}
+                        onClick={handleSize}
+                        title={sizingId !== null ? 'Stop summing the folder size' : 'Show folder size'}
+                    >
+                        {sizingId !== null ? '⏹' : '⚖'}
+                    </button>
+                )}
+                {onPin && (
+                    <button
+                        className="tree-pin-button"
+                        onClick={(e) => {
+                            e.stopPropagation();
+                            onPin(entry);
+                        }}
+                        title="Pin to the top"
+                    >
+                        📌
+                    </button>
+                )}
+                {actions}
             </div>
 
             {entry.is_dir && isExpanded && children && (
@@ -117,8 +336,17 @@ const FileTreeNode: React.FC<{
                             onFileSelect={onFileSelect}
                             expandedDirs={expandedDirs}
                             onToggleDir={onToggleDir}
+                            showIgnored={showIgnored}
+                            onPin={onPin}
                         />
                     ))}
+                    {children.length < totalCount && (
+                        <LoadMoreRow
+                            level={level + 1}
+                            remaining={totalCount - children.length}
+                            onLoadMore={loadMoreChildren}
+                        />
+                    )}
                     {children.length === 0 && (
                         <div className="tree-empty" style={{ paddingLeft: `${(level + 1) * 16 + 8}px` }}>
                             (empty)
@@ -132,9 +360,30 @@ const FileTreeNode: React.FC<{
 
 const FileExplorer: React.FC<FileExplorerProps> = ({ projectPath, onFileSelect }) => {
     const [rootEntries, setRootEntries] = useState<FileEntry[]>([]);
+    const [rootTotal, setRootTotal] = useState(0);
+    const [isLoadingMore, setIsLoadingMore] = useState(false);
     const [isLoading, setIsLoading] = useState(false);
     const [expandedDirs, setExpandedDirs] = useState<Set<string>>(new Set());
     const [error, setError] = useState<string | null>(null);
+    // Ignore-rules generation the tree was filtered with; part of node keys
+    const [generation, setGeneration] = useState(0);
+    // List what .gitignore and ignorePatterns hide, too
+    const [showIgnored, setShowIgnored] = useState(false);
+    // Bumped on fs-changed; part of node keys so open folders reload
+    const [changeCount, setChangeCount] = useState(0);
+    // Ignore patterns being edited, one per line (null when the editor is closed)
+    const [patternsDraft, setPatternsDraft] = useState<string | null>(null);
+    const [patternsError, setPatternsError] = useState<string | null>(null);
+    // Proposed .gitignore being reviewed, with the draft as edited
+    const [gitignore, setGitignore] = useState<{ suggestion: GitignoreSuggestion; draft: string } | null>(null);
+    const [searchQuery, setSearchQuery] = useState('');
+    const [searchResults, setSearchResults] = useState<FileSearchResults | null>(null);
+    // Pinned files and folders of the project, in the user's order
+    const [bookmarks, setBookmarks] = useState<BookmarkEntry[]>([]);
+    // Recently opened files of the project (null when the list is closed)
+    const [recentFiles, setRecentFiles] = useState<RecentFile[] | null>(null);
+    // Newest search generation received; older responses are dropped
+    const searchGeneration = useRef(0);
 
     useEffect(() => {
         if (projectPath) {
@@ -142,16 +391,159 @@ const FileExplorer: React.FC<FileExplorerProps> = ({ projectPath, onFileSelect }
         } else {
             setRootEntries([]);
         }
+    }, [projectPath, showIgnored]);
+
+    useEffect(() => {
+        if (!projectPath || !searchQuery.trim()) {
+            setSearchResults(null);
+            return;
+        }
+        invoke<FileSearchResults>('search_files', { root: projectPath, query: searchQuery })
+            .then((results) => {
+                if (results.cancelled || results.generation < searchGeneration.current) return;
+                searchGeneration.current = results.generation;
+                setSearchResults(results);
+            })
+            .catch((err) => console.error('File search failed:', err));
+    }, [projectPath, searchQuery]);
+
+    // .gitignore or ignorePatterns changed: re-fetch the root, which remounts open folders
+    useEffect(() => {
+        const unlisten = listen<{ root: string; generation: number }>('ignore-rules-changed', (event) => {
+            if (projectPath && event.payload.generation > generation) {
+                loadRootDirectory();
+            }
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [projectPath, generation]);
+
+    // Files of the project changed on disk: re-fetch the root and open folders
+    useEffect(() => {
+        const unlisten = listen<{ root: string }>('fs-changed', (event) => {
+            if (projectPath && event.payload.root === projectPath) {
+                setChangeCount((count) => count + 1);
+                loadRootDirectory();
+            }
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [projectPath, showIgnored]);
+
+    // Each project has its own list; close the one of the previous project
+    useEffect(() => {
+        setRecentFiles(null);
     }, [projectPath]);
 
+    // Pins of this project; re-fetched when files change, as targets may be gone
+    useEffect(() => {
+        if (!projectPath) {
+            setBookmarks([]);
+            return;
+        }
+        invoke<BookmarkEntry[]>('list_bookmarks')
+            .then(setBookmarks)
+            .catch((err) => console.error('Failed to load bookmarks:', err));
+    }, [projectPath, changeCount]);
+
+    const updateBookmarks = async (command: string, args: Record<string, unknown>) => {
+        try {
+            setBookmarks(await invoke<BookmarkEntry[]>(command, args));
+        } catch (err) {
+            console.error(`${command} failed:`, err);
+        }
+    };
+
+    const pinEntry = (entry: FileEntry) => updateBookmarks('add_bookmark', { path: entry.path, label: entry.name });
+
+    // Swap the bookmark at `index` with its neighbour `step` away
+    const moveBookmark = (index: number, step: number) => {
+        const ids = bookmarks.map((b) => b.id);
+        const target = index + step;
+        if (target < 0 || target >= ids.length) return;
+        [ids[index], ids[target]] = [ids[target], ids[index]];
+        updateBookmarks('reorder_bookmarks', { ids });
+    };
+
+    const toggleRecentFiles = async () => {
+        if (recentFiles !== null) {
+            setRecentFiles(null);
+            return;
+        }
+        try {
+            setRecentFiles(await invoke<RecentFile[]>('get_recent_files'));
+        } catch (err) {
+            console.error('Failed to load recent files:', err);
+        }
+    };
+
+    const clearRecentFiles = async () => {
+        try {
+            await invoke('clear_recent_files');
+            setRecentFiles([]);
+        } catch (err) {
+            console.error('Failed to clear recent files:', err);
+        }
+    };
+
+    const togglePatternsEditor = async () => {
+        if (patternsDraft !== null) {
+            setPatternsDraft(null);
+            return;
+        }
+        try {
+            const current = await invoke<IgnorePatterns>('get_ignore_patterns');
+            setPatternsError(null);
+            setPatternsDraft(current.patterns.join('\n'));
+        } catch (err) {
+            console.error('Failed to load ignore patterns:', err);
+        }
+    };
+
+    // The saved patterns rebuild the matcher; ignore-rules-changed then re-fetches the tree
+    const savePatterns = async (patterns: string[] | null) => {
+        try {
+            const saved = await invoke<IgnorePatterns>('set_ignore_patterns', { patterns });
+            setPatternsDraft(patterns === null ? saved.patterns.join('\n') : null);
+            setPatternsError(null);
+        } catch (err) {
+            setPatternsError(String(err));
+        }
+    };
+
+    const suggestGitignore = async () => {
+        try {
+            const suggestion = await invoke<GitignoreSuggestion>('suggest_gitignore');
+            setPatternsError(null);
+            setGitignore({ suggestion, draft: suggestion.content });
+        } catch (err) {
+            setPatternsError(String(err));
+        }
+    };
+
+    // The rules are rescanned after the write; ignore-rules-changed then re-fetches the tree
+    const applyGitignore = async (merge: boolean) => {
+        if (!gitignore) return;
+        try {
+            await invoke('apply_gitignore', { content: gitignore.draft, merge });
+            setGitignore(null);
+        } catch (err) {
+            setPatternsError(String(err));
+        }
+    };
+
     const loadRootDirectory = async () => {
         if (!projectPath) return;
 
         setIsLoading(true);
         setError(null);
         try {
-            const entries = await invoke<FileEntry[]>('list_directory', { path: projectPath });
-            setRootEntries(entries);
+            const listing = await invoke<DirectoryListing>('list_directory', { path: projectPath, showIgnored });
+            setRootEntries(listing.entries);
+            setRootTotal(listing.total_count);
+            setGeneration(listing.generation);
         } catch (err) {
             console.error('Failed to load project directory:', err);
             setError(String(err));
@@ -160,6 +552,21 @@ const FileExplorer: React.FC<FileExplorerProps> = ({ projectPath, onFileSelect }
         }
     };
 
+    const loadMoreRootEntries = async () => {
+        if (!projectPath || isLoadingMore) return;
+
+        setIsLoadingMore(true);
+        try {
+            const listing = await fetchNextPage(projectPath, rootEntries.length, showIgnored);
+            setRootEntries([...rootEntries, ...listing.entries]);
+            setRootTotal(listing.total_count);
+        } catch (err) {
+            console.error('Failed to load more entries:', err);
+        } finally {
+            setIsLoadingMore(false);
+        }
+    };
+
     const handleToggleDir = (path: string) => {
         setExpandedDirs(prev => {
             const next = new Set(prev);
@@ -197,18 +604,178 @@ const FileExplorer: React.FC<FileExplorerProps> = ({ projectPath, onFileSelect }
         <div className="file-explorer">
             <div className="explorer-header">
                 <span className="explorer-title">EXPLORER</span>
+                <button
+                    className={`explorer-refresh ${showIgnored ? 'active' : ''}`}
+                    onClick={() => setShowIgnored(!showIgnored)}
+                    title={showIgnored ? 'Hide ignored files' : 'Show ignored files'}
+                >
+                    👁
+                </button>
+                <button
+                    className={`explorer-refresh ${recentFiles !== null ? 'active' : ''}`}
+                    onClick={toggleRecentFiles}
+                    title="Recently opened files"
+                >
+                    🕘
+                </button>
+                <button
+                    className={`explorer-refresh ${patternsDraft !== null ? 'active' : ''}`}
+                    onClick={togglePatternsEditor}
+                    title="Edit ignore patterns"
+                >
+                    ⚙
+                </button>
                 <button className="explorer-refresh" onClick={loadRootDirectory} title="Refresh">
                     🔄
                 </button>
             </div>
 
+            {patternsDraft !== null && (
+                <div className="explorer-patterns">
+                    <label>Ignore patterns (gitignore syntax, one per line)</label>
+                    <textarea
+                        value={patternsDraft}
+                        onChange={(e) => setPatternsDraft(e.target.value)}
+                        rows={6}
+                        spellCheck={false}
+                    />
+                    {patternsError && <div className="patterns-error">{patternsError}</div>}
+                    <div className="patterns-actions">
+                        <button onClick={() => savePatterns(patternsDraft.split('\n'))}>Save</button>
+                        <button onClick={() => savePatterns(null)}>Reset to defaults</button>
+                        <button onClick={suggestGitignore} title="Propose a .gitignore for this kind of project">
+                            .gitignore...
+                        </button>
+                        <button onClick={() => setPatternsDraft(null)}>Cancel</button>
+                    </div>
+                    {gitignore && (
+                        <>
+                            <label>
+                                Proposed .gitignore ({gitignore.suggestion.sections.map((s) => s.title).join(', ')})
+                                {gitignore.suggestion.exists && `: ${gitignore.suggestion.missing} pattern(s) missing from the current file`}
+                            </label>
+                            <textarea
+                                value={gitignore.draft}
+                                onChange={(e) => setGitignore({ ...gitignore, draft: e.target.value })}
+                                rows={10}
+                                spellCheck={false}
+                            />
+                            <div className="patterns-actions">
+                                {gitignore.suggestion.exists && (
+                                    <button onClick={() => applyGitignore(true)}>Merge into .gitignore</button>
+                                )}
+                                <button onClick={() => applyGitignore(false)}>
+                                    {gitignore.suggestion.exists ? 'Replace .gitignore' : 'Create .gitignore'}
+                                </button>
+                                <button onClick={() => setGitignore(null)}>Cancel</button>
+                            </div>
+                        </>
+                    )}
+                </div>
+            )}
+
+            {recentFiles !== null && (
+                <div className="explorer-recent">
+                    <div className="recent-header">
+                        <span>Recent files</span>
+                        {recentFiles.length > 0 && <button onClick={clearRecentFiles}>Clear</button>}
+                    </div>
+                    {recentFiles.map((recent) => (
+                        <div
+                            key={recent.path}
+                            className="tree-item file"
+                            style={{ paddingLeft: '8px' }}
+                            onClick={() => onFileSelect?.(recent.path)}
+                            title={`${recent.path}\nOpened ${new Date(recent.opened_at).toLocaleString()}`}
+                        >
+                            <span className="tree-name">{relativeToProject(recent.path, projectPath)}</span>
+                        </div>
+                    ))}
+                    {recentFiles.length === 0 && (
+                        <div className="tree-empty" style={{ paddingLeft: '8px' }}>No files opened yet</div>
+                    )}
+                </div>
+            )}
+
             <div className="explorer-project">
                 <span className="project-icon">📁</span>
                 <span className="project-title">{getProjectName()}</span>
             </div>
 
+            <div className="explorer-search">
+                <input
+                    type="text"
+                    placeholder="Search files..."
+                    value={searchQuery}
+                    onChange={(e) => setSearchQuery(e.target.value)}
+                />
+            </div>
+
+            {bookmarks.length > 0 && !searchQuery.trim() && (
+                <div className="explorer-pinned">
+                    {bookmarks.map((bookmark, index) => {
+                        const actions = (
+                            <span className="pinned-actions" onClick={(e) => e.stopPropagation()}>
+                                <button onClick={() => moveBookmark(index, -1)} disabled={index === 0} title="Move up">▲</button>
+                                <button onClick={() => moveBookmark(index, 1)} disabled={index === bookmarks.length - 1} title="Move down">▼</button>
+                                <button onClick={() => updateBookmarks('remove_bookmark', { id: bookmark.id })} title="Unpin">✕</button>
+                            </span>
+                        );
+                        return bookmark.exists ? (
+                            <FileTreeNode
+                                key={`${bookmark.id}@${generation}.${changeCount}${showIgnored ? '+ignored' : ''}`}
+                                entry={{
+                                    name: bookmark.label,
+                                    path: bookmark.path,
+                                    is_dir: bookmark.is_dir,
+                                    extension: bookmark.is_dir ? null : bookmark.path.split('.').pop() ?? null,
+                                    size: null,
+                                    children: null,
+                                }}
+                                level={0}
+                                onFileSelect={onFileSelect}
+                                expandedDirs={expandedDirs}
+                                onToggleDir={handleToggleDir}
+                                showIgnored={showIgnored}
+                                actions={actions}
+                            />
+                        ) : (
+                            <div key={bookmark.id} className="tree-item file" style={{ paddingLeft: '8px' }} title={`${bookmark.relative_path} no longer exists`}>
+                                <span className="tree-icon">⚠</span>
+                                <span className="tree-name broken">{bookmark.label}</span>
+                                {actions}
+                            </div>
+                        );
+                    })}
+                </div>
+            )}
+
             <div className="explorer-tree">
-                {isLoading ? (
+                {searchQuery.trim() ? (
+                    <>
+                        {searchResults?.results.map((match) => (
+                            <div
+                                key={match.path}
+                                className="tree-item file search-result"
+                                style={{ paddingLeft: '8px' }}
+                                onClick={() => onFileSelect?.(match.path)}
+                                title={match.relative_path}
+                            >
+                                <span className="tree-icon">{getFileIcon(match)}</span>
+                                <span className="tree-name">
+                                    <HighlightedName name={match.name} positions={match.positions} />
+                                </span>
+                                <span className="search-path">{match.relative_path}</span>
+                            </div>
+                        ))}
+                        {searchResults && searchResults.results.length === 0 && (
+                            <div className="tree-empty" style={{ paddingLeft: '8px' }}>No matching files</div>
+                        )}
+                        {searchResults?.truncated && (
+                            <div className="tree-empty" style={{ paddingLeft: '8px' }}>More files match; refine the search</div>
+                        )}
+                    </>
+                ) : isLoading ? (
                     <div className="explorer-loading">
                         <span className="loading-spinner" />
                         Loading...
@@ -218,16 +785,27 @@ const FileExplorer: React.FC<FileExplorerProps> = ({ projectPath, onFileSelect }
                         <span>❌</span> {error}
                     </div>
                 ) : (
-                    rootEntries.map((entry) => (
-                        <FileTreeNode
-                            key={entry.path}
-                            entry={entry}
-                            level={0}
-                            onFileSelect={onFileSelect}
-                            expandedDirs={expandedDirs}
-                            onToggleDir={handleToggleDir}
-                        />
-                    ))
+                    <>
+                        {rootEntries.map((entry) => (
+                            <FileTreeNode
+                                key={`${entry.path}@${generation}.${changeCount}${showIgnored ? '+ignored' : ''}`}
+                                entry={entry}
+                                level={0}
+                                onFileSelect={onFileSelect}
+                                expandedDirs={expandedDirs}
+                                onToggleDir={handleToggleDir}
+                                showIgnored={showIgnored}
+                                onPin={pinEntry}
+                            />
+                        ))}
+                        {rootEntries.length < rootTotal && (
+                            <LoadMoreRow
+                                level={0}
+                                remaining={rootTotal - rootEntries.length}
+                                onLoadMore={loadMoreRootEntries}
+                            />
+                        )}
+                    </>
                 )}
             </div>
         </div>
@@ -1,7 +1,61 @@
-import React, { useState, useEffect } from 'react';
+import React, { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import './FileViewer.css';
 
+// Returned by read_file_content
+interface FileContent {
+    kind: 'text' | 'binary' | 'image';
+    text: string | null;
+    base64: string | null;
+    mime: string | null;
+    size: number;
+    encoding: string | null;
+    bom: boolean;
+    lossy: boolean;
+}
+
+// Returned by get_file_info / read_file_range, for files over the 1 MB cap
+interface FileInfo {
+    kind: 'text' | 'binary' | 'image';
+    size: number;
+    encoding: string | null;
+    line_count: number;
+    longest_line: number;
+}
+
+// Returned by get_path_metadata, for the status bar
+interface PathMetadata {
+    size: number;
+    modified: string | null;
+    created: string | null;
+    readonly: boolean;
+    mode: number | null;
+    line_endings: 'lf' | 'crlf' | 'mixed' | 'none' | null;
+    git_tracked: boolean | null;
+}
+
+interface FileRange {
+    first_line: number | null;
+    lines: string[];
+    cut_lines: number[];
+    byte_start: number;
+    byte_end: number;
+    eof: boolean;
+}
+
+// Lines fetched per page of a large file
+const PAGE_LINES = 1000;
+
+// Encodings a text file can be reopened in (labels read_file_content accepts)
+const ENCODINGS = ['UTF-8', 'UTF-16LE', 'UTF-16BE', 'windows-1252', 'Shift_JIS', 'EUC-JP'];
+
+const formatSize = (bytes: number): string => {
+    if (bytes < 1024) return `${bytes} B`;
+    if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+    return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+};
+
 interface FileViewerProps {
     filePath: string | null;
     onClose: () => void;
@@ -60,23 +114,67 @@ const getFileIcon = (path: string): string => {
 
 const FileViewer: React.FC<FileViewerProps> = ({ filePath, onClose }) => {
     const [content, setContent] = useState<string>('');
+    // Set for images and other binary files, which have no text to show
+    const [preview, setPreview] = useState<FileContent | null>(null);
     const [isLoading, setIsLoading] = useState(false);
     const [error, setError] = useState<string | null>(null);
     const [lineNumbers, setLineNumbers] = useState<number[]>([]);
+    // Large text file read a page at a time as the view scrolls down
+    const [paged, setPaged] = useState<{ info: FileInfo; loaded: number; eof: boolean } | null>(null);
+    const isLoadingPage = useRef(false);
+    // Encoding the text was decoded with, and whether bytes were replaced
+    const [textEncoding, setTextEncoding] = useState<{ name: string; lossy: boolean } | null>(null);
+    // Encoding chosen for a file instead of the detected one
+    const [chosenEncoding, setChosenEncoding] = useState<{ path: string; encoding: string } | null>(null);
+    const forcedEncoding = chosenEncoding?.path === filePath ? chosenEncoding.encoding : null;
+    const [metadata, setMetadata] = useState<PathMetadata | null>(null);
 
     useEffect(() => {
         if (filePath) {
             loadFileContent();
         }
-    }, [filePath]);
+    }, [filePath, forcedEncoding]);
+
+    // Reload when the open file changes on disk
+    useEffect(() => {
+        if (!filePath) return;
+        const unlisten = listen<{ modified: string[]; rescan: boolean }>('fs-changed', (event) => {
+            if (event.payload.rescan || event.payload.modified.includes(filePath)) {
+                loadFileContent();
+            }
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [filePath, forcedEncoding]);
 
     const loadFileContent = async () => {
         if (!filePath) return;
 
         setIsLoading(true);
         setError(null);
+        invoke<PathMetadata>('get_path_metadata', { path: filePath })
+            .then(setMetadata)
+            .catch(() => setMetadata(null));
         try {
-            const fileContent = await invoke<string>('read_file_content', { path: filePath });
+            const file = await invoke<FileContent>('read_file_content', { path: filePath, encoding: forcedEncoding });
+            setPreview(file.kind === 'text' ? null : file);
+            setTextEncoding(file.encoding ? { name: file.encoding, lossy: file.lossy } : null);
+            if (file.kind === 'text' && file.text === null) {
+                const info = await invoke<FileInfo>('get_file_info', { path: filePath });
+                const range = await invoke<FileRange>('read_file_range', {
+                    path: filePath,
+                    offsetLines: 0,
+                    lineCount: PAGE_LINES,
+                    encoding: forcedEncoding,
+                });
+                setPaged({ info, loaded: range.lines.length, eof: range.eof });
+                setContent(range.lines.join('\n'));
+                setLineNumbers(range.lines.map((_, i) => i + 1));
+                return;
+            }
+            const fileContent = file.text ?? '';
+            setPaged(null);
             setContent(fileContent);
             // Generate line numbers
             const lines = fileContent.split('\n');
@@ -84,6 +182,9 @@ const FileViewer: React.FC<FileViewerProps> = ({ filePath, onClose }) => {
         } catch (err) {
             console.error('Failed to read file:', err);
             setError(String(err));
+            setPreview(null);
+            setPaged(null);
+            setTextEncoding(null);
             setContent('');
             setLineNumbers([]);
         } finally {
@@ -91,6 +192,30 @@ const FileViewer: React.FC<FileViewerProps> = ({ filePath, onClose }) => {
         }
     };
 
+    // Near the bottom of a paged file: append its next lines
+    const loadNextPage = async (event: React.UIEvent<HTMLDivElement>) => {
+        const view = event.currentTarget;
+        if (!filePath || !paged || paged.eof || isLoadingPage.current) return;
+        if (view.scrollTop + view.clientHeight < view.scrollHeight - view.clientHeight) return;
+
+        isLoadingPage.current = true;
+        try {
+            const range = await invoke<FileRange>('read_file_range', {
+                path: filePath,
+                offsetLines: paged.loaded,
+                lineCount: PAGE_LINES,
+                encoding: forcedEncoding,
+            });
+            setContent((text) => (range.lines.length ? `${text}\n${range.lines.join('\n')}` : text));
+            setLineNumbers((numbers) => [...numbers, ...range.lines.map((_, i) => paged.loaded + i + 1)]);
+            setPaged({ ...paged, loaded: paged.loaded + range.lines.length, eof: range.eof });
+        } catch (err) {
+            console.error('Failed to read file range:', err);
+        } finally {
+            isLoadingPage.current = false;
+        }
+    };
+
     if (!filePath) {
         return (
             <div className="file-viewer empty">
@@ -119,11 +244,37 @@ const FileViewer: React.FC<FileViewerProps> = ({ filePath, onClose }) => {
             {/* Breadcrumb */}
             <div className="viewer-breadcrumb">
                 <span className="breadcrumb-path">{filePath}</span>
+                {textEncoding && (
+                    <select
+                        className="breadcrumb-encoding"
+                        value={textEncoding.name}
+                        onChange={(e) => setChosenEncoding({ path: filePath, encoding: e.target.value })}
+                        title="Reopen with encoding"
+                    >
+                        {[...new Set([textEncoding.name, ...ENCODINGS])].map((name) => (
+                            <option key={name} value={name}>{name}</option>
+                        ))}
+                    </select>
+                )}
                 <span className="breadcrumb-lang">{language}</span>
             </div>
 
+            {textEncoding?.lossy && (
+                <div className="viewer-paged">
+                    Some bytes are not valid {textEncoding.name} and are shown as �; try another encoding
+                </div>
+            )}
+
+            {paged && (
+                <div className="viewer-paged">
+                    Large file ({formatSize(paged.info.size)}): showing {paged.loaded.toLocaleString()} of{' '}
+                    {paged.info.line_count.toLocaleString()} lines
+                    {paged.info.longest_line > 64 * 1024 && ', lines over 64 KB are cut'}
+                </div>
+            )}
+
             {/* Content Area */}
-            <div className="viewer-content">
+            <div className="viewer-content" onScroll={loadNextPage}>
                 {isLoading ? (
                     <div className="viewer-loading">
                         <span className="loading-spinner" />
@@ -135,6 +286,17 @@ const FileViewer: React.FC<FileViewerProps> = ({ filePath, onClose }) => {
                         <p>Failed to load file</p>
                         <p className="error-message">{error}</p>
                     </div>
+                ) : preview?.kind === 'image' && preview.base64 ? (
+                    <div className="viewer-preview">
+                        <img src={`data:${preview.mime};base64,${preview.base64}`} alt={getFileName(filePath)} />
+                        <p className="preview-meta">{preview.mime} · {formatSize(preview.size)}</p>
+                    </div>
+                ) : preview ? (
+                    <div className="viewer-preview">
+                        <span className="preview-icon">{preview.kind === 'image' ? '🖼️' : '📦'}</span>
+                        <p>{preview.kind === 'image' ? 'Image too large to preview' : 'Binary file'}</p>
+                        <p className="preview-meta">{preview.mime ?? 'No text preview'} · {formatSize(preview.size)}</p>
+                    </div>
                 ) : (
                     <div className="code-container">
                         {/* Line Numbers */}
@@ -150,6 +312,24 @@ const FileViewer: React.FC<FileViewerProps> = ({ filePath, onClose }) => {
                     </div>
                 )}
             </div>
+
+            {metadata && (
+                <div className="viewer-status">
+                    {metadata.line_endings && metadata.line_endings !== 'none' && (
+                        <span>{metadata.line_endings === 'mixed' ? 'Mixed line endings' : metadata.line_endings.toUpperCase()}</span>
+                    )}
+                    {textEncoding && <span>{textEncoding.name}</span>}
+                    <span>{formatSize(metadata.size)}</span>
+                    {metadata.readonly && <span className="status-warning">Read-only</span>}
+                    {metadata.mode !== null && <span>{metadata.mode.toString(8).padStart(4, '0')}</span>}
+                    {metadata.git_tracked !== null && <span>{metadata.git_tracked ? 'Tracked' : 'Untracked'}</span>}
+                    {metadata.modified && (
+                        <span title={metadata.created ? `Created ${new Date(metadata.created).toLocaleString()}` : undefined}>
+                            Modified {new Date(metadata.modified).toLocaleString()}
+                        </span>
+                    )}
+                </div>
+            )}
         </div>
     );
 };
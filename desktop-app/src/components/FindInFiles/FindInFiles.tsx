@@ -0,0 +1,201 @@
+import React, { useState, useEffect, useRef } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import './FindInFiles.css';
+
+// Matches ContentMatch in src-tauri/src/content_search.rs
+interface ContentMatch {
+    path: string;
+    line_number: number;
+    line_text: string;
+    match_start: number;
+    match_end: number;
+}
+
+// Matches ContentSearchBatch in src-tauri/src/content_search.rs
+interface ContentSearchBatch {
+    search_id: string;
+    operation_id: string;
+    matches: ContentMatch[];
+}
+
+// Matches ContentSearchSummary in src-tauri/src/content_search.rs
+interface ContentSearchSummary {
+    search_id: string;
+    matches: number;
+    files_searched: number;
+    files_skipped: number;
+    truncated: boolean;
+    cancelled: boolean;
+}
+
+// Matches ContentSearchError in src-tauri/src/content_search.rs
+interface ContentSearchError {
+    kind: 'empty_query' | 'invalid_regex' | 'io';
+    message: string;
+}
+
+interface FindInFilesProps {
+    projectPath: string | null;
+    onFileSelect?: (path: string) => void;
+}
+
+// Wait for typing to pause before searching
+const DEBOUNCE_MS = 300;
+
+const getFileName = (path: string): string => {
+    const parts = path.replace(/\\/g, '/').split('/');
+    return parts[parts.length - 1] || path;
+};
+
+// Line text with the matched characters highlighted
+const MatchLine: React.FC<{ match: ContentMatch }> = ({ match }) => {
+    const chars = Array.from(match.line_text);
+    return (
+        <span className="find-line">
+            {chars.slice(0, match.match_start).join('').trimStart()}
+            <b className="find-hit">{chars.slice(match.match_start, match.match_end).join('')}</b>
+            {chars.slice(match.match_end).join('')}
+        </span>
+    );
+};
+
+const FindInFiles: React.FC<FindInFilesProps> = ({ projectPath, onFileSelect }) => {
+    const [isCollapsed, setIsCollapsed] = useState(true);
+    const [query, setQuery] = useState('');
+    const [regex, setRegex] = useState(false);
+    const [caseSensitive, setCaseSensitive] = useState(false);
+    const [includeGlob, setIncludeGlob] = useState('');
+    const [excludeGlob, setExcludeGlob] = useState('');
+    const [matches, setMatches] = useState<ContentMatch[]>([]);
+    const [summary, setSummary] = useState<ContentSearchSummary | null>(null);
+    const [error, setError] = useState<string | null>(null);
+    // Search whose batches are shown, and its operation (to cancel it)
+    const searchId = useRef<string | null>(null);
+    const operationId = useRef<string | null>(null);
+
+    useEffect(() => {
+        const unlisten = listen<ContentSearchBatch>('content-search-results', (event) => {
+            if (event.payload.search_id !== searchId.current) return;
+            operationId.current = event.payload.operation_id;
+            setMatches(prev => [...prev, ...event.payload.matches]);
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    useEffect(() => {
+        if (operationId.current) {
+            invoke('cancel_operation', { id: operationId.current }).catch(() => {});
+            operationId.current = null;
+        }
+        setMatches([]);
+        setSummary(null);
+        setError(null);
+        if (!projectPath || !query) {
+            searchId.current = null;
+            return;
+        }
+
+        const id = crypto.randomUUID();
+        const timer = setTimeout(() => {
+            searchId.current = id;
+            invoke<ContentSearchSummary>('search_in_files', {
+                root: projectPath,
+                query,
+                options: {
+                    regex,
+                    case_sensitive: caseSensitive,
+                    include_glob: includeGlob || null,
+                    exclude_glob: excludeGlob || null,
+                },
+                searchId: id,
+            })
+                .then((result) => {
+                    if (searchId.current === id) setSummary(result);
+                })
+                .catch((err: ContentSearchError) => {
+                    if (searchId.current === id) setError(err.message ?? String(err));
+                });
+        }, DEBOUNCE_MS);
+        return () => clearTimeout(timer);
+    }, [projectPath, query, regex, caseSensitive, includeGlob, excludeGlob]);
+
+    return (
+        <div className={`find-panel ${isCollapsed ? 'collapsed' : ''}`}>
+            <div className="find-header" onClick={() => setIsCollapsed(!isCollapsed)}>
+                <span className={`collapse-arrow ${isCollapsed ? '' : 'expanded'}`}>▶</span>
+                <span className="find-title">FIND IN FILES</span>
+                {matches.length > 0 && <span className="find-count">{matches.length}</span>}
+            </div>
+
+            {!isCollapsed && (
+                <div className="find-content">
+                    <div className="find-query">
+                        <input
+                            type="text"
+                            placeholder="Search"
+                            value={query}
+                            onChange={(e) => setQuery(e.target.value)}
+                        />
+                        <button
+                            className={`find-toggle ${caseSensitive ? 'active' : ''}`}
+                            onClick={() => setCaseSensitive(!caseSensitive)}
+                            title="Match case"
+                        >
+                            Aa
+                        </button>
+                        <button
+                            className={`find-toggle ${regex ? 'active' : ''}`}
+                            onClick={() => setRegex(!regex)}
+                            title="Use regular expression"
+                        >
+                            .*
+                        </button>
+                    </div>
+                    <input
+                        className="find-glob"
+                        type="text"
+                        placeholder="Files to include (e.g. *.ts, src/**)"
+                        value={includeGlob}
+                        onChange={(e) => setIncludeGlob(e.target.value)}
+                    />
+                    <input
+                        className="find-glob"
+                        type="text"
+                        placeholder="Files to exclude"
+                        value={excludeGlob}
+                        onChange={(e) => setExcludeGlob(e.target.value)}
+                    />
+
+                    {error && <div className="find-error">{error}</div>}
+                    {summary && (
+                        <div className="find-summary">
+                            {summary.matches} results in {summary.files_searched} files
+                            {summary.truncated && ' (more not shown)'}
+                        </div>
+                    )}
+
+                    <div className="find-results">
+                        {matches.map((match, index) => (
+                            <div
+                                key={index}
+                                className="find-item"
+                                onClick={() => onFileSelect?.(match.path)}
+                                title={match.path}
+                            >
+                                <span className="find-location">
+                                    {getFileName(match.path)}:{match.line_number}
+                                </span>
+                                <MatchLine match={match} />
+                            </div>
+                        ))}
+                    </div>
+                </div>
+            )}
+        </div>
+    );
+};
+
+export default FindInFiles;
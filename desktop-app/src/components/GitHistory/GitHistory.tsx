@@ -0,0 +1,233 @@
+import React, { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import './GitHistory.css';
+
+// Matches CommitFileStat in src-tauri/src/git_log.rs
+interface CommitFileStat {
+    path: string;
+    old_path: string | null;
+    lines_added: number;
+    lines_removed: number;
+    binary: boolean;
+}
+
+// Matches CommitSummary in src-tauri/src/git_log.rs
+interface CommitSummary {
+    hash: string;
+    author: string;
+    email: string;
+    date: string;
+    subject: string;
+    files: CommitFileStat[];
+    lines_added: number;
+    lines_removed: number;
+}
+
+// Matches GitLogPage in src-tauri/src/git_log.rs
+interface GitLogPage {
+    commits: CommitSummary[];
+    has_more: boolean;
+}
+
+interface DiffLine {
+    kind: 'context' | 'added' | 'removed';
+    old_line: number | null;
+    new_line: number | null;
+    text: string;
+}
+
+interface DiffHunk {
+    old_start: number;
+    old_lines: number;
+    new_start: number;
+    new_lines: number;
+    section: string;
+    lines: DiffLine[];
+}
+
+// Matches CommitFileDiff in src-tauri/src/git_log.rs
+interface CommitFileDiff {
+    path: string;
+    old_path: string | null;
+    status: string;
+    binary: boolean;
+    hunks: DiffHunk[];
+    lines_added: number;
+    lines_removed: number;
+    truncated: boolean;
+}
+
+// Matches CommitDiff in src-tauri/src/git_log.rs
+interface CommitDiff {
+    hash: string;
+    parent: string | null;
+    author: string;
+    email: string;
+    date: string;
+    subject: string;
+    message: string;
+    files: CommitFileDiff[];
+}
+
+interface GitHistoryProps {
+    projectPath: string | null;
+}
+
+const PAGE_SIZE = 50;
+
+const errorMessage = (error: unknown): string => (error as { message?: string })?.message ?? String(error);
+
+const GitHistory: React.FC<GitHistoryProps> = ({ projectPath }) => {
+    const [isCollapsed, setIsCollapsed] = useState(true);
+    const [pathFilter, setPathFilter] = useState('');
+    const [commits, setCommits] = useState<CommitSummary[]>([]);
+    const [hasMore, setHasMore] = useState(false);
+    const [error, setError] = useState<string | null>(null);
+    const [isLoading, setIsLoading] = useState(false);
+    // Commit expanded in the list, with its diff once loaded
+    const [openCommit, setOpenCommit] = useState<{ hash: string; diff?: CommitDiff; error?: string } | null>(null);
+
+    const loadPage = async (skip: number) => {
+        setIsLoading(true);
+        try {
+            const page = await invoke<GitLogPage>('get_git_log', {
+                limit: PAGE_SIZE,
+                skip,
+                pathFilter: pathFilter.trim() || null,
+            });
+            setCommits((previous) => (skip === 0 ? page.commits : [...previous, ...page.commits]));
+            setHasMore(page.has_more);
+            setError(null);
+        } catch (e) {
+            setCommits([]);
+            setHasMore(false);
+            setError(errorMessage(e));
+        } finally {
+            setIsLoading(false);
+        }
+    };
+
+    useEffect(() => {
+        setOpenCommit(null);
+        if (projectPath && !isCollapsed) loadPage(0);
+    }, [projectPath, isCollapsed]);
+
+    useEffect(() => {
+        if (isCollapsed) return;
+        // New commits, checkouts and fetches
+        const unlisten = listen('git-status-updated', () => loadPage(0));
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [isCollapsed, pathFilter]);
+
+    const toggleCommit = async (hash: string, full = false) => {
+        if (openCommit?.hash === hash && !full) {
+            setOpenCommit(null);
+            return;
+        }
+        if (!full) setOpenCommit({ hash });
+        try {
+            const diff = await invoke<CommitDiff>('get_commit_diff', { hash, full });
+            setOpenCommit({ hash, diff });
+        } catch (e) {
+            setOpenCommit({ hash, error: errorMessage(e) });
+        }
+    };
+
+    const renderDiff = () => {
+        if (!openCommit) return null;
+        const { diff, error } = openCommit;
+        if (error) return <div className="history-message">{error}</div>;
+        if (!diff) return <div className="history-message">Loading commit...</div>;
+        const truncated = diff.files.some((f) => f.truncated);
+        return (
+            <div className="history-diff">
+                {diff.message !== diff.subject && <pre className="history-body">{diff.message}</pre>}
+                {diff.files.map((file) => (
+                    <div key={file.path} className="history-file">
+                        <div className="history-file-title">
+                            <span className={`history-status status-${file.status}`}>{file.status}</span>
+                            {file.old_path ? `${file.old_path} → ${file.path}` : file.path}
+                        </div>
+                        {file.binary ? (
+                            <div className="history-message">Binary file changed</div>
+                        ) : (
+                            file.hunks.map((hunk, h) => (
+                                <div key={h} className="history-hunk">
+                                    <div className="history-hunk-header">
+                                        @@ -{hunk.old_start},{hunk.old_lines} +{hunk.new_start},{hunk.new_lines} @@ {hunk.section}
+                                    </div>
+                                    {hunk.lines.map((line, i) => (
+                                        <div key={i} className={`history-line history-${line.kind}`}>
+                                            {line.kind === 'added' ? '+' : line.kind === 'removed' ? '-' : ' '}
+                                            {line.text}
+                                        </div>
+                                    ))}
+                                </div>
+                            ))
+                        )}
+                    </div>
+                ))}
+                {truncated && (
+                    <button className="history-more" onClick={() => toggleCommit(diff.hash, true)}>
+                        Show full diff
+                    </button>
+                )}
+            </div>
+        );
+    };
+
+    if (!projectPath) return null;
+
+    return (
+        <div className={`history-panel ${isCollapsed ? 'collapsed' : ''}`}>
+            <div className="history-header" onClick={() => setIsCollapsed(!isCollapsed)}>
+                <span className={`collapse-arrow ${isCollapsed ? '' : 'expanded'}`}>▶</span>
+                <span className="history-title">HISTORY</span>
+            </div>
+
+            {!isCollapsed && (
+                <div className="history-content">
+                    <input
+                        className="history-filter"
+                        value={pathFilter}
+                        onChange={(e) => setPathFilter(e.target.value)}
+                        onKeyDown={(e) => e.key === 'Enter' && loadPage(0)}
+                        placeholder="Filter by path (e.g. src/api), Enter to apply"
+                    />
+                    {error && <div className="history-message">{error}</div>}
+                    {!error && commits.length === 0 && !isLoading && <div className="history-message">No commits</div>}
+                    {commits.map((commit) => (
+                        <React.Fragment key={commit.hash}>
+                            <div
+                                className={`history-commit ${openCommit?.hash === commit.hash ? 'active' : ''}`}
+                                onClick={() => toggleCommit(commit.hash)}
+                                title={`${commit.hash}\n${commit.author} <${commit.email}>\n${commit.files.map((f) => f.path).join('\n')}`}
+                            >
+                                <span className="history-subject">{commit.subject}</span>
+                                <span className="history-meta">
+                                    {commit.author} · {new Date(commit.date).toLocaleString()}
+                                </span>
+                                <span className="history-stats">
+                                    {commit.files.length} file{commit.files.length === 1 ? '' : 's'}
+                                    <span className="stat-added">+{commit.lines_added}</span>
+                                    <span className="stat-removed">-{commit.lines_removed}</span>
+                                </span>
+                            </div>
+                            {openCommit?.hash === commit.hash && renderDiff()}
+                        </React.Fragment>
+                    ))}
+                    {hasMore && (
+                        <button className="history-more" disabled={isLoading} onClick={() => loadPage(commits.length)}>
+                            {isLoading ? 'Loading...' : 'Load more'}
+                        </button>
+                    )}
+                </div>
+            )}
+        </div>
+    );
+};
+
+export default GitHistory;
@@ -8,9 +8,29 @@ interface SidebarProps {
     onProjectChange?: (path: string | null) => void;
 }
 
+/** Error returned by set_project_path / open_project_dialog */
+interface ProjectSwitchError {
+    kind: 'unsaved_changes' | 'invalid_path' | 'io';
+    message: string;
+    target?: string;
+    files?: string[];
+}
+
+/** Entry of get_recent_projects (pinned first, then most recent first) */
+interface RecentProject {
+    path: string;
+    name: string;
+    last_opened: string | null;
+    pinned: boolean;
+    /** The directory no longer exists */
+    missing: boolean;
+}
+
 const Sidebar: React.FC<SidebarProps> = ({ activeItem, onItemClick, onProjectChange }) => {
     const [currentProject, setCurrentProject] = useState<string | null>(null);
     const [isSelectingProject, setIsSelectingProject] = useState(false);
+    const [recentProjects, setRecentProjects] = useState<RecentProject[]>([]);
+    const [showRecent, setShowRecent] = useState(false);
 
     useEffect(() => {
         loadCurrentProject();
@@ -27,21 +47,81 @@ const Sidebar: React.FC<SidebarProps> = ({ activeItem, onItemClick, onProjectCha
         }
     };
 
+    const projectOpened = (path: string) => {
+        setCurrentProject(path);
+        onProjectChange?.(path);
+    };
+
+    /** Offer to force a switch refused for unsaved changes */
+    const handleSwitchError = async (error: unknown) => {
+        const switchError = error as ProjectSwitchError;
+        if (switchError?.kind === 'unsaved_changes' && switchError.target) {
+            const files = (switchError.files ?? []).join('\n');
+            if (window.confirm(`${switchError.message}:\n\n${files}\n\nSwitch project anyway?`)) {
+                try {
+                    projectOpened(await invoke<string>('set_project_path', { path: switchError.target, force: true }));
+                } catch (forceError) {
+                    console.error('Failed to switch project:', forceError);
+                }
+            }
+        } else {
+            console.error('Failed to open project:', error);
+        }
+    };
+
     const handleOpenProject = async () => {
         setIsSelectingProject(true);
         try {
             const path = await invoke<string | null>('open_project_dialog');
             if (path) {
-                setCurrentProject(path);
-                onProjectChange?.(path);
+                projectOpened(path);
             }
         } catch (error) {
-            console.error('Failed to open project:', error);
+            await handleSwitchError(error);
         } finally {
             setIsSelectingProject(false);
         }
     };
 
+    const toggleRecent = async () => {
+        if (showRecent) {
+            setShowRecent(false);
+            return;
+        }
+        try {
+            setRecentProjects(await invoke<RecentProject[]>('get_recent_projects'));
+            setShowRecent(true);
+        } catch (error) {
+            console.error('Failed to load recent projects:', error);
+        }
+    };
+
+    const handleOpenRecent = async (project: RecentProject) => {
+        if (project.missing) return;
+        setShowRecent(false);
+        try {
+            projectOpened(await invoke<string>('set_project_path', { path: project.path }));
+        } catch (error) {
+            await handleSwitchError(error);
+        }
+    };
+
+    const handlePinRecent = async (project: RecentProject) => {
+        try {
+            setRecentProjects(await invoke<RecentProject[]>('pin_project', { path: project.path, pinned: !project.pinned }));
+        } catch (error) {
+            console.error('Failed to pin project:', error);
+        }
+    };
+
+    const handleRemoveRecent = async (project: RecentProject) => {
+        try {
+            setRecentProjects(await invoke<RecentProject[]>('remove_recent_project', { path: project.path }));
+        } catch (error) {
+            console.error('Failed to remove recent project:', error);
+        }
+    };
+
     const getProjectName = (path: string | null) => {
         if (!path) return null;
         const parts = path.replace(/\\/g, '/').split('/');
@@ -78,6 +158,36 @@ const Sidebar: React.FC<SidebarProps> = ({ activeItem, onItemClick, onProjectCha
                 >
                     <FolderOpenIcon />
                 </button>
+                <button className="recent-projects-btn" onClick={toggleRecent} title="Recent projects">
+                    <RecentIcon />
+                </button>
+                {showRecent && (
+                    <div className="recent-projects">
+                        {recentProjects.length === 0 && <div className="recent-project-empty">No recent projects</div>}
+                        {recentProjects.map((project) => (
+                            <div
+                                key={project.path}
+                                className={`recent-project ${project.missing ? 'missing' : ''} ${project.path === currentProject ? 'current' : ''}`}
+                                title={project.missing ? `${project.path} (folder not found)` : project.path}
+                            >
+                                <button className="recent-project-open" onClick={() => handleOpenRecent(project)} disabled={project.missing}>
+                                    <span className="recent-project-name">{project.name}</span>
+                                    <span className="recent-project-path">{project.missing ? 'Folder not found' : project.path}</span>
+                                </button>
+                                <button
+                                    className={`recent-project-action ${project.pinned ? 'pinned' : ''}`}
+                                    onClick={() => handlePinRecent(project)}
+                                    title={project.pinned ? 'Unpin' : 'Pin'}
+                                >
+                                    📌
+                                </button>
+                                <button className="recent-project-action" onClick={() => handleRemoveRecent(project)} title="Remove from list">
+                                    ✕
+                                </button>
+                            </div>
+                        ))}
+                    </div>
+                )}
                 {currentProject && (
                     <div className="project-indicator" title={currentProject}>
                         <span className="project-dot" />
@@ -125,6 +235,14 @@ const FolderOpenIcon = () => (
     </svg>
 );
 
+const RecentIcon = () => (
+    <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
+        <path d="M3 12a9 9 0 1 0 3-6.7L3 8" />
+        <path d="M3 3v5h5" />
+        <path d="M12 7v5l3 3" />
+    </svg>
+);
+
 const DashboardIcon = () => (
     <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
         <circle cx="12" cy="12" r="10" />
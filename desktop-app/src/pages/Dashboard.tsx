@@ -10,6 +10,8 @@ import CacheManager from '../components/CacheManager/CacheManager';
 import ServiceTools from '../components/ServiceTools/ServiceTools';
 import AddAccountModal from '../components/AddAccountModal/AddAccountModal';
 import { useAntigravityServer, useQuotaData, useAccounts, useOAuth } from '../hooks';
+import { VersionInfo, formatVersionInfo } from '../types/version';
+import { ProjectContext, SessionStats } from '../types/execution';
 import {
     AgentData,
     QuotaDisplayItem,
@@ -290,8 +292,8 @@ const Dashboard: React.FC = () => {
 
     const loadStats = async () => {
         try {
-            const result = await invoke<string>('get_stats');
-            setStats(result);
+            const result = await invoke<SessionStats>('get_stats');
+            setStats(result.text);
         } catch (error) {
             console.error('Failed to load stats:', error);
         }
@@ -299,8 +301,8 @@ const Dashboard: React.FC = () => {
 
     const loadContext = async () => {
         try {
-            const result = await invoke<string>('get_context');
-            _setContext(result);
+            const result = await invoke<ProjectContext>('get_context');
+            _setContext(result.content);
         } catch (error) {
             console.error('Failed to load context:', error);
         }
@@ -322,7 +324,7 @@ const Dashboard: React.FC = () => {
     const handleRestartServer = async () => {
         showNotification('🔄 Đang restart services...', 'info');
         try {
-            await invoke<string>('get_stats');
+            await invoke<SessionStats>('get_stats');
             showNotification('✅ Services restarted successfully!', 'success');
             loadStats();
         } catch (error) {
@@ -347,15 +349,22 @@ const Dashboard: React.FC = () => {
     const handleRunDiagnostics = async () => {
         showNotification('🔍 Đang chạy diagnostics...', 'info');
         try {
-            const statsResult = await invoke<string>('get_stats');
-            const contextResult = await invoke<string>('get_context');
-
-            console.log('=== DIAGNOSTICS REPORT ===');
-            console.log('Stats:', statsResult);
-            console.log('Context:', contextResult);
-
-            setStats(statsResult);
-            _setContext(contextResult);
+            const versionInfo = await invoke<VersionInfo>('get_version_info');
+            const statsResult = await invoke<SessionStats>('get_stats');
+            const contextResult = await invoke<ProjectContext>('get_context');
+
+            const report = [
+                '=== DIAGNOSTICS REPORT ===',
+                formatVersionInfo(versionInfo),
+                '',
+                `Stats: ${statsResult.text}`,
+                `Context: ${contextResult.content}`,
+            ].join('\n');
+            console.log(report);
+            await navigator.clipboard?.writeText(report).catch(() => undefined);
+
+            setStats(statsResult.text);
+            _setContext(contextResult.content);
             showNotification('✅ Diagnostics hoàn thành! Xem console.', 'success');
         } catch (error) {
             showNotification(`❌ Diagnostics failed: ${error}`, 'error');
@@ -28,6 +28,8 @@ import {
     useActiveView
 } from '../../stores';
 import { SavedAccount } from '../../types';
+import { VersionInfo, formatVersionInfo } from '../../types/version';
+import { SessionStats } from '../../types/execution';
 
 const Dashboard: React.FC = () => {
     // Zustand state
@@ -73,7 +75,7 @@ const Dashboard: React.FC = () => {
     const handleRestartServer = useCallback(async () => {
         showNotification('🔄 Đang restart services...', 'info');
         try {
-            await invoke<string>('get_stats');
+            await invoke<SessionStats>('get_stats');
             showNotification('✅ Services restarted successfully!', 'success');
             loadStats();
         } catch (error) {
@@ -91,9 +93,16 @@ const Dashboard: React.FC = () => {
     const handleRunDiagnostics = useCallback(async () => {
         showNotification('🔍 Đang chạy diagnostics...', 'info');
         try {
-            const statsResult = await invoke<string>('get_stats');
-            console.log('=== DIAGNOSTICS REPORT ===');
-            console.log('Stats:', statsResult);
+            const versionInfo = await invoke<VersionInfo>('get_version_info');
+            const statsResult = await invoke<SessionStats>('get_stats');
+            const report = [
+                '=== DIAGNOSTICS REPORT ===',
+                formatVersionInfo(versionInfo),
+                '',
+                `Stats: ${statsResult.text}`,
+            ].join('\n');
+            console.log(report);
+            await navigator.clipboard?.writeText(report).catch(() => undefined);
             showNotification('✅ Diagnostics hoàn thành! Xem console.', 'success');
         } catch (error) {
             showNotification(`❌ Diagnostics failed: ${error}`, 'error');
@@ -11,6 +11,7 @@ import {
     QuotaDisplayItem,
     TokenUsageData,
 } from '../../../types';
+import { SessionStats } from '../../../types/execution';
 import { mockQuotas, mockTokenUsage, mockChartData } from '../mockData';
 
 // Helper functions
@@ -46,8 +47,8 @@ export function useDashboardData() {
     // Load stats
     const loadStats = useCallback(async () => {
         try {
-            const result = await invoke<string>('get_stats');
-            setStats(result);
+            const result = await invoke<SessionStats>('get_stats');
+            setStats(result.text);
         } catch (error) {
             console.error('Failed to load stats:', error);
         }
@@ -28,6 +28,7 @@ const SkillFactory: React.FC = () => {
     const [researchResults, setResearchResults] = useState<ResearchResult | null>(null);
     const [isResearching, setIsResearching] = useState(false);
     const [generatedContent, setGeneratedContent] = useState<string>('');
+    const [provenance, setProvenance] = useState<any>(null);
     const [isGenerating, setIsGenerating] = useState(false);
 
     // Generate SKILL.md content from research results
@@ -158,13 +159,19 @@ ${researchResults.sources.map(source => `- [${source.title}](${source.url})`).jo
                     tools: result.tools,
                     patterns: result.patterns,
                     sources: [
-                        { title: 'Generated by Gemini 1.5 Flash AI', url: 'https://ai.google.dev/' },
+                        { title: `Generated by ${result.provenance?.model ?? 'Gemini'}`, url: 'https://ai.google.dev/' },
                         { title: `Best practices for ${intent.name}`, url: '#' }
                     ]
                 });
 
                 // Set the generated content directly
                 setGeneratedContent(result.skill_content);
+                setProvenance(result.provenance);
+
+                const budget = result.input_budget;
+                if (budget?.context_summarized || budget?.context_truncated) {
+                    alert(`ℹ️ Ngữ cảnh bổ sung quá dài (~${budget.original_context_tokens} tokens) nên đã được ${budget.context_summarized ? 'tóm tắt' : 'cắt bớt'} để vừa giới hạn ${budget.limit_tokens} tokens của Gemini.`);
+                }
             } else {
                 throw new Error(result.error || 'Unknown error');
             }
@@ -172,7 +179,7 @@ ${researchResults.sources.map(source => `- [${source.title}](${source.url})`).jo
             setIsResearching(false);
         } catch (error: any) {
             console.error('AI Generation failed:', error);
-            const errorMsg = error?.toString() || 'Unknown error';
+            const errorMsg = error?.message ?? error?.toString() ?? 'Unknown error';
 
             // Show specific error - NO silent fallback
             setIsResearching(false);
@@ -183,6 +190,13 @@ ${researchResults.sources.map(source => `- [${source.title}](${source.url})`).jo
                 return;
             }
 
+            if (error?.kind === 'input_too_large') {
+                const excess = error.measured_tokens - error.limit_tokens;
+                alert(`📏 ${errorMsg}\n\nCần bỏ bớt khoảng ${excess} tokens (~${excess * 4} ký tự) trong phần ngữ cảnh.`);
+                setCurrentStage('intent');
+                return;
+            }
+
             alert(`❌ Lỗi tạo skill với AI:\n\n${errorMsg}`);
             setCurrentStage('intent');
         }
@@ -377,11 +391,30 @@ ${researchResults.sources.map(source => `- [${source.title}](${source.url})`).jo
             URL.revokeObjectURL(url);
         };
 
+        const handleSave = async () => {
+            if (!generatedContent || !provenance) {
+                alert('⚠️ Nội dung skill chưa sẵn sàng.\n\nVui lòng thử lại từ bước 1.');
+                return;
+            }
+            try {
+                const { invoke } = await import('@tauri-apps/api/core');
+                const skill: any = await invoke('save_generated_skill', {
+                    name: intent.name,
+                    content: generatedContent,
+                    provenance
+                });
+                alert(`✅ Đã lưu skill "${skill.name}" (kèm generation.json)`);
+            } catch (error: any) {
+                alert(`❌ Không thể lưu skill:\n\n${error?.message ?? error}`);
+            }
+        };
+
         const handleReset = () => {
             setCurrentStage('intent');
             setIntent({ name: '', description: '', purpose: '', context: '' });
             setResearchResults(null);
             setGeneratedContent('');
+            setProvenance(null);
             setIsResearching(false);
             setIsGenerating(false);
         };
@@ -424,6 +457,9 @@ ${researchResults.sources.map(source => `- [${source.title}](${source.url})`).jo
                         <button className="btn btn-secondary" onClick={handleReset}>
                             🔄 Tạo skill mới
                         </button>
+                        <button className="btn btn-secondary" onClick={handleSave} disabled={!provenance}>
+                            💾 Lưu vào Skills
+                        </button>
                         <button className="btn btn-primary" onClick={handleDownload}>
                             ⬇️ Tải xuống .md
                         </button>
@@ -1,5 +1,6 @@
 import React, { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { formatInvocation } from '../types/execution';
 
 
 interface Skill {
@@ -15,6 +16,15 @@ interface Skill {
     updated_at: string;
 }
 
+interface DeletePreview {
+    skill_id: string;
+    path: string;
+    files: { path: string; size: number }[];
+    total_bytes: number;
+    references: { kind: 'skill' | 'workflow'; id: string; path: string }[];
+    preview_token: string;
+}
+
 interface CreateSkillModalProps {
     isOpen: boolean;
     onClose: () => void;
@@ -196,10 +206,18 @@ const SkillsManager: React.FC = () => {
     };
 
     const handleDeleteSkill = async (skill: Skill) => {
-        if (!window.confirm(`Bạn có chắc muốn xóa skill "${skill.name}"?`)) return;
-
         try {
-            await invoke('delete_skill', { skillId: skill.id });
+            const preview = await invoke<DeletePreview>('preview_skill_delete', { skillId: skill.id });
+            const kb = (preview.total_bytes / 1024).toFixed(1);
+            const references = preview.references.length > 0
+                ? `\n\n⚠️ Đang được dùng bởi:\n${preview.references.map(r => `- ${r.kind}: ${r.id}`).join('\n')}`
+                : '';
+            const files = preview.files.map(f => `- ${f.path}`).join('\n');
+            if (!window.confirm(
+                `Bạn có chắc muốn xóa skill "${skill.name}"?\n\n${preview.files.length} file (${kb} KB):\n${files}${references}`
+            )) return;
+
+            await invoke('delete_skill', { skillId: skill.id, previewToken: preview.preview_token });
             showNotification(`🗑️ Đã xóa skill "${skill.name}"`, 'success');
             if (selectedSkill?.id === skill.id) {
                 setSelectedSkill(null);
@@ -223,7 +241,8 @@ const SkillsManager: React.FC = () => {
                 scriptName
             });
 
-            const output = `✅ Script executed successfully!\n\nExecution Time: ${result.execution_time.toFixed(3)}s\n\nOutput:\n${result.output}`;
+            const command = result.invocation ? `\nCommand: ${formatInvocation(result.invocation)}` : '';
+            const output = `✅ Script executed successfully!\n\nExecution Time: ${result.execution_time.toFixed(3)}s${command}\n\nOutput:\n${result.output}`;
             const errorOutput = result.error ? `\n\nErrors:\n${result.error}` : '';
 
             setScriptOutput(output + errorOutput);
@@ -264,7 +283,7 @@ const SkillsManager: React.FC = () => {
             });
             showNotification(`📦 Đã export "${result.skill_name}" (${(result.file_size / 1024).toFixed(1)} KB)`, 'success');
         } catch (err) {
-            showNotification(`Lỗi export: ${err}`, 'error');
+            showNotification(`Lỗi export: ${(err as any)?.message ?? err}`, 'error');
         } finally {
             setIsExporting(false);
         }
@@ -1,5 +1,8 @@
 import React, { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { Invocation, ProjectContext, formatInvocation } from '../types/execution';
+import { confirmConflicts } from '../services/mergeConflicts';
 import './TaskExecutor.css';
 
 interface TaskResult {
@@ -11,22 +14,110 @@ interface TaskResult {
     executionTime?: number;
     timestamp: string;
     cost?: string;
+    invocation?: Invocation;
+    retry?: { attempt: number; maxAttempts: number };
+    inputRequest?: { prompt: string; defaultAnswer: string };
+    progress?: { step: number | null; totalSteps: number | null; percent: number | null; message: string };
+    labels?: string[];
 }
 
 interface TauriTaskResult {
+    task_id: string;
     success: boolean;
     output: string;
+    stderr: string;
     agent_used: string;
     execution_time: number;
+    invocation?: Invocation;
+    files_changed: string[];
+    exit_code: number | null;
+    attempts: number;
+    agent_attempts: AgentAttempt[];
+    output_truncated: boolean;
+    full_output_path: string | null;
 }
 
+// Matches AgentAttempt in src-tauri/src/agent_fallback.rs
+interface AgentAttempt {
+    agent: string;
+    success: boolean;
+    execution_time: number;
+    fallback_reason: string | null;
+}
+
+// Payload of the `task-retry` event (src-tauri/src/task_retry.rs)
+interface TaskRetryEvent {
+    task_id: string;
+    attempt: number;
+    max_attempts: number;
+    delay_ms: number;
+    reason: string;
+}
+
+// Payload of the `task-progress` event (src-tauri/src/task_progress.rs)
+interface TaskProgressEvent {
+    task_id: string;
+    step: number | null;
+    total_steps: number | null;
+    percent: number | null;
+    message: string;
+}
+
+// Payload of the `task-input-request` event (src-tauri/src/task_input.rs)
+interface TaskInputRequestEvent {
+    task_id: string;
+    prompt: string;
+    default_answer: string;
+    timeout_secs: number | null;
+}
+
+// Rate limits hit the API agent most; other agents fail fast
+const API_RETRY_POLICY = { max_attempts: 3, base_delay_ms: 2000 };
+
+// Matches TaskHistoryEntry in src-tauri/src/task_history.rs
+interface TaskHistoryEntry {
+    task_id: string;
+    task: string;
+    agent: string;
+    success: boolean;
+    execution_time: number;
+    timestamp: string;
+    output: string;
+    output_truncated: boolean;
+    labels: string[];
+    changed_files: { path: string; status: string; lines_added: number | null; lines_removed: number | null }[];
+}
+
+// Console output, stderr of failed runs (unless it already is the console log) and changed files
+const formatResult = (result: TauriTaskResult): string => {
+    let text = result.output;
+    if (!result.success && result.stderr.trim() && result.stderr !== result.output) {
+        text += `\n\n⚠️ stderr (exit code ${result.exit_code ?? '?'}):\n${result.stderr}`;
+    }
+    if (result.output_truncated) {
+        text += `\n\n✂️ Output truncated${result.full_output_path ? ` — full log: ${result.full_output_path}` : ''}`;
+    }
+    if (result.agent_attempts.length > 1) {
+        const steps = result.agent_attempts.map(a =>
+            `  ${a.success ? '✅' : '❌'} ${a.agent} (${a.execution_time.toFixed(1)}s)${a.fallback_reason ? ` → ${a.fallback_reason}` : ''}`
+        );
+        text += `\n\n🔀 Agents:\n${steps.join('\n')}`;
+    }
+    if (result.files_changed.length > 0) {
+        text += `\n\n📝 Files changed:\n${result.files_changed.map(f => `  ${f}`).join('\n')}`;
+    }
+    return text;
+};
+
 const TaskExecutor: React.FC = () => {
     const [taskInput, setTaskInput] = useState('');
     const [selectedAgent, setSelectedAgent] = useState('auto');
+    const [autoFallback, setAutoFallback] = useState(false);
     const [isExecuting, setIsExecuting] = useState(false);
     const [taskHistory, setTaskHistory] = useState<TaskResult[]>([]);
     const [projectContext, setProjectContext] = useState<string>('');
     const [expandedTasks, setExpandedTasks] = useState<Set<string>>(new Set());
+    const [inputAnswers, setInputAnswers] = useState<Record<string, string>>({});
     const chatEndRef = useRef<HTMLDivElement>(null);
 
     const agents = [
@@ -38,6 +129,43 @@ const TaskExecutor: React.FC = () => {
 
     useEffect(() => {
         loadContext();
+        loadHistory();
+    }, []);
+
+    useEffect(() => {
+        const unlisten = listen<TaskRetryEvent>('task-retry', (event) => {
+            const { task_id, attempt, max_attempts } = event.payload;
+            setTaskHistory(prev =>
+                prev.map(t => (t.id === task_id ? { ...t, retry: { attempt, maxAttempts: max_attempts } } : t))
+            );
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    useEffect(() => {
+        const unlisten = listen<TaskInputRequestEvent>('task-input-request', (event) => {
+            const { task_id, prompt, default_answer } = event.payload;
+            setTaskHistory(prev =>
+                prev.map(t => (t.id === task_id ? { ...t, inputRequest: { prompt, defaultAnswer: default_answer } } : t))
+            );
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    useEffect(() => {
+        const unlisten = listen<TaskProgressEvent>('task-progress', (event) => {
+            const { task_id, step, total_steps, percent, message } = event.payload;
+            setTaskHistory(prev =>
+                prev.map(t => (t.id === task_id ? { ...t, progress: { step, totalSteps: total_steps, percent, message } } : t))
+            );
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
     }, []);
 
     useEffect(() => {
@@ -47,15 +175,39 @@ const TaskExecutor: React.FC = () => {
 
     const loadContext = async () => {
         try {
-            const context = await invoke<string>('get_context');
-            setProjectContext(context);
+            const context = await invoke<ProjectContext>('get_context');
+            setProjectContext(context.content);
         } catch (error) {
             console.error('Failed to load context:', error);
         }
     };
 
+    // Runs from earlier sessions of the current project, oldest first
+    const loadHistory = async () => {
+        try {
+            const entries = await invoke<TaskHistoryEntry[]>('get_task_history', { limit: 50 });
+            setTaskHistory(entries.reverse().map(entry => ({
+                id: entry.task_id,
+                task: entry.task,
+                agent: entry.agent,
+                status: entry.success ? 'completed' : 'failed',
+                result: (entry.output_truncated ? `${entry.output}\n…` : entry.output)
+                    + (entry.changed_files?.length
+                        ? `\n\n📝 Files changed:\n${entry.changed_files.map(f => `  ${f.path} (${f.status} +${f.lines_added ?? '?'} -${f.lines_removed ?? '?'})`).join('\n')}`
+                        : ''),
+                executionTime: entry.execution_time,
+                timestamp: new Date(entry.timestamp).toLocaleString('vi-VN'),
+                labels: entry.labels,
+            })));
+        } catch (error) {
+            console.error('Failed to load task history:', error);
+        }
+    };
+
     const handleExecuteTask = async () => {
         if (!taskInput.trim() || isExecuting) return;
+        const allowConflicts = await confirmConflicts();
+        if (allowConflicts === null) return;
 
         const newTask: TaskResult = {
             id: Date.now().toString(),
@@ -76,6 +228,10 @@ const TaskExecutor: React.FC = () => {
             const result = await invoke<TauriTaskResult>('execute_task', {
                 task: taskDescription,
                 agent: selectedAgent,
+                taskId: newTask.id,
+                retry: selectedAgent === 'api' ? API_RETRY_POLICY : null,
+                autoFallback,
+                allowConflicts,
             });
 
             setTaskHistory(prev =>
@@ -84,8 +240,12 @@ const TaskExecutor: React.FC = () => {
                         ? {
                             ...t,
                             status: result.success ? 'completed' : 'failed',
-                            result: result.output,
+                            result: formatResult(result),
                             executionTime: result.execution_time,
+                            invocation: result.invocation,
+                            retry: undefined,
+                            inputRequest: undefined,
+                            progress: undefined,
                         }
                         : t
                 )
@@ -107,6 +267,34 @@ const TaskExecutor: React.FC = () => {
         }
     };
 
+    const handleCancelTask = async (taskId: string) => {
+        try {
+            await invoke('cancel_task', { taskId });
+        } catch (error) {
+            console.error('Failed to cancel task:', error);
+        }
+    };
+
+    // Panic button: drops queued tasks and kills every running task, workflow and script
+    const handleKillAll = async () => {
+        if (!window.confirm('Dừng tất cả task, workflow và script đang chạy?')) return;
+        try {
+            await invoke('kill_all_tasks');
+        } catch (error) {
+            console.error('Failed to kill tasks:', error);
+        }
+    };
+
+    const handleSendInput = async (taskId: string, text: string) => {
+        try {
+            await invoke('send_task_input', { taskId, text });
+            setTaskHistory(prev => prev.map(t => (t.id === taskId ? { ...t, inputRequest: undefined } : t)));
+            setInputAnswers(prev => ({ ...prev, [taskId]: '' }));
+        } catch (error) {
+            console.error('Failed to send task input:', error);
+        }
+    };
+
     const handleKeyPress = (e: React.KeyboardEvent) => {
         if (e.key === 'Enter' && !e.shiftKey) {
             e.preventDefault();
@@ -193,6 +381,9 @@ const TaskExecutor: React.FC = () => {
                                 <div className="message-agent-tag" style={{ backgroundColor: getAgentInfo(task.agent).color }}>
                                     {getAgentInfo(task.agent).icon} {getAgentInfo(task.agent).name}
                                 </div>
+                                {task.labels?.map(label => (
+                                    <span key={label} className="message-label">#{label}</span>
+                                ))}
                             </div>
                         </div>
 
@@ -208,7 +399,12 @@ const TaskExecutor: React.FC = () => {
                                         <span className="message-time">{task.executionTime.toFixed(1)}s</span>
                                     )}
                                     <span className={`status-indicator ${task.status}`}>
-                                        {task.status === 'running' && <><span className="spinner-mini" /> Đang xử lý...</>}
+                                        {task.status === 'running' && (
+                                            <>
+                                                <span className="spinner-mini" />
+                                                {task.retry ? ` Đang thử lại ${task.retry.attempt}/${task.retry.maxAttempts}...` : ' Đang xử lý...'}
+                                            </>
+                                        )}
                                         {task.status === 'completed' && '✅ Hoàn thành'}
                                         {task.status === 'failed' && '❌ Thất bại'}
                                     </span>
@@ -222,9 +418,46 @@ const TaskExecutor: React.FC = () => {
                                             <span></span>
                                         </div>
                                         <span className="loading-text">AI đang suy nghĩ...</span>
+                                        {task.progress && (
+                                            <div className="message-progress">
+                                                {task.progress.percent !== null && (
+                                                    <div className="message-progress-track">
+                                                        <div className="message-progress-bar" style={{ width: `${task.progress.percent}%` }} />
+                                                    </div>
+                                                )}
+                                                <span>
+                                                    {task.progress.step !== null && `${task.progress.step}/${task.progress.totalSteps} `}
+                                                    {task.progress.message}
+                                                </span>
+                                            </div>
+                                        )}
+                                        <button className="btn btn-secondary btn-sm" onClick={() => handleCancelTask(task.id)}>
+                                            ⛔ Dừng
+                                        </button>
+                                        {task.inputRequest && (
+                                            <div className="message-input-request">
+                                                <span>❓ {task.inputRequest.prompt}</span>
+                                                <input
+                                                    value={inputAnswers[task.id] ?? ''}
+                                                    placeholder={task.inputRequest.defaultAnswer}
+                                                    onChange={(e) => setInputAnswers(prev => ({ ...prev, [task.id]: e.target.value }))}
+                                                    onKeyDown={(e) => {
+                                                        if (e.key === 'Enter') handleSendInput(task.id, inputAnswers[task.id] ?? '');
+                                                    }}
+                                                />
+                                                <button className="btn btn-primary btn-sm" onClick={() => handleSendInput(task.id, inputAnswers[task.id] ?? '')}>
+                                                    Trả lời
+                                                </button>
+                                            </div>
+                                        )}
                                     </div>
                                 ) : task.result ? (
                                     <div className="message-result">
+                                        {task.invocation && (
+                                            <div className="result-invocation" title={`env: ${task.invocation.env_keys.join(', ')}`}>
+                                                <code>{formatInvocation(task.invocation)}</code>
+                                            </div>
+                                        )}
                                         <div
                                             className={`result-content ${expandedTasks.has(task.id) ? 'expanded' : 'collapsed'}`}
                                             onClick={() => toggleTaskExpand(task.id)}
@@ -277,6 +510,17 @@ const TaskExecutor: React.FC = () => {
                             <span className="chip-name">{agent.name}</span>
                         </button>
                     ))}
+                    <label className="fallback-toggle" title="Thử agent tiếp theo khi gặp lỗi xác thực / hết quota">
+                        <input
+                            type="checkbox"
+                            checked={autoFallback}
+                            onChange={(e) => setAutoFallback(e.target.checked)}
+                        />
+                        Tự chuyển agent
+                    </label>
+                    <button className="kill-all-btn" onClick={handleKillAll} title="Dừng mọi tiến trình đang chạy">
+                        ⛔ Dừng tất cả
+                    </button>
                 </div>
 
                 {/* Input Box */}
@@ -1,5 +1,8 @@
-import React, { useState, useEffect } from 'react';
+import React, { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { Invocation, formatInvocation } from '../types/execution';
+import { confirmConflicts } from '../services/mergeConflicts';
 import './WorkflowRunner.css';
 
 interface Workflow {
@@ -10,35 +13,275 @@ interface Workflow {
     steps: string[];
     lastRun?: string;
     status: 'idle' | 'running' | 'completed' | 'error';
+    scope?: WorkflowScope;
 }
 
+// Matches workflow_scope.rs: project workflows shadow global ones
+type WorkflowScope = 'project' | 'global';
+
 interface WorkflowInfo {
     name: string;
     description: string;
+    steps_count: number | null;
+    file_path: string | null;
+    scope: WorkflowScope;
+    yaml_error: YamlDiagnostic | null;
+}
+
+// Matches YamlDiagnostic in src-tauri/src/workflow_yaml.rs
+interface YamlDiagnostic {
+    line: number;
+    column: number;
+    message: string;
+    // Line before, offending line and a `^` under the column
+    snippet: string;
 }
 
-interface TauriTaskResult {
+// Matches StepResult in src-tauri/src/workflow_run.rs
+interface StepResult {
+    index: number;
+    id: string | null;
+    name: string;
+    agent: string;
+    status: 'succeeded' | 'failed' | 'skipped' | 'cancelled';
     success: boolean;
+    duration: number;
     output: string;
-    agent_used: string;
+    error: string | null;
+    exit_code: number | null;
+    invocation?: Invocation;
+    output_truncated: boolean;
+    full_output_path: string | null;
+    warnings?: string[];
+}
+
+// Matches WorkflowRunResult in src-tauri/src/workflow_run.rs
+// Matches Snapshot in src-tauri/src/workflow_snapshot.rs
+interface Snapshot {
+    kind: 'skipped' | 'git' | 'backup';
+    commit?: string;
+    dir?: string;
+    files?: number;
+}
+
+interface WorkflowRunResult {
+    task_id: string;
+    workflow: string;
+    success: boolean;
+    cancelled: boolean;
+    aborted: boolean;
+    total_steps: number;
+    steps: StepResult[];
     execution_time: number;
+    snapshot: Snapshot | null;
+}
+
+// Matches WorkflowRunRecord in src-tauri/src/workflow_history.rs
+interface WorkflowRunRecord {
+    run_id: string;
+    workflow: string;
+    dry_run: boolean;
+    success: boolean;
+    cancelled: boolean;
+    aborted?: boolean;
+    failed_step: number | null;
+    execution_time: number;
+    started_at: string;
+    snapshot?: Snapshot | null;
+}
+
+interface StepStartedEvent {
+    task_id: string;
+    index: number;
+    total_steps: number;
+    name: string;
+    agent: string;
+}
+
+interface StepFinishedEvent extends StepResult {
+    task_id: string;
+    total_steps: number;
 }
 
+interface WorkflowAbortedEvent {
+    task_id: string;
+    workflow: string;
+    current_step: number | null;
+}
+
+// Matches WorkflowValidation in src-tauri/src/workflow_validation.rs
+interface WorkflowValidation {
+    is_valid: boolean;
+    path: string;
+    errors: { line: number; message: string }[];
+    warnings: { line: number; message: string }[];
+    yaml_error: YamlDiagnostic | null;
+}
+
+// Matches WorkflowVariable in src-tauri/src/workflow_variables.rs
+interface WorkflowVariable {
+    name: string;
+    default: string | null;
+    line: number;
+}
+
+// Matches WorkflowTemplateInfo in src-tauri/src/workflow_templates.rs
+interface WorkflowTemplate {
+    id: string;
+    name: string;
+    description: string;
+    variables: WorkflowVariable[];
+    source: 'builtin' | 'custom';
+    file_path: string | null;
+}
+
+/** "2h ago"-style age of an ISO timestamp */
+const timeAgo = (iso: string): string => {
+    const minutes = Math.max(0, Math.floor((Date.now() - new Date(iso).getTime()) / 60000));
+    if (minutes < 1) return 'vừa xong';
+    if (minutes < 60) return `${minutes} phút trước`;
+    const hours = Math.floor(minutes / 60);
+    if (hours < 24) return `${hours} giờ trước`;
+    return `${Math.floor(hours / 24)} ngày trước`;
+};
+
+/** Badge text for a workflow's last run */
+const lastRunLabel = (run: WorkflowRunRecord): string => {
+    const outcome = run.success
+        ? '✅'
+        : run.aborted
+            ? '⏹️ đã dừng'
+            : run.cancelled
+                ? '⛔ đã hủy'
+            : `❌ lỗi ở bước ${run.failed_step ?? '?'}`;
+    return `Lần chạy cuối: ${timeAgo(run.started_at)}, ${outcome}`;
+};
+
+/** Log line for the safety snapshot taken before a run */
+const snapshotLogLine = (snapshot: Snapshot): string => {
+    switch (snapshot.kind) {
+        case 'git':
+            return `📸 Snapshot: commit ${snapshot.commit?.slice(0, 8)}`;
+        case 'backup':
+            return `📸 Snapshot: ${snapshot.files} file → ${snapshot.dir}`;
+        default:
+            return '📸 Không có thay đổi chưa commit, bỏ qua snapshot';
+    }
+};
+
+/** Log lines for a finished step: its command, output and outcome */
+const stepLogLines = (step: StepResult, totalSteps: number): string[] => {
+    const label = `[${step.index}/${totalSteps}] ${step.name}`;
+    if (step.status === 'skipped') {
+        return [`⏭️ ${label}: ${step.error ?? 'skipped'}`];
+    }
+    const lines = step.invocation ? [formatInvocation(step.invocation)] : [];
+    lines.push(...(step.warnings ?? []).map(warning => `⚠️ ${warning}`));
+    lines.push(...step.output.split('\n').filter(line => line.trim()));
+    if (step.output_truncated) {
+        lines.push(`✂️ Output truncated${step.full_output_path ? ` — full log: ${step.full_output_path}` : ''}`);
+    }
+    if (step.success) {
+        lines.push(`✅ ${label} (${step.duration.toFixed(1)}s)`);
+    } else {
+        lines.push(`❌ ${label}: ${step.error ?? `exit code ${step.exit_code ?? '?'}`} (${step.duration.toFixed(1)}s)`);
+    }
+    return lines;
+};
+
 const WorkflowRunner: React.FC = () => {
     const [workflows, setWorkflows] = useState<Workflow[]>([]);
     const [selectedWorkflow, setSelectedWorkflow] = useState<string | null>(null);
     const [runningWorkflow, setRunningWorkflow] = useState<string | null>(null);
     const [workflowLogs, setWorkflowLogs] = useState<string[]>([]);
+    const [variables, setVariables] = useState<WorkflowVariable[]>([]);
+    const [variableValues, setVariableValues] = useState<Record<string, string>>({});
     const [isLoading, setIsLoading] = useState(true);
     const [notification, setNotification] = useState<{ message: string; type: 'success' | 'error' | 'info' } | null>(null);
     const [showCreateModal, setShowCreateModal] = useState(false);
     const [newWorkflowName, setNewWorkflowName] = useState('');
+    const [newWorkflowScope, setNewWorkflowScope] = useState<WorkflowScope>('global');
+    const [templates, setTemplates] = useState<WorkflowTemplate[]>([]);
+    // Empty: the plain two-step stub
+    const [templateId, setTemplateId] = useState('');
+    const [templateValues, setTemplateValues] = useState<Record<string, string>>({});
+    // Latest real (not dry-run) run of each workflow, by name
+    const [lastRuns, setLastRuns] = useState<Record<string, WorkflowRunRecord>>({});
+    // Task id of the run whose step events go to the log
+    const currentRunId = useRef<string | null>(null);
 
     // Load workflows on mount
     useEffect(() => {
         loadWorkflows();
+        loadLastRuns();
     }, []);
 
+    const loadLastRuns = async () => {
+        try {
+            const runs = await invoke<WorkflowRunRecord[]>('get_workflow_runs', { name: null, limit: 500 });
+            const latest: Record<string, WorkflowRunRecord> = {};
+            runs.filter(run => !run.dry_run).forEach(run => {
+                if (!latest[run.workflow]) latest[run.workflow] = run;
+            });
+            setLastRuns(latest);
+        } catch (error) {
+            console.error('Failed to load workflow runs:', error);
+        }
+    };
+
+    // Workflow files edited outside the app (e.g. in an editor)
+    useEffect(() => {
+        const changed = listen<{ added: string[]; modified: string[]; removed: string[] }>('workflows-changed', () => {
+            loadWorkflows();
+        });
+        return () => {
+            changed.then((fn) => fn());
+        };
+    }, []);
+
+    // Step events of the current run, logged as they arrive
+    useEffect(() => {
+        const started = listen<StepStartedEvent>('workflow-step-started', (event) => {
+            const { task_id, index, total_steps, name, agent } = event.payload;
+            if (task_id !== currentRunId.current) return;
+            setWorkflowLogs(prev => [...prev, `▶️ [${index}/${total_steps}] ${name} (${agent})`]);
+        });
+        const finished = listen<StepFinishedEvent>('workflow-step-finished', (event) => {
+            const step = event.payload;
+            if (step.task_id !== currentRunId.current) return;
+            setWorkflowLogs(prev => [...prev, ...stepLogLines(step, step.total_steps)]);
+        });
+        const aborted = listen<WorkflowAbortedEvent>('workflow-aborted', (event) => {
+            const { task_id, current_step } = event.payload;
+            if (task_id !== currentRunId.current) return;
+            const step = current_step !== null ? ` (đang dừng bước ${current_step})` : '';
+            setWorkflowLogs(prev => [...prev, `⏹️ Đang dừng workflow${step}...`]);
+        });
+        return () => {
+            started.then((fn) => fn());
+            finished.then((fn) => fn());
+            aborted.then((fn) => fn());
+        };
+    }, []);
+
+    // Declared variables of the selected workflow, for the override form
+    useEffect(() => {
+        setVariables([]);
+        setVariableValues({});
+        if (!selectedWorkflow) return;
+        invoke<WorkflowVariable[]>('get_workflow_variables', { name: selectedWorkflow })
+            .then(setVariables)
+            .catch(error => console.error('Failed to load workflow variables:', error));
+    }, [selectedWorkflow]);
+
+    // Only values that differ from the declared defaults are sent
+    const variableOverrides = (): Record<string, string> =>
+        Object.fromEntries(
+            Object.entries(variableValues).filter(([name, value]) =>
+                value !== (variables.find(v => v.name === name)?.default ?? '')
+            )
+        );
+
     const loadWorkflows = async () => {
         setIsLoading(true);
         try {
@@ -66,8 +309,10 @@ const WorkflowRunner: React.FC = () => {
                 name: w.name,
                 description: w.description,
                 icon: workflowIcons[w.name] || '📋',
-                steps: workflowSteps[w.name] || ['Step 1', 'Step 2', 'Step 3'],
+                steps: workflowSteps[w.name]
+                    || Array.from({ length: w.steps_count ?? 3 }, (_, i) => `Step ${i + 1}`),
                 status: 'idle' as const,
+                scope: w.scope,
             }));
 
             setWorkflows(mappedWorkflows);
@@ -123,33 +368,63 @@ const WorkflowRunner: React.FC = () => {
     const handleRunWorkflow = async (workflowId: string) => {
         const workflow = workflows.find(w => w.id === workflowId);
         if (!workflow) return;
+        const allowConflicts = await confirmConflicts();
+        if (allowConflicts === null) return;
 
         setRunningWorkflow(workflowId);
         setSelectedWorkflow(workflowId);
         setWorkflowLogs([`▶️ Bắt đầu workflow: ${workflow.name}`]);
 
+        const taskId = Date.now().toString();
+        currentRunId.current = taskId;
         try {
-            // Call real Tauri command
-            const result = await invoke<TauriTaskResult>('run_workflow', {
+            const result = await invoke<WorkflowRunResult>('run_workflow', {
                 name: workflow.name,
                 dryRun: false,
+                variables: workflowId === selectedWorkflow ? variableOverrides() : {},
+                taskId,
+                scope: workflow.scope,
+                allowConflicts,
             });
 
-            // Parse and display output line by line
-            const lines = result.output.split('\n').filter(line => line.trim());
-            lines.forEach(line => {
-                setWorkflowLogs(prev => [...prev, line]);
-            });
-
+            if (result.snapshot) {
+                setWorkflowLogs(prev => [...prev, snapshotLogLine(result.snapshot!)]);
+            }
+            const failed = result.steps.filter(step => step.status === 'failed').length;
             if (result.success) {
                 setWorkflowLogs(prev => [...prev, `✅ Workflow hoàn thành thành công! (${result.execution_time.toFixed(1)}s)`]);
+            } else if (result.aborted) {
+                setWorkflowLogs(prev => [...prev, '⏹️ Workflow đã dừng']);
+            } else if (result.cancelled) {
+                setWorkflowLogs(prev => [...prev, '⛔ Workflow đã bị hủy']);
             } else {
-                setWorkflowLogs(prev => [...prev, `❌ Workflow thất bại`]);
+                setWorkflowLogs(prev => [...prev, `❌ Workflow thất bại (${failed}/${result.total_steps} bước lỗi)`]);
             }
         } catch (error) {
             setWorkflowLogs(prev => [...prev, `❌ Error: ${error}`]);
         } finally {
+            currentRunId.current = null;
             setRunningWorkflow(null);
+            loadLastRuns();
+        }
+    };
+
+    const handleRestoreSnapshot = async (run: WorkflowRunRecord) => {
+        if (!window.confirm(`Khôi phục thư mục làm việc về trước lần chạy "${run.workflow}"? Các thay đổi sau đó sẽ bị ghi đè.`)) return;
+        try {
+            const restored = await invoke<{ restored: number; removed: number }>('restore_snapshot', { runId: run.run_id });
+            showNotification(`⏪ Đã khôi phục ${restored.restored} file, xóa ${restored.removed} file`, 'success');
+        } catch (error) {
+            showNotification(`❌ Lỗi: ${error}`, 'error');
+        }
+    };
+
+    const handleAbortWorkflow = async () => {
+        if (!currentRunId.current) return;
+        try {
+            await invoke('abort_workflow', { runId: currentRunId.current });
+        } catch (error) {
+            setWorkflowLogs(prev => [...prev, `❌ Error: ${error}`]);
         }
     };
 
@@ -161,20 +436,101 @@ const WorkflowRunner: React.FC = () => {
         setWorkflowLogs([`🔍 Preview workflow: ${workflow.name} (dry-run)`]);
 
         try {
-            const result = await invoke<TauriTaskResult>('run_workflow', {
+            const validation = await invoke<WorkflowValidation>('validate_workflow', { nameOrPath: workflow.name });
+            setWorkflowLogs(prev => [
+                ...prev,
+                ...validation.errors.map(d => `❌ Dòng ${d.line}: ${d.message}`),
+                ...validation.warnings.map(d => `⚠️ Dòng ${d.line}: ${d.message}`),
+                ...(validation.yaml_error?.snippet.split('\n') ?? []),
+            ]);
+            if (!validation.is_valid) return;
+
+            const result = await invoke<WorkflowRunResult>('run_workflow', {
                 name: workflow.name,
                 dryRun: true,
+                variables: workflowId === selectedWorkflow ? variableOverrides() : {},
+                scope: workflow.scope,
             });
 
-            const lines = result.output.split('\n').filter(line => line.trim());
-            lines.forEach(line => {
-                setWorkflowLogs(prev => [...prev, line]);
+            result.steps.forEach(step => {
+                setWorkflowLogs(prev => [...prev, ...stepLogLines(step, result.total_steps)]);
             });
         } catch (error) {
             setWorkflowLogs(prev => [...prev, `❌ Error: ${error}`]);
         }
     };
 
+    const handleDeleteWorkflow = async (workflowId: string) => {
+        if (!window.confirm(`Xóa workflow "${workflowId}"? File sẽ được chuyển vào workflows/.trash`)) return;
+        try {
+            await invoke<string>('delete_workflow', { name: workflowId });
+            showNotification(`🗑️ Đã xóa workflow: ${workflowId}`, 'success');
+            setSelectedWorkflow(null);
+            loadWorkflows();
+        } catch (error) {
+            showNotification(`❌ Lỗi: ${error}`, 'error');
+        }
+    };
+
+    const handleRenameWorkflow = async (workflowId: string) => {
+        const newName = window.prompt('Tên mới cho workflow:', workflowId)?.trim();
+        if (!newName || newName === workflowId) return;
+        try {
+            await invoke<string>('rename_workflow', { oldName: workflowId, newName });
+            showNotification(`✏️ Đã đổi tên: ${workflowId} → ${newName}`, 'success');
+            setSelectedWorkflow(null);
+            loadWorkflows();
+        } catch (error) {
+            showNotification(`❌ Lỗi: ${error}`, 'error');
+        }
+    };
+
+    const handleDuplicateWorkflow = async (workflowId: string) => {
+        const newName = window.prompt('Tên cho bản sao:', `${workflowId}-copy`)?.trim();
+        if (!newName) return;
+        try {
+            const copy = await invoke<WorkflowInfo>('duplicate_workflow', { sourceName: workflowId, newName });
+            showNotification(`📄 Đã nhân bản: ${copy.name}`, 'success');
+            await loadWorkflows();
+            setSelectedWorkflow(copy.name);
+        } catch (error) {
+            showNotification(`❌ Lỗi: ${error}`, 'error');
+        }
+    };
+
+    const handleExportWorkflow = async (workflowId: string) => {
+        try {
+            const exported = await invoke<{ export_path: string }>('export_workflow', { name: workflowId });
+            showNotification(`📦 Đã xuất: ${exported.export_path}`, 'success');
+        } catch (error) {
+            showNotification(`❌ Lỗi: ${error}`, 'error');
+        }
+    };
+
+    const handleImportWorkflow = async () => {
+        try {
+            const { open } = await import('@tauri-apps/plugin-dialog');
+            const zipPath = await open({ filters: [{ name: 'Workflow package', extensions: ['zip'] }] });
+            if (typeof zipPath !== 'string') return;
+
+            const install = (overwrite: boolean) =>
+                invoke<{ name: string }>('import_workflow', { zipPath, overwrite });
+            let imported;
+            try {
+                imported = await install(false);
+            } catch (error) {
+                if (!String(error).includes('already exists')) throw error;
+                if (!window.confirm(`${error}\n\nGhi đè? Workflow cũ sẽ được chuyển vào workflows/.trash`)) return;
+                imported = await install(true);
+            }
+            showNotification(`📥 Đã nhập workflow: ${imported.name}`, 'success');
+            await loadWorkflows();
+            setSelectedWorkflow(imported.name);
+        } catch (error) {
+            showNotification(`❌ Lỗi: ${error}`, 'error');
+        }
+    };
+
     const showNotification = (message: string, type: 'success' | 'error' | 'info') => {
         setNotification({ message, type });
         setTimeout(() => setNotification(null), 3000);
@@ -189,6 +545,21 @@ const WorkflowRunner: React.FC = () => {
         }
     };
 
+    const openCreateModal = async () => {
+        setShowCreateModal(true);
+        try {
+            setTemplates(await invoke<WorkflowTemplate[]>('list_workflow_templates'));
+        } catch (error) {
+            console.error('Failed to load workflow templates:', error);
+        }
+    };
+
+    const selectTemplate = (id: string) => {
+        setTemplateId(id);
+        const template = templates.find(t => t.id === id);
+        setTemplateValues(Object.fromEntries((template?.variables ?? []).map(v => [v.name, v.default ?? ''])));
+    };
+
     const handleCreateWorkflow = async () => {
         if (!newWorkflowName.trim()) {
             showNotification('⚠️ Vui lòng nhập tên workflow', 'error');
@@ -196,10 +567,16 @@ const WorkflowRunner: React.FC = () => {
         }
 
         try {
-            const path = await invoke<string>('create_workflow', { name: newWorkflowName });
+            const path = await invoke<string>('create_workflow', {
+                name: newWorkflowName,
+                scope: newWorkflowScope,
+                templateId: templateId || null,
+                variables: templateId ? templateValues : null,
+            });
             showNotification(`✅ Đã tạo workflow: ${path}`, 'success');
             setShowCreateModal(false);
             setNewWorkflowName('');
+            selectTemplate('');
             // Reload workflows
             loadWorkflows();
         } catch (error) {
@@ -207,6 +584,10 @@ const WorkflowRunner: React.FC = () => {
         }
     };
 
+    // Last run of the selected workflow, when it left a snapshot to restore
+    const selectedRun = lastRuns[workflows.find(w => w.id === selectedWorkflow)?.name ?? ''];
+    const restorableRun = selectedRun?.snapshot && selectedRun.snapshot.kind !== 'skipped' ? selectedRun : null;
+
     return (
         <div className="workflow-runner">
             <div className="runner-header">
@@ -237,8 +618,23 @@ const WorkflowRunner: React.FC = () => {
                                 >
                                     <div className="workflow-icon">{workflow.icon}</div>
                                     <div className="workflow-info">
-                                        <h4>{workflow.name}</h4>
+                                        <h4>
+                                            {workflow.name}
+                                            {workflow.scope === 'project' && (
+                                                <span className="scope-badge" title="Workflow riêng của dự án (.agent/workflows)">
+                                                    📁 Dự án
+                                                </span>
+                                            )}
+                                        </h4>
                                         <p>{workflow.description}</p>
+                                        {lastRuns[workflow.name] && (
+                                            <span
+                                                className={`last-run-badge ${lastRuns[workflow.name].success ? 'success' : 'failed'}`}
+                                                title={`${lastRuns[workflow.name].execution_time.toFixed(1)}s`}
+                                            >
+                                                {lastRunLabel(lastRuns[workflow.name])}
+                                            </span>
+                                        )}
                                     </div>
                                     <div className="workflow-meta">
                                         <span className="step-count">{workflow.steps.length} bước</span>
@@ -270,6 +666,18 @@ const WorkflowRunner: React.FC = () => {
                                         >
                                             👁️
                                         </button>
+                                        {runningWorkflow === workflow.id && (
+                                            <button
+                                                className="abort-btn"
+                                                onClick={(e) => {
+                                                    e.stopPropagation();
+                                                    handleAbortWorkflow();
+                                                }}
+                                                title="Dừng workflow"
+                                            >
+                                                ⏹️
+                                            </button>
+                                        )}
                                     </div>
                                 </div>
                             ))}
@@ -282,7 +690,42 @@ const WorkflowRunner: React.FC = () => {
                     {selectedWorkflow ? (
                         <>
                             <div className="details-section">
-                                <h3>Chi tiết Workflow</h3>
+                                <div className="details-header">
+                                    <h3>Chi tiết Workflow</h3>
+                                    <div className="details-actions">
+                                        <button
+                                            onClick={() => handleDuplicateWorkflow(selectedWorkflow)}
+                                            disabled={runningWorkflow !== null}
+                                        >
+                                            📄 Nhân bản
+                                        </button>
+                                        {restorableRun && (
+                                            <button
+                                                onClick={() => handleRestoreSnapshot(restorableRun)}
+                                                disabled={runningWorkflow !== null}
+                                                title={`Snapshot trước lần chạy ${new Date(restorableRun.started_at).toLocaleString('vi-VN')}`}
+                                            >
+                                                ⏪ Khôi phục
+                                            </button>
+                                        )}
+                                        <button onClick={() => handleExportWorkflow(selectedWorkflow)}>
+                                            📦 Xuất
+                                        </button>
+                                        <button
+                                            onClick={() => handleRenameWorkflow(selectedWorkflow)}
+                                            disabled={runningWorkflow !== null}
+                                        >
+                                            ✏️ Đổi tên
+                                        </button>
+                                        <button
+                                            className="danger"
+                                            onClick={() => handleDeleteWorkflow(selectedWorkflow)}
+                                            disabled={runningWorkflow !== null}
+                                        >
+                                            🗑️ Xóa
+                                        </button>
+                                    </div>
+                                </div>
                                 <div className="workflow-steps">
                                     {workflows.find(w => w.id === selectedWorkflow)?.steps.map((step, index) => (
                                         <div key={index} className="step-item">
@@ -293,6 +736,26 @@ const WorkflowRunner: React.FC = () => {
                                 </div>
                             </div>
 
+                            {variables.length > 0 && (
+                                <div className="details-section">
+                                    <h3>Biến</h3>
+                                    <div className="workflow-variables">
+                                        {variables.map(variable => (
+                                            <label key={variable.name} className="variable-field">
+                                                <span>{variable.name}</span>
+                                                <input
+                                                    value={variableValues[variable.name] ?? variable.default ?? ''}
+                                                    onChange={(e) =>
+                                                        setVariableValues(prev => ({ ...prev, [variable.name]: e.target.value }))
+                                                    }
+                                                    disabled={runningWorkflow !== null}
+                                                />
+                                            </label>
+                                        ))}
+                                    </div>
+                                </div>
+                            )}
+
                             {workflowLogs.length > 0 && (
                                 <div className="logs-section">
                                     <div className="logs-header">
@@ -331,12 +794,15 @@ const WorkflowRunner: React.FC = () => {
                 <h3>🛠️ Tạo Workflow mới</h3>
                 <p>Tạo workflow bằng cách thêm file YAML vào <code>workflows/</code></p>
                 <div className="custom-workflow-buttons">
-                    <button className="create-workflow-btn" onClick={() => setShowCreateModal(true)}>
+                    <button className="create-workflow-btn" onClick={openCreateModal}>
                         <PlusIcon /> Tạo mới
                     </button>
                     <button className="open-folder-btn" onClick={handleOpenWorkflowsFolder}>
                         <FolderIcon /> Mở thư mục
                     </button>
+                    <button className="open-folder-btn" onClick={handleImportWorkflow}>
+                        📥 Nhập gói
+                    </button>
                 </div>
             </div>
 
@@ -354,6 +820,41 @@ const WorkflowRunner: React.FC = () => {
                             onKeyPress={(e) => e.key === 'Enter' && handleCreateWorkflow()}
                             autoFocus
                         />
+                        <select
+                            className="scope-select"
+                            value={newWorkflowScope}
+                            onChange={(e) => setNewWorkflowScope(e.target.value as WorkflowScope)}
+                        >
+                            <option value="global">Dùng chung cho mọi dự án (workflows/)</option>
+                            <option value="project">Chỉ dự án hiện tại (.agent/workflows)</option>
+                        </select>
+                        <select
+                            className="scope-select"
+                            value={templateId}
+                            onChange={(e) => selectTemplate(e.target.value)}
+                        >
+                            <option value="">Mẫu trống (2 bước)</option>
+                            {templates.map(t => (
+                                <option key={t.id} value={t.id}>
+                                    {t.name}{t.source === 'custom' ? ' (tùy chỉnh)' : ''}
+                                </option>
+                            ))}
+                        </select>
+                        {templateId && (
+                            <div className="template-fields">
+                                <p>{templates.find(t => t.id === templateId)?.description}</p>
+                                {Object.keys(templateValues).map(name => (
+                                    <label key={name}>
+                                        {name}
+                                        <input
+                                            type="text"
+                                            value={templateValues[name]}
+                                            onChange={(e) => setTemplateValues(prev => ({ ...prev, [name]: e.target.value }))}
+                                        />
+                                    </label>
+                                ))}
+                            </div>
+                        )}
                         <div className="modal-actions">
                             <button className="cancel-btn" onClick={() => setShowCreateModal(false)}>Hủy</button>
                             <button className="confirm-btn" onClick={handleCreateWorkflow}>Tạo</button>
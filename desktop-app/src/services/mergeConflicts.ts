@@ -0,0 +1,36 @@
+// src/services/mergeConflicts.ts
+
+import { invoke } from '@tauri-apps/api/core';
+
+// Returned by get_conflicted_files
+export interface ConflictedFile {
+    path: string;
+    relative_path: string;
+    markers: number;
+}
+
+// Payload of the conflicts-detected event
+export interface ConflictsDetected {
+    root: string;
+    files: ConflictedFile[];
+}
+
+/**
+ * Ask before starting work in a project with merge conflicts.
+ * Returns the allowConflicts flag to pass, or null when the user cancels.
+ */
+export async function confirmConflicts(): Promise<boolean | null> {
+    let files: ConflictedFile[] = [];
+    try {
+        files = await invoke<ConflictedFile[]>('get_conflicted_files');
+    } catch {
+        // Not a git repository: nothing to merge
+        return false;
+    }
+    if (files.length === 0) return false;
+    const listed = files.slice(0, 5).map(f => `  ${f.relative_path} (${f.markers})`).join('\n');
+    const proceed = window.confirm(
+        `Dự án còn ${files.length} file bị xung đột merge chưa giải quyết:\n${listed}\n\nVẫn chạy?`
+    );
+    return proceed ? true : null;
+}
@@ -0,0 +1,33 @@
+// Execution record types (matches Rust backend)
+// Source: desktop-app/src-tauri/src/child_env.rs, vibe_json.rs
+
+export interface Invocation {
+    program: string;
+    args: string[]; // sensitive flag values already redacted
+    cwd: string | null;
+    env_keys: string[]; // names only, never values
+}
+
+/** Result of `get_stats` */
+export interface SessionStats {
+    project: string | null;
+    session_duration: number | null;
+    tasks_executed: number | null;
+    api_cost: number | null;
+    api_requests: number | null;
+    text: string; // ready to display
+}
+
+/** Result of `get_context` */
+export interface ProjectContext {
+    project_path: string | null;
+    content: string;
+}
+
+/** One-line "$ program args (in cwd)" summary for logs and history */
+export function formatInvocation(invocation: Invocation): string {
+    const command = [invocation.program, ...invocation.args]
+        .map(arg => (arg.includes(' ') ? `"${arg}"` : arg))
+        .join(' ');
+    return invocation.cwd ? `$ ${command}  (in ${invocation.cwd})` : `$ ${command}`;
+}
@@ -0,0 +1,17 @@
+// Background operation types (matches Rust backend)
+// Source: desktop-app/src-tauri/src/operations.rs
+
+export type OperationState = 'running' | 'cancelling' | 'finished' | 'cancelled';
+
+/** Payload of the `operation-progress` event and entry of `list_operations` */
+export interface OperationProgress {
+    id: string;
+    kind: string;
+    done: number;
+    total: number | null;
+    message: string | null;
+    state: OperationState;
+    started_at: string;
+}
+
+export const OPERATION_PROGRESS_EVENT = 'operation-progress';
@@ -0,0 +1,28 @@
+// Version info types (matches Rust backend)
+// Source: desktop-app/src-tauri/src/version_info.rs
+
+export interface VersionInfo {
+    app_version: string;
+    git_commit: string | null;
+    tauri_version: string;
+    webview_version: string | null;
+    settings_schema_version: number;
+    agents_registry_hash: string | null;
+    prompt_templates: { name: string; sha256: string }[];
+    target: string;
+    os: string;
+    arch: string;
+}
+
+/** Plain-text block placed at the top of diagnostics reports */
+export function formatVersionInfo(info: VersionInfo): string {
+    const templates = info.prompt_templates.map(t => `${t.name}@${t.sha256}`).join(', ') || 'none';
+    return [
+        `Vibecode ${info.app_version} (${info.git_commit ?? 'unknown commit'})`,
+        `Tauri ${info.tauri_version}, webview ${info.webview_version ?? 'unknown'}`,
+        `Platform ${info.target || `${info.os}-${info.arch}`}`,
+        `Settings schema v${info.settings_schema_version}`,
+        `Agents registry ${info.agents_registry_hash ?? 'not found'}`,
+        `Prompt templates ${templates}`,
+    ].join('\n');
+}
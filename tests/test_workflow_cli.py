@@ -0,0 +1,59 @@
+"""
+Tests for running workflows through vibe.py
+"""
+import pytest
+
+import vibe
+
+WORKFLOW = """name: {title}
+description: Test workflow
+steps:
+  - id: {step_id}
+    name: {step_name}
+    agent: api
+    prompt: Do it
+"""
+
+
+@pytest.fixture
+def shadowing_workflow(temp_project_dir, monkeypatch):
+    """A global workflow 'build' and a project workflow with the same name"""
+    vibecode = temp_project_dir / "vibecode"
+    (vibecode / "workflows").mkdir(parents=True)
+    (vibecode / "workflows" / "build.yaml").write_text(
+        WORKFLOW.format(title="Global build", step_id="global", step_name="Global step"), encoding='utf-8'
+    )
+
+    project = temp_project_dir / "project"
+    (project / ".agent" / "workflows").mkdir(parents=True)
+    project_file = project / ".agent" / "workflows" / "build.yaml"
+    project_file.write_text(
+        WORKFLOW.format(title="Project build", step_id="project", step_name="Project step"), encoding='utf-8'
+    )
+
+    monkeypatch.setattr(vibe, "PROJECT_ROOT", vibecode)
+    monkeypatch.setattr(vibe, "REPORT", vibe.JsonReport())
+    return project, project_file
+
+
+class TestWorkflowSteps:
+    """`workflow run <file> --step <n>` as the desktop app runs each step"""
+
+    def test_shadowing_project_workflow_runs_its_own_steps(self, shadowing_workflow, temp_project_dir):
+        project, project_file = shadowing_workflow
+        outputs = temp_project_dir / "outputs.json"
+        outputs.write_text("{}", encoding='utf-8')
+        step_options = ["--step", "1", "--outputs", str(outputs), "--dry-run"]
+
+        assert vibe.cmd_workflow(["run", str(project_file), *step_options], project_root=project) == 0
+        assert vibe.REPORT.data["name"] == "Project step"
+        assert vibe.REPORT.data["step"] == 1
+
+        # By name only the global workflow is found
+        assert vibe.cmd_workflow(["build", *step_options], project_root=project) == 0
+        assert vibe.REPORT.data["name"] == "Global step"
+
+    def test_step_options_are_checked(self, shadowing_workflow):
+        project, project_file = shadowing_workflow
+        assert vibe.cmd_workflow(["run", str(project_file), "--step", "0"], project_root=project) == 1
+        assert vibe.cmd_workflow(["run", str(project_file), "--step", "2", "--dry-run"], project_root=project) == 1
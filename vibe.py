@@ -5,6 +5,8 @@ Main entry point for the Vibecode AI System
 """
 import sys
 import os
+import json
+import subprocess
 from pathlib import Path
 
 # Fix Windows console encoding
@@ -31,6 +33,51 @@ console = Console(force_terminal=True)
 VERSION = "0.2.0"
 
 
+class JsonReport:
+    """`--json` mode: human-readable output goes to stderr, one JSON object to stdout"""
+
+    def __init__(self):
+        self.enabled = False
+        self.data = None
+        self._stdout = sys.stdout
+
+    def enable(self):
+        self.enabled = True
+        self._stdout = sys.stdout
+        # Rich consoles resolve sys.stdout on every write
+        sys.stdout = sys.stderr
+
+    def set(self, data):
+        self.data = data
+
+    def emit(self):
+        if self.enabled and self.data is not None:
+            self._stdout.write(json.dumps(self.data, ensure_ascii=False, default=str) + "\n")
+            self._stdout.flush()
+
+
+REPORT = JsonReport()
+
+
+def git_status(project_root):
+    """`git status --porcelain` lines of the project (empty outside a git repo)"""
+    try:
+        result = subprocess.run(
+            ["git", "-C", str(project_root), "status", "--porcelain", "-uall"],
+            capture_output=True, text=True, encoding="utf-8", timeout=30
+        )
+    except (OSError, subprocess.SubprocessError):
+        return set()
+    if result.returncode != 0:
+        return set()
+    return set(line for line in result.stdout.splitlines() if line.strip())
+
+
+def files_changed(before, after):
+    """Paths whose git status changed between two `git_status` snapshots"""
+    return sorted(set(line[3:] for line in after - before))
+
+
 def show_banner():
     """Display welcome banner"""
     console.print(Panel(
@@ -68,6 +115,10 @@ def show_help():
   context                    Show current project context
   help                       Show this help message
 
+[bold cyan]Global Options:[/bold cyan]
+  --project <path>           Work on this project instead of the Vibecode repo
+  --json                     Print one JSON object on stdout (output goes to stderr)
+
 [bold cyan]Task Options:[/bold cyan]
   --api                      Force use of API agent
   --cli                      Force use of CLI agent
@@ -76,6 +127,8 @@ def show_help():
 [bold cyan]Workflow Options:[/bold cyan]
   --dry-run                  Preview workflow without executing
   --var key=value            Set workflow variable
+  --step <n>                 Run only the n-th step (1-based)
+  --outputs <file>           JSON file with outputs saved by earlier steps (with --step)
 
 [bold cyan]Examples:[/bold cyan]
   python vibe.py task "Analyze the architecture"
@@ -87,7 +140,86 @@ def show_help():
 """)
 
 
-def cmd_workflow(args):
+def pop_project_option(args):
+    """Remove `--project <path>` from args; returns (project root, remaining args)"""
+    if '--project' not in args:
+        return PROJECT_ROOT, args
+
+    i = args.index('--project')
+    if i + 1 >= len(args):
+        raise ValueError("--project requires a path")
+
+    project = Path(args[i + 1]).resolve()
+    if not project.is_dir():
+        raise ValueError(f"Project directory not found: {project}")
+    return project, args[:i] + args[i + 2:]
+
+
+def pop_step_options(args):
+    """Remove `--step <n>` and `--outputs <file>` from args; returns (step, outputs, remaining args)"""
+    values = {}
+    for flag in ('--step', '--outputs'):
+        if flag not in args:
+            continue
+        i = args.index(flag)
+        if i + 1 >= len(args):
+            raise ValueError(f"{flag} requires a value")
+        values[flag] = args[i + 1]
+        args = args[:i] + args[i + 2:]
+
+    step = None
+    if '--step' in values:
+        if not values['--step'].isdigit() or int(values['--step']) < 1:
+            raise ValueError("--step must be a step number starting at 1")
+        step = int(values['--step'])
+
+    outputs = {}
+    if '--outputs' in values:
+        with open(values['--outputs'], 'r', encoding='utf-8') as f:
+            outputs = json.load(f)
+    return step, outputs, args
+
+
+def run_workflow_file(engine, workflow_file, options, project_root):
+    """Run a workflow file, or only its `--step` with `--outputs`; options are the arguments after the file"""
+    try:
+        step, outputs, options = pop_step_options(options)
+    except (ValueError, OSError) as e:
+        console.print(f"[red]Error: {e}[/red]")
+        return 1
+    dry_run = '--dry-run' in options
+
+    # Variables: `key=value`, alone or after `--var`
+    variables = {}
+    for arg in options:
+        if '=' in arg and not arg.startswith('--'):
+            key, value = arg.split('=', 1)
+            variables[key] = value
+
+    try:
+        workflow = engine.load_workflow(workflow_file)
+
+        # Override variables
+        for key, value in variables.items():
+            workflow.variables[key] = value
+
+        before = git_status(project_root)
+        if step is not None:
+            result = engine.execute_step(workflow, step, outputs, dry_run=dry_run)
+        else:
+            result = engine.execute(workflow, dry_run=dry_run)
+        REPORT.set({**result, "files_changed": files_changed(before, git_status(project_root))})
+        return 0 if result.get('success') else 1
+
+    except FileNotFoundError:
+        console.print(f"[red]Workflow file not found: {workflow_file}[/red]")
+        return 1
+    except Exception as e:
+        console.print(f"[red]Workflow error: {e}[/red]")
+        return 1
+
+
+def cmd_workflow(args, project_root=PROJECT_ROOT):
     """Handle workflow commands"""
     from core.workflow_engine import WorkflowEngine, Workflow
 
@@ -97,11 +229,23 @@ def cmd_workflow(args):
         return 1
 
     subcmd = args[0]
-    engine = WorkflowEngine(str(PROJECT_ROOT))
+    engine = WorkflowEngine(str(project_root))
 
     # List workflows
     if subcmd == 'list':
-        workflows = engine.list_workflows()
+        # Workflow definitions ship with Vibecode, whichever project they run on
+        workflows = engine.list_workflows(str(PROJECT_ROOT / "workflows"))
+        REPORT.set({"workflows": [
+            {
+                "name": Path(wf['file']).stem,
+                "title": wf.get('name'),
+                "description": wf.get('description', ''),
+                "steps_count": wf.get('steps'),
+                "file_path": str(PROJECT_ROOT / "workflows" / wf['file']),
+                "error": wf.get('error'),
+            }
+            for wf in workflows
+        ]})
         if not workflows:
             console.print("[yellow]No workflows found in workflows/ directory[/yellow]")
             return 0
@@ -126,71 +270,17 @@ def cmd_workflow(args):
         console.print(table)
         return 0
 
-    # Run workflow from file
+    # Run workflow from file (the app passes project workflows by path)
     if subcmd == 'run':
         if len(args) < 2:
             console.print("[red]Error: Workflow file required[/red]")
             return 1
-
-        workflow_file = args[1]
-        dry_run = '--dry-run' in args
-
-        # Parse variables
-        variables = {}
-        for arg in args[2:]:
-            if arg.startswith('--var'):
-                continue
-            if '=' in arg:
-                key, value = arg.split('=', 1)
-                variables[key] = value
-
-        try:
-            workflow = engine.load_workflow(workflow_file)
-
-            # Override variables
-            for key, value in variables.items():
-                workflow.variables[key] = value
-
-            result = engine.execute(workflow, dry_run=dry_run)
-            return 0 if result.get('success') else 1
-
-        except FileNotFoundError:
-            console.print(f"[red]Workflow file not found: {workflow_file}[/red]")
-            return 1
-        except Exception as e:
-            console.print(f"[red]Workflow error: {e}[/red]")
-            return 1
+        return run_workflow_file(engine, args[1], args[2:], project_root)
 
     # Run workflow by name (shortcut)
     workflow_file = PROJECT_ROOT / "workflows" / f"{subcmd}.yaml"
     if workflow_file.exists():
-        dry_run = '--dry-run' in args
-
-        # Parse variables
-        variables = {}
-        for i, arg in enumerate(args[1:]):
-            if arg == '--var' and i + 2 < len(args):
-                var_arg = args[i + 2]
-                if '=' in var_arg:
-                    key, value = var_arg.split('=', 1)
-                    variables[key] = value
-            elif '=' in arg and not arg.startswith('--'):
-                key, value = arg.split('=', 1)
-                variables[key] = value
-
-        try:
-            workflow = engine.load_workflow(str(workflow_file))
-
-            # Override variables
-            for key, value in variables.items():
-                workflow.variables[key] = value
-
-            result = engine.execute(workflow, dry_run=dry_run)
-            return 0 if result.get('success') else 1
-
-        except Exception as e:
-            console.print(f"[red]Workflow error: {e}[/red]")
-            return 1
+        return run_workflow_file(engine, str(workflow_file), args[1:], project_root)
 
     console.print(f"[red]Unknown workflow command or file: {subcmd}[/red]")
     return 1
@@ -237,6 +327,15 @@ def cmd_swarm(args):
 def main():
     """Main entry point"""
     args = sys.argv[1:]
+    if '--json' in args:
+        REPORT.enable()
+        args = [arg for arg in args if arg != '--json']
+
+    try:
+        project_root, args = pop_project_option(args)
+    except ValueError as e:
+        console.print(f"[red]Error: {e}[/red]")
+        return 1
 
     # No arguments - show help
     if not args:
@@ -259,7 +358,7 @@ def main():
 
     # Workflow command
     if command == 'workflow':
-        return cmd_workflow(args[1:])
+        return cmd_workflow(args[1:], project_root)
 
     # Swarm command
     if command == 'swarm':
@@ -267,7 +366,7 @@ def main():
 
     # Initialize orchestrator
     try:
-        orchestrator = Orchestrator(str(PROJECT_ROOT))
+        orchestrator = Orchestrator(str(project_root))
     except Exception as e:
         console.print(f"[red]Failed to initialize orchestrator: {e}[/red]")
         return 1
@@ -296,11 +395,19 @@ def main():
             include_context = False
 
         # Execute task
+        before = git_status(project_root)
         result = orchestrator.execute_task(
             task_description,
             force_agent=force_agent,
             include_context=include_context
         )
+        REPORT.set({
+            "success": bool(result.get('success')),
+            "agent_used": result.get('agent_used'),
+            "execution_time": result.get('execution_time'),
+            "error": result.get('error'),
+            "files_changed": files_changed(before, git_status(project_root)),
+        })
 
         return 0 if result.get('success') else 1
 
@@ -312,18 +419,19 @@ def main():
 
     # Stats command
     if command == 'stats':
-        orchestrator.display_stats()
+        REPORT.set(orchestrator.get_session_stats())
+        if not REPORT.enabled:
+            orchestrator.display_stats()
         return 0
 
     # Context command
     if command == 'context':
         from core.context_manager import get_context_manager
-        ctx = get_context_manager(str(PROJECT_ROOT))
-        console.print(Panel(
-            ctx.get_full_context_for_agent(),
-            title="Project Context",
-            border_style="blue"
-        ))
+        ctx = get_context_manager(str(project_root))
+        content = ctx.get_full_context_for_agent()
+        REPORT.set({"project_path": str(project_root), "content": content})
+        if not REPORT.enabled:
+            console.print(Panel(content, title="Project Context", border_style="blue"))
         return 0
 
     # Route command - explain routing
@@ -345,7 +453,9 @@ def main():
 
 if __name__ == "__main__":
     try:
-        sys.exit(main())
+        code = main()
+        REPORT.emit()
+        sys.exit(code)
     except KeyboardInterrupt:
         console.print("\n[yellow]Interrupted[/yellow]")
         sys.exit(130)